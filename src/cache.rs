@@ -1,4 +1,6 @@
+use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, SystemTime};
 
 use directories::ProjectDirs;
@@ -7,8 +9,11 @@ use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
 use crate::error::{DocsError, Result};
+use crate::singleflight::Singleflight;
 
 const CACHE_TTL_SECS: u64 = 24 * 60 * 60; // 1 day
+/// A 404 from a HEAD probe is kept briefly: docs.rs may finish the build meanwhile.
+const MISSING_TTL_SECS: u64 = 60 * 60;
 
 #[derive(Serialize, Deserialize)]
 struct CacheEntry {
@@ -19,17 +24,48 @@ struct CacheEntry {
 
 pub struct DiskCache {
     cache_dir: PathBuf,
+    /// Whether `cache_dir` is a throwaway directory removed with the cache
+    scratch: bool,
+    /// Fetches in progress, by cache key, so concurrent misses for one URL download it once
+    pub(crate) flights: Singleflight,
 }
 
 impl DiskCache {
     pub fn new() -> Result<Self> {
-        let cache_dir = resolve_cache_dir()?;
+        Self::at(resolve_cache_dir()?)
+    }
+
+    /// Use an explicit cache directory instead of the platform default.
+    pub fn at(cache_dir: PathBuf) -> Result<Self> {
         std::fs::create_dir_all(&cache_dir)?;
-        let cache = Self { cache_dir };
+        let cache = Self { cache_dir, scratch: false, flights: Singleflight::default() };
         cache.prune_expired()?;
         Ok(cache)
     }
 
+    /// A cache in a new temporary directory, deleted when the cache is dropped or
+    /// [`remove_scratch`](Self::remove_scratch) is called.
+    pub fn scratch(prefix: &str) -> Result<Self> {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        let name = format!("{prefix}-{}-{}", std::process::id(), NEXT.fetch_add(1, Ordering::Relaxed));
+        let cache_dir = std::env::temp_dir().join(name);
+        std::fs::create_dir_all(&cache_dir)?;
+        Ok(Self { cache_dir, scratch: true, flights: Singleflight::default() })
+    }
+
+    /// Delete the directory of a [`scratch`](Self::scratch) cache; a no-op for others.
+    /// `std::process::exit` skips destructors, so the binary calls this before exiting.
+    pub fn remove_scratch(&self) {
+        if self.scratch {
+            let _ = std::fs::remove_dir_all(&self.cache_dir);
+        }
+    }
+
+    /// Directory holding cached responses.
+    pub fn dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
     fn cache_path(&self, key: &str) -> PathBuf {
         self.cache_dir.join(format!("{key}.json"))
     }
@@ -46,96 +82,155 @@ impl DiskCache {
     {
         let key = Self::cache_key(url);
         let path = self.cache_path(&key);
+        self.flights.run(&key, || async {
+            if let Some(body) = self.read_valid_cache(&path)? {
+                return serde_json::from_str(&body).map_err(DocsError::Json);
+            }
 
-        if let Some(body) = self.read_valid_cache(&path)? {
-            return serde_json::from_str(&body).map_err(DocsError::Json);
-        }
-
-        let resp = client.get(url).send().await?;
-        if !resp.status().is_success() {
-            return Err(DocsError::Other(format!(
-                "HTTP {} for {}",
-                resp.status(),
-                url
-            )));
-        }
-        let body = resp.text().await?;
-        let value = serde_json::from_str(&body).map_err(DocsError::Json)?;
-        self.write_cache(&path, url, &body)?;
-        Ok(value)
+            tracing::debug!("cache miss: {url}");
+            let resp = client.get(url).send().await?;
+            if !resp.status().is_success() {
+                return Err(DocsError::Status { status: resp.status(), url: url.to_string() });
+            }
+            let body = resp.text().await?;
+            let value = serde_json::from_str(&body).map_err(DocsError::Json)?;
+            self.write_cache(&path, url, &body)?;
+            Ok(value)
+        }).await
     }
 
-    /// Download a zstd-compressed JSON file and return the deserialized value.
-    ///
-    /// docs.rs serves rustdoc JSON as `Content-Type: application/zstd` bodies.
-    /// The decompressed JSON text is cached so repeat calls skip the download.
-    pub async fn get_zstd_json<T>(&self, client: &reqwest_middleware::ClientWithMiddleware, url: &str) -> Result<T>
+    /// POST a JSON `body` to `url` and return the deserialized response, cached per body.
+    pub async fn post_json<T>(&self, client: &reqwest_middleware::ClientWithMiddleware, url: &str, body: &str) -> Result<T>
     where
         T: serde::de::DeserializeOwned,
     {
-        let key = Self::cache_key(url);
+        let key = Self::cache_key(&format!("{url}\n{body}"));
         let path = self.cache_path(&key);
+        self.flights.run(&key, || async {
+            if let Some(cached) = self.read_valid_cache(&path)? {
+                return serde_json::from_str(&cached).map_err(DocsError::Json);
+            }
 
-        if let Some(body) = self.read_valid_cache(&path)? {
-            return serde_json::from_str(&body).map_err(DocsError::Json);
-        }
+            tracing::debug!("cache miss: POST {url} {body}");
+            let resp = client.post(url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body.to_string())
+                .send()
+                .await?;
+            if !resp.status().is_success() {
+                return Err(DocsError::Status { status: resp.status(), url: url.to_string() });
+            }
+            let text = resp.text().await?;
+            let value = serde_json::from_str(&text).map_err(DocsError::Json)?;
+            self.write_cache(&path, url, &text)?;
+            Ok(value)
+        }).await
+    }
 
-        let resp = client.get(url).send().await?;
-        if !resp.status().is_success() {
-            return Err(DocsError::Other(format!(
-                "HTTP {} for {}",
-                resp.status(),
-                url
-            )));
-        }
-        let bytes = resp.bytes().await?;
-        let body = decompress_zstd(&bytes)?;
-        let value = serde_json::from_str(&body).map_err(DocsError::Json)?;
-        self.write_cache(&path, url, &body)?;
-        Ok(value)
+    /// Download a zstd-compressed file and return its decompressed text parsed by `parse`.
+    ///
+    /// docs.rs serves rustdoc JSON as `Content-Type: application/zstd` bodies. Text over
+    /// `max_len` bytes is refused with the error `too_large` makes from its length, without
+    /// holding it in memory. The decompressed text is cached once it parses, so repeat
+    /// calls skip the download.
+    pub async fn get_zstd_parsed<T>(
+        &self,
+        client: &reqwest_middleware::ClientWithMiddleware,
+        url: &str,
+        max_len: Option<usize>,
+        too_large: impl FnOnce(usize) -> DocsError,
+        parse: impl FnOnce(&str) -> Result<T>,
+    ) -> Result<T> {
+        let key = Self::cache_key(url);
+        let path = self.cache_path(&key);
+        self.flights.run(&key, || async {
+            if let Some(body) = self.read_valid_cache(&path)? {
+                // Cached under a higher limit
+                if max_len.is_some_and(|max| body.len() > max) {
+                    return Err(too_large(body.len()));
+                }
+                return parse(&body);
+            }
+            tracing::debug!("cache miss: {url}");
+            let resp = client.get(url).send().await?;
+            if !resp.status().is_success() {
+                return Err(DocsError::Status { status: resp.status(), url: url.to_string() });
+            }
+            match resp.content_length() {
+                Some(len) => tracing::info!("downloading {url} ({} KiB)", len / 1024),
+                None => tracing::info!("downloading {url}"),
+            }
+            let bytes = resp.bytes().await?;
+            let body = match max_len {
+                Some(max_len) => decompress_zstd_capped(&bytes, max_len, too_large)?,
+                None => decompress_zstd(&bytes)?,
+            };
+            drop(bytes);
+            let value = parse(&body)?;
+            self.write_cache(&path, url, &body)?;
+            Ok(value)
+        }).await
     }
 
     pub async fn get_text(&self, client: &reqwest_middleware::ClientWithMiddleware, url: &str) -> Result<String> {
         let key = Self::cache_key(url);
         let path = self.cache_path(&key);
+        self.flights.run(&key, || async {
+            if let Some(body) = self.read_valid_cache(&path)? {
+                // body was stored as JSON string, decode it
+                return serde_json::from_str::<String>(&body).map_err(DocsError::Json);
+            }
 
-        if let Some(body) = self.read_valid_cache(&path)? {
-            // body was stored as JSON string, decode it
-            return serde_json::from_str::<String>(&body).map_err(DocsError::Json);
-        }
-
-        let resp = client.get(url).send().await?;
-        if !resp.status().is_success() {
-            return Err(DocsError::Other(format!(
-                "HTTP {} for {}",
-                resp.status(),
-                url
-            )));
-        }
-        let text = resp.text().await?;
-        // Store text as JSON string
-        let body = serde_json::to_string(&text)?;
-        self.write_cache(&path, url, &body)?;
-        Ok(text)
+            tracing::debug!("cache miss: {url}");
+            let resp = client.get(url).send().await?;
+            if !resp.status().is_success() {
+                return Err(DocsError::Status { status: resp.status(), url: url.to_string() });
+            }
+            let text = resp.text().await?;
+            // Store text as JSON string
+            let body = serde_json::to_string(&text)?;
+            self.write_cache(&path, url, &body)?;
+            Ok(text)
+        }).await
     }
 
     /// Returns true if URL returns success (200), false for 404, error for other failures.
+    ///
+    /// Answers are cached like bodies, except that a miss expires after an hour.
     pub async fn head_check(&self, client: &reqwest_middleware::ClientWithMiddleware, url: &str) -> Result<bool> {
-        let resp = client.head(url).send().await?;
-        Ok(resp.status().is_success())
+        let key = Self::cache_key(&format!("HEAD {url}"));
+        let path = self.cache_path(&key);
+        self.flights.run(&key, || async {
+            let cached = self.read_entry(&path)?.and_then(|entry| {
+                let exists = entry.body == "true";
+                let ttl = if exists { CACHE_TTL_SECS } else { MISSING_TTL_SECS };
+                (unix_now().saturating_sub(entry.cached_at) <= ttl).then_some(exists)
+            });
+            crate::telemetry::record_cache_lookup(cached.is_some());
+            if let Some(exists) = cached {
+                return Ok(exists);
+            }
+            let resp = client.head(url).send().await?;
+            let status = resp.status();
+            if !status.is_success() && status != reqwest::StatusCode::NOT_FOUND {
+                // Throttling and server errors say nothing about the build; don't remember them
+                return Ok(false);
+            }
+            self.write_cache(&path, url, if status.is_success() { "true" } else { "false" })?;
+            Ok(status.is_success())
+        }).await
     }
 
+    /// The cached body at `path` if present and fresh, counted as a hit or miss for telemetry.
     fn read_valid_cache(&self, path: &Path) -> Result<Option<String>> {
-        if !path.exists() {
+        let cached = self.read_cache_entry(path)?;
+        crate::telemetry::record_cache_lookup(cached.is_some());
+        Ok(cached)
+    }
+
+    fn read_cache_entry(&self, path: &Path) -> Result<Option<String>> {
+        let Some(entry) = self.read_entry(path)? else {
             return Ok(None);
-        }
-        let raw = std::fs::read_to_string(path)?;
-        let entry: CacheEntry = match serde_json::from_str(&raw) {
-            Ok(e) => e,
-            Err(_) => {
-                let _ = std::fs::remove_file(path);
-                return Ok(None);
-            }
         };
         let now = unix_now();
         if now.saturating_sub(entry.cached_at) > CACHE_TTL_SECS {
@@ -145,6 +240,21 @@ impl DiskCache {
         Ok(Some(entry.body))
     }
 
+    /// The entry at `path` whatever its age; unreadable entries are deleted.
+    fn read_entry(&self, path: &Path) -> Result<Option<CacheEntry>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let raw = std::fs::read_to_string(path)?;
+        match serde_json::from_str(&raw) {
+            Ok(entry) => Ok(Some(entry)),
+            Err(_) => {
+                let _ = std::fs::remove_file(path);
+                Ok(None)
+            }
+        }
+    }
+
     fn write_cache(&self, path: &Path, url: &str, body: &str) -> Result<()> {
         let entry = CacheEntry {
             cached_at: unix_now(),
@@ -152,11 +262,12 @@ impl DiskCache {
             body: body.to_string(),
         };
         let raw = serde_json::to_string(&entry)?;
-        std::fs::write(path, raw)?;
+        write_atomic(path, raw.as_bytes())?;
         Ok(())
     }
 
     fn prune_expired(&self) -> Result<()> {
+        crate::cratesio::tarball::prune_unused_archives(&self.cache_dir);
         let now = unix_now();
         let Ok(entries) = std::fs::read_dir(&self.cache_dir) else {
             return Ok(());
@@ -178,6 +289,19 @@ impl DiskCache {
     }
 }
 
+/// Write `contents` to a temporary file beside `path`, then rename it into place, so
+/// another session or process reading the same cache never sees a half-written file.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(".{}.{}.tmp", std::process::id(), NEXT.fetch_add(1, Ordering::Relaxed)));
+    let tmp = PathBuf::from(tmp);
+    std::fs::write(&tmp, contents)?;
+    std::fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&tmp);
+    })
+}
+
 /// Decompress a zstd-compressed byte slice and return it as a UTF-8 string.
 ///
 /// docs.rs serves rustdoc JSON as `Content-Type: application/zstd` with a
@@ -189,6 +313,35 @@ pub fn decompress_zstd(bytes: &[u8]) -> Result<String> {
         .map_err(|e| DocsError::Other(format!("Decompressed content is not valid UTF-8: {e}")))
 }
 
+/// Decompress a zstd body, refusing text over `max_len` bytes with the error `too_large`
+/// makes from its length. A frame header recording the content size refuses it before
+/// decompressing; otherwise decompression stops keeping text past the limit and only
+/// counts the rest.
+pub fn decompress_zstd_capped(bytes: &[u8], max_len: usize, too_large: impl FnOnce(usize) -> DocsError) -> Result<String> {
+    let failed = |e: std::io::Error| DocsError::Other(format!("Zstd decompression failed: {e}"));
+    let recorded_len = zstd::zstd_safe::get_frame_content_size(bytes).ok().flatten();
+    if let Some(len) = recorded_len.filter(|&len| len > max_len as u64) {
+        return Err(too_large(usize::try_from(len).unwrap_or(usize::MAX)));
+    }
+    let mut decoder = zstd::stream::read::Decoder::new(bytes).map_err(failed)?;
+    let mut decompressed = vec![];
+    (&mut decoder).take(max_len as u64 + 1).read_to_end(&mut decompressed).map_err(failed)?;
+    if decompressed.len() > max_len {
+        let kept = decompressed.len();
+        drop(decompressed);
+        let rest = std::io::copy(&mut decoder, &mut std::io::sink()).map_err(failed)?;
+        return Err(too_large(kept.saturating_add(usize::try_from(rest).unwrap_or(usize::MAX))));
+    }
+    String::from_utf8(decompressed)
+        .map_err(|e| DocsError::Other(format!("Decompressed content is not valid UTF-8: {e}")))
+}
+
+impl Drop for DiskCache {
+    fn drop(&mut self) {
+        self.remove_scratch();
+    }
+}
+
 fn unix_now() -> u64 {
     SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)
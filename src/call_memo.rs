@@ -0,0 +1,184 @@
+//! Short-lived memo of tool results, so an agent repeating the exact same call within a
+//! session gets the previous answer instantly instead of spending crates.io quota again.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use rmcp::model::{CallToolResult, Content, JsonObject, Tool};
+use serde_json::Value;
+
+/// Env var setting how long a result is reused, in seconds. `0` turns the memo off.
+pub const TTL_ENV: &str = "DOCS_MCP_DEDUP_TTL_SECS";
+pub const DEFAULT_TTL: Duration = Duration::from_secs(60);
+
+/// Results answering "what is happening right now" are never reused, nor are those of
+/// tools not marked idempotent.
+const UNCACHEABLE_TOOLS: [&str; 4] = ["server_status", "server_stats", "session_context", "cache_warm"];
+/// Results are JSON documents of up to a few hundred KiB; keep only the most recent.
+const MAX_ENTRIES: usize = 32;
+
+/// Per-session result memo keyed by tool name and normalized arguments.
+pub struct CallMemo {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, (Instant, CallToolResult)>>,
+}
+
+impl CallMemo {
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl, entries: Mutex::new(HashMap::new()) }
+    }
+
+    /// A memo whose TTL comes from [`TTL_ENV`], defaulting to [`DEFAULT_TTL`].
+    pub fn from_env() -> Self {
+        let ttl = std::env::var(TTL_ENV).ok()
+            .and_then(|v| v.trim().parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_TTL);
+        Self::new(ttl)
+    }
+
+    /// A memo that never reuses results.
+    pub fn disabled() -> Self {
+        Self::new(Duration::ZERO)
+    }
+
+    /// How long a result is reused; zero when the memo is off.
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Memo key for a call to `tool`, or `None` when the call must not be reused.
+    pub fn key(&self, tool: &Tool, arguments: Option<&JsonObject>) -> Option<String> {
+        let idempotent = tool.annotations.as_ref().is_some_and(|a| a.is_idempotent());
+        if self.ttl.is_zero() || !idempotent || UNCACHEABLE_TOOLS.contains(&tool.name.as_ref()) {
+            return None;
+        }
+        let args = arguments.map(normalize_args).unwrap_or_default();
+        Some(format!("{}:{}", tool.name, Value::Object(args)))
+    }
+
+    /// The earlier result for `key`, marked as deduplicated, if it is still fresh.
+    pub fn get(&self, key: &str) -> Option<CallToolResult> {
+        let entries = self.entries.lock().unwrap();
+        let (stored_at, result) = entries.get(key)?;
+        (stored_at.elapsed() < self.ttl).then(|| mark_deduplicated(result))
+    }
+
+    /// Remember a successful result. Errors are not stored, so a retry really retries.
+    pub fn insert(&self, key: String, result: &CallToolResult) {
+        if result.is_error == Some(true) {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, (stored_at, _)| stored_at.elapsed() < self.ttl);
+        if entries.len() >= MAX_ENTRIES {
+            let oldest = entries.iter().min_by_key(|(_, (t, _))| *t).map(|(k, _)| k.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(key, (Instant::now(), result.clone()));
+    }
+}
+
+/// Arguments with keys sorted at every level and top-level nulls dropped, since an
+/// explicit `null` and an omitted optional parameter mean the same call.
+fn normalize_args(args: &JsonObject) -> JsonObject {
+    args.iter()
+        .filter(|(_, v)| !v.is_null())
+        .map(|(k, v)| (k.clone(), sort_keys(v)))
+        .collect::<BTreeMap<_, _>>()
+        .into_iter()
+        .collect()
+}
+
+fn sort_keys(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), sort_keys(v)))
+                .collect::<BTreeMap<_, _>>()
+                .into_iter()
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(sort_keys).collect()),
+        other => other.clone(),
+    }
+}
+
+/// Copy of `result` whose JSON text content carries `"deduplicated": true`.
+fn mark_deduplicated(result: &CallToolResult) -> CallToolResult {
+    with_field(result, "deduplicated", Value::Bool(true))
+}
+
+/// Copy of `result` with `key` set on each JSON object in its text content.
+///
+/// Content that isn't a JSON object is left as is.
+pub(crate) fn with_field(result: &CallToolResult, key: &str, value: Value) -> CallToolResult {
+    let mut result = result.clone();
+    result.content = result.content.iter()
+        .map(|content| {
+            let Some(Value::Object(mut object)) = content.as_text()
+                .and_then(|t| serde_json::from_str::<Value>(&t.text).ok())
+            else {
+                return content.clone();
+            };
+            object.insert(key.to_string(), value.clone());
+            serde_json::to_string_pretty(&object)
+                .map(Content::text)
+                .unwrap_or_else(|_| content.clone())
+        })
+        .collect();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::ToolAnnotations;
+    use serde_json::json;
+
+    fn args(value: Value) -> JsonObject {
+        value.as_object().unwrap().clone()
+    }
+
+    fn tool(name: &'static str, idempotent: bool) -> Tool {
+        Tool::new(name, "", std::sync::Arc::new(Default::default()))
+            .annotate(ToolAnnotations::new().idempotent(idempotent))
+    }
+
+    #[test]
+    fn key_ignores_argument_order_and_nulls() {
+        let memo = CallMemo::new(DEFAULT_TTL);
+        let (crate_get, item_list) = (tool("crate_get", true), tool("crate_item_list", true));
+        let a = memo.key(&crate_get, Some(&args(json!({"name": "serde", "version": null}))));
+        let b = memo.key(&crate_get, Some(&args(json!({"name": "serde"}))));
+        assert_eq!(a, b);
+        let c = memo.key(&item_list, Some(&args(json!({"query": "x", "name": "serde"}))));
+        let d = memo.key(&item_list, Some(&args(json!({"name": "serde", "query": "x"}))));
+        assert_eq!(c, d);
+        assert_ne!(a, memo.key(&crate_get, Some(&args(json!({"name": "tokio"})))));
+        assert_eq!(memo.key(&tool("server_status", true), None), None);
+        assert_eq!(memo.key(&tool("cache_warm", false), None), None);
+        assert_eq!(memo.key(&Tool::new("crate_get", "", std::sync::Arc::new(Default::default())), None), None);
+        assert_eq!(CallMemo::disabled().key(&crate_get, None), None);
+    }
+
+    #[test]
+    fn get_marks_reused_results() {
+        let memo = CallMemo::new(DEFAULT_TTL);
+        let key = memo.key(&tool("crate_get", true), Some(&args(json!({"name": "serde"})))).unwrap();
+        assert!(memo.get(&key).is_none());
+
+        memo.insert(key.clone(), &CallToolResult::success(vec![Content::text(r#"{"name":"serde"}"#)]));
+        let reused = memo.get(&key).unwrap();
+        let text = &reused.content[0].as_text().unwrap().text;
+        let value: Value = serde_json::from_str(text).unwrap();
+        assert_eq!(value, json!({"name": "serde", "deduplicated": true}));
+
+        let failed = memo.key(&tool("crate_get", true), Some(&args(json!({"name": "nope"})))).unwrap();
+        memo.insert(failed.clone(), &CallToolResult::error(vec![Content::text("not found")]));
+        assert!(memo.get(&failed).is_none());
+    }
+}
@@ -0,0 +1,240 @@
+//! Command line: with no subcommand docs-mcp serves MCP over stdio; `docs-mcp query <tool>
+//! --param value ...` makes one tool call and prints its result, for shell scripts,
+//! debugging and warming the cache in CI without an MCP client.
+
+use std::num::{NonZeroU64, NonZeroUsize};
+
+use clap::{Parser, Subcommand};
+use rmcp::{
+    ErrorData, ServerHandler, ServiceExt,
+    model::{CallToolRequestParams, CallToolResult, JsonObject, Tool},
+    service::ServiceError,
+};
+use serde_json::Value;
+
+use crate::server::DocsMcpServer;
+
+/// Server options; each also has an environment variable, read by the module that owns
+/// it ([`crate::http_config`], [`crate::tool_filter`], [`crate::house_rules`]), which the
+/// flag overrides.
+#[derive(Debug, Parser)]
+#[command(name = "docs-mcp", version, about = "MCP server for Rust crate documentation from docs.rs and crates.io")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Serve the bundled fixtures with no network access
+    #[arg(long, global = true)]
+    pub fixture_mode: bool,
+    /// Fixture directory for --fixture-mode (default: $DOCS_MCP_FIXTURE_DIR, else
+    /// tests/fixtures in the current directory)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub fixture_dir: Option<std::path::PathBuf>,
+    /// TOML config file with `[tools]` allow and deny lists (default: $DOCS_MCP_CONFIG)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<std::path::PathBuf>,
+    /// Register only these tools or groups (comma-separated)
+    #[arg(long, global = true, value_name = "NAMES")]
+    pub tools: Option<String>,
+    /// Leave out these tools or groups (comma-separated)
+    #[arg(long, global = true, value_name = "NAMES")]
+    pub disable_tools: Option<String>,
+    /// Text or markdown file of house rules sent with the server instructions
+    #[arg(long, global = true, value_name = "PATH")]
+    pub instructions_file: Option<std::path::PathBuf>,
+    /// "append" (default) or "replace" the built-in instructions
+    #[arg(long, global = true, value_name = "MODE")]
+    pub instructions_mode: Option<String>,
+    /// Total time allowed for one HTTP request, in seconds
+    #[arg(long, global = true, value_name = "SECS")]
+    pub http_timeout: Option<NonZeroU64>,
+    /// Time allowed to establish a connection, in seconds
+    #[arg(long, global = true, value_name = "SECS")]
+    pub connect_timeout: Option<NonZeroU64>,
+    /// Requests in flight at once, across all hosts
+    #[arg(long, global = true, value_name = "N")]
+    pub max_connections: Option<NonZeroUsize>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Call one tool and print its result as JSON, e.g.
+    /// `docs-mcp query crate_item_get --name serde --item-path serde::Serialize`
+    Query {
+        /// Tool name, as listed by docs_mcp_help
+        tool: String,
+        /// Tool arguments as `--param value` (kebab- or snake_case). Values of non-string
+        /// parameters are read as JSON; repeat a list parameter to pass several values.
+        /// Server options must come before the tool name.
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true, value_name = "ARGS")]
+        args: Vec<String>,
+    },
+}
+
+/// Call `tool` on `server` with arguments parsed from `args`.
+///
+/// The call goes through an in-process MCP connection, so it behaves exactly as it would
+/// for a client: same validation, memo, error codes and `newer_available` hints.
+pub async fn query(server: DocsMcpServer, tool: &str, args: &[String]) -> Result<CallToolResult, ErrorData> {
+    let Some(spec) = server.get_tool(tool) else {
+        let names: Vec<String> = server.tools().into_iter().map(|t| t.name.into_owned()).collect();
+        return Err(ErrorData::invalid_params(
+            format!("Unknown tool '{tool}'. Available tools: {}.", names.join(", ")),
+            None,
+        ));
+    };
+    let arguments = tool_arguments(&spec, args)?;
+
+    let (server_side, client_side) = tokio::io::duplex(1 << 16);
+    let serving = tokio::spawn(async move {
+        if let Ok(running) = server.serve(server_side).await {
+            let _ = running.waiting().await;
+        }
+    });
+    let client = ().serve(client_side).await
+        .map_err(|e| ErrorData::internal_error(format!("Cannot start the tool call: {e}"), None))?;
+    let result = client.call_tool(CallToolRequestParams {
+        meta: None,
+        name: spec.name.clone(),
+        arguments: Some(arguments),
+        task: None,
+    }).await;
+    let _ = client.cancel().await;
+    let _ = serving.await;
+    result.map_err(|e| match e {
+        ServiceError::McpError(err) => err,
+        other => ErrorData::internal_error(format!("The tool call failed: {other}"), None),
+    })
+}
+
+/// The JSON arguments for `tool` given `--param value` pairs.
+///
+/// A flag with no value is `true`. Values of parameters whose schema allows a string are
+/// passed as-is; others are parsed as JSON, falling back to the raw string so the
+/// server's own validation reports the mismatch.
+pub fn tool_arguments(tool: &Tool, args: &[String]) -> Result<JsonObject, ErrorData> {
+    let properties = tool.input_schema.get("properties").and_then(Value::as_object);
+    let mut arguments = JsonObject::new();
+    let mut rest = args.iter().peekable();
+    while let Some(arg) = rest.next() {
+        let Some(flag) = arg.strip_prefix("--") else {
+            return Err(ErrorData::invalid_params(
+                format!("Expected a --param flag for {}, got '{arg}'.", tool.name),
+                None,
+            ));
+        };
+        let (flag, inline) = match flag.split_once('=') {
+            Some((flag, value)) => (flag, Some(value.to_string())),
+            None => (flag, None),
+        };
+        let key = flag.replace('-', "_");
+        let Some(schema) = properties.and_then(|p| p.get(&key)) else {
+            let known: Vec<String> = properties.into_iter().flat_map(|p| p.keys())
+                .map(|k| format!("--{}", k.replace('_', "-")))
+                .collect();
+            return Err(ErrorData::invalid_params(
+                format!("{} has no parameter '{key}'. Parameters: {}.", tool.name, known.join(", ")),
+                None,
+            ));
+        };
+        let raw = inline.or_else(|| rest.next_if(|next| !next.starts_with("--")).cloned());
+        let Some(raw) = raw else {
+            arguments.insert(key, Value::Bool(true));
+            continue;
+        };
+        if allows_type(schema, "array") {
+            let item_schema = schema.get("items").unwrap_or(&Value::Null);
+            let value = parse_value(item_schema, &raw);
+            let list = arguments.entry(key).or_insert_with(|| Value::Array(vec![]));
+            match (list, value) {
+                (Value::Array(list), Value::Array(values)) => list.extend(values),
+                (Value::Array(list), value) => list.push(value),
+                _ => {}
+            }
+        } else {
+            arguments.insert(key, parse_value(schema, &raw));
+        }
+    }
+    Ok(arguments)
+}
+
+fn parse_value(schema: &Value, raw: &str) -> Value {
+    if allows_type(schema, "string") {
+        return Value::String(raw.to_string());
+    }
+    serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))
+}
+
+/// Whether the schema's `type` (a name or a list of names) includes `ty`.
+fn allows_type(schema: &Value, ty: &str) -> bool {
+    match schema.get("type") {
+        Some(Value::String(t)) => t == ty,
+        Some(Value::Array(types)) => types.iter().any(|t| t == ty),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::json;
+
+    use super::*;
+
+    fn tool() -> Tool {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "version": { "type": ["string", "null"] },
+                "item_paths": { "type": "array", "items": { "type": "string" } },
+                "include_methods": { "type": ["boolean", "null"] },
+                "limit": { "type": ["integer", "null"] },
+            },
+        });
+        Tool::new("crate_items_get_many", "", Arc::new(schema.as_object().unwrap().clone()))
+    }
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn flags_become_typed_arguments() {
+        let parsed = tool_arguments(&tool(), &args(&[
+            "--name", "serde", "--version=1.0", "--item-paths", "serde::Serialize",
+            "--item_paths", "serde::Deserialize", "--include-methods", "--limit", "5",
+        ])).unwrap();
+        assert_eq!(Value::Object(parsed), json!({
+            "name": "serde",
+            "version": "1.0",
+            "item_paths": ["serde::Serialize", "serde::Deserialize"],
+            "include_methods": true,
+            "limit": 5,
+        }));
+    }
+
+    #[test]
+    fn unknown_parameters_and_stray_values_are_rejected() {
+        let err = tool_arguments(&tool(), &args(&["--nmae", "serde"])).unwrap_err();
+        assert!(err.message.contains("--item-paths"), "{}", err.message);
+        assert!(tool_arguments(&tool(), &args(&["serde"])).is_err());
+    }
+
+    #[test]
+    fn server_options_are_parsed_in_either_form() {
+        let cli = Cli::parse_from(["docs-mcp", "--tools=docs", "--disable-tools", "cache_warm", "--http-timeout=30"]);
+        assert_eq!((cli.tools.as_deref(), cli.disable_tools.as_deref()), (Some("docs"), Some("cache_warm")));
+        assert_eq!(cli.http_timeout, NonZeroU64::new(30));
+        assert!(Cli::try_parse_from(["docs-mcp", "--max-connections", "0"]).is_err());
+        assert!(Cli::try_parse_from(["docs-mcp", "--connect-timeout", "ten"]).is_err());
+    }
+
+    #[test]
+    fn query_args_keep_their_flags() {
+        let cli = Cli::parse_from(["docs-mcp", "--fixture-mode", "query", "crate_get", "--name", "rmcp"]);
+        assert!(cli.fixture_mode);
+        let Some(Command::Query { tool, args }) = cli.command else { panic!("expected query") };
+        assert_eq!((tool.as_str(), args.as_slice()), ("crate_get", ["--name".to_string(), "rmcp".to_string()].as_slice()));
+    }
+}
@@ -1,13 +1,26 @@
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
 use reqwest_middleware::ClientWithMiddleware;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 
+use super::dump_db::DumpDatabase;
 use crate::cache::DiskCache;
 use crate::error::{DocsError, Result};
 
 const CRATESIO_BASE: &str = "https://crates.io/api/v1";
 
+/// Characters escaped in a user-supplied URL path segment: all but `-`, `_` and `~`, so
+/// `/`, `?` and `#` stay inside the segment and `..` can't climb out of it.
+const PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'~');
+
+/// Sort orders crates.io documents for listing an owner's crates.
+const OWNED_CRATES_SORTS: [&str; 5] = ["downloads", "recent-downloads", "recent-updates", "new", "alpha"];
+const GITHUB_RAW_BASE: &str = "https://raw.githubusercontent.com";
+
+/// README file names tried in a repository, most common first.
+const README_NAMES: [&str; 3] = ["README.md", "readme.md", "README"];
+
 // ─── Response types ────────────────────────────────────────────────────────────
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -62,6 +75,39 @@ pub struct VersionInfo {
     pub features: Option<HashMap<String, Vec<String>>>,
     pub links: Option<Value>,
     pub lib_links: Option<String>,
+    /// Set when the version was published by a CI workflow through trusted publishing
+    pub trustpub_data: Option<TrustpubData>,
+}
+
+/// The CI run that published a version through crates.io trusted publishing.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TrustpubData {
+    /// "github" or "gitlab"
+    pub provider: String,
+    /// GitHub `owner/repo`
+    pub repository: Option<String>,
+    pub run_id: Option<String>,
+    /// GitLab `group/project`
+    pub project_path: Option<String>,
+    pub job_id: Option<String>,
+    /// Commit the workflow ran on
+    pub sha: Option<String>,
+}
+
+impl TrustpubData {
+    /// Repository the workflow ran in, as `owner/repo` or `group/project`.
+    pub fn repository(&self) -> Option<&str> {
+        self.repository.as_deref().or(self.project_path.as_deref())
+    }
+
+    /// Link to the workflow run or job that published the version.
+    pub fn run_url(&self) -> Option<String> {
+        match self.provider.as_str() {
+            "github" => Some(format!("https://github.com/{}/actions/runs/{}", self.repository.as_ref()?, self.run_id.as_ref()?)),
+            "gitlab" => Some(format!("https://gitlab.com/{}/-/jobs/{}", self.project_path.as_ref()?, self.job_id.as_ref()?)),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -139,6 +185,7 @@ pub struct ReverseDep {
     pub default_features: bool,
     pub features: Vec<String>,
     pub kind: Option<String>,
+    /// All-time downloads of the dependent crate
     pub downloads: Option<u64>,
 }
 
@@ -149,6 +196,8 @@ pub struct ReverseDepVersion {
     #[serde(rename = "crate")]
     pub crate_name: String,
     pub downloads: u64,
+    #[serde(default)]
+    pub created_at: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -168,16 +217,96 @@ pub struct VersionDownload {
     pub date: String,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct OwnersResponse {
+    pub users: Vec<Owner>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Owner {
+    pub id: u64,
+    pub login: String,
+    /// "user" or "team"
+    pub kind: Option<String>,
+    pub name: Option<String>,
+}
+
+/// A crates.io user or team account, as `/users/{login}` and `/teams/{login}` return it.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Account {
+    pub id: u64,
+    pub login: String,
+    pub name: Option<String>,
+    pub avatar: Option<String>,
+    pub url: Option<String>,
+}
+
+/// A crate version's README.
+#[derive(Debug, Clone)]
+pub enum Readme {
+    /// crates.io's HTML rendering of the README published in the package
+    Published(String),
+    /// Markdown from the default branch of the crate's repository, for crates published
+    /// without a README; it may have changed since the release
+    Repository { url: String, markdown: String },
+}
+
+impl Readme {
+    /// The README as Markdown, with links in crates.io's rendering mapped through `map_link`.
+    pub fn to_markdown(&self, map_link: impl Fn(&str) -> Option<String>) -> String {
+        match self {
+            Readme::Published(html) => crate::html::to_markdown(html, map_link),
+            Readme::Repository { markdown, .. } => markdown.clone(),
+        }
+    }
+}
+
 // ─── Client ───────────────────────────────────────────────────────────────────
 
 pub struct CratesIoClient<'a> {
     client: &'a ClientWithMiddleware,
     cache: &'a DiskCache,
+    dump: Option<&'a DumpDatabase>,
 }
 
 impl<'a> CratesIoClient<'a> {
     pub fn new(client: &'a ClientWithMiddleware, cache: &'a DiskCache) -> Self {
-        Self { client, cache }
+        Self { client, cache, dump: None }
+    }
+
+    /// Serve lookups the DB dump covers from `dump` while it is fresh, falling back to the API.
+    pub fn with_dump(mut self, dump: Option<&'a DumpDatabase>) -> Self {
+        self.dump = dump;
+        self
+    }
+
+    /// Run `query` on the blocking pool against the dump database if one is attached and
+    /// fresh.
+    ///
+    /// Crates missing from the dump (e.g. published today) and database errors fall through
+    /// to the API.
+    async fn query_dump<T: Send + 'static>(
+        &self,
+        query: impl FnOnce(&DumpDatabase) -> Result<Option<T>> + Send + 'static,
+    ) -> Option<T> {
+        let dump = self.dump?.clone();
+        let found = tokio::task::spawn_blocking(move || {
+            if !dump.is_fresh() {
+                return Ok(None);
+            }
+            query(&dump)
+        }).await;
+        match found {
+            Ok(Ok(found)) => found,
+            Ok(Err(e)) => {
+                tracing::debug!("DB dump lookup failed, using the API: {e}");
+                None
+            }
+            Err(e) => {
+                tracing::debug!("DB dump lookup task failed, using the API: {e}");
+                None
+            }
+        }
     }
 
     pub async fn search(
@@ -203,19 +332,58 @@ impl<'a> CratesIoClient<'a> {
     }
 
     pub async fn get_crate(&self, name: &str) -> Result<CrateResponse> {
+        let owned = name.to_string();
+        if let Some(resp) = self.query_dump(move |db| db.crate_response(&owned)).await {
+            return Ok(resp);
+        }
         let url = format!("{CRATESIO_BASE}/crates/{name}");
-        self.cache.get_json(self.client, &url).await
+        self.cache.get_json(self.client, &url).await.map_err(|e| crate_not_found(e, name))
     }
 
     pub async fn get_readme(&self, name: &str, version: &str) -> Result<String> {
+        self.published_readme(name, version).await.map_err(readme_error)
+    }
+
+    async fn published_readme(&self, name: &str, version: &str) -> Result<String> {
         let url = format!("{CRATESIO_BASE}/crates/{name}/{version}/readme");
         // README endpoint returns HTML; we fetch as text
-        self.cache.get_text(self.client, &url).await.or_else(|e| {
-            Err(DocsError::Other(format!("Failed to fetch README: {e}")))
-        })
+        self.cache.get_text(self.client, &url).await
+    }
+
+    /// The published README, or when crates.io has none, the README on the default branch
+    /// of the crate's GitHub repository. Fails with the crates.io error if neither exists,
+    /// or if crates.io fails for any reason other than having no README.
+    pub async fn get_readme_or_repository(&self, name: &str, version: &str) -> Result<Readme> {
+        let published_err = match self.published_readme(name, version).await {
+            Ok(html) => return Ok(Readme::Published(html)),
+            Err(e) if e.is_not_found() => readme_error(e),
+            Err(e) => return Err(readme_error(e)),
+        };
+        let repository = self.get_crate(name).await.ok().and_then(|c| c.krate.repository);
+        for url in repository.as_deref().map(repository_readme_urls).unwrap_or_default() {
+            match self.cache.get_text(self.client, &url).await {
+                Ok(markdown) => {
+                    tracing::info!("no README on crates.io for {name} {version}; using {url}");
+                    return Ok(Readme::Repository { url, markdown });
+                }
+                Err(e) if e.is_not_found() => continue,
+                Err(e) => {
+                    tracing::debug!("fetching {url} failed: {e}");
+                    break;
+                }
+            }
+        }
+        Err(published_err)
     }
 
     pub async fn get_version(&self, name: &str, version: &str) -> Result<VersionInfo> {
+        let (owned, wanted) = (name.to_string(), version.to_string());
+        let from_dump = self.query_dump(move |db| {
+            Ok(db.versions(&owned)?.and_then(|r| r.versions.into_iter().find(|v| v.num == wanted)))
+        }).await;
+        if let Some(v) = from_dump {
+            return Ok(v);
+        }
         let url = format!("{CRATESIO_BASE}/crates/{name}/{version}");
         #[derive(Deserialize)]
         struct Wrapper {
@@ -226,11 +394,19 @@ impl<'a> CratesIoClient<'a> {
     }
 
     pub async fn get_versions(&self, name: &str) -> Result<VersionsResponse> {
+        let owned = name.to_string();
+        if let Some(resp) = self.query_dump(move |db| db.versions(&owned)).await {
+            return Ok(resp);
+        }
         let url = format!("{CRATESIO_BASE}/crates/{name}/versions");
-        self.cache.get_json(self.client, &url).await
+        self.cache.get_json(self.client, &url).await.map_err(|e| crate_not_found(e, name))
     }
 
     pub async fn get_dependencies(&self, name: &str, version: &str) -> Result<DependenciesResponse> {
+        let (owned, wanted) = (name.to_string(), version.to_string());
+        if let Some(resp) = self.query_dump(move |db| db.dependencies(&owned, &wanted)).await {
+            return Ok(resp);
+        }
         let url = format!("{CRATESIO_BASE}/crates/{name}/{version}/dependencies");
         self.cache.get_json(self.client, &url).await
     }
@@ -241,15 +417,122 @@ impl<'a> CratesIoClient<'a> {
         page: u32,
         per_page: u32,
     ) -> Result<ReverseDepsResponse> {
+        let owned = name.to_string();
+        if let Some(resp) = self.query_dump(move |db| db.reverse_deps(&owned, page, per_page)).await {
+            return Ok(resp);
+        }
         let url = format!("{CRATESIO_BASE}/crates/{name}/reverse_dependencies?page={page}&per_page={per_page}");
         self.cache.get_json(self.client, &url).await
     }
 
     pub async fn get_downloads(&self, name: &str, before_date: Option<&str>) -> Result<DownloadsResponse> {
+        // The dump only holds the latest 90 days
+        let owned = name.to_string();
+        let from_dump = match before_date {
+            None => self.query_dump(move |db| db.downloads(&owned)).await,
+            Some(_) => None,
+        };
+        if let Some(resp) = from_dump {
+            return Ok(resp);
+        }
         let mut url = format!("{CRATESIO_BASE}/crates/{name}/downloads");
         if let Some(d) = before_date {
             url.push_str(&format!("?before_date={d}"));
         }
         self.cache.get_json(self.client, &url).await
     }
+
+    pub async fn get_owners(&self, name: &str) -> Result<OwnersResponse> {
+        let url = format!("{CRATESIO_BASE}/crates/{name}/owners");
+        self.cache.get_json(self.client, &url).await
+    }
+
+    /// A user by login, or a team by its `github:{org}:{team}` login.
+    pub async fn get_account(&self, login: &str) -> Result<Account> {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            #[serde(alias = "team")]
+            user: Account,
+        }
+        let kind = if login.contains(':') { "teams" } else { "users" };
+        let login = utf8_percent_encode(login, PATH_SEGMENT);
+        let url = format!("{CRATESIO_BASE}/{kind}/{login}");
+        let w: Wrapper = self.cache.get_json(self.client, &url).await?;
+        Ok(w.user)
+    }
+
+    /// Crates `account` owns; teams are told apart from users by the `:` in their login.
+    /// `sort` must be one of [`OWNED_CRATES_SORTS`].
+    pub async fn crates_owned_by(
+        &self,
+        account: &Account,
+        sort: Option<&str>,
+        page: u32,
+        per_page: u32,
+    ) -> Result<SearchResult> {
+        if let Some(s) = sort.filter(|s| !OWNED_CRATES_SORTS.contains(s)) {
+            return Err(DocsError::InvalidParams(format!(
+                "Unknown sort '{s}'; use one of {}", OWNED_CRATES_SORTS.join(", "),
+            )));
+        }
+        let owner = if account.login.contains(':') { "team_id" } else { "user_id" };
+        let mut url = format!("{CRATESIO_BASE}/crates?{owner}={}&page={page}&per_page={per_page}", account.id);
+        if let Some(s) = sort {
+            url.push_str(&format!("&sort={s}"));
+        }
+        self.cache.get_json(self.client, &url).await
+    }
+}
+
+/// Raw URLs where a GitHub `repository` may keep its README, most likely first: in the
+/// directory a `/tree/{ref}/{path}` URL names, else at the root of the default branch.
+/// Empty for repositories hosted elsewhere.
+fn repository_readme_urls(repository: &str) -> Vec<String> {
+    let repo = repository.trim().trim_end_matches('/').trim_end_matches(".git");
+    let Some(rest) = ["https://github.com/", "http://github.com/", "https://www.github.com/"]
+        .iter()
+        .find_map(|host| repo.strip_prefix(host))
+    else {
+        return vec![];
+    };
+    let segments: Vec<&str> = rest.split('/').filter(|s| !s.is_empty()).collect();
+    let base = match segments.as_slice() {
+        [owner, name, "tree" | "blob", reference, dir @ ..] => {
+            let mut base = format!("{GITHUB_RAW_BASE}/{owner}/{name}/{reference}");
+            for segment in dir {
+                base.push('/');
+                base.push_str(segment);
+            }
+            base
+        }
+        // HEAD resolves to the default branch, whatever it is called
+        [owner, name, ..] => format!("{GITHUB_RAW_BASE}/{owner}/{name}/HEAD"),
+        _ => return vec![],
+    };
+    README_NAMES.iter().map(|file| format!("{base}/{file}")).collect()
+}
+
+fn readme_error(e: DocsError) -> DocsError {
+    DocsError::Other(format!("Failed to fetch README: {e}"))
+}
+
+/// A 404 for a crate-level endpoint means the crate doesn't exist.
+fn crate_not_found(e: DocsError, name: &str) -> DocsError {
+    if e.is_not_found() { DocsError::CrateNotFound(name.to_string()) } else { e }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn readme_urls_follow_the_repository_url() {
+        assert_eq!(repository_readme_urls("https://github.com/serde-rs/serde.git")[0],
+                   "https://raw.githubusercontent.com/serde-rs/serde/HEAD/README.md");
+        assert_eq!(repository_readme_urls("https://github.com/tokio-rs/tokio/tree/master/tokio-util/")[0],
+                   "https://raw.githubusercontent.com/tokio-rs/tokio/master/tokio-util/README.md");
+        assert_eq!(repository_readme_urls("https://github.com/owner/repo").len(), README_NAMES.len());
+        assert!(repository_readme_urls("https://gitlab.com/owner/repo").is_empty());
+        assert!(repository_readme_urls("https://github.com/owner").is_empty());
+    }
 }
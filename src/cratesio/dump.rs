@@ -0,0 +1,396 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use reqwest_middleware::ClientWithMiddleware;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+
+use super::dump_db::DumpDatabase;
+use crate::cache::write_atomic;
+use crate::error::{DocsError, Result};
+
+/// Daily PostgreSQL dump of the crates.io database (CSV tables in a .tar.gz).
+pub const DUMP_URL: &str = "https://static.crates.io/db-dump.tar.gz";
+
+/// Rebuild the snapshot once it is older than this.
+const REFRESH_AFTER: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+/// The dump download may take far longer than the client-wide request timeout.
+const DUMP_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+/// Only crates with at least this many dependents are kept — the ones whose
+/// reverse-dependency queries are expensive and most often throttled.
+const POPULAR_MIN_DEPENDENTS: usize = 25;
+const SNAPSHOT_TABLES: [&str; 3] = ["crates.csv", "versions.csv", "dependencies.csv"];
+const DATABASE_TABLES: [&str; 10] = [
+    "crates.csv", "crate_downloads.csv", "versions.csv", "dependencies.csv", "version_downloads.csv",
+    "keywords.csv", "crates_keywords.csv", "categories.csv", "crates_categories.csv", "users.csv",
+];
+
+/// One dependent crate as recorded in the DB dump (latest non-yanked version only).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SnapshotDependent {
+    pub crate_name: String,
+    pub version: String,
+    pub req: String,
+    pub optional: bool,
+    pub default_features: bool,
+    pub features: Vec<String>,
+    /// "normal", "build" or "dev"
+    pub kind: String,
+}
+
+/// Dependents of one crate, as stored on disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotDependents {
+    /// Unix timestamp (secs) of the dump the snapshot was built from
+    pub built_at: u64,
+    pub dependents: Vec<SnapshotDependent>,
+}
+
+/// Local reverse-dependency snapshot built from the crates.io DB dump.
+///
+/// Opt-in with `DOCS_MCP_DB_DUMP=1`. Used by `crate_dependents_list` when the crates.io
+/// reverse-dependencies endpoint fails (typically because it is throttled). Built by [`refresh`].
+#[derive(Debug, Clone)]
+pub struct DependentsSnapshot {
+    dir: PathBuf,
+}
+
+impl DependentsSnapshot {
+    pub fn new(cache_dir: &Path) -> Self {
+        Self { dir: cache_dir.join("db-dump") }
+    }
+
+    /// Whether the user opted in to downloading the DB dump.
+    pub fn enabled() -> bool {
+        std::env::var("DOCS_MCP_DB_DUMP").is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+    }
+
+    fn marker_path(&self) -> PathBuf {
+        self.dir.join("built_at")
+    }
+
+    /// Unix timestamp of the last successful build, if any.
+    pub fn built_at(&self) -> Option<u64> {
+        std::fs::read_to_string(self.marker_path()).ok()?.trim().parse().ok()
+    }
+
+    pub fn is_stale(&self) -> bool {
+        let Some(built_at) = self.built_at() else { return true };
+        let built = SystemTime::UNIX_EPOCH + Duration::from_secs(built_at);
+        built.elapsed().map(|age| age > REFRESH_AFTER).unwrap_or(true)
+    }
+
+    /// Snapshot dependents of `name`, or `None` if the crate is not in the snapshot.
+    pub fn dependents(&self, name: &str) -> Option<SnapshotDependents> {
+        let raw = std::fs::read_to_string(self.crate_path(name)).ok()?;
+        serde_json::from_str(&raw).ok()
+    }
+
+    fn crate_path(&self, name: &str) -> PathBuf {
+        self.dir.join("dependents").join(crate_file_name(name))
+    }
+
+    /// Rebuild the per-crate dependents files from extracted dump tables.
+    ///
+    /// The files are written to a scratch directory that then replaces the old one, so
+    /// an interrupted build leaves the previous snapshot in place.
+    fn build_from_tables(&self, tables_dir: &Path, built_at: u64) -> Result<()> {
+        let by_crate = dependents_from_tables(tables_dir)?;
+        let out_dir = self.dir.join("dependents");
+        let (partial, old) = (self.dir.join("dependents.partial"), self.dir.join("dependents.old"));
+        let _ = std::fs::remove_dir_all(&partial);
+        std::fs::create_dir_all(&partial)?;
+        for (name, dependents) in by_crate {
+            if dependents.len() < POPULAR_MIN_DEPENDENTS { continue; }
+            let entry = SnapshotDependents { built_at, dependents };
+            std::fs::write(partial.join(crate_file_name(&name)), serde_json::to_string(&entry)?)?;
+        }
+        let _ = std::fs::remove_dir_all(&old);
+        if out_dir.exists() {
+            std::fs::rename(&out_dir, &old)?;
+        }
+        std::fs::rename(&partial, &out_dir)?;
+        let _ = std::fs::remove_dir_all(&old);
+        write_atomic(&self.marker_path(), built_at.to_string().as_bytes())?;
+        Ok(())
+    }
+}
+
+fn crate_file_name(name: &str) -> String {
+    format!("{}.json", name.to_lowercase())
+}
+
+/// Download the DB dump once and rebuild every enabled consumer from it.
+///
+/// Takes minutes and several GB of scratch space under `work_dir`; run it in the background.
+/// Only the built outputs are kept afterwards, and the scratch files are removed even when
+/// `cancel` stops the refresh part way.
+pub async fn refresh(
+    client: &ClientWithMiddleware,
+    work_dir: &Path,
+    snapshot: Option<DependentsSnapshot>,
+    database: Option<DumpDatabase>,
+    cancel: CancellationToken,
+) -> Result<()> {
+    if snapshot.is_none() && database.is_none() {
+        return Ok(());
+    }
+    std::fs::create_dir_all(work_dir)?;
+    let archive = work_dir.join("db-dump.tar.gz");
+    let tables_dir = work_dir.join("tables");
+    let result = async {
+        download(client, &archive, &cancel).await?;
+        let (archive, tables_dir) = (archive.clone(), tables_dir.clone());
+        tokio::task::spawn_blocking(move || build(&archive, &tables_dir, snapshot, database, &cancel))
+            .await
+            .map_err(|e| DocsError::Other(format!("DB dump build task failed: {e}")))?
+    }.await;
+    // The archive and raw tables are several GB; only the built outputs are kept
+    let _ = tokio::fs::remove_dir_all(&tables_dir).await;
+    let _ = tokio::fs::remove_file(&archive).await;
+    result
+}
+
+/// Extract the tables the consumers need from `archive` and build each consumer.
+fn build(
+    archive: &Path,
+    tables_dir: &Path,
+    snapshot: Option<DependentsSnapshot>,
+    database: Option<DumpDatabase>,
+    cancel: &CancellationToken,
+) -> Result<()> {
+    check_cancelled(cancel)?;
+    let mut tables: Vec<&str> = Vec::new();
+    if snapshot.is_some() {
+        tables.extend(SNAPSHOT_TABLES);
+    }
+    if database.is_some() {
+        tables.extend(DATABASE_TABLES);
+    }
+    std::fs::create_dir_all(tables_dir)?;
+    extract_tables(archive, tables_dir, &tables, cancel)?;
+
+    let built_at = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs();
+    if let Some(snapshot) = &snapshot {
+        check_cancelled(cancel)?;
+        snapshot.build_from_tables(tables_dir, built_at)?;
+    }
+    if let Some(database) = &database {
+        check_cancelled(cancel)?;
+        database.load_tables(tables_dir, built_at)?;
+    }
+    Ok(())
+}
+
+fn check_cancelled(cancel: &CancellationToken) -> Result<()> {
+    if cancel.is_cancelled() {
+        return Err(DocsError::Other("DB dump refresh cancelled".to_string()));
+    }
+    Ok(())
+}
+
+/// Stream the dump archive to `dest`.
+async fn download(client: &ClientWithMiddleware, dest: &Path, cancel: &CancellationToken) -> Result<()> {
+    let mut resp = client.get(DUMP_URL).timeout(DUMP_TIMEOUT).send().await?;
+    if !resp.status().is_success() {
+        return Err(DocsError::Status { status: resp.status(), url: DUMP_URL.to_string() });
+    }
+    let mut file = tokio::fs::File::create(dest).await?;
+    loop {
+        let chunk = tokio::select! {
+            _ = cancel.cancelled() => return check_cancelled(cancel),
+            chunk = resp.chunk() => chunk.map_err(DocsError::Http)?,
+        };
+        let Some(chunk) = chunk else { return Ok(()) };
+        tokio::io::AsyncWriteExt::write_all(&mut file, &chunk).await?;
+    }
+}
+
+/// Unpack the named CSV tables from `archive` into `dest`.
+fn extract_tables(archive: &Path, dest: &Path, tables: &[&str], cancel: &CancellationToken) -> Result<()> {
+    let file = std::fs::File::open(archive)?;
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    for entry in tar.entries()? {
+        check_cancelled(cancel)?;
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        let Some(file_name) = path.file_name().and_then(|f| f.to_str()) else { continue };
+        // Tables live under `<date>/data/<table>.csv`
+        let in_data_dir = path.parent().and_then(|p| p.file_name()).is_some_and(|d| d == "data");
+        if in_data_dir && tables.contains(&file_name) {
+            entry.unpack(dest.join(file_name))?;
+        }
+    }
+    Ok(())
+}
+
+/// Build `crate name → dependents` from the `crates`, `versions` and `dependencies` tables,
+/// considering only the latest non-yanked version of each dependent crate.
+fn dependents_from_tables(dir: &Path) -> Result<HashMap<String, Vec<SnapshotDependent>>> {
+    // crates.csv: id → name
+    let mut crates = csv_reader(&dir.join("crates.csv"))?;
+    let [c_id, c_name] = columns(&mut crates, ["id", "name"])?;
+    let mut crate_names: HashMap<String, String> = HashMap::new();
+    for row in crates.records() {
+        let row = row.map_err(csv_error)?;
+        crate_names.insert(row[c_id].to_string(), row[c_name].to_string());
+    }
+
+    // versions.csv: latest non-yanked version per crate
+    let mut versions = csv_reader(&dir.join("versions.csv"))?;
+    let [v_id, v_crate, v_num, v_yanked] = columns(&mut versions, ["id", "crate_id", "num", "yanked"])?;
+    let mut latest: HashMap<String, (Version, String)> = HashMap::new();
+    for row in versions.records() {
+        let row = row.map_err(csv_error)?;
+        if &row[v_yanked] == "t" { continue; }
+        let Ok(num) = Version::parse(&row[v_num]) else { continue };
+        let newer = latest.get(&row[v_crate]).is_none_or(|(v, _)| num > *v);
+        if newer {
+            latest.insert(row[v_crate].to_string(), (num, row[v_id].to_string()));
+        }
+    }
+    // version id → (dependent crate id, version)
+    let latest_versions: HashMap<String, (String, String)> = latest.into_iter()
+        .map(|(crate_id, (num, version_id))| (version_id, (crate_id, num.to_string())))
+        .collect();
+
+    // dependencies.csv: rows from those latest versions, keyed by the depended-on crate
+    let mut deps = csv_reader(&dir.join("dependencies.csv"))?;
+    let [d_version, d_crate, d_req, d_optional, d_default, d_features, d_kind] = columns(
+        &mut deps,
+        ["version_id", "crate_id", "req", "optional", "default_features", "features", "kind"],
+    )?;
+    let mut by_crate: HashMap<String, Vec<SnapshotDependent>> = HashMap::new();
+    let mut seen: HashSet<(String, String)> = HashSet::new();
+    for row in deps.records() {
+        let row = row.map_err(csv_error)?;
+        let Some((dependent_id, version)) = latest_versions.get(&row[d_version]) else { continue };
+        let (Some(target), Some(dependent)) = (crate_names.get(&row[d_crate]), crate_names.get(dependent_id)) else {
+            continue;
+        };
+        // A crate can depend on the same crate twice (e.g. normal + dev); keep the first
+        if !seen.insert((target.clone(), dependent.clone())) { continue; }
+        by_crate.entry(target.clone()).or_default().push(SnapshotDependent {
+            crate_name: dependent.clone(),
+            version: version.clone(),
+            req: row[d_req].to_string(),
+            optional: &row[d_optional] == "t",
+            default_features: &row[d_default] == "t",
+            features: parse_pg_array(&row[d_features]),
+            kind: dependency_kind(&row[d_kind]).to_string(),
+        });
+    }
+    for dependents in by_crate.values_mut() {
+        dependents.sort_by(|a, b| a.crate_name.cmp(&b.crate_name));
+    }
+    Ok(by_crate)
+}
+
+pub(crate) fn csv_reader(path: &Path) -> Result<csv::Reader<std::fs::File>> {
+    csv::Reader::from_path(path).map_err(csv_error)
+}
+
+/// Indices of the named columns, so the dump's column order doesn't matter.
+pub(crate) fn columns<const N: usize>(reader: &mut csv::Reader<std::fs::File>, names: [&str; N]) -> Result<[usize; N]> {
+    let headers = reader.headers().map_err(csv_error)?.clone();
+    let mut out = [0; N];
+    for (slot, name) in out.iter_mut().zip(names) {
+        *slot = headers.iter().position(|h| h == name)
+            .ok_or_else(|| DocsError::Other(format!("DB dump table is missing column '{name}'")))?;
+    }
+    Ok(out)
+}
+
+pub(crate) fn csv_error(e: csv::Error) -> DocsError {
+    DocsError::Other(format!("DB dump CSV error: {e}"))
+}
+
+/// Parse a PostgreSQL array literal like `{derive,"rc"}` into its elements.
+pub(crate) fn parse_pg_array(s: &str) -> Vec<String> {
+    let inner = s.trim().trim_start_matches('{').trim_end_matches('}');
+    inner.split(',')
+        .map(|f| f.trim().trim_matches('"'))
+        .filter(|f| !f.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// The dump stores dependency kinds as integers.
+pub(crate) fn dependency_kind(kind: &str) -> &'static str {
+    match kind {
+        "1" => "build",
+        "2" => "dev",
+        _ => "normal",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_pg_array_handles_quotes_and_empty() {
+        assert_eq!(parse_pg_array("{derive,\"rc\"}"), vec!["derive", "rc"]);
+        assert!(parse_pg_array("{}").is_empty());
+    }
+
+    #[test]
+    fn dependents_from_tables_uses_latest_versions_only() {
+        let dir = std::env::temp_dir().join(format!("docs-mcp-dump-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("crates.csv"), "id,name\n1,serde\n2,app\n3,tool\n").unwrap();
+        std::fs::write(dir.join("versions.csv"), "id,crate_id,num,yanked\n10,2,0.1.0,f\n11,2,0.2.0,f\n12,2,0.3.0,t\n20,3,1.0.0,f\n").unwrap();
+        std::fs::write(
+            dir.join("dependencies.csv"),
+            "id,version_id,crate_id,req,optional,default_features,features,kind\n\
+             1,10,1,^0.9,f,t,{},0\n\
+             2,11,1,^1.0,f,t,{derive},0\n\
+             3,12,1,^1.1,f,t,{},0\n\
+             4,20,1,^1,t,f,{},2\n",
+        ).unwrap();
+
+        let by_crate = dependents_from_tables(&dir).unwrap();
+        let serde = &by_crate["serde"];
+        assert_eq!(serde.len(), 2);
+        assert_eq!(serde[0].crate_name, "app");
+        assert_eq!(serde[0].version, "0.2.0", "yanked 0.3.0 is skipped");
+        assert_eq!(serde[0].features, vec!["derive"]);
+        assert_eq!(serde[1].crate_name, "tool");
+        assert_eq!(serde[1].kind, "dev");
+        assert!(serde[1].optional);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rebuild_replaces_the_whole_snapshot_unless_cancelled() {
+        let cache_dir = std::env::temp_dir().join(format!("docs-mcp-dump-build-{}", std::process::id()));
+        let tables_dir = cache_dir.join("tables");
+        std::fs::create_dir_all(&tables_dir).unwrap();
+        std::fs::write(tables_dir.join("crates.csv"), "id,name\n1,serde\n").unwrap();
+        std::fs::write(tables_dir.join("versions.csv"), "id,crate_id,num,yanked\n").unwrap();
+        std::fs::write(tables_dir.join("dependencies.csv"), "id,version_id,crate_id,req,optional,default_features,features,kind\n").unwrap();
+        let snapshot = DependentsSnapshot::new(&cache_dir);
+        let stale = snapshot.crate_path("stale");
+        std::fs::create_dir_all(stale.parent().unwrap()).unwrap();
+        std::fs::write(&stale, "{}").unwrap();
+
+        let cancelled = CancellationToken::new();
+        cancelled.cancel();
+        let archive = cache_dir.join("missing.tar.gz");
+        let err = build(&archive, &tables_dir, Some(snapshot.clone()), None, &cancelled).unwrap_err();
+        assert!(err.to_string().contains("cancelled"), "{err}");
+        assert!(stale.exists(), "a cancelled build keeps the old snapshot");
+
+        snapshot.build_from_tables(&tables_dir, 42).unwrap();
+        assert!(!stale.exists(), "the new snapshot replaces the old one whole");
+        assert_eq!(snapshot.built_at(), Some(42));
+        assert!(!cache_dir.join("db-dump/dependents.partial").exists());
+        std::fs::remove_dir_all(&cache_dir).unwrap();
+    }
+}
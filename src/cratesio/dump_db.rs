@@ -0,0 +1,849 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use rusqlite::{Connection, OpenFlags, OptionalExtension, params};
+use semver::Version;
+
+use super::client::{
+    Category, CrateInfo, CrateResponse, DependenciesResponse, Dependency, DownloadsResponse, Keyword,
+    Publisher, ReverseDep, ReverseDepVersion, ReverseDepsMetaSerde, ReverseDepsResponse, VersionDownload,
+    VersionInfo, VersionsResponse,
+};
+use super::dump::{csv_error, csv_reader, dependency_kind, parse_pg_array};
+use crate::error::Result;
+
+const DB_FILE: &str = "crates.sqlite";
+/// Bumped whenever [`SCHEMA`] changes; databases built with another version are rebuilt.
+const SCHEMA_VERSION: &str = "2";
+/// The dump is published daily; older data is not served.
+const FRESH_FOR: Duration = Duration::from_secs(2 * 24 * 60 * 60);
+/// Rebuild once the database is older than this.
+const REFRESH_AFTER: Duration = Duration::from_secs(24 * 60 * 60);
+/// Matches the window of the crates.io downloads endpoint.
+const DOWNLOAD_DAYS: i64 = 90;
+
+const SCHEMA: &str = "
+CREATE TABLE meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+CREATE TABLE crates (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    description TEXT,
+    homepage TEXT,
+    documentation TEXT,
+    repository TEXT,
+    downloads INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    max_version TEXT,
+    max_stable_version TEXT,
+    newest_version TEXT,
+    default_version_id INTEGER
+);
+CREATE TABLE versions (
+    id INTEGER PRIMARY KEY,
+    crate_id INTEGER NOT NULL,
+    num TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT,
+    downloads INTEGER NOT NULL DEFAULT 0,
+    features TEXT,
+    yanked INTEGER NOT NULL,
+    yank_message TEXT,
+    license TEXT,
+    crate_size INTEGER,
+    rust_version TEXT,
+    edition TEXT,
+    has_lib INTEGER,
+    bin_names TEXT,
+    checksum TEXT,
+    links TEXT,
+    published_by INTEGER
+);
+CREATE TABLE dependencies (
+    id INTEGER PRIMARY KEY,
+    version_id INTEGER NOT NULL,
+    crate_id INTEGER NOT NULL,
+    req TEXT NOT NULL,
+    optional INTEGER NOT NULL,
+    default_features INTEGER NOT NULL,
+    features TEXT NOT NULL,
+    target TEXT,
+    kind TEXT NOT NULL
+);
+CREATE TABLE version_downloads (
+    version_id INTEGER NOT NULL,
+    date TEXT NOT NULL,
+    downloads INTEGER NOT NULL
+);
+CREATE TABLE keywords (
+    id INTEGER PRIMARY KEY,
+    keyword TEXT NOT NULL,
+    crates_cnt INTEGER NOT NULL DEFAULT 0
+);
+CREATE TABLE crates_keywords (crate_id INTEGER NOT NULL, keyword_id INTEGER NOT NULL);
+CREATE TABLE categories (
+    id INTEGER PRIMARY KEY,
+    slug TEXT NOT NULL,
+    category TEXT NOT NULL,
+    description TEXT,
+    crates_cnt INTEGER NOT NULL DEFAULT 0
+);
+CREATE TABLE crates_categories (crate_id INTEGER NOT NULL, category_id INTEGER NOT NULL);
+CREATE TABLE users (
+    id INTEGER PRIMARY KEY,
+    login TEXT NOT NULL,
+    name TEXT,
+    avatar TEXT
+);
+";
+
+const INDEXES: &str = "
+CREATE UNIQUE INDEX crates_name ON crates (name);
+CREATE INDEX crates_default_version ON crates (default_version_id);
+CREATE INDEX versions_crate ON versions (crate_id);
+CREATE INDEX dependencies_version ON dependencies (version_id);
+CREATE INDEX dependencies_crate ON dependencies (crate_id);
+CREATE INDEX version_downloads_version ON version_downloads (version_id);
+CREATE INDEX crates_keywords_crate ON crates_keywords (crate_id);
+CREATE INDEX crates_categories_crate ON crates_categories (crate_id);
+";
+
+/// Embedded SQLite copy of the crates.io DB dump.
+///
+/// Opt-in with `DOCS_MCP_DB_DUMP_SQLITE=1`. While the data is fresh, `CratesIoClient`
+/// answers crate, versions, dependencies, dependents and downloads lookups from it instead
+/// of the rate-limited API, which makes batch audits over many crates practical.
+///
+/// Queries are blocking SQLite reads; async callers run them on the blocking pool.
+#[derive(Debug, Clone)]
+pub struct DumpDatabase {
+    path: PathBuf,
+    /// `built_at` of the file last read, so freshness checks only reopen the database
+    /// after a rebuild
+    built: Arc<Mutex<Option<BuiltAt>>>,
+}
+
+/// The `built_at` read from the database file last modified at `modified`.
+#[derive(Debug, Clone, Copy)]
+struct BuiltAt {
+    modified: SystemTime,
+    built_at: Option<u64>,
+}
+
+impl DumpDatabase {
+    pub fn new(cache_dir: &Path) -> Self {
+        Self::at(cache_dir.join("db-dump").join(DB_FILE))
+    }
+
+    fn at(path: PathBuf) -> Self {
+        Self { path, built: Arc::default() }
+    }
+
+    /// Whether the user opted in to the SQLite store.
+    pub fn enabled() -> bool {
+        std::env::var("DOCS_MCP_DB_DUMP_SQLITE").is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Unix timestamp of the dump the database was built from, if it exists and has the
+    /// current schema.
+    pub fn built_at(&self) -> Option<u64> {
+        let modified = std::fs::metadata(&self.path).and_then(|m| m.modified()).ok()?;
+        let mut built = self.built.lock().ok()?;
+        if let Some(seen) = *built
+            && seen.modified == modified
+        {
+            return seen.built_at;
+        }
+        let built_at = self.read_built_at();
+        *built = Some(BuiltAt { modified, built_at });
+        built_at
+    }
+
+    fn read_built_at(&self) -> Option<u64> {
+        let conn = self.open().ok()??;
+        let meta = |key: &str| {
+            conn.query_row("SELECT value FROM meta WHERE key = ?1", params![key], |row| row.get::<_, String>(0)).ok()
+        };
+        if meta("schema").as_deref() != Some(SCHEMA_VERSION) {
+            return None;
+        }
+        meta("built_at")?.parse().ok()
+    }
+
+    fn age(&self) -> Option<Duration> {
+        let built = SystemTime::UNIX_EPOCH + Duration::from_secs(self.built_at()?);
+        built.elapsed().ok()
+    }
+
+    /// Whether queries should be served from the database.
+    pub fn is_fresh(&self) -> bool {
+        self.age().is_some_and(|age| age <= FRESH_FOR)
+    }
+
+    pub fn is_stale(&self) -> bool {
+        self.age().is_none_or(|age| age > REFRESH_AFTER)
+    }
+
+    /// Open read-only, or `None` if the database has not been built yet.
+    ///
+    /// A new connection per lookup keeps reads consistent across an atomic rebuild.
+    fn open(&self) -> Result<Option<Connection>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(Connection::open_with_flags(&self.path, OpenFlags::SQLITE_OPEN_READ_ONLY)?))
+    }
+
+    // ─── Loading ──────────────────────────────────────────────────────────────
+
+    /// Build a new database from extracted dump tables and swap it in.
+    pub(crate) fn load_tables(&self, tables_dir: &Path, built_at: u64) -> Result<()> {
+        let tmp = self.path.with_extension("sqlite.tmp");
+        let _ = std::fs::remove_file(&tmp);
+        let mut conn = Connection::open(&tmp)?;
+        conn.execute_batch("PRAGMA journal_mode = OFF; PRAGMA synchronous = OFF;")?;
+        conn.execute_batch(SCHEMA)?;
+
+        let tx = conn.transaction()?;
+        load_crates(&tx, tables_dir)?;
+        load_versions(&tx, tables_dir)?;
+        load_dependencies(&tx, tables_dir)?;
+        load_version_downloads(&tx, tables_dir)?;
+        load_labels(&tx, tables_dir)?;
+        load_users(&tx, tables_dir)?;
+        tx.execute("INSERT INTO meta (key, value) VALUES ('built_at', ?1)", params![built_at.to_string()])?;
+        tx.execute("INSERT INTO meta (key, value) VALUES ('schema', ?1)", params![SCHEMA_VERSION])?;
+        tx.commit()?;
+        conn.execute_batch(INDEXES)?;
+        drop(conn);
+
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+
+    // ─── Queries ──────────────────────────────────────────────────────────────
+
+    /// Equivalent of `GET /crates/{name}`, or `None` if the crate is not in the dump.
+    pub fn crate_response(&self, name: &str) -> Result<Option<CrateResponse>> {
+        let Some(conn) = self.open()? else { return Ok(None) };
+        let krate = conn.query_row(
+            "SELECT id, name, description, homepage, documentation, repository, downloads,
+                    created_at, updated_at, max_stable_version, max_version, newest_version
+             FROM crates WHERE name = ?1",
+            params![name],
+            |row| Ok((row.get::<_, i64>(0)?, CrateInfo {
+                id: row.get(1)?,
+                name: row.get(1)?,
+                description: row.get(2)?,
+                homepage: row.get(3)?,
+                documentation: row.get(4)?,
+                repository: row.get(5)?,
+                downloads: row.get::<_, i64>(6)? as u64,
+                recent_downloads: None,
+                created_at: row.get(7)?,
+                updated_at: row.get(8)?,
+                max_stable_version: row.get(9)?,
+                max_version: row.get(10)?,
+                newest_version: row.get(11)?,
+                links: Some(crate_links(name)),
+                categories: None,
+                keywords: None,
+            })),
+        ).optional()?;
+        let Some((crate_id, mut krate)) = krate else { return Ok(None) };
+
+        krate.recent_downloads = Some(conn.query_row(
+            "SELECT COALESCE(SUM(d.downloads), 0) FROM version_downloads d
+             JOIN versions v ON v.id = d.version_id WHERE v.crate_id = ?1",
+            params![crate_id],
+            |row| row.get::<_, i64>(0),
+        )? as u64);
+        let versions = query_versions(&conn, crate_id, &krate.name)?;
+
+        let mut stmt = conn.prepare(
+            "SELECT k.keyword, k.crates_cnt FROM crates_keywords ck JOIN keywords k ON k.id = ck.keyword_id
+             WHERE ck.crate_id = ?1 ORDER BY k.keyword",
+        )?;
+        let keywords = stmt.query_map(params![crate_id], |row| Ok(Keyword {
+            id: row.get(0)?,
+            keyword: row.get(0)?,
+            crates_cnt: row.get::<_, i64>(1)? as u64,
+        }))?.collect::<rusqlite::Result<Vec<_>>>()?;
+        let mut stmt = conn.prepare(
+            "SELECT c.slug, c.category, c.crates_cnt, c.description FROM crates_categories cc
+             JOIN categories c ON c.id = cc.category_id WHERE cc.crate_id = ?1 ORDER BY c.category",
+        )?;
+        let categories = stmt.query_map(params![crate_id], |row| Ok(Category {
+            id: row.get(0)?,
+            category: row.get(1)?,
+            crates_cnt: row.get::<_, i64>(2)? as u64,
+            description: row.get(3)?,
+        }))?.collect::<rusqlite::Result<Vec<_>>>()?;
+        krate.keywords = Some(keywords.iter().map(|k| k.id.clone()).collect());
+        krate.categories = Some(categories.iter().map(|c| c.id.clone()).collect());
+        Ok(Some(CrateResponse {
+            krate,
+            versions: Some(versions),
+            keywords: Some(keywords),
+            categories: Some(categories),
+        }))
+    }
+
+    /// Equivalent of `GET /crates/{name}/versions`.
+    pub fn versions(&self, name: &str) -> Result<Option<VersionsResponse>> {
+        let Some(conn) = self.open()? else { return Ok(None) };
+        let Some(crate_id) = crate_id(&conn, name)? else { return Ok(None) };
+        Ok(Some(VersionsResponse { versions: query_versions(&conn, crate_id, name)? }))
+    }
+
+    /// Equivalent of `GET /crates/{name}/{version}/dependencies`.
+    pub fn dependencies(&self, name: &str, version: &str) -> Result<Option<DependenciesResponse>> {
+        let Some(conn) = self.open()? else { return Ok(None) };
+        let version_id: Option<i64> = conn.query_row(
+            "SELECT v.id FROM versions v JOIN crates c ON c.id = v.crate_id WHERE c.name = ?1 AND v.num = ?2",
+            params![name, version],
+            |row| row.get(0),
+        ).optional()?;
+        let Some(version_id) = version_id else { return Ok(None) };
+
+        let mut stmt = conn.prepare(
+            "SELECT d.id, d.version_id, c.name, d.req, d.optional, d.default_features, d.features, d.target, d.kind
+             FROM dependencies d JOIN crates c ON c.id = d.crate_id
+             WHERE d.version_id = ?1 ORDER BY c.name",
+        )?;
+        let dependencies = stmt.query_map(params![version_id], |row| Ok(Dependency {
+            id: Some(row.get::<_, i64>(0)? as u64),
+            version_id: Some(row.get::<_, i64>(1)? as u64),
+            crate_id: row.get(2)?,
+            req: row.get(3)?,
+            optional: row.get(4)?,
+            default_features: row.get(5)?,
+            features: json_list(row.get::<_, String>(6)?),
+            target: row.get(7)?,
+            kind: row.get(8)?,
+            downloads: None,
+        }))?.collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(Some(DependenciesResponse { dependencies }))
+    }
+
+    /// Equivalent of `GET /crates/{name}/reverse_dependencies`: dependents' default
+    /// versions, most downloaded first.
+    pub fn reverse_deps(&self, name: &str, page: u32, per_page: u32) -> Result<Option<ReverseDepsResponse>> {
+        let Some(conn) = self.open()? else { return Ok(None) };
+        let Some(crate_id) = crate_id(&conn, name)? else { return Ok(None) };
+
+        let total: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM dependencies d JOIN crates c ON c.default_version_id = d.version_id
+             WHERE d.crate_id = ?1",
+            params![crate_id],
+            |row| row.get(0),
+        )?;
+        let mut stmt = conn.prepare(
+            "SELECT d.id, d.version_id, d.req, d.optional, d.default_features, d.features, d.kind,
+                    v.num, c.name, v.downloads, v.created_at, c.downloads
+             FROM dependencies d
+             JOIN crates c ON c.default_version_id = d.version_id
+             JOIN versions v ON v.id = d.version_id
+             WHERE d.crate_id = ?1
+             ORDER BY c.downloads DESC, c.name
+             LIMIT ?2 OFFSET ?3",
+        )?;
+        let offset = page.saturating_sub(1) as i64 * per_page as i64;
+        let rows = stmt.query_map(params![crate_id, per_page as i64, offset], |row| {
+            let version_id = row.get::<_, i64>(1)? as u64;
+            Ok((
+                ReverseDep {
+                    id: row.get::<_, i64>(0)? as u64,
+                    version_id,
+                    crate_id: name.to_string(),
+                    req: row.get(2)?,
+                    optional: row.get(3)?,
+                    default_features: row.get(4)?,
+                    features: json_list(row.get::<_, String>(5)?),
+                    kind: row.get(6)?,
+                    downloads: Some(row.get::<_, i64>(11)? as u64),
+                },
+                ReverseDepVersion {
+                    id: version_id,
+                    num: row.get(7)?,
+                    crate_name: row.get(8)?,
+                    downloads: row.get::<_, i64>(9)? as u64,
+                    created_at: row.get(10)?,
+                },
+            ))
+        })?.collect::<rusqlite::Result<Vec<_>>>()?;
+        let (dependencies, versions): (Vec<_>, Vec<_>) = rows.into_iter().unzip();
+        Ok(Some(ReverseDepsResponse {
+            dependencies,
+            versions,
+            meta: ReverseDepsMetaSerde { total: total as u64 },
+        }))
+    }
+
+    /// Equivalent of `GET /crates/{name}/downloads`: per-version daily counts for the
+    /// last 90 days of the dump.
+    pub fn downloads(&self, name: &str) -> Result<Option<DownloadsResponse>> {
+        let Some(conn) = self.open()? else { return Ok(None) };
+        let Some(crate_id) = crate_id(&conn, name)? else { return Ok(None) };
+        let mut stmt = conn.prepare(
+            "SELECT d.version_id, d.downloads, d.date FROM version_downloads d
+             JOIN versions v ON v.id = d.version_id
+             WHERE v.crate_id = ?1 ORDER BY d.date, d.version_id",
+        )?;
+        let version_downloads = stmt.query_map(params![crate_id], |row| Ok(VersionDownload {
+            version: row.get::<_, i64>(0)? as u64,
+            downloads: row.get::<_, i64>(1)? as u64,
+            date: row.get(2)?,
+        }))?.collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(Some(DownloadsResponse { version_downloads }))
+    }
+}
+
+fn crate_id(conn: &Connection, name: &str) -> Result<Option<i64>> {
+    Ok(conn.query_row("SELECT id FROM crates WHERE name = ?1", params![name], |row| row.get(0)).optional()?)
+}
+
+/// The API paths crates.io lists under a crate's `links`.
+fn crate_links(name: &str) -> serde_json::Value {
+    let path = |rest: &str| format!("/api/v1/crates/{name}/{rest}");
+    serde_json::json!({
+        "owner_team": path("owner_team"),
+        "owner_user": path("owner_user"),
+        "owners": path("owners"),
+        "reverse_dependencies": path("reverse_dependencies"),
+        "version_downloads": path("downloads"),
+        "versions": null,
+    })
+}
+
+/// All versions of a crate, newest first (the API's order).
+fn query_versions(conn: &Connection, crate_id: i64, name: &str) -> Result<Vec<VersionInfo>> {
+    let mut stmt = conn.prepare(
+        "SELECT v.id, v.num, v.created_at, v.updated_at, v.downloads, v.features, v.yanked, v.yank_message,
+                v.license, v.crate_size, v.rust_version, v.edition, v.has_lib, v.bin_names, v.checksum,
+                v.links, u.id, u.login, u.name, u.avatar
+         FROM versions v LEFT JOIN users u ON u.id = v.published_by
+         WHERE v.crate_id = ?1 ORDER BY v.created_at DESC",
+    )?;
+    let versions = stmt.query_map(params![crate_id], |row| {
+        let num: String = row.get(1)?;
+        let published_by = match row.get::<_, Option<i64>>(16)? {
+            Some(id) => Some(Publisher {
+                id: id as u64,
+                login: row.get(17)?,
+                name: row.get(18)?,
+                avatar: row.get(19)?,
+            }),
+            None => None,
+        };
+        let version_path = |rest: &str| format!("/api/v1/crates/{name}/{num}/{rest}");
+        let links = serde_json::json!({
+            "authors": version_path("authors"),
+            "dependencies": version_path("dependencies"),
+            "version_downloads": version_path("downloads"),
+        });
+        Ok(VersionInfo {
+            id: row.get::<_, i64>(0)? as u64,
+            dl_path: Some(format!("/api/v1/crates/{name}/{num}/download")),
+            readme_path: Some(format!("/api/v1/crates/{name}/{num}/readme")),
+            crate_id: Some(name.to_string()),
+            num,
+            created_at: row.get(2)?,
+            updated_at: row.get(3)?,
+            downloads: row.get::<_, i64>(4)? as u64,
+            features: row.get::<_, Option<String>>(5)?.and_then(|f| serde_json::from_str(&f).ok()),
+            yanked: row.get(6)?,
+            yank_message: row.get(7)?,
+            license: row.get(8)?,
+            crate_size: row.get::<_, Option<i64>>(9)?.map(|s| s as u64),
+            rust_version: row.get(10)?,
+            edition: row.get(11)?,
+            has_lib: row.get(12)?,
+            bins: row.get::<_, Option<String>>(13)?.map(json_list),
+            published_by,
+            checksum: row.get(14)?,
+            links: Some(links),
+            lib_links: row.get(15)?,
+            // Not in the dump; only the API reports trusted publishing
+            trustpub_data: None,
+        })
+    })?.collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(versions)
+}
+
+/// Lists are stored as JSON arrays.
+fn json_list(raw: String) -> Vec<String> {
+    serde_json::from_str(&raw).unwrap_or_default()
+}
+
+// ─── CSV loading ──────────────────────────────────────────────────────────────
+
+/// Header-name → column index for one dump table. Columns come and go between dump
+/// versions, so everything beyond the keys is looked up optionally.
+struct Columns(HashMap<String, usize>);
+
+impl Columns {
+    fn of(reader: &mut csv::Reader<std::fs::File>) -> Result<Self> {
+        let headers = reader.headers().map_err(csv_error)?;
+        Ok(Self(headers.iter().enumerate().map(|(i, h)| (h.to_string(), i)).collect()))
+    }
+
+    fn required(&self, name: &str) -> Result<usize> {
+        self.0.get(name).copied().ok_or_else(|| {
+            crate::error::DocsError::Other(format!("DB dump table is missing column '{name}'"))
+        })
+    }
+
+    fn optional(&self, name: &str) -> Option<usize> {
+        self.0.get(name).copied()
+    }
+}
+
+/// Non-empty field at an optional column; the dump writes NULL as an empty string.
+fn field(row: &csv::StringRecord, col: Option<usize>) -> Option<&str> {
+    col.and_then(|c| row.get(c)).filter(|v| !v.is_empty())
+}
+
+fn flag(row: &csv::StringRecord, col: Option<usize>) -> bool {
+    field(row, col) == Some("t")
+}
+
+fn number(row: &csv::StringRecord, col: Option<usize>) -> Option<i64> {
+    field(row, col).and_then(|v| v.parse().ok())
+}
+
+fn load_crates(tx: &rusqlite::Transaction, dir: &Path) -> Result<()> {
+    // Newer dumps keep download totals in their own table
+    let mut totals: HashMap<String, i64> = HashMap::new();
+    if let Ok(mut reader) = csv_reader(&dir.join("crate_downloads.csv")) {
+        let cols = Columns::of(&mut reader)?;
+        let (id, downloads) = (cols.required("crate_id")?, cols.required("downloads")?);
+        for row in reader.records() {
+            let row = row.map_err(csv_error)?;
+            totals.insert(row[id].to_string(), number(&row, Some(downloads)).unwrap_or(0));
+        }
+    }
+
+    let mut reader = csv_reader(&dir.join("crates.csv"))?;
+    let cols = Columns::of(&mut reader)?;
+    let (id, name) = (cols.required("id")?, cols.required("name")?);
+    let (created, updated) = (cols.optional("created_at"), cols.optional("updated_at"));
+    let (desc, home, docs, repo, downloads) = (
+        cols.optional("description"),
+        cols.optional("homepage"),
+        cols.optional("documentation"),
+        cols.optional("repository"),
+        cols.optional("downloads"),
+    );
+    let mut stmt = tx.prepare(
+        "INSERT INTO crates (id, name, description, homepage, documentation, repository, downloads, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+    )?;
+    for row in reader.records() {
+        let row = row.map_err(csv_error)?;
+        let total = totals.get(&row[id]).copied().or_else(|| number(&row, downloads)).unwrap_or(0);
+        stmt.execute(params![
+            &row[id],
+            &row[name],
+            field(&row, desc),
+            field(&row, home),
+            field(&row, docs),
+            field(&row, repo),
+            total,
+            field(&row, created).unwrap_or_default(),
+            field(&row, updated).unwrap_or_default(),
+        ])?;
+    }
+    Ok(())
+}
+
+/// Highest/newest versions of one crate, tracked while loading `versions.csv`.
+#[derive(Default)]
+struct VersionSummary {
+    max: Option<(Version, i64)>,
+    max_stable: Option<(Version, i64)>,
+    newest: Option<(String, String)>,
+}
+
+impl VersionSummary {
+    fn add(&mut self, id: i64, num: &str, created_at: &str, yanked: bool) {
+        if yanked { return; }
+        if self.newest.as_ref().is_none_or(|(at, _)| created_at > at.as_str()) {
+            self.newest = Some((created_at.to_string(), num.to_string()));
+        }
+        let Ok(version) = Version::parse(num) else { return };
+        if version.pre.is_empty() && self.max_stable.as_ref().is_none_or(|(v, _)| version > *v) {
+            self.max_stable = Some((version.clone(), id));
+        }
+        if self.max.as_ref().is_none_or(|(v, _)| version > *v) {
+            self.max = Some((version, id));
+        }
+    }
+
+    /// The version crates.io treats as the crate's default: highest stable, else highest.
+    fn default_version_id(&self) -> Option<i64> {
+        self.max_stable.as_ref().or(self.max.as_ref()).map(|(_, id)| *id)
+    }
+}
+
+fn load_versions(tx: &rusqlite::Transaction, dir: &Path) -> Result<()> {
+    let mut reader = csv_reader(&dir.join("versions.csv"))?;
+    let cols = Columns::of(&mut reader)?;
+    let (id, crate_id, num) = (cols.required("id")?, cols.required("crate_id")?, cols.required("num")?);
+    let c = |name: &str| cols.optional(name);
+    let (created, updated, downloads, features, yanked, yank_message) =
+        (c("created_at"), c("updated_at"), c("downloads"), c("features"), c("yanked"), c("yank_message"));
+    let (license, size, rust_version, edition, has_lib, bins, checksum, links, published_by) = (
+        c("license"), c("crate_size"), c("rust_version"), c("edition"),
+        c("has_lib"), c("bin_names"), c("checksum"), c("links"), c("published_by"),
+    );
+
+    let mut summaries: HashMap<i64, VersionSummary> = HashMap::new();
+    {
+        let mut stmt = tx.prepare(
+            "INSERT INTO versions (id, crate_id, num, created_at, updated_at, downloads, features, yanked,
+                                   yank_message, license, crate_size, rust_version, edition, has_lib, bin_names,
+                                   checksum, links, published_by)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)",
+        )?;
+        for row in reader.records() {
+            let row = row.map_err(csv_error)?;
+            let (Ok(version_id), Ok(owner)) = (row[id].parse::<i64>(), row[crate_id].parse::<i64>()) else {
+                continue;
+            };
+            let created_at = field(&row, created).unwrap_or_default();
+            let is_yanked = flag(&row, yanked);
+            summaries.entry(owner).or_default().add(version_id, &row[num], created_at, is_yanked);
+            let bin_names = field(&row, bins)
+                .map(|b| serde_json::to_string(&parse_pg_array(b)))
+                .transpose()?;
+            stmt.execute(params![
+                version_id,
+                owner,
+                &row[num],
+                created_at,
+                field(&row, updated),
+                number(&row, downloads).unwrap_or(0),
+                field(&row, features),
+                is_yanked,
+                field(&row, yank_message),
+                field(&row, license),
+                number(&row, size),
+                field(&row, rust_version),
+                field(&row, edition),
+                field(&row, has_lib).map(|v| v == "t"),
+                bin_names,
+                field(&row, checksum),
+                field(&row, links),
+                number(&row, published_by),
+            ])?;
+        }
+    }
+
+    let mut stmt = tx.prepare(
+        "UPDATE crates SET max_version = ?2, max_stable_version = ?3, newest_version = ?4, default_version_id = ?5
+         WHERE id = ?1",
+    )?;
+    for (crate_id, summary) in summaries {
+        stmt.execute(params![
+            crate_id,
+            summary.max.as_ref().map(|(v, _)| v.to_string()),
+            summary.max_stable.as_ref().map(|(v, _)| v.to_string()),
+            summary.newest.as_ref().map(|(_, n)| n.as_str()),
+            summary.default_version_id(),
+        ])?;
+    }
+    Ok(())
+}
+
+fn load_dependencies(tx: &rusqlite::Transaction, dir: &Path) -> Result<()> {
+    let mut reader = csv_reader(&dir.join("dependencies.csv"))?;
+    let cols = Columns::of(&mut reader)?;
+    let (id, version_id, crate_id, req) = (
+        cols.required("id")?, cols.required("version_id")?, cols.required("crate_id")?, cols.required("req")?,
+    );
+    let (optional, default_features, features, target, kind) = (
+        cols.optional("optional"), cols.optional("default_features"), cols.optional("features"),
+        cols.optional("target"), cols.optional("kind"),
+    );
+    let mut stmt = tx.prepare(
+        "INSERT INTO dependencies (id, version_id, crate_id, req, optional, default_features, features, target, kind)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+    )?;
+    for row in reader.records() {
+        let row = row.map_err(csv_error)?;
+        let features = serde_json::to_string(&parse_pg_array(field(&row, features).unwrap_or_default()))?;
+        stmt.execute(params![
+            &row[id],
+            &row[version_id],
+            &row[crate_id],
+            &row[req],
+            flag(&row, optional),
+            flag(&row, default_features),
+            features,
+            field(&row, target),
+            dependency_kind(field(&row, kind).unwrap_or_default()),
+        ])?;
+    }
+    Ok(())
+}
+
+fn load_version_downloads(tx: &rusqlite::Transaction, dir: &Path) -> Result<()> {
+    let cutoff = (chrono::Utc::now().date_naive() - chrono::Duration::days(DOWNLOAD_DAYS)).to_string();
+    let mut reader = csv_reader(&dir.join("version_downloads.csv"))?;
+    let cols = Columns::of(&mut reader)?;
+    let (version_id, downloads, date) =
+        (cols.required("version_id")?, cols.required("downloads")?, cols.required("date")?);
+    let mut stmt = tx.prepare("INSERT INTO version_downloads (version_id, date, downloads) VALUES (?1, ?2, ?3)")?;
+    for row in reader.records() {
+        let row = row.map_err(csv_error)?;
+        // ISO dates compare correctly as strings
+        if &row[date] < cutoff.as_str() { continue; }
+        stmt.execute(params![&row[version_id], &row[date], number(&row, Some(downloads)).unwrap_or(0)])?;
+    }
+    Ok(())
+}
+
+/// Keywords and categories and which crates carry them. Older dumps without these
+/// tables leave the lists empty.
+fn load_labels(tx: &rusqlite::Transaction, dir: &Path) -> Result<()> {
+    if let Ok(mut reader) = csv_reader(&dir.join("keywords.csv")) {
+        let cols = Columns::of(&mut reader)?;
+        let (id, keyword, count) = (cols.required("id")?, cols.required("keyword")?, cols.optional("crates_cnt"));
+        let mut stmt = tx.prepare("INSERT INTO keywords (id, keyword, crates_cnt) VALUES (?1, ?2, ?3)")?;
+        for row in reader.records() {
+            let row = row.map_err(csv_error)?;
+            stmt.execute(params![&row[id], &row[keyword], number(&row, count).unwrap_or(0)])?;
+        }
+    }
+    if let Ok(mut reader) = csv_reader(&dir.join("categories.csv")) {
+        let cols = Columns::of(&mut reader)?;
+        let (id, slug, category) = (cols.required("id")?, cols.required("slug")?, cols.required("category")?);
+        let (description, count) = (cols.optional("description"), cols.optional("crates_cnt"));
+        let mut stmt = tx.prepare(
+            "INSERT INTO categories (id, slug, category, description, crates_cnt) VALUES (?1, ?2, ?3, ?4, ?5)",
+        )?;
+        for row in reader.records() {
+            let row = row.map_err(csv_error)?;
+            stmt.execute(params![
+                &row[id],
+                &row[slug],
+                &row[category],
+                field(&row, description),
+                number(&row, count).unwrap_or(0),
+            ])?;
+        }
+    }
+    for (table, label) in [("crates_keywords", "keyword_id"), ("crates_categories", "category_id")] {
+        let Ok(mut reader) = csv_reader(&dir.join(format!("{table}.csv"))) else { continue };
+        let cols = Columns::of(&mut reader)?;
+        let (crate_id, label_id) = (cols.required("crate_id")?, cols.required(label)?);
+        let mut stmt = tx.prepare(&format!("INSERT INTO {table} (crate_id, {label}) VALUES (?1, ?2)"))?;
+        for row in reader.records() {
+            let row = row.map_err(csv_error)?;
+            stmt.execute(params![&row[crate_id], &row[label_id]])?;
+        }
+    }
+    Ok(())
+}
+
+/// Accounts that published versions, for `published_by`.
+fn load_users(tx: &rusqlite::Transaction, dir: &Path) -> Result<()> {
+    let Ok(mut reader) = csv_reader(&dir.join("users.csv")) else { return Ok(()) };
+    let cols = Columns::of(&mut reader)?;
+    let (id, login) = (cols.required("id")?, cols.required("gh_login")?);
+    let (name, avatar) = (cols.optional("name"), cols.optional("gh_avatar"));
+    let mut stmt = tx.prepare("INSERT INTO users (id, login, name, avatar) VALUES (?1, ?2, ?3, ?4)")?;
+    for row in reader.records() {
+        let row = row.map_err(csv_error)?;
+        stmt.execute(params![&row[id], &row[login], field(&row, name), field(&row, avatar)])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_tables_and_answers_like_the_api() {
+        let dir = std::env::temp_dir().join(format!("docs-mcp-dump-db-test-{}", std::process::id()));
+        let tables = dir.join("tables");
+        std::fs::create_dir_all(&tables).unwrap();
+        let today = chrono::Utc::now().date_naive();
+        std::fs::write(
+            tables.join("crates.csv"),
+            "id,name,created_at,updated_at,description,homepage,documentation,repository\n\
+             1,serde,2015-01-01,2024-01-01,A serialization framework,,,https://github.com/serde-rs/serde\n\
+             2,app,2020-01-01,2024-01-01,,,,\n",
+        ).unwrap();
+        std::fs::write(tables.join("crate_downloads.csv"), "crate_id,downloads\n1,1000\n2,5\n").unwrap();
+        std::fs::write(
+            tables.join("versions.csv"),
+            "id,crate_id,num,created_at,updated_at,downloads,features,yanked,license,rust_version,bin_names,published_by\n\
+             10,1,1.0.0,2017-01-01,2017-01-01,900,{},f,MIT,,,7\n\
+             11,1,1.1.0-rc.1,2018-01-01,2018-01-01,100,\"{\"\"std\"\":[]}\",f,MIT,1.31,,\n\
+             20,2,0.1.0,2020-01-01,2020-01-01,5,{},f,,,{app},\n",
+        ).unwrap();
+        std::fs::write(
+            tables.join("dependencies.csv"),
+            "id,version_id,crate_id,req,optional,default_features,features,target,kind\n\
+             100,20,1,^1.0,f,t,{derive},,0\n",
+        ).unwrap();
+        std::fs::write(
+            tables.join("version_downloads.csv"),
+            format!("version_id,downloads,counted,date\n10,7,7,{today}\n10,9,9,2000-01-01\n"),
+        ).unwrap();
+
+        std::fs::write(tables.join("keywords.csv"), "id,keyword,crates_cnt,created_at\n3,serde,2000,2015-01-01\n").unwrap();
+        std::fs::write(tables.join("crates_keywords.csv"), "crate_id,keyword_id\n1,3\n").unwrap();
+        std::fs::write(
+            tables.join("categories.csv"),
+            "id,category,slug,description,crates_cnt\n4,Encoding,encoding,Encoding and decoding,500\n",
+        ).unwrap();
+        std::fs::write(tables.join("crates_categories.csv"), "category_id,crate_id\n4,1\n").unwrap();
+        std::fs::write(tables.join("users.csv"), "id,gh_login,name,gh_avatar,gh_id\n7,dtolnay,David Tolnay,,1\n").unwrap();
+
+        let db = DumpDatabase::at(dir.join(DB_FILE));
+        db.load_tables(&tables, 1).unwrap();
+        assert_eq!(db.built_at(), Some(1));
+
+        let krate = db.crate_response("serde").unwrap().unwrap();
+        assert_eq!(krate.krate.downloads, 1000);
+        assert_eq!(krate.krate.max_stable_version.as_deref(), Some("1.0.0"));
+        assert_eq!(krate.krate.max_version.as_deref(), Some("1.1.0-rc.1"));
+        assert_eq!(krate.krate.recent_downloads, Some(7), "downloads older than 90 days are dropped");
+        let versions = krate.versions.unwrap();
+        assert_eq!(versions[0].num, "1.1.0-rc.1", "newest first");
+        assert_eq!(versions[0].rust_version.as_deref(), Some("1.31"));
+        assert!(versions[0].features.as_ref().unwrap().contains_key("std"));
+        assert_eq!(versions[1].published_by.as_ref().map(|p| p.login.as_str()), Some("dtolnay"));
+        assert!(versions[0].published_by.is_none());
+        assert_eq!(versions[1].links.as_ref().unwrap()["dependencies"], "/api/v1/crates/serde/1.0.0/dependencies");
+        assert_eq!(krate.krate.keywords.as_deref(), Some(&["serde".to_string()][..]));
+        assert_eq!(krate.krate.categories.as_deref(), Some(&["encoding".to_string()][..]));
+        assert_eq!(krate.categories.as_ref().unwrap()[0].category, "Encoding");
+        assert_eq!(krate.keywords.as_ref().unwrap()[0].crates_cnt, 2000);
+        assert_eq!(krate.krate.links.as_ref().unwrap()["owners"], "/api/v1/crates/serde/owners");
+
+        let deps = db.dependencies("app", "0.1.0").unwrap().unwrap();
+        assert_eq!(deps.dependencies[0].crate_id, "serde");
+        assert_eq!(deps.dependencies[0].features, vec!["derive"]);
+        assert_eq!(deps.dependencies[0].kind.as_deref(), Some("normal"));
+
+        let rdeps = db.reverse_deps("serde", 1, 10).unwrap().unwrap();
+        assert_eq!(rdeps.meta.total, 1);
+        assert_eq!(rdeps.versions[0].crate_name, "app");
+        assert_eq!(rdeps.versions[0].created_at.as_deref(), Some("2020-01-01"));
+        assert_eq!(rdeps.dependencies[0].downloads, Some(5), "the dependent crate's downloads");
+
+        assert!(db.crate_response("missing").unwrap().is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
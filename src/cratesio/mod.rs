@@ -1,3 +1,6 @@
 pub mod client;
+pub mod dump;
+pub mod dump_db;
+pub mod tarball;
 
 pub use client::*;
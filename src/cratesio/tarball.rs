@@ -0,0 +1,488 @@
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use futures::StreamExt;
+use reqwest_middleware::ClientWithMiddleware;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::cache::DiskCache;
+use crate::error::{DocsError, Result};
+use crate::sparse_index::is_valid_crate_name;
+
+/// Published `.crate` archives: `{DOWNLOAD_BASE}/{name}/{name}-{version}.crate`.
+pub const DOWNLOAD_BASE: &str = "https://static.crates.io/crates";
+
+/// One file in a published crate package.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct CrateFile {
+    /// Path relative to the package root, e.g. `src/lib.rs`
+    pub path: String,
+    /// Size in bytes
+    pub size: u64,
+}
+
+/// One file read from a package, possibly cut short.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileContents {
+    /// Full size of the file in bytes
+    pub size: u64,
+    /// UTF-8 text, or `None` for binary files
+    pub text: Option<String>,
+    /// Whether `text` stops before the end of the file
+    pub truncated: bool,
+}
+
+/// The outcome of checking a package archive against its sparse index checksum.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct Checksum {
+    /// SHA-256 of the archive, in hex
+    pub sha256: String,
+    /// Whether it matched the index's `cksum`; false only when the index gave none
+    pub verified: bool,
+}
+
+/// What building a package runs besides compiling its own sources.
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub struct BuildProfile {
+    /// Build script cargo compiles and runs on the build machine first, e.g. `build.rs`
+    pub build_script: Option<String>,
+    /// Native library the package declares it links, from `package.links`
+    pub links: Option<String>,
+    /// A procedural macro, which runs inside the compiler for every crate using it
+    pub proc_macro: bool,
+}
+
+/// README file names tried when the manifest does not name one.
+const README_NAMES: [&str; 4] = ["README.md", "README", "README.markdown", "README.txt"];
+/// Manifests are small; this only guards against a malformed archive.
+const MANIFEST_MAX_BYTES: usize = 256 * 1024;
+/// Largest archive downloaded. crates.io caps uploads at 10 MB unless it raises the
+/// limit for a crate; this leaves room for those.
+const ARCHIVE_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Archives not used for this long are deleted when the cache is opened.
+const ARCHIVE_TTL: std::time::Duration = std::time::Duration::from_secs(30 * 24 * 60 * 60);
+
+/// A downloaded `.crate` source archive (a gzipped tar with a `{name}-{version}/` root).
+///
+/// Published versions never change, so archives are kept under `crates/` in the cache
+/// dir until they go unused for [`ARCHIVE_TTL`]. Every archive, downloaded or cached, is
+/// checked against the SHA-256 `cksum` of its sparse index line before anything reads it.
+#[derive(Debug, Clone)]
+pub struct CrateTarball {
+    path: PathBuf,
+    prefix: String,
+    checksum: Checksum,
+}
+
+impl CrateTarball {
+    /// Download the archive for `name` `version` unless it is already cached, and verify
+    /// it against `cksum`, the hex SHA-256 from the sparse index.
+    ///
+    /// A download that does not match is discarded with [`DocsError::ChecksumMismatch`];
+    /// a cached archive that does not match is downloaded again.
+    pub async fn fetch(
+        name: &str,
+        version: &str,
+        cksum: &str,
+        client: &ClientWithMiddleware,
+        cache: &DiskCache,
+    ) -> Result<Self> {
+        // Both end up in a cache path and a URL
+        if !is_valid_crate_name(name) {
+            return Err(DocsError::InvalidParams(format!("'{name}' is not a valid crate name")));
+        }
+        if semver::Version::parse(version).is_err() {
+            return Err(DocsError::InvalidParams(format!("'{version}' is not a semver version")));
+        }
+        // One download per archive, however many calls want it at once
+        let key = format!("crate {name}@{version}");
+        cache.flights.run(&key, || Self::reuse_or_download(name, version, cksum, client, cache)).await
+    }
+
+    /// The body of [`fetch`](Self::fetch), for a valid name and version.
+    async fn reuse_or_download(
+        name: &str,
+        version: &str,
+        cksum: &str,
+        client: &ClientWithMiddleware,
+        cache: &DiskCache,
+    ) -> Result<Self> {
+        let dir = cache.dir().join("crates");
+        let path = archive_path(cache, name, version);
+        if path.is_file() {
+            // Reading and hashing up to ARCHIVE_MAX_BYTES would stall the async threads
+            let (cached, expected) = (path.clone(), cksum.to_string());
+            let checksum = tokio::task::spawn_blocking(move || std::fs::read(&cached).map(|bytes| verify(&bytes, &expected)))
+                .await
+                .unwrap_or_else(|e| Err(std::io::Error::other(e)))?;
+            if checksum.verified || cksum.is_empty() {
+                // The modification time marks the last use, for pruning
+                let _ = std::fs::File::options().write(true).open(&path)
+                    .and_then(|f| f.set_modified(std::time::SystemTime::now()));
+                return Ok(Self { path, prefix: format!("{name}-{version}/"), checksum });
+            }
+            tracing::warn!("cached {name} {version} archive does not match the index checksum; downloading it again");
+            std::fs::remove_file(&path)?;
+        }
+
+        let url = format!("{DOWNLOAD_BASE}/{name}/{name}-{version}.crate");
+        tracing::info!("downloading {name} {version} package archive");
+        let resp = client.get(&url).send().await?;
+        let status = resp.status();
+        // static.crates.io answers 403 rather than 404 for unknown files
+        if status == reqwest::StatusCode::NOT_FOUND || status == reqwest::StatusCode::FORBIDDEN {
+            return Err(DocsError::VersionNotFound { name: name.to_string(), version: version.to_string() });
+        }
+        if !status.is_success() {
+            return Err(DocsError::Status { status, url });
+        }
+        let too_large = || DocsError::Other(format!(
+            "The {name} {version} package archive is over {} MiB; it was not downloaded",
+            ARCHIVE_MAX_BYTES >> 20,
+        ));
+        if resp.content_length().is_some_and(|len| len > ARCHIVE_MAX_BYTES) {
+            return Err(too_large());
+        }
+        let mut bytes: Vec<u8> = vec![];
+        let mut body = resp.bytes_stream();
+        while let Some(chunk) = body.next().await {
+            bytes.extend_from_slice(&chunk?);
+            if bytes.len() as u64 > ARCHIVE_MAX_BYTES {
+                return Err(too_large());
+            }
+        }
+        let checksum = verify(&bytes, cksum);
+        if !checksum.verified && !cksum.is_empty() {
+            return Err(DocsError::ChecksumMismatch {
+                name: name.to_string(),
+                version: version.to_string(),
+                expected: cksum.to_string(),
+                actual: checksum.sha256,
+            });
+        }
+        let tarball = Self { path, prefix: format!("{name}-{version}/"), checksum };
+
+        // Write then rename so a concurrent reader never sees a partial archive
+        std::fs::create_dir_all(&dir)?;
+        let tmp = tarball.path.with_extension(format!("crate.{}.tmp", std::process::id()));
+        std::fs::write(&tmp, &bytes)?;
+        std::fs::rename(&tmp, &tarball.path)?;
+        Ok(tarball)
+    }
+
+    /// Whether the archive for `name` `version` is already in the cache, so fetching it
+    /// needs no download.
+    pub fn is_cached(name: &str, version: &str, cache: &DiskCache) -> bool {
+        is_valid_crate_name(name) && semver::Version::parse(version).is_ok()
+            && archive_path(cache, name, version).is_file()
+    }
+
+    /// How the archive compared with the sparse index checksum.
+    pub fn checksum(&self) -> &Checksum {
+        &self.checksum
+    }
+
+    /// Every regular file in the package, in archive order.
+    pub async fn files(&self) -> Result<Vec<CrateFile>> {
+        let tarball = self.clone();
+        tokio::task::spawn_blocking(move || tarball.read_files())
+            .await
+            .map_err(|e| DocsError::Other(format!("crate archive task failed: {e}")))?
+    }
+
+    /// Read one file (path relative to the package root), keeping at most `max_bytes`.
+    /// Returns `None` when the package has no such file.
+    pub async fn read_file(&self, path: &str, max_bytes: usize) -> Result<Option<FileContents>> {
+        let tarball = self.clone();
+        let path = path.trim_start_matches("./").to_string();
+        tokio::task::spawn_blocking(move || tarball.read_file_blocking(&path, max_bytes))
+            .await
+            .map_err(|e| DocsError::Other(format!("crate archive task failed: {e}")))?
+    }
+
+    /// The package README as published: the file named by `readme` in `Cargo.toml`, or a
+    /// conventionally named README at the package root. Returns `(path, contents)`.
+    pub async fn readme(&self, max_bytes: usize) -> Result<Option<(String, FileContents)>> {
+        let manifest = self.read_file("Cargo.toml", MANIFEST_MAX_BYTES).await?;
+        let declared = manifest.as_ref()
+            .and_then(|m| m.text.as_deref())
+            .and_then(|m| manifest_string(m, "[package]", "readme"));
+        let candidates = declared.into_iter()
+            .chain(README_NAMES.iter().map(|n| n.to_string()));
+        for path in candidates {
+            if let Some(contents) = self.read_file(&path, max_bytes).await? {
+                return Ok(Some((path, contents)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The library crate root: the `[lib]` path from `Cargo.toml`, or `src/lib.rs`.
+    /// Returns `(path, contents)`, or `None` for a package without a library.
+    pub async fn lib_root(&self, max_bytes: usize) -> Result<Option<(String, FileContents)>> {
+        let manifest = self.read_file("Cargo.toml", MANIFEST_MAX_BYTES).await?;
+        let path = manifest.as_ref()
+            .and_then(|m| m.text.as_deref())
+            .and_then(|m| manifest_string(m, "[lib]", "path"))
+            .unwrap_or_else(|| "src/lib.rs".to_string());
+        Ok(self.read_file(&path, max_bytes).await?.map(|contents| (path, contents)))
+    }
+
+    /// Whether the package has a build script, links a native library or is a proc macro,
+    /// from its `Cargo.toml` and files.
+    pub async fn build_profile(&self) -> Result<BuildProfile> {
+        let manifest = self.read_file("Cargo.toml", MANIFEST_MAX_BYTES).await?
+            .and_then(|m| m.text)
+            .unwrap_or_default();
+        let has_build_rs = self.read_file("build.rs", 0).await?.is_some();
+        Ok(build_profile(&manifest, has_build_rs))
+    }
+
+    fn read_file_blocking(&self, path: &str, max_bytes: usize) -> Result<Option<FileContents>> {
+        for entry in self.archive()?.entries()? {
+            let entry = entry?;
+            if !entry.header().entry_type().is_file() || self.relative_path(&entry.path()?).as_deref() != Some(path) {
+                continue;
+            }
+            let size = entry.size();
+            let mut bytes = Vec::new();
+            entry.take(max_bytes as u64).read_to_end(&mut bytes)?;
+            let truncated = (bytes.len() as u64) < size;
+            return Ok(Some(FileContents { size, text: decode_text(&bytes, truncated), truncated }));
+        }
+        Ok(None)
+    }
+
+    fn read_files(&self) -> Result<Vec<CrateFile>> {
+        let mut files = vec![];
+        for entry in self.archive()?.entries()? {
+            let entry = entry?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let Some(path) = self.relative_path(&entry.path()?) else { continue };
+            files.push(CrateFile { path, size: entry.size() });
+        }
+        Ok(files)
+    }
+
+    fn archive(&self) -> Result<tar::Archive<flate2::read::GzDecoder<std::fs::File>>> {
+        let file = std::fs::File::open(&self.path)?;
+        Ok(tar::Archive::new(flate2::read::GzDecoder::new(file)))
+    }
+
+    /// Path inside the package, without the `{name}-{version}/` root directory.
+    fn relative_path(&self, path: &Path) -> Option<String> {
+        let path = path.to_str()?;
+        path.strip_prefix(&self.prefix).filter(|p| !p.is_empty()).map(str::to_string)
+    }
+}
+
+/// Where the archive of `name` `version` is cached.
+fn archive_path(cache: &DiskCache, name: &str, version: &str) -> PathBuf {
+    cache.dir().join("crates").join(format!("{name}-{version}.crate"))
+}
+
+/// Delete archives under `cache_dir` unused for [`ARCHIVE_TTL`].
+pub(crate) fn prune_unused_archives(cache_dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(cache_dir.join("crates")) else { return };
+    for entry in entries.flatten() {
+        let unused_for = entry.metadata().and_then(|m| m.modified()).ok()
+            .and_then(|modified| modified.elapsed().ok());
+        if unused_for.is_some_and(|age| age > ARCHIVE_TTL) {
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+}
+
+/// SHA-256 of `bytes`, and whether it equals the index's `cksum`.
+fn verify(bytes: &[u8], cksum: &str) -> Checksum {
+    let sha256 = hex::encode(Sha256::digest(bytes));
+    let verified = !cksum.is_empty() && sha256.eq_ignore_ascii_case(cksum.trim());
+    Checksum { sha256, verified }
+}
+
+/// Build script, native library and proc-macro flags from a normalized `Cargo.toml`.
+///
+/// Recent `cargo package` writes `build = false` or the script's path; older manifests
+/// leave it out, and cargo then runs a `build.rs` at the package root if there is one.
+fn build_profile(manifest: &str, has_build_rs: bool) -> BuildProfile {
+    let build_script = match manifest_value(manifest, "[package]", "build").as_deref() {
+        Some("false") => None,
+        Some("true") | None => has_build_rs.then(|| "build.rs".to_string()),
+        Some(_) => manifest_string(manifest, "[package]", "build"),
+    };
+    // `proc_macro` is the older spelling cargo still accepts
+    let proc_macro = ["proc-macro", "proc_macro"].iter()
+        .any(|key| manifest_value(manifest, "[lib]", key).as_deref() == Some("true"));
+    BuildProfile { build_script, links: manifest_string(manifest, "[package]", "links"), proc_macro }
+}
+
+/// A string value of `key` in `table` (e.g. `"[package]"`) of a (normalized) `Cargo.toml`.
+///
+/// `cargo package` rewrites `readme` to the README's location inside the package, so a
+/// workspace README at `../README.md` is found at `README.md`. Non-string values such as
+/// `readme = false` yield `None`.
+fn manifest_string(manifest: &str, table: &str, key: &str) -> Option<String> {
+    let value = manifest_value(manifest, table, key)?;
+    value.strip_prefix('"').and_then(|v| v.strip_suffix('"')).map(str::to_string)
+}
+
+/// The raw value of `key` in `table`, as written after the `=`.
+fn manifest_value(manifest: &str, table: &str, key: &str) -> Option<String> {
+    let mut in_table = false;
+    for line in manifest.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_table = line == table;
+            continue;
+        }
+        if !in_table {
+            continue;
+        }
+        let Some((k, value)) = line.split_once('=') else { continue };
+        if k.trim() == key {
+            return Some(value.trim().to_string());
+        }
+    }
+    None
+}
+
+/// Decode file bytes as UTF-8 text, or `None` if they look binary (NUL bytes or invalid
+/// UTF-8). When `truncated`, a multi-byte character cut off at the end is dropped.
+fn decode_text(bytes: &[u8], truncated: bool) -> Option<String> {
+    if bytes.contains(&0) {
+        return None;
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(text) => Some(text.to_string()),
+        // `error_len() == None` means the input ended mid-character
+        Err(e) if truncated && e.error_len().is_none() => {
+            String::from_utf8(bytes[..e.valid_up_to()].to_vec()).ok()
+        }
+        Err(_) => None,
+    }
+}
+
+/// Match `path` against a glob: `*` and `?` stay within one path segment, `**` spans
+/// segments. A pattern without `/` is matched against the file name alone, so `*.proto`
+/// finds proto files at any depth.
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    if !pattern.contains('/') {
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+        return match_bytes(pattern.as_bytes(), file_name.as_bytes());
+    }
+    match_bytes(pattern.as_bytes(), path.as_bytes())
+}
+
+fn match_bytes(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        [b'*', b'*', rest @ ..] => {
+            // `**/` also matches zero directories
+            let rest_after_slash = rest.strip_prefix(b"/").unwrap_or(rest);
+            match_bytes(rest_after_slash, text)
+                || (0..text.len()).any(|i| match_bytes(rest, &text[i..]) || match_bytes(rest_after_slash, &text[i + 1..]))
+        }
+        [b'*', rest @ ..] => {
+            (0..=text.len())
+                .take_while(|&i| i == 0 || text[i - 1] != b'/')
+                .any(|i| match_bytes(rest, &text[i..]))
+        }
+        [b'?', rest @ ..] => text.first().is_some_and(|&c| c != b'/') && match_bytes(rest, &text[1..]),
+        [c, rest @ ..] => text.first() == Some(c) && match_bytes(rest, &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_match_segments() {
+        assert!(glob_match("*.proto", "proto/api/v1/service.proto"));
+        assert!(glob_match("src/*.rs", "src/lib.rs"));
+        assert!(!glob_match("src/*.rs", "src/transport/io.rs"));
+        assert!(glob_match("src/**/*.rs", "src/transport/io.rs"));
+        assert!(glob_match("src/**/*.rs", "src/lib.rs"));
+        assert!(glob_match("migrations/**", "migrations/2024-01-01/up.sql"));
+        assert!(glob_match("Cargo.toml", "Cargo.toml"));
+        assert!(glob_match("benches/?.rs", "benches/a.rs"));
+        assert!(!glob_match("benches/?.rs", "benches/ab.rs"));
+        assert!(!glob_match("*.sql", "schema.sqlite"));
+    }
+
+    #[test]
+    fn decode_text_detects_binary_and_cut_characters() {
+        assert_eq!(decode_text(b"fn main() {}", false).as_deref(), Some("fn main() {}"));
+        assert_eq!(decode_text(b"\x89PNG\r\n\x1a\n\0\0", false), None);
+        assert_eq!(decode_text(&[0xff, 0xfe, b'a'], false), None);
+        // "é" is two bytes; cutting after the first keeps the text before it
+        assert_eq!(decode_text(&[b'c', b'a', b'f', 0xc3], true).as_deref(), Some("caf"));
+        assert_eq!(decode_text(&[b'c', b'a', b'f', 0xc3], false), None);
+    }
+
+    #[test]
+    fn manifest_string_reads_the_named_table_only() {
+        let manifest = "[package]\nname = \"demo\"\nreadme = \"docs/README.md\"\n\n[badges]\nreadme = \"x\"\n\n[lib]\npath = \"src/demo.rs\"\n";
+        assert_eq!(manifest_string(manifest, "[package]", "readme").as_deref(), Some("docs/README.md"));
+        assert_eq!(manifest_string(manifest, "[lib]", "path").as_deref(), Some("src/demo.rs"));
+        assert_eq!(manifest_string("[package]\nreadme = false\n", "[package]", "readme"), None);
+        assert_eq!(manifest_string("[lib]\nreadme = \"README.md\"\n", "[package]", "readme"), None);
+    }
+
+    #[test]
+    fn verify_compares_against_the_index_checksum() {
+        // SHA-256 of the empty input
+        let empty = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+        assert_eq!(verify(b"", empty), Checksum { sha256: empty.to_string(), verified: true });
+        assert!(verify(b"", &empty.to_uppercase()).verified);
+        assert!(!verify(b"tampered", empty).verified);
+        assert!(!verify(b"", "").verified);
+    }
+
+    #[test]
+    fn build_profile_reads_build_links_and_proc_macro() {
+        let manifest = "[package]\nname = \"ring\"\nbuild = \"build.rs\"\nlinks = \"ring_core_0_17_14_\"\n";
+        let profile = build_profile(manifest, true);
+        assert_eq!(profile.build_script.as_deref(), Some("build.rs"));
+        assert_eq!(profile.links.as_deref(), Some("ring_core_0_17_14_"));
+        assert!(!profile.proc_macro);
+
+        assert_eq!(build_profile("[package]\nbuild = false\n", true).build_script, None);
+        // Older manifests leave `build` out and cargo finds build.rs itself
+        assert_eq!(build_profile("[package]\nname = \"old\"\n", true).build_script.as_deref(), Some("build.rs"));
+        assert_eq!(build_profile("[package]\nname = \"old\"\n", false).build_script, None);
+        assert!(build_profile("[package]\nbuild = false\n\n[lib]\nproc-macro = true\n", false).proc_macro);
+    }
+
+    #[test]
+    fn relative_path_strips_package_root() {
+        let checksum = Checksum { sha256: String::new(), verified: false };
+        let tarball = CrateTarball { path: PathBuf::new(), prefix: "demo-1.0.0/".to_string(), checksum };
+        assert_eq!(tarball.relative_path(Path::new("demo-1.0.0/src/lib.rs")).as_deref(), Some("src/lib.rs"));
+        assert_eq!(tarball.relative_path(Path::new("demo-1.0.0/")), None);
+        assert_eq!(tarball.relative_path(Path::new("other/src/lib.rs")), None);
+    }
+
+    #[test]
+    fn archives_unused_past_the_ttl_are_pruned() {
+        let cache = DiskCache::scratch("docs-mcp-test-archives").unwrap();
+        let dir = cache.dir().join("crates");
+        std::fs::create_dir_all(&dir).unwrap();
+        let (old, recent) = (dir.join("old-1.0.0.crate"), dir.join("recent-1.0.0.crate"));
+        for path in [&old, &recent] {
+            std::fs::write(path, b"archive").unwrap();
+        }
+        let long_ago = std::time::SystemTime::now() - ARCHIVE_TTL - std::time::Duration::from_secs(60);
+        std::fs::File::options().write(true).open(&old).unwrap().set_modified(long_ago).unwrap();
+
+        prune_unused_archives(cache.dir());
+        assert!(!old.exists());
+        assert!(recent.exists());
+        assert!(CrateTarball::is_cached("recent", "1.0.0", &cache));
+        assert!(!CrateTarball::is_cached("old", "1.0.0", &cache));
+    }
+}
@@ -0,0 +1,191 @@
+//! Package, dependency-graph and project data from deps.dev, including the OpenSSF
+//! Scorecard of a crate's source repository.
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::cache::DiskCache;
+use crate::error::Result;
+
+const DEPS_DEV_BASE: &str = "https://api.deps.dev/v3";
+
+/// One crate version as deps.dev knows it.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageVersion {
+    /// SPDX expressions deps.dev derived from the package
+    #[serde(default)]
+    pub licenses: Vec<String>,
+    #[serde(default)]
+    pub advisory_keys: Vec<AdvisoryKey>,
+    #[serde(default)]
+    pub related_projects: Vec<RelatedProject>,
+    pub published_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AdvisoryKey {
+    pub id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelatedProject {
+    pub project_key: ProjectKey,
+    /// How the link was established, e.g. "RP_SOURCE_REPO_METADATA"
+    #[serde(default)]
+    pub relation_provenance: String,
+    /// "SOURCE_REPO", "ISSUE_TRACKER", ...
+    #[serde(default)]
+    pub relation_type: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ProjectKey {
+    /// e.g. "github.com/tokio-rs/tokio"
+    pub id: String,
+}
+
+impl PackageVersion {
+    /// The project deps.dev links as this version's source repository.
+    pub fn source_repo(&self) -> Option<&str> {
+        self.related_projects.iter()
+            .find(|p| p.relation_type == "SOURCE_REPO")
+            .map(|p| p.project_key.id.as_str())
+    }
+}
+
+/// The resolved dependency graph of one crate version; node 0 is the version itself.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DependencyGraph {
+    #[serde(default)]
+    pub nodes: Vec<GraphNode>,
+    /// Non-empty when deps.dev could not resolve the whole graph
+    #[serde(default)]
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphNode {
+    pub version_key: VersionKey,
+    /// "SELF", "DIRECT" or "INDIRECT"
+    pub relation: String,
+    #[serde(default)]
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct VersionKey {
+    pub name: String,
+    pub version: String,
+}
+
+/// A source repository with its popularity counts and Scorecard.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Project {
+    pub project_key: ProjectKey,
+    #[serde(default, deserialize_with = "count")]
+    pub open_issues_count: Option<u64>,
+    #[serde(default, deserialize_with = "count")]
+    pub stars_count: Option<u64>,
+    #[serde(default, deserialize_with = "count")]
+    pub forks_count: Option<u64>,
+    pub license: Option<String>,
+    pub scorecard: Option<Scorecard>,
+}
+
+/// OpenSSF Scorecard results: security practice checks scored 0 to 10.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct Scorecard {
+    /// When the repository was last scored
+    pub date: Option<String>,
+    pub overall_score: Option<f64>,
+    #[serde(default)]
+    pub checks: Vec<ScorecardCheck>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ScorecardCheck {
+    pub name: String,
+    /// 0 to 10, or -1 when the check could not run
+    pub score: i32,
+    pub reason: Option<String>,
+}
+
+pub async fn package_version(
+    client: &reqwest_middleware::ClientWithMiddleware,
+    cache: &DiskCache,
+    name: &str,
+    version: &str,
+) -> Result<PackageVersion> {
+    let url = format!("{DEPS_DEV_BASE}/systems/cargo/packages/{}/versions/{}", escape(name), escape(version));
+    cache.get_json(client, &url).await
+}
+
+pub async fn dependencies(
+    client: &reqwest_middleware::ClientWithMiddleware,
+    cache: &DiskCache,
+    name: &str,
+    version: &str,
+) -> Result<DependencyGraph> {
+    let url = format!("{DEPS_DEV_BASE}/systems/cargo/packages/{}/versions/{}:dependencies", escape(name), escape(version));
+    cache.get_json(client, &url).await
+}
+
+/// A project by its key, e.g. "github.com/tokio-rs/tokio".
+pub async fn project(
+    client: &reqwest_middleware::ClientWithMiddleware,
+    cache: &DiskCache,
+    id: &str,
+) -> Result<Project> {
+    let url = format!("{DEPS_DEV_BASE}/projects/{}", escape(id));
+    cache.get_json(client, &url).await
+}
+
+/// A count deps.dev sends as a JSON string, the proto mapping of int64.
+fn count<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Option<u64>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Count {
+        Number(u64),
+        Text(String),
+    }
+    Ok(match Option::<Count>::deserialize(deserializer)? {
+        Some(Count::Number(n)) => Some(n),
+        Some(Count::Text(s)) => s.parse().ok(),
+        None => None,
+    })
+}
+
+/// Percent-encode everything but unreserved characters, so a project key's slashes and a
+/// version's `+build` metadata stay inside one path segment.
+fn escape(segment: &str) -> String {
+    segment.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_parse_from_strings_or_numbers() {
+        let project: Project = serde_json::from_str(
+            r#"{"projectKey": {"id": "github.com/a/b"}, "starsCount": "2741", "forksCount": 12}"#,
+        ).unwrap();
+        assert_eq!((project.stars_count, project.forks_count, project.open_issues_count), (Some(2741), Some(12), None));
+    }
+
+    #[test]
+    fn escapes_keys_into_one_segment() {
+        assert_eq!(escape("github.com/tokio-rs/tokio"), "github.com%2Ftokio-rs%2Ftokio");
+        assert_eq!(escape("1.0.0+wasi-0.2"), "1.0.0%2Bwasi-0.2");
+        assert_eq!(escape("serde_json"), "serde_json");
+    }
+}
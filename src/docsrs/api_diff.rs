@@ -0,0 +1,66 @@
+use std::collections::BTreeMap;
+
+use super::parser::{format_generics_for_item, function_signature};
+use super::types::RustdocJson;
+
+/// Differences between the public APIs of two versions of a crate.
+#[derive(Debug, Clone, Default)]
+pub struct ApiDiff {
+    /// Paths present only in the newer version
+    pub added: Vec<String>,
+    /// Paths present only in the older version
+    pub removed: Vec<String>,
+    /// Paths present in both whose rendered signature differs
+    pub changed: Vec<String>,
+}
+
+impl ApiDiff {
+    /// Fraction of the old API that was removed or changed (0.0 = fully compatible).
+    pub fn churn(&self, old_len: usize) -> f64 {
+        (self.removed.len() + self.changed.len()) as f64 / old_len.max(1) as f64
+    }
+}
+
+/// Collect the crate's own public API as `path → signature`.
+///
+/// Includes every local item in `doc.paths` (skipping `use`/`impl` entries and items from
+/// external crates) plus inherent methods as `Type::method`. Signatures use the same
+/// rendering as `crate_item_get`, so a changed parameter or bound shows up as a change.
+pub fn public_api(doc: &RustdocJson) -> BTreeMap<String, String> {
+    let mut api = BTreeMap::new();
+
+    for (id, entry) in &doc.paths {
+        if entry.crate_id != 0 { continue; }
+        let kind = entry.kind_name();
+        if matches!(kind, "use" | "import" | "impl" | "primitive") { continue; }
+        let signature = match doc.index.get(id) {
+            Some(item) if kind == "function" => function_signature(item),
+            Some(item) => format!("{kind}{}", format_generics_for_item(item, kind)),
+            None => kind.to_string(),
+        };
+        api.insert(entry.full_path(), signature);
+    }
+
+    for (id, parent_path) in doc.method_parents() {
+        let Some(item) = doc.index.get(id) else { continue };
+        if item.kind() != Some("function") { continue; }
+        let Some(name) = item.name.as_deref() else { continue };
+        api.insert(format!("{parent_path}::{name}"), function_signature(item));
+    }
+
+    api
+}
+
+/// Compare two public API maps produced by [`public_api`].
+pub fn diff_api(old: &BTreeMap<String, String>, new: &BTreeMap<String, String>) -> ApiDiff {
+    let mut diff = ApiDiff::default();
+    for (path, old_sig) in old {
+        match new.get(path) {
+            None => diff.removed.push(path.clone()),
+            Some(new_sig) if new_sig != old_sig => diff.changed.push(path.clone()),
+            Some(_) => {}
+        }
+    }
+    diff.added = new.keys().filter(|p| !old.contains_key(*p)).cloned().collect();
+    diff
+}
@@ -0,0 +1,352 @@
+use std::collections::HashSet;
+use std::fmt;
+
+/// A parsed `cfg` predicate, e.g. `all(feature = "rt", unix)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cfg {
+    /// `cfg(true)` / `cfg(false)`
+    Bool(bool),
+    /// A bare flag such as `unix` or `docsrs`
+    Flag(String),
+    /// A `name = "value"` pair such as `feature = "rt"` or `target_os = "linux"`
+    KeyValue(String, String),
+    All(Vec<Cfg>),
+    Any(Vec<Cfg>),
+    Not(Box<Cfg>),
+    /// A predicate variant this parser does not understand (e.g. `Version`), kept by name
+    Other(String),
+}
+
+impl fmt::Display for Cfg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |items: &[Cfg]| items.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(", ");
+        match self {
+            Cfg::Bool(b) => write!(f, "{b}"),
+            Cfg::Flag(name) | Cfg::Other(name) => write!(f, "{name}"),
+            Cfg::KeyValue(name, value) => write!(f, "{name} = \"{value}\""),
+            Cfg::All(items) => write!(f, "all({})", join(items)),
+            Cfg::Any(items) => write!(f, "any({})", join(items)),
+            Cfg::Not(inner) => write!(f, "not({inner})"),
+        }
+    }
+}
+
+impl Cfg {
+    /// Feature names this predicate can enable the item with (features under `not(..)` are skipped).
+    pub fn features(&self) -> Vec<&str> {
+        match self {
+            Cfg::KeyValue(name, value) if name == "feature" => vec![value.as_str()],
+            Cfg::All(items) | Cfg::Any(items) => items.iter().flat_map(|c| c.features()).collect(),
+            _ => vec![],
+        }
+    }
+
+    /// True if every leaf is a target predicate (`unix`, `windows`, `target_*`).
+    pub fn is_target(&self) -> bool {
+        match self {
+            Cfg::Flag(name) => matches!(name.as_str(), "unix" | "windows"),
+            Cfg::KeyValue(name, _) => name.starts_with("target_"),
+            Cfg::All(items) | Cfg::Any(items) => !items.is_empty() && items.iter().all(Cfg::is_target),
+            Cfg::Not(inner) => inner.is_target(),
+            Cfg::Bool(_) | Cfg::Other(_) => false,
+        }
+    }
+
+    /// The predicate as the `CfgTrace([..])` entry rustdoc format 57 writes, which
+    /// [`parse_cfg_attr`] reads back.
+    pub fn to_trace(&self) -> String {
+        let join = |items: &[Cfg]| items.iter().map(Cfg::to_trace).collect::<Vec<_>>().join(", ");
+        match self {
+            Cfg::Bool(b) => format!("Bool({b})"),
+            Cfg::Flag(name) => format!("NameValue {{ name: {name:?}, value: None }}"),
+            Cfg::KeyValue(name, value) => format!("NameValue {{ name: {name:?}, value: Some({value:?}) }}"),
+            Cfg::All(items) => format!("All([{}])", join(items)),
+            Cfg::Any(items) => format!("Any([{}])", join(items)),
+            Cfg::Not(inner) => format!("Not({})", inner.to_trace()),
+            Cfg::Other(name) => name.clone(),
+        }
+    }
+
+    /// Top-level conjuncts: the members of an `all(..)`, or the predicate itself.
+    pub fn conjuncts(&self) -> Vec<&Cfg> {
+        match self {
+            Cfg::All(items) => items.iter().flat_map(|c| c.conjuncts()).collect(),
+            other => vec![other],
+        }
+    }
+}
+
+/// Feature and target requirements of an item, derived from its `cfg` attributes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CfgRequirements {
+    /// Cargo features that gate the item
+    pub features: Vec<String>,
+    /// Target conditions the item is restricted to, in cfg syntax (e.g. `unix`, `target_os = "linux"`)
+    pub targets: Vec<String>,
+    /// The complete condition in cfg syntax, if the item is cfg-gated at all
+    pub cfg: Option<String>,
+}
+
+/// Parse every `cfg` attribute of an item and summarize its requirements.
+///
+/// Features are cross-referenced against `declared_features` when it is non-empty, so
+/// non-feature cfgs that happen to use the `feature` key are dropped.
+pub fn cfg_requirements(attrs: &[String], declared_features: &HashSet<String>) -> CfgRequirements {
+    let mut entries: Vec<Cfg> = attrs.iter().filter_map(|a| parse_cfg_attr(a)).collect();
+    if entries.is_empty() {
+        return CfgRequirements::default();
+    }
+    let cfg = if entries.len() == 1 { entries.remove(0) } else { Cfg::All(entries) };
+
+    let mut features: Vec<String> = cfg.features().into_iter()
+        .filter(|f| declared_features.is_empty() || declared_features.contains(*f))
+        .map(str::to_string)
+        .collect();
+    features.sort();
+    features.dedup();
+
+    let mut targets: Vec<String> = vec![];
+    for conjunct in cfg.conjuncts() {
+        let rendered = conjunct.to_string();
+        if conjunct.is_target() && !targets.contains(&rendered) {
+            targets.push(rendered);
+        }
+    }
+
+    CfgRequirements { features, targets, cfg: Some(cfg.to_string()) }
+}
+
+/// Parse a v57 attribute string of the form `#[attr = CfgTrace([..])]` into a [`Cfg`].
+///
+/// The trace is rustc's `Debug` rendering of its cfg entries, e.g.
+/// `Any([NameValue { name: "feature", value: Some("a"), span: .. }, ..], span)`.
+/// Multiple entries in one trace are combined with `all(..)`.
+pub fn parse_cfg_attr(attr: &str) -> Option<Cfg> {
+    const MARKER: &str = "CfgTrace(";
+    let start = attr.find(MARKER)? + MARKER.len();
+    let mut cursor = Cursor { s: &attr[start..], pos: 0 };
+    let mut entries = cursor.list()?;
+    match entries.len() {
+        0 => None,
+        1 => Some(entries.remove(0)),
+        _ => Some(Cfg::All(entries)),
+    }
+}
+
+/// Parse a predicate in `cfg` syntax, e.g. `all(feature = "rt", not(windows))`.
+pub fn parse_cfg_syntax(predicate: &str) -> Option<Cfg> {
+    let mut cursor = Cursor { s: predicate, pos: 0 };
+    let cfg = cursor.predicate()?;
+    cursor.skip_ws();
+    cursor.rest().is_empty().then_some(cfg)
+}
+
+/// Minimal recursive-descent reader over rustc's `Debug` output for cfg entries.
+struct Cursor<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn rest(&self) -> &'a str {
+        &self.s[self.pos..]
+    }
+
+    fn skip_ws(&mut self) {
+        let trimmed = self.rest().trim_start();
+        self.pos = self.s.len() - trimmed.len();
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_ws();
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn ident(&mut self) -> Option<&'a str> {
+        self.skip_ws();
+        let rest = self.rest();
+        let len = rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(rest.len());
+        if len == 0 { return None; }
+        self.pos += len;
+        Some(&rest[..len])
+    }
+
+    fn string(&mut self) -> Option<String> {
+        if !self.eat("\"") { return None; }
+        let mut out = String::new();
+        let mut chars = self.rest().char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Some(out);
+                }
+                '\\' => out.extend(chars.next().map(|(_, c)| c)),
+                c => out.push(c),
+            }
+        }
+        None
+    }
+
+    /// Skip to the next `,` or closing bracket at depth 0 without consuming it.
+    fn skip_value(&mut self) {
+        let mut depth = 0usize;
+        let mut in_str = false;
+        let mut escaped = false;
+        for (i, c) in self.rest().char_indices() {
+            if in_str {
+                match c {
+                    _ if escaped => escaped = false,
+                    '\\' => escaped = true,
+                    '"' => in_str = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match c {
+                '"' => in_str = true,
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' if depth == 0 => {
+                    self.pos += i;
+                    return;
+                }
+                ')' | ']' | '}' => depth -= 1,
+                ',' if depth == 0 => {
+                    self.pos += i;
+                    return;
+                }
+                _ => {}
+            }
+        }
+        self.pos = self.s.len();
+    }
+
+    /// Skip any remaining arguments (e.g. spans) and consume the closing `)`.
+    fn close_paren(&mut self) -> Option<()> {
+        while self.eat(",") {
+            self.skip_value();
+        }
+        self.eat(")").then_some(())
+    }
+
+    fn list(&mut self) -> Option<Vec<Cfg>> {
+        if !self.eat("[") { return None; }
+        let mut entries = vec![];
+        if self.eat("]") { return Some(entries); }
+        loop {
+            entries.push(self.entry()?);
+            if self.eat(",") { continue; }
+            if self.eat("]") { return Some(entries); }
+            return None;
+        }
+    }
+
+    /// One predicate in `cfg` syntax.
+    fn predicate(&mut self) -> Option<Cfg> {
+        let name = self.ident()?;
+        if self.eat("=") {
+            return Some(Cfg::KeyValue(name.to_string(), self.string()?));
+        }
+        if !matches!(name, "all" | "any" | "not") {
+            return Some(match name {
+                "true" | "false" => Cfg::Bool(name == "true"),
+                flag => Cfg::Flag(flag.to_string()),
+            });
+        }
+        if !self.eat("(") { return None; }
+        let mut items = vec![];
+        if !self.eat(")") {
+            loop {
+                items.push(self.predicate()?);
+                // A trailing comma is allowed
+                let more = self.eat(",");
+                if self.eat(")") { break; }
+                if !more { return None; }
+            }
+        }
+        match name {
+            "all" => Some(Cfg::All(items)),
+            "any" => Some(Cfg::Any(items)),
+            _ if items.len() == 1 => Some(Cfg::Not(Box::new(items.remove(0)))),
+            _ => None,
+        }
+    }
+
+    fn entry(&mut self) -> Option<Cfg> {
+        let variant = self.ident()?;
+        match variant {
+            "All" | "Any" => {
+                if !self.eat("(") { return None; }
+                let items = self.list()?;
+                self.close_paren()?;
+                Some(if variant == "All" { Cfg::All(items) } else { Cfg::Any(items) })
+            }
+            "Not" => {
+                if !self.eat("(") { return None; }
+                let inner = self.entry()?;
+                self.close_paren()?;
+                Some(Cfg::Not(Box::new(inner)))
+            }
+            "Bool" => {
+                if !self.eat("(") { return None; }
+                let value = self.ident()? == "true";
+                self.close_paren()?;
+                Some(Cfg::Bool(value))
+            }
+            "NameValue" => self.name_value(),
+            other => {
+                if self.eat("(") {
+                    self.skip_value();
+                    self.close_paren()?;
+                } else if self.eat("{") {
+                    loop {
+                        self.skip_value();
+                        if self.eat("}") { break; }
+                        if !self.eat(",") { return None; }
+                    }
+                }
+                Some(Cfg::Other(other.to_string()))
+            }
+        }
+    }
+
+    /// `NameValue { name: "feature", value: Some("rt"), span: .. }`; the value may also be
+    /// rendered as `Some(("rt", span))` by newer toolchains.
+    fn name_value(&mut self) -> Option<Cfg> {
+        if !self.eat("{") { return None; }
+        let mut name = None;
+        let mut value = None;
+        loop {
+            if self.eat("}") { break; }
+            let field = self.ident()?;
+            if !self.eat(":") { return None; }
+            match field {
+                "name" => name = Some(self.string()?),
+                "value" if self.eat("Some(") => {
+                    if self.eat("(") {
+                        value = Some(self.string()?);
+                        self.close_paren()?;
+                    } else {
+                        value = Some(self.string()?);
+                    }
+                    self.close_paren()?;
+                }
+                _ => self.skip_value(),
+            }
+            if !self.eat(",") {
+                if !self.eat("}") { return None; }
+                break;
+            }
+        }
+        let name = name?;
+        Some(match value {
+            Some(value) => Cfg::KeyValue(name, value),
+            None => Cfg::Flag(name),
+        })
+    }
+}
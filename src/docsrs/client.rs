@@ -1,21 +1,55 @@
+use std::time::Instant;
+
+use futures::{Stream, StreamExt, TryStreamExt, stream};
 use reqwest_middleware::ClientWithMiddleware;
 
 use crate::cache::DiskCache;
 use crate::error::{DocsError, Result};
-use super::types::RustdocJson;
+use super::format::{CURRENT_FORMAT_VERSION, normalize};
+use super::types::{DocDetail, OutlineJson, RustdocJson};
 
 const DOCSRS_BASE: &str = "https://docs.rs";
 
-/// Fetch the rustdoc JSON for a crate from docs.rs.
+/// Maximum number of docs.rs HEAD probes in flight at once.
+const MAX_CONCURRENT_PROBES: usize = 4;
+
+/// Env var for the largest rustdoc JSON, in MiB decompressed, parsed at [`DocDetail::Full`];
+/// `0` for no limit.
+pub const MAX_DOC_MB_ENV: &str = "DOCS_MCP_MAX_DOC_MB";
+/// A full parse takes several times the JSON's size in memory.
+pub const DEFAULT_MAX_DOC_MB: usize = 256;
+
+/// The [`MAX_DOC_MB_ENV`] limit, or `None` when it is `0`.
+pub fn max_doc_mb() -> Option<usize> {
+    let mb = std::env::var(MAX_DOC_MB_ENV).ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(DEFAULT_MAX_DOC_MB);
+    (mb > 0).then_some(mb)
+}
+
+/// The docs.rs rustdoc JSON URL, for the crate's default target unless `target` is given.
+fn json_url(name: &str, version: &str, target: Option<&str>) -> String {
+    match target {
+        Some(target) => format!("{DOCSRS_BASE}/crate/{name}/{version}/{target}/json"),
+        None => format!("{DOCSRS_BASE}/crate/{name}/{version}/json"),
+    }
+}
+
+/// Fetch the rustdoc JSON for a crate from docs.rs, built for `target` if given
+/// (e.g. "x86_64-pc-windows-msvc"), parsed at `detail`.
 ///
-/// Returns `Err(DocsError::DocsNotFound)` if docs.rs has no successful build.
+/// Returns `Err(DocsError::DocsNotFound)` if docs.rs has no successful build, and
+/// `Err(DocsError::DocsTooLarge)` for a full parse of JSON over [`max_doc_mb`]; outlines
+/// are small enough to parse at any size.
 pub async fn fetch_rustdoc_json(
     name: &str,
     version: &str,
+    target: Option<&str>,
+    detail: DocDetail,
     client: &ClientWithMiddleware,
     cache: &DiskCache,
 ) -> Result<RustdocJson> {
-    let url = format!("{DOCSRS_BASE}/crate/{name}/{version}/json");
+    let url = json_url(name, version, target);
 
     // HEAD check first to avoid downloading a large file that 404s
     let exists = cache.head_check(client, &url).await?;
@@ -26,13 +60,32 @@ pub async fn fetch_rustdoc_json(
         });
     }
 
-    let doc: RustdocJson = cache.get_zstd_json(client, &url).await?;
+    let max_len = max_doc_mb().filter(|_| detail == DocDetail::Full);
+    let too_large = |len: usize| DocsError::DocsTooLarge {
+        name: name.to_string(),
+        version: version.to_string(),
+        size_mb: len.div_ceil(1 << 20),
+        limit_mb: max_len.unwrap_or_default(),
+    };
+    // Large rustdoc JSON takes a noticeable while to parse, cached or not
+    let parse = |body: &str| -> Result<RustdocJson> {
+        let started = Instant::now();
+        let mut doc: RustdocJson = match detail {
+            DocDetail::Full => serde_json::from_str(body)?,
+            DocDetail::Outline => serde_json::from_str::<OutlineJson>(body)?.into(),
+        };
+        tracing::debug!("parsed {url} ({} KiB, {detail:?}) in {:.1?}", body.len() / 1024, started.elapsed());
+        doc.source_len = body.len();
+        Ok(doc)
+    };
+    let mut doc = cache.get_zstd_parsed(client, &url, max_len.map(|mb| mb << 20), too_large, parse).await?;
 
-    if doc.format_version < 33 {
-        return Err(DocsError::Other(format!(
-            "Unsupported rustdoc JSON format version: {}. Expected >= 33.",
-            doc.format_version
-        )));
+    if doc.format_version < CURRENT_FORMAT_VERSION {
+        let applied = normalize(&mut doc);
+        tracing::info!("{name} {version}: normalized rustdoc JSON format {} with {}", doc.format_version, applied.join(", "));
+    }
+    for (kind, count) in doc.unknown_kinds() {
+        tracing::warn!("{name} {version}: {count} items of unrecognized kind '{kind}'");
     }
 
     Ok(doc)
@@ -45,6 +98,49 @@ pub async fn docs_exist(
     client: &ClientWithMiddleware,
     cache: &DiskCache,
 ) -> Result<bool> {
-    let url = format!("{DOCSRS_BASE}/crate/{name}/{version}/json");
-    cache.head_check(client, &url).await
+    cache.head_check(client, &json_url(name, version, None)).await
+}
+
+/// Check several versions for a docs.rs build, running up to [`MAX_CONCURRENT_PROBES`]
+/// cached HEAD requests at a time. Results are returned in the same order as `versions`.
+pub async fn docs_exist_many(
+    name: &str,
+    versions: &[String],
+    client: &ClientWithMiddleware,
+    cache: &DiskCache,
+) -> Result<Vec<bool>> {
+    probes(name, versions, client, cache).try_collect().await
+}
+
+/// The first of `candidates` (in order) that has a docs.rs build.
+///
+/// Candidates are probed [`MAX_CONCURRENT_PROBES`] at a time and probing stops at the
+/// first hit, so a miss on the first few costs one round trip rather than one per version.
+pub async fn nearest_built_version(
+    name: &str,
+    candidates: &[String],
+    client: &ClientWithMiddleware,
+    cache: &DiskCache,
+) -> Result<Option<String>> {
+    let mut exists = std::pin::pin!(probes(name, candidates, client, cache));
+    let mut candidates = candidates.iter();
+    while let Some(built) = exists.try_next().await? {
+        let version = candidates.next();
+        if built {
+            return Ok(version.cloned());
+        }
+    }
+    Ok(None)
+}
+
+/// Whether each of `versions` has a docs.rs build, in order, probed bounded-parallel.
+fn probes<'a>(
+    name: &'a str,
+    versions: &'a [String],
+    client: &'a ClientWithMiddleware,
+    cache: &'a DiskCache,
+) -> impl Stream<Item = Result<bool>> + 'a {
+    stream::iter(versions)
+        .map(move |version| async move { cache.head_check(client, &json_url(name, version, None)).await })
+        .buffered(MAX_CONCURRENT_PROBES)
 }
@@ -0,0 +1,256 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use super::parser::function_signature;
+use super::types::{Item, RustdocJson};
+
+/// Integer types `#[repr(..)]` accepts for an enum's discriminant.
+const REPR_INTS: [&str; 12] = ["u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize"];
+
+/// A function on the FFI boundary: a foreign ABI, an exported symbol, or raw pointers in
+/// its signature.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FfiFunction {
+    pub path: String,
+    /// Signature including the `extern "ABI"` qualifier
+    pub signature: String,
+    /// Non-Rust ABI, e.g. `"C"` or `"system"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abi: Option<String>,
+    pub is_unsafe: bool,
+    /// A parameter or the return type contains `*const T` / `*mut T`
+    pub raw_pointers: bool,
+    /// Symbol name fixed by `#[no_mangle]` or `#[export_name]`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+}
+
+/// A struct, enum or union with a layout other code can rely on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FfiType {
+    pub path: String,
+    pub kind: String,
+    /// The `repr` arguments, e.g. `"C, u8"` or `"transparent"`
+    pub repr: String,
+}
+
+/// Everything on a crate's FFI boundary, sorted by path.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FfiSurface {
+    pub functions: Vec<FfiFunction>,
+    pub types: Vec<FfiType>,
+}
+
+/// Collect the FFI surface of the crate's own items, including inherent methods.
+///
+/// Hidden and non-public items are skipped unless `include_hidden`.
+pub fn ffi_surface(doc: &RustdocJson, include_hidden: bool) -> FfiSurface {
+    let mut surface = FfiSurface::default();
+    let visible = |item: &Item| include_hidden || item.is_visible();
+
+    for (id, entry) in &doc.paths {
+        if entry.crate_id != 0 { continue; }
+        let Some(item) = doc.index.get(id) else { continue };
+        if !visible(item) { continue; }
+        match entry.kind_name() {
+            "function" => surface.functions.extend(ffi_function(item, entry.full_path())),
+            kind @ ("struct" | "enum" | "union") => {
+                if let Some(repr) = repr(item).filter(|r| is_stable_layout(r)) {
+                    surface.types.push(FfiType { path: entry.full_path(), kind: kind.to_string(), repr });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for (id, parent_path) in doc.method_parents() {
+        let Some(item) = doc.index.get(id) else { continue };
+        if item.kind() != Some("function") || !visible(item) { continue; }
+        let Some(name) = item.name.as_deref() else { continue };
+        surface.functions.extend(ffi_function(item, format!("{parent_path}::{name}")));
+    }
+
+    surface.functions.sort_by(|a, b| a.path.cmp(&b.path));
+    surface.types.sort_by(|a, b| a.path.cmp(&b.path));
+    surface
+}
+
+/// The function as an FFI entry, or `None` if nothing about it crosses a language boundary.
+fn ffi_function(item: &Item, path: String) -> Option<FfiFunction> {
+    let inner = item.inner_for("function")?;
+    let header = inner.get("header");
+    let abi = header.and_then(|h| h.get("abi")).and_then(abi_name);
+    let raw_pointers = inner.get("sig").is_some_and(|sig| {
+        sig.get("inputs").and_then(Value::as_array).into_iter().flatten()
+            .filter_map(|pair| pair.get(1))
+            .chain(sig.get("output"))
+            .any(has_raw_pointer)
+    });
+    let symbol = exported_symbol(item);
+    if abi.is_none() && !raw_pointers && symbol.is_none() {
+        return None;
+    }
+
+    let mut signature = function_signature(item);
+    if let Some(abi) = &abi {
+        signature = signature.replacen("fn ", &format!("extern \"{abi}\" fn "), 1);
+    }
+    Some(FfiFunction {
+        path,
+        signature,
+        abi,
+        is_unsafe: header.and_then(|h| h.get("is_unsafe")).and_then(Value::as_bool).unwrap_or(false),
+        raw_pointers,
+        symbol,
+    })
+}
+
+/// ABI name from a function header, `None` for the Rust ABI.
+///
+/// Rustdoc writes `"Rust"`, `{"C": {"unwind": false}}`, `{"System": {..}}` or `{"Other": "efiapi"}`.
+fn abi_name(abi: &Value) -> Option<String> {
+    let (key, detail) = match abi {
+        Value::String(s) => (s.as_str(), &Value::Null),
+        Value::Object(map) => map.iter().next().map(|(k, v)| (k.as_str(), v))?,
+        _ => return None,
+    };
+    let name = match (key, detail) {
+        ("Rust", _) => return None,
+        ("Other", Value::String(other)) => return Some(other.clone()),
+        ("C", _) => "C".to_string(),
+        (other, _) => other.to_lowercase(),
+    };
+    let unwind = detail.get("unwind").and_then(Value::as_bool).unwrap_or(false);
+    Some(if unwind { format!("{name}-unwind") } else { name })
+}
+
+/// Whether a rustdoc type contains a raw pointer anywhere, e.g. `Option<*mut T>`.
+fn has_raw_pointer(ty: &Value) -> bool {
+    match ty {
+        Value::Object(map) => map.contains_key("raw_pointer") || map.values().any(has_raw_pointer),
+        Value::Array(items) => items.iter().any(has_raw_pointer),
+        _ => false,
+    }
+}
+
+/// Symbol fixed by `#[no_mangle]` (the item's own name) or `#[export_name = ".."]`.
+fn exported_symbol(item: &Item) -> Option<String> {
+    for attr in &item.attrs {
+        if attr == "no_mangle" || attr.get("no_mangle").is_some() {
+            return item.name.clone();
+        }
+        if let Some(name) = attr.get("export_name").and_then(Value::as_str) {
+            return Some(name.to_string());
+        }
+    }
+    let strings = item.attr_strings();
+    if strings.iter().any(|a| a.contains("no_mangle")) {
+        return item.name.clone();
+    }
+    strings.iter().find_map(|a| {
+        let rest = a.split_once("export_name")?.1;
+        Some(rest.split('"').nth(1)?.to_string())
+    })
+}
+
+/// The `repr` of an item as written, e.g. `"C, u8"`.
+///
+/// Reads both the structured `{"repr": {..}}` attribute and a plain `#[repr(..)]` string.
+fn repr(item: &Item) -> Option<String> {
+    if let Some(repr) = item.attrs.iter().find_map(|a| a.get("repr")) {
+        let mut parts = vec![];
+        match repr.get("kind").and_then(Value::as_str) {
+            Some("c") => parts.push("C".to_string()),
+            Some(kind) if kind != "rust" => parts.push(kind.to_string()),
+            _ => {}
+        }
+        if let Some(int) = repr.get("int").and_then(Value::as_str) {
+            parts.push(int.to_string());
+        }
+        if let Some(n) = repr.get("packed").and_then(Value::as_u64) {
+            parts.push(if n == 1 { "packed".to_string() } else { format!("packed({n})") });
+        }
+        if let Some(n) = repr.get("align").and_then(Value::as_u64) {
+            parts.push(format!("align({n})"));
+        }
+        return (!parts.is_empty()).then(|| parts.join(", "));
+    }
+    item.attr_strings().iter().find_map(|a| {
+        let args = a.trim().strip_prefix("#[repr(")?.strip_suffix(")]")?;
+        Some(args.split(',').map(str::trim).collect::<Vec<_>>().join(", "))
+    })
+}
+
+/// `C`, `transparent` and integer reprs fix the layout; `packed`/`align` alone do not.
+fn is_stable_layout(repr: &str) -> bool {
+    repr.split(", ").any(|part| part == "C" || part == "transparent" || REPR_INTS.contains(&part))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn make_doc() -> RustdocJson {
+        let no_generics = json!({"params": [], "where_predicates": []});
+        let header = |abi: Value, is_unsafe: bool| json!({"is_const": false, "is_unsafe": is_unsafe, "is_async": false, "abi": abi});
+        let byte_ptr = json!({"raw_pointer": {"is_mutable": false, "type": {"primitive": "u8"}}});
+        let function = |name: &str, abi: Value, inputs: Value, attrs: Value| json!({
+            "id": 0, "name": name, "attrs": attrs, "visibility": "public",
+            "inner": {"function": {
+                "generics": no_generics.clone(), "header": header(abi, false), "has_body": true,
+                "sig": {"inputs": inputs, "output": null, "is_c_variadic": false},
+            }},
+        });
+        let record = |name: &str, attrs: Value| json!({
+            "id": 0, "name": name, "attrs": attrs, "visibility": "public",
+            "inner": {"struct": {"generics": no_generics.clone(), "kind": {"unit": null}, "impls": []}},
+        });
+        serde_json::from_value(json!({
+            "format_version": 57,
+            "root": 0,
+            "index": {
+                "1": function("demo_init", json!({"C": {"unwind": false}}), json!([["flags", {"primitive": "u32"}]]), json!(["no_mangle"])),
+                "2": function("from_raw", json!("Rust"), json!([["ptr", byte_ptr]]), json!([])),
+                "3": function("plain", json!("Rust"), json!([["n", {"primitive": "u32"}]]), json!([])),
+                "4": record("Header", json!([{"repr": {"kind": "c", "align": null, "packed": null, "int": null}}])),
+                "5": record("Handle", json!([{"other": "#[repr(transparent)]"}])),
+                "6": record("Aligned", json!([{"repr": {"kind": "rust", "align": 8, "packed": null, "int": null}}])),
+            },
+            "paths": {
+                "1": {"crate_id": 0, "path": ["demo", "demo_init"], "kind": "function"},
+                "2": {"crate_id": 0, "path": ["demo", "from_raw"], "kind": "function"},
+                "3": {"crate_id": 0, "path": ["demo", "plain"], "kind": "function"},
+                "4": {"crate_id": 0, "path": ["demo", "Header"], "kind": "struct"},
+                "5": {"crate_id": 0, "path": ["demo", "Handle"], "kind": "struct"},
+                "6": {"crate_id": 0, "path": ["demo", "Aligned"], "kind": "struct"},
+            }
+        })).unwrap()
+    }
+
+    #[test]
+    fn collects_foreign_abi_and_raw_pointer_functions() {
+        let surface = ffi_surface(&make_doc(), false);
+        let paths: Vec<&str> = surface.functions.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, ["demo::demo_init", "demo::from_raw"]);
+        let init = &surface.functions[0];
+        assert_eq!(init.signature, "extern \"C\" fn demo_init(flags: u32)");
+        assert_eq!((init.abi.as_deref(), init.symbol.as_deref()), (Some("C"), Some("demo_init")));
+        assert!(surface.functions[1].raw_pointers && surface.functions[1].abi.is_none());
+    }
+
+    #[test]
+    fn collects_types_with_stable_layout() {
+        let surface = ffi_surface(&make_doc(), false);
+        let types: Vec<(&str, &str)> = surface.types.iter().map(|t| (t.path.as_str(), t.repr.as_str())).collect();
+        assert_eq!(types, [("demo::Handle", "transparent"), ("demo::Header", "C")]);
+    }
+
+    #[test]
+    fn abi_names() {
+        assert_eq!(abi_name(&json!("Rust")), None);
+        assert_eq!(abi_name(&json!({"C": {"unwind": true}})).as_deref(), Some("C-unwind"));
+        assert_eq!(abi_name(&json!({"System": {"unwind": false}})).as_deref(), Some("system"));
+        assert_eq!(abi_name(&json!({"Other": "efiapi"})).as_deref(), Some("efiapi"));
+    }
+}
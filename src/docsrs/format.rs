@@ -0,0 +1,248 @@
+//! Normalizes rustdoc JSON of any format version into the format 57 shapes the parser
+//! and tools read.
+//!
+//! Each change in rustdoc's output is handled by one [`FormatAdapter`], applied to
+//! documents older than the format that introduced the change. Supporting a new rustdoc
+//! release means adding an adapter that rewrites the previous current shape, then
+//! bumping [`CURRENT_FORMAT_VERSION`].
+
+use serde_json::{Map, Value, json};
+
+use super::cfg::parse_cfg_syntax;
+use super::types::{Item, PathEntry, RustdocJson};
+
+/// The format the parser and tools are written against.
+pub const CURRENT_FORMAT_VERSION: u32 = 57;
+
+/// Oldest format whose signatures, generics and impls survive normalization intact;
+/// older documents keep their names, paths and docs.
+pub const MIN_FULL_FORMAT_VERSION: u32 = 33;
+
+/// Rewrites one older shape of rustdoc JSON into the next.
+trait FormatAdapter: Sync {
+    /// What the adapter rewrites, for logs.
+    fn name(&self) -> &'static str;
+
+    fn upgrade_item(&self, _item: &mut Item) {}
+
+    fn upgrade_path(&self, _entry: &mut PathEntry) {}
+}
+
+/// Adapters in application order, each with the format whose shape it produces:
+/// documents older than that format go through it.
+const ADAPTERS: [(u32, &dyn FormatAdapter); 4] = [
+    (MIN_FULL_FORMAT_VERSION, &TaggedItems),
+    (MIN_FULL_FORMAT_VERSION, &RenamedKinds),
+    (CURRENT_FORMAT_VERSION, &RenamedFields),
+    (CURRENT_FORMAT_VERSION, &StructuredAttrs),
+];
+
+/// Bring `doc` to the [`CURRENT_FORMAT_VERSION`] shapes, returning the adapters applied.
+pub fn normalize(doc: &mut RustdocJson) -> Vec<&'static str> {
+    let adapters: Vec<&dyn FormatAdapter> = ADAPTERS.iter()
+        .filter(|(produces, _)| doc.format_version < *produces)
+        .map(|(_, adapter)| *adapter)
+        .collect();
+    for adapter in &adapters {
+        doc.index.values_mut().for_each(|item| adapter.upgrade_item(item));
+        doc.paths.values_mut().for_each(|entry| adapter.upgrade_path(entry));
+    }
+    adapters.iter().map(|a| a.name()).collect()
+}
+
+/// Items with a separate `kind` field and an untagged `inner`, and type nodes written as
+/// `{"kind": k, "inner": v}`, become tagged: `{k: v}`.
+struct TaggedItems;
+
+impl FormatAdapter for TaggedItems {
+    fn name(&self) -> &'static str {
+        "tagged_items"
+    }
+
+    fn upgrade_item(&self, item: &mut Item) {
+        if let Some(kind) = item.legacy_kind.take() {
+            item.inner = json!({ kind: item.inner.take() });
+        }
+        walk(&mut item.inner, &|value| {
+            let untagged_kind = value.as_object()
+                .filter(|map| map.len() == 2 && map.contains_key("inner"))
+                .and_then(|map| map.get("kind")?.as_str())
+                .map(str::to_string);
+            if let Some(kind) = untagged_kind {
+                let inner = value["inner"].take();
+                *value = json!({ kind: inner });
+            }
+        });
+    }
+}
+
+/// Kind names of older formats and their current equivalents.
+const KIND_RENAMES: [(&str, &str); 7] = [
+    ("typedef", "type_alias"),
+    ("method", "function"),
+    ("foreign_type", "extern_type"),
+    ("import", "use"),
+    ("proc_attribute", "proc_macro"),
+    ("proc_derive", "proc_macro"),
+    ("opaque_ty", "type_alias"),
+];
+
+/// `typedef`, `method`, `import`, ... in item tags and `paths` get their current names.
+struct RenamedKinds;
+
+impl RenamedKinds {
+    fn current(kind: &str) -> &str {
+        KIND_RENAMES.iter().find(|(old, _)| *old == kind).map_or(kind, |(_, new)| new)
+    }
+}
+
+impl FormatAdapter for RenamedKinds {
+    fn name(&self) -> &'static str {
+        "renamed_kinds"
+    }
+
+    fn upgrade_item(&self, item: &mut Item) {
+        if let Value::Object(map) = item.inner.take() {
+            item.inner = Value::Object(map.into_iter().map(|(k, v)| (Self::current(&k).to_string(), v)).collect());
+        }
+    }
+
+    fn upgrade_path(&self, entry: &mut PathEntry) {
+        entry.kind = Self::current(&entry.kind).to_string();
+    }
+}
+
+/// Fields renamed over the format's history, as `(object key or "*", old, new)`; `"*"`
+/// renames the field in every object.
+const FIELD_RENAMES: [(&str, &str, &str); 6] = [
+    ("*", "decl", "sig"),
+    ("*", "mutable", "is_mutable"),
+    ("*", "fields_stripped", "has_stripped_fields"),
+    ("resolved_path", "name", "path"),
+    ("assoc_const", "default", "value"),
+    ("assoc_type", "default", "type"),
+];
+
+/// Field renames from [`FIELD_RENAMES`], at any depth of an item's `inner`.
+struct RenamedFields;
+
+impl FormatAdapter for RenamedFields {
+    fn name(&self) -> &'static str {
+        "renamed_fields"
+    }
+
+    fn upgrade_item(&self, item: &mut Item) {
+        walk(&mut item.inner, &|value| {
+            let Value::Object(map) = value else { return };
+            for (owner, from, to) in FIELD_RENAMES {
+                if owner == "*" {
+                    rename(map, from, to);
+                } else if let Some(Value::Object(owned)) = map.get_mut(owner) {
+                    rename(owned, from, to);
+                }
+            }
+        });
+    }
+}
+
+/// Plain-string `attrs` become `{"other": ..}` entries, with `#[cfg(..)]` rewritten as the
+/// `#[attr = CfgTrace([..])]` form that feature and target detection reads.
+struct StructuredAttrs;
+
+impl FormatAdapter for StructuredAttrs {
+    fn name(&self) -> &'static str {
+        "structured_attrs"
+    }
+
+    fn upgrade_item(&self, item: &mut Item) {
+        for attr in &mut item.attrs {
+            let Value::String(text) = attr else { continue };
+            let trace = text.trim()
+                .strip_prefix("#[cfg(")
+                .and_then(|rest| rest.strip_suffix(")]"))
+                .and_then(parse_cfg_syntax)
+                .map(|cfg| format!("#[attr = CfgTrace([{}])]", cfg.to_trace()));
+            *attr = json!({"other": trace.unwrap_or_else(|| std::mem::take(text))});
+        }
+    }
+}
+
+/// Apply `f` to `value` and then to everything inside it.
+fn walk(value: &mut Value, f: &dyn Fn(&mut Value)) {
+    f(value);
+    match value {
+        Value::Object(map) => map.values_mut().for_each(|v| walk(v, f)),
+        Value::Array(items) => items.iter_mut().for_each(|v| walk(v, f)),
+        _ => {}
+    }
+}
+
+fn rename(map: &mut Map<String, Value>, from: &str, to: &str) {
+    if map.contains_key(to) {
+        return;
+    }
+    if let Some(value) = map.remove(from) {
+        map.insert(to.to_string(), value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docsrs::cfg_requirements;
+
+    #[test]
+    fn normalizes_untagged_items_and_old_kind_names() {
+        let mut doc: RustdocJson = serde_json::from_value(json!({
+            "format_version": 20,
+            "root": "0:0",
+            "crate_version": "0.1.0",
+            "index": {
+                "0:1": {
+                    "id": "0:1", "name": "parse", "docs": "Parses it.",
+                    "attrs": ["#[must_use]"], "deprecation": null, "visibility": "public",
+                    "span": null, "links": {},
+                    "kind": "method",
+                    "inner": {
+                        "decl": {"inputs": [["s", {"kind": "borrowed_ref", "inner": {"lifetime": null, "mutable": false, "type": {"kind": "primitive", "inner": "str"}}}]],
+                                 "output": {"resolved_path": {"name": "Config", "id": "0:2", "args": null}}},
+                        "generics": {"params": [], "where_predicates": []},
+                    },
+                },
+                "0:2": {
+                    "id": "0:2", "name": "Alias", "docs": null, "attrs": [], "deprecation": null,
+                    "visibility": "public", "span": null, "links": {},
+                    "inner": {"typedef": {"type": {"primitive": "u8"}}},
+                },
+            },
+            "paths": {
+                "0:2": {"crate_id": 0, "path": ["demo", "Alias"], "kind": "typedef"},
+            },
+            "external_crates": {},
+        })).unwrap();
+        let applied = normalize(&mut doc);
+        assert_eq!(applied, ["tagged_items", "renamed_kinds", "renamed_fields", "structured_attrs"]);
+
+        let parse = &doc.index["0:1"];
+        assert_eq!(parse.kind(), Some("function"));
+        assert_eq!(crate::docsrs::function_signature(parse), "fn parse(s: &str) -> Config");
+        assert_eq!(parse.attr_strings(), ["#[must_use]"]);
+        assert_eq!(doc.index["0:2"].kind(), Some("type_alias"));
+        assert_eq!(doc.paths["0:2"].kind_name(), "type_alias");
+    }
+
+    #[test]
+    fn rewrites_plain_cfg_attrs_as_cfg_traces() {
+        let mut item: Item = serde_json::from_value(json!({
+            "id": 1, "name": "rt", "docs": null, "deprecation": null, "visibility": "public",
+            "span": null, "links": {}, "inner": {"module": {}},
+            "attrs": [r#"#[cfg(all(feature = "rt", not(windows)))]"#, "#[doc(hidden)]"],
+        })).unwrap();
+        StructuredAttrs.upgrade_item(&mut item);
+
+        let requirements = cfg_requirements(&item.attr_strings(), &Default::default());
+        assert_eq!(requirements.features, ["rt"]);
+        assert_eq!(requirements.cfg.as_deref(), Some(r#"all(feature = "rt", not(windows))"#));
+        assert!(item.is_doc_hidden());
+    }
+}
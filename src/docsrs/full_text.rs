@@ -0,0 +1,260 @@
+use std::collections::{HashMap, HashSet};
+
+use regex::Regex;
+use serde::Serialize;
+
+use super::parser::hidden_module_prefixes;
+use super::types::RustdocJson;
+
+/// BM25 term-frequency saturation.
+const K1: f32 = 1.2;
+/// BM25 document-length normalization.
+const B: f32 = 0.75;
+/// Longest snippet, in bytes, before the matching line is cut down around its first match.
+const SNIPPET_LEN: usize = 240;
+
+/// Inverted index over the complete doc comments of a crate's visible items and methods.
+#[derive(Debug, Default)]
+pub struct DocsIndex {
+    entries: Vec<IndexedItem>,
+    /// Term → `(entry, occurrences)`, in entry order
+    postings: HashMap<String, Vec<(u32, u32)>>,
+    /// Terms in each entry's docs
+    lengths: Vec<u32>,
+    avg_length: f32,
+}
+
+#[derive(Debug)]
+struct IndexedItem {
+    id: String,
+    path: String,
+    kind: String,
+}
+
+/// An item whose docs match a full-text query.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DocsHit {
+    pub path: String,
+    pub kind: String,
+    pub score: f64,
+    /// Query terms found in the docs
+    pub matched_terms: Vec<String>,
+    /// Heading of the doc section holding the snippet, e.g. "Examples"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section: Option<String>,
+    /// The doc line with the most query terms, each wrapped in `**`
+    pub snippet: String,
+}
+
+impl DocsIndex {
+    /// Index the docs of every visible item with a path, and every inherent method.
+    pub fn build(doc: &RustdocJson) -> Self {
+        let hidden_modules = hidden_module_prefixes(doc);
+        let method_parents = doc.method_parents();
+        let mut index = DocsIndex::default();
+        let mut ids: Vec<&String> = doc.index.keys().collect();
+        ids.sort_by_key(|id| id.parse::<u64>().unwrap_or(u64::MAX));
+        for id in ids {
+            let item = &doc.index[id];
+            let (Some(name), Some(docs)) = (item.name.as_deref(), item.docs.as_deref()) else { continue };
+            if !item.is_visible() || docs.trim().is_empty() {
+                continue;
+            }
+            let (path, kind) = match (doc.paths.get(id), method_parents.get(id)) {
+                (Some(entry), _) if entry.crate_id == 0 => (entry.full_path(), entry.kind_name().to_string()),
+                (None, Some(parent)) if item.kind() == Some("function") => (format!("{parent}::{name}"), "method".to_string()),
+                _ => continue,
+            };
+            if hidden_modules.iter().any(|m| path.starts_with(m.as_str())) {
+                continue;
+            }
+
+            let entry = index.entries.len() as u32;
+            let mut counts: HashMap<String, u32> = HashMap::new();
+            let mut length = 0;
+            for term in terms(docs) {
+                *counts.entry(term).or_default() += 1;
+                length += 1;
+            }
+            for (term, count) in counts {
+                index.postings.entry(term).or_default().push((entry, count));
+            }
+            index.lengths.push(length);
+            index.entries.push(IndexedItem { id: id.clone(), path, kind });
+        }
+        let total: u32 = index.lengths.iter().sum();
+        index.avg_length = total as f32 / index.lengths.len().max(1) as f32;
+        index
+    }
+
+    /// Rough number of bytes this index holds in memory.
+    pub fn estimated_size(&self) -> usize {
+        const ENTRY_OVERHEAD: usize = 3 * std::mem::size_of::<String>() + std::mem::size_of::<u32>();
+        let entries: usize = self.entries.iter()
+            .map(|e| ENTRY_OVERHEAD + e.id.len() + e.path.len() + e.kind.len())
+            .sum();
+        let postings: usize = self.postings.iter()
+            .map(|(term, list)| std::mem::size_of::<String>() + term.len() + std::mem::size_of_val(list.as_slice()))
+            .sum();
+        entries + postings
+    }
+
+    /// Items whose docs contain any of the query's terms, best BM25 score first, with the
+    /// total number of matches before `limit`.
+    pub fn search(
+        &self,
+        doc: &RustdocJson,
+        query: &str,
+        kind_filter: Option<&str>,
+        module_prefix: Option<&str>,
+        limit: usize,
+    ) -> (usize, Vec<DocsHit>) {
+        let mut query_terms: Vec<String> = vec![];
+        for term in terms(query) {
+            if !query_terms.contains(&term) {
+                query_terms.push(term);
+            }
+        }
+        let kind_filter = kind_filter.map(|kf| match kf {
+            "fn" => "function",
+            "mod" => "module",
+            "type" => "type_alias",
+            other => other,
+        });
+
+        // Entry → (score, matched terms)
+        let mut scores: HashMap<u32, (f32, Vec<&str>)> = HashMap::new();
+        let n = self.entries.len() as f32;
+        for term in &query_terms {
+            let Some(postings) = self.postings.get(term) else { continue };
+            let df = postings.len() as f32;
+            let idf = (1.0 + (n - df + 0.5) / (df + 0.5)).ln();
+            for &(entry, count) in postings {
+                let tf = count as f32;
+                let length = self.lengths[entry as usize] as f32;
+                let score = idf * tf * (K1 + 1.0) / (tf + K1 * (1.0 - B + B * length / self.avg_length));
+                let slot = scores.entry(entry).or_insert((0.0, vec![]));
+                slot.0 += score;
+                slot.1.push(term);
+            }
+        }
+
+        let mut ranked: Vec<(u32, f32, Vec<&str>)> = scores.into_iter()
+            .filter(|(entry, _)| {
+                let item = &self.entries[*entry as usize];
+                kind_filter.is_none_or(|k| item.kind == k)
+                    && module_prefix.is_none_or(|p| item.path.starts_with(p))
+            })
+            // Items with every query term rank above items with some of them
+            .map(|(entry, (score, matched))| {
+                let coverage = matched.len() as f32 / query_terms.len() as f32;
+                (entry, score * coverage, matched)
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let total = ranked.len();
+
+        let hits = ranked.into_iter().take(limit)
+            .map(|(entry, score, matched)| {
+                let item = &self.entries[entry as usize];
+                let docs = doc.index.get(&item.id).and_then(|i| i.docs.as_deref()).unwrap_or("");
+                let (section, snippet) = snippet(docs, &matched);
+                DocsHit {
+                    path: item.path.clone(),
+                    kind: item.kind.clone(),
+                    score: (score as f64 * 1000.0).round() / 1000.0,
+                    matched_terms: matched.iter().map(|t| t.to_string()).collect(),
+                    section,
+                    snippet,
+                }
+            })
+            .collect();
+        (total, hits)
+    }
+}
+
+/// Lowercased words of `text`; `snake_case` words also yield their parts, so `spawn_blocking`
+/// is found by "spawn_blocking", "spawn" and "blocking".
+fn terms(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .map(|w| w.trim_matches('_'))
+        .filter(|w| !w.is_empty())
+        .flat_map(|w| {
+            let word = w.to_lowercase();
+            let parts: Vec<String> = if word.contains('_') {
+                word.split('_').filter(|p| !p.is_empty()).map(str::to_string).collect()
+            } else {
+                vec![]
+            };
+            std::iter::once(word).chain(parts)
+        })
+}
+
+/// The heading above and text of the doc line with the most distinct `matched` terms,
+/// with each match in `**`.
+fn snippet(docs: &str, matched: &[&str]) -> (Option<String>, String) {
+    let alternatives: Vec<String> = matched.iter().map(|t| regex::escape(t)).collect();
+    let Ok(pattern) = Regex::new(&format!(r"(?i)\b(?:{})\b", alternatives.join("|"))) else {
+        return (None, String::new());
+    };
+
+    let mut section = None;
+    let mut in_fence = false;
+    // (distinct terms, line, section)
+    let mut best: Option<(usize, &str, Option<&str>)> = None;
+    for line in docs.lines().map(str::trim) {
+        if line.starts_with("```") || line.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        // `# ` lines inside a code block are hidden doctest lines, not headings
+        let heading = if in_fence { None } else { line.strip_prefix('#').filter(|h| h.starts_with([' ', '#'])) };
+        if let Some(heading) = heading {
+            section = Some(heading.trim_start_matches('#').trim());
+        }
+        let distinct: HashSet<String> = pattern.find_iter(line).map(|m| m.as_str().to_lowercase()).collect();
+        if !distinct.is_empty() && best.is_none_or(|(count, _, _)| distinct.len() > count) {
+            best = Some((distinct.len(), line, section));
+        }
+    }
+    let Some((_, line, section)) = best else { return (None, String::new()) };
+
+    // Cut long lines to a window starting a little before the first match
+    let first = pattern.find(line).map_or(0, |m| m.start());
+    let mut start = first.saturating_sub(SNIPPET_LEN / 3);
+    while !line.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (start + SNIPPET_LEN).min(line.len());
+    while !line.is_char_boundary(end) {
+        end += 1;
+    }
+    let window = pattern.replace_all(&line[start..end], "**$0**");
+    let prefix = if start > 0 { "…" } else { "" };
+    let suffix = if end < line.len() { "…" } else { "" };
+    (section.map(str::to_string), format!("{prefix}{window}{suffix}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_snake_case_terms() {
+        let terms: Vec<String> = terms("Runs `spawn_blocking` on the Pool.").collect();
+        assert_eq!(terms, ["runs", "spawn_blocking", "spawn", "blocking", "on", "the", "pool"]);
+    }
+
+    #[test]
+    fn snippet_picks_the_line_with_most_terms_and_its_section() {
+        let docs = "A channel.\n\n# Examples\n\n```\n# use tokio::sync::mpsc;\n```\n\n# Cancel safety\n\nThe `recv` method is cancel safe: no value is lost.\nOther text.";
+        let (section, snippet) = snippet(docs, &["cancel", "recv"]);
+        assert_eq!(section.as_deref(), Some("Cancel safety"));
+        assert_eq!(snippet, "The `**recv**` method is **cancel** safe: no value is lost.");
+
+        let long = format!("{} needle {}", "word ".repeat(100), "tail ".repeat(100));
+        let (_, snippet) = super::snippet(&long, &["needle"]);
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'), "{snippet}");
+        assert!(snippet.contains("**needle**") && snippet.len() < SNIPPET_LEN + 20);
+    }
+}
@@ -1,11 +1,27 @@
+pub mod api_diff;
+pub mod cfg;
 pub mod client;
+pub mod ffi;
+pub mod format;
+pub mod full_text;
 pub mod parser;
+pub mod summary;
+pub mod traits;
 pub mod types;
 
-pub use client::{fetch_rustdoc_json, docs_exist};
+pub use cfg::{cfg_requirements, parse_cfg_attr, parse_cfg_syntax, Cfg, CfgRequirements};
+pub use ffi::{ffi_surface, FfiFunction, FfiSurface, FfiType};
+pub use full_text::{DocsIndex, DocsHit};
+pub use client::{fetch_rustdoc_json, docs_exist, docs_exist_many, nearest_built_version, max_doc_mb};
 pub use parser::{
-    type_to_string, function_signature, extract_feature_requirements,
-    format_generics_for_item,
-    build_module_tree, search_items, ModuleNode, ItemSummary, SearchResult,
+    type_to_string, function_signature, const_info, ConstInfo, extract_feature_requirements,
+    format_generics_for_item, struct_fields, FieldInfo, impl_header, find_item_id,
+    resolve_links, rewrite_doc_links, docs_url, ResolvedLink, source_link, SourceLink,
+    split_doc_sections, select_doc_sections, DocSection, doc_caveats, DocCaveats,
+    split_doc_examples, DocExample,
+    build_module_tree, build_module_subtree, MAX_MODULE_DEPTH,
+    search_items, search_items_scored, SearchCandidate, ModuleNode, ItemSummary, SearchResult,
 };
-pub use types::{RustdocJson, Item, PathEntry, Deprecation, Span};
+pub use summary::SummaryPolicy;
+pub use traits::{impl_skeleton, trait_members, TraitMember};
+pub use types::{id_to_string, RustdocJson, DocDetail, OutlineJson, Item, ItemKind, PathEntry, Deprecation, Span};
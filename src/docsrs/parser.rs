@@ -1,9 +1,9 @@
 use std::collections::{HashMap, HashSet};
 
-use regex::Regex;
 use serde_json::Value;
 
-use super::types::{Item, RustdocJson};
+use super::cfg::cfg_requirements;
+use super::types::{id_to_string, Item, RustdocJson, Span};
 
 // ─── Type-to-string ───────────────────────────────────────────────────────────
 
@@ -33,28 +33,15 @@ pub fn type_to_string(ty: &Value) -> String {
     // Resolved path (e.g. Option<T>, Vec<T>, custom types)
     if let Some(rp) = obj.get("resolved_path") {
         let name = rp.get("path")
-            .or_else(|| rp.get("name"))
             .and_then(|v| v.as_str())
             .unwrap_or("_");
-        let args = rp.get("args")
-            .and_then(|a| a.get("angle_bracketed"))
-            .and_then(|ab| ab.get("args"))
-            .and_then(|a| a.as_array());
-        if let Some(args) = args {
-            let type_args: Vec<String> = args.iter()
-                .filter_map(|a| a.get("type").map(type_to_string))
-                .collect();
-            if !type_args.is_empty() {
-                return format!("{name}<{}>", type_args.join(", "));
-            }
-        }
-        return name.to_string();
+        return format!("{name}{}", generic_args_to_string(rp.get("args")));
     }
 
     // Borrowed reference (&T or &'a T or &'a mut T)
     if let Some(br) = obj.get("borrowed_ref") {
         let lifetime = br.get("lifetime").and_then(|v| v.as_str());
-        let mutable = br.get("mutable").and_then(|v| v.as_bool()).unwrap_or(false);
+        let mutable = br.get("is_mutable").and_then(|v| v.as_bool()).unwrap_or(false);
         let inner = br.get("type").map(type_to_string).unwrap_or_else(|| "_".to_string());
         let mut_str = if mutable { "mut " } else { "" };
         return match lifetime {
@@ -91,7 +78,7 @@ pub fn type_to_string(ty: &Value) -> String {
 
     // Raw pointer (*const T or *mut T)
     if let Some(rp) = obj.get("raw_pointer") {
-        let mutable = rp.get("mutable").and_then(|v| v.as_bool()).unwrap_or(false);
+        let mutable = rp.get("is_mutable").and_then(|v| v.as_bool()).unwrap_or(false);
         let inner = rp.get("type").map(type_to_string).unwrap_or_else(|| "_".to_string());
         let mut_str = if mutable { "mut" } else { "const" };
         return format!("*{mut_str} {inner}");
@@ -99,12 +86,7 @@ pub fn type_to_string(ty: &Value) -> String {
 
     // ImplTrait (impl Trait1 + Trait2)
     if let Some(bounds) = obj.get("impl_trait").and_then(|v| v.as_array()) {
-        let parts: Vec<String> = bounds.iter()
-            .filter_map(|b| b.get("trait_bound"))
-            .filter_map(|tb| tb.get("trait"))
-            .map(type_to_string)
-            .collect();
-        return format!("impl {}", parts.join(" + "));
+        return format!("impl {}", bounds_to_string(bounds));
     }
 
     // DynTrait
@@ -127,30 +109,26 @@ pub fn type_to_string(ty: &Value) -> String {
     }
 
     // FunctionPointer
-    if let Some(fp) = obj.get("function_pointer") {
-        let decl = fp.get("sig")
-            .or_else(|| fp.get("decl"));
-        if let Some(decl) = decl {
-            let inputs = decl.get("inputs")
-                .and_then(|v| v.as_array())
-                .map(|inputs| {
-                    inputs.iter()
-                        .filter_map(|i| i.as_array())
-                        .map(|pair| {
-                            let name = pair.first().and_then(|v| v.as_str()).unwrap_or("_");
-                            let ty = pair.get(1).map(type_to_string).unwrap_or_else(|| "_".to_string());
-                            format!("{name}: {ty}")
-                        })
-                        .collect::<Vec<_>>()
-                        .join(", ")
-                })
-                .unwrap_or_default();
-            let output = decl.get("output").map(type_to_string).unwrap_or_default();
-            if output.is_empty() || output == "()" {
-                return format!("fn({inputs})");
-            } else {
-                return format!("fn({inputs}) -> {output}");
-            }
+    if let Some(decl) = obj.get("function_pointer").and_then(|fp| fp.get("sig")) {
+        let inputs = decl.get("inputs")
+            .and_then(|v| v.as_array())
+            .map(|inputs| {
+                inputs.iter()
+                    .filter_map(|i| i.as_array())
+                    .map(|pair| {
+                        let name = pair.first().and_then(|v| v.as_str()).unwrap_or("_");
+                        let ty = pair.get(1).map(type_to_string).unwrap_or_else(|| "_".to_string());
+                        format!("{name}: {ty}")
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .unwrap_or_default();
+        let output = decl.get("output").map(type_to_string).unwrap_or_default();
+        if output.is_empty() || output == "()" {
+            return format!("fn({inputs})");
+        } else {
+            return format!("fn({inputs}) -> {output}");
         }
     }
 
@@ -159,7 +137,10 @@ pub fn type_to_string(ty: &Value) -> String {
         let self_type = qp.get("self_type").map(type_to_string).unwrap_or_else(|| "_".to_string());
         let name = qp.get("name").and_then(|v| v.as_str()).unwrap_or("_");
         let trait_val = qp.get("trait");
-        let trait_is_absent = trait_val.map(|v| v.is_null()).unwrap_or(true);
+        // Inside a trait's own items rustdoc writes `Self::Name` with an empty trait path
+        let trait_is_absent = trait_val.is_none_or(|v| {
+            v.is_null() || v.get("path").and_then(|p| p.as_str()) == Some("")
+        });
         if trait_is_absent {
             // No explicit trait disambiguation — emit `T::Name` (shorthand the compiler resolves).
             return format!("{self_type}::{name}");
@@ -173,19 +154,7 @@ pub fn type_to_string(ty: &Value) -> String {
     if obj.contains_key("id") {
         if let Some(path_str) = obj.get("path").and_then(|v| v.as_str()) {
             let name = if path_str.is_empty() { "_" } else { path_str };
-            let args = obj.get("args")
-                .and_then(|a| a.get("angle_bracketed"))
-                .and_then(|ab| ab.get("args"))
-                .and_then(|a| a.as_array());
-            if let Some(args) = args {
-                let type_args: Vec<String> = args.iter()
-                    .filter_map(|a| a.get("type").map(type_to_string))
-                    .collect();
-                if !type_args.is_empty() {
-                    return format!("{name}<{}>", type_args.join(", "));
-                }
-            }
-            return name.to_string();
+            return format!("{name}{}", generic_args_to_string(obj.get("args")));
         }
     }
 
@@ -193,6 +162,75 @@ pub fn type_to_string(ty: &Value) -> String {
     ty.to_string()
 }
 
+/// Render a path's generic args: `<'a, T, N, Item = u8>`, `(A, B) -> C` for `Fn` traits,
+/// or an empty string when there are none.
+fn generic_args_to_string(args: Option<&Value>) -> String {
+    let Some(args) = args.filter(|a| !a.is_null()) else { return String::new() };
+
+    if let Some(p) = args.get("parenthesized") {
+        let inputs: Vec<String> = p.get("inputs")
+            .and_then(|v| v.as_array())
+            .map(|is| is.iter().map(type_to_string).collect())
+            .unwrap_or_default();
+        let output = match p.get("output").filter(|o| !o.is_null()).map(type_to_string) {
+            Some(o) if o != "()" => format!(" -> {o}"),
+            _ => String::new(),
+        };
+        return format!("({}){output}", inputs.join(", "));
+    }
+
+    let Some(ab) = args.get("angle_bracketed") else { return String::new() };
+    let mut parts: Vec<String> = ab.get("args")
+        .and_then(|a| a.as_array())
+        .map(|args| args.iter()
+            .filter_map(generic_arg_to_string)
+            .collect())
+        .unwrap_or_default();
+
+    // Associated item constraints: `Output = T` or `Item: Clone`
+    if let Some(constraints) = ab.get("constraints").and_then(|c| c.as_array()) {
+        parts.extend(constraints.iter().filter_map(|c| {
+            let name = c.get("name")?.as_str()?;
+            let name = format!("{name}{}", generic_args_to_string(c.get("args")));
+            let binding = c.get("binding")?;
+            if let Some(eq) = binding.get("equality") {
+                let rhs = match eq.get("type") {
+                    Some(t) => type_to_string(t),
+                    None => eq.get("constant")
+                        .and_then(|c| c.get("expr"))
+                        .and_then(|e| e.as_str())
+                        .unwrap_or("_")
+                        .to_string(),
+                };
+                return Some(format!("{name} = {rhs}"));
+            }
+            let bounds = binding.get("constraint")?.as_array()?;
+            Some(format!("{name}: {}", bounds_to_string(bounds)))
+        }));
+    }
+
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", parts.join(", "))
+    }
+}
+
+/// One generic argument as written: a type, a lifetime (`'a`), a const expression, or `_`.
+pub fn generic_arg_to_string(arg: &Value) -> Option<String> {
+    if let Some(t) = arg.get("type") {
+        return Some(type_to_string(t));
+    }
+    if let Some(lt) = arg.get("lifetime").and_then(|l| l.as_str()) {
+        // Some formats write lifetimes without the apostrophe
+        return Some(if lt.starts_with('\'') { lt.to_string() } else { format!("'{lt}") });
+    }
+    if let Some(c) = arg.get("const") {
+        return c.get("expr").and_then(|e| e.as_str()).map(str::to_string);
+    }
+    (arg.as_str() == Some("infer")).then(|| "_".to_string())
+}
+
 // ─── Signature reconstruction ─────────────────────────────────────────────────
 
 /// Reconstruct a function signature from rustdoc JSON format v57.
@@ -263,27 +301,108 @@ pub fn function_signature(item: &Item) -> String {
     format!("{prefix}fn {name}{generic_str}({inputs}){output_str}{where_str}")
 }
 
-/// Reconstruct a struct's signature fields.
-pub fn struct_fields(item: &Item) -> Vec<String> {
-    let inner = match item.inner_for("struct") {
-        Some(s) => s,
-        None => return vec![],
-    };
+/// Type and value of a constant, associated constant, or static.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstInfo {
+    /// `const`, `static`, or `static mut`
+    pub keyword: &'static str,
+    pub ty: String,
+    /// Rendered initializer; `None` for trait consts without a default
+    pub value: Option<String>,
+}
 
-    let kind = inner.get("kind");
-    if let Some(plain) = kind.and_then(|k| k.get("plain")) {
-        let fields = plain.get("fields")
-            .and_then(|f| f.as_array())
-            .map(|v| v.as_slice()).unwrap_or(&[]);
-        fields.iter()
-            .filter_map(|id| id.as_str())
-            .map(|_id| "/* field */".to_string()) // IDs need resolution from index
-            .collect()
-    } else {
-        vec![]
+impl ConstInfo {
+    /// `const MAX: usize = 64;`
+    pub fn declaration(&self, name: &str) -> String {
+        match &self.value {
+            Some(v) => format!("{} {name}: {} = {v};", self.keyword, self.ty),
+            None => format!("{} {name}: {};", self.keyword, self.ty),
+        }
+    }
+}
+
+/// Extract the type and value of a `constant`, `assoc_const` or `static` item.
+///
+/// rustdoc renders non-trivial initializers as `_`; the evaluated `value` is used instead
+/// when rustdoc provides one.
+pub fn const_info(item: &Item) -> Option<ConstInfo> {
+    let ty_of = |inner: &Value| inner.get("type").map(type_to_string).unwrap_or_else(|| "_".to_string());
+    let non_empty = |v: Option<&Value>| v.and_then(|v| v.as_str()).filter(|s| !s.is_empty()).map(str::to_string);
+
+    if let Some(inner) = item.inner_for("constant") {
+        let c = inner.get("const");
+        let expr = non_empty(c.and_then(|c| c.get("expr"))).filter(|e| e != "_");
+        let value = expr.or_else(|| non_empty(c.and_then(|c| c.get("value"))));
+        return Some(ConstInfo { keyword: "const", ty: ty_of(inner), value });
+    }
+    if let Some(inner) = item.inner_for("assoc_const") {
+        let value = non_empty(inner.get("value"));
+        return Some(ConstInfo { keyword: "const", ty: ty_of(inner), value });
+    }
+    if let Some(inner) = item.inner_for("static") {
+        let mutable = inner.get("is_mutable").and_then(|v| v.as_bool()).unwrap_or(false);
+        let value = non_empty(inner.get("expr")).filter(|e| e != "_");
+        let keyword = if mutable { "static mut" } else { "static" };
+        return Some(ConstInfo { keyword, ty: ty_of(inner), value });
+    }
+    None
+}
+
+/// A named field of a plain struct, resolved from `doc.index`.
+#[derive(Debug, Clone)]
+pub struct FieldInfo {
+    pub name: String,
+    /// Rendered field type, e.g. `Option<String>`
+    pub type_str: String,
+    pub docs: Option<String>,
+}
+
+impl FieldInfo {
+    /// `name: Type` as it would appear in the struct definition.
+    pub fn declaration(&self) -> String {
+        format!("{}: {}", self.name, self.type_str)
     }
 }
 
+/// Resolve the fields of a plain (braced) struct.
+///
+/// Field IDs in `inner.struct.kind.plain.fields` are looked up in `doc.index`, where each
+/// field is an item with `inner.struct_field` holding its type. Returns the visible fields
+/// and whether rustdoc stripped private or `#[doc(hidden)]` fields. Tuple and unit structs
+/// return no fields.
+pub fn struct_fields(doc: &RustdocJson, item: &Item) -> (Vec<FieldInfo>, bool) {
+    let Some(plain) = item.inner_for("struct")
+        .and_then(|s| s.get("kind"))
+        .and_then(|k| k.get("plain"))
+    else {
+        return (vec![], false);
+    };
+
+    let stripped = plain.get("has_stripped_fields")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let fields = plain.get("fields")
+        .and_then(|f| f.as_array())
+        .map(|ids| {
+            ids.iter()
+                .filter_map(id_to_string)
+                .filter_map(|id| doc.index.get(&id))
+                .filter_map(|field| {
+                    let ty = field.inner_for("struct_field")?;
+                    Some(FieldInfo {
+                        name: field.name.clone().unwrap_or_else(|| "_".to_string()),
+                        type_str: type_to_string(ty),
+                        docs: field.docs.clone(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    (fields, stripped)
+}
+
 /// Extract generic params from the inner block of any item kind (struct/enum/trait/type alias).
 /// Returns a formatted `<T, 'a, const N: usize>` string, or empty string if none.
 pub fn format_generics_for_item(item: &Item, kind: &str) -> String {
@@ -300,7 +419,7 @@ pub fn format_generics_for_item(item: &Item, kind: &str) -> String {
     String::new()
 }
 
-fn format_generics(generics: Option<&Value>) -> String {
+pub(crate) fn format_generics(generics: Option<&Value>) -> String {
     let generics = match generics {
         Some(g) => g,
         None => return String::new(),
@@ -321,31 +440,37 @@ fn format_generics(generics: Option<&Value>) -> String {
                 return None;
             }
             let kind = p.get("kind");
-            // Const generic param: {"const": {"type": T, "default": ...}} → `const N: type`
+            // Const generic param: {"const": {"type": T, "default": "3"}} → `const N: type = 3`
             if let Some(const_info) = kind.and_then(|k| k.get("const")) {
                 let ty_str = const_info.get("type").map(type_to_string).unwrap_or_else(|| "_".to_string());
-                return Some(format!("const {name}: {ty_str}"));
+                return Some(match const_info.get("default").and_then(|v| v.as_str()) {
+                    Some(default) => format!("const {name}: {ty_str} = {default}"),
+                    None => format!("const {name}: {ty_str}"),
+                });
             }
-            // Type param: may have bounds
-            if let Some(type_bounds) = kind.and_then(|k| k.get("type")).and_then(|t| t.get("bounds")) {
-                let bounds = type_bounds.as_array()
-                    .map(|bs| {
-                        bs.iter()
-                            .filter_map(|b| b.get("trait_bound"))
-                            .filter_map(|tb| tb.get("trait"))
-                            .map(type_to_string)
-                            .collect::<Vec<_>>()
-                            .join(" + ")
-                    })
+            // Type param: {"type": {"bounds": [...], "default": T}} → `T: Bound = Default`
+            if let Some(type_info) = kind.and_then(|k| k.get("type")) {
+                let bounds = type_info.get("bounds")
+                    .and_then(|v| v.as_array())
+                    .map(|bs| bounds_to_string(bs))
                     .unwrap_or_default();
-                if bounds.is_empty() {
-                    Some(name.to_string())
-                } else {
-                    Some(format!("{name}: {bounds}"))
+                let mut out = name.to_string();
+                if !bounds.is_empty() {
+                    out.push_str(&format!(": {bounds}"));
                 }
-            } else {
-                // Lifetime param (kind = {"lifetime": {...}}) or unbounded type param
+                if let Some(default) = type_info.get("default").filter(|d| !d.is_null()) {
+                    out.push_str(&format!(" = {}", type_to_string(default)));
+                }
+                return Some(out);
+            }
+            // Lifetime param: {"lifetime": {"outlives": ["'b"]}} → `'a: 'b`
+            let outlives = kind.and_then(|k| k.get("lifetime"))
+                .map(lifetimes_outlived)
+                .unwrap_or_default();
+            if outlives.is_empty() {
                 Some(name.to_string())
+            } else {
+                Some(format!("{name}: {outlives}"))
             }
         })
         .collect();
@@ -356,38 +481,101 @@ fn format_generics(generics: Option<&Value>) -> String {
     }
 }
 
-fn format_where(generics: Option<&Value>) -> String {
-    let generics = match generics {
-        Some(g) => g,
-        None => return String::new(),
-    };
-    let clauses = match generics.get("where_predicates").and_then(|v| v.as_array()) {
-        Some(c) => c,
-        None => return String::new(),
-    };
-    if clauses.is_empty() {
-        return String::new();
+/// Join the `outlives` list of a lifetime param or predicate as `'b + 'c`.
+fn lifetimes_outlived(v: &Value) -> String {
+    v.get("outlives")
+        .and_then(|o| o.as_array())
+        .map(|ls| ls.iter().filter_map(|l| l.as_str()).collect::<Vec<_>>().join(" + "))
+        .unwrap_or_default()
+}
+
+/// Render a higher-ranked binder such as `for<'a> ` from a `generic_params` list.
+///
+/// Returns an empty string when there are no bound params.
+fn hrtb_prefix(generic_params: Option<&Value>) -> String {
+    let names: Vec<&str> = generic_params
+        .and_then(|v| v.as_array())
+        .map(|ps| ps.iter().filter_map(|p| p.get("name").and_then(|n| n.as_str())).collect())
+        .unwrap_or_default();
+    if names.is_empty() {
+        String::new()
+    } else {
+        format!("for<{}> ", names.join(", "))
+    }
+}
+
+/// Render one generic bound: `for<'a> Fn(&'a T)`, `?Sized`, `~const Trait`, or `'a`.
+fn bound_to_string(b: &Value) -> Option<String> {
+    if let Some(tb) = b.get("trait_bound") {
+        let path = type_to_string(tb.get("trait")?);
+        let modifier = match tb.get("modifier").and_then(|m| m.as_str()) {
+            Some("maybe") => "?",
+            Some("maybe_const") => "~const ",
+            _ => "",
+        };
+        return Some(format!("{}{modifier}{path}", hrtb_prefix(tb.get("generic_params"))));
     }
-    let parts: Vec<String> = clauses.iter()
+    b.get("outlives").and_then(|v| v.as_str()).map(|s| s.to_string())
+}
+
+/// Render a list of generic bounds as `Trait + Trait2 + 'a`.
+///
+/// Handles `trait_bound` (including `for<'a>` binders and `?Sized`) and `outlives` lifetime bounds.
+pub fn bounds_to_string(bounds: &[Value]) -> String {
+    bounds.iter()
+        .filter_map(bound_to_string)
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+/// Render each where-clause predicate, e.g. `T: Clone + Send` or `'a: 'b`.
+pub fn where_predicates(generics: Option<&Value>) -> Vec<String> {
+    let Some(clauses) = generics
+        .and_then(|g| g.get("where_predicates"))
+        .and_then(|v| v.as_array())
+    else {
+        return vec![];
+    };
+    clauses.iter()
         .filter_map(|c| {
+            // `for<'a> T: Trait<'a>`
             if let Some(bp) = c.get("bound_predicate") {
                 let ty = bp.get("type").map(type_to_string)?;
-                let bounds = bp.get("bounds")?.as_array()?;
-                let bound_strs: Vec<String> = bounds.iter()
-                    .filter_map(|b| b.get("trait_bound"))
-                    .filter_map(|tb| tb.get("trait"))
-                    .map(type_to_string)
-                    .collect();
-                if bound_strs.is_empty() {
-                    None
-                } else {
-                    Some(format!("{ty}: {}", bound_strs.join(" + ")))
+                let bounds = bounds_to_string(bp.get("bounds")?.as_array()?);
+                if bounds.is_empty() {
+                    return None;
                 }
-            } else {
-                None
+                let binder = hrtb_prefix(bp.get("generic_params"));
+                return Some(format!("{binder}{ty}: {bounds}"));
+            }
+            // `'a: 'b`
+            if let Some(lp) = c.get("lifetime_predicate") {
+                let lifetime = lp.get("lifetime")?.as_str()?;
+                let outlives = lifetimes_outlived(lp);
+                return (!outlives.is_empty()).then(|| format!("{lifetime}: {outlives}"));
             }
+            // `<T as Iterator>::Item = u8`
+            if let Some(eq) = c.get("eq_predicate") {
+                let lhs = eq.get("lhs").map(type_to_string)?;
+                let rhs = eq.get("rhs")?;
+                let rhs = match rhs.get("type") {
+                    Some(ty) => type_to_string(ty),
+                    None => rhs.get("constant")
+                        .and_then(|c| c.get("expr"))
+                        .and_then(|e| e.as_str())
+                        .unwrap_or("_")
+                        .to_string(),
+                };
+                return Some(format!("{lhs} = {rhs}"));
+            }
+            None
         })
-        .collect();
+        .collect()
+}
+
+/// Where clause for `generics` as `\nwhere\n    ...`, or empty when there is none.
+pub fn format_where(generics: Option<&Value>) -> String {
+    let parts = where_predicates(generics);
     if parts.is_empty() {
         String::new()
     } else {
@@ -395,41 +583,98 @@ fn format_where(generics: Option<&Value>) -> String {
     }
 }
 
+/// Render an impl block header, e.g. `impl<T: Clone> From<T> for Wrapper<T>` followed by
+/// any where clause. `impl_inner` is the `inner.impl` object of an impl item.
+pub fn impl_header(impl_inner: &Value) -> String {
+    let generics = impl_inner.get("generics");
+    let generic_str = format_generics(generics);
+    let for_type = impl_inner.get("for").map(type_to_string).unwrap_or_else(|| "_".to_string());
+    let negative = impl_inner.get("is_negative").and_then(|v| v.as_bool()).unwrap_or(false);
+    let unsafe_str = if impl_inner.get("is_unsafe").and_then(|v| v.as_bool()).unwrap_or(false) {
+        "unsafe "
+    } else {
+        ""
+    };
+    let trait_str = match impl_inner.get("trait").filter(|t| !t.is_null()) {
+        Some(t) => format!("{}{} for ", if negative { "!" } else { "" }, type_to_string(t)),
+        None => String::new(),
+    };
+    let where_str = format_where(generics);
+    format!("{unsafe_str}impl{generic_str} {trait_str}{for_type}{where_str}")
+}
+
+// ─── Source links ─────────────────────────────────────────────────────────────
+
+/// Location of an item's source on docs.rs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLink {
+    /// Path within the crate package, e.g. `src/sync/mutex.rs`
+    pub file: String,
+    pub begin_line: u32,
+    pub end_line: u32,
+    /// docs.rs source browser URL anchored at the first line
+    pub url: String,
+}
+
+/// Map an item span to a docs.rs source page.
+///
+/// Relative filenames belong to the crate being documented (`name` `version`). Absolute
+/// paths into the docs.rs build's cargo registry (`.../registry/src/<index>/{crate}-{version}/...`)
+/// are linked to that dependency's sources. Anything else (e.g. std) yields `None`.
+pub fn source_link(span: &Span, name: &str, version: &str) -> Option<SourceLink> {
+    let (krate, krate_version, file) = if span.filename.starts_with('/') {
+        let (_, rest) = span.filename.split_once("/registry/src/")?;
+        let (_index_dir, rest) = rest.split_once('/')?;
+        let (package_dir, file) = rest.split_once('/')?;
+        let (krate, krate_version) = split_package_dir(package_dir)?;
+        (krate.to_string(), krate_version.to_string(), file.to_string())
+    } else if !span.filename.is_empty() {
+        (name.to_string(), version.to_string(), span.filename.clone())
+    } else {
+        return None;
+    };
+    let begin_line = span.begin.0;
+    Some(SourceLink {
+        url: format!("https://docs.rs/crate/{krate}/{krate_version}/source/{file}#L{begin_line}"),
+        file,
+        begin_line,
+        end_line: span.end.0,
+    })
+}
+
+/// Path of the local module whose source encloses `span`, e.g. `rmcp::transport::child_process`.
+///
+/// Impl blocks have no `paths` entry; this places them by file and line range, preferring
+/// the innermost inline module.
+pub fn defining_module(doc: &RustdocJson, span: &Span) -> Option<String> {
+    doc.paths.iter()
+        .filter(|(_, p)| p.crate_id == 0 && p.kind_name() == "module")
+        .filter_map(|(id, p)| Some((doc.index.get(id)?.span.as_ref()?, p)))
+        .filter(|(m, _)| m.filename == span.filename && m.begin.0 <= span.begin.0 && m.end.0 >= span.end.0)
+        .max_by_key(|(m, p)| (m.begin.0, p.path.len()))
+        .map(|(_, p)| p.full_path())
+}
+
+/// Split a registry directory name like `tower-http-0.6.8` into (`tower-http`, `0.6.8`).
+fn split_package_dir(dir: &str) -> Option<(&str, &str)> {
+    dir.match_indices('-')
+        .map(|(i, _)| (&dir[..i], &dir[i + 1..]))
+        .find(|(_, v)| semver::Version::parse(v).is_ok())
+}
+
 // ─── Feature flag extraction ──────────────────────────────────────────────────
 
 /// Extract feature requirements from rustdoc JSON item attributes.
 ///
-/// Uses the correct v57 attr format: `name: "feature", value: Some("auth")`
-/// NOT the broken `#[cfg(feature = "...")]` pattern.
+/// Parses the v57 `CfgTrace` attrs structurally (see [`super::cfg`]), so features inside
+/// `any(..)`/`all(..)` are found and features under `not(..)` are ignored.
 ///
 /// Cross-references against the set of declared features from the sparse index.
 pub fn extract_feature_requirements(
     attrs: &[String],
     declared_features: &HashSet<String>,
 ) -> Vec<String> {
-    // Lazy static would be cleaner, but we create the regex once per call
-    // (attrs are small, so this is acceptable)
-    let Ok(re) = Regex::new(r#"name: "feature", value: Some\("([^"]+)"\)"#) else {
-        return vec![];
-    };
-
-    let mut features: Vec<String> = attrs
-        .iter()
-        .flat_map(|attr| {
-            re.captures_iter(attr)
-                .filter_map(|cap| cap.get(1).map(|m| m.as_str().to_string()))
-                .collect::<Vec<_>>()
-        })
-        .collect();
-
-    // Cross-reference against declared features (filter out non-feature cfgs)
-    if !declared_features.is_empty() {
-        features.retain(|f| declared_features.contains(f));
-    }
-
-    features.sort();
-    features.dedup();
-    features
+    cfg_requirements(attrs, declared_features).features
 }
 
 // ─── Module tree building ─────────────────────────────────────────────────────
@@ -450,50 +695,68 @@ pub struct ModuleNode {
     pub item_counts: HashMap<String, usize>,
     /// Direct non-module items (structs, fns, traits, etc.) — populated for include_items.
     pub items: Vec<ItemSummary>,
+    /// Features named in the module's own `cfg` attributes (e.g. `["net"]` for `tokio::net`).
+    /// Submodules are implicitly gated by their parent too but list only their own.
+    pub feature_requirements: Vec<String>,
+    /// The module's full cfg condition, e.g. `any(feature = "client", feature = "server")`
+    pub cfg: Option<String>,
     pub children: Vec<ModuleNode>,
 }
 
-pub fn build_module_tree(doc: &RustdocJson) -> Vec<ModuleNode> {
-    // Find the root module
-    let root_id = doc.root_id();
-    let root_item = doc.index.get(&root_id);
-    if root_item.is_none() {
-        return vec![];
-    }
+/// Module levels returned by [`build_module_tree`].
+pub const MAX_MODULE_DEPTH: usize = 6;
 
-    // Build children of root
-    if let Some(root) = root_item {
-        if let Some(module) = root.inner_for("module") {
-            let item_ids = module.get("items")
-                .and_then(|v| v.as_array())
-                .cloned()
-                .unwrap_or_default();
+/// Build the module hierarchy below the crate root.
+///
+/// Non-public and `#[doc(hidden)]` items (and everything inside hidden modules) are
+/// skipped unless `include_hidden` is set.
+pub fn build_module_tree(doc: &RustdocJson, include_hidden: bool) -> Vec<ModuleNode> {
+    build_module_subtree(doc, None, MAX_MODULE_DEPTH, include_hidden).unwrap_or_default()
+}
 
-            return build_children(&item_ids, doc, 0);
-        }
+/// Build the module hierarchy below `root`, or below the crate root when `root` is `None`.
+///
+/// With a `root` path (e.g. `tokio::sync`) the result is that module's own node, with
+/// `max_depth` levels of submodules beneath it. Returns `None` when `root` does not name a
+/// module, or names one hidden from the public API without `include_hidden`.
+pub fn build_module_subtree(
+    doc: &RustdocJson,
+    root: Option<&str>,
+    max_depth: usize,
+    include_hidden: bool,
+) -> Option<Vec<ModuleNode>> {
+    let Some(root) = root else {
+        let root_item = doc.index.get(&doc.root_id())?;
+        let item_ids = module_item_ids(root_item);
+        return Some(build_children(&item_ids, doc, max_depth, include_hidden));
+    };
+    let id = find_item_id(doc, root)?;
+    let item = doc.index.get(&id)?;
+    if item.kind() != Some("module") || (!include_hidden && !item.is_visible()) {
+        return None;
     }
-    vec![]
+    Some(vec![module_node(&id, item, doc, max_depth, include_hidden)])
 }
 
-fn id_val_to_string(id_val: &Value) -> Option<String> {
-    match id_val {
-        Value::String(s) => Some(s.clone()),
-        Value::Number(n) => Some(n.to_string()),
-        _ => None,
-    }
+fn module_item_ids(item: &Item) -> Vec<Value> {
+    item.inner_for("module")
+        .and_then(|m| m.get("items"))
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default()
 }
 
-fn build_children(item_ids: &[Value], doc: &RustdocJson, depth: usize) -> Vec<ModuleNode> {
-    if depth > 5 {
+/// Module nodes for the modules among `item_ids`, with up to `levels - 1` levels of
+/// submodules beneath each.
+fn build_children(item_ids: &[Value], doc: &RustdocJson, levels: usize, include_hidden: bool) -> Vec<ModuleNode> {
+    if levels == 0 {
         return vec![];
     }
 
     let mut modules = vec![];
-    let mut other_counts: HashMap<String, usize> = HashMap::new();
-
     for id_val in item_ids {
         // v57 IDs are integers in JSON; the index HashMap has string keys
-        let id = match id_val_to_string(id_val) {
+        let id = match id_to_string(id_val) {
             Some(s) => s,
             None => continue,
         };
@@ -502,61 +765,60 @@ fn build_children(item_ids: &[Value], doc: &RustdocJson, depth: usize) -> Vec<Mo
             Some(i) => i,
             None => continue,
         };
+        if !include_hidden && !item.is_visible() { continue; }
 
-        let kind = item.kind().unwrap_or("unknown");
-
-        if kind == "module" {
-            let path = doc.paths.get(&id)
-                .map(|p| p.full_path())
-                .or_else(|| item.name.clone())
-                .unwrap_or_else(|| id.clone());
-
-            let doc_summary = item.doc_summary();
+        if item.kind() == Some("module") {
+            modules.push(module_node(&id, item, doc, levels - 1, include_hidden));
+        }
+    }
 
-            let sub_items = item.inner_for("module")
-                .and_then(|m| m.get("items"))
-                .and_then(|v| v.as_array())
-                .cloned()
-                .unwrap_or_default();
+    modules
+}
 
-            let mut item_counts = HashMap::new();
-            let mut direct_items = vec![];
-            for sub_id_val in &sub_items {
-                if let Some(sub_id) = id_val_to_string(sub_id_val) {
-                    if let Some(sub_item) = doc.index.get(&sub_id) {
-                        if let Some(k) = sub_item.kind() {
-                            // Skip "use"/"import" re-exports from counts — they're noise
-                            // (re-exported items already appear under their canonical path).
-                            if k == "use" || k == "import" { continue; }
-                            *item_counts.entry(k.to_string()).or_insert(0) += 1;
-                            // Collect non-module items for include_items
-                            if k != "module" {
-                                direct_items.push(ItemSummary {
-                                    kind: k.to_string(),
-                                    name: sub_item.name.clone().unwrap_or_default(),
-                                    doc_summary: sub_item.doc_summary(),
-                                });
-                            }
-                        }
-                    }
+/// One module with its item counts, direct items and `levels` levels of submodules.
+fn module_node(id: &str, item: &Item, doc: &RustdocJson, levels: usize, include_hidden: bool) -> ModuleNode {
+    let path = doc.paths.get(id)
+        .map(|p| p.full_path())
+        .or_else(|| item.name.clone())
+        .unwrap_or_else(|| id.to_string());
+
+    let doc_summary = item.doc_summary();
+    let sub_items = module_item_ids(item);
+
+    let mut item_counts = HashMap::new();
+    let mut direct_items = vec![];
+    for sub_id_val in &sub_items {
+        if let Some(sub_id) = id_to_string(sub_id_val) && let Some(sub_item) = doc.index.get(&sub_id) {
+            if !include_hidden && !sub_item.is_visible() { continue; }
+            if let Some(k) = sub_item.kind() {
+                // Skip "use"/"import" re-exports from counts — they're noise
+                // (re-exported items already appear under their canonical path).
+                if k == "use" || k == "import" { continue; }
+                *item_counts.entry(k.to_string()).or_insert(0) += 1;
+                // Collect non-module items for include_items
+                if k != "module" {
+                    direct_items.push(ItemSummary {
+                        kind: k.to_string(),
+                        name: sub_item.name.clone().unwrap_or_default(),
+                        doc_summary: sub_item.doc_summary(),
+                    });
                 }
             }
-
-            let children = build_children(&sub_items, doc, depth + 1);
-
-            modules.push(ModuleNode {
-                path,
-                doc_summary,
-                item_counts,
-                items: direct_items,
-                children,
-            });
-        } else {
-            *other_counts.entry(kind.to_string()).or_insert(0) += 1;
         }
     }
 
-    modules
+    let children = build_children(&sub_items, doc, levels, include_hidden);
+    let requirements = cfg_requirements(&item.attr_strings(), &HashSet::new());
+
+    ModuleNode {
+        path,
+        doc_summary,
+        item_counts,
+        items: direct_items,
+        feature_requirements: requirements.features,
+        cfg: requirements.cfg,
+        children,
+    }
 }
 
 // ─── Method parent map ───────────────────────────────────────────────────────
@@ -581,7 +843,7 @@ fn type_item_id(val: &Value) -> Option<String> {
 ///
 /// Covers inherent impl blocks. Trait-impl method IDs are intentionally excluded
 /// because they are covered by looking up the implementing type directly.
-fn build_method_parent_map(doc: &RustdocJson) -> HashMap<String, String> {
+pub(crate) fn build_method_parent_map(doc: &RustdocJson) -> HashMap<String, String> {
     let mut map: HashMap<String, String> = HashMap::new();
 
     for item in doc.index.values() {
@@ -609,7 +871,7 @@ fn build_method_parent_map(doc: &RustdocJson) -> HashMap<String, String> {
             .unwrap_or_default();
 
         for method_id_val in &method_ids {
-            if let Some(mid) = id_val_to_string(method_id_val) {
+            if let Some(mid) = id_to_string(method_id_val) {
                 map.insert(mid, parent_path.clone());
             }
         }
@@ -618,6 +880,380 @@ fn build_method_parent_map(doc: &RustdocJson) -> HashMap<String, String> {
     map
 }
 
+// ─── Intra-doc links ──────────────────────────────────────────────────────────
+
+/// An intra-doc link from an item's `links` map, resolved to its target.
+#[derive(Debug, Clone)]
+pub struct ResolvedLink {
+    /// Link text as written in the doc comment, e.g. `` `Foo` `` or `crate::Foo`
+    pub text: String,
+    /// Fully-qualified path of the target, e.g. `tokio::sync::Mutex`
+    pub path: String,
+    pub kind: String,
+    /// docs.rs page for the target, when its kind has a page of its own
+    pub url: Option<String>,
+}
+
+/// Resolve an item's intra-doc links (`item.links`, link text → item ID) via `doc.paths`.
+///
+/// Links whose targets have no path entry (e.g. methods) are skipped. Results are sorted
+/// by link text.
+pub fn resolve_links(doc: &RustdocJson, item: &Item) -> Vec<ResolvedLink> {
+    let Some(links) = &item.links else { return vec![] };
+    let mut resolved: Vec<ResolvedLink> = links.iter()
+        .filter_map(|(text, id_val)| {
+            let entry = doc.paths.get(&id_to_string(id_val)?)?;
+            Some(ResolvedLink {
+                text: text.clone(),
+                path: entry.full_path(),
+                kind: entry.kind.clone(),
+                url: docs_url(doc, entry),
+            })
+        })
+        .collect();
+    resolved.sort_by(|a, b| a.text.cmp(&b.text));
+    resolved
+}
+
+/// Build the docs.rs URL for a path entry, e.g.
+/// `https://docs.rs/tokio/1.0.0/tokio/sync/struct.Mutex.html`.
+///
+/// Local items use the documented crate version; items from external crates use `latest`.
+/// Returns `None` for kinds that live on their parent's page (fields, variants, assoc items).
+pub fn docs_url(doc: &RustdocJson, entry: &super::types::PathEntry) -> Option<String> {
+    let krate = entry.path.first()?;
+    let version = match (&doc.crate_version, entry.crate_id) {
+        (Some(v), 0) => v.as_str(),
+        _ => "latest",
+    };
+    let base = format!("https://docs.rs/{krate}/{version}");
+    if entry.kind == "module" {
+        return Some(format!("{base}/{}/index.html", entry.path.join("/")));
+    }
+    let prefix = match entry.kind.as_str() {
+        "struct" => "struct",
+        "enum" => "enum",
+        "union" => "union",
+        "trait" => "trait",
+        "trait_alias" => "traitalias",
+        "function" => "fn",
+        "type_alias" => "type",
+        "constant" => "constant",
+        "static" => "static",
+        "macro" => "macro",
+        "proc_attribute" => "attr",
+        "proc_derive" => "derive",
+        "primitive" => "primitive",
+        _ => return None,
+    };
+    let (name, parents) = entry.path.split_last()?;
+    Some(format!("{base}/{}/{prefix}.{name}.html", parents.join("/")))
+}
+
+/// Rewrite intra-doc link syntax in `docs` into standard markdown links.
+///
+/// `[text]` becomes `[text](url)`, and the reference forms `[label][text]` and
+/// `[label](text)` have their target replaced. Links without a URL use the item path.
+pub fn rewrite_doc_links(docs: &str, links: &[ResolvedLink]) -> String {
+    let mut out = docs.to_string();
+    for link in links {
+        let target = link.url.as_deref().unwrap_or(&link.path);
+        out = out.replace(&format!("]({})", link.text), &format!("]({target})"));
+        out = out.replace(&format!("][{}]", link.text), &format!("]({target})"));
+
+        // Shortcut form: `[text]` not already followed by a target.
+        let needle = format!("[{}]", link.text);
+        let mut result = String::with_capacity(out.len());
+        let mut rest = out.as_str();
+        while let Some(pos) = rest.find(&needle) {
+            let after = &rest[pos + needle.len()..];
+            result.push_str(&rest[..pos + needle.len()]);
+            if !after.starts_with('(') && !after.starts_with('[') {
+                result.push_str(&format!("({target})"));
+            }
+            rest = after;
+        }
+        result.push_str(rest);
+        out = result;
+    }
+    out
+}
+
+// ─── Doc sections ─────────────────────────────────────────────────────────────
+
+/// A markdown section of a doc comment, from its heading up to the next heading of the
+/// same or a higher level (so subsections are included).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocSection {
+    /// Heading level (1 for `#`); 0 for the text before the first heading
+    pub level: usize,
+    /// Heading text without the `#` markers; empty for the leading text
+    pub title: String,
+    /// The heading line and everything under it
+    pub text: String,
+}
+
+/// Split markdown `docs` at its ATX headings (`#` to `######`), ignoring `#` lines inside
+/// fenced code blocks, where they are usually hidden doctest lines.
+pub fn split_doc_sections(docs: &str) -> Vec<DocSection> {
+    let lines: Vec<&str> = docs.lines().collect();
+    let mut headings: Vec<(usize, usize, String)> = vec![]; // (line, level, title)
+    let mut fence: Option<&str> = None;
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_start();
+        if let Some(open) = fence {
+            if trimmed.starts_with(open) {
+                fence = None;
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+            continue;
+        }
+        let level = line.chars().take_while(|c| *c == '#').count();
+        if (1..=6).contains(&level) && line[level..].starts_with(' ') {
+            let title = line[level..].trim().trim_end_matches('#').trim_end().to_string();
+            headings.push((i, level, title));
+        }
+    }
+
+    let mut sections = vec![];
+    let first = headings.first().map(|h| h.0).unwrap_or(lines.len());
+    if lines[..first].iter().any(|l| !l.trim().is_empty()) {
+        sections.push(DocSection { level: 0, title: String::new(), text: lines[..first].join("\n").trim_end().to_string() });
+    }
+    for (n, (start, level, title)) in headings.iter().enumerate() {
+        let end = headings[n + 1..].iter()
+            .find(|(_, l, _)| l <= level)
+            .map(|h| h.0)
+            .unwrap_or(lines.len());
+        sections.push(DocSection {
+            level: *level,
+            title: title.clone(),
+            text: lines[*start..end].join("\n").trim_end().to_string(),
+        });
+    }
+    sections
+}
+
+/// Sections whose title matches one of `titles`, case-insensitively. An exact title wins;
+/// otherwise every section whose title contains the query matches.
+///
+/// Returns the matches in document order, plus the queries that matched nothing. A match
+/// nested under another match is dropped, since the outer section's text already has it.
+pub fn select_doc_sections<'a>(sections: &'a [DocSection], titles: &[String]) -> (Vec<&'a DocSection>, Vec<String>) {
+    let mut selected: Vec<usize> = vec![];
+    let mut missing = vec![];
+    for query in titles {
+        let query_lower = query.trim().to_lowercase();
+        let exact: Vec<usize> = sections.iter().enumerate()
+            .filter(|(_, s)| s.level > 0 && s.title.to_lowercase() == query_lower)
+            .map(|(i, _)| i)
+            .collect();
+        let found = if exact.is_empty() {
+            sections.iter().enumerate()
+                .filter(|(_, s)| s.level > 0 && s.title.to_lowercase().contains(&query_lower))
+                .map(|(i, _)| i)
+                .collect()
+        } else {
+            exact
+        };
+        if found.is_empty() {
+            missing.push(query.clone());
+        }
+        selected.extend(found);
+    }
+    selected.sort_unstable();
+    selected.dedup();
+    // Sections after `outer` with deeper headings are inside it, up to the next heading
+    // at its level or above
+    let inside = |outer: usize, inner: usize| sections[outer + 1..=inner].iter().all(|s| s.level > sections[outer].level);
+    let mut kept: Vec<usize> = vec![];
+    for i in selected {
+        if !kept.last().is_some_and(|&outer| inside(outer, i)) {
+            kept.push(i);
+        }
+    }
+    (kept.into_iter().map(|i| &sections[i]).collect(), missing)
+}
+
+/// The conventional caveat sections of an item's docs, each without its heading.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocCaveats {
+    /// `# Panics`: the inputs or states that make the item panic
+    pub panics: Option<String>,
+    /// `# Errors`: when a `Result`-returning item returns `Err`
+    pub errors: Option<String>,
+    /// `# Safety`: the invariants a caller of an `unsafe` item must uphold
+    pub safety: Option<String>,
+}
+
+/// Pull the `# Panics`, `# Errors` and `# Safety` sections out of `docs`, at any heading
+/// level and in any case. Each stops at its first subheading; repeated sections are
+/// joined with a blank line.
+pub fn doc_caveats(docs: &str) -> DocCaveats {
+    let mut caveats = DocCaveats::default();
+    let sections = split_doc_sections(docs);
+    for (i, section) in sections.iter().enumerate() {
+        let slot = match section.title.to_lowercase().as_str() {
+            "panics" | "panic" => &mut caveats.panics,
+            "errors" | "error" => &mut caveats.errors,
+            "safety" => &mut caveats.safety,
+            _ => continue,
+        };
+        // Drop the heading line, and any subsection (the next section, when it is deeper)
+        let mut text = section.text.as_str();
+        if let Some(child) = sections.get(i + 1).filter(|next| next.level > section.level) {
+            let child_heading = child.text.lines().next().unwrap_or_default();
+            if let Some(at) = text.find(&format!("\n{child_heading}")) {
+                text = &text[..at];
+            }
+        }
+        let body = text.split_once('\n').map_or("", |(_, body)| body).trim();
+        if body.is_empty() {
+            continue;
+        }
+        match slot {
+            Some(text) => {
+                text.push_str("\n\n");
+                text.push_str(body);
+            }
+            None => *slot = Some(body.to_string()),
+        }
+    }
+    caveats
+}
+
+/// Code block attributes rustdoc understands, besides `edition20xx` and `ignore-<target>`.
+const DOCTEST_ATTRIBUTES: [&str; 7] =
+    ["ignore", "no_run", "should_panic", "compile_fail", "test_harness", "standalone_crate", "allow_fail"];
+
+/// A fenced code block from an item's docs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocExample {
+    /// Language tag; untagged blocks and blocks with only doctest attributes are `rust`
+    pub language: String,
+    /// Doctest attributes from the fence, e.g. `no_run`, `ignore`, `edition2021`
+    pub attributes: Vec<String>,
+    /// The code as rendered on docs.rs: hidden `# ` lines of Rust blocks removed
+    pub code: String,
+    /// The complete doctest including hidden lines; `None` when it equals `code`
+    pub full_code: Option<String>,
+    /// Title of the heading the block sits under, if any
+    pub section: Option<String>,
+}
+
+/// Separate `docs` into prose and its fenced code blocks. The prose keeps everything but
+/// the blocks, with the blank lines they leave collapsed.
+pub fn split_doc_examples(docs: &str) -> (String, Vec<DocExample>) {
+    let mut prose: Vec<&str> = vec![];
+    let mut examples = vec![];
+    let mut section: Option<String> = None;
+    // Open fence: (marker, info string, body lines)
+    let mut open: Option<(&str, &str, Vec<&str>)> = None;
+    for line in docs.lines() {
+        let trimmed = line.trim_start();
+        if let Some((marker, info, body)) = &mut open {
+            // A closing fence uses the same character, at least as many times, and nothing else
+            if trimmed.starts_with(*marker) && trimmed.trim_end().chars().all(|c| marker.starts_with(c)) {
+                examples.push(doc_example(info, body, section.clone()));
+                open = None;
+            } else {
+                body.push(line);
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            let fence_char = trimmed.as_bytes()[0] as char;
+            let len = trimmed.chars().take_while(|c| *c == fence_char).count();
+            open = Some((&trimmed[..len], trimmed[len..].trim(), vec![]));
+            continue;
+        }
+        let level = line.chars().take_while(|c| *c == '#').count();
+        if (1..=6).contains(&level) && line[level..].starts_with(' ') {
+            section = Some(line[level..].trim().trim_end_matches('#').trim_end().to_string());
+        }
+        if !(line.trim().is_empty() && prose.last().is_some_and(|l| l.trim().is_empty())) {
+            prose.push(line);
+        }
+    }
+    // An unclosed fence runs to the end of the docs, as in rustdoc
+    if let Some((_, info, body)) = &open {
+        examples.push(doc_example(info, body, section));
+    }
+    (prose.join("\n").trim().to_string(), examples)
+}
+
+fn doc_example(info: &str, body: &[&str], section: Option<String>) -> DocExample {
+    let mut language = None;
+    let mut attributes = vec![];
+    for token in info.trim_matches(|c| c == '{' || c == '}').split([',', ' ', '\t']).filter(|t| !t.is_empty()) {
+        let token = token.trim_start_matches('.');
+        if DOCTEST_ATTRIBUTES.contains(&token) || token.starts_with("edition") || token.starts_with("ignore-") {
+            attributes.push(token.to_string());
+        } else if language.is_none() {
+            language = Some(token.to_string());
+        }
+    }
+    let language = language.unwrap_or_else(|| "rust".to_string());
+
+    let full_code = body.join("\n");
+    if language != "rust" {
+        return DocExample { language, attributes, code: full_code, full_code: None, section };
+    }
+    // Rustdoc hides `# code` and bare `#` lines, and unescapes `##` to `#`
+    let mut full = vec![];
+    let mut shown = vec![];
+    for line in body {
+        let trimmed = line.trim_start();
+        let indent = &line[..line.len() - trimmed.len()];
+        if let Some(rest) = trimmed.strip_prefix("##") {
+            let unescaped = format!("{indent}#{rest}");
+            full.push(unescaped.clone());
+            shown.push(unescaped);
+        } else if trimmed == "#" || trimmed.starts_with("# ") {
+            full.push(format!("{indent}{}", trimmed.strip_prefix("# ").unwrap_or("")));
+        } else {
+            full.push(line.to_string());
+            shown.push(line.to_string());
+        }
+    }
+    let code = shown.join("\n").trim_matches('\n').to_string();
+    let full = full.join("\n");
+    let full_code = (full != code).then_some(full);
+    DocExample { language, attributes, code, full_code, section }
+}
+
+// ─── Path lookup ──────────────────────────────────────────────────────────────
+
+/// Find the item ID for a fully-qualified path in `doc.paths`.
+///
+/// Tries an exact match first, then falls back to re-export matching: the non-crate
+/// components of `path` must be a subsequence of the stored path, so `tokio::sync::Mutex`
+/// finds `tokio::sync::mutex::Mutex`.
+pub fn find_item_id(doc: &RustdocJson, path: &str) -> Option<String> {
+    if let Some(id) = doc.id_for_path(path) {
+        return Some(id.to_string());
+    }
+    // Only entries ending in the same name can match
+    let target_parts: Vec<&str> = path.split("::").collect();
+    let (first, target_rest) = target_parts.split_first()?;
+    let last = target_rest.last()?;
+    doc.ids_with_last_segment(last).iter()
+        .find(|id| {
+            let Some(parts) = doc.paths.get(*id).map(|p| &p.path) else { return false };
+            let Some((stored_first, stored_rest)) = parts.split_first() else { return false };
+            if stored_first != first { return false; }
+            let mut ti = 0;
+            for s in stored_rest {
+                if ti < target_rest.len() && *s == target_rest[ti] { ti += 1; }
+            }
+            ti == target_rest.len()
+        })
+        .cloned()
+}
+
 // ─── Item search ──────────────────────────────────────────────────────────────
 
 pub struct SearchResult {
@@ -626,9 +1262,30 @@ pub struct SearchResult {
     pub signature: String,
     pub doc_summary: String,
     pub feature_requirements: Vec<String>,
+    /// Target conditions in cfg syntax (e.g. `unix`); empty for portable items
+    pub target_requirements: Vec<String>,
     pub score: f32,
 }
 
+/// `"path::"` prefixes of local modules that are non-public or `#[doc(hidden)]`.
+pub(crate) fn hidden_module_prefixes(doc: &RustdocJson) -> Vec<String> {
+    doc.paths.iter()
+        .filter(|(_, p)| p.crate_id == 0 && p.kind_name() == "module")
+        .filter(|(id, _)| doc.index.get(*id).is_some_and(|i| !i.is_visible()))
+        .map(|(_, p)| format!("{}::", p.full_path()))
+        .collect()
+}
+
+/// An item or method that passed a search's filters, for its scorer to rank.
+pub struct SearchCandidate<'a> {
+    /// Rustdoc ID of the item
+    pub id: &'a str,
+    pub name: &'a str,
+    /// The owning type's path, for methods
+    pub parent_path: Option<&'a str>,
+    pub doc_summary: &'a str,
+}
+
 /// Search for items in the rustdoc JSON by name or concept.
 pub fn search_items(
     doc: &RustdocJson,
@@ -637,9 +1294,42 @@ pub fn search_items(
     module_prefix: Option<&str>,
     limit: usize,
     declared_features: &HashSet<String>,
+    include_hidden: bool,
 ) -> Vec<SearchResult> {
     let query_lower = query.to_lowercase();
+    let score = |c: &SearchCandidate| {
+        let name_lower = c.name.to_lowercase();
+        if name_lower == query_lower {
+            Some(1.0f32)
+        } else if name_lower.starts_with(&query_lower) {
+            Some(0.9)
+        } else if name_lower.contains(&query_lower) {
+            Some(0.7)
+        } else if c.parent_path.is_some_and(|p| p.to_lowercase().contains(&query_lower)) {
+            Some(0.6) // query matches parent type name, e.g. "TokioChildProcess" → all its methods
+        } else if c.doc_summary.to_lowercase().contains(&query_lower) {
+            Some(if c.parent_path.is_some() { 0.4 } else { 0.2 })
+        } else {
+            None
+        }
+    };
+    search_items_scored(doc, kind_filter, module_prefix, limit, declared_features, include_hidden, score)
+}
+
+/// Rank the items and methods passing the kind, module and visibility filters by `score`,
+/// best first; candidates scored `None` are left out.
+pub fn search_items_scored(
+    doc: &RustdocJson,
+    kind_filter: Option<&str>,
+    module_prefix: Option<&str>,
+    limit: usize,
+    declared_features: &HashSet<String>,
+    include_hidden: bool,
+    score: impl Fn(&SearchCandidate) -> Option<f32>,
+) -> Vec<SearchResult> {
     let mut results: Vec<SearchResult> = vec![];
+    let hidden_modules = if include_hidden { vec![] } else { hidden_module_prefixes(doc) };
+    let in_hidden_module = |path: &str| hidden_modules.iter().any(|m| path.starts_with(m.as_str()));
 
     for (id, item) in &doc.index {
         let path_entry = match doc.paths.get(id) {
@@ -648,6 +1338,9 @@ pub fn search_items(
         };
 
         let full_path = path_entry.full_path();
+        if !include_hidden && (!item.is_visible() || in_hidden_module(&full_path)) {
+            continue;
+        }
         let name = item.name.as_deref().unwrap_or("");
         let item_kind = path_entry.kind_name();
 
@@ -676,36 +1369,28 @@ pub fn search_items(
             continue;
         }
 
-        let name_lower = name.to_lowercase();
         let doc_summary = item.doc_summary();
-        let doc_lower = doc_summary.to_lowercase();
-
-        // Score calculation
-        let score = if name_lower == query_lower {
-            1.0f32
-        } else if name_lower.starts_with(&query_lower) {
-            0.9
-        } else if name_lower.contains(&query_lower) {
-            0.7
-        } else if doc_lower.contains(&query_lower) {
-            0.2
-        } else {
+        let Some(score) = score(&SearchCandidate { id, name, parent_path: None, doc_summary: &doc_summary }) else {
             continue; // no match
         };
 
         let signature = match item.kind().unwrap_or("") {
             "function" => function_signature(item),
+            "constant" | "static" => const_info(item)
+                .map(|c| c.declaration(name))
+                .unwrap_or_else(|| format!("{} {}", item_kind, name)),
             _ => format!("{} {}", item_kind, name),
         };
 
-        let feature_requirements = extract_feature_requirements(&item.attr_strings(), declared_features);
+        let requirements = cfg_requirements(&item.attr_strings(), declared_features);
 
         results.push(SearchResult {
             path: full_path,
             kind: item_kind.to_string(),
             signature,
             doc_summary,
-            feature_requirements,
+            feature_requirements: requirements.features,
+            target_requirements: requirements.targets,
             score,
         });
     }
@@ -716,7 +1401,7 @@ pub fn search_items(
     let want_methods = kind_filter.is_none() || kind_filter == Some("method");
 
     if want_methods {
-        let method_parent_map = build_method_parent_map(doc);
+        let method_parent_map = doc.method_parents();
 
         for (id, item) in &doc.index {
             if doc.paths.contains_key(id) { continue; } // already searched above
@@ -725,41 +1410,30 @@ pub fn search_items(
             let Some(parent_path) = method_parent_map.get(id) else { continue };
             let name = item.name.as_deref().unwrap_or("");
             if name.is_empty() { continue; }
+            if !include_hidden && (!item.is_visible() || in_hidden_module(parent_path.as_str())) {
+                continue;
+            }
 
             // Module prefix filter: parent type path must start with the prefix
             if let Some(prefix) = module_prefix {
                 if !parent_path.starts_with(prefix) { continue; }
             }
 
-            let name_lower = name.to_lowercase();
-            let parent_lower = parent_path.to_lowercase();
             let doc_summary = item.doc_summary();
-            let doc_lower = doc_summary.to_lowercase();
-
-            let score = if name_lower == query_lower {
-                1.0f32
-            } else if name_lower.starts_with(&query_lower) {
-                0.9
-            } else if name_lower.contains(&query_lower) {
-                0.7
-            } else if parent_lower.contains(&query_lower) {
-                0.6 // query matches parent type name, e.g. "TokioChildProcess" → all its methods
-            } else if doc_lower.contains(&query_lower) {
-                0.4
-            } else {
-                continue;
-            };
+            let candidate = SearchCandidate { id, name, parent_path: Some(parent_path), doc_summary: &doc_summary };
+            let Some(score) = score(&candidate) else { continue };
 
             let full_path = format!("{parent_path}::{name}");
             let signature = function_signature(item);
-            let feature_requirements = extract_feature_requirements(&item.attr_strings(), declared_features);
+            let requirements = cfg_requirements(&item.attr_strings(), declared_features);
 
             results.push(SearchResult {
                 path: full_path,
                 kind: "method".to_string(),
                 signature,
                 doc_summary,
-                feature_requirements,
+                feature_requirements: requirements.features,
+                target_requirements: requirements.targets,
                 score,
             });
         }
@@ -792,7 +1466,7 @@ mod tests {
         let ty = serde_json::json!({
             "borrowed_ref": {
                 "lifetime": null,
-                "mutable": false,
+                "is_mutable": false,
                 "type": {"primitive": "str"}
             }
         });
@@ -804,7 +1478,7 @@ mod tests {
         let ty = serde_json::json!({
             "borrowed_ref": {
                 "lifetime": "a",
-                "mutable": true,
+                "is_mutable": true,
                 "type": {"generic": "T"}
             }
         });
@@ -0,0 +1,117 @@
+//! How much of a doc comment `Item::doc_summary` keeps.
+
+use std::sync::OnceLock;
+
+use serde::Serialize;
+
+/// Env var for the sentences kept in a summary; `0` keeps the whole first paragraph.
+pub const SENTENCES_ENV: &str = "DOCS_MCP_SUMMARY_SENTENCES";
+/// Env var for the longest summary, in characters; `0` for no limit.
+pub const MAX_CHARS_ENV: &str = "DOCS_MCP_SUMMARY_CHARS";
+
+pub const DEFAULT_SENTENCES: usize = 1;
+pub const DEFAULT_MAX_CHARS: usize = 200;
+
+/// Words whose trailing period does not end a sentence.
+const ABBREVIATIONS: [&str; 6] = ["e.g.", "i.e.", "etc.", "vs.", "cf.", "approx."];
+
+/// Summary length: the first `sentences` sentences of the first paragraph, cut to
+/// `max_chars` at a word boundary. Headings and code blocks end the paragraph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct SummaryPolicy {
+    pub sentences: usize,
+    pub max_chars: usize,
+}
+
+impl Default for SummaryPolicy {
+    fn default() -> Self {
+        Self { sentences: DEFAULT_SENTENCES, max_chars: DEFAULT_MAX_CHARS }
+    }
+}
+
+impl SummaryPolicy {
+    /// Policy from [`SENTENCES_ENV`] and [`MAX_CHARS_ENV`]; unset or garbage values keep the defaults.
+    pub fn from_env() -> Self {
+        let env = |name: &str| std::env::var(name).ok().and_then(|v| v.trim().parse::<usize>().ok());
+        let default = Self::default();
+        Self {
+            sentences: env(SENTENCES_ENV).unwrap_or(default.sentences),
+            max_chars: env(MAX_CHARS_ENV).unwrap_or(default.max_chars),
+        }
+    }
+
+    /// The policy every summary in this process uses, read from the environment on first use.
+    pub fn global() -> Self {
+        static POLICY: OnceLock<SummaryPolicy> = OnceLock::new();
+        *POLICY.get_or_init(Self::from_env)
+    }
+
+    /// Summary of a markdown doc comment.
+    pub fn summarize(&self, docs: &str) -> String {
+        // Leading headings (`# Tokio` atop a crate root) title the docs rather than end them
+        let paragraph: Vec<&str> = docs.lines()
+            .map(str::trim)
+            .skip_while(|l| l.is_empty() || l.starts_with('#'))
+            .take_while(|l| !l.is_empty() && !l.starts_with('#') && !l.starts_with("```") && !l.starts_with("~~~"))
+            .collect();
+        let paragraph = paragraph.join(" ");
+
+        let mut summary = match self.sentences {
+            0 => paragraph.as_str(),
+            n => sentence_ends(&paragraph).nth(n - 1).map_or(paragraph.as_str(), |end| &paragraph[..end]),
+        }.to_string();
+
+        if self.max_chars > 0 && summary.chars().count() > self.max_chars {
+            let cut = summary.char_indices().nth(self.max_chars).map_or(summary.len(), |(i, _)| i);
+            let cut = summary[..cut].rfind(char::is_whitespace).filter(|&i| i > 0).unwrap_or(cut);
+            summary.truncate(cut);
+            summary.push('…');
+        }
+        summary
+    }
+}
+
+/// Byte offsets just past each sentence-ending `.`, `!` or `?` followed by whitespace,
+/// outside `code spans` and abbreviations.
+fn sentence_ends(text: &str) -> impl Iterator<Item = usize> + '_ {
+    let mut in_code = false;
+    let mut chars = text.char_indices().peekable();
+    std::iter::from_fn(move || {
+        while let Some((i, c)) = chars.next() {
+            if c == '`' {
+                in_code = !in_code;
+                continue;
+            }
+            let followed_by_space = chars.peek().is_none_or(|(_, next)| next.is_whitespace());
+            if in_code || !matches!(c, '.' | '!' | '?') || !followed_by_space {
+                continue;
+            }
+            let end = i + c.len_utf8();
+            let word = text[..end].rsplit(char::is_whitespace).next().unwrap_or("");
+            if !ABBREVIATIONS.contains(&word.to_lowercase().as_str()) {
+                return Some(end);
+            }
+        }
+        None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_wrapped_sentences() {
+        let policy = SummaryPolicy::default();
+        let docs = "Creates a new channel, e.g. for\nsending `Vec<T>` values. Blocks until\nready.\n\nMore details.";
+        assert_eq!(policy.summarize(docs), "Creates a new channel, e.g. for sending `Vec<T>` values.");
+        assert_eq!(policy.summarize("# Tokio\n\nA runtime. Fast!"), "A runtime.");
+        assert_eq!(policy.summarize("Calls `a.b()` here\n# Panics\nNever."), "Calls `a.b()` here");
+
+        let two = SummaryPolicy { sentences: 2, max_chars: 0 };
+        assert_eq!(two.summarize(docs), "Creates a new channel, e.g. for sending `Vec<T>` values. Blocks until ready.");
+
+        let short = SummaryPolicy { sentences: 0, max_chars: 20 };
+        assert_eq!(short.summarize(docs), "Creates a new…");
+    }
+}
@@ -0,0 +1,263 @@
+//! What implementing a trait takes: a trait's members, and an impl skeleton stubbing the
+//! ones an impl must define.
+
+use serde_json::{Value, json};
+
+use super::parser::{bounds_to_string, const_info, format_generics, format_where, function_signature, type_to_string};
+use super::types::{id_to_string, Item, RustdocJson};
+
+/// One method, associated type or associated const declared by a trait.
+pub struct TraitMember {
+    pub name: String,
+    /// "method", "assoc_type" or "assoc_const"
+    pub kind: &'static str,
+    /// Signature or declaration as it appears in the trait
+    pub declaration: String,
+    /// True when the trait gives no default, so every impl must define it
+    pub required: bool,
+    /// Bounds an associated type must satisfy, e.g. `std::error::Error + Send`
+    pub bounds: Option<String>,
+    pub doc_summary: String,
+}
+
+impl TraitMember {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "name": self.name,
+            "kind": self.kind,
+            "declaration": self.declaration,
+            "doc_summary": self.doc_summary,
+        })
+    }
+}
+
+/// Members of a trait in declaration order, each marked required or provided.
+pub fn trait_members(doc: &RustdocJson, trait_item: &Item) -> Vec<TraitMember> {
+    let ids = trait_item.inner_for("trait")
+        .and_then(|t| t.get("items"))
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+    ids.iter()
+        .filter_map(|id| doc.index.get(&id_to_string(id)?))
+        .filter_map(|member| {
+            let name = member.name.clone()?;
+            let doc_summary = member.doc_summary();
+            if let Some(f) = member.inner_for("function") {
+                let required = !f.get("has_body").and_then(|v| v.as_bool()).unwrap_or(false);
+                let declaration = function_signature(member);
+                return Some(TraitMember { name, kind: "method", declaration, required, bounds: None, doc_summary });
+            }
+            if let Some(at) = member.inner_for("assoc_type") {
+                let bounds = at.get("bounds")
+                    .and_then(|v| v.as_array())
+                    .map(|bs| bounds_to_string(bs))
+                    .filter(|s| !s.is_empty());
+                let default = at.get("type").filter(|v| !v.is_null()).map(type_to_string);
+                let mut declaration = format!("type {name}");
+                if let Some(b) = &bounds { declaration.push_str(&format!(": {b}")); }
+                if let Some(d) = &default { declaration.push_str(&format!(" = {d}")); }
+                declaration.push(';');
+                return Some(TraitMember {
+                    name, kind: "assoc_type", declaration, required: default.is_none(), bounds, doc_summary,
+                });
+            }
+            let c = const_info(member).filter(|_| member.inner_for("assoc_const").is_some())?;
+            Some(TraitMember {
+                declaration: c.declaration(&name),
+                name,
+                kind: "assoc_const",
+                required: c.value.is_none(),
+                bounds: None,
+                doc_summary,
+            })
+        })
+        .collect()
+}
+
+/// Render `impl<..> Trait<..> for Type<..> { .. }` with a stub for each of `members`.
+///
+/// `self_generics` are the implementing type's generic params; they are declared on the
+/// impl alongside the trait's. Methods and associated consts get `todo!()`; associated
+/// types get a `/* … */` placeholder naming the bounds, to be replaced before it compiles.
+pub fn impl_skeleton(
+    trait_path: &str,
+    trait_item: &Item,
+    self_ty: &str,
+    self_generics: Option<&Value>,
+    members: &[&TraitMember],
+) -> String {
+    let trait_generics = trait_item.inner_for("trait").and_then(|t| t.get("generics"));
+    let generics = merge_generics(&[self_generics, trait_generics]);
+
+    let mut out = format!(
+        "impl{} {trait_path}{} for {self_ty}{}{}",
+        format_generics(Some(&generics)),
+        generic_args(trait_generics),
+        generic_args(self_generics),
+        format_where(Some(&generics)),
+    );
+    out.push_str(if out.contains('\n') { "\n{\n" } else { " {\n" });
+
+    let mut first = true;
+    for member in members {
+        if !first { out.push('\n'); }
+        first = false;
+        let stub = match member.kind {
+            "assoc_type" => assoc_type_stub(&member.name, member.bounds.as_deref()),
+            "assoc_const" => format!("{} = todo!();", member.declaration.trim_end_matches(';')),
+            _ => {
+                let brace = if member.declaration.contains('\n') { "\n{" } else { " {" };
+                format!("{}{brace}\n    todo!()\n}}", member.declaration)
+            }
+        };
+        for line in stub.lines() {
+            out.push_str("    ");
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out.push('}');
+    out
+}
+
+/// `type Name = /* … */;`, the comment naming the bounds the chosen type must meet. No
+/// type is guessed: a wrong guess compiles or fails far from the real decision.
+fn assoc_type_stub(name: &str, bounds: Option<&str>) -> String {
+    match bounds {
+        Some(bounds) => format!("type {name} = /* a type implementing {bounds} */;"),
+        None => format!("type {name} = /* choose a type */;"),
+    }
+}
+
+/// Concatenate the params and where predicates of several `generics` objects. A param
+/// several of them declare (the type's `T` and the trait's `T`) is declared once, with
+/// the bounds of each.
+fn merge_generics(parts: &[Option<&Value>]) -> Value {
+    let collect = |key: &str| -> Vec<Value> {
+        parts.iter()
+            .flatten()
+            .filter_map(|g| g.get(key).and_then(|v| v.as_array()))
+            .flatten()
+            .cloned()
+            .collect()
+    };
+    let mut params: Vec<Value> = vec![];
+    for param in collect("params") {
+        match params.iter_mut().find(|p| p.get("name") == param.get("name")) {
+            Some(declared) => merge_param_bounds(declared, &param),
+            None => params.push(param),
+        }
+    }
+    json!({ "params": params, "where_predicates": collect("where_predicates") })
+}
+
+/// Add the bounds of `from` (trait bounds, or outlived lifetimes) missing from `into`.
+fn merge_param_bounds(into: &mut Value, from: &Value) {
+    for pointer in ["/kind/type/bounds", "/kind/lifetime/outlives"] {
+        let Some(extra) = from.pointer(pointer).and_then(|v| v.as_array()) else { continue };
+        if let Some(Value::Array(bounds)) = into.pointer_mut(pointer) {
+            for bound in extra {
+                if !bounds.contains(bound) {
+                    bounds.push(bound.clone());
+                }
+            }
+        }
+    }
+}
+
+/// Generic params as arguments, e.g. `<'a, T, N>`, or empty when there are none.
+fn generic_args(generics: Option<&Value>) -> String {
+    let names: Vec<&str> = generics
+        .and_then(|g| g.get("params"))
+        .and_then(|p| p.as_array())
+        .map(|ps| ps.iter()
+            .filter_map(|p| p.get("name")?.as_str())
+            .filter(|n| !n.starts_with("impl "))
+            .collect())
+        .unwrap_or_default();
+    if names.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", names.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_doc() -> RustdocJson {
+        let no_generics = json!({"params": [], "where_predicates": []});
+        let sig = |inputs: Value, output: Value| json!({"inputs": inputs, "output": output, "is_c_variadic": false});
+        let header = json!({"is_const": false, "is_unsafe": false, "is_async": false, "abi": "Rust"});
+        serde_json::from_value(json!({
+            "format_version": 57,
+            "root": 0,
+            "index": {
+                // trait Codec<T> { type Error: Display; fn encode(&self, value: T) -> Vec<u8>; fn name(&self) -> String { .. } }
+                "1": {"id": 1, "name": "Codec", "inner": {"trait": {
+                    "generics": {"params": [{"name": "T", "kind": {"type": {"bounds": [], "default": null, "is_synthetic": false}}}], "where_predicates": []},
+                    "items": [2, 3, 4], "bounds": [], "implementations": [], "is_auto": false, "is_unsafe": false, "is_dyn_compatible": true
+                }}},
+                "2": {"id": 2, "name": "Error", "inner": {"assoc_type": {
+                    "generics": no_generics.clone(), "type": null,
+                    "bounds": [{"trait_bound": {"trait": {"path": "Display", "id": 90, "args": null}, "generic_params": [], "modifier": "none"}}]
+                }}},
+                "3": {"id": 3, "name": "encode", "inner": {"function": {
+                    "generics": no_generics.clone(), "header": header.clone(), "has_body": false,
+                    "sig": sig(json!([["self", {"borrowed_ref": {"lifetime": null, "is_mutable": false, "type": {"generic": "Self"}}}], ["value", {"generic": "T"}]]),
+                               json!({"resolved_path": {"path": "Vec", "id": 91, "args": {"angle_bracketed": {"args": [{"type": {"primitive": "u8"}}], "constraints": []}}}}))
+                }}},
+                "4": {"id": 4, "name": "name", "inner": {"function": {
+                    "generics": no_generics.clone(), "header": header.clone(), "has_body": true,
+                    "sig": sig(json!([["self", {"borrowed_ref": {"lifetime": null, "is_mutable": false, "type": {"generic": "Self"}}}]]),
+                               json!({"resolved_path": {"path": "String", "id": 92, "args": null}}))
+                }}},
+                "5": {"id": 5, "name": "Widget", "inner": {"struct": {
+                    "generics": no_generics.clone(), "kind": {"unit": null}, "impls": []
+                }}}
+            },
+            "paths": {
+                "1": {"crate_id": 0, "path": ["demo", "Codec"], "kind": "trait"},
+                "5": {"crate_id": 0, "path": ["demo", "Widget"], "kind": "struct"}
+            }
+        })).unwrap()
+    }
+
+    #[test]
+    fn trait_members_mark_required_and_provided() {
+        let doc = make_doc();
+        let members = trait_members(&doc, doc.index.get("1").unwrap());
+        let summary: Vec<(&str, bool)> = members.iter().map(|m| (m.name.as_str(), m.required)).collect();
+        assert_eq!(summary, vec![("Error", true), ("encode", true), ("name", false)]);
+        assert_eq!(members[0].declaration, "type Error: Display;");
+    }
+
+    #[test]
+    fn skeleton_stubs_required_members() {
+        let doc = make_doc();
+        let trait_item = doc.index.get("1").unwrap();
+        let members = trait_members(&doc, trait_item);
+        let required: Vec<&TraitMember> = members.iter().filter(|m| m.required).collect();
+        let skeleton = impl_skeleton("demo::Codec", trait_item, "Widget", None, &required);
+        assert_eq!(skeleton, "impl<T> demo::Codec<T> for Widget {\n    \
+                              type Error = /* a type implementing Display */;\n\n    \
+                              fn encode(&self, value: T) -> Vec<u8> {\n        \
+                              todo!()\n    \
+                              }\n\
+                              }");
+    }
+
+    #[test]
+    fn skeleton_declares_a_shared_param_once() {
+        let doc = make_doc();
+        let trait_item = doc.index.get("1").unwrap();
+        let self_generics = json!({"params": [{"name": "T", "kind": {"type": {
+            "bounds": [{"trait_bound": {"trait": {"path": "Clone", "id": 93, "args": null}, "generic_params": [], "modifier": "none"}}],
+            "default": null, "is_synthetic": false
+        }}}], "where_predicates": []});
+        let skeleton = impl_skeleton("demo::Codec", trait_item, "Widget", Some(&self_generics), &[]);
+        assert_eq!(skeleton, "impl<T: Clone> demo::Codec<T> for Widget<T> {\n}");
+    }
+}
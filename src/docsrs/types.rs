@@ -1,9 +1,15 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::{Arc, OnceLock};
 
+use serde::de::{Deserializer, IgnoredAny, MapAccess, Visitor};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Value};
 
-/// Top-level rustdoc JSON document (format version 57).
+use super::format::MIN_FULL_FORMAT_VERSION;
+use super::summary::SummaryPolicy;
+
+/// Top-level rustdoc JSON document, in format 57 shapes once `format::normalize` has run.
 #[derive(Debug, Deserialize, Serialize)]
 pub struct RustdocJson {
     pub format_version: u32,
@@ -18,19 +24,263 @@ pub struct RustdocJson {
     pub external_crates: HashMap<String, ExternalCrate>,
     /// Crate name
     pub crate_version: Option<String>,
+    /// How much of each item was parsed
+    #[serde(skip)]
+    pub detail: DocDetail,
+    /// Length of the JSON text this was parsed from, 0 if unknown
+    #[serde(skip)]
+    pub source_len: usize,
+    /// Lookup tables derived from `index` and `paths`, built on first use
+    #[serde(skip)]
+    lookups: DocLookups,
+}
+
+/// How much of each item a rustdoc document is parsed with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DocDetail {
+    /// Every field of every item.
+    #[default]
+    Full,
+    /// What module trees and module docs need: names, docs, attrs and visibility of every
+    /// item, `inner` of modules and `use` items only, and `links` of modules only. Spans
+    /// are skipped, and other items keep just their kind, so signatures, fields and impls
+    /// are absent.
+    Outline,
+}
+
+/// Lazily-built auxiliary indexes over one document.
+#[derive(Debug, Default)]
+struct DocLookups {
+    method_parents: OnceLock<HashMap<String, String>>,
+    by_path: OnceLock<HashMap<String, String>>,
+    by_name: OnceLock<HashMap<String, Vec<String>>>,
+    by_last_segment: OnceLock<HashMap<String, Vec<String>>>,
+    unknown_kinds: OnceLock<Vec<(String, usize)>>,
+    full_text: OnceLock<super::full_text::DocsIndex>,
+}
+
+/// An item ID as the string keying `index` and `paths`. Format 57 writes IDs as
+/// integers and older formats as strings; any other value is no ID.
+pub fn id_to_string(id: &Value) -> Option<String> {
+    match id {
+        Value::Number(n) => Some(n.to_string()),
+        Value::String(s) => Some(s.clone()),
+        _ => None,
+    }
 }
 
 impl RustdocJson {
     /// Get the root ID as a string (handles both integer and string JSON representations).
     pub fn root_id(&self) -> String {
-        match &self.root {
-            serde_json::Value::Number(n) => n.to_string(),
-            serde_json::Value::String(s) => s.clone(),
-            other => other.to_string(),
+        id_to_string(&self.root).unwrap_or_else(|| self.root.to_string())
+    }
+
+    /// Rough number of bytes this document holds in memory, for sizing caches of parsed
+    /// documents. Parsed JSON takes a few times its text size; without the text size the
+    /// item count stands in. A full-text index, once built, adds its own size.
+    pub fn estimated_size(&self) -> usize {
+        const PARSED_BYTES_PER_JSON_BYTE: usize = 3;
+        const BYTES_PER_ITEM: usize = 2048;
+        let parsed = match self.source_len {
+            0 => self.index.len().saturating_mul(BYTES_PER_ITEM),
+            len => len.saturating_mul(PARSED_BYTES_PER_JSON_BYTE),
+        };
+        let full_text = self.lookups.full_text.get().map_or(0, super::full_text::DocsIndex::estimated_size);
+        parsed.saturating_add(full_text)
+    }
+
+    /// Inherent method ID → parent type's full path (see `build_method_parent_map`).
+    pub fn method_parents(&self) -> &HashMap<String, String> {
+        self.lookups.method_parents.get_or_init(|| super::parser::build_method_parent_map(self))
+    }
+
+    /// Full-text index over every visible item's complete docs, built on the blocking
+    /// pool on first use and kept with the document.
+    pub async fn full_text(self: &Arc<Self>) -> crate::error::Result<&super::full_text::DocsIndex> {
+        if self.lookups.full_text.get().is_none() {
+            let doc = self.clone();
+            tokio::task::spawn_blocking(move || {
+                doc.lookups.full_text.get_or_init(|| super::full_text::DocsIndex::build(&doc));
+            })
+            .await
+            .map_err(|e| crate::error::DocsError::Other(format!("full-text index task failed: {e}")))?;
+        }
+        Ok(self.lookups.full_text.get().expect("built above"))
+    }
+
+    /// ID of the item whose `paths` entry is exactly `path` (e.g. `serde::de::Deserialize`).
+    pub fn id_for_path(&self, path: &str) -> Option<&str> {
+        let by_path = self.lookups.by_path.get_or_init(|| {
+            self.paths.iter().map(|(id, p)| (p.full_path(), id.clone())).collect()
+        });
+        by_path.get(path).map(String::as_str)
+    }
+
+    /// IDs of every indexed item named `name`, in ascending ID order.
+    pub fn ids_named(&self, name: &str) -> &[String] {
+        let by_name = self.lookups.by_name.get_or_init(|| {
+            let mut map: HashMap<String, Vec<String>> = HashMap::new();
+            for (id, item) in &self.index {
+                if let Some(n) = &item.name {
+                    map.entry(n.clone()).or_default().push(id.clone());
+                }
+            }
+            for ids in map.values_mut() {
+                ids.sort_by_key(|id| id.parse::<u64>().unwrap_or(u64::MAX));
+            }
+            map
+        });
+        by_name.get(name).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Item kinds in this document that [`ItemKind`] does not know, with their counts.
+    pub fn unknown_kinds(&self) -> &[(String, usize)] {
+        self.lookups.unknown_kinds.get_or_init(|| {
+            let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+            for item in self.index.values() {
+                if let ItemKind::Unknown(kind) = item.item_kind() {
+                    *counts.entry(kind).or_default() += 1;
+                }
+            }
+            counts.into_iter().collect()
+        })
+    }
+
+    /// Warnings about parts of this document that tools cannot render faithfully.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings: Vec<String> = vec![];
+        if self.format_version < MIN_FULL_FORMAT_VERSION {
+            warnings.push(format!(
+                "This docs.rs build uses rustdoc JSON format {}, older than {MIN_FULL_FORMAT_VERSION}: \
+                 names, paths and docs are reliable, but signatures, generics and impls may be incomplete",
+                self.format_version,
+            ));
+        }
+        warnings.extend(self.unknown_kinds().iter()
+            .map(|(kind, count)| format!(
+                "{count} item(s) of unrecognized kind '{kind}' (rustdoc format {}) are listed but not fully rendered",
+                self.format_version,
+            )));
+        warnings
+    }
+
+    /// IDs of every `paths` entry whose last segment is `segment`, in ascending ID order.
+    ///
+    /// Narrows the re-export fallback in `find_item_id` to a handful of candidates.
+    pub fn ids_with_last_segment(&self, segment: &str) -> &[String] {
+        let by_last = self.lookups.by_last_segment.get_or_init(|| {
+            let mut map: HashMap<String, Vec<String>> = HashMap::new();
+            for (id, p) in &self.paths {
+                if let Some(last) = p.path.last() {
+                    map.entry(last.clone()).or_default().push(id.clone());
+                }
+            }
+            for ids in map.values_mut() {
+                ids.sort_by_key(|id| id.parse::<u64>().unwrap_or(u64::MAX));
+            }
+            map
+        });
+        by_last.get(segment).map(Vec::as_slice).unwrap_or_default()
+    }
+}
+
+/// A [`RustdocJson`] deserialized at [`DocDetail::Outline`]; convert with `into()`.
+#[derive(Debug, Deserialize)]
+pub struct OutlineJson {
+    format_version: u32,
+    root: Value,
+    index: HashMap<String, OutlineItem>,
+    paths: HashMap<String, PathEntry>,
+    #[serde(default)]
+    external_crates: HashMap<String, ExternalCrate>,
+    crate_version: Option<String>,
+}
+
+/// An [`Item`] without its span, whose `links` are dropped unless it is a module.
+#[derive(Debug, Deserialize)]
+struct OutlineItem {
+    id: Value,
+    name: Option<String>,
+    docs: Option<String>,
+    #[serde(default)]
+    attrs: Vec<Value>,
+    deprecation: Option<Deprecation>,
+    #[serde(deserialize_with = "outline_inner")]
+    inner: Value,
+    visibility: Option<Value>,
+    links: Option<HashMap<String, Value>>,
+    #[serde(default, rename = "kind")]
+    legacy_kind: Option<String>,
+}
+
+impl From<OutlineJson> for RustdocJson {
+    fn from(outline: OutlineJson) -> Self {
+        let index = outline.index.into_iter().map(|(id, item)| {
+            let is_module = item.inner.get("module").is_some() || item.legacy_kind.as_deref() == Some("module");
+            let item = Item {
+                id: item.id,
+                name: item.name,
+                docs: item.docs,
+                attrs: item.attrs,
+                deprecation: item.deprecation,
+                inner: item.inner,
+                span: None,
+                visibility: item.visibility,
+                links: item.links.filter(|_| is_module),
+                legacy_kind: item.legacy_kind,
+            };
+            (id, item)
+        }).collect();
+        RustdocJson {
+            format_version: outline.format_version,
+            root: outline.root,
+            index,
+            paths: outline.paths,
+            external_crates: outline.external_crates,
+            crate_version: outline.crate_version,
+            detail: DocDetail::Outline,
+            source_len: 0,
+            lookups: DocLookups::default(),
         }
     }
 }
 
+/// An item's `inner`, skipping the contents of every kind but modules and `use` items.
+///
+/// Keys that are not kinds (the untagged `inner` of old formats) are kept whole.
+fn outline_inner<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Value, D::Error> {
+    struct InnerVisitor;
+
+    impl<'de> Visitor<'de> for InnerVisitor {
+        type Value = Value;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("an item's inner object")
+        }
+
+        fn visit_unit<E>(self) -> Result<Value, E> {
+            Ok(Value::Null)
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+            let mut inner = Map::new();
+            while let Some(key) = map.next_key::<String>()? {
+                let kind = ItemKind::from_key(&key);
+                let value = if kind.is_known() && !matches!(kind, ItemKind::Module | ItemKind::Use) {
+                    map.next_value::<IgnoredAny>()?;
+                    Value::Null
+                } else {
+                    map.next_value()?
+                };
+                inner.insert(key, value);
+            }
+            Ok(Value::Object(inner))
+        }
+    }
+
+    deserializer.deserialize_map(InnerVisitor)
+}
+
 /// A path entry describing an item's location in the module tree.
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct PathEntry {
@@ -40,6 +290,9 @@ pub struct PathEntry {
     pub path: Vec<String>,
     /// Brief summary (first line of docs)
     pub summary: Option<String>,
+    /// Index into `external_crates`; 0 is the crate being documented
+    #[serde(default)]
+    pub crate_id: u32,
 }
 
 impl PathEntry {
@@ -72,12 +325,107 @@ pub struct Item {
     pub span: Option<Span>,
     pub visibility: Option<Value>,
     pub links: Option<HashMap<String, serde_json::Value>>,
+    /// Separate kind field of formats before `inner` was tagged; see `format::normalize`
+    #[serde(default, rename = "kind", skip_serializing)]
+    pub(crate) legacy_kind: Option<String>,
+}
+
+/// Item kinds this server understands, as keys of an item's `inner` object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ItemKind {
+    Module,
+    ExternCrate,
+    Use,
+    Union,
+    Struct,
+    StructField,
+    Enum,
+    Variant,
+    Function,
+    TraitAlias,
+    Trait,
+    Impl,
+    TypeAlias,
+    Constant,
+    Static,
+    ExternType,
+    Macro,
+    ProcMacro,
+    Primitive,
+    AssocConst,
+    AssocType,
+    /// A kind from a newer rustdoc, kept by name so it can be reported
+    Unknown(String),
+}
+
+impl ItemKind {
+    const KNOWN: [(&'static str, ItemKind); 21] = [
+        ("module", ItemKind::Module),
+        ("extern_crate", ItemKind::ExternCrate),
+        ("use", ItemKind::Use),
+        ("union", ItemKind::Union),
+        ("struct", ItemKind::Struct),
+        ("struct_field", ItemKind::StructField),
+        ("enum", ItemKind::Enum),
+        ("variant", ItemKind::Variant),
+        ("function", ItemKind::Function),
+        ("trait_alias", ItemKind::TraitAlias),
+        ("trait", ItemKind::Trait),
+        ("impl", ItemKind::Impl),
+        ("type_alias", ItemKind::TypeAlias),
+        ("constant", ItemKind::Constant),
+        ("static", ItemKind::Static),
+        ("extern_type", ItemKind::ExternType),
+        ("macro", ItemKind::Macro),
+        ("proc_macro", ItemKind::ProcMacro),
+        ("primitive", ItemKind::Primitive),
+        ("assoc_const", ItemKind::AssocConst),
+        ("assoc_type", ItemKind::AssocType),
+    ];
+
+    pub fn from_key(key: &str) -> Self {
+        Self::KNOWN.iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, kind)| kind.clone())
+            .unwrap_or_else(|| ItemKind::Unknown(key.to_string()))
+    }
+
+    /// The `inner` key for this kind, e.g. `"assoc_type"`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            ItemKind::Unknown(key) => key,
+            known => Self::KNOWN.iter()
+                .find(|(_, kind)| kind == known)
+                .map(|(k, _)| *k)
+                .unwrap_or("unknown"),
+        }
+    }
+
+    pub fn is_known(&self) -> bool {
+        !matches!(self, ItemKind::Unknown(_))
+    }
 }
 
 impl Item {
     /// Returns the kind string from `inner`, e.g. "function", "struct", "module".
+    ///
+    /// Prefers a known kind key when `inner` has several, so extra keys added by a newer
+    /// rustdoc cannot shadow the real kind. Falls back to the first key.
     pub fn kind(&self) -> Option<&str> {
-        self.inner.as_object()?.keys().next().map(|s| s.as_str())
+        let keys = self.inner.as_object()?.keys();
+        let mut first = None;
+        for key in keys {
+            if ItemKind::from_key(key).is_known() {
+                return Some(key);
+            }
+            first.get_or_insert(key.as_str());
+        }
+        first
+    }
+
+    /// Typed kind of the item; `Unknown` for kinds missing from [`ItemKind`].
+    pub fn item_kind(&self) -> ItemKind {
+        ItemKind::from_key(self.kind().unwrap_or("unknown"))
     }
 
     /// Returns `inner[kind]` for a given kind string.
@@ -93,15 +441,29 @@ impl Item {
         }).collect()
     }
 
-    /// Doc summary: first non-empty line of the doc comment.
+    /// True for `pub` items and for items whose visibility is implied by their parent
+    /// (`"default"`: trait members, enum variants). False for `pub(crate)`/`pub(in ..)`.
+    pub fn is_public(&self) -> bool {
+        match &self.visibility {
+            None => true,
+            Some(Value::String(v)) => v == "public" || v == "default",
+            Some(_) => false,
+        }
+    }
+
+    /// True if the item carries `#[doc(hidden)]`.
+    pub fn is_doc_hidden(&self) -> bool {
+        self.attr_strings().iter().any(|a| a.contains("doc(hidden)"))
+    }
+
+    /// Public and not `#[doc(hidden)]` — what a downstream user can rely on.
+    pub fn is_visible(&self) -> bool {
+        self.is_public() && !self.is_doc_hidden()
+    }
+
+    /// Doc summary: the opening sentence(s) of the doc comment, per [`SummaryPolicy::global`].
     pub fn doc_summary(&self) -> String {
-        self.docs
-            .as_deref()
-            .unwrap_or("")
-            .lines()
-            .find(|l| !l.trim().is_empty())
-            .unwrap_or("")
-            .to_string()
+        SummaryPolicy::global().summarize(self.docs.as_deref().unwrap_or(""))
     }
 }
 
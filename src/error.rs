@@ -1,3 +1,6 @@
+use reqwest::StatusCode;
+use serde::Serialize;
+use serde_json::{json, Value};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -17,23 +20,166 @@ pub enum DocsError {
     #[error("Crate not found: {0}")]
     CrateNotFound(String),
 
+    #[error("Version '{version}' of {name} not found in the crates.io index. \
+             Use crate_versions_list to see published versions.")]
+    VersionNotFound { name: String, version: String },
+
     #[error("Docs.rs build not found for {name} {version}")]
     DocsNotFound { name: String, version: String },
 
+    #[error("The rustdoc JSON of {name} {version} is {size_mb} MiB, over the {limit_mb} MiB limit for loading it whole. \
+             crate_docs_get still reads its module tree and docs; raise DOCS_MCP_MAX_DOC_MB (0 for no limit) to load the rest")]
+    DocsTooLarge { name: String, version: String, size_mb: usize, limit_mb: usize },
+
+    #[error("The {name} {version} package archive does not match the crates.io index checksum \
+             (expected SHA-256 {expected}, got {actual}); it was not extracted")]
+    ChecksumMismatch { name: String, version: String, expected: String, actual: String },
+
     #[error("No stable version found for {0}")]
     NoStableVersion(String),
 
     #[error("Semver error: {0}")]
     Semver(#[from] semver::Error),
 
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error("HTTP {status} for {url}")]
+    Status { status: StatusCode, url: String },
+
+    /// An argument that cannot name anything upstream, such as a malformed crate name
+    #[error("{0}")]
+    InvalidParams(String),
+
     #[error("{0}")]
     Other(String),
 }
 
+/// Machine-readable failure kind, sent as `data.code` on every tool error so agents can
+/// branch on it instead of parsing the message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    CrateNotFound,
+    VersionNotFound,
+    DocsBuildMissing,
+    /// The crate's rustdoc JSON is over the size limit for a full parse
+    DocsTooLarge,
+    /// A downloaded `.crate` archive does not match the sparse index checksum
+    ChecksumMismatch,
+    NoStableVersion,
+    /// An item, module, type or trait path that isn't in the crate's docs
+    ItemNotFound,
+    FileNotFound,
+    /// Any other upstream 404
+    NotFound,
+    RateLimited,
+    /// The upstream host could not be reached
+    Offline,
+    Timeout,
+    /// An upstream error status or a response that could not be read
+    UpstreamError,
+    InvalidParams,
+    Internal,
+}
+
+impl ErrorCode {
+    /// Whether the same call may succeed if retried later.
+    pub fn retryable(self) -> bool {
+        matches!(self, Self::RateLimited | Self::Offline | Self::Timeout | Self::UpstreamError)
+    }
+
+    /// The `data` object for an `ErrorData` carrying this code.
+    pub fn data(self) -> Option<Value> {
+        Some(json!({"code": self, "retryable": self.retryable()}))
+    }
+}
+
+impl DocsError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::Http(e) | Self::Middleware(reqwest_middleware::Error::Reqwest(e)) => reqwest_code(e),
+            Self::Middleware(reqwest_middleware::Error::Middleware(_)) => ErrorCode::Internal,
+            Self::Json(_) => ErrorCode::UpstreamError,
+            Self::CrateNotFound(_) => ErrorCode::CrateNotFound,
+            Self::VersionNotFound { .. } => ErrorCode::VersionNotFound,
+            Self::DocsNotFound { .. } => ErrorCode::DocsBuildMissing,
+            Self::DocsTooLarge { .. } => ErrorCode::DocsTooLarge,
+            Self::ChecksumMismatch { .. } => ErrorCode::ChecksumMismatch,
+            Self::NoStableVersion(_) => ErrorCode::NoStableVersion,
+            Self::Semver(_) | Self::InvalidParams(_) => ErrorCode::InvalidParams,
+            Self::Status { status, .. } => status_code(*status),
+            Self::Io(_) | Self::Sqlite(_) | Self::Other(_) => ErrorCode::Internal,
+        }
+    }
+
+    /// Whether this is a 404 from upstream.
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Self::Status { status: StatusCode::NOT_FOUND, .. })
+    }
+}
+
+fn reqwest_code(e: &reqwest::Error) -> ErrorCode {
+    if e.is_timeout() {
+        ErrorCode::Timeout
+    } else if e.is_connect() {
+        ErrorCode::Offline
+    } else {
+        e.status().map_or(ErrorCode::UpstreamError, status_code)
+    }
+}
+
+fn status_code(status: StatusCode) -> ErrorCode {
+    match status {
+        StatusCode::NOT_FOUND => ErrorCode::NotFound,
+        StatusCode::TOO_MANY_REQUESTS => ErrorCode::RateLimited,
+        _ => ErrorCode::UpstreamError,
+    }
+}
+
 impl From<DocsError> for rmcp::ErrorData {
     fn from(e: DocsError) -> Self {
-        rmcp::ErrorData::internal_error(e.to_string(), None)
+        let code = e.code();
+        match code {
+            ErrorCode::InvalidParams | ErrorCode::VersionNotFound => rmcp::ErrorData::invalid_params(e.to_string(), code.data()),
+            _ => rmcp::ErrorData::internal_error(e.to_string(), code.data()),
+        }
     }
 }
 
 pub type Result<T> = std::result::Result<T, DocsError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_errors_map_to_codes() {
+        let status = |status| DocsError::Status { status, url: "https://crates.io/api/v1/crates/x".to_string() };
+        assert_eq!(status(StatusCode::TOO_MANY_REQUESTS).code(), ErrorCode::RateLimited);
+        assert_eq!(status(StatusCode::BAD_GATEWAY).code(), ErrorCode::UpstreamError);
+        assert_eq!(status(StatusCode::NOT_FOUND).code(), ErrorCode::NotFound);
+        assert_eq!(status(StatusCode::NOT_FOUND).to_string(), "HTTP 404 Not Found for https://crates.io/api/v1/crates/x");
+    }
+
+    #[test]
+    fn error_data_carries_code_and_retryable() {
+        let data = rmcp::ErrorData::from(DocsError::CrateNotFound("nope".to_string())).data.unwrap();
+        assert_eq!(data, json!({"code": "CRATE_NOT_FOUND", "retryable": false}));
+        let data = ErrorCode::RateLimited.data().unwrap();
+        assert_eq!(data["retryable"], true);
+        let missing = rmcp::ErrorData::from(DocsError::VersionNotFound { name: "serde".to_string(), version: "9.9.9".to_string() });
+        assert_eq!(missing.code, rmcp::model::ErrorCode::INVALID_PARAMS);
+        assert_eq!(missing.data.unwrap(), json!({"code": "VERSION_NOT_FOUND", "retryable": false}));
+    }
+
+    #[test]
+    fn oversized_docs_name_the_limit_and_the_way_around_it() {
+        let e = DocsError::DocsTooLarge { name: "windows".to_string(), version: "0.61.1".to_string(), size_mb: 900, limit_mb: 256 };
+        assert_eq!(e.code(), ErrorCode::DocsTooLarge);
+        assert!(!e.code().retryable());
+        let message = e.to_string();
+        assert!(message.starts_with("The rustdoc JSON of windows 0.61.1 is 900 MiB, over the 256 MiB limit"), "{message}");
+        assert!(message.contains("crate_docs_get") && message.contains("DOCS_MCP_MAX_DOC_MB"), "{message}");
+    }
+}
@@ -0,0 +1,175 @@
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use http::{Extensions, StatusCode};
+use reqwest::{Method, Request, Url};
+use reqwest_middleware::{Middleware, Next};
+
+/// Env var naming the fixture directory when `--fixture-dir` isn't given.
+pub const FIXTURE_DIR_ENV: &str = "DOCS_MCP_FIXTURE_DIR";
+
+/// Directory holding the fixtures: [`FIXTURE_DIR_ENV`] if set, else `tests/fixtures`
+/// under the current directory, which is right when running from a source checkout.
+///
+/// Resolved at run time, so an installed binary works once pointed at a copy.
+pub fn default_fixture_dir() -> PathBuf {
+    std::env::var_os(FIXTURE_DIR_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new("tests").join("fixtures"))
+}
+
+/// Answers every outbound request from files on disk instead of the network.
+///
+/// Layout under the fixture directory:
+/// - `{name}_{version}.json` — rustdoc JSON served for `docs.rs/crate/{name}/{version}/json`
+///   (`{name}_{version}_{target}.json` for a `/{target}/json` build)
+/// - `index/{name}` — sparse index NDJSON for `index.crates.io`
+/// - `cratesio/{path}.json` or `.html` — crates.io API responses for `/api/v1/{path}`
+/// - `crates/{name}-{version}.crate` — package archives from `static.crates.io`
+/// - `depsdev/{path}.json` — deps.dev responses for `/v3/{path}`, with `:` written as `_`
+///   and encoded slashes of project keys as directories
+/// - `rust-lang/channel-rust-{channel}.toml` — release channel manifests from
+///   `static.rust-lang.org/dist`
+///
+/// Anything without a matching file gets a 404, exactly like an unknown crate online.
+pub struct FixtureMiddleware {
+    dir: PathBuf,
+}
+
+impl FixtureMiddleware {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+}
+
+#[async_trait]
+impl Middleware for FixtureMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        _extensions: &mut Extensions,
+        _next: Next<'_>,
+    ) -> reqwest_middleware::Result<reqwest::Response> {
+        let Some(fixture) = fixture_path(&self.dir, req.url()) else {
+            return Ok(respond(StatusCode::NOT_FOUND, vec![]));
+        };
+        if *req.method() == Method::HEAD {
+            return Ok(respond(StatusCode::OK, vec![]));
+        }
+
+        let body = std::fs::read(&fixture.path).map_err(reqwest_middleware::Error::middleware)?;
+        let body = if fixture.zstd {
+            // docs.rs serves rustdoc JSON zstd-compressed; mirror that so the normal decode path runs
+            zstd::encode_all(body.as_slice(), 3).map_err(reqwest_middleware::Error::middleware)?
+        } else {
+            body
+        };
+        Ok(respond(StatusCode::OK, body))
+    }
+}
+
+/// A fixture file matched to a request URL.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Fixture {
+    pub path: PathBuf,
+    /// Whether the body must be zstd-compressed before serving
+    pub zstd: bool,
+}
+
+/// Map a request URL to an existing fixture file, or `None` for a 404.
+pub fn fixture_path(dir: &Path, url: &Url) -> Option<Fixture> {
+    let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+    let (path, zstd) = match url.host_str()? {
+        "docs.rs" => match segments.as_slice() {
+            ["crate", name, version, "json"] => (dir.join(format!("{name}_{version}.json")), true),
+            ["crate", name, version, target, "json"] => (dir.join(format!("{name}_{version}_{target}.json")), true),
+            _ => return None,
+        },
+        "index.crates.io" => (dir.join("index").join(segments.last()?), false),
+        "static.crates.io" => match segments.as_slice() {
+            ["crates", _name, file] => (dir.join("crates").join(file), false),
+            _ => return None,
+        },
+        "static.rust-lang.org" => match segments.as_slice() {
+            ["dist", file] => (dir.join("rust-lang").join(file), false),
+            _ => return None,
+        },
+        "api.deps.dev" => {
+            let rest = segments.strip_prefix(&["v3"])?;
+            let path = rest.iter()
+                .flat_map(|s| s.split("%2F"))
+                .fold(dir.join("depsdev"), |p, s| p.join(s.replace(':', "_")));
+            let mut path = path.into_os_string();
+            path.push(".json");
+            (PathBuf::from(path), false)
+        }
+        "crates.io" => {
+            let rest = segments.strip_prefix(&["api", "v1"])?;
+            let base = rest.iter().fold(dir.join("cratesio"), |p, s| p.join(s));
+            // Append rather than `with_extension`, which would eat the ".0" of "0.16.0"
+            let path = ["json", "html"].iter()
+                .map(|ext| {
+                    let mut p = base.clone().into_os_string();
+                    p.push(format!(".{ext}"));
+                    PathBuf::from(p)
+                })
+                .find(|p| p.is_file())?;
+            (path, false)
+        }
+        _ => return None,
+    };
+    path.is_file().then_some(Fixture { path, zstd })
+}
+
+pub(crate) fn respond(status: StatusCode, body: Vec<u8>) -> reqwest::Response {
+    let resp = http::Response::builder()
+        .status(status)
+        .body(body)
+        .expect("status and body are always valid");
+    reqwest::Response::from(resp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn fixture_path_routes_each_host() {
+        let dir = default_fixture_dir();
+        let docs = fixture_path(&dir, &url("https://docs.rs/crate/rmcp/0.16.0/json")).unwrap();
+        assert_eq!(docs, Fixture { path: dir.join("rmcp_0.16.0.json"), zstd: true });
+
+        let index = fixture_path(&dir, &url("https://index.crates.io/cl/ap/clap")).unwrap();
+        assert_eq!(index.path, dir.join("index").join("clap"));
+
+        let krate = fixture_path(&dir, &url("https://crates.io/api/v1/crates/rmcp")).unwrap();
+        assert_eq!(krate.path, dir.join("cratesio").join("crates").join("rmcp.json"));
+
+        let search = fixture_path(&dir, &url("https://crates.io/api/v1/crates?q=mcp&page=1")).unwrap();
+        assert_eq!(search.path, dir.join("cratesio").join("crates.json"));
+
+        let archive = fixture_path(&dir, &url("https://static.crates.io/crates/rmcp/rmcp-0.16.0.crate")).unwrap();
+        assert_eq!(archive.path, dir.join("crates").join("rmcp-0.16.0.crate"));
+
+        let manifest = fixture_path(&dir, &url("https://static.rust-lang.org/dist/channel-rust-stable.toml")).unwrap();
+        assert_eq!(manifest.path, dir.join("rust-lang").join("channel-rust-stable.toml"));
+
+        let graph = fixture_path(&dir, &url("https://api.deps.dev/v3/systems/cargo/packages/rmcp/versions/0.16.0:dependencies")).unwrap();
+        assert_eq!(graph.path, dir.join("depsdev/systems/cargo/packages/rmcp/versions/0.16.0_dependencies.json"));
+        let project = fixture_path(&dir, &url("https://api.deps.dev/v3/projects/github.com%2Fmodelcontextprotocol%2Frust-sdk")).unwrap();
+        assert_eq!(project.path, dir.join("depsdev/projects/github.com/modelcontextprotocol/rust-sdk.json"));
+    }
+
+    #[test]
+    fn fixture_path_misses_are_none() {
+        let dir = default_fixture_dir();
+        assert!(fixture_path(&dir, &url("https://docs.rs/crate/rmcp/0.1.0/json")).is_none());
+        assert!(fixture_path(&dir, &url("https://docs.rs/crate/rmcp/0.16.0/wasm32-unknown-unknown/json")).is_none());
+        assert!(fixture_path(&dir, &url("https://index.crates.io/se/rd/serde")).is_none());
+        assert!(fixture_path(&dir, &url("https://example.com/anything")).is_none());
+    }
+}
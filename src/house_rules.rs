@@ -0,0 +1,94 @@
+//! Operator-supplied instructions ("house rules") sent to the client with the built-in
+//! server instructions, e.g. "always prefer crates we already depend on".
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::error::{DocsError, Result};
+
+/// Env var naming a text or markdown file of instructions (CLI: `--instructions-file`).
+pub const FILE_ENV: &str = "DOCS_MCP_INSTRUCTIONS_FILE";
+/// Env var choosing `append` (the default) or `replace` (CLI: `--instructions-mode`).
+pub const MODE_ENV: &str = "DOCS_MCP_INSTRUCTIONS_MODE";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    /// Follow the built-in instructions with the file's
+    Append,
+    /// Send only the file's instructions
+    Replace,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HouseRules {
+    /// Where the rules were read from; a path on the server, so never sent to clients
+    #[serde(skip)]
+    pub path: PathBuf,
+    pub mode: Mode,
+    #[serde(skip)]
+    pub text: String,
+}
+
+impl HouseRules {
+    /// Rules from `file` and `mode` (`--instructions-file` and `--instructions-mode`),
+    /// else [`FILE_ENV`] and [`MODE_ENV`]; `None` when no file is set.
+    ///
+    /// A file that was asked for but cannot be read is an error, so a typo in the path
+    /// does not silently drop the rules.
+    pub fn load(file: Option<&Path>, mode: Option<&str>) -> Result<Option<Self>> {
+        let env = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let from_env = env(FILE_ENV).map(PathBuf::from);
+        let Some(path) = file.map(Path::to_path_buf).or(from_env) else { return Ok(None) };
+        let mode = match mode.map(str::to_string).or_else(|| env(MODE_ENV)).as_deref().map(str::trim) {
+            None | Some("append") => Mode::Append,
+            Some("replace") => Mode::Replace,
+            Some(other) => return Err(DocsError::Other(format!(
+                "instructions mode must be \"append\" or \"replace\", not \"{other}\""
+            ))),
+        };
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| DocsError::Other(format!("cannot read instructions file {}: {e}", path.display())))?;
+        Ok(Some(Self { path, mode, text: text.trim().to_string() }))
+    }
+
+    /// The instructions to send, given the built-in ones.
+    pub fn apply(&self, built_in: String) -> String {
+        match self.mode {
+            Mode::Replace => self.text.clone(),
+            Mode::Append if self.text.is_empty() => built_in,
+            Mode::Append => format!("{built_in}\n\nHouse rules from the operator of this server:\n{}", self.text),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(mode: Mode, text: &str) -> HouseRules {
+        HouseRules { path: PathBuf::from("rules.md"), mode, text: text.to_string() }
+    }
+
+    #[test]
+    fn apply_appends_or_replaces() {
+        let rule = "- Never suggest yanked versions.";
+        let appended = rules(Mode::Append, rule).apply("Built in.".to_string());
+        assert!(appended.starts_with("Built in.") && appended.ends_with(rule));
+        assert_eq!(rules(Mode::Replace, rule).apply("Built in.".to_string()), rule);
+        assert_eq!(rules(Mode::Append, "").apply("Built in.".to_string()), "Built in.");
+    }
+
+    #[test]
+    fn load_reads_the_flagged_file() {
+        let path = std::env::temp_dir().join(format!("docs-mcp-house-rules-{}.md", std::process::id()));
+        std::fs::write(&path, "Prefer crates we already depend on.\n").unwrap();
+        let loaded = HouseRules::load(Some(&path), Some("replace")).unwrap().unwrap();
+        assert_eq!((loaded.mode, loaded.text.as_str()), (Mode::Replace, "Prefer crates we already depend on."));
+        assert!(HouseRules::load(Some(&path), Some("prepend")).is_err());
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(HouseRules::load(Some(&path), None).is_err());
+    }
+}
@@ -0,0 +1,648 @@
+//! HTML → Markdown conversion for rendered READMEs and other HTML sources.
+//!
+//! The input is parsed into an element tree first (with the usual implied end tags for
+//! `<p>`, `<li>`, `<tr>` and table cells), then rendered:
+//! - headings → `#` lines; `<ul>`/`<ol>` → `-` / `1.` items, nested lists indented
+//! - `<blockquote>` → `>`-prefixed lines
+//! - `<pre>` → fenced code block, tagged with the language from `lang` or `class="language-…"`
+//! - `<code>` → backticks; `<strong>`/`<em>`/`<del>` → `**` / `*` / `~~`
+//! - `<a href>` → `[text](url)`, with the URL mapped by the caller (e.g. to resolve relative links)
+//! - `<img alt>` → `[alt]`, so badges keep their label without their image URL
+//! - `<table>` → pipe-separated rows, with a `---` rule under a header row
+//! - `<script>`, `<style>` and comments are dropped; named and numeric entities are decoded
+
+/// Elements with no content and no end tag.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track", "wbr",
+];
+/// Elements whose content is raw text that is never shown.
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style"];
+/// Elements rendered as nothing at all.
+const HIDDEN_ELEMENTS: &[&str] = &["script", "style", "head", "template", "noscript"];
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address", "article", "aside", "blockquote", "center", "dd", "details", "div", "dl", "dt",
+    "figcaption", "figure", "footer", "h1", "h2", "h3", "h4", "h5", "h6", "header", "hr", "li",
+    "main", "nav", "ol", "p", "pre", "section", "summary", "table", "tbody", "tfoot", "thead",
+    "tr", "ul",
+];
+/// Block elements that end an open `<p>`.
+const CLOSES_PARAGRAPH: &[&str] = &[
+    "blockquote", "div", "dl", "h1", "h2", "h3", "h4", "h5", "h6", "hr", "ol", "p", "pre",
+    "table", "ul",
+];
+
+/// Convert `html` to Markdown. `map_link` receives each `href` and returns the URL to emit,
+/// or `None` to keep only the link text (e.g. for in-page anchors).
+pub fn to_markdown(html: &str, map_link: impl Fn(&str) -> Option<String>) -> String {
+    let root = parse(html);
+    let renderer = Renderer { map_link: &map_link };
+    normalize_blank_lines(&renderer.blocks(&root.children, "\n\n"))
+}
+
+// ─── Parsing ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Element(Element),
+    Text(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+struct Element {
+    name: String,
+    attrs: Vec<(String, String)>,
+    children: Vec<Node>,
+}
+
+impl Element {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+    }
+
+    fn child_elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|n| match n {
+            Node::Element(e) => Some(e),
+            Node::Text(_) => None,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Open { name: String, attrs: Vec<(String, String)>, self_closing: bool },
+    Close(String),
+    Text(String),
+}
+
+/// Split HTML into tags and decoded text. Comments, doctypes and the content of raw-text
+/// elements are skipped; a `<` that does not start a tag is kept as text.
+fn tokenize(html: &str) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut rest = html;
+    while !rest.is_empty() {
+        let Some(lt) = rest.find('<') else {
+            tokens.push(Token::Text(decode_entities(rest)));
+            break;
+        };
+        if lt > 0 {
+            tokens.push(Token::Text(decode_entities(&rest[..lt])));
+            rest = &rest[lt..];
+        }
+        if let Some(after) = rest.strip_prefix("<!--") {
+            rest = after.find("-->").map_or("", |i| &after[i + 3..]);
+            continue;
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest = rest.find('>').map_or("", |i| &rest[i + 1..]);
+            continue;
+        }
+        let Some((token, after)) = parse_tag(rest) else {
+            tokens.push(Token::Text("<".to_string()));
+            rest = &rest[1..];
+            continue;
+        };
+        rest = after;
+        if let Token::Open { name, self_closing: false, .. } = &token
+            && RAW_TEXT_ELEMENTS.contains(&name.as_str())
+        {
+            let close = format!("</{name}");
+            let end = rest.to_ascii_lowercase().find(&close);
+            rest = end.map_or("", |i| &rest[i..]);
+        }
+        tokens.push(token);
+    }
+    tokens
+}
+
+/// Parse one tag at the start of `s` (which begins with `<`), returning it and the rest.
+fn parse_tag(s: &str) -> Option<(Token, &str)> {
+    let bytes = s.as_bytes();
+    let closing = bytes.get(1) == Some(&b'/');
+    let name_start = if closing { 2 } else { 1 };
+    if !bytes.get(name_start)?.is_ascii_alphabetic() {
+        return None;
+    }
+    let name_end = name_start + s[name_start..]
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+        .unwrap_or(s.len() - name_start);
+    let name = s[name_start..name_end].to_ascii_lowercase();
+
+    // Attributes up to the closing `>`, which may not appear inside a quoted value
+    let mut attrs = vec![];
+    let mut i = name_end;
+    let mut self_closing = false;
+    loop {
+        while i < bytes.len() && (bytes[i].is_ascii_whitespace() || bytes[i] == b'/') {
+            self_closing = bytes[i] == b'/';
+            i += 1;
+        }
+        match bytes.get(i) {
+            None => return None,
+            Some(b'>') => break,
+            _ => {}
+        }
+        self_closing = false;
+        let attr_start = i;
+        while i < bytes.len() && !matches!(bytes[i], b'=' | b'>' | b'/') && !bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        let attr_name = s[attr_start..i].to_ascii_lowercase();
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        let mut value = String::new();
+        if bytes.get(i) == Some(&b'=') {
+            i += 1;
+            while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            match bytes.get(i) {
+                Some(&q @ (b'"' | b'\'')) => {
+                    let end = i + 1 + s[i + 1..].find(q as char)?;
+                    value = decode_entities(&s[i + 1..end]);
+                    i = end + 1;
+                }
+                _ => {
+                    let start = i;
+                    while i < bytes.len() && bytes[i] != b'>' && !bytes[i].is_ascii_whitespace() {
+                        i += 1;
+                    }
+                    value = decode_entities(&s[start..i]);
+                }
+            }
+        }
+        if !attr_name.is_empty() {
+            attrs.push((attr_name, value));
+        }
+    }
+
+    let rest = &s[i + 1..];
+    let token = if closing { Token::Close(name) } else { Token::Open { name, attrs, self_closing } };
+    Some((token, rest))
+}
+
+/// Build the element tree, closing elements the way browsers imply their end tags.
+fn parse(html: &str) -> Element {
+    let mut stack = vec![Element::default()];
+    for token in tokenize(html) {
+        match token {
+            Token::Text(text) => stack.last_mut().unwrap().children.push(Node::Text(text)),
+            Token::Open { name, attrs, self_closing } => {
+                close_implied(&mut stack, &name);
+                let element = Element { name, attrs, children: vec![] };
+                if self_closing || VOID_ELEMENTS.contains(&element.name.as_str()) {
+                    stack.last_mut().unwrap().children.push(Node::Element(element));
+                } else {
+                    stack.push(element);
+                }
+            }
+            Token::Close(name) => {
+                // Stray end tags are ignored
+                if let Some(pos) = stack.iter().skip(1).rposition(|e| e.name == name) {
+                    while stack.len() > pos + 1 {
+                        pop(&mut stack);
+                    }
+                }
+            }
+        }
+    }
+    while stack.len() > 1 {
+        pop(&mut stack);
+    }
+    stack.pop().unwrap()
+}
+
+fn pop(stack: &mut Vec<Element>) {
+    let element = stack.pop().unwrap();
+    stack.last_mut().unwrap().children.push(Node::Element(element));
+}
+
+/// Close elements that `name` implicitly ends: an open `<p>` before a block, a previous
+/// `<li>` in the same list, a previous row or cell in the same table.
+fn close_implied(stack: &mut Vec<Element>, name: &str) {
+    let (targets, boundaries): (&[&str], &[&str]) = match name {
+        "li" => (&["li"], &["ul", "ol"]),
+        "dt" | "dd" => (&["dt", "dd"], &["dl"]),
+        "tr" => (&["tr"], &["table"]),
+        "td" | "th" => (&["td", "th"], &["tr", "table"]),
+        "thead" | "tbody" | "tfoot" => (&["thead", "tbody", "tfoot"], &["table"]),
+        n if CLOSES_PARAGRAPH.contains(&n) => {
+            if stack.last().is_some_and(|e| e.name == "p") {
+                pop(stack);
+            }
+            return;
+        }
+        _ => return,
+    };
+    let open = stack.iter().skip(1).rposition(|e| {
+        targets.contains(&e.name.as_str()) || boundaries.contains(&e.name.as_str())
+    });
+    if let Some(pos) = open.map(|p| p + 1)
+        && targets.contains(&stack[pos].name.as_str())
+    {
+        while stack.len() > pos {
+            pop(stack);
+        }
+    }
+}
+
+/// Decode named and numeric character references. Unknown references are kept as written.
+fn decode_entities(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest[1..].find(';')
+            .filter(|&end| end <= 32)
+            .and_then(|end| decode_entity(&rest[1..end + 1]).map(|c| (c, end + 2)));
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    if let Some(num) = name.strip_prefix('#') {
+        let code = match num.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => num.parse().ok()?,
+        };
+        return char::from_u32(code);
+    }
+    Some(match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "mdash" => '—',
+        "ndash" => '–',
+        "hellip" => '…',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "laquo" => '«',
+        "raquo" => '»',
+        "bull" => '•',
+        "middot" => '·',
+        "times" => '×',
+        "rarr" => '→',
+        "larr" => '←',
+        "deg" => '°',
+        _ => return None,
+    })
+}
+
+// ─── Rendering ────────────────────────────────────────────────────────────────
+
+struct Renderer<'a> {
+    map_link: &'a dyn Fn(&str) -> Option<String>,
+}
+
+impl Renderer<'_> {
+    /// Render a sequence of nodes as Markdown blocks joined by `separator`. Runs of inline
+    /// content between blocks become paragraphs.
+    fn blocks(&self, nodes: &[Node], separator: &str) -> String {
+        let mut out: Vec<String> = vec![];
+        let mut inline = String::new();
+        for node in nodes {
+            match node {
+                Node::Element(e) if is_block(&e.name) => {
+                    push_paragraph(&mut out, &mut inline);
+                    let block = self.block(e);
+                    if !block.trim().is_empty() {
+                        out.push(block);
+                    }
+                }
+                _ => inline.push_str(&self.inline(std::slice::from_ref(node))),
+            }
+        }
+        push_paragraph(&mut out, &mut inline);
+        out.join(separator)
+    }
+
+    fn block(&self, e: &Element) -> String {
+        match e.name.as_str() {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = usize::from(e.name.as_bytes()[1] - b'0');
+                let text = tidy_inline(&self.inline(&e.children)).replace('\n', " ");
+                format!("{} {text}", "#".repeat(level))
+            }
+            "ul" | "ol" => self.list(e),
+            "li" => indent_item("- ", &self.blocks(&e.children, "\n")),
+            "blockquote" => self.blocks(&e.children, "\n\n")
+                .lines()
+                .map(|l| if l.is_empty() { ">".to_string() } else { format!("> {l}") })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            "pre" => code_block(e),
+            "table" => self.table(e),
+            "hr" => "---".to_string(),
+            "summary" | "dt" => format!("**{}**", tidy_inline(&self.inline(&e.children))),
+            "dd" => indent_item("  ", &self.blocks(&e.children, "\n")),
+            "dl" => self.blocks(&e.children, "\n"),
+            _ => self.blocks(&e.children, "\n\n"),
+        }
+    }
+
+    fn list(&self, list: &Element) -> String {
+        let ordered = list.name == "ol";
+        let mut number: usize = list.attr("start").and_then(|s| s.trim().parse().ok()).unwrap_or(1);
+        let mut items = vec![];
+        for child in list.child_elements() {
+            match child.name.as_str() {
+                "li" => {
+                    let marker = if ordered { format!("{number}. ") } else { "- ".to_string() };
+                    number += 1;
+                    items.push(indent_item(&marker, &self.blocks(&child.children, "\n")));
+                }
+                // A list nested directly in a list (invalid, but common) belongs to the previous item
+                "ul" | "ol" => items.push(indent_item("  ", &self.list(child))),
+                _ => {}
+            }
+        }
+        items.join("\n")
+    }
+
+    fn table(&self, table: &Element) -> String {
+        let mut rows: Vec<(bool, Vec<String>)> = vec![];
+        let mut collect = |section: &Element, in_head: bool| {
+            for row in section.child_elements().filter(|e| e.name == "tr") {
+                let cells: Vec<&Element> = row.child_elements()
+                    .filter(|c| c.name == "td" || c.name == "th")
+                    .collect();
+                let header = in_head || cells.iter().any(|c| c.name == "th");
+                let texts = cells.iter()
+                    .map(|c| tidy_inline(&self.inline(&c.children)).replace('\n', " ").replace('|', "\\|"))
+                    .collect();
+                rows.push((header, texts));
+            }
+        };
+        collect(table, false);
+        for section in table.child_elements() {
+            if matches!(section.name.as_str(), "thead" | "tbody" | "tfoot") {
+                collect(section, section.name == "thead");
+            }
+        }
+
+        let mut lines = vec![];
+        for (i, (header, cells)) in rows.iter().enumerate() {
+            lines.push(format!("| {} |", cells.join(" | ")));
+            if i == 0 && *header {
+                lines.push(format!("|{}", " --- |".repeat(cells.len())));
+            }
+        }
+        lines.join("\n")
+    }
+
+    /// Render nodes as inline Markdown. Source whitespace collapses to single spaces;
+    /// `<br>` becomes a line break.
+    fn inline(&self, nodes: &[Node]) -> String {
+        let mut out = String::new();
+        for node in nodes {
+            let e = match node {
+                Node::Text(text) => {
+                    out.push_str(&collapse_whitespace(text));
+                    continue;
+                }
+                Node::Element(e) => e,
+            };
+            match e.name.as_str() {
+                n if HIDDEN_ELEMENTS.contains(&n) => {}
+                "br" => out.push('\n'),
+                "img" => {
+                    if let Some(alt) = e.attr("alt").filter(|a| !a.trim().is_empty()) {
+                        out.push_str(&format!("[{}]", alt.trim()));
+                    }
+                }
+                "a" => {
+                    let text = self.inline(&e.children);
+                    let url = e.attr("href").and_then(|h| (self.map_link)(h.trim()));
+                    match url {
+                        Some(url) if !text.trim().is_empty() => {
+                            out.push_str(&wrap(&text, "[", &format!("]({url})")));
+                        }
+                        _ => out.push_str(&text),
+                    }
+                }
+                "code" | "kbd" | "samp" | "tt" | "pre" => out.push_str(&code_span(&text_content(&e.children))),
+                "strong" | "b" => out.push_str(&wrap(&self.inline(&e.children), "**", "**")),
+                "em" | "i" => out.push_str(&wrap(&self.inline(&e.children), "*", "*")),
+                "del" | "s" | "strike" => out.push_str(&wrap(&self.inline(&e.children), "~~", "~~")),
+                n if is_block(n) => {
+                    out.push(' ');
+                    out.push_str(&self.inline(&e.children));
+                    out.push(' ');
+                }
+                _ => out.push_str(&self.inline(&e.children)),
+            }
+        }
+        out
+    }
+}
+
+fn is_block(name: &str) -> bool {
+    BLOCK_ELEMENTS.contains(&name)
+}
+
+fn push_paragraph(out: &mut Vec<String>, inline: &mut String) {
+    let text = tidy_inline(inline);
+    if !text.is_empty() {
+        out.push(text);
+    }
+    inline.clear();
+}
+
+/// Collapse spaces within each line of rendered inline content and drop empty lines.
+fn tidy_inline(s: &str) -> String {
+    s.lines()
+        .map(|l| l.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn collapse_whitespace(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut last_space = false;
+    for c in s.chars() {
+        let space = c.is_whitespace();
+        if !(space && last_space) {
+            out.push(if space { ' ' } else { c });
+        }
+        last_space = space;
+    }
+    out
+}
+
+/// Wrap the trimmed content in `open`/`close`, keeping surrounding spaces outside the markers.
+fn wrap(content: &str, open: &str, close: &str) -> String {
+    let trimmed = content.trim();
+    if trimmed.is_empty() {
+        return content.to_string();
+    }
+    let lead = if content.starts_with(char::is_whitespace) { " " } else { "" };
+    let trail = if content.ends_with(char::is_whitespace) { " " } else { "" };
+    format!("{lead}{open}{trimmed}{close}{trail}")
+}
+
+fn code_span(text: &str) -> String {
+    let text = collapse_whitespace(text.trim());
+    if text.is_empty() {
+        String::new()
+    } else if text.contains('`') {
+        format!("`` {text} ``")
+    } else {
+        format!("`{text}`")
+    }
+}
+
+fn code_block(pre: &Element) -> String {
+    let code = pre.child_elements().find(|c| c.name == "code");
+    let lang = pre.attr("lang")
+        .or_else(|| {
+            code.and_then(|c| c.attr("class"))
+                .and_then(|class| class.split_whitespace()
+                    .find_map(|c| c.strip_prefix("language-").or_else(|| c.strip_prefix("lang-"))))
+        })
+        .unwrap_or("");
+    let text = text_content(&pre.children);
+    let text = text.strip_prefix('\n').unwrap_or(&text).trim_end();
+    format!("```{lang}\n{text}\n```")
+}
+
+/// Concatenated text of the nodes, as written (for code).
+fn text_content(nodes: &[Node]) -> String {
+    let mut out = String::new();
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Element(e) if e.name == "br" => out.push('\n'),
+            Node::Element(e) => out.push_str(&text_content(&e.children)),
+        }
+    }
+    out
+}
+
+/// Keep at most one blank line between blocks, except inside fenced code, and end with a
+/// single newline.
+fn normalize_blank_lines(s: &str) -> String {
+    let mut out = String::new();
+    let mut in_fence = false;
+    let mut blank = false;
+    for line in s.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        if line.trim().is_empty() && !in_fence {
+            blank = !out.is_empty();
+            continue;
+        }
+        if blank {
+            out.push('\n');
+            blank = false;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+/// Prefix the first line with `marker` and indent the rest to line up under it.
+fn indent_item(marker: &str, body: &str) -> String {
+    let pad = " ".repeat(marker.chars().count());
+    body.lines()
+        .enumerate()
+        .map(|(i, line)| match (i, line.is_empty()) {
+            (0, _) => format!("{marker}{line}"),
+            (_, true) => String::new(),
+            _ => format!("{pad}{line}"),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn md(html: &str) -> String {
+        to_markdown(html, |href| Some(href.to_string()))
+    }
+
+    fn attrs(tag: &str) -> Vec<(String, String)> {
+        match parse_tag(tag) {
+            Some((Token::Open { attrs, .. }, _)) => attrs,
+            other => panic!("expected an open tag, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn attributes_accept_both_quote_styles() {
+        assert_eq!(attrs(r#"<img src="x.png" alt="hello">"#)[1], ("alt".to_string(), "hello".to_string()));
+        assert_eq!(attrs("<img src='x.png' alt='world'>")[1], ("alt".to_string(), "world".to_string()));
+        assert_eq!(attrs("<a HREF=/docs/Guide.md>")[0], ("href".to_string(), "/docs/Guide.md".to_string()));
+        assert!(attrs(r#"<img src="x.png">"#).iter().all(|(k, _)| k != "alt"));
+        // `>` inside a quoted value does not end the tag
+        assert_eq!(attrs(r#"<img alt="a > b" src=x>"#)[0].1, "a > b");
+    }
+
+    #[test]
+    fn nested_lists_are_indented() {
+        let html = "<ul><li>one<ul><li>nested</li></ul></li><li>two</li></ul><ol start=\"3\"><li>three<li>four</ol>";
+        assert_eq!(md(html), "- one\n  - nested\n- two\n\n3. three\n4. four\n");
+    }
+
+    #[test]
+    fn blockquotes_prefix_every_line() {
+        let html = "<blockquote><p>Note:</p><p>second <em>paragraph</em></p></blockquote>";
+        assert_eq!(md(html), "> Note:\n>\n> second *paragraph*\n");
+    }
+
+    #[test]
+    fn numeric_and_named_entities_are_decoded() {
+        assert_eq!(decode_entities("&#169; 2024 &#x2014; a &lt;b&gt; &amp;amp; &unknown; & x"), "© 2024 — a <b> &amp; &unknown; & x");
+        assert_eq!(md("<p>Tom &amp; Jerry&#39;s &hellip;</p>"), "Tom & Jerry's …\n");
+    }
+
+    #[test]
+    fn code_blocks_keep_language_and_whitespace() {
+        let html = "<pre lang=\"rust\"><code>fn main() {\n    println!(\"&lt;hi&gt;\");\n}\n</code></pre>";
+        assert_eq!(md(html), "```rust\nfn main() {\n    println!(\"<hi>\");\n}\n```\n");
+        let html = "<pre><code class=\"language-toml\">[dependencies]\n\n\nserde = \"1\"</code></pre>";
+        assert_eq!(md(html), "```toml\n[dependencies]\n\n\nserde = \"1\"\n```\n");
+    }
+
+    #[test]
+    fn headings_paragraphs_and_emphasis() {
+        let html = "<h2>Usage</h2>\n<p>Add <strong>this</strong> to\n your <code>Cargo.toml</code>:<br>done</p><hr><p>bye";
+        assert_eq!(md(html), "## Usage\n\nAdd **this** to your `Cargo.toml`:\ndone\n\n---\n\nbye\n");
+    }
+
+    #[test]
+    fn comments_and_scripts_are_dropped() {
+        assert_eq!(md("<!-- hidden --><p>a<script>if (1 < 2) {}</script>b</p><style>p{}</style>"), "ab\n");
+    }
+
+    #[test]
+    fn links_map_through_caller() {
+        let html = r##"<p><a href="#x">here</a> and <a href="guide.md"> the <b>guide</b> </a></p>"##;
+        let text = to_markdown(html, |href| (!href.starts_with('#')).then(|| format!("https://example.com/{href}")));
+        assert_eq!(text, "here and [the **guide**](https://example.com/guide.md)\n");
+    }
+}
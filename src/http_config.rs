@@ -0,0 +1,107 @@
+//! Timeouts and connection limits for the outbound HTTP client.
+
+use std::num::{NonZeroU64, NonZeroUsize};
+use std::time::Duration;
+
+use serde::Serialize;
+
+/// Env var for the whole-request timeout, in seconds (CLI: `--http-timeout`).
+pub const TIMEOUT_ENV: &str = "DOCS_MCP_HTTP_TIMEOUT_SECS";
+/// Env var for the TCP/TLS connect timeout, in seconds (CLI: `--connect-timeout`).
+pub const CONNECT_TIMEOUT_ENV: &str = "DOCS_MCP_CONNECT_TIMEOUT_SECS";
+/// Env var for the number of requests allowed in flight at once (CLI: `--max-connections`).
+pub const MAX_CONNECTIONS_ENV: &str = "DOCS_MCP_MAX_CONNECTIONS";
+
+/// Long enough for the largest rustdoc JSON downloads from docs.rs.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(120);
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+pub const DEFAULT_MAX_CONNECTIONS: usize = 16;
+
+/// HTTP client settings, from the environment with command-line flags taking precedence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct HttpConfig {
+    /// Limit for a whole request, including reading the body
+    #[serde(rename = "timeout_secs", serialize_with = "as_secs")]
+    pub timeout: Duration,
+    #[serde(rename = "connect_timeout_secs", serialize_with = "as_secs")]
+    pub connect_timeout: Duration,
+    /// Requests in flight at once, across all hosts
+    pub max_connections: usize,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_TIMEOUT,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+        }
+    }
+}
+
+impl HttpConfig {
+    /// Settings from [`TIMEOUT_ENV`], [`CONNECT_TIMEOUT_ENV`] and [`MAX_CONNECTIONS_ENV`].
+    pub fn from_env() -> Self {
+        let env = |name: &str| std::env::var(name).ok();
+        Self::default().apply(env(TIMEOUT_ENV), env(CONNECT_TIMEOUT_ENV), env(MAX_CONNECTIONS_ENV))
+    }
+
+    /// Override with the `--http-timeout`, `--connect-timeout` and `--max-connections`
+    /// values, which clap has already checked are positive.
+    pub fn with_flags(mut self, timeout: Option<NonZeroU64>, connect_timeout: Option<NonZeroU64>, max_connections: Option<NonZeroUsize>) -> Self {
+        if let Some(secs) = timeout {
+            self.timeout = Duration::from_secs(secs.get());
+        }
+        if let Some(secs) = connect_timeout {
+            self.connect_timeout = Duration::from_secs(secs.get());
+        }
+        if let Some(n) = max_connections {
+            self.max_connections = n.get();
+        }
+        self
+    }
+
+    /// Replace each setting whose value parses; zero and garbage keep the current one.
+    fn apply(mut self, timeout: Option<String>, connect_timeout: Option<String>, max_connections: Option<String>) -> Self {
+        let positive = |v: Option<String>| v.and_then(|v| v.trim().parse::<u64>().ok()).filter(|&n| n > 0);
+        if let Some(secs) = positive(timeout) {
+            self.timeout = Duration::from_secs(secs);
+        }
+        if let Some(secs) = positive(connect_timeout) {
+            self.connect_timeout = Duration::from_secs(secs);
+        }
+        if let Some(n) = positive(max_connections) {
+            self.max_connections = usize::try_from(n).unwrap_or(usize::MAX);
+        }
+        self
+    }
+}
+
+fn as_secs<S: serde::Serializer>(d: &Duration, s: S) -> std::result::Result<S::Ok, S::Error> {
+    s.serialize_u64(d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(value: &str) -> Option<String> {
+        Some(value.to_string())
+    }
+
+    #[test]
+    fn env_values_apply_only_when_valid() {
+        let config = HttpConfig::default().apply(env("30"), env("0"), env("four"));
+        assert_eq!(config.timeout, Duration::from_secs(30));
+        assert_eq!(config.connect_timeout, DEFAULT_CONNECT_TIMEOUT);
+        assert_eq!(config.max_connections, DEFAULT_MAX_CONNECTIONS);
+    }
+
+    #[test]
+    fn flags_override_env() {
+        let config = HttpConfig::default().apply(env("30"), None, None)
+            .with_flags(NonZeroU64::new(5), None, NonZeroUsize::new(2));
+        assert_eq!((config.timeout, config.max_connections), (Duration::from_secs(5), 2));
+        assert_eq!(config.connect_timeout, DEFAULT_CONNECT_TIMEOUT);
+    }
+}
@@ -0,0 +1,274 @@
+//! Tool results too large for clients to hand to the model (giant module trees, the root
+//! docs of big crates) are written to the cache directory and replaced by an outline and
+//! resource links the client reads whole or part by part with `resources/read`.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use hex::encode as hex_encode;
+use rmcp::{
+    ErrorData,
+    model::{CallToolResult, Content, RawResource},
+};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+use crate::cache::write_atomic;
+
+/// Env var setting the largest result returned inline, in bytes. `0` turns this off.
+pub const MAX_BYTES_ENV: &str = "DOCS_MCP_MAX_OUTPUT_BYTES";
+/// About 25k tokens of JSON, within what common clients pass on.
+pub const DEFAULT_MAX_BYTES: usize = 100_000;
+/// Stored results are served as `docs-mcp://outputs/<id>`, and their parts as
+/// `docs-mcp://outputs/<id>/<n>` (from 1).
+pub const URI_PREFIX: &str = "docs-mcp://outputs/";
+/// Stored results older than this are deleted when the next one is written.
+const KEEP_FOR: Duration = Duration::from_secs(24 * 60 * 60);
+/// Strings up to this long are shown in the outline as they are.
+const SHORT_STRING: usize = 120;
+
+/// Size above which a result is stored and linked instead of returned inline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputLimit {
+    max_bytes: usize,
+}
+
+impl OutputLimit {
+    pub fn new(max_bytes: usize) -> Self {
+        Self { max_bytes }
+    }
+
+    /// A limit from [`MAX_BYTES_ENV`], defaulting to [`DEFAULT_MAX_BYTES`].
+    pub fn from_env() -> Self {
+        let max_bytes = std::env::var(MAX_BYTES_ENV).ok()
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(DEFAULT_MAX_BYTES);
+        Self::new(max_bytes)
+    }
+
+    /// No limit: every result is returned inline.
+    pub fn disabled() -> Self {
+        Self::new(0)
+    }
+
+    /// Whether results can be stored and linked at all.
+    pub fn is_enabled(&self) -> bool {
+        self.max_bytes > 0
+    }
+
+    /// Bytes per part of a stored result.
+    fn part_bytes(&self) -> usize {
+        if self.max_bytes == 0 { DEFAULT_MAX_BYTES } else { self.max_bytes }
+    }
+
+    /// `result`, or when its text is over the limit, an outline of it with links to the
+    /// full text stored under `cache_dir`. Errors are never stored; if writing fails the
+    /// result is returned as it is.
+    pub async fn apply(&self, cache_dir: &Path, tool: &str, result: CallToolResult) -> CallToolResult {
+        if !self.is_enabled() || result.is_error == Some(true) {
+            return result;
+        }
+        let texts: Vec<&str> = result.content.iter().filter_map(|c| c.as_text()).map(|t| t.text.as_str()).collect();
+        let bytes: usize = texts.iter().map(|t| t.len()).sum();
+        if bytes <= self.max_bytes {
+            return result;
+        }
+        let text = texts.concat();
+        let id = output_id(&text);
+        // The directory sweep and the write are blocking file system calls
+        let (dir, stored_id) = (cache_dir.to_path_buf(), id.clone());
+        let stored = tokio::task::spawn_blocking(move || store(&dir, &stored_id, &text).map(|()| text))
+            .await
+            .unwrap_or_else(|e| Err(std::io::Error::other(e)));
+        let text = match stored {
+            Ok(text) => text,
+            Err(e) => {
+                tracing::warn!("cannot store the {bytes}-byte {tool} result, returning it inline: {e}");
+                return result;
+            }
+        };
+        tracing::info!("{tool} result is {bytes} bytes; returning an outline and {URI_PREFIX}{id}");
+
+        let uri = format!("{URI_PREFIX}{id}");
+        let chunks = split(&text, self.part_bytes());
+        let parts: Vec<String> = (1..=chunks.len()).map(|n| format!("{uri}/{n}")).collect();
+        let outline = serde_json::from_str::<Value>(&text)
+            .map(|v| outline(&v))
+            .unwrap_or_else(|_| json!(format!("text, {bytes} bytes")));
+        let summary = json!({
+            "oversized": true,
+            "bytes": bytes,
+            "resource": uri,
+            "parts": parts,
+            "outline": outline,
+            "hint": format!(
+                "The result is over {} bytes, so it was stored instead of returned. Read the resource \
+                 (or its parts in order) with resources/read, or call {tool} again with narrower \
+                 parameters.",
+                self.max_bytes,
+            ),
+        });
+
+        // A part is a byte range of the text, not a JSON document of its own
+        let link = |uri: &str, name: String, size: usize, description: String, mime_type: &str| {
+            let mut resource = RawResource::new(uri, name);
+            resource.mime_type = Some(mime_type.to_string());
+            resource.size = u32::try_from(size).ok();
+            resource.description = Some(description);
+            Content::resource_link(resource)
+        };
+        let mut content = vec![
+            Content::text(serde_json::to_string_pretty(&summary).unwrap_or_default()),
+            link(&uri, format!("{tool} result"), bytes, format!("Full {tool} result"), "application/json"),
+        ];
+        for (n, (part, chunk)) in parts.iter().zip(&chunks).enumerate() {
+            content.push(link(part, format!("{tool} result, part {}", n + 1), chunk.len(),
+                format!("Part {} of {} of the {tool} result", n + 1, chunks.len()), "text/plain"));
+        }
+        CallToolResult { content, ..result }
+    }
+
+    /// Text of a stored result or one of its parts, by URI.
+    pub async fn read(&self, cache_dir: &Path, uri: &str) -> Result<String, ErrorData> {
+        let not_found = || ErrorData::resource_not_found(
+            format!("No stored result at {uri}; stored results are kept for a day. Call the tool again."),
+            None,
+        );
+        let rest = uri.strip_prefix(URI_PREFIX).ok_or_else(not_found)?;
+        let (id, part) = match rest.split_once('/') {
+            Some((id, part)) => (id, Some(part.parse::<usize>().map_err(|_| not_found())?)),
+            None => (rest, None),
+        };
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(not_found());
+        }
+        let text = tokio::fs::read_to_string(output_path(cache_dir, id)).await.map_err(|_| not_found())?;
+        match part {
+            None => Ok(text),
+            Some(n) => split(&text, self.part_bytes())
+                .get(n.wrapping_sub(1))
+                .map(|chunk| chunk.to_string())
+                .ok_or_else(not_found),
+        }
+    }
+}
+
+fn output_id(text: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(text.as_bytes());
+    hex_encode(&hasher.finalize()[..8])
+}
+
+fn output_path(cache_dir: &Path, id: &str) -> PathBuf {
+    cache_dir.join("outputs").join(format!("{id}.json"))
+}
+
+/// Write `text` under `id`, dropping stored results past [`KEEP_FOR`].
+fn store(cache_dir: &Path, id: &str, text: &str) -> std::io::Result<()> {
+    let path = output_path(cache_dir, id);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+        for entry in std::fs::read_dir(dir)?.flatten() {
+            let expired = entry.metadata().and_then(|m| m.modified()).ok()
+                .and_then(|t| SystemTime::now().duration_since(t).ok())
+                .is_some_and(|age| age > KEEP_FOR);
+            if expired {
+                let _ = std::fs::remove_file(entry.path());
+            }
+        }
+    }
+    write_atomic(&path, text.as_bytes())
+}
+
+/// `text` in pieces of at most `max` bytes, cut after a newline where there is one.
+fn split(text: &str, max: usize) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut rest = text;
+    while rest.len() > max {
+        let mut end = max;
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        if end == 0 {
+            // A character wider than `max` goes into a part of its own
+            end = rest.chars().next().map_or(rest.len(), char::len_utf8);
+        }
+        let cut = rest[..end].rfind('\n').map_or(end, |i| i + 1);
+        let (part, tail) = rest.split_at(cut);
+        parts.push(part);
+        rest = tail;
+    }
+    if !rest.is_empty() || parts.is_empty() {
+        parts.push(rest);
+    }
+    parts
+}
+
+/// The top-level fields of `value`, with long strings, arrays and objects replaced by
+/// their sizes.
+fn outline(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => map.iter().map(|(k, v)| (k.clone(), describe(v))).collect(),
+        other => describe(other),
+    }
+}
+
+fn describe(value: &Value) -> Value {
+    let bytes = || serde_json::to_string(value).map(|s| s.len()).unwrap_or(0);
+    match value {
+        Value::String(s) if s.len() > SHORT_STRING => json!(format!("string, {} bytes", s.len())),
+        Value::Array(items) => json!(format!("{} items, {} bytes", items.len(), bytes())),
+        Value::Object(map) => json!(format!("object with {} fields, {} bytes", map.len(), bytes())),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(value: Value) -> CallToolResult {
+        CallToolResult::success(vec![Content::text(serde_json::to_string_pretty(&value).unwrap())])
+    }
+
+    #[test]
+    fn split_cuts_after_newlines_within_the_limit() {
+        assert_eq!(split("ab\ncd\nef", 4), ["ab\n", "cd\n", "ef"]);
+        assert_eq!(split("abcdef", 4), ["abcd", "ef"]);
+        assert_eq!(split("", 4), [""]);
+        assert_eq!(split("é", 1).concat(), "é");
+    }
+
+    #[tokio::test]
+    async fn oversized_results_are_stored_and_linked() {
+        let dir = std::env::temp_dir().join(format!("docs-mcp-large-output-{}", std::process::id()));
+        let limit = OutputLimit::new(200);
+        let small = result(json!({"name": "serde"}));
+        assert_eq!(limit.apply(&dir, "crate_get", small.clone()).await, small);
+
+        let items: Vec<String> = (0..40).map(|i| format!("serde::item_{i}")).collect();
+        let full = result(json!({"name": "serde", "items": items}));
+        let linked = limit.apply(&dir, "crate_item_list", full.clone()).await;
+        let summary: Value = serde_json::from_str(&linked.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(summary["outline"]["name"], "serde");
+        assert!(summary["outline"]["items"].as_str().unwrap().starts_with("40 items"));
+
+        let uri = summary["resource"].as_str().unwrap();
+        let parts: Vec<&str> = summary["parts"].as_array().unwrap().iter().map(|p| p.as_str().unwrap()).collect();
+        assert_eq!(linked.content.len(), 2 + parts.len());
+        let text = &full.content[0].as_text().unwrap().text;
+        assert_eq!(&limit.read(&dir, uri).await.unwrap(), text);
+        let mut joined = String::new();
+        for part in &parts {
+            joined += &limit.read(&dir, part).await.unwrap();
+        }
+        assert_eq!(&joined, text);
+        let mime = |n: usize| linked.content[n].as_resource_link().unwrap().mime_type.clone();
+        assert_eq!((mime(1).as_deref(), mime(2).as_deref()), (Some("application/json"), Some("text/plain")));
+        assert!(limit.read(&dir, &format!("{uri}/{}", parts.len() + 1)).await.is_err());
+        assert!(limit.read(&dir, "docs-mcp://outputs/../secrets").await.is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(OutputLimit::disabled().apply(&dir, "crate_item_list", full.clone()).await, full);
+    }
+}
@@ -1,7 +1,26 @@
 pub mod cache;
+pub mod call_memo;
+pub mod cli;
 pub mod cratesio;
+pub mod depsdev;
 pub mod docsrs;
 pub mod error;
+pub mod fixtures;
+pub mod house_rules;
+pub mod html;
+pub mod http_config;
+pub mod large_output;
+pub mod mcp_log;
+pub mod metrics;
+pub mod osv;
+pub mod rust_releases;
+#[cfg(feature = "semantic-search")]
+pub mod semantic;
 pub mod server;
+pub mod session;
+pub mod singleflight;
 pub mod sparse_index;
+pub mod store;
+pub mod telemetry;
+pub mod tool_filter;
 pub mod tools;
@@ -1,25 +1,170 @@
 use anyhow::Result;
-use docs_mcp::{server::DocsMcpServer, tools::AppState};
+use clap::Parser;
+use docs_mcp::{call_memo::CallMemo, cli::{self, Cli, Command}, house_rules::HouseRules, http_config::HttpConfig, large_output::OutputLimit, mcp_log::McpLogger, server::DocsMcpServer, telemetry, tool_filter::ToolFilter, tools::AppState};
 use rmcp::ServiceExt;
 use rmcp::transport::io::stdio;
 use std::sync::Arc;
+use std::time::Duration;
+use tracing_subscriber::prelude::*;
+
+/// How long background work (a cache warm-up, the DB dump refresh) gets to stop on exit.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging to stderr (stdout is used for MCP protocol)
-    tracing_subscriber::fmt()
-        .with_writer(std::io::stderr)
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::from_default_env()
-                .add_directive("docs_mcp=info".parse()?),
+    let code = run().await?;
+    // tokio's stdin reader can be parked in a blocking read that keeps the runtime from
+    // shutting down until the client writes again; everything is flushed, so leave now
+    std::process::exit(code);
+}
+
+/// Serve MCP, or make the one call asked for by `query`; returns the exit code.
+async fn run() -> Result<i32> {
+    // Initialize logging to stderr (stdout is used for MCP protocol), and forward our own
+    // events to the client as MCP log notifications at the level it asks for
+    let logger = McpLogger::from_env();
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(std::io::stderr)
+                .with_filter(
+                    tracing_subscriber::EnvFilter::from_default_env()
+                        .add_directive("docs_mcp=info".parse()?),
+                ),
         )
+        .with(logger.layer())
         .init();
 
-    let state = AppState::new().await?;
-    let server = DocsMcpServer::new_with_state(Arc::new(state));
+    let cli = Cli::parse();
+    // --fixture-mode: serve the bundled fixtures with no network access (for client CI)
+    let state = if cli.fixture_mode {
+        let dir = cli.fixture_dir.clone().unwrap_or_else(docs_mcp::fixtures::default_fixture_dir);
+        if !dir.is_dir() {
+            anyhow::bail!(
+                "fixture directory {} not found; pass --fixture-dir or set {}",
+                dir.display(),
+                docs_mcp::fixtures::FIXTURE_DIR_ENV,
+            );
+        }
+        tracing::info!("fixture mode: serving requests from {}", dir.display());
+        AppState::with_fixtures(dir).await?
+    } else {
+        AppState::with_http_config(
+            HttpConfig::from_env().with_flags(cli.http_timeout, cli.connect_timeout, cli.max_connections),
+        ).await?
+    };
+    let state = Arc::new(state);
+    if let Some(interval) = telemetry::log_interval_from_env() {
+        let state = state.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                state.telemetry.log_summary();
+            }
+        });
+    }
+    let tool_filter = ToolFilter::load(cli.config.as_deref())?
+        .with_flags(cli.tools.as_deref(), cli.disable_tools.as_deref());
+    let mut server = DocsMcpServer::new_with_state(state.clone())
+        .with_call_memo(CallMemo::from_env())
+        .with_tool_filter(&tool_filter);
+    if let Some(rules) = HouseRules::load(cli.instructions_file.as_deref(), cli.instructions_mode.as_deref())? {
+        tracing::info!("house rules from {}", rules.path.display());
+        server = server.with_house_rules(rules);
+    }
+    if let Some(Command::Query { tool, args: tool_args }) = &cli.command {
+        let code = match cli::query(server, tool, tool_args).await {
+            Ok(result) => {
+                for text in result.content.iter().filter_map(|c| c.as_text()) {
+                    println!("{}", text.text);
+                }
+                i32::from(result.is_error == Some(true))
+            }
+            Err(err) => {
+                eprintln!("{}", serde_json::to_string_pretty(&err)?);
+                1
+            }
+        };
+        finish(&state).await;
+        return Ok(code);
+    }
+    let server = server.with_logger(logger).with_output_limit(OutputLimit::from_env());
 
-    let running = server.serve(stdio()).await?;
-    running.waiting().await?;
+    let mut signals = watch_signals();
+    let running = tokio::select! {
+        running = server.serve(stdio()) => running?,
+        Some(signal) = signals.recv() => {
+            tracing::info!("received {signal} before the client connected; exiting");
+            finish(&state).await;
+            return Ok(0);
+        }
+    };
+    let connection = running.cancellation_token();
+    tokio::spawn(async move {
+        let Some(signal) = signals.recv().await else { return };
+        tracing::info!("received {signal}; cancelling in-flight calls and closing the connection");
+        connection.cancel();
+        // A second signal skips the rest of the cleanup
+        let Some(signal) = signals.recv().await else { return };
+        tracing::warn!("received {signal} again; exiting now");
+        std::process::exit(130);
+    });
+    let quit = running.waiting().await?;
+    tracing::debug!("MCP connection ended: {quit:?}");
+
+    state.telemetry.log_summary();
+    finish(&state).await;
+    Ok(0)
+}
+
+/// Stop background work, giving it [`SHUTDOWN_GRACE`] to remove its scratch files, and
+/// save what would otherwise be lost on exit.
+async fn finish(state: &AppState) {
+    if !state.shut_down(SHUTDOWN_GRACE).await {
+        tracing::warn!("background work did not stop within {}s; exiting anyway", SHUTDOWN_GRACE.as_secs());
+    }
+    // Requests counted since the last periodic save would otherwise be lost
+    state.metrics.flush();
+    state.cache.remove_scratch();
+}
+
+/// Names of shutdown signals as they arrive, from a handler installed right away.
+fn watch_signals() -> tokio::sync::mpsc::UnboundedReceiver<&'static str> {
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        while tx.send(shutdown_signal().await).is_ok() {}
+    });
+    rx
+}
 
-    Ok(())
+/// Wait for Ctrl-C, or SIGTERM on Unix, and name the signal received.
+async fn shutdown_signal() -> &'static str {
+    let ctrl_c = async {
+        // Without a handler there is nothing to wait for; never resolve rather than
+        // shutting down at once
+        if tokio::signal::ctrl_c().await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    };
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let terminate = async {
+            match signal(SignalKind::terminate()) {
+                Ok(mut term) => { term.recv().await; }
+                Err(_) => std::future::pending::<()>().await,
+            }
+        };
+        tokio::select! {
+            _ = ctrl_c => "SIGINT",
+            _ = terminate => "SIGTERM",
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        ctrl_c.await;
+        "Ctrl-C"
+    }
 }
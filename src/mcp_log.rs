@@ -0,0 +1,263 @@
+//! Forward the server's own tracing events to the client as MCP `notifications/message`.
+//!
+//! Slow calls (a cold rustdoc JSON download, a crate archive fetch) otherwise look hung
+//! from the client side. [`McpLogLayer`] picks up `docs_mcp` events at or above the level
+//! the client asked for with `logging/setLevel` and queues them; once a client has
+//! initialized, a forwarder task sends them to every connected session. The queue is
+//! bounded: when the clients read too slowly, new events are dropped and the count is
+//! reported once they catch up.
+
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use rmcp::model::{LoggingLevel, LoggingMessageNotificationParam};
+use rmcp::{Peer, RoleServer};
+use serde_json::{Map, Value, json};
+use tokio::sync::mpsc;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+/// Env var setting the initial minimum level: an MCP level name ("debug", "info",
+/// "warning", ...) or "off". Clients can change it later with `logging/setLevel`.
+pub const LEVEL_ENV: &str = "DOCS_MCP_LOG_LEVEL";
+
+/// Only events from this crate are forwarded; HTTP client internals are noise to users.
+const TARGET_PREFIX: &str = "docs_mcp";
+const OFF: u8 = u8::MAX;
+/// Events queued for the client before new ones are dropped.
+const QUEUE_CAPACITY: usize = 256;
+
+/// Shared between the tracing layer and the server handler.
+pub struct McpLogger {
+    min_level: AtomicU8,
+    connected: AtomicBool,
+    sender: mpsc::Sender<LoggingMessageNotificationParam>,
+    receiver: Mutex<Option<mpsc::Receiver<LoggingMessageNotificationParam>>>,
+    /// Sessions that initialized and have not disconnected
+    peers: Arc<Mutex<Vec<Peer<RoleServer>>>>,
+    /// Events dropped because the queue was full, since the last report
+    dropped: Arc<AtomicU64>,
+}
+
+impl McpLogger {
+    /// A logger whose initial level comes from [`LEVEL_ENV`] (default "info").
+    pub fn from_env() -> Arc<Self> {
+        let level = std::env::var(LEVEL_ENV).ok()
+            .and_then(|v| parse_level(&v))
+            .unwrap_or(LoggingLevel::Info as u8);
+        Arc::new(Self::with_min_level(level))
+    }
+
+    fn with_min_level(min_level: u8) -> Self {
+        let (sender, receiver) = mpsc::channel(QUEUE_CAPACITY);
+        Self {
+            min_level: AtomicU8::new(min_level),
+            connected: AtomicBool::new(false),
+            sender,
+            receiver: Mutex::new(Some(receiver)),
+            peers: Arc::default(),
+            dropped: Arc::default(),
+        }
+    }
+
+    /// The tracing layer feeding this logger.
+    pub fn layer(self: &Arc<Self>) -> McpLogLayer {
+        McpLogLayer { logger: Arc::clone(self) }
+    }
+
+    /// Apply a client's `logging/setLevel` request.
+    pub fn set_level(&self, level: LoggingLevel) {
+        self.min_level.store(level as u8, Ordering::Relaxed);
+    }
+
+    pub fn enabled(&self, level: LoggingLevel) -> bool {
+        let min = self.min_level.load(Ordering::Relaxed);
+        min != OFF && level as u8 >= min
+    }
+
+    /// Send queued events to `peer` too, until it disconnects. Events before the first
+    /// session attaches are dropped rather than buffered, since no client can receive
+    /// them yet.
+    pub fn attach(&self, peer: Peer<RoleServer>) {
+        self.peers.lock().unwrap().push(peer);
+        let Some(mut receiver) = self.receiver.lock().unwrap().take() else { return };
+        self.connected.store(true, Ordering::Relaxed);
+        let (peers, dropped) = (Arc::clone(&self.peers), Arc::clone(&self.dropped));
+        tokio::spawn(async move {
+            while let Some(param) = receiver.recv().await {
+                let skipped = dropped.swap(0, Ordering::Relaxed);
+                let mut notices = vec![param];
+                if skipped > 0 {
+                    notices.insert(0, dropped_notice(skipped));
+                }
+                let sessions = peers.lock().unwrap().clone();
+                for peer in sessions {
+                    for notice in &notices {
+                        if peer.notify_logging_message(notice.clone()).await.is_err() {
+                            // Closed; forget it
+                            peers.lock().unwrap().retain(|p| !p.is_transport_closed());
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// `tracing_subscriber` layer that hands events to an [`McpLogger`].
+pub struct McpLogLayer {
+    logger: Arc<McpLogger>,
+}
+
+impl<S: Subscriber> Layer<S> for McpLogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let meta = event.metadata();
+        if !self.logger.connected.load(Ordering::Relaxed) || !meta.target().starts_with(TARGET_PREFIX) {
+            return;
+        }
+        let level = mcp_level(meta.level());
+        if !self.logger.enabled(level) {
+            return;
+        }
+
+        let mut visitor = EventVisitor::default();
+        event.record(&mut visitor);
+        let data = if visitor.fields.is_empty() {
+            Value::String(visitor.message)
+        } else {
+            let mut fields = visitor.fields;
+            fields.insert("message".to_string(), json!(visitor.message));
+            Value::Object(fields)
+        };
+        let param = LoggingMessageNotificationParam { level, logger: Some(meta.target().to_string()), data };
+        if let Err(mpsc::error::TrySendError::Full(_)) = self.logger.sender.try_send(param) {
+            self.logger.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// The notification standing in for `count` events dropped from a full queue.
+fn dropped_notice(count: u64) -> LoggingMessageNotificationParam {
+    LoggingMessageNotificationParam {
+        level: LoggingLevel::Warning,
+        logger: Some(TARGET_PREFIX.to_string()),
+        data: json!(format!("{count} log messages were dropped because the client read them too slowly")),
+    }
+}
+
+fn mcp_level(level: &Level) -> LoggingLevel {
+    match *level {
+        Level::ERROR => LoggingLevel::Error,
+        Level::WARN => LoggingLevel::Warning,
+        Level::INFO => LoggingLevel::Info,
+        _ => LoggingLevel::Debug,
+    }
+}
+
+/// Rank of an MCP level name (case-insensitive), or [`OFF`] for "off".
+fn parse_level(name: &str) -> Option<u8> {
+    let name = name.trim().to_ascii_lowercase();
+    if name == "off" {
+        return Some(OFF);
+    }
+    serde_json::from_value::<LoggingLevel>(Value::String(name)).ok().map(|l| l as u8)
+}
+
+#[derive(Default)]
+struct EventVisitor {
+    message: String,
+    fields: Map<String, Value>,
+}
+
+impl Visit for EventVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            self.fields.insert(field.name().to_string(), json!(format!("{value:?}")));
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            self.fields.insert(field.name().to_string(), json!(value));
+        }
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.fields.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.fields.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.fields.insert(field.name().to_string(), json!(value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn parse_level_accepts_mcp_names_and_off() {
+        assert_eq!(parse_level("warning"), Some(LoggingLevel::Warning as u8));
+        assert_eq!(parse_level(" DEBUG "), Some(LoggingLevel::Debug as u8));
+        assert_eq!(parse_level("off"), Some(OFF));
+        assert_eq!(parse_level("warn"), None);
+    }
+
+    #[test]
+    fn layer_forwards_own_events_at_or_above_level() {
+        let logger = Arc::new(McpLogger::with_min_level(LoggingLevel::Info as u8));
+        logger.connected.store(true, Ordering::Relaxed);
+        let mut receiver = logger.receiver.lock().unwrap().take().unwrap();
+        let subscriber = tracing_subscriber::registry().with(logger.layer());
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!(target: "docs_mcp::cache", "cache miss");
+            tracing::info!(target: "docs_mcp::docsrs", bytes = 42u64, "downloading {}", "serde");
+            tracing::warn!(target: "reqwest::connect", "not ours");
+            logger.set_level(LoggingLevel::Debug);
+            tracing::debug!(target: "docs_mcp::cache", "cache miss");
+        });
+
+        let first = receiver.try_recv().unwrap();
+        assert_eq!(first.level, LoggingLevel::Info);
+        assert_eq!(first.logger.as_deref(), Some("docs_mcp::docsrs"));
+        assert_eq!(first.data, json!({"message": "downloading serde", "bytes": 42}));
+        let second = receiver.try_recv().unwrap();
+        assert_eq!(second.level, LoggingLevel::Debug);
+        assert_eq!(second.data, json!("cache miss"));
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn full_queue_drops_and_counts_events() {
+        let logger = Arc::new(McpLogger::with_min_level(LoggingLevel::Info as u8));
+        logger.connected.store(true, Ordering::Relaxed);
+        let mut receiver = logger.receiver.lock().unwrap().take().unwrap();
+        let subscriber = tracing_subscriber::registry().with(logger.layer());
+
+        tracing::subscriber::with_default(subscriber, || {
+            for n in 0..QUEUE_CAPACITY + 3 {
+                tracing::info!(target: "docs_mcp::cache", "event {n}");
+            }
+        });
+
+        assert_eq!(logger.dropped.load(Ordering::Relaxed), 3);
+        let mut queued = 0;
+        while receiver.try_recv().is_ok() {
+            queued += 1;
+        }
+        assert_eq!(queued, QUEUE_CAPACITY);
+    }
+}
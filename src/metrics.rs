@@ -0,0 +1,263 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+const STATS_FILE: &str = "request_stats.json";
+
+/// Default and fastest crates.io pacing: the crates.io data access policy allows 1 req/s.
+const CRATES_IO_MIN_INTERVAL_MS: u64 = 1000;
+/// Slowest pacing the tuner backs off to after repeated 429s.
+const CRATES_IO_MAX_INTERVAL_MS: u64 = 4000;
+/// crates.io requests observed before the pacing is re-tuned.
+const TUNE_SAMPLE: u64 = 100;
+/// Stats are flushed to disk every this many requests (and on every 429).
+const SAVE_EVERY: u64 = 20;
+
+/// Request and throttle counts for one host.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HostStats {
+    pub requests: u64,
+    /// Responses with HTTP 429 Too Many Requests
+    pub throttled: u64,
+}
+
+/// On-disk stats, persisted across runs in the cache directory.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StatsFile {
+    /// Lifetime counts per host
+    #[serde(default)]
+    pub hosts: BTreeMap<String, HostStats>,
+    /// crates.io counts since the pacing was last tuned
+    #[serde(default)]
+    pub window: HostStats,
+    /// Current crates.io pacing interval
+    #[serde(default = "default_interval_ms")]
+    pub crates_io_interval_ms: u64,
+}
+
+fn default_interval_ms() -> u64 {
+    CRATES_IO_MIN_INTERVAL_MS
+}
+
+impl Default for StatsFile {
+    fn default() -> Self {
+        Self {
+            hosts: BTreeMap::new(),
+            window: HostStats::default(),
+            crates_io_interval_ms: CRATES_IO_MIN_INTERVAL_MS,
+        }
+    }
+}
+
+impl StatsFile {
+    /// Re-tune the crates.io pacing from the current window once it has enough samples.
+    ///
+    /// More than 1% throttled doubles the interval; a clean window halves it again, never
+    /// going faster than the policy's 1 req/s. The window is reset after each adjustment.
+    pub fn tune(&mut self) {
+        if self.window.requests < TUNE_SAMPLE {
+            return;
+        }
+        let interval = self.crates_io_interval_ms;
+        self.crates_io_interval_ms = if self.window.throttled * 100 > self.window.requests {
+            (interval * 2).min(CRATES_IO_MAX_INTERVAL_MS)
+        } else if self.window.throttled == 0 {
+            (interval / 2).max(CRATES_IO_MIN_INTERVAL_MS)
+        } else {
+            interval
+        };
+        self.window = HostStats::default();
+    }
+}
+
+/// Per-host request counters shared by the HTTP middleware and `server_status`.
+pub struct RequestMetrics {
+    /// Stats file location; `None` keeps metrics in memory only (fixture mode)
+    path: Option<PathBuf>,
+    stats: Arc<Mutex<StatsFile>>,
+    /// Held while the stats file is written, so snapshots reach the disk in order
+    writing: Arc<Mutex<()>>,
+    process: Mutex<BTreeMap<String, HostStats>>,
+    /// crates.io pacing chosen at startup (the limiter is built once)
+    crates_io_interval: Duration,
+}
+
+impl RequestMetrics {
+    /// Load stats from `cache_dir`, tune the crates.io pacing, and persist the result.
+    ///
+    /// A missing or unreadable stats file starts fresh at the default pacing.
+    pub fn load(cache_dir: &Path) -> Self {
+        let path = cache_dir.join(STATS_FILE);
+        let mut stats: StatsFile = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+        stats.tune();
+        let metrics = Self::from_stats(Some(path), stats);
+        metrics.save();
+        metrics
+    }
+
+    /// Metrics that are never written to disk.
+    pub fn in_memory() -> Self {
+        Self::from_stats(None, StatsFile::default())
+    }
+
+    fn from_stats(path: Option<PathBuf>, stats: StatsFile) -> Self {
+        let interval = stats.crates_io_interval_ms.clamp(CRATES_IO_MIN_INTERVAL_MS, CRATES_IO_MAX_INTERVAL_MS);
+        Self {
+            path,
+            stats: Arc::new(Mutex::new(stats)),
+            writing: Arc::default(),
+            process: Mutex::new(BTreeMap::new()),
+            crates_io_interval: Duration::from_millis(interval),
+        }
+    }
+
+    /// Minimum spacing between crates.io API requests.
+    pub fn crates_io_interval(&self) -> Duration {
+        self.crates_io_interval
+    }
+
+    /// Record one response from `host`.
+    pub fn record(&self, host: &str, throttled: bool) {
+        let bump = |s: &mut HostStats| {
+            s.requests += 1;
+            s.throttled += u64::from(throttled);
+        };
+        if let Ok(mut process) = self.process.lock() {
+            bump(process.entry(host.to_string()).or_default());
+        }
+        let should_save = match self.stats.lock() {
+            Ok(mut stats) => {
+                let lifetime = stats.hosts.entry(host.to_string()).or_default();
+                bump(lifetime);
+                let total = lifetime.requests;
+                if host == "crates.io" {
+                    bump(&mut stats.window);
+                }
+                throttled || total % SAVE_EVERY == 0
+            }
+            Err(_) => false,
+        };
+        if should_save {
+            self.save_in_background();
+        }
+    }
+
+    /// Counts since this process started, per host, across all sessions.
+    pub fn process(&self) -> BTreeMap<String, HostStats> {
+        self.process.lock().map(|s| s.clone()).unwrap_or_default()
+    }
+
+    /// Persisted stats including this process.
+    pub fn stats(&self) -> StatsFile {
+        self.stats.lock().map(|s| s.clone()).unwrap_or_default()
+    }
+
+    /// Write the stats gathered since the last periodic save, e.g. before exiting.
+    pub fn flush(&self) {
+        self.save();
+    }
+
+    fn save(&self) {
+        if let Some(path) = &self.path {
+            write_stats(path, &self.stats, &self.writing);
+        }
+    }
+
+    /// [`save`](Self::save) on the blocking pool, so the HTTP middleware calling
+    /// [`record`](Self::record) never waits on the disk.
+    fn save_in_background(&self) {
+        let Some(path) = self.path.clone() else { return };
+        let (stats, writing) = (self.stats.clone(), self.writing.clone());
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => {
+                runtime.spawn_blocking(move || write_stats(&path, &stats, &writing));
+            }
+            Err(_) => write_stats(&path, &stats, &writing),
+        }
+    }
+}
+
+/// Best-effort write of the stats file; metrics must never fail a request.
+fn write_stats(path: &Path, stats: &Mutex<StatsFile>, writing: &Mutex<()>) {
+    let _writing = writing.lock();
+    let Ok(snapshot) = stats.lock().map(|s| s.clone()) else { return };
+    let Ok(raw) = serde_json::to_string_pretty(&snapshot) else { return };
+    if let Err(e) = crate::cache::write_atomic(path, raw.as_bytes()) {
+        tracing::debug!("failed to write {}: {e}", path.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(interval: u64, requests: u64, throttled: u64) -> StatsFile {
+        StatsFile {
+            window: HostStats { requests, throttled },
+            crates_io_interval_ms: interval,
+            ..StatsFile::default()
+        }
+    }
+
+    #[test]
+    fn tune_waits_for_enough_samples() {
+        let mut s = stats(2000, TUNE_SAMPLE - 1, 50);
+        s.tune();
+        assert_eq!(s.crates_io_interval_ms, 2000);
+        assert_eq!(s.window.requests, TUNE_SAMPLE - 1);
+    }
+
+    #[test]
+    fn tune_backs_off_on_throttling_and_relaxes_when_clean() {
+        let mut s = stats(1000, 200, 5);
+        s.tune();
+        assert_eq!(s.crates_io_interval_ms, 2000);
+        assert_eq!(s.window, HostStats::default());
+
+        let mut s = stats(4000, 200, 10);
+        s.tune();
+        assert_eq!(s.crates_io_interval_ms, CRATES_IO_MAX_INTERVAL_MS);
+
+        let mut s = stats(2000, 200, 0);
+        s.tune();
+        assert_eq!(s.crates_io_interval_ms, 1000);
+
+        // Never faster than the policy limit
+        let mut s = stats(1000, 200, 0);
+        s.tune();
+        assert_eq!(s.crates_io_interval_ms, CRATES_IO_MIN_INTERVAL_MS);
+    }
+
+    #[test]
+    fn record_counts_per_host_and_window() {
+        let metrics = RequestMetrics::in_memory();
+        metrics.record("crates.io", false);
+        metrics.record("crates.io", true);
+        metrics.record("docs.rs", false);
+        let process = metrics.process();
+        assert_eq!(process["crates.io"], HostStats { requests: 2, throttled: 1 });
+        assert_eq!(process["docs.rs"], HostStats { requests: 1, throttled: 0 });
+        assert_eq!(metrics.stats().window, HostStats { requests: 2, throttled: 1 });
+    }
+
+    #[tokio::test]
+    async fn throttled_responses_are_saved_off_the_async_worker() {
+        let dir = std::env::temp_dir().join(format!("docs-mcp-metrics-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let metrics = RequestMetrics::load(&dir);
+        metrics.record("crates.io", true);
+        // The background write and a flush both take the write lock; after the flush the
+        // file holds the throttled response either way
+        metrics.flush();
+        let saved: StatsFile = serde_json::from_str(&std::fs::read_to_string(dir.join(STATS_FILE)).unwrap()).unwrap();
+        assert_eq!(saved.hosts["crates.io"], HostStats { requests: 1, throttled: 1 });
+        // The background write may still be renaming its file into place
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
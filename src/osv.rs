@@ -0,0 +1,343 @@
+//! Security advisories from the OSV database, which imports every RustSec advisory
+//! for crates.io packages along with GitHub advisories that RustSec lacks.
+
+use std::collections::HashMap;
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::cache::DiskCache;
+use crate::error::Result;
+
+const OSV_QUERY_URL: &str = "https://api.osv.dev/v1/query";
+const OSV_BATCH_URL: &str = "https://api.osv.dev/v1/querybatch";
+const OSV_VULN_URL: &str = "https://api.osv.dev/v1/vulns";
+
+/// Queries per `querybatch` request, the most OSV accepts.
+const MAX_BATCH: usize = 1000;
+
+#[derive(Debug, Default, Deserialize)]
+struct QueryResponse {
+    #[serde(default)]
+    vulns: Vec<Advisory>,
+    /// Set when more advisories match than fit in one response; sent back as
+    /// `page_token` to get the next page
+    #[serde(default)]
+    next_page_token: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct BatchResponse {
+    #[serde(default)]
+    results: Vec<QueryResponse>,
+}
+
+/// One advisory, trimmed to the fields needed to match it against versions.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Advisory {
+    /// RUSTSEC-… for advisories imported from RustSec
+    pub id: String,
+    /// CVE and GHSA identifiers for the same issue
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub published: Option<String>,
+    /// Set when the advisory was retracted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub withdrawn: Option<String>,
+    /// Severity scores, e.g. a CVSS vector
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub severity: Vec<Severity>,
+    #[serde(default, skip_serializing)]
+    affected: Vec<Affected>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Severity {
+    /// "CVSS_V3", "CVSS_V4", ...
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub score: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Affected {
+    package: Package,
+    #[serde(default)]
+    ranges: Vec<Range>,
+    #[serde(default)]
+    versions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Package {
+    name: String,
+    #[serde(default)]
+    ecosystem: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Range {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    events: Vec<Event>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct Event {
+    introduced: Option<String>,
+    fixed: Option<String>,
+    last_affected: Option<String>,
+}
+
+impl Advisory {
+    /// Whether `version` of crate `name` falls inside this advisory's affected ranges.
+    pub fn affects(&self, name: &str, version: &Version) -> bool {
+        self.affected.iter()
+            .filter(|a| a.package.name == name && a.package.ecosystem == "crates.io")
+            .any(|a| {
+                a.versions.iter().any(|v| *v == version.to_string())
+                    || a.ranges.iter().filter(|r| r.kind == "SEMVER").any(|r| in_range(&r.events, version))
+            })
+    }
+
+    /// Versions of crate `name` that fix this advisory, one per affected range.
+    pub fn fixed_in(&self, name: &str) -> Vec<String> {
+        self.affected.iter()
+            .filter(|a| a.package.name == name && a.package.ecosystem == "crates.io")
+            .flat_map(|a| &a.ranges)
+            .flat_map(|r| &r.events)
+            .filter_map(|e| e.fixed.clone())
+            .collect()
+    }
+}
+
+/// Walk OSV range events in order: `introduced` opens an interval, `fixed` closes it
+/// before that version and `last_affected` closes it after.
+fn in_range(events: &[Event], version: &Version) -> bool {
+    let mut start: Option<Version> = None;
+    for event in events {
+        if let Some(v) = &event.introduced {
+            start = Some(parse_event_version(v));
+            continue;
+        }
+        let (end, inclusive) = match (event.fixed.as_deref(), event.last_affected.as_deref()) {
+            (Some(v), _) => (v, false),
+            (None, Some(v)) => (v, true),
+            (None, None) => continue,
+        };
+        let Ok(end) = Version::parse(end) else { continue };
+        let within = |s: Version| *version >= s && if inclusive { *version <= end } else { *version < end };
+        if start.take().is_some_and(within) {
+            return true;
+        }
+    }
+    // An interval left open runs through every later release
+    start.is_some_and(|s| *version >= s)
+}
+
+/// OSV writes "0" for "since the first release".
+fn parse_event_version(v: &str) -> Version {
+    Version::parse(v).unwrap_or_else(|_| Version::new(0, 0, 0))
+}
+
+/// Every advisory OSV lists for crates.io package `name`.
+pub async fn advisories(
+    client: &reqwest_middleware::ClientWithMiddleware,
+    cache: &DiskCache,
+    name: &str,
+) -> Result<Vec<Advisory>> {
+    let mut vulns = vec![];
+    let mut page_token: Option<String> = None;
+    loop {
+        let body = query(name, None, page_token.as_deref()).to_string();
+        let resp: QueryResponse = cache.post_json(client, OSV_QUERY_URL, &body).await?;
+        vulns.extend(resp.vulns);
+        match resp.next_page_token {
+            Some(token) => page_token = Some(token),
+            None => return Ok(vulns),
+        }
+    }
+}
+
+/// An OSV query for crates.io package `name`, at `version` if given, continuing from
+/// `page_token` if given.
+fn query(name: &str, version: Option<&str>, page_token: Option<&str>) -> serde_json::Value {
+    let mut query = json!({"package": {"name": name, "ecosystem": "crates.io"}});
+    if let Some(version) = version {
+        query["version"] = json!(version);
+    }
+    if let Some(token) = page_token {
+        query["page_token"] = json!(token);
+    }
+    query
+}
+
+/// The advisories covering each `(crate, version)` of `packages`, in the same order, leaving
+/// out withdrawn ones.
+///
+/// One `querybatch` request finds the matching advisory ids for up to [`MAX_BATCH`]
+/// packages, and packages with more matches than one page are queried again from their
+/// page token; each distinct advisory is then fetched once.
+pub async fn vulnerabilities(
+    client: &reqwest_middleware::ClientWithMiddleware,
+    cache: &DiskCache,
+    packages: &[(String, String)],
+) -> Result<Vec<Vec<Advisory>>> {
+    let mut ids: Vec<Vec<String>> = vec![vec![]; packages.len()];
+    // Index into `packages` and the page to ask for
+    let mut pending: Vec<(usize, Option<String>)> = (0..packages.len()).map(|i| (i, None)).collect();
+    while !pending.is_empty() {
+        let mut next_pages = vec![];
+        for chunk in pending.chunks(MAX_BATCH) {
+            let queries: Vec<_> = chunk.iter()
+                .map(|(i, token)| query(&packages[*i].0, Some(&packages[*i].1), token.as_deref()))
+                .collect();
+            let body = json!({"queries": queries}).to_string();
+            let resp: BatchResponse = cache.post_json(client, OSV_BATCH_URL, &body).await?;
+            for ((i, _), result) in chunk.iter().zip(resp.results) {
+                ids[*i].extend(result.vulns.into_iter().map(|v| v.id));
+                if let Some(token) = result.next_page_token {
+                    next_pages.push((*i, Some(token)));
+                }
+            }
+        }
+        pending = next_pages;
+    }
+
+    let mut records: HashMap<String, Advisory> = HashMap::new();
+    for id in ids.iter().flatten() {
+        if !records.contains_key(id) {
+            let advisory: Advisory = cache.get_json(client, &format!("{OSV_VULN_URL}/{id}")).await?;
+            records.insert(id.clone(), advisory);
+        }
+    }
+    Ok(ids.into_iter()
+        .map(|ids| ids.iter()
+            .filter_map(|id| records.get(id))
+            .filter(|a| a.withdrawn.is_none())
+            .cloned()
+            .collect())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use async_trait::async_trait;
+    use http::{Extensions, StatusCode};
+    use reqwest::{Request, Response};
+    use reqwest_middleware::{Middleware, Next};
+
+    /// RUSTSEC-2023-0001 as OSV serves it, trimmed to the fields read here.
+    const TOKIO_ADVISORY: &str = r#"{
+        "id": "RUSTSEC-2023-0001",
+        "summary": "reject_remote_clients Configuration corruption",
+        "aliases": ["CVE-2023-22466", "GHSA-7rrj-xr53-82p5"],
+        "published": "2023-01-04T12:00:00Z",
+        "affected": [{
+            "package": {"ecosystem": "crates.io", "name": "tokio", "purl": "pkg:cargo/tokio"},
+            "ranges": [{"type": "SEMVER", "events": [
+                {"introduced": "0.2.1"}, {"fixed": "1.18.4"},
+                {"introduced": "1.19.0"}, {"fixed": "1.20.3"},
+                {"introduced": "1.21.0"}, {"fixed": "1.23.1"}
+            ]}]
+        }]
+    }"#;
+
+    /// Answers OSV requests: tokio's matches span two pages, and demo's only match is withdrawn.
+    struct FakeOsv;
+
+    #[async_trait]
+    impl Middleware for FakeOsv {
+        async fn handle(&self, req: Request, _: &mut Extensions, _: Next<'_>) -> reqwest_middleware::Result<Response> {
+            let body = match req.url().path() {
+                "/v1/querybatch" => {
+                    let request: serde_json::Value = serde_json::from_slice(req.body().and_then(|b| b.as_bytes()).unwrap()).unwrap();
+                    let results: Vec<_> = request["queries"].as_array().unwrap().iter()
+                        .map(|q| match (q["package"]["name"].as_str().unwrap(), q["page_token"].as_str()) {
+                            ("tokio", None) => json!({"vulns": [{"id": "RUSTSEC-2023-0001"}], "next_page_token": "page-2"}),
+                            ("tokio", Some("page-2")) => json!({"vulns": [{"id": "RUSTSEC-2099-0002"}]}),
+                            ("demo", None) => json!({"vulns": [{"id": "RUSTSEC-2099-0003"}]}),
+                            _ => json!({}),
+                        })
+                        .collect();
+                    json!({"results": results}).to_string()
+                }
+                "/v1/vulns/RUSTSEC-2023-0001" => TOKIO_ADVISORY.to_string(),
+                "/v1/vulns/RUSTSEC-2099-0002" => json!({"id": "RUSTSEC-2099-0002"}).to_string(),
+                "/v1/vulns/RUSTSEC-2099-0003" => json!({"id": "RUSTSEC-2099-0003", "withdrawn": "2099-01-02T00:00:00Z"}).to_string(),
+                _ => return Ok(crate::fixtures::respond(StatusCode::NOT_FOUND, vec![])),
+            };
+            Ok(crate::fixtures::respond(StatusCode::OK, body.into_bytes()))
+        }
+    }
+
+    #[tokio::test]
+    async fn batch_queries_follow_page_tokens_and_drop_withdrawn_advisories() {
+        let client = reqwest_middleware::ClientBuilder::new(reqwest::Client::new()).with(FakeOsv).build();
+        let cache = DiskCache::scratch("docs-mcp-test-osv").unwrap();
+        let packages = [("tokio", "1.20.0"), ("demo", "1.0.0"), ("other", "0.1.0")]
+            .map(|(n, v)| (n.to_string(), v.to_string()));
+        let found = vulnerabilities(&client, &cache, &packages).await.unwrap();
+
+        let ids: Vec<Vec<&str>> = found.iter().map(|a| a.iter().map(|a| a.id.as_str()).collect()).collect();
+        assert_eq!(ids, [vec!["RUSTSEC-2023-0001", "RUSTSEC-2099-0002"], vec![], vec![]]);
+        let tokio = &found[0][0];
+        assert_eq!(tokio.aliases, ["CVE-2023-22466", "GHSA-7rrj-xr53-82p5"]);
+        assert!(tokio.affects("tokio", &v("1.20.0")));
+        assert!(!tokio.affects("tokio", &v("1.20.3")));
+        assert_eq!(tokio.fixed_in("tokio"), ["1.18.4", "1.20.3", "1.23.1"]);
+    }
+
+    fn advisory(events: serde_json::Value) -> Advisory {
+        serde_json::from_value(json!({
+            "id": "RUSTSEC-2099-0001",
+            "affected": [{
+                "package": {"name": "demo", "ecosystem": "crates.io"},
+                "ranges": [{"type": "SEMVER", "events": events}],
+            }],
+        })).unwrap()
+    }
+
+    fn v(s: &str) -> Version {
+        Version::parse(s).unwrap()
+    }
+
+    #[test]
+    fn fixed_and_last_affected_bound_the_range() {
+        let adv = advisory(json!([
+            {"introduced": "0"}, {"fixed": "1.2.3"},
+            {"introduced": "2.0.0"}, {"last_affected": "2.1.0"},
+        ]));
+        assert!(adv.affects("demo", &v("0.1.0")));
+        assert!(adv.affects("demo", &v("1.2.2")));
+        assert!(!adv.affects("demo", &v("1.2.3")));
+        assert!(!adv.affects("demo", &v("1.9.0")));
+        assert!(adv.affects("demo", &v("2.1.0")));
+        assert!(!adv.affects("demo", &v("2.1.1")));
+        assert!(!adv.affects("other", &v("0.1.0")));
+    }
+
+    #[test]
+    fn fixed_in_lists_each_range_fix() {
+        let adv = advisory(json!([
+            {"introduced": "0"}, {"fixed": "1.2.3"},
+            {"introduced": "2.0.0"}, {"fixed": "2.0.4"},
+        ]));
+        assert_eq!(adv.fixed_in("demo"), ["1.2.3", "2.0.4"]);
+        assert!(adv.fixed_in("other").is_empty());
+    }
+
+    #[test]
+    fn open_interval_affects_every_later_version() {
+        let adv = advisory(json!([{"introduced": "0.3.0"}]));
+        assert!(!adv.affects("demo", &v("0.2.9")));
+        assert!(adv.affects("demo", &v("9.0.0")));
+    }
+}
@@ -0,0 +1,128 @@
+//! Current Rust toolchain releases from the channel manifests rustup installs from, and
+//! the fixed six-week schedule that dates every stable minor release.
+
+use chrono::{Datelike, Days, NaiveDate};
+use serde::Serialize;
+
+use crate::cache::DiskCache;
+use crate::error::{DocsError, Result};
+
+const DIST_BASE: &str = "https://static.rust-lang.org/dist";
+
+/// Rust 1.0.0 shipped on Friday 2015-05-15; every minor release since has shipped on a
+/// Thursday, six weeks apart, counted from the day before.
+const SCHEDULE_START: (i32, u32, u32) = (2015, 5, 14);
+const RELEASE_CYCLE_DAYS: u64 = 42;
+/// Latest minor release dated; the schedule says nothing useful about releases further
+/// out than this (1.1000 would ship in 2130).
+pub const MAX_SCHEDULED_MINOR: u64 = 1000;
+
+/// The toolchain a release channel currently serves.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChannelRelease {
+    /// e.g. "1.87.0", "1.88.0-beta.4" or "1.89.0-nightly"
+    pub version: String,
+    /// Day the manifest was published: the release day for stable and beta, the build
+    /// day for nightly
+    pub date: String,
+    /// Short hash of the rustc commit
+    pub commit: Option<String>,
+}
+
+/// The release `channel` ("stable", "beta" or "nightly") serves today.
+pub async fn channel_release(
+    client: &reqwest_middleware::ClientWithMiddleware,
+    cache: &DiskCache,
+    channel: &str,
+) -> Result<ChannelRelease> {
+    let url = format!("{DIST_BASE}/channel-rust-{channel}.toml");
+    let manifest = cache.get_text(client, &url).await?;
+    parse_manifest(&manifest)
+        .ok_or_else(|| DocsError::Other(format!("{url} has no date or [pkg.rust] version")))
+}
+
+/// Day the stable `1.{minor}.0` release shipped, or is scheduled to; `None` past
+/// [`MAX_SCHEDULED_MINOR`].
+pub fn stable_release_date(minor: u64) -> Option<NaiveDate> {
+    let (y, m, d) = SCHEDULE_START;
+    let start = NaiveDate::from_ymd_opt(y, m, d)?;
+    if minor == 0 {
+        return start.succ_opt();
+    }
+    if minor > MAX_SCHEDULED_MINOR {
+        return None;
+    }
+    start.checked_add_days(Days::new(minor.checked_mul(RELEASE_CYCLE_DAYS)?))
+}
+
+/// Whole months from `from` to `to`, counting a month only once its day has been reached.
+pub fn months_between(from: NaiveDate, to: NaiveDate) -> i64 {
+    let months = (to.year() - from.year()) as i64 * 12 + to.month() as i64 - from.month() as i64;
+    if to.day() < from.day() { months - 1 } else { months }
+}
+
+/// Read the top-level `date` and the `[pkg.rust]` version line out of a channel manifest.
+///
+/// The manifests run to hundreds of kilobytes of per-target download tables; only these
+/// two keys are needed, so they are picked out line by line.
+fn parse_manifest(manifest: &str) -> Option<ChannelRelease> {
+    let mut table = "";
+    let mut date = None;
+    let mut version_line = None;
+    for line in manifest.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            table = line;
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else { continue };
+        let value = value.trim().trim_matches('"');
+        match (table, key.trim()) {
+            ("", "date") => date = Some(value.to_string()),
+            ("[pkg.rust]", "version") => version_line = Some(value.to_string()),
+            _ => {}
+        }
+        if date.is_some() && version_line.is_some() {
+            break;
+        }
+    }
+    // "1.87.0 (17067e9ac 2025-05-09)"
+    let version_line = version_line?;
+    let mut parts = version_line.split_whitespace();
+    let version = parts.next()?.to_string();
+    let commit = parts.next().map(|c| c.trim_start_matches('(').to_string());
+    Some(ChannelRelease { version, date: date?, commit })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stable_releases_follow_the_six_week_schedule() {
+        let date = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        assert_eq!(stable_release_date(0), Some(date("2015-05-15")));
+        assert_eq!(stable_release_date(48), Some(date("2020-11-19")));
+        assert_eq!(stable_release_date(70), Some(date("2023-06-01")));
+        assert_eq!(stable_release_date(80), Some(date("2024-07-25")));
+        assert_eq!(stable_release_date(87), Some(date("2025-05-15")));
+        assert!(stable_release_date(MAX_SCHEDULED_MINOR).is_some());
+        assert_eq!(stable_release_date(MAX_SCHEDULED_MINOR + 1), None);
+        assert_eq!(stable_release_date(u64::MAX), None);
+        assert_eq!(months_between(date("2023-06-01"), date("2025-06-01")), 24);
+        assert_eq!(months_between(date("2023-06-01"), date("2025-05-31")), 23);
+    }
+
+    #[test]
+    fn parse_manifest_reads_rustc_not_cargo() {
+        let manifest = "manifest-version = \"2\"\ndate = \"2025-05-15\"\n\
+            [pkg.cargo]\nversion = \"0.88.0 (873a06493 2025-05-10)\"\n\
+            [pkg.rust]\nversion = \"1.87.0 (17067e9ac 2025-05-09)\"\n\
+            [pkg.rust.target.x86_64-unknown-linux-gnu]\navailable = true\n";
+        let release = parse_manifest(manifest).unwrap();
+        assert_eq!(release.version, "1.87.0");
+        assert_eq!(release.date, "2025-05-15");
+        assert_eq!(release.commit.as_deref(), Some("17067e9ac"));
+        assert!(parse_manifest("date = \"2025-05-15\"\n").is_none());
+    }
+}
@@ -0,0 +1,212 @@
+//! Text embedding backends: a built-in offline hashing model, or any OpenAI-compatible API.
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::error::{DocsError, Result};
+
+/// Env var with an OpenAI-compatible `/embeddings` endpoint; unset uses [`HashingEmbedder`].
+pub const EMBEDDINGS_URL_ENV: &str = "DOCS_MCP_EMBEDDINGS_URL";
+/// Env var naming the model to request from [`EMBEDDINGS_URL_ENV`].
+pub const EMBEDDINGS_MODEL_ENV: &str = "DOCS_MCP_EMBEDDINGS_MODEL";
+/// Env var with a bearer token for [`EMBEDDINGS_URL_ENV`].
+pub const EMBEDDINGS_API_KEY_ENV: &str = "DOCS_MCP_EMBEDDINGS_API_KEY";
+
+const DEFAULT_MODEL: &str = "text-embedding-3-small";
+/// Texts sent per API request.
+const API_BATCH: usize = 64;
+
+/// Words too common in docs to say anything about an item.
+const STOPWORDS: [&str; 24] = [
+    "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "in", "is",
+    "it", "of", "on", "or", "that", "the", "this", "to", "was", "which", "will", "with",
+];
+
+/// Turns texts into fixed-length vectors whose cosine similarity tracks meaning.
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    /// Model name and settings; indexes built by different embedders are stored apart.
+    fn id(&self) -> String;
+
+    /// One vector per text, in order.
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>>;
+}
+
+/// The embedder configured by [`EMBEDDINGS_URL_ENV`], or the offline hashing model.
+pub fn from_env(client: &reqwest_middleware::ClientWithMiddleware) -> Box<dyn Embedder> {
+    let env = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+    match env(EMBEDDINGS_URL_ENV) {
+        Some(url) => Box::new(ApiEmbedder {
+            client: client.clone(),
+            url,
+            model: env(EMBEDDINGS_MODEL_ENV).unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+            api_key: env(EMBEDDINGS_API_KEY_ENV),
+        }),
+        None => Box::new(HashingEmbedder::default()),
+    }
+}
+
+/// Feature-hashed bag of stemmed words and word pairs. Needs no model download and
+/// catches shared vocabulary ("rate limiter" ~ "limit the rate"), not synonyms.
+pub struct HashingEmbedder {
+    pub dims: usize,
+}
+
+impl Default for HashingEmbedder {
+    fn default() -> Self {
+        Self { dims: 384 }
+    }
+}
+
+impl HashingEmbedder {
+    pub fn embed_one(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0f32; self.dims];
+        let words = words(text);
+        let pairs = words.windows(2).map(|w| format!("{} {}", w[0], w[1]));
+        for (feature, weight) in words.iter().cloned().map(|w| (w, 1.0)).chain(pairs.map(|p| (p, 0.5))) {
+            let hash = fnv1a(&feature);
+            let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+            vector[(hash % self.dims as u64) as usize] += sign * weight;
+        }
+        normalize(&mut vector);
+        vector
+    }
+}
+
+#[async_trait]
+impl Embedder for HashingEmbedder {
+    fn id(&self) -> String {
+        format!("hashing-{}", self.dims)
+    }
+
+    /// Hashes on the blocking pool: a crate's docs are tens of thousands of chunks.
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let (embedder, texts) = (Self { dims: self.dims }, texts.to_vec());
+        tokio::task::spawn_blocking(move || texts.iter().map(|t| embedder.embed_one(t)).collect())
+            .await
+            .map_err(|e| DocsError::Other(format!("embedding task failed: {e}")))
+    }
+}
+
+/// An OpenAI-compatible embeddings endpoint (OpenAI, Ollama, llama.cpp, text-embeddings-inference, ...).
+pub struct ApiEmbedder {
+    client: reqwest_middleware::ClientWithMiddleware,
+    url: String,
+    model: String,
+    api_key: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingsResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+#[async_trait]
+impl Embedder for ApiEmbedder {
+    fn id(&self) -> String {
+        self.model.clone()
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(API_BATCH) {
+            let mut request = self.client.post(&self.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(json!({"model": self.model, "input": batch}).to_string());
+            if let Some(key) = &self.api_key {
+                request = request.header(reqwest::header::AUTHORIZATION, format!("Bearer {key}"));
+            }
+            let resp = request.send().await?;
+            if !resp.status().is_success() {
+                return Err(DocsError::Status { status: resp.status(), url: self.url.clone() });
+            }
+            let mut response: EmbeddingsResponse = serde_json::from_str(&resp.text().await?)?;
+            if response.data.len() != batch.len() {
+                return Err(DocsError::Other(format!(
+                    "embeddings endpoint returned {} vectors for {} texts", response.data.len(), batch.len()
+                )));
+            }
+            response.data.sort_by_key(|d| d.index);
+            vectors.extend(response.data.into_iter().map(|d| {
+                let mut v = d.embedding;
+                normalize(&mut v);
+                v
+            }));
+        }
+        Ok(vectors)
+    }
+}
+
+/// Lowercased, stemmed words of `text`, with `snake_case` and `CamelCase` identifiers split.
+fn words(text: &str) -> Vec<String> {
+    let mut words = vec![];
+    for token in text.split(|c: char| !c.is_alphanumeric()).filter(|t| !t.is_empty()) {
+        let mut word = String::new();
+        let mut prev_lower = false;
+        for c in token.chars() {
+            if c.is_uppercase() && prev_lower {
+                words.push(std::mem::take(&mut word));
+            }
+            prev_lower = c.is_lowercase() || c.is_ascii_digit();
+            word.extend(c.to_lowercase());
+        }
+        words.push(word);
+    }
+    words.retain(|w| !STOPWORDS.contains(&w.as_str()));
+    words.iter().map(|w| stem(w).to_string()).collect()
+}
+
+/// Crude suffix stripping, so "limiting", "limited" and "limits" meet at "limit".
+fn stem(word: &str) -> &str {
+    for suffix in ["ing", "ed", "es", "s"] {
+        let stem = word.strip_suffix(suffix).filter(|s| s.len() >= 3 && !s.ends_with('s'));
+        if let Some(stem) = stem {
+            return stem;
+        }
+    }
+    word
+}
+
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// Scale `vector` to unit length, so a dot product is the cosine similarity.
+pub(crate) fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cosine(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b).map(|(x, y)| x * y).sum()
+    }
+
+    #[test]
+    fn splits_identifiers_and_stems_words() {
+        assert_eq!(words("RateLimiter limits the token_bucket"), ["rate", "limiter", "limit", "token", "bucket"]);
+    }
+
+    #[test]
+    fn hashing_embedder_ranks_shared_vocabulary_higher() {
+        let embedder = HashingEmbedder::default();
+        let query = embedder.embed_one("rate limiting with a token bucket");
+        let limiter = embedder.embed_one("A token bucket rate limiter. Limits requests to a fixed rate.");
+        let mutex = embedder.embed_one("An asynchronous mutual exclusion primitive for protecting shared data.");
+        assert!(cosine(&query, &limiter) > 0.4);
+        assert!(cosine(&query, &limiter) > cosine(&query, &mutex) + 0.3);
+        assert!((cosine(&limiter, &limiter) - 1.0).abs() < 1e-5);
+    }
+}
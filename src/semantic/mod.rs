@@ -0,0 +1,263 @@
+//! Embedding-based search over item docs, for conceptual queries ("rate limiting with a
+//! token bucket") that share no words with the item names. Built with the
+//! `semantic-search` feature.
+//!
+//! Each item's path, kind and doc summary, plus its first few prose paragraphs, are
+//! embedded once per crate version and embedder, and stored under `embeddings/` in the
+//! cache directory. The most recently used indexes also stay in memory.
+
+pub mod embedder;
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+pub use embedder::{ApiEmbedder, Embedder, HashingEmbedder, from_env};
+use crate::cache::write_atomic;
+use crate::docsrs::{RustdocJson, SearchResult, search_items_scored, split_doc_examples};
+use crate::error::{DocsError, Result};
+
+/// Longest prose chunk, in bytes; paragraphs are packed together up to this size.
+const MAX_CHUNK_LEN: usize = 800;
+/// Prose chunks kept per item, after its header chunk.
+const MAX_PROSE_CHUNKS: usize = 4;
+/// Chunks embedded per crate version, bounding memory and embedding API cost. Every
+/// item's header chunk comes before any prose.
+const MAX_CHUNKS: usize = 40_000;
+/// Indexes kept in memory.
+const MAX_LOADED: usize = 8;
+/// Cosine similarity below which an item is not a match.
+const MIN_SIMILARITY: f32 = 0.15;
+
+/// Indexes already read or built, by file path, most recently used last.
+static LOADED: Mutex<VecDeque<(PathBuf, Arc<SemanticIndex>)>> = Mutex::new(VecDeque::new());
+
+/// Embedded chunks of one crate version's docs: one row per chunk, tagged with its item.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SemanticIndex {
+    dims: usize,
+    /// Rustdoc ID of each row's item
+    item_ids: Vec<String>,
+    /// Unit-length rows, `dims` floats each
+    vectors: Vec<f32>,
+}
+
+/// First line of a stored index; the vectors follow as little-endian `f32`s.
+#[derive(Serialize, Deserialize)]
+struct IndexHeader {
+    dims: usize,
+    item_ids: Vec<String>,
+}
+
+impl SemanticIndex {
+    /// Embed every named item of `doc`.
+    pub async fn build(doc: &RustdocJson, embedder: &dyn Embedder) -> Result<Self> {
+        let (item_ids, texts): (Vec<String>, Vec<String>) = chunks(doc).into_iter().unzip();
+        let rows = embedder.embed(&texts).await?;
+        let dims = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|r| r.len() != dims) {
+            return Err(DocsError::Other("embedder returned vectors of different lengths".to_string()));
+        }
+        Ok(Self { dims, item_ids, vectors: rows.concat() })
+    }
+
+    /// The index stored for `key` (`name@version`, plus the target if any) and this
+    /// embedder, or a new one built from `doc` and stored.
+    pub async fn load_or_build(cache_dir: &Path, key: &str, doc: &RustdocJson, embedder: &dyn Embedder) -> Result<Arc<Self>> {
+        let path = index_path(cache_dir, &embedder.id(), key);
+        if let Some(index) = loaded(&path) {
+            return Ok(index);
+        }
+        match tokio::fs::read(&path).await {
+            Ok(bytes) => match Self::from_bytes(&bytes) {
+                Some(index) => return Ok(remember(path, index)),
+                None => tracing::warn!("discarding unreadable embedding index {}", path.display()),
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        let started = std::time::Instant::now();
+        let index = Self::build(doc, embedder).await?;
+        tracing::info!("embedded {} chunks for {key} in {:.1}s", index.item_ids.len(), started.elapsed().as_secs_f64());
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        // Each build writes its own temporary file, so concurrent builds of the same
+        // index never interleave and a reader never sees half of one
+        let (bytes, target) = (index.to_bytes()?, path.clone());
+        tokio::task::spawn_blocking(move || write_atomic(&target, &bytes))
+            .await
+            .map_err(|e| DocsError::Other(format!("index write task failed: {e}")))??;
+        Ok(remember(path, index))
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>> {
+        let header = IndexHeader { dims: self.dims, item_ids: self.item_ids.clone() };
+        let mut bytes = serde_json::to_vec(&header)?;
+        bytes.push(b'\n');
+        bytes.extend(self.vectors.iter().flat_map(|x| x.to_le_bytes()));
+        Ok(bytes)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let newline = bytes.iter().position(|&b| b == b'\n')?;
+        let header: IndexHeader = serde_json::from_slice(&bytes[..newline]).ok()?;
+        let body = &bytes[newline + 1..];
+        if body.len() != header.dims * header.item_ids.len() * 4 {
+            return None;
+        }
+        let vectors = body.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect();
+        Some(Self { dims: header.dims, item_ids: header.item_ids, vectors })
+    }
+
+    /// Each item's best similarity to `query` (a unit vector) over its chunks.
+    pub fn similarities(&self, query: &[f32]) -> HashMap<&str, f32> {
+        let mut best: HashMap<&str, f32> = HashMap::new();
+        if query.len() != self.dims || self.dims == 0 {
+            return best;
+        }
+        for (id, row) in self.item_ids.iter().zip(self.vectors.chunks_exact(self.dims)) {
+            let similarity: f32 = row.iter().zip(query).map(|(a, b)| a * b).sum();
+            let entry = best.entry(id.as_str()).or_insert(f32::MIN);
+            *entry = entry.max(similarity);
+        }
+        best
+    }
+}
+
+/// Rank the items of `doc` by how close their docs are to `query` in meaning, with the
+/// same filters and result shape as [`crate::docsrs::search_items`].
+#[allow(clippy::too_many_arguments)]
+pub async fn search(
+    doc: &RustdocJson,
+    index: &SemanticIndex,
+    embedder: &dyn Embedder,
+    query: &str,
+    kind_filter: Option<&str>,
+    module_prefix: Option<&str>,
+    limit: usize,
+    declared_features: &HashSet<String>,
+    include_hidden: bool,
+) -> Result<Vec<SearchResult>> {
+    let query_vector = embedder.embed(&[query.to_string()]).await?.pop().unwrap_or_default();
+    let similarities = index.similarities(&query_vector);
+    let score = |c: &crate::docsrs::SearchCandidate| {
+        similarities.get(c.id).copied()
+            .filter(|&s| s >= MIN_SIMILARITY)
+            .map(|s| (s * 1000.0).round() / 1000.0)
+    };
+    Ok(search_items_scored(doc, kind_filter, module_prefix, limit, declared_features, include_hidden, score))
+}
+
+/// The in-memory index for `path`, marked as most recently used.
+fn loaded(path: &Path) -> Option<Arc<SemanticIndex>> {
+    let mut loaded = LOADED.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+    let position = loaded.iter().position(|(p, _)| p == path)?;
+    let entry = loaded.remove(position)?;
+    let index = entry.1.clone();
+    loaded.push_back(entry);
+    Some(index)
+}
+
+/// Keep `index` in memory, dropping the least recently used past [`MAX_LOADED`].
+fn remember(path: PathBuf, index: SemanticIndex) -> Arc<SemanticIndex> {
+    let index = Arc::new(index);
+    let mut loaded = LOADED.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+    loaded.retain(|(p, _)| *p != path);
+    loaded.push_back((path, index.clone()));
+    while loaded.len() > MAX_LOADED {
+        loaded.pop_front();
+    }
+    index
+}
+
+fn index_path(cache_dir: &Path, embedder_id: &str, key: &str) -> PathBuf {
+    let safe = |s: &str| s.chars().map(|c| if c.is_alphanumeric() || "-_.@".contains(c) { c } else { '_' }).collect::<String>();
+    cache_dir.join("embeddings").join(safe(embedder_id)).join(format!("{}.idx", safe(key)))
+}
+
+/// `(item id, text)` chunks to embed: a header naming each item, then its prose
+/// paragraphs, at most [`MAX_CHUNKS`] in all.
+pub fn chunks(doc: &RustdocJson) -> Vec<(String, String)> {
+    let method_parents = doc.method_parents();
+    let mut chunks = vec![];
+    let mut prose_rows = vec![];
+    for (id, item) in &doc.index {
+        let Some(name) = item.name.as_deref().filter(|n| !n.is_empty()) else { continue };
+        let (path, kind) = match (doc.paths.get(id), method_parents.get(id)) {
+            (Some(entry), _) => (entry.full_path(), entry.kind_name().to_string()),
+            (None, Some(parent)) if item.kind() == Some("function") => (format!("{parent}::{name}"), "method".to_string()),
+            _ => continue,
+        };
+        chunks.push((id.clone(), format!("{kind} {path}: {}", item.doc_summary())));
+
+        let (prose, _) = split_doc_examples(item.docs.as_deref().unwrap_or(""));
+        let mut chunk = String::new();
+        let mut prose_chunks = 0;
+        // The first paragraph is the summary, already in the header chunk
+        for paragraph in prose.split("\n\n").skip(1).map(str::trim).filter(|p| !p.is_empty()) {
+            if prose_chunks == MAX_PROSE_CHUNKS {
+                break;
+            }
+            if !chunk.is_empty() && chunk.len() + paragraph.len() > MAX_CHUNK_LEN {
+                prose_rows.push((id.clone(), format!("{path}: {}", std::mem::take(&mut chunk))));
+                prose_chunks += 1;
+            }
+            if !chunk.is_empty() {
+                chunk.push('\n');
+            }
+            let mut end = paragraph.len().min(MAX_CHUNK_LEN);
+            while !paragraph.is_char_boundary(end) {
+                end -= 1;
+            }
+            chunk.push_str(&paragraph[..end]);
+        }
+        if !chunk.is_empty() && prose_chunks < MAX_PROSE_CHUNKS {
+            prose_rows.push((id.clone(), format!("{path}: {chunk}")));
+        }
+    }
+    chunks.extend(prose_rows);
+    if chunks.len() > MAX_CHUNKS {
+        tracing::info!("embedding the first {MAX_CHUNKS} of {} doc chunks", chunks.len());
+        chunks.truncate(MAX_CHUNKS);
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_round_trips_through_bytes() {
+        let index = SemanticIndex {
+            dims: 2,
+            item_ids: vec!["1".to_string(), "1".to_string(), "7".to_string()],
+            vectors: vec![1.0, 0.0, 0.6, 0.8, 0.0, -1.0],
+        };
+        assert_eq!(SemanticIndex::from_bytes(&index.to_bytes().unwrap()), Some(index.clone()));
+
+        // Best chunk per item
+        let similarities = index.similarities(&[0.0, 1.0]);
+        assert!((similarities["1"] - 0.8).abs() < 1e-6);
+        assert!((similarities["7"] + 1.0).abs() < 1e-6);
+
+        let truncated = &index.to_bytes().unwrap()[..40];
+        assert_eq!(SemanticIndex::from_bytes(truncated), None);
+    }
+
+    #[test]
+    fn loaded_indexes_are_kept_most_recent_last() {
+        let index = |dims| SemanticIndex { dims, item_ids: vec![], vectors: vec![] };
+        let path = |i: usize| PathBuf::from(format!("/nonexistent/loaded-test-{i}.idx"));
+        let first = remember(path(0), index(1));
+        assert!(Arc::ptr_eq(&loaded(&path(0)).unwrap(), &first));
+        for i in 1..=MAX_LOADED {
+            remember(path(i), index(2));
+        }
+        assert!(loaded(&path(0)).is_none(), "the least recently used index is dropped");
+        assert!(loaded(&path(MAX_LOADED)).is_some());
+    }
+}
@@ -1,36 +1,82 @@
 use std::sync::Arc;
+use std::time::Instant;
 
 use rmcp::{
     ErrorData as McpError,
+    RoleServer,
     ServerHandler,
     handler::server::{
         router::tool::ToolRouter,
+        tool::ToolCallContext,
         wrapper::Parameters,
     },
     model::*,
-    tool, tool_handler, tool_router,
+    service::{NotificationContext, RequestContext},
+    tool, tool_router,
 };
 
+use crate::call_memo::{self, CallMemo};
+use crate::house_rules::HouseRules;
+use crate::large_output::{self, OutputLimit};
+use crate::error::ErrorCode as ToolErrorCode;
+use crate::mcp_log::McpLogger;
+use crate::session::{self, SessionLog};
+use crate::telemetry::{self, Outcome};
+use crate::tool_filter::{self, ToolFilter};
 use crate::tools::{
     AppState,
     crate_list::{self, CrateListParams},
     crate_get::{self, CrateGetParams},
     crate_readme_get::{self, CrateReadmeGetParams},
+    crate_files_list::{self, CrateFilesListParams},
+    crate_file_get::{self, CrateFileGetParams},
     crate_docs_get::{self, CrateDocsGetParams},
+    crate_docs_search::{self, CrateDocsSearchParams},
+    crate_module_expand::{self, CrateModuleExpandParams},
     crate_item_list::{self, CrateItemListParams},
+    multi_crate_item_search::{self, MultiCrateItemSearchParams},
     crate_item_get::{self, CrateItemGetParams},
+    crate_item_compare::{self, CrateItemCompareParams},
+    crate_items_get_many::{self, CrateItemsGetManyParams},
     crate_impls_list::{self, CrateImplsListParams},
+    crate_impl_get::{self, CrateImplGetParams},
+    crate_trait_check::{self, CrateTraitCheckParams},
+    crate_trait_gap::{self, CrateTraitGapParams},
+    crate_ffi_list::{self, CrateFfiListParams},
+    crate_errors_list::{self, CrateErrorsListParams},
+    crate_async_list::{self, CrateAsyncListParams},
     crate_versions_list::{self, CrateVersionsListParams},
     crate_version_get::{self, CrateVersionGetParams},
+    crate_yanks_list::{self, CrateYanksListParams},
+    crate_scorecard_get::{self, CrateScorecardGetParams},
+    crate_build_risk::{self, CrateBuildRiskParams},
+    crate_audit_report::{self, CrateAuditReportParams},
+    owner_crates_list::{self, OwnerCratesListParams},
+    crate_maintenance_get::{self, CrateMaintenanceGetParams},
+    rust_toolchain_get::{self, RustToolchainGetParams},
+    crate_no_std_check::{self, CrateNoStdCheckParams},
     crate_dependencies_list::{self, CrateDependenciesListParams},
     crate_dependents_list::{self, CrateDependentsListParams},
     crate_downloads_get::{self, CrateDownloadsGetParams},
+    crate_health_report::{self, CrateHealthReportParams},
+    server_status,
+    server_stats,
+    session_context,
+    docs_mcp_help::{self, DocsMcpHelpParams},
+    crates_analyze::{self, CratesAnalyzeParams},
+    cache_warm::{self, CacheWarmParams},
 };
 
 #[derive(Clone)]
 pub struct DocsMcpServer {
     tool_router: ToolRouter<DocsMcpServer>,
     state: Arc<AppState>,
+    logger: Option<Arc<McpLogger>>,
+    memo: Arc<CallMemo>,
+    house_rules: Option<Arc<HouseRules>>,
+    output_limit: OutputLimit,
+    /// What this handler's client has looked at and the requests its calls caused
+    session: Arc<SessionLog>,
 }
 
 #[tool_router]
@@ -39,10 +85,70 @@ impl DocsMcpServer {
         Self {
             tool_router: Self::tool_router(),
             state,
+            logger: None,
+            memo: Arc::new(CallMemo::disabled()),
+            house_rules: None,
+            output_limit: OutputLimit::disabled(),
+            session: Arc::default(),
         }
     }
 
-    #[tool(description = "Search crates.io by keyword, category, or free-text query. Returns crate summaries ranked by relevance, download count, or recency. Entry point for crate discovery when you don't have a crate name yet.")]
+    /// Answer repeated identical tool calls from `memo` (see [`crate::call_memo`]).
+    pub fn with_call_memo(mut self, memo: CallMemo) -> Self {
+        self.memo = Arc::new(memo);
+        self
+    }
+
+    /// A handler for another client: it shares the state, tool list, logger and house
+    /// rules, and starts with an empty memo and session log.
+    pub fn new_session(&self) -> Self {
+        Self {
+            memo: Arc::new(CallMemo::new(self.memo.ttl())),
+            session: Arc::default(),
+            ..self.clone()
+        }
+    }
+
+    /// Store results larger than `limit` and return links to them (see
+    /// [`crate::large_output`]).
+    pub fn with_output_limit(mut self, limit: OutputLimit) -> Self {
+        self.output_limit = limit;
+        self
+    }
+
+    /// Register only the tools `filter` allows (see [`crate::tool_filter`]).
+    pub fn with_tool_filter(mut self, filter: &ToolFilter) -> Self {
+        let tools = self.tool_router.list_all();
+        for unknown in filter.unknown_names(&tools) {
+            tracing::warn!("tool filter: '{unknown}' is neither a tool nor a group; ignoring it");
+        }
+        for tool in tools.iter().filter(|t| !filter.allows(t)) {
+            self.tool_router.remove_route(&tool.name);
+        }
+        if self.tool_router.list_all().is_empty() {
+            tracing::warn!("tool filter leaves no tools registered");
+        }
+        self
+    }
+
+    /// The registered tools.
+    pub fn tools(&self) -> Vec<Tool> {
+        self.tool_router.list_all()
+    }
+
+    /// Send the operator's `rules` with the server instructions (see [`crate::house_rules`]).
+    pub fn with_house_rules(mut self, rules: HouseRules) -> Self {
+        self.house_rules = Some(Arc::new(rules));
+        self
+    }
+
+    /// Forward tracing events to the client through `logger` (see [`crate::mcp_log`]).
+    pub fn with_logger(mut self, logger: Arc<McpLogger>) -> Self {
+        self.logger = Some(logger);
+        self
+    }
+
+    #[tool(description = "Search crates.io by keyword, category, or free-text query. Returns crate summaries ranked by relevance, download count, or recency. Entry point for crate discovery when you don't have a crate name yet. Narrow results to adoptable crates with license (e.g. \"MIT,Apache-2.0\"), max_rust_version (e.g. \"1.70\") and max_edition (e.g. \"2021\"); rejected names are listed in filtered_out. Set detail to \"names\" for a cheap list of crate names or \"full\" for complete crates.io records.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("docs"))]
     async fn crate_list(
         &self,
         Parameters(params): Parameters<CrateListParams>,
@@ -50,7 +156,7 @@ impl DocsMcpServer {
         crate_list::execute(&self.state, params).await
     }
 
-    #[tool(description = "Get comprehensive metadata for a single crate: description, homepage, repository, download counts, latest stable version, feature flag definitions, and MSRV. Combines crates.io API with the sparse index for authoritative feature map.")]
+    #[tool(description = "Get comprehensive metadata for a single crate: description, homepage, repository, download counts, latest stable version with its release date and MSRV, feature flag definitions, and whether docs.rs has a build (docs_available). Pass version to get that release's features, MSRV, dependency count and yank status instead of the latest stable's. Combines crates.io API with the sparse index for authoritative feature map.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("docs"))]
     async fn crate_get(
         &self,
         Parameters(params): Parameters<CrateGetParams>,
@@ -58,7 +164,7 @@ impl DocsMcpServer {
         crate_get::execute(&self.state, params).await
     }
 
-    #[tool(description = "Fetch the crate's README for a specific version as readable text. Contains the author's intended narrative: why the crate exists, how it compares to alternatives, installation instructions, and quick-start examples. Prefer crate_docs_get when you want structured docs plus a module tree; use this tool when you want the raw README prose.")]
+    #[tool(description = "Fetch the crate's README for a specific version as readable text. Contains the author's intended narrative: why the crate exists, how it compares to alternatives, installation instructions, and quick-start examples. Prefer crate_docs_get when you want structured docs plus a module tree; use this tool when you want the raw README prose. Pass format='markdown' for the original README file from the published package, with links and tables intact. Pass section='Usage' (or any heading) to get just that part and skip badges and boilerplate.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("docs"))]
     async fn crate_readme_get(
         &self,
         Parameters(params): Parameters<CrateReadmeGetParams>,
@@ -66,7 +172,23 @@ impl DocsMcpServer {
         crate_readme_get::execute(&self.state, params).await
     }
 
-    #[tool(description = "Get high-level documentation structure from rustdoc JSON: the crate-level //! documentation (architecture overview, feature table, usage examples), module tree, and per-module item summaries. Falls back to README when docs.rs has no build yet. Primary entry point for understanding a library you're already using. Use crate_readme_get instead only when you specifically want the raw README prose.")]
+    #[tool(description = "List the files a published crate version ships (paths and sizes), read from the .crate archive on crates.io. Finds content rustdoc never shows: proto files, SQL migrations, config templates, examples, benches, build scripts. Filter with a glob pattern such as '*.proto' or 'migrations/**'.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("releases"))]
+    async fn crate_files_list(
+        &self,
+        Parameters(params): Parameters<CrateFilesListParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate_files_list::execute(&self.state, params).await
+    }
+
+    #[tool(description = "Read one file from a published crate version's .crate archive, e.g. build.rs, an example, or an embedded JSON schema. Text is returned as-is up to max_bytes; binary files report only their size. Use crate_files_list to find paths.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("releases"))]
+    async fn crate_file_get(
+        &self,
+        Parameters(params): Parameters<CrateFileGetParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate_file_get::execute(&self.state, params).await
+    }
+
+    #[tool(description = "Get high-level documentation structure from rustdoc JSON: the crate-level //! documentation (architecture overview, feature table, usage examples), module tree, and per-module item summaries. Pass module (e.g. 'tokio::sync') and max_depth to fetch one subtree of a large crate. Pass sections=['toc'] for the docs' table of contents, or heading titles (e.g. ['Feature flags']) to get only those sections. Falls back to README when docs.rs has no build yet. Primary entry point for understanding a library you're already using. Use crate_readme_get instead only when you specifically want the raw README prose.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("docs"))]
     async fn crate_docs_get(
         &self,
         Parameters(params): Parameters<CrateDocsGetParams>,
@@ -74,7 +196,15 @@ impl DocsMcpServer {
         crate_docs_get::execute(&self.state, params).await
     }
 
-    #[tool(description = "Search for items (types, functions, traits, methods, etc.) within a crate's API by name or concept. Returns ranked results with signatures and doc summaries. Use kind='method' to search inherent methods on types. Use after crate_docs_get to find specific items without browsing the module tree. Use crate_item_get once you know the exact fully-qualified path of the item you want.")]
+    #[tool(description = "Expand one module of a crate: its direct items (kind, path, doc summary) and its immediate submodules with item counts and has_submodules. Omit path to start at the crate root, then call again with a submodule's path to drill down. Use this instead of crate_docs_get's full module tree for very large crates.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("docs"))]
+    async fn crate_module_expand(
+        &self,
+        Parameters(params): Parameters<CrateModuleExpandParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate_module_expand::execute(&self.state, params).await
+    }
+
+    #[tool(description = "Search for items (types, functions, traits, methods, etc.) within a crate's API by name or concept. Returns ranked results with signatures and doc summaries. Use kind='method' to search inherent methods on types. On servers built with the semantic-search feature, semantic=true ranks by meaning for conceptual queries ('rate limiting with a token bucket'). Use after crate_docs_get to find specific items without browsing the module tree. Use crate_item_get once you know the exact fully-qualified path of the item you want.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("docs"))]
     async fn crate_item_list(
         &self,
         Parameters(params): Parameters<CrateItemListParams>,
@@ -82,7 +212,23 @@ impl DocsMcpServer {
         crate_item_list::execute(&self.state, params).await
     }
 
-    #[tool(description = "Get complete documentation for a specific item by fully-qualified path. Returns the full doc comment, exact type signature, generic parameters, where clauses, inherent methods, implemented traits, and feature flags. Primary API reference tool. Requires knowing the exact path — use crate_item_list first to search if you don't have it.")]
+    #[tool(description = "Full-text search over the complete doc comments of a crate's items and methods, not just their names and first lines: finds where the docs discuss a topic, e.g. \"cancel safe\", \"backpressure\" or \"blocking\". Returns items ranked by BM25 relevance, each with the query terms it matched, the doc section holding the best match (e.g. \"Cancel safety\") and a snippet with matches in **bold**. The index is built on the first search of a crate version. Use crate_item_list to find items by name; use crate_item_get to read a result in full.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("docs"))]
+    async fn crate_docs_search(
+        &self,
+        Parameters(params): Parameters<CrateDocsSearchParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate_docs_search::execute(&self.state, params).await
+    }
+
+    #[tool(description = "Run one item search across several crates at once, e.g. all direct dependencies of a project: \"which of my deps has a retry/backoff utility?\". Takes up to 30 crate names (optionally pinned as name@version) and returns each crate's ranked matches, crates with the strongest match first. Crates without matches or without docs are listed separately instead of failing the call. Use crate_item_list to search a single crate.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("docs"))]
+    async fn multi_crate_item_search(
+        &self,
+        Parameters(params): Parameters<MultiCrateItemSearchParams>,
+    ) -> Result<CallToolResult, McpError> {
+        multi_crate_item_search::execute(&self.state, params).await
+    }
+
+    #[tool(description = "Get complete documentation for a specific item by fully-qualified path. Returns the doc comment prose, its code blocks as an examples array (language, no_run/ignore attributes, and the full doctest with hidden lines), its Panics, Errors and Safety sections as separate fields (also on methods that have them), exact type signature, generic parameters, where clauses, inherent methods, implemented traits, and feature flags. Also lists related items: the parent module with its summary and other public items beside this one. Structs and enums also list constructors: associated functions returning Self (new, from_*, try_*) and Default/From/TryFrom/FromStr impls. For traits, pass impl_for (a type name) to get a compile-ready impl skeleton with exact signatures and todo!() bodies. For types whose API comes mostly from trait impls (iterator adapters, Deref wrappers), pass include_trait_impl_methods=true to expand each trait impl with its method signatures, associated types and inherited provided methods. Method paths (Type::method, Trait::method) return the method with the impl block that defines it. Primary API reference tool. Requires knowing the exact path — use crate_item_list first to search if you don't have it.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("docs"))]
     async fn crate_item_get(
         &self,
         Parameters(params): Parameters<CrateItemGetParams>,
@@ -90,7 +236,23 @@ impl DocsMcpServer {
         crate_item_get::execute(&self.state, params).await
     }
 
-    #[tool(description = "Find implementors of a trait, or all traits implemented by a type. Answers: 'what do I need to implement to use this abstraction?' and 'what can I call on this type?' Requires either trait_path (e.g. 'Default') to find types implementing that trait, or type_path (e.g. 'MyStruct') to find all traits a type implements. Use crate_item_list to discover valid type/trait names first.")]
+    #[tool(description = "Compare two items side by side, from the same crate or different crates (e.g. parking_lot::Mutex vs tokio::sync::Mutex). Returns each item's signature, generics and doc summary, then aligns methods, fields and trait impls into common / only_a / only_b. Use when choosing between alternative APIs; use crate_item_get for one item's full details.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("docs"))]
+    async fn crate_item_compare(
+        &self,
+        Parameters(params): Parameters<CrateItemCompareParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate_item_compare::execute(&self.state, params).await
+    }
+
+    #[tool(description = "Get several items from one crate in a single call: up to 20 fully-qualified item or method paths, each returned as crate_item_get would return it, from one load of the crate's docs. A path that cannot be found gets an error entry with suggestions instead of failing the batch. Use instead of repeated crate_item_get calls when you already know the paths you need.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("docs"))]
+    async fn crate_items_get_many(
+        &self,
+        Parameters(params): Parameters<CrateItemsGetManyParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate_items_get_many::execute(&self.state, params).await
+    }
+
+    #[tool(description = "Find implementors of a trait, or all traits implemented by a type. Answers: 'what do I need to implement to use this abstraction?' and 'what can I call on this type?' Requires either trait_path (e.g. 'Default') to find types implementing that trait, or type_path (e.g. 'MyStruct') to find all traits a type implements. Each result carries the full impl header with generics and where clause, plus its defining module and source link. Trait impls also carry an origin (local_trait_local_type, foreign_trait_local_type, local_trait_foreign_type, or a *_blanket form) with the crates defining the trait and the type, for orphan-rule questions and for knowing which crate's docs to read next. Use crate_item_list to discover valid type/trait names first.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("docs"))]
     async fn crate_impls_list(
         &self,
         Parameters(params): Parameters<CrateImplsListParams>,
@@ -98,7 +260,55 @@ impl DocsMcpServer {
         crate_impls_list::execute(&self.state, params).await
     }
 
-    #[tool(description = "List all published versions with feature maps, MSRV, dependency counts, and yank status. Use to understand release history, find when a feature was introduced, audit yanked versions, or compare features across versions.")]
+    #[tool(description = "Expand a single impl block: the impl header with generics and where clauses, the concrete method signatures it provides, associated types/consts, and which trait methods fall back to defaults. Answers 'what does this blanket impl actually give me?'. Pass impl_id from crate_impls_list, or trait_path (plus type_path for a specific type).", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("docs"))]
+    async fn crate_impl_get(
+        &self,
+        Parameters(params): Parameters<CrateImplGetParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate_impl_get::execute(&self.state, params).await
+    }
+
+    #[tool(description = "Answer 'does this type implement this trait?' within a crate. Considers direct impls, generic impls on the type (reported with their constraints), and blanket impls such as impl<T: Clone> Trait for T whose bounds the type meets. Returns a verdict (yes / conditional / no) and the matching impls with impl_id for crate_impl_get. Unmet blanket-impl bounds are listed to explain a 'no'.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("docs"))]
+    async fn crate_trait_check(
+        &self,
+        Parameters(params): Parameters<CrateTraitCheckParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate_trait_check::execute(&self.state, params).await
+    }
+
+    #[tool(description = "Plan implementing a trait for a type: lists which of the trait's methods, associated types and consts the type's existing impls already provide, which required ones are still missing (with exact signatures), and which fall back to defaults. Returns a ready-to-fill impl skeleton with todo!() bodies for the missing items. Pass trait_crate when the trait comes from another crate (e.g. serde).", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("docs"))]
+    async fn crate_trait_gap(
+        &self,
+        Parameters(params): Parameters<CrateTraitGapParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate_trait_gap::execute(&self.state, params).await
+    }
+
+    #[tool(description = "List a crate's FFI surface from its rustdoc JSON: functions with a foreign ABI (extern \"C\", \"system\", ...), functions exported with #[no_mangle]/#[export_name], functions whose signatures take or return raw pointers, and structs/enums/unions with a C, transparent or integer repr. Use when binding Rust to another language or auditing the unsafe boundary. Pass target for a platform-specific build.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("docs"))]
+    async fn crate_ffi_list(
+        &self,
+        Parameters(params): Parameters<CrateFfiListParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate_ffi_list::execute(&self.state, params).await
+    }
+
+    #[tool(description = "Catalogue a crate's error types: every public type implementing std::error::Error or named *Error, with its enum variants (e.g. Io(std::io::Error)) and their docs, the types it converts from via From (what ? turns into it) and the types it converts into. Use to see an API's failure modes and how its errors compose with yours.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("docs"))]
+    async fn crate_errors_list(
+        &self,
+        Parameters(params): Parameters<CrateErrorsListParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate_errors_list::execute(&self.state, params).await
+    }
+
+    #[tool(description = "List a crate's async API surface grouped by module: async fns, and functions or methods returning impl Future, Pin<Box<dyn Future>>, BoxFuture, impl Stream or BoxStream. The summary gives counts and async_share, the fraction of public functions that are async. Use to judge whether a crate is async-first or a sync library with a few async helpers.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("docs"))]
+    async fn crate_async_list(
+        &self,
+        Parameters(params): Parameters<CrateAsyncListParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate_async_list::execute(&self.state, params).await
+    }
+
+    #[tool(description = "List all published versions with feature maps, MSRV, dependency counts, and yank status. Use to understand release history, find when a feature was introduced, audit yanked versions, or compare features across versions. Pass max_rust_version (e.g. \"1.70\") to keep only versions whose MSRV fits that toolchain; newest_compatible then names the newest usable version. Pass cadence=true for release dates and a maintenance summary: releases per year, median days between releases, days since the last release.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("releases"))]
     async fn crate_versions_list(
         &self,
         Parameters(params): Parameters<CrateVersionsListParams>,
@@ -106,7 +316,7 @@ impl DocsMcpServer {
         crate_versions_list::execute(&self.state, params).await
     }
 
-    #[tool(description = "Get rich per-version metadata from crates.io: Rust edition, library vs binary targets, binary names, line counts, license, and publisher. Use after crate_versions_list when you need details beyond what the index provides.")]
+    #[tool(description = "Get rich per-version metadata from crates.io: Rust edition, library vs binary targets, binary names, line counts, license, and publisher, including whether the release was published by a CI workflow through trusted publishing (with the repository, commit and run link) or from a personal API token. Use after crate_versions_list when you need details beyond what the index provides.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("releases"))]
     async fn crate_version_get(
         &self,
         Parameters(params): Parameters<CrateVersionGetParams>,
@@ -114,7 +324,23 @@ impl DocsMcpServer {
         crate_version_get::execute(&self.state, params).await
     }
 
-    #[tool(description = "Get the dependency list for a crate version with semver requirements, optional flags, enabled features, and target conditions. Version defaults to latest stable. Use for due diligence: a large or unusual dependency tree is a risk multiplier.")]
+    #[tool(description = "List a crate's yanked versions with the yank message the publisher left, cross-referenced against RustSec and GitHub advisories (via OSV.dev). Each yanked version names the advisories whose affected ranges cover it and a security_related flag. Use for due diligence when the sparse index only tells you a version was yanked, not why.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("releases"))]
+    async fn crate_yanks_list(
+        &self,
+        Parameters(params): Parameters<CrateYanksListParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate_yanks_list::execute(&self.state, params).await
+    }
+
+    #[tool(description = "Check whether a crate supports no_std, read from the #![no_std] / #![cfg_attr(..., no_std)] attributes in its published crate root and its feature map. Reports support (unconditional, conditional or unsupported), the features that turn std back on, whether default features enable them, any alloc feature, and a ready-to-paste dependency line for a no_std build. Also lists required dependencies built with default features, which may pull std back in. Version defaults to latest stable.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("releases"))]
+    async fn crate_no_std_check(
+        &self,
+        Parameters(params): Parameters<CrateNoStdCheckParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate_no_std_check::execute(&self.state, params).await
+    }
+
+    #[tool(description = "Get the dependency list for a crate version with semver requirements, optional flags, enabled features, and target conditions. Version defaults to latest stable. Pass depth (e.g. 3) to follow normal dependencies through the sparse index and get a flattened, deduplicated transitive list with the path that pulled each crate in. Pass resolve=true to see the version each requirement selects today, with its MSRV and yank status. Pass audit=true to list OSV.dev vulnerability records (RustSec and GitHub advisories, with fixed versions) against this version and every resolved dependency. Pass graph='dot' or graph='mermaid' to also get the resolved tree as Graphviz or Mermaid text to paste into docs or chat. Use for due diligence: a large or unusual dependency tree is a risk multiplier.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("releases"))]
     async fn crate_dependencies_list(
         &self,
         Parameters(params): Parameters<CrateDependenciesListParams>,
@@ -122,7 +348,7 @@ impl DocsMcpServer {
         crate_dependencies_list::execute(&self.state, params).await
     }
 
-    #[tool(description = "List crates that depend on a given crate (reverse dependencies). Reveals ecosystem adoption breadth. A crate trusted by 5000 other crates has a different risk profile than one with 20. Use for due diligence.")]
+    #[tool(description = "List crates that depend on a given crate (reverse dependencies). Reveals ecosystem adoption breadth. A crate trusted by 5000 other crates has a different risk profile than one with 20. Pass sort=\"downloads\" and min_downloads to see the most serious users first, or sort=\"recent\" for the newest dependent releases. Use for due diligence.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("due_diligence"))]
     async fn crate_dependents_list(
         &self,
         Parameters(params): Parameters<CrateDependentsListParams>,
@@ -130,23 +356,234 @@ impl DocsMcpServer {
         crate_dependents_list::execute(&self.state, params).await
     }
 
-    #[tool(description = "Get per-day download counts broken out by version for the past 90 days. Use to assess active ecosystem adoption, whether users have migrated to newer versions, and whether a download spike indicates recent adoption by a major project.")]
+    #[tool(description = "Get per-day download counts broken out by version for the past 90 days. Use to assess active ecosystem adoption, whether users have migrated to newer versions, and whether a download spike indicates recent adoption by a major project. Pass compare_with (e.g. [\"async-std\"]) to compare crates: per-crate totals, share of combined downloads, and daily counts aligned by date.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("due_diligence"))]
     async fn crate_downloads_get(
         &self,
         Parameters(params): Parameters<CrateDownloadsGetParams>,
     ) -> Result<CallToolResult, McpError> {
         crate_downloads_get::execute(&self.state, params).await
     }
+
+    #[tool(description = "Get deps.dev's view of a crate version: licenses, known advisories, the size of its resolved dependency graph (include_dependencies=true lists each crate), and the OpenSSF Scorecard of its source repository with per-check scores (Maintained, Code-Review, Branch-Protection, Fuzzing, ...), weakest first. Version defaults to latest stable. Use when a security review wants an external, recognized signal rather than this server's own heuristics.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("due_diligence"))]
+    async fn crate_scorecard_get(
+        &self,
+        Parameters(params): Parameters<CrateScorecardGetParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate_scorecard_get::execute(&self.state, params).await
+    }
+
+    #[tool(description = "Check what building a crate runs besides compiling Rust: whether the published package has a build script (build.rs), declares a native library through links, or is a proc macro, then check every crate in its resolved dependency tree (normal and build dependencies) the same way and list the ones that run build-time code. Package archives are verified against the index checksum. Version defaults to latest stable; depth=0 checks only the crate itself. Use for supply-chain review: build scripts and proc macros run arbitrary code on the build machine.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("due_diligence"))]
+    async fn crate_build_risk(
+        &self,
+        Parameters(params): Parameters<CrateBuildRiskParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate_build_risk::execute(&self.state, params).await
+    }
+
+    #[tool(description = "One-call supply-chain review of a crate version: owners (users and teams), download trend (last 30 days against the 30 before), dependent count, OSV advisories affecting the version, yank history, repository health from deps.dev (stars, open issues, OpenSSF Scorecard and its weak checks), and dependency counts by kind, topped with a list of flags worth a closer look. Sections that fail to load are reported under errors without failing the report. format=\"markdown\" returns a readable summary instead of JSON. Use instead of calling the yanks, downloads, dependents, dependencies and scorecard tools one by one.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("due_diligence"))]
+    async fn crate_audit_report(
+        &self,
+        Parameters(params): Parameters<CrateAuditReportParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate_audit_report::execute(&self.state, params).await
+    }
+
+    #[tool(description = "List the crates a crates.io user or team owns, most downloaded first, with each crate's version, downloads and repository. Pass a user login (e.g. \"dtolnay\") or a team login as \"github:{org}:{team}\", as crate owner lists show them. Use when assessing trust in an author: how established their other crates are, and whether a crate is one of many or their only one.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("due_diligence"))]
+    async fn owner_crates_list(
+        &self,
+        Parameters(params): Parameters<OwnerCratesListParams>,
+    ) -> Result<CallToolResult, McpError> {
+        owner_crates_list::execute(&self.state, params).await
+    }
+
+    #[tool(description = "Classify whether a crate is actively maintained, in maintenance mode, or likely abandoned, from the age of its last stable release, its release cadence and recently yanked releases, with the evidence behind the call. include_repository=true also weighs commit and issue activity in the source repository (the OpenSSF Scorecard \"Maintained\" check from deps.dev), which keeps a crate with an active repository but rare releases from reading as abandoned. Use before depending on a crate that has not released in a while.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("due_diligence"))]
+    async fn crate_maintenance_get(
+        &self,
+        Parameters(params): Parameters<CrateMaintenanceGetParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate_maintenance_get::execute(&self.state, params).await
+    }
+
+    #[tool(description = "Get the Rust versions the stable, beta and nightly channels serve today, with their release dates and rustc commits, from the static.rust-lang.org channel manifests. Pass rust_version (e.g. \"1.70\", a crate's MSRV) to date it on the six-week stable schedule: when it was released, how many months ago, and how many releases behind current stable it is. Use to frame an MSRV as \"requires a Rust released N months ago\" rather than a bare version number.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("releases"))]
+    async fn rust_toolchain_get(
+        &self,
+        Parameters(params): Parameters<RustToolchainGetParams>,
+    ) -> Result<CallToolResult, McpError> {
+        rust_toolchain_get::execute(&self.state, params).await
+    }
+
+    #[tool(description = "Summarize a crate's health for due diligence. Currently reports API stability: diffs the public API (items and signatures) across recent minor releases and returns per-release added/removed/changed counts, a mean churn ratio, and a stability rating. Use when weighing whether a library breaks its API often.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("due_diligence"))]
+    async fn crate_health_report(
+        &self,
+        Parameters(params): Parameters<CrateHealthReportParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate_health_report::execute(&self.state, params).await
+    }
+
+    #[tool(description = "Cross-crate analysis over the metadata this server has gathered (versions, dependencies, MSRVs, owners). analysis=\"dependents_skew\": which requirement ranges known dependents use for each crate and whether they accept its latest release. analysis=\"owner_overlap\": owners shared between the crates. analysis=\"stack\": dependencies the crates have in common, to spot version conflicts in a planned stack.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = true), meta = tool_filter::group("due_diligence"))]
+    async fn crates_analyze(
+        &self,
+        Parameters(params): Parameters<CratesAnalyzeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crates_analyze::execute(&self.state, params).await
+    }
+
+    #[tool(description = "Prefetch crates into the local cache before going offline or starting a large task: the sparse index, crates.io metadata and rustdoc JSON of each crate's resolved version. Pass crate names (optionally name@version) or manifest_path to a Cargo.toml under the server's working directory to warm all of its dependencies at the newest version matching each requirement. Runs in the background by default; call again with no crates for progress, pass cancel=true to stop it, or pass wait=true to block until it finishes. Reports which crates have no docs.rs build.", annotations(destructive_hint = false, open_world_hint = true), meta = tool_filter::group("server"))]
+    async fn cache_warm(
+        &self,
+        Parameters(params): Parameters<CacheWarmParams>,
+    ) -> Result<CallToolResult, McpError> {
+        cache_warm::execute(&self.state, params).await
+    }
+
+    #[tool(description = "Report docs-mcp's own status: version, cache directory, the effective crates.io request rate (auto-tuned from 429s seen in previous runs), per-host request counts for this session and across runs, and the age of any local crates.io DB dump. Use to diagnose slow or throttled responses.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = false), meta = tool_filter::group("server"))]
+    async fn server_status(&self) -> Result<CallToolResult, McpError> {
+        server_status::execute(&self.state).await
+    }
+
+    #[tool(description = "Report per-tool telemetry since the server started: call counts, errors, deduplicated calls, latency (average, p50, p95, max), and disk-cache hit rates, plus per-host request counts. Use to find which tools are slow or are driving network traffic.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = false), meta = tool_filter::group("server"))]
+    async fn server_stats(&self) -> Result<CallToolResult, McpError> {
+        server_stats::execute(&self.state).await
+    }
+
+    #[tool(description = "Recap what this session has already looked at: every crate a tool was called with, the versions resolved for it (and whether each was defaulted to the latest stable), those versions' default features, and which tools were used. Call this to re-ground a long conversation instead of re-fetching crates.", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = false), meta = tool_filter::group("server"))]
+    async fn session_context(&self) -> Result<CallToolResult, McpError> {
+        session_context::execute(&self.state).await
+    }
+
+    #[tool(description = "Describe this server: every tool with when to use it and its parameters, the recommended workflows, common parameter mistakes (e.g. item_path must be fully qualified), and the current configuration. Pass topic as a tool name for just that tool, or \"workflows\", \"gotchas\" or \"config\".", annotations(read_only_hint = true, idempotent_hint = true, open_world_hint = false), meta = tool_filter::group("server"))]
+    async fn docs_mcp_help(
+        &self,
+        Parameters(params): Parameters<DocsMcpHelpParams>,
+    ) -> Result<CallToolResult, McpError> {
+        docs_mcp_help::execute(&self.state, &self.tool_router.list_all(), self.memo.ttl(), self.house_rules.as_deref(), params).await
+    }
 }
 
-#[tool_handler]
 impl ServerHandler for DocsMcpServer {
+    // Written out instead of generated by `#[tool_handler]` so repeated calls can be
+    // answered from the memo.
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let crate_name = request.arguments.as_ref()
+            .and_then(|a| a.get("name"))
+            .and_then(|n| n.as_str())
+            .map(str::to_string);
+        if let Some(name) = &crate_name {
+            self.session.record_call(&request.name, name, request.arguments.as_ref());
+        }
+        let started = Instant::now();
+        let tool = request.name.to_string();
+        let key = self.tool_router.get(&request.name).and_then(|t| self.memo.key(t, request.arguments.as_ref()));
+        if let Some(result) = key.as_deref().and_then(|k| self.memo.get(k)) {
+            self.state.telemetry.record(&tool, Outcome::Deduplicated, started.elapsed(), (0, 0));
+            return Ok(result);
+        }
+        let pinned = pinned_version(request.arguments.as_ref());
+        // Cancelled when the client cancels this request or the connection shuts down
+        let cancelled = context.ct.clone();
+        let call = session::scope(self.session.clone(), telemetry::count_cache_lookups(async {
+            let mut result = self.tool_router.call(ToolCallContext::new(self, request, context)).await?;
+            let newer = match pinned.filter(|_| result.is_error != Some(true)) {
+                Some((name, version)) => self.state.newer_available(&name, &version).await,
+                None => None,
+            };
+            if let Some(newer) = newer {
+                result = call_memo::with_field(&result, "newer_available", serde_json::to_value(newer).unwrap_or_default());
+            }
+            Ok::<_, McpError>(result)
+        }));
+        // Dropping the call aborts its downloads; nothing is half-written, since cache
+        // files are replaced atomically
+        let finished = tokio::select! {
+            done = call => Some(done),
+            _ = cancelled.cancelled() => None,
+        };
+        let Some((result, cache)) = finished else {
+            self.state.telemetry.record(&tool, Outcome::Error, started.elapsed(), (0, 0));
+            // Clients ignore the response to a request they cancelled
+            return Err(McpError::internal_error("The call was cancelled.", None));
+        };
+        let outcome = match &result {
+            Ok(r) if r.is_error != Some(true) => Outcome::Ok,
+            _ => Outcome::Error,
+        };
+        self.state.telemetry.record(&tool, outcome, started.elapsed(), cache);
+        let result = match result {
+            Ok(result) => result,
+            Err(err) => return Err(with_crate_suggestions(&self.state, with_error_code(err), crate_name.as_deref()).await),
+        };
+        let result = self.output_limit.apply(self.state.cache.dir(), &tool, result).await;
+        if let Some(key) = key {
+            self.memo.insert(key, &result);
+        }
+        Ok(result)
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        Ok(ListToolsResult {
+            tools: self.tool_router.list_all(),
+            meta: None,
+            next_cursor: None,
+        })
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, McpError> {
+        let template = |uri_template: String, name: &str, description: &str| RawResourceTemplate {
+            uri_template,
+            name: name.to_string(),
+            title: None,
+            description: Some(description.to_string()),
+            mime_type: Some("application/json".to_string()),
+            icons: None,
+        }.no_annotation();
+        Ok(ListResourceTemplatesResult {
+            resource_templates: vec![
+                template(format!("{}{{id}}", large_output::URI_PREFIX), "Stored tool result",
+                    "A tool result too large to return inline, linked from the call's outline"),
+                template(format!("{}{{id}}/{{part}}", large_output::URI_PREFIX), "Part of a stored tool result",
+                    "One part, from 1, of a stored tool result; the parts concatenate to the whole"),
+            ],
+            meta: None,
+            next_cursor: None,
+        })
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        let text = self.output_limit.read(self.state.cache.dir(), &request.uri).await?;
+        Ok(ReadResourceResult { contents: vec![ResourceContents::text(text, request.uri)] })
+    }
+
+    fn get_tool(&self, name: &str) -> Option<Tool> {
+        self.tool_router.get(name).cloned()
+    }
+
     fn get_info(&self) -> ServerInfo {
+        let mut capabilities = ServerCapabilities::builder()
+            .enable_tools()
+            .enable_resources()
+            .build();
+        if self.logger.is_some() {
+            capabilities.logging = Some(JsonObject::new());
+        }
         ServerInfo {
             protocol_version: ProtocolVersion::V_2024_11_05,
-            capabilities: ServerCapabilities::builder()
-                .enable_tools()
-                .build(),
+            capabilities,
             server_info: Implementation {
                 name: "docs-mcp".to_string(),
                 version: env!("CARGO_PKG_VERSION").to_string(),
@@ -155,22 +592,64 @@ impl ServerHandler for DocsMcpServer {
                 icons: None,
                 website_url: None,
             },
-            instructions: Some(
-                "This server provides accurate, up-to-date access to the Rust crate ecosystem.\n\
-                \n\
-                DISCOVERY WORKFLOW: crate_list → crate_get → crate_readme_get\n\
-                UNDERSTANDING WORKFLOW: crate_docs_get → crate_item_list → crate_item_get → crate_impls_list\n\
-                DUE DILIGENCE: crate_versions_list → crate_downloads_get → crate_dependents_list → crate_dependencies_list\n\
-                \n\
-                Tool selection guide:\n\
-                - crate_docs_get: structured docs + module tree (falls back to README if no docs.rs build)\n\
-                - crate_readme_get: raw README prose only\n\
-                - crate_item_list: search items by name/concept when you don't have the exact path\n\
-                - crate_item_get: full item details when you have the exact fully-qualified path\n\
-                - crate_impls_list: requires trait_path OR type_path (use crate_item_list to find names)\n\
-                \n\
-                All tools default to the latest stable version when version is not specified.".to_string()
-            ),
+            instructions: Some(match &self.house_rules {
+                Some(rules) => rules.apply(docs_mcp_help::instructions(&self.tool_router.list_all(), self.output_limit.is_enabled())),
+                None => docs_mcp_help::instructions(&self.tool_router.list_all(), self.output_limit.is_enabled()),
+            }),
+        }
+    }
+
+    async fn set_level(
+        &self,
+        request: SetLevelRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        let Some(logger) = &self.logger else {
+            return Err(McpError::method_not_found::<SetLevelRequestMethod>());
+        };
+        logger.set_level(request.level);
+        Ok(())
+    }
+
+    async fn on_initialized(&self, context: NotificationContext<RoleServer>) {
+        if let Some(logger) = &self.logger {
+            logger.attach(context.peer);
         }
     }
 }
+
+/// Give errors raised without a code the generic one for their JSON-RPC error kind, so
+/// every tool error carries `data.code`.
+fn with_error_code(mut err: McpError) -> McpError {
+    if err.data.is_none() {
+        let code = if err.code == ErrorCode::INVALID_PARAMS { ToolErrorCode::InvalidParams } else { ToolErrorCode::Internal };
+        err.data = code.data();
+    }
+    err
+}
+
+/// Add "did you mean" names to a `CRATE_NOT_FOUND` error for the crate the call named.
+async fn with_crate_suggestions(state: &AppState, mut err: McpError, name: Option<&str>) -> McpError {
+    let Some(name) = name else { return err };
+    let not_found = err.data.as_ref().is_some_and(|d| d["code"] == serde_json::json!(ToolErrorCode::CrateNotFound));
+    if !not_found {
+        return err;
+    }
+    let suggestions = state.crate_name_suggestions(name).await;
+    if suggestions.is_empty() {
+        return err;
+    }
+    err.message = format!("{}. Did you mean: {}?", err.message.trim_end_matches('.'), suggestions.join(", ")).into();
+    if let Some(data) = err.data.as_mut() {
+        data["suggestions"] = serde_json::json!(suggestions);
+    }
+    err
+}
+
+/// The crate and version a call explicitly asked for, if it named one other than "latest".
+fn pinned_version(arguments: Option<&JsonObject>) -> Option<(String, String)> {
+    let arguments = arguments?;
+    let name = arguments.get("name")?.as_str()?;
+    let version = arguments.get("version")?.as_str()?;
+    (!version.is_empty() && version != "latest").then(|| (name.to_string(), version.to_string()))
+}
@@ -0,0 +1,219 @@
+//! What the current session has looked at, so an agent can re-ground itself in a long
+//! conversation without re-fetching crates it already pulled.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::metrics::HostStats;
+
+tokio::task_local! {
+    /// The session whose tool call is running, so state shared by every session can
+    /// attribute work to the right one.
+    static CURRENT: Arc<SessionLog>;
+}
+
+/// Tools that report on the server rather than on a crate; their calls are not recorded.
+const UNTRACKED_TOOLS: [&str; 4] = ["server_status", "server_stats", "session_context", "docs_mcp_help"];
+/// Distinct calls kept per crate; the oldest go first.
+const MAX_CALLS_PER_CRATE: usize = 20;
+
+/// One version of a crate the session resolved.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResolvedVersion {
+    pub version: String,
+    /// Picked as the latest stable because the call named no version
+    pub defaulted: bool,
+}
+
+/// One distinct call made with a crate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CallRecord {
+    pub tool: String,
+    /// The call's arguments other than the crate `name`
+    pub arguments: Map<String, Value>,
+}
+
+/// Everything the session did with one crate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CrateTouch {
+    pub name: String,
+    /// Versions in the order they were first resolved
+    pub versions: Vec<ResolvedVersion>,
+    /// Tools called with this crate, in first-use order
+    pub tools: Vec<String>,
+    /// What was asked for, the most recent [`MAX_CALLS_PER_CRATE`] distinct calls in the
+    /// order last made
+    pub requests: Vec<CallRecord>,
+    pub calls: u64,
+}
+
+impl CrateTouch {
+    fn new(name: &str) -> Self {
+        Self { name: name.to_string(), versions: vec![], tools: vec![], requests: vec![], calls: 0 }
+    }
+}
+
+/// Crates one session has touched, in first-touch order, and the requests it caused.
+///
+/// Each server handler owns one; a stdio server has a single session for the process
+/// lifetime, while a server handing out cloned handlers has one per client.
+#[derive(Default)]
+pub struct SessionLog {
+    crates: Mutex<Vec<CrateTouch>>,
+    requests: Mutex<BTreeMap<String, HostStats>>,
+}
+
+/// Run `f` with `session` as the current session.
+pub async fn scope<F: Future>(session: Arc<SessionLog>, f: F) -> F::Output {
+    CURRENT.scope(session, f).await
+}
+
+/// The session whose tool call is running, if any.
+pub fn current() -> Option<Arc<SessionLog>> {
+    CURRENT.try_with(Arc::clone).ok()
+}
+
+/// Carry the current session into `f`, for work handed to a spawned task.
+pub fn bind<F: Future>(f: F) -> impl Future<Output = F::Output> {
+    let session = current();
+    async move {
+        match session {
+            Some(session) => CURRENT.scope(session, f).await,
+            None => f.await,
+        }
+    }
+}
+
+impl SessionLog {
+    /// Record a tool call naming `krate`, with its `arguments`.
+    pub fn record_call(&self, tool: &str, krate: &str, arguments: Option<&Map<String, Value>>) {
+        if UNTRACKED_TOOLS.contains(&tool) {
+            return;
+        }
+        let mut arguments = arguments.cloned().unwrap_or_default();
+        arguments.remove("name");
+        let call = CallRecord { tool: tool.to_string(), arguments };
+        self.with_crate(krate, |touch| {
+            touch.calls += 1;
+            if !touch.tools.iter().any(|t| t == tool) {
+                touch.tools.push(tool.to_string());
+            }
+            // A repeated call moves to the end instead of being listed twice
+            touch.requests.retain(|c| *c != call);
+            touch.requests.push(call);
+            if touch.requests.len() > MAX_CALLS_PER_CRATE {
+                touch.requests.remove(0);
+            }
+        });
+    }
+
+    /// Record that `version` of `krate` was resolved, `defaulted` when no version was asked for.
+    pub fn record_version(&self, krate: &str, version: &str, defaulted: bool) {
+        self.with_crate(krate, |touch| {
+            match touch.versions.iter_mut().find(|v| v.version == version) {
+                Some(v) => v.defaulted |= defaulted,
+                None => touch.versions.push(ResolvedVersion { version: version.to_string(), defaulted }),
+            }
+        });
+    }
+
+    /// Count one response from `host` against this session.
+    pub fn record_request(&self, host: &str, throttled: bool) {
+        let Ok(mut requests) = self.requests.lock() else { return };
+        let stats = requests.entry(host.to_string()).or_default();
+        stats.requests += 1;
+        stats.throttled += u64::from(throttled);
+    }
+
+    /// Requests this session caused, per host.
+    pub fn requests(&self) -> BTreeMap<String, HostStats> {
+        self.requests.lock().map(|r| r.clone()).unwrap_or_default()
+    }
+
+    /// Snapshot of every crate touched so far.
+    pub fn crates(&self) -> Vec<CrateTouch> {
+        self.crates.lock().map(|c| c.clone()).unwrap_or_default()
+    }
+
+    fn with_crate(&self, krate: &str, f: impl FnOnce(&mut CrateTouch)) {
+        let Ok(mut crates) = self.crates.lock() else { return };
+        let pos = match crates.iter().position(|c| c.name == krate) {
+            Some(pos) => pos,
+            None => {
+                crates.push(CrateTouch::new(krate));
+                crates.len() - 1
+            }
+        };
+        f(&mut crates[pos]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_crates_in_first_touch_order() {
+        let log = SessionLog::default();
+        log.record_call("crate_get", "tokio", None);
+        log.record_version("tokio", "1.40.0", true);
+        log.record_call("crate_docs_get", "serde", None);
+        log.record_call("crate_docs_get", "tokio", None);
+        log.record_call("crate_get", "tokio", None);
+        log.record_version("tokio", "1.40.0", false);
+        log.record_version("tokio", "1.38.0", false);
+        log.record_call("server_status", "tokio", None);
+
+        let crates = log.crates();
+        assert_eq!(crates.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(), ["tokio", "serde"]);
+        let tokio = &crates[0];
+        assert_eq!(tokio.calls, 3);
+        assert_eq!(tokio.tools, ["crate_get", "crate_docs_get"]);
+        assert_eq!(tokio.versions, [
+            ResolvedVersion { version: "1.40.0".to_string(), defaulted: true },
+            ResolvedVersion { version: "1.38.0".to_string(), defaulted: false },
+        ]);
+    }
+
+    #[test]
+    fn records_the_arguments_of_each_distinct_call() {
+        let log = SessionLog::default();
+        let args = |v: Value| v.as_object().cloned().unwrap();
+        let item = args(serde_json::json!({"name": "tokio", "item_path": "tokio::sync::Mutex"}));
+        log.record_call("crate_item_get", "tokio", Some(&item));
+        log.record_call("crate_docs_get", "tokio", Some(&args(serde_json::json!({"name": "tokio", "module": "tokio::sync"}))));
+        log.record_call("crate_item_get", "tokio", Some(&item));
+
+        let tokio = &log.crates()[0];
+        assert_eq!(tokio.calls, 3);
+        let requests: Vec<(&str, &Map<String, Value>)> = tokio.requests.iter().map(|c| (c.tool.as_str(), &c.arguments)).collect();
+        assert_eq!(requests, [
+            ("crate_docs_get", &args(serde_json::json!({"module": "tokio::sync"}))),
+            ("crate_item_get", &args(serde_json::json!({"item_path": "tokio::sync::Mutex"}))),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn bound_work_counts_against_its_own_session() {
+        let (a, b) = (Arc::new(SessionLog::default()), Arc::new(SessionLog::default()));
+        let count = || {
+            if let Some(session) = current() {
+                session.record_request("crates.io", false);
+            }
+        };
+        scope(a.clone(), async {
+            count();
+            // A spawned task keeps the session it was bound in
+            tokio::spawn(bind(async move { count() })).await.unwrap();
+        }).await;
+        scope(b.clone(), async { count() }).await;
+        count();
+
+        assert_eq!(a.requests()["crates.io"].requests, 2);
+        assert_eq!(b.requests()["crates.io"].requests, 1);
+    }
+}
@@ -0,0 +1,62 @@
+//! One fetch at a time per key, shared by every session of the process.
+//!
+//! Concurrent calls for the same crate index, rustdoc JSON or archive would otherwise
+//! each download it. A call holds the key's lock while it fetches; the calls that waited
+//! then find the result in the cache it filled and skip the download.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Per-key async locks, dropped once no call holds or waits for them.
+#[derive(Default)]
+pub struct Singleflight {
+    locks: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl Singleflight {
+    /// Run `work` while holding the lock for `key`. `work` should look in its cache first,
+    /// since a call that waited runs after the one that filled it.
+    pub async fn run<T, F: Future<Output = T>>(&self, key: &str, work: impl FnOnce() -> F) -> T {
+        let lock = self.locks().entry(key.to_string()).or_default().clone();
+        let output = {
+            let _held = lock.lock().await;
+            work().await
+        };
+        let mut locks = self.locks();
+        // Two references: the map's and this call's, so no one else is waiting
+        if locks.get(key).is_some_and(|l| Arc::ptr_eq(l, &lock) && Arc::strong_count(l) == 2) {
+            locks.remove(key);
+        }
+        output
+    }
+
+    fn locks(&self) -> std::sync::MutexGuard<'_, HashMap<String, Arc<tokio::sync::Mutex<()>>>> {
+        self.locks.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn waiting_calls_find_the_first_calls_result() {
+        let flights = Singleflight::default();
+        let (cache, downloads) = (Mutex::new(None::<u32>), AtomicUsize::new(0));
+        let call = || flights.run("serde", || async {
+            if let Some(value) = *cache.lock().unwrap() {
+                return value;
+            }
+            downloads.fetch_add(1, Ordering::SeqCst);
+            tokio::task::yield_now().await;
+            *cache.lock().unwrap() = Some(7);
+            7
+        });
+        let results = futures::future::join_all((0..4).map(|_| call())).await;
+        assert_eq!((results, downloads.load(Ordering::SeqCst)), (vec![7; 4], 1));
+        assert!(flights.locks().is_empty(), "finished keys are forgotten");
+    }
+}
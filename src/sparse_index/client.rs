@@ -2,7 +2,7 @@ use reqwest_middleware::ClientWithMiddleware;
 
 use crate::cache::DiskCache;
 use crate::error::{DocsError, Result};
-use super::types::{IndexLine, compute_path};
+use super::types::{IndexLine, compute_path, is_valid_crate_name};
 
 const INDEX_BASE: &str = "https://index.crates.io";
 
@@ -12,10 +12,17 @@ pub async fn fetch_index(
     client: &ClientWithMiddleware,
     cache: &DiskCache,
 ) -> Result<Vec<IndexLine>> {
+    // No crate has such a name, and it must not reach the URL
+    if !is_valid_crate_name(name) {
+        return Err(DocsError::CrateNotFound(name.to_string()));
+    }
     let path = compute_path(name);
     let url = format!("{INDEX_BASE}/{path}");
 
-    let text = cache.get_text(client, &url).await?;
+    let text = match cache.get_text(client, &url).await {
+        Err(e) if e.is_not_found() => return Err(DocsError::CrateNotFound(name.to_string())),
+        result => result?,
+    };
     parse_ndjson(&text)
 }
 
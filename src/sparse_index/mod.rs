@@ -2,4 +2,4 @@ pub mod client;
 pub mod types;
 
 pub use client::{fetch_index, parse_ndjson};
-pub use types::{IndexLine, DepEntry, DepKind, compute_path, find_latest_stable};
+pub use types::{IndexLine, DepEntry, DepKind, compute_path, is_valid_crate_name, find_latest_stable, find_matching, freshness, Freshness};
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use serde::{Deserialize, Serialize};
 
@@ -35,6 +35,49 @@ impl IndexLine {
         }
         merged
     }
+
+    /// Names (as the manifest spells them) of the optional dependencies turned on by
+    /// `features`, plus the default features unless `default_features` is false.
+    pub fn enabled_optional_deps(&self, features: &[String], default_features: bool) -> HashSet<String> {
+        let all = self.all_features();
+        let optional: HashSet<&str> = self.deps.iter().filter(|d| d.optional).map(|d| d.name.as_str()).collect();
+        let mut pending: Vec<&str> = features.iter().map(String::as_str).collect();
+        if default_features {
+            pending.push("default");
+        }
+        let mut visited = HashSet::new();
+        let mut enabled = HashSet::new();
+        while let Some(feature) = pending.pop() {
+            if !visited.insert(feature) {
+                continue;
+            }
+            if let Some(values) = all.get(feature) {
+                for value in values {
+                    if let Some(dep) = value.strip_prefix("dep:") {
+                        enabled.insert(dep.to_string());
+                    } else if let Some((dep, _)) = value.split_once('/') {
+                        // `dep?/feature` leaves `dep` off unless something else turns it on
+                        if optional.contains(dep) {
+                            enabled.insert(dep.to_string());
+                        }
+                    } else {
+                        pending.push(value);
+                    }
+                }
+            } else if optional.contains(feature) {
+                // The implicit feature of an optional dependency
+                enabled.insert(feature.to_string());
+            }
+        }
+        enabled
+    }
+
+    /// Number of normal (non-dev, non-build) dependencies
+    pub fn normal_dep_count(&self) -> usize {
+        self.deps.iter()
+            .filter(|d| d.kind.as_ref().is_none_or(|k| *k == DepKind::Normal))
+            .count()
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -79,6 +122,17 @@ pub fn compute_path(name: &str) -> String {
     }
 }
 
+/// Longest crate name crates.io accepts.
+pub const MAX_CRATE_NAME_LEN: usize = 64;
+
+/// Whether `name` follows the crates.io naming rules: an ASCII letter followed by ASCII
+/// letters, digits, `-` or `_`, at most [`MAX_CRATE_NAME_LEN`] characters.
+pub fn is_valid_crate_name(name: &str) -> bool {
+    name.len() <= MAX_CRATE_NAME_LEN
+        && name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
 /// Find the latest stable version from a list of index lines.
 ///
 /// - Filters out yanked versions
@@ -107,10 +161,77 @@ pub fn find_latest_stable(lines: &[IndexLine]) -> Option<&IndexLine> {
         .max_by_key(|l| Version::parse(&l.vers).ok())
 }
 
+/// Find the highest non-yanked version satisfying the requirement `req`, as cargo would
+/// pick it for a fresh lockfile.
+///
+/// Pre-releases only match requirements that name a pre-release themselves.
+pub fn find_matching<'a>(lines: &'a [IndexLine], req: &str) -> Option<&'a IndexLine> {
+    use semver::{Version, VersionReq};
+
+    let req = VersionReq::parse(req).ok()?;
+    lines
+        .iter()
+        .filter(|l| !l.yanked)
+        .filter_map(|l| Version::parse(&l.vers).ok().map(|v| (v, l)))
+        .filter(|(v, _)| req.matches(v))
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, l)| l)
+}
+
+/// How far an explicitly requested version trails the latest stable release.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Freshness {
+    pub latest_stable: String,
+    /// Non-yanked stable releases published after the requested version
+    pub releases_behind: usize,
+    /// Later stable releases that were pulled, often over a bug or security fix
+    pub yanked_since: Vec<String>,
+    pub requested_yanked: bool,
+    /// Whether `cargo update` could move a `^requested` requirement to the latest stable
+    pub semver_compatible: bool,
+}
+
+/// Compare `version` against the latest stable release, or `None` when it is current
+/// (or newer, e.g. a pre-release of the next version).
+pub fn freshness(lines: &[IndexLine], version: &str) -> Option<Freshness> {
+    use semver::{Version, VersionReq};
+
+    let requested = Version::parse(version).ok()?;
+    let latest = find_latest_stable(lines)?;
+    let latest_version = Version::parse(&latest.vers).ok()?;
+    if latest_version <= requested {
+        return None;
+    }
+    let newer: Vec<&IndexLine> = lines.iter()
+        .filter(|l| !l.vers.contains('-'))
+        .filter(|l| Version::parse(&l.vers).is_ok_and(|v| v > requested))
+        .collect();
+    Some(Freshness {
+        latest_stable: latest.vers.clone(),
+        releases_behind: newer.iter().filter(|l| !l.yanked).count(),
+        yanked_since: newer.iter().filter(|l| l.yanked).map(|l| l.vers.clone()).collect(),
+        requested_yanked: lines.iter().any(|l| l.vers == version && l.yanked),
+        semver_compatible: VersionReq::parse(&format!("^{requested}")).is_ok_and(|r| r.matches(&latest_version)),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn crate_names_follow_the_crates_io_rules() {
+        assert!(is_valid_crate_name("serde"));
+        assert!(is_valid_crate_name("tokio-util"));
+        assert!(is_valid_crate_name("Inflector_2"));
+        assert!(!is_valid_crate_name(""));
+        assert!(!is_valid_crate_name("../etc"));
+        assert!(!is_valid_crate_name("a/b"));
+        assert!(!is_valid_crate_name("2fast"));
+        assert!(!is_valid_crate_name("café"));
+        assert!(!is_valid_crate_name(&"a".repeat(MAX_CRATE_NAME_LEN + 1)));
+    }
+
     #[test]
     fn test_compute_path_1_char() {
         assert_eq!(compute_path("a"), "1/a");
@@ -168,6 +289,74 @@ mod tests {
         assert_eq!(latest.vers, "1.0.0-alpha.1");
     }
 
+    #[test]
+    fn test_find_matching_picks_highest_compatible() {
+        let lines = vec![
+            make_line("1.2.0", false, false),
+            make_line("1.4.0", true, false), // yanked
+            make_line("1.3.1", false, false),
+            make_line("2.0.0", false, false),
+            make_line("1.5.0-rc.1", false, true),
+        ];
+        assert_eq!(find_matching(&lines, "^1.2").unwrap().vers, "1.3.1");
+        assert_eq!(find_matching(&lines, "=1.2.0").unwrap().vers, "1.2.0");
+        assert_eq!(find_matching(&lines, "*").unwrap().vers, "2.0.0");
+        assert!(find_matching(&lines, "^3").is_none());
+        assert!(find_matching(&lines, "not a req").is_none());
+    }
+
+    #[test]
+    fn test_freshness_counts_later_releases() {
+        let lines = vec![
+            make_line("1.1.0", false, false),
+            make_line("1.2.0", true, false), // yanked
+            make_line("1.3.0", false, false),
+            make_line("2.0.0", false, false),
+            make_line("2.1.0-beta.1", false, true),
+        ];
+        let f = freshness(&lines, "1.1.0").unwrap();
+        assert_eq!(f.latest_stable, "2.0.0");
+        assert_eq!(f.releases_behind, 2);
+        assert_eq!(f.yanked_since, vec!["1.2.0"]);
+        assert!(!f.requested_yanked);
+        assert!(!f.semver_compatible);
+
+        assert!(freshness(&lines, "2.0.0").is_none());
+        assert!(freshness(&lines, "2.1.0-beta.1").is_none());
+        assert!(freshness(&lines, "1.3.0").is_some_and(|f| f.releases_behind == 1));
+        assert!(freshness(&lines, "1.2.0").is_some_and(|f| f.requested_yanked));
+    }
+
+    #[test]
+    fn test_enabled_optional_deps() {
+        let dep = |name: &str| DepEntry {
+            name: name.to_string(),
+            req: "1".to_string(),
+            package: None,
+            kind: None,
+            optional: true,
+            default_features: true,
+            features: vec![],
+            target: None,
+        };
+        let mut line = make_line("1.0.0", false, false);
+        line.deps = ["tls", "json", "zstd", "log", "gzip"].into_iter().map(dep).collect();
+        line.features = HashMap::from([
+            ("default".to_string(), vec!["net".to_string(), "json".to_string(), "log?/std".to_string()]),
+            ("net".to_string(), vec!["dep:tls".to_string()]),
+            ("compress".to_string(), vec!["zstd/std".to_string()]),
+        ]);
+
+        let mut enabled: Vec<String> = line.enabled_optional_deps(&[], true).into_iter().collect();
+        enabled.sort();
+        assert_eq!(enabled, ["json", "tls"]);
+        let mut enabled: Vec<String> = line.enabled_optional_deps(&["compress".to_string(), "gzip".to_string()], false)
+            .into_iter()
+            .collect();
+        enabled.sort();
+        assert_eq!(enabled, ["gzip", "zstd"]);
+    }
+
     fn make_line(vers: &str, yanked: bool, _is_pre: bool) -> IndexLine {
         IndexLine {
             name: "test".to_string(),
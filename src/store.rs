@@ -0,0 +1,354 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use rusqlite::{Connection, OptionalExtension, params};
+use semver::{Version, VersionReq};
+use serde::Serialize;
+
+use crate::error::{DocsError, Result};
+use crate::sparse_index::{DepKind, IndexLine, find_latest_stable};
+
+const STORE_FILE: &str = "metadata.sqlite";
+/// How long a write waits for another process holding the database lock.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+/// Index data for a crate is rewritten at most this often.
+const REFRESH_AFTER: Duration = Duration::from_secs(60 * 60);
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS crates (
+    name TEXT PRIMARY KEY,
+    latest_stable TEXT,
+    indexed_at INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS versions (
+    crate TEXT NOT NULL,
+    version TEXT NOT NULL,
+    yanked INTEGER NOT NULL,
+    rust_version TEXT,
+    features TEXT NOT NULL,
+    PRIMARY KEY (crate, version)
+);
+CREATE TABLE IF NOT EXISTS dependencies (
+    crate TEXT NOT NULL,
+    version TEXT NOT NULL,
+    dependency TEXT NOT NULL,
+    req TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    optional INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS dependencies_crate ON dependencies (crate, version);
+CREATE INDEX IF NOT EXISTS dependencies_dependency ON dependencies (dependency);
+CREATE TABLE IF NOT EXISTS owners (
+    crate TEXT NOT NULL,
+    login TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    PRIMARY KEY (crate, login)
+);
+";
+
+/// Normalized crate metadata accumulated from the data the tools fetch.
+///
+/// Every sparse-index fetch records the crate's versions, MSRVs, features and dependencies,
+/// so cross-crate questions (who depends on which version of X, which owners maintain a set
+/// of crates) can be answered without refetching everything.
+///
+/// Every method is a blocking SQLite call; async code goes through
+/// [`AppState::with_store`](crate::tools::AppState::with_store).
+pub struct MetadataStore {
+    conn: Mutex<Connection>,
+    /// When this process last recorded each crate, so [`needs_record`](Self::needs_record)
+    /// answers without touching the database
+    recorded: Mutex<HashMap<String, Instant>>,
+}
+
+/// How the crates seen so far constrain one dependency.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct RequirementGroup {
+    pub req: String,
+    /// Whether the requirement accepts the dependency's latest stable version
+    pub accepts_latest: Option<bool>,
+    pub dependents: Vec<String>,
+}
+
+/// A dependency shared by several crates in a stack.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct SharedDependency {
+    pub name: String,
+    pub used_by: Vec<String>,
+    /// Requirement strings in use, most common first
+    pub reqs: Vec<String>,
+}
+
+impl MetadataStore {
+    /// Open (or create) the store in `cache_dir`.
+    pub fn open(cache_dir: &Path) -> Result<Self> {
+        let conn = Connection::open(cache_dir.join(STORE_FILE))?;
+        // Other docs-mcp processes may share the cache directory; wait out their writes
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        Self::from_connection(conn)
+    }
+
+    /// A store that lives only as long as the process (fixture mode and tests).
+    pub fn in_memory() -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn: Mutex::new(conn), recorded: Mutex::default() })
+    }
+
+    fn conn(&self) -> Result<std::sync::MutexGuard<'_, Connection>> {
+        self.conn.lock().map_err(|_| DocsError::Other("metadata store lock poisoned".to_string()))
+    }
+
+    /// Whether [`record_index`](Self::record_index) would write `name`, as far as this
+    /// process knows: false if it recorded the crate within the refresh interval.
+    pub fn needs_record(&self, name: &str) -> bool {
+        let recorded = self.recorded.lock().map(|r| r.get(name).copied()).ok().flatten();
+        recorded.is_none_or(|at| at.elapsed() >= REFRESH_AFTER)
+    }
+
+    /// Record a crate's sparse index entries, unless they were recorded recently.
+    pub fn record_index(&self, name: &str, lines: &[IndexLine]) -> Result<()> {
+        let now = unix_now();
+        if let Ok(mut recorded) = self.recorded.lock() {
+            recorded.insert(name.to_string(), Instant::now());
+        }
+        let mut conn = self.conn()?;
+        let indexed_at: Option<i64> = conn.query_row(
+            "SELECT indexed_at FROM crates WHERE name = ?1",
+            params![name],
+            |row| row.get(0),
+        ).optional()?;
+        if indexed_at.is_some_and(|t| now.saturating_sub(t as u64) < REFRESH_AFTER.as_secs()) {
+            return Ok(());
+        }
+
+        let latest = find_latest_stable(lines).map(|l| l.vers.clone());
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM versions WHERE crate = ?1", params![name])?;
+        tx.execute("DELETE FROM dependencies WHERE crate = ?1", params![name])?;
+        tx.execute(
+            "INSERT OR REPLACE INTO crates (name, latest_stable, indexed_at) VALUES (?1, ?2, ?3)",
+            params![name, latest, now as i64],
+        )?;
+        {
+            let mut version_stmt = tx.prepare(
+                "INSERT OR REPLACE INTO versions (crate, version, yanked, rust_version, features)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?;
+            let mut dep_stmt = tx.prepare(
+                "INSERT INTO dependencies (crate, version, dependency, req, kind, optional)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for line in lines {
+                let features = serde_json::to_string(&line.all_features())?;
+                version_stmt.execute(params![name, line.vers, line.yanked, line.rust_version, features])?;
+                for dep in &line.deps {
+                    // Renamed deps record the real package name
+                    let dependency = dep.package.as_deref().unwrap_or(&dep.name);
+                    let kind = match dep.kind {
+                        Some(DepKind::Dev) => "dev",
+                        Some(DepKind::Build) => "build",
+                        _ => "normal",
+                    };
+                    dep_stmt.execute(params![name, line.vers, dependency, dep.req, kind, dep.optional])?;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Record a crate's owners (user logins and team names).
+    pub fn record_owners(&self, name: &str, owners: &[(String, String)]) -> Result<()> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM owners WHERE crate = ?1", params![name])?;
+        for (login, kind) in owners {
+            tx.execute(
+                "INSERT OR REPLACE INTO owners (crate, login, kind) VALUES (?1, ?2, ?3)",
+                params![name, login, kind],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Number of crates recorded so far.
+    pub fn crate_count(&self) -> Result<u64> {
+        let conn = self.conn()?;
+        Ok(conn.query_row("SELECT COUNT(*) FROM crates", [], |row| row.get::<_, i64>(0))? as u64)
+    }
+
+    /// Latest stable version recorded for `name`.
+    pub fn latest_stable(&self, name: &str) -> Result<Option<String>> {
+        let conn = self.conn()?;
+        Ok(conn.query_row("SELECT latest_stable FROM crates WHERE name = ?1", params![name], |row| row.get(0))
+            .optional()?
+            .flatten())
+    }
+
+    /// How recorded crates (at their latest stable version) constrain `name`, grouped by
+    /// requirement, most dependents first.
+    pub fn dependents_skew(&self, name: &str) -> Result<Vec<RequirementGroup>> {
+        let latest = self.latest_stable(name)?.and_then(|v| Version::parse(&v).ok());
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT d.req, d.crate FROM dependencies d
+             JOIN crates c ON c.name = d.crate AND c.latest_stable = d.version
+             WHERE d.dependency = ?1 AND d.kind != 'dev'
+             ORDER BY d.crate",
+        )?;
+        let rows = stmt.query_map(params![name], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (req, dependent) in rows {
+            let deps = groups.entry(req).or_default();
+            if !deps.contains(&dependent) {
+                deps.push(dependent);
+            }
+        }
+        let mut out: Vec<RequirementGroup> = groups.into_iter()
+            .map(|(req, dependents)| RequirementGroup {
+                accepts_latest: latest.as_ref()
+                    .and_then(|v| VersionReq::parse(&req).ok().map(|r| r.matches(v))),
+                req,
+                dependents,
+            })
+            .collect();
+        out.sort_by(|a, b| b.dependents.len().cmp(&a.dependents.len()).then_with(|| a.req.cmp(&b.req)));
+        Ok(out)
+    }
+
+    /// Owners shared by more than one of `crates`, mapped to the crates they own.
+    pub fn owner_overlap(&self, crates: &[String]) -> Result<BTreeMap<String, Vec<String>>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare("SELECT login FROM owners WHERE crate = ?1 ORDER BY login")?;
+        let mut by_owner: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for krate in crates {
+            let logins = stmt.query_map(params![krate], |row| row.get::<_, String>(0))?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            for login in logins {
+                by_owner.entry(login).or_default().push(krate.clone());
+            }
+        }
+        by_owner.retain(|_, owned| owned.len() > 1);
+        Ok(by_owner)
+    }
+
+    /// Normal dependencies that at least two of `crates` share at their latest stable
+    /// versions, most shared first.
+    pub fn shared_dependencies(&self, crates: &[String]) -> Result<Vec<SharedDependency>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT d.dependency, d.req FROM dependencies d
+             JOIN crates c ON c.name = d.crate AND c.latest_stable = d.version
+             WHERE d.crate = ?1 AND d.kind = 'normal'",
+        )?;
+        let mut by_dep: HashMap<String, (Vec<String>, Vec<String>)> = HashMap::new();
+        for krate in crates {
+            let rows = stmt.query_map(params![krate], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            for (dep, req) in rows {
+                let (users, reqs) = by_dep.entry(dep).or_default();
+                if !users.contains(krate) {
+                    users.push(krate.clone());
+                }
+                reqs.push(req);
+            }
+        }
+        let mut out: Vec<SharedDependency> = by_dep.into_iter()
+            .filter(|(_, (users, _))| users.len() > 1)
+            .map(|(name, (used_by, reqs))| SharedDependency { name, used_by, reqs: most_common_first(reqs) })
+            .collect();
+        out.sort_by(|a, b| b.used_by.len().cmp(&a.used_by.len()).then_with(|| a.name.cmp(&b.name)));
+        Ok(out)
+    }
+}
+
+/// Distinct values ordered by frequency (then alphabetically).
+fn most_common_first(values: Vec<String>) -> Vec<String> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for v in values {
+        *counts.entry(v).or_default() += 1;
+    }
+    let mut out: Vec<(String, usize)> = counts.into_iter().collect();
+    out.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    out.into_iter().map(|(v, _)| v).collect()
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sparse_index::DepEntry;
+
+    fn line(name: &str, vers: &str, deps: &[(&str, &str)]) -> IndexLine {
+        IndexLine {
+            name: name.to_string(),
+            vers: vers.to_string(),
+            deps: deps.iter().map(|(dep, req)| DepEntry {
+                name: dep.to_string(),
+                req: req.to_string(),
+                package: None,
+                kind: None,
+                optional: false,
+                default_features: true,
+                features: vec![],
+                target: None,
+            }).collect(),
+            cksum: String::new(),
+            features: Default::default(),
+            yanked: false,
+            rust_version: None,
+            features2: None,
+        }
+    }
+
+    #[test]
+    fn skew_and_shared_dependencies_use_latest_versions() {
+        let store = MetadataStore::in_memory().unwrap();
+        store.record_index("serde", &[line("serde", "1.0.200", &[])]).unwrap();
+        store.record_index("a", &[line("a", "0.1.0", &[("serde", "^0.9")]), line("a", "0.2.0", &[("serde", "^1.0")])]).unwrap();
+        store.record_index("b", &[line("b", "1.0.0", &[("serde", "^1.0"), ("log", "^0.4")])]).unwrap();
+        store.record_index("c", &[line("c", "1.0.0", &[("serde", "^0.8"), ("log", "^0.4.20")])]).unwrap();
+
+        let skew = store.dependents_skew("serde").unwrap();
+        assert_eq!(skew[0].req, "^1.0");
+        assert_eq!(skew[0].dependents, vec!["a", "b"]);
+        assert_eq!(skew[0].accepts_latest, Some(true));
+        assert_eq!(skew[1].req, "^0.8");
+        assert_eq!(skew[1].accepts_latest, Some(false));
+        assert_eq!(skew.len(), 2, "a's old ^0.9 requirement is ignored");
+
+        let crates: Vec<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+        let shared = store.shared_dependencies(&crates).unwrap();
+        assert_eq!(shared[0].name, "serde");
+        assert_eq!(shared[0].used_by.len(), 3);
+        assert_eq!(shared[1].name, "log");
+    }
+
+    #[test]
+    fn owner_overlap_keeps_shared_owners_only() {
+        let store = MetadataStore::in_memory().unwrap();
+        let owners = |logins: &[&str]| -> Vec<(String, String)> {
+            logins.iter().map(|l| (l.to_string(), "user".to_string())).collect()
+        };
+        store.record_owners("tokio", &owners(&["carllerche", "Darksonn"])).unwrap();
+        store.record_owners("mio", &owners(&["carllerche", "Thomasdezeeuw"])).unwrap();
+        let overlap = store.owner_overlap(&["tokio".to_string(), "mio".to_string()]).unwrap();
+        assert_eq!(overlap.len(), 1);
+        assert_eq!(overlap["carllerche"], vec!["tokio", "mio"]);
+    }
+}
@@ -0,0 +1,206 @@
+//! Per-tool call counts, latencies, disk-cache hit rates and errors, for operators who need
+//! to see what is slow and what is hammering the network.
+
+use std::cell::Cell;
+use std::collections::{BTreeMap, VecDeque};
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+/// Env var asking for a stats summary in the log every this many seconds. Unset or `0`: never.
+pub const LOG_INTERVAL_ENV: &str = "DOCS_MCP_STATS_LOG_SECS";
+/// Latencies kept per tool for the percentiles; older calls only count toward the totals.
+const LATENCY_SAMPLE: usize = 200;
+
+tokio::task_local! {
+    /// Disk-cache `(hits, misses)` of the tool call running on this task.
+    static CALL_CACHE: Cell<(u64, u64)>;
+}
+
+/// Count a disk-cache lookup toward the tool call in progress, if any.
+pub fn record_cache_lookup(hit: bool) {
+    let _ = CALL_CACHE.try_with(|c| {
+        let (hits, misses) = c.get();
+        c.set(if hit { (hits + 1, misses) } else { (hits, misses + 1) });
+    });
+}
+
+/// Run `fut`, returning its output with the disk-cache `(hits, misses)` it caused.
+///
+/// Lookups made from tasks it spawns are not attributed.
+pub async fn count_cache_lookups<F: Future>(fut: F) -> (F::Output, (u64, u64)) {
+    CALL_CACHE.scope(Cell::new((0, 0)), async {
+        let output = fut.await;
+        (output, CALL_CACHE.with(Cell::get))
+    }).await
+}
+
+/// How one tool call ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Ok,
+    Error,
+    /// Answered from the call memo without running the tool
+    Deduplicated,
+}
+
+#[derive(Debug, Default)]
+struct ToolCounters {
+    calls: u64,
+    errors: u64,
+    deduplicated: u64,
+    cache_hits: u64,
+    cache_misses: u64,
+    total: Duration,
+    max: Duration,
+    recent: VecDeque<Duration>,
+}
+
+/// One tool's numbers since the server started.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ToolStats {
+    pub tool: String,
+    pub calls: u64,
+    pub errors: u64,
+    pub deduplicated: u64,
+    pub avg_ms: u64,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    pub max_ms: u64,
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+    /// Share of disk-cache lookups served without a network request; `None` before any lookup
+    pub cache_hit_rate: Option<f64>,
+}
+
+/// Per-tool counters for the server's lifetime.
+pub struct Telemetry {
+    started: Instant,
+    tools: Mutex<BTreeMap<String, ToolCounters>>,
+}
+
+impl Default for Telemetry {
+    fn default() -> Self {
+        Self { started: Instant::now(), tools: Mutex::default() }
+    }
+}
+
+impl Telemetry {
+    /// Record one call of `tool` that took `elapsed` and made `cache` `(hits, misses)`.
+    pub fn record(&self, tool: &str, outcome: Outcome, elapsed: Duration, cache: (u64, u64)) {
+        let Ok(mut tools) = self.tools.lock() else { return };
+        let c = tools.entry(tool.to_string()).or_default();
+        c.calls += 1;
+        match outcome {
+            Outcome::Ok => {}
+            Outcome::Error => c.errors += 1,
+            Outcome::Deduplicated => c.deduplicated += 1,
+        }
+        c.cache_hits += cache.0;
+        c.cache_misses += cache.1;
+        c.total += elapsed;
+        c.max = c.max.max(elapsed);
+        if c.recent.len() == LATENCY_SAMPLE {
+            c.recent.pop_front();
+        }
+        c.recent.push_back(elapsed);
+    }
+
+    /// Time since the server started.
+    pub fn uptime(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Stats for every tool called so far, busiest first.
+    pub fn snapshot(&self) -> Vec<ToolStats> {
+        let Ok(tools) = self.tools.lock() else { return vec![] };
+        let mut stats: Vec<ToolStats> = tools.iter().map(|(tool, c)| {
+            let mut sorted: Vec<Duration> = c.recent.iter().copied().collect();
+            sorted.sort();
+            let lookups = c.cache_hits + c.cache_misses;
+            ToolStats {
+                tool: tool.clone(),
+                calls: c.calls,
+                errors: c.errors,
+                deduplicated: c.deduplicated,
+                avg_ms: ms(c.total / u32::try_from(c.calls.max(1)).unwrap_or(u32::MAX)),
+                p50_ms: ms(percentile(&sorted, 50)),
+                p95_ms: ms(percentile(&sorted, 95)),
+                max_ms: ms(c.max),
+                cache_hits: c.cache_hits,
+                cache_misses: c.cache_misses,
+                cache_hit_rate: (lookups > 0).then(|| c.cache_hits as f64 / lookups as f64),
+            }
+        }).collect();
+        stats.sort_by(|a, b| b.calls.cmp(&a.calls).then_with(|| a.tool.cmp(&b.tool)));
+        stats
+    }
+
+    /// Log a one-line summary per tool at info level.
+    pub fn log_summary(&self) {
+        for s in self.snapshot() {
+            tracing::info!(
+                "stats {}: {} calls, {} errors, {} deduplicated, p50 {}ms, p95 {}ms, cache {}/{} hits",
+                s.tool, s.calls, s.errors, s.deduplicated, s.p50_ms, s.p95_ms,
+                s.cache_hits, s.cache_hits + s.cache_misses,
+            );
+        }
+    }
+}
+
+/// The periodic summary interval from [`LOG_INTERVAL_ENV`], if one is set.
+pub fn log_interval_from_env() -> Option<Duration> {
+    std::env::var(LOG_INTERVAL_ENV).ok()
+        .and_then(|v| v.trim().parse().ok())
+        .filter(|&secs: &u64| secs > 0)
+        .map(Duration::from_secs)
+}
+
+/// Nearest-rank percentile of an ascending sample.
+fn percentile(sorted: &[Duration], pct: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (sorted.len() * pct).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+fn ms(d: Duration) -> u64 {
+    u64::try_from(d.as_millis()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregates_calls_per_tool() {
+        let telemetry = Telemetry::default();
+        for ms in 1..=20 {
+            telemetry.record("crate_get", Outcome::Ok, Duration::from_millis(ms * 10), (1, 1));
+        }
+        telemetry.record("crate_get", Outcome::Error, Duration::from_millis(5), (0, 2));
+        telemetry.record("crate_docs_get", Outcome::Deduplicated, Duration::ZERO, (0, 0));
+
+        let stats = telemetry.snapshot();
+        assert_eq!(stats.iter().map(|s| s.tool.as_str()).collect::<Vec<_>>(), ["crate_get", "crate_docs_get"]);
+        let get = &stats[0];
+        assert_eq!((get.calls, get.errors, get.deduplicated), (21, 1, 0));
+        assert_eq!((get.p50_ms, get.p95_ms, get.max_ms), (100, 190, 200));
+        assert_eq!((get.cache_hits, get.cache_misses), (20, 22));
+        assert_eq!(stats[1].cache_hit_rate, None);
+    }
+
+    #[tokio::test]
+    async fn cache_lookups_are_attributed_to_the_enclosing_call() {
+        record_cache_lookup(true); // outside any call: ignored
+        let ((), counts) = count_cache_lookups(async {
+            record_cache_lookup(true);
+            record_cache_lookup(false);
+            record_cache_lookup(true);
+        }).await;
+        assert_eq!(counts, (2, 1));
+    }
+}
@@ -0,0 +1,185 @@
+//! Which tools the server registers, so operators can trim the list an LLM sees.
+//!
+//! Both lists take tool names and group names, comma-separated. With an allow list only
+//! the tools it names are registered; the deny list is applied after it. Each tool names
+//! its group in its `#[tool]` attribute (`meta = tool_filter::group("docs")`), so a
+//! new tool is grouped where it is declared.
+//!
+//! The lists come from a TOML config file, then the environment, then the command line,
+//! each replacing the lists the one before set:
+//!
+//! ```toml
+//! [tools]
+//! enabled = ["docs", "server"]
+//! disabled = ["cache_warm"]
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use rmcp::model::{Meta, Tool};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{DocsError, Result};
+
+/// Env var listing the only tools or groups to register (CLI: `--tools`).
+pub const ENABLED_ENV: &str = "DOCS_MCP_TOOLS";
+/// Env var listing tools or groups to leave out (CLI: `--disable-tools`).
+pub const DISABLED_ENV: &str = "DOCS_MCP_DISABLED_TOOLS";
+/// Env var naming the TOML config file (CLI: `--config`).
+pub const CONFIG_ENV: &str = "DOCS_MCP_CONFIG";
+
+/// Tool groups; every registered tool is in exactly one.
+pub const GROUPS: [&str; 4] = ["docs", "releases", "due_diligence", "server"];
+
+/// Key of the group name in a tool's `_meta`.
+const GROUP_META_KEY: &str = "docs-mcp/group";
+
+/// Tool metadata putting a tool in `group`, one of [`GROUPS`].
+pub fn group(group: &str) -> Meta {
+    debug_assert!(GROUPS.contains(&group), "unknown tool group '{group}'");
+    let mut meta = Meta::new();
+    meta.0.insert(GROUP_META_KEY.to_string(), group.into());
+    meta
+}
+
+/// The group `tool` declares, if any.
+pub fn group_of(tool: &Tool) -> Option<&str> {
+    tool.meta.as_ref()?.0.get(GROUP_META_KEY)?.as_str()
+}
+
+/// The `[tools]` table of the config file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigTools {
+    enabled: Option<Vec<String>>,
+    disabled: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ConfigFile {
+    #[serde(default)]
+    tools: ConfigTools,
+}
+
+/// Allow and deny lists of tool or group names; the default registers every tool.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ToolFilter {
+    /// When set, only these are registered
+    pub enabled: Option<Vec<String>>,
+    pub disabled: Vec<String>,
+}
+
+impl ToolFilter {
+    /// Lists from the `[tools]` table of the config file at `config` (`--config`, else
+    /// [`CONFIG_ENV`]), then [`ENABLED_ENV`] and [`DISABLED_ENV`].
+    ///
+    /// A config file that was asked for but cannot be read or parsed is an error, so a
+    /// typo does not silently register every tool.
+    pub fn load(config: Option<&Path>) -> Result<Self> {
+        let from_env = std::env::var_os(CONFIG_ENV).filter(|v| !v.is_empty()).map(PathBuf::from);
+        let filter = match config.map(Path::to_path_buf).or(from_env) {
+            Some(path) => Self::from_config(&path)?,
+            None => Self::default(),
+        };
+        let env = |name: &str| std::env::var(name).ok();
+        Ok(filter.apply(env(ENABLED_ENV), env(DISABLED_ENV)))
+    }
+
+    fn from_config(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| DocsError::Other(format!("cannot read config file {}: {e}", path.display())))?;
+        let config: ConfigFile = toml::from_str(&text)
+            .map_err(|e| DocsError::Other(format!("cannot parse config file {}: {e}", path.display())))?;
+        let join = |names: Option<Vec<String>>| names.map(|n| n.join(","));
+        Ok(Self::default().apply(join(config.tools.enabled), join(config.tools.disabled)))
+    }
+
+    /// Override with the `--tools` and `--disable-tools` values.
+    pub fn with_flags(self, enabled: Option<&str>, disabled: Option<&str>) -> Self {
+        self.apply(enabled.map(str::to_string), disabled.map(str::to_string))
+    }
+
+    /// Replace each list that is given and not blank.
+    fn apply(mut self, enabled: Option<String>, disabled: Option<String>) -> Self {
+        if let Some(names) = enabled.as_deref().and_then(split_names) {
+            self.enabled = Some(names);
+        }
+        if let Some(names) = disabled.as_deref().and_then(split_names) {
+            self.disabled = names;
+        }
+        self
+    }
+
+    /// Whether `tool` is registered.
+    pub fn allows(&self, tool: &Tool) -> bool {
+        let group = group_of(tool);
+        let names = |list: &[String]| list.iter().any(|n| *n == tool.name || Some(n.as_str()) == group);
+        self.enabled.as_deref().is_none_or(names) && !names(&self.disabled)
+    }
+
+    /// Names in either list that are neither one of `tools` nor a group.
+    pub fn unknown_names<'a>(&'a self, tools: &[Tool]) -> Vec<&'a str> {
+        self.enabled.iter().flatten().chain(&self.disabled)
+            .map(String::as_str)
+            .filter(|n| !tools.iter().any(|t| t.name == *n) && !GROUPS.contains(n))
+            .collect()
+    }
+}
+
+fn split_names(list: &str) -> Option<Vec<String>> {
+    let names: Vec<String> = list.split(',')
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .map(str::to_string)
+        .collect();
+    (!names.is_empty()).then_some(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(name: &'static str, in_group: &str) -> Tool {
+        let mut tool = Tool::new(name, "", std::sync::Arc::new(Default::default()));
+        tool.meta = Some(group(in_group));
+        tool
+    }
+
+    #[test]
+    fn allow_list_then_deny_list_by_tool_or_group() {
+        let (item_get, help) = (tool("crate_item_get", "docs"), tool("docs_mcp_help", "server"));
+        let (warm, audit) = (tool("cache_warm", "server"), tool("crate_audit_report", "due_diligence"));
+        assert!(ToolFilter::default().allows(&audit));
+
+        let docs_only = ToolFilter::default().apply(Some("docs, server".into()), Some("cache_warm".into()));
+        assert!(docs_only.allows(&item_get));
+        assert!(docs_only.allows(&help));
+        assert!(!docs_only.allows(&warm));
+        assert!(!docs_only.allows(&audit));
+
+        let no_audit = ToolFilter::default().apply(Some(" ".into()), Some("due_diligence".into()));
+        assert_eq!(no_audit.enabled, None);
+        assert!(no_audit.allows(&item_get) && !no_audit.allows(&audit));
+    }
+
+    #[test]
+    fn flags_override_env_and_unknown_names_are_reported() {
+        let filter = ToolFilter::default().apply(None, Some("docs".into())).with_flags(None, Some("crate_lst,server"));
+        assert_eq!(filter.disabled, ["crate_lst", "server"]);
+        assert_eq!(filter.unknown_names(&[tool("crate_list", "docs")]), ["crate_lst"]);
+    }
+
+    #[test]
+    fn config_file_sets_the_lists() {
+        let path = std::env::temp_dir().join(format!("docs-mcp-tools-{}.toml", std::process::id()));
+        std::fs::write(&path, "[tools]\nenabled = [\"docs\", \"server\"]\ndisabled = [\"cache_warm\"]\n").unwrap();
+        let filter = ToolFilter::from_config(&path).unwrap();
+        assert_eq!(filter.enabled.as_deref(), Some(["docs".to_string(), "server".to_string()].as_slice()));
+        assert_eq!(filter.disabled, ["cache_warm"]);
+
+        std::fs::write(&path, "[tools]\nenable = [\"docs\"]\n").unwrap();
+        assert!(ToolFilter::from_config(&path).is_err(), "a misspelled key is an error");
+        std::fs::remove_file(&path).unwrap();
+        assert!(ToolFilter::from_config(&path).is_err());
+    }
+}
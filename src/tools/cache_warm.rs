@@ -0,0 +1,383 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use rmcp::{ErrorData, model::{CallToolResult, Content}};
+use serde::{Deserialize, Serialize};
+use rmcp::schemars::{self, JsonSchema};
+use serde_json::json;
+use tokio_util::sync::CancellationToken;
+
+use super::AppState;
+use crate::cratesio::CratesIoClient;
+use crate::docsrs::{fetch_rustdoc_json, DocDetail};
+use crate::error::{DocsError, ErrorCode};
+use crate::sparse_index::{find_latest_stable, find_matching};
+
+/// Crates accepted per warm-up; each costs up to three fetches, one of them rate-limited.
+const MAX_CRATES: usize = 100;
+
+/// Dependency tables of a manifest, also read under `[target.<cfg>]`.
+const DEPENDENCY_TABLES: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// Directory `manifest_path` must be under; defaults to the server's working directory.
+pub const MANIFEST_ROOT_ENV: &str = "DOCS_MCP_MANIFEST_ROOT";
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CacheWarmParams {
+    /// Crates to prefetch, as names ("serde") or pinned versions ("serde@1.0.217")
+    pub crates: Option<Vec<String>>,
+    /// Path to a Cargo.toml whose dependencies (normal, dev, build, target-specific and
+    /// workspace) to prefetch, at the newest version matching each requirement. Must be
+    /// under the server's working directory (or `DOCS_MCP_MANIFEST_ROOT`).
+    pub manifest_path: Option<String>,
+    /// Wait for the warm-up to finish and return its summary (default: false, run it in the
+    /// background and return at once). Call again with no crates to see progress.
+    pub wait: Option<bool>,
+    /// Stop the running warm-up; crates already fetched stay cached (default: false)
+    pub cancel: Option<bool>,
+}
+
+/// One crate that was prefetched.
+#[derive(Debug, Clone, Serialize)]
+struct Warmed {
+    #[serde(rename = "crate")]
+    name: String,
+    version: String,
+    /// Whether docs.rs has a rustdoc JSON build of `version`, now cached
+    docs: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Failed {
+    #[serde(rename = "crate")]
+    name: String,
+    error: String,
+}
+
+/// A warm-up in progress or finished.
+#[derive(Debug, Clone)]
+struct WarmJob {
+    started: Instant,
+    total: usize,
+    current: Option<String>,
+    warmed: Vec<Warmed>,
+    failed: Vec<Failed>,
+    finished_after: Option<f64>,
+    /// Stops the warm-up between or during crates
+    cancel: CancellationToken,
+}
+
+/// The latest warm-up, shared between the task running it and `cache_warm` calls
+/// asking for its progress.
+#[derive(Default)]
+pub struct WarmProgress {
+    job: Mutex<Option<WarmJob>>,
+}
+
+impl WarmProgress {
+    fn update(&self, f: impl FnOnce(&mut WarmJob)) {
+        if let Some(job) = self.job.lock().unwrap().as_mut() {
+            f(job);
+        }
+    }
+
+    fn snapshot(&self) -> Option<WarmJob> {
+        self.job.lock().unwrap().clone()
+    }
+}
+
+pub async fn execute(state: &Arc<AppState>, params: CacheWarmParams) -> Result<CallToolResult, ErrorData> {
+    if params.cancel.unwrap_or(false) {
+        if let Some(job) = state.warming.snapshot() {
+            job.cancel.cancel();
+        }
+        return progress_result(state.warming.snapshot());
+    }
+    let mut crates: Vec<(String, Option<String>)> = vec![];
+    for spec in params.crates.iter().flatten().map(|c| c.trim()).filter(|c| !c.is_empty()) {
+        match spec.split_once('@') {
+            Some((name, version)) => crates.push((name.trim().to_string(), Some(format!("={}", version.trim())))),
+            None => crates.push((spec.to_string(), None)),
+        }
+    }
+    if let Some(path) = &params.manifest_path {
+        let path = allowed_manifest(path).await?;
+        let manifest = tokio::fs::read_to_string(&path).await.map_err(|e| ErrorData::invalid_params(
+            format!("Could not read manifest '{}': {e}", path.display()),
+            ErrorCode::FileNotFound.data(),
+        ))?;
+        crates.extend(manifest_dependencies(&manifest).map_err(|e| ErrorData::invalid_params(
+            format!("Could not parse manifest '{}': {e}", path.display()),
+            ErrorCode::InvalidParams.data(),
+        ))?);
+    }
+    let mut seen = vec![];
+    crates.retain(|(name, _)| {
+        let new = !seen.contains(name);
+        seen.push(name.clone());
+        new
+    });
+
+    // No crates: report on the current or last warm-up
+    if crates.is_empty() {
+        return progress_result(state.warming.snapshot());
+    }
+    if crates.len() > MAX_CRATES {
+        return Err(ErrorData::invalid_params(
+            format!("Pass at most {MAX_CRATES} crates (got {}).", crates.len()),
+            ErrorCode::InvalidParams.data(),
+        ));
+    }
+    // Server shutdown stops a warm-up too
+    let cancel = state.shutdown.child_token();
+    {
+        let mut job = state.warming.job.lock().unwrap();
+        if job.as_ref().is_some_and(|j| j.finished_after.is_none()) {
+            return Err(ErrorData::invalid_params(
+                "A cache warm-up is already running. Call cache_warm with no crates to see its progress, \
+                 or with cancel=true to stop it.",
+                ErrorCode::InvalidParams.data(),
+            ));
+        }
+        *job = Some(WarmJob {
+            started: Instant::now(),
+            total: crates.len(),
+            current: None,
+            warmed: vec![],
+            failed: vec![],
+            finished_after: None,
+            cancel: cancel.clone(),
+        });
+    }
+
+    if params.wait.unwrap_or(false) {
+        warm(state, crates, cancel).await;
+    } else {
+        let shared = state.clone();
+        state.spawn_background(crate::session::bind(async move { warm(&shared, crates, cancel).await }));
+    }
+    progress_result(state.warming.snapshot())
+}
+
+/// The canonical path of `path` if it names a `Cargo.toml` under the allowed root, so a
+/// client cannot have the server read arbitrary files.
+async fn allowed_manifest(path: &str) -> Result<PathBuf, ErrorData> {
+    let root = match std::env::var_os(MANIFEST_ROOT_ENV) {
+        Some(root) => PathBuf::from(root),
+        None => std::env::current_dir().map_err(|e| ErrorData::internal_error(e.to_string(), None))?,
+    };
+    if Path::new(path).file_name().is_none_or(|name| name != "Cargo.toml") {
+        return Err(ErrorData::invalid_params(
+            format!("manifest_path must name a Cargo.toml file, not '{path}'."),
+            ErrorCode::InvalidParams.data(),
+        ));
+    }
+    let not_found = |e: std::io::Error| ErrorData::invalid_params(
+        format!("Could not read manifest '{path}': {e}"),
+        ErrorCode::FileNotFound.data(),
+    );
+    let canonical = tokio::fs::canonicalize(path).await.map_err(not_found)?;
+    let root = tokio::fs::canonicalize(&root).await.unwrap_or(root);
+    // A symlink could point a Cargo.toml name elsewhere; check the resolved path again
+    if !canonical.starts_with(&root) || canonical.file_name().is_none_or(|name| name != "Cargo.toml") {
+        return Err(ErrorData::invalid_params(
+            format!("manifest_path must be a Cargo.toml under {}; set {MANIFEST_ROOT_ENV} to allow another directory.", root.display()),
+            ErrorCode::InvalidParams.data(),
+        ));
+    }
+    Ok(canonical)
+}
+
+/// Fetch each crate's sparse index, crates.io metadata and rustdoc JSON into the disk cache.
+/// Stops early when `cancel` fires.
+async fn warm(state: &AppState, crates: Vec<(String, Option<String>)>, cancel: CancellationToken) {
+    for (name, requirement) in crates {
+        state.warming.update(|job| job.current = Some(name.clone()));
+        let result = tokio::select! {
+            biased;
+            _ = cancel.cancelled() => break,
+            result = warm_one(state, &name, requirement.as_deref()) => result,
+        };
+        match result {
+            Ok(warmed) => state.warming.update(|job| job.warmed.push(warmed)),
+            Err(e) => state.warming.update(|job| job.failed.push(Failed { name, error: e.to_string() })),
+        }
+    }
+    state.warming.update(|job| {
+        job.current = None;
+        job.finished_after = Some(job.started.elapsed().as_secs_f64());
+    });
+}
+
+async fn warm_one(state: &AppState, name: &str, requirement: Option<&str>) -> Result<Warmed, DocsError> {
+    let lines = state.fetch_index(name).await?;
+    let line = match requirement {
+        Some(req) => find_matching(&lines, req),
+        None => find_latest_stable(&lines),
+    };
+    let version = line
+        .ok_or_else(|| DocsError::Other(format!("no published version matches '{}'", requirement.unwrap_or("*"))))?
+        .vers
+        .clone();
+
+    CratesIoClient::new(&state.client, &state.cache).get_crate(name).await?;
+    // The parsed document is dropped: only the cached download matters here, so parse
+    // the cheap outline
+    let docs = match fetch_rustdoc_json(name, &version, None, DocDetail::Outline, &state.client, &state.cache).await {
+        Ok(_) => true,
+        Err(DocsError::DocsNotFound { .. }) => false,
+        Err(e) => return Err(e),
+    };
+    Ok(Warmed { name: name.to_string(), version, docs })
+}
+
+fn progress_result(job: Option<WarmJob>) -> Result<CallToolResult, ErrorData> {
+    let output = match job {
+        None => json!({"state": "idle", "note": "No cache warm-up has run since the server started."}),
+        Some(job) => json!({
+            "state": match job.finished_after {
+                None if job.cancel.is_cancelled() => "cancelling",
+                None => "running",
+                Some(_) if job.cancel.is_cancelled() => "cancelled",
+                Some(_) => "finished",
+            },
+            "total": job.total,
+            "completed": job.warmed.len() + job.failed.len(),
+            "current": job.current,
+            "elapsed_secs": job.finished_after.unwrap_or_else(|| job.started.elapsed().as_secs_f64()),
+            "without_docs": job.warmed.iter().filter(|w| !w.docs).count(),
+            "warmed": job.warmed,
+            "failed": job.failed,
+        }),
+    };
+    let json = serde_json::to_string_pretty(&output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
+
+/// Registry dependencies of a manifest as `(package name, version requirement)`.
+///
+/// Reads the dependency tables, their `[target.<cfg>]` variants and
+/// `[workspace.dependencies]`; a renamed dependency gives its `package`. Path and git
+/// dependencies without a version, and `workspace = true` entries (listed under
+/// `[workspace.dependencies]`), are skipped.
+fn manifest_dependencies(manifest: &str) -> Result<Vec<(String, Option<String>)>, toml::de::Error> {
+    let manifest: toml::Table = manifest.parse()?;
+    let tables_in = |table: &toml::Table| -> Vec<toml::Table> {
+        DEPENDENCY_TABLES.iter().filter_map(|t| table.get(*t)?.as_table().cloned()).collect()
+    };
+    let mut tables = tables_in(&manifest);
+    for target in manifest.get("target").and_then(toml::Value::as_table).into_iter().flat_map(|t| t.values()) {
+        tables.extend(target.as_table().map(tables_in).unwrap_or_default());
+    }
+    tables.extend(manifest.get("workspace")
+        .and_then(|w| w.get("dependencies"))
+        .and_then(toml::Value::as_table)
+        .cloned());
+    Ok(tables.iter()
+        .flat_map(|table| table.iter())
+        .filter_map(|(key, spec)| dependency(key, spec))
+        .collect())
+}
+
+fn dependency(key: &str, spec: &toml::Value) -> Option<(String, Option<String>)> {
+    let fields = match spec {
+        toml::Value::String(version) => return Some((key.to_string(), Some(version.clone()))),
+        toml::Value::Table(fields) => fields,
+        _ => return None,
+    };
+    if fields.get("workspace").and_then(toml::Value::as_bool) == Some(true) {
+        return None;
+    }
+    let version = fields.get("version").and_then(toml::Value::as_str).map(str::to_string);
+    if version.is_none() && (fields.contains_key("path") || fields.contains_key("git")) {
+        return None;
+    }
+    let package = fields.get("package").and_then(toml::Value::as_str).unwrap_or(key);
+    Some((package.to_string(), version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_registry_dependencies_from_a_manifest() {
+        let manifest = r#"
+[package]
+name = "demo"
+version = "0.1.0"
+
+[dependencies]
+serde = { version = "1.0", features = ["derive"] } # serialization
+tokio = "1" # runtime
+local = { path = "../local" }
+vendored = { path = "../vendored", version = "2" }
+shared = { workspace = true }
+json = { package = "serde_json", version = "1" }
+
+[target.'cfg(unix)'.dependencies]
+libc = "0.2"
+
+[target.x86_64-pc-windows-msvc.build-dependencies]
+winres = { version = "0.1", default-features = false }
+
+[dev-dependencies.tempfile]
+version = "3"
+
+[workspace.dependencies]
+shared = "0.4"
+"#;
+        let deps = manifest_dependencies(manifest).unwrap();
+        let deps: Vec<(&str, Option<&str>)> = deps.iter().map(|(n, v)| (n.as_str(), v.as_deref())).collect();
+        // Tables in order, keys sorted within each
+        assert_eq!(deps, [
+            ("serde_json", Some("1")),
+            ("serde", Some("1.0")),
+            ("tokio", Some("1")),
+            ("vendored", Some("2")),
+            ("tempfile", Some("3")),
+            ("libc", Some("0.2")),
+            ("winres", Some("0.1")),
+            ("shared", Some("0.4")),
+        ]);
+        assert!(manifest_dependencies("[dependencies\nserde = ").is_err());
+    }
+
+    #[tokio::test]
+    async fn cancelled_warm_ups_stop_before_the_next_crate() {
+        let state = AppState::with_fixtures(crate::fixtures::default_fixture_dir()).await.unwrap();
+        let cancel = CancellationToken::new();
+        *state.warming.job.lock().unwrap() = Some(WarmJob {
+            started: Instant::now(),
+            total: 1,
+            current: None,
+            warmed: vec![],
+            failed: vec![],
+            finished_after: None,
+            cancel: cancel.clone(),
+        });
+        cancel.cancel();
+        warm(&state, vec![("rmcp".to_string(), None)], cancel).await;
+
+        let result = progress_result(state.warming.snapshot()).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(json["state"], "cancelled");
+        assert_eq!(json["completed"], 0);
+    }
+
+    #[tokio::test]
+    async fn manifests_must_be_cargo_toml_under_the_root() {
+        let root = std::env::current_dir().unwrap();
+        assert!(allowed_manifest(root.join("Cargo.toml").to_str().unwrap()).await.is_ok());
+        assert!(allowed_manifest(root.join("README.md").to_str().unwrap()).await.is_err());
+        assert!(allowed_manifest(root.join("src/../Cargo.toml").to_str().unwrap()).await.is_ok());
+        let outside = std::env::temp_dir().join(format!("docs-mcp-manifest-{}", std::process::id()));
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::write(outside.join("Cargo.toml"), "[dependencies]\n").unwrap();
+        let err = allowed_manifest(outside.join("Cargo.toml").to_str().unwrap()).await.unwrap_err();
+        assert!(err.message.contains(MANIFEST_ROOT_ENV), "got: {}", err.message);
+        let _ = std::fs::remove_dir_all(outside);
+    }
+}
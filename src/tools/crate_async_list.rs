@@ -0,0 +1,196 @@
+use std::collections::BTreeMap;
+
+use rmcp::{ErrorData, model::{CallToolResult, Content}};
+use serde::{Deserialize, Serialize};
+use rmcp::schemars::{self, JsonSchema};
+use serde_json::{Value, json};
+
+use super::{AppState, docs_not_found_error};
+use crate::docsrs::{function_signature, id_to_string, Item, RustdocJson};
+
+/// Traits whose `impl Trait` / `dyn Trait` return types are futures.
+const FUTURE_TRAITS: [&str; 3] = ["Future", "TryFuture", "IntoFuture"];
+/// Traits whose `impl Trait` / `dyn Trait` return types are streams.
+const STREAM_TRAITS: [&str; 3] = ["Stream", "TryStream", "AsyncIterator"];
+/// Boxed-future and boxed-stream aliases from `futures`.
+const FUTURE_ALIASES: [&str; 2] = ["BoxFuture", "LocalBoxFuture"];
+const STREAM_ALIASES: [&str; 2] = ["BoxStream", "LocalBoxStream"];
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CrateAsyncListParams {
+    /// Crate name
+    pub name: String,
+    /// Version string. Defaults to latest stable.
+    pub version: Option<String>,
+}
+
+/// How a function is asynchronous.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum AsyncKind {
+    /// `async fn`
+    AsyncFn,
+    /// Returns `impl Future`, `Pin<Box<dyn Future>>`, `BoxFuture`, ...
+    Future,
+    /// Returns `impl Stream`, `BoxStream`, ...
+    Stream,
+}
+
+#[derive(Debug, Serialize)]
+struct AsyncFunction {
+    path: String,
+    kind: AsyncKind,
+    signature: String,
+}
+
+pub async fn execute(state: &AppState, params: CrateAsyncListParams) -> Result<CallToolResult, ErrorData> {
+    let name = &params.name;
+    let version = state.resolve_version(name, params.version.as_deref()).await
+        .map_err(ErrorData::from)?;
+
+    let doc = match state.rustdoc(name, &version).await {
+        Ok(d) => d,
+        Err(crate::error::DocsError::DocsNotFound { .. }) => {
+            return Err(docs_not_found_error(state, name, &version).await);
+        }
+        Err(e) => return Err(ErrorData::from(e)),
+    };
+
+    let functions = public_functions(&doc);
+    let total = functions.len();
+    let mut by_module: BTreeMap<String, Vec<AsyncFunction>> = BTreeMap::new();
+    for (module, path, item) in functions {
+        let Some(kind) = async_kind(item) else { continue };
+        by_module.entry(module).or_default().push(AsyncFunction { path, kind, signature: function_signature(item) });
+    }
+    let count = |kind: AsyncKind| by_module.values().flatten().filter(|f| f.kind == kind).count();
+    let (async_fns, futures, streams) = (count(AsyncKind::AsyncFn), count(AsyncKind::Future), count(AsyncKind::Stream));
+    let async_total = async_fns + futures + streams;
+
+    let modules: Vec<Value> = by_module.into_iter()
+        .map(|(module, mut functions)| {
+            functions.sort_by(|a, b| a.path.cmp(&b.path));
+            json!({"module": module, "count": functions.len(), "functions": functions})
+        })
+        .collect();
+    let output = json!({
+        "name": name,
+        "version": version,
+        "summary": {
+            "async_fns": async_fns,
+            "future_returning": futures,
+            "stream_returning": streams,
+            "public_functions": total,
+            // Share of the public functions and methods that are async in one of the three ways
+            "async_share": if total == 0 { 0.0 } else { (async_total as f64 / total as f64 * 1000.0).round() / 1000.0 },
+        },
+        "modules": modules,
+    });
+
+    let json = serde_json::to_string_pretty(&output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
+
+/// Every visible free function, inherent method and trait method of the crate, as
+/// `(module, path, item)`. Methods belong to their type's module.
+fn public_functions(doc: &RustdocJson) -> Vec<(String, String, &Item)> {
+    let module_of = |path: &str| path.rsplit_once("::").map_or(path, |(module, _)| module).to_string();
+    let mut functions = vec![];
+    for (id, entry) in &doc.paths {
+        if entry.crate_id != 0 { continue; }
+        let Some(item) = doc.index.get(id).filter(|i| i.is_visible()) else { continue };
+        let path = entry.full_path();
+        match entry.kind_name() {
+            "function" => functions.push((module_of(&path), path, item)),
+            "trait" => {
+                let ids = item.inner_for("trait").and_then(|t| t.get("items")).and_then(Value::as_array);
+                for method in ids.into_iter().flatten().filter_map(|id| doc.index.get(&id_to_string(id)?)) {
+                    if method.kind() != Some("function") { continue; }
+                    let Some(method_name) = method.name.as_deref() else { continue };
+                    functions.push((module_of(&path), format!("{path}::{method_name}"), method));
+                }
+            }
+            _ => {}
+        }
+    }
+    for (id, parent_path) in doc.method_parents() {
+        let Some(item) = doc.index.get(id).filter(|i| i.is_visible()) else { continue };
+        if item.kind() != Some("function") { continue; }
+        let Some(method_name) = item.name.as_deref() else { continue };
+        functions.push((module_of(parent_path), format!("{parent_path}::{method_name}"), item));
+    }
+    functions
+}
+
+/// Whether a function is `async`, or returns a future or stream; `None` when synchronous.
+fn async_kind(item: &Item) -> Option<AsyncKind> {
+    let inner = item.inner_for("function")?;
+    if inner.get("header").and_then(|h| h.get("is_async")).and_then(Value::as_bool).unwrap_or(false) {
+        return Some(AsyncKind::AsyncFn);
+    }
+    let output = inner.get("sig")?.get("output").filter(|o| !o.is_null())?;
+    returned_kind(output)
+}
+
+/// Future or stream found in a return type: an `impl`/`dyn` bound on one of
+/// [`FUTURE_TRAITS`] / [`STREAM_TRAITS`] or a boxed alias, at any depth (`Pin<Box<dyn Future>>`).
+fn returned_kind(ty: &Value) -> Option<AsyncKind> {
+    match ty {
+        Value::Object(map) => {
+            // `trait` holds the bound of both `impl Trait` and `dyn Trait`
+            let traits = map.get("trait").and_then(|t| t.get("path")).and_then(Value::as_str);
+            let aliases = map.get("resolved_path").and_then(|r| r.get("path")).and_then(Value::as_str);
+            let named = |names: &[&str], path: Option<&str>| {
+                path.and_then(|p| p.rsplit("::").next()).is_some_and(|last| names.contains(&last))
+            };
+            if named(&STREAM_TRAITS, traits) || named(&STREAM_ALIASES, aliases) {
+                return Some(AsyncKind::Stream);
+            }
+            if named(&FUTURE_TRAITS, traits) || named(&FUTURE_ALIASES, aliases) {
+                return Some(AsyncKind::Future);
+            }
+            map.values().find_map(returned_kind)
+        }
+        Value::Array(items) => items.iter().find_map(returned_kind),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn function(is_async: bool, output: Value) -> Item {
+        serde_json::from_value(json!({
+            "id": 1, "name": "f",
+            "inner": {"function": {
+                "generics": {"params": [], "where_predicates": []},
+                "header": {"is_const": false, "is_unsafe": false, "is_async": is_async, "abi": "Rust"},
+                "has_body": true,
+                "sig": {"inputs": [], "output": output, "is_c_variadic": false},
+            }},
+        })).unwrap()
+    }
+
+    fn bound(path: &str) -> Value {
+        json!({"trait_bound": {"trait": {"path": path, "id": 9, "args": null}, "generic_params": [], "modifier": "none"}})
+    }
+
+    #[test]
+    fn classifies_async_fns_futures_and_streams() {
+        assert_eq!(async_kind(&function(true, Value::Null)), Some(AsyncKind::AsyncFn));
+        assert_eq!(async_kind(&function(false, json!({"impl_trait": [bound("Future"), bound("Send")]}))), Some(AsyncKind::Future));
+        assert_eq!(async_kind(&function(false, json!({"impl_trait": [bound("futures_core::Stream")]}))), Some(AsyncKind::Stream));
+
+        // Pin<Box<dyn Future<Output = ()> + Send>>
+        let dyn_future = json!({"dyn_trait": {"traits": [{"trait": {"path": "Future", "id": 9, "args": null}, "generic_params": []}], "lifetime": null}});
+        let boxed = json!({"resolved_path": {"path": "Box", "id": 7, "args": {"angle_bracketed": {"args": [{"type": dyn_future}], "constraints": []}}}});
+        let pinned = json!({"resolved_path": {"path": "Pin", "id": 8, "args": {"angle_bracketed": {"args": [{"type": boxed}], "constraints": []}}}});
+        assert_eq!(async_kind(&function(false, pinned)), Some(AsyncKind::Future));
+
+        // A type merely named like a stream is not one
+        let tcp = json!({"resolved_path": {"path": "TcpStream", "id": 6, "args": null}});
+        assert_eq!(async_kind(&function(false, tcp)), None);
+    }
+}
@@ -0,0 +1,539 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rmcp::{ErrorData, model::{CallToolResult, Content}};
+use serde::{Deserialize, Serialize};
+use rmcp::schemars::{self, JsonSchema};
+use semver::Version;
+
+use super::AppState;
+use crate::cratesio::{CratesIoClient, DownloadsResponse, VersionInfo};
+use crate::depsdev;
+use crate::osv::Advisory;
+use crate::sparse_index::{DepKind, IndexLine};
+
+/// Days in each of the two download windows compared for the trend.
+const TREND_WINDOW_DAYS: i64 = 30;
+/// Relative change in downloads between the windows that counts as growth or decline.
+const TREND_THRESHOLD: f64 = 0.2;
+/// A latest release older than this many days is flagged as possibly unmaintained.
+const STALE_AFTER_DAYS: i64 = 365;
+/// Scorecard checks scoring below this are listed as weak, and an overall score below it flagged.
+const WEAK_SCORE: f64 = 5.0;
+/// Most recent yanks listed in the report.
+const MAX_LISTED_YANKS: usize = 5;
+
+#[derive(Serialize)]
+struct AuditReport {
+    name: String,
+    version: String,
+    /// Concerns worth a closer look, most serious first
+    flags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    overview: Option<Overview>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    owners: Option<Owners>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    downloads: Option<Downloads>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dependents: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    advisories: Option<Advisories>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    yanks: Option<Yanks>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    repository_health: Option<RepositoryHealth>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dependencies: Option<DependencyCounts>,
+    /// Sections that could not be fetched, with why; the rest of the report stands
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    errors: BTreeMap<&'static str, String>,
+}
+
+#[derive(Serialize)]
+struct Overview {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    repository: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    license: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latest_stable: Option<String>,
+    /// When the audited version was published
+    #[serde(skip_serializing_if = "Option::is_none")]
+    published_at: Option<String>,
+    /// Days since the newest release of any version
+    #[serde(skip_serializing_if = "Option::is_none")]
+    days_since_last_release: Option<i64>,
+    total_downloads: u64,
+}
+
+#[derive(Serialize)]
+struct Owners {
+    users: Vec<String>,
+    teams: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct Downloads {
+    last_30_days: u64,
+    previous_30_days: u64,
+    /// "growing", "steady", "declining" or "new" (no downloads in the earlier window)
+    trend: &'static str,
+    /// Relative change from the earlier window to the latest one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    change: Option<f64>,
+}
+
+#[derive(Serialize)]
+struct Advisories {
+    /// Advisories ever filed against the crate, withdrawn ones excluded
+    total: usize,
+    /// Those whose affected ranges include the audited version
+    affecting_version: Vec<AdvisorySummary>,
+}
+
+#[derive(Serialize)]
+struct AdvisorySummary {
+    id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+    fixed_in: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct Yanks {
+    total_versions: usize,
+    yanked_count: usize,
+    version_yanked: bool,
+    /// The most recently published yanked versions
+    recent: Vec<YankedVersion>,
+}
+
+#[derive(Serialize)]
+struct YankedVersion {
+    version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    yank_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct RepositoryHealth {
+    project: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stars: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    open_issues: Option<u64>,
+    /// OpenSSF Scorecard overall score, 0 to 10
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scorecard: Option<f64>,
+    /// Scorecard checks scoring below 5, weakest first
+    weak_checks: Vec<String>,
+}
+
+/// Dependencies declared by the audited version, by kind.
+#[derive(Serialize)]
+struct DependencyCounts {
+    /// Normal dependencies every build compiles
+    required: usize,
+    optional: usize,
+    build: usize,
+    dev: usize,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CrateAuditReportParams {
+    /// Crate name
+    pub name: String,
+    /// Version string. Defaults to latest stable.
+    pub version: Option<String>,
+    /// "json" (default) for the structured report, or "markdown" for a readable summary
+    pub format: Option<String>,
+}
+
+pub async fn execute(state: &AppState, params: CrateAuditReportParams) -> Result<CallToolResult, ErrorData> {
+    let name = &params.name;
+    let markdown = match params.format.as_deref().unwrap_or("json") {
+        "json" => false,
+        "markdown" => true,
+        other => {
+            return Err(ErrorData::invalid_params(
+                format!("Unknown format '{other}'. Use \"json\" or \"markdown\"."),
+                None,
+            ));
+        }
+    };
+    let version = state.resolve_version(name, params.version.as_deref()).await
+        .map_err(ErrorData::from)?;
+    let lines = state.fetch_index(name).await
+        .map_err(ErrorData::from)?;
+
+    let client = CratesIoClient::new(&state.client, &state.cache)
+        .with_dump(state.dump_db.as_ref());
+    let (krate, versions, owners, downloads, dependents, advisories, package) = tokio::join!(
+        client.get_crate(name),
+        client.get_versions(name),
+        client.get_owners(name),
+        client.get_downloads(name, None),
+        client.get_reverse_deps(name, 1, 1),
+        crate::osv::advisories(&state.client, &state.cache, name),
+        depsdev::package_version(&state.client, &state.cache, name, &version)
+    );
+
+    let mut report = AuditReport {
+        name: name.clone(),
+        version: version.clone(),
+        flags: vec![],
+        overview: None,
+        owners: None,
+        downloads: None,
+        dependents: None,
+        advisories: None,
+        yanks: None,
+        repository_health: None,
+        dependencies: lines.iter().find(|l| l.vers == version).map(dependency_counts),
+        errors: BTreeMap::new(),
+    };
+    let now = Utc::now();
+
+    let versions = versions.map(|v| v.versions);
+    match (krate, &versions) {
+        (Ok(krate), Ok(versions)) => report.overview = Some(overview(krate.krate, versions, &version, now)),
+        (Err(e), _) => {
+            report.errors.insert("overview", e.to_string());
+        }
+        (_, Err(e)) => {
+            report.errors.insert("overview", e.to_string());
+        }
+    }
+    match &versions {
+        Ok(versions) => report.yanks = Some(yanks(versions, &version)),
+        Err(e) => {
+            report.errors.insert("yanks", e.to_string());
+        }
+    }
+    match owners {
+        Ok(owners) => {
+            let (teams, users): (Vec<_>, Vec<_>) = owners.users.into_iter()
+                .partition(|o| o.kind.as_deref() == Some("team"));
+            report.owners = Some(Owners {
+                users: users.into_iter().map(|o| o.login).collect(),
+                teams: teams.into_iter().map(|o| o.login).collect(),
+            });
+        }
+        Err(e) => {
+            report.errors.insert("owners", e.to_string());
+        }
+    }
+    match downloads {
+        Ok(downloads) => report.downloads = Some(download_trend(&downloads)),
+        Err(e) => {
+            report.errors.insert("downloads", e.to_string());
+        }
+    }
+    match dependents {
+        Ok(dependents) => report.dependents = Some(dependents.meta.total),
+        Err(e) => {
+            report.errors.insert("dependents", e.to_string());
+        }
+    }
+    match advisories {
+        Ok(advisories) => report.advisories = Some(advisories_for(name, &version, advisories)),
+        Err(e) => {
+            report.errors.insert("advisories", e.to_string());
+        }
+    }
+    let repo = match package {
+        Ok(package) => package.source_repo().map(str::to_string),
+        Err(e) => {
+            report.errors.insert("repository_health", e.to_string());
+            None
+        }
+    };
+    if let Some(repo) = repo {
+        match depsdev::project(&state.client, &state.cache, &repo).await {
+            Ok(project) => report.repository_health = Some(repository_health(project)),
+            Err(e) => {
+                report.errors.insert("repository_health", format!("{repo}: {e}"));
+            }
+        }
+    }
+
+    report.flags = flags(&report);
+    let text = if markdown {
+        to_markdown(&report)
+    } else {
+        serde_json::to_string_pretty(&report)
+            .map_err(|e| ErrorData::internal_error(e.to_string(), None))?
+    };
+    Ok(CallToolResult::success(vec![Content::text(text)]))
+}
+
+fn overview(krate: crate::cratesio::CrateInfo, versions: &[VersionInfo], version: &str, now: DateTime<Utc>) -> Overview {
+    let audited = versions.iter().find(|v| v.num == version);
+    let last_release = versions.iter()
+        .filter_map(|v| DateTime::parse_from_rfc3339(&v.created_at).ok())
+        .max();
+    Overview {
+        description: krate.description.map(|d| d.trim().to_string()),
+        repository: krate.repository,
+        license: audited.and_then(|v| v.license.clone()),
+        latest_stable: krate.max_stable_version,
+        published_at: audited.map(|v| v.created_at.clone()),
+        days_since_last_release: last_release.map(|d| (now - d.with_timezone(&Utc)).num_days()),
+        total_downloads: krate.downloads,
+    }
+}
+
+fn yanks(versions: &[VersionInfo], version: &str) -> Yanks {
+    let mut yanked: Vec<&VersionInfo> = versions.iter().filter(|v| v.yanked).collect();
+    yanked.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Yanks {
+        total_versions: versions.len(),
+        yanked_count: yanked.len(),
+        version_yanked: yanked.iter().any(|v| v.num == version),
+        recent: yanked.iter().take(MAX_LISTED_YANKS)
+            .map(|v| YankedVersion { version: v.num.clone(), yank_message: v.yank_message.clone() })
+            .collect(),
+    }
+}
+
+/// Downloads in the latest [`TREND_WINDOW_DAYS`] of the data against the window before.
+fn download_trend(downloads: &DownloadsResponse) -> Downloads {
+    let dates: Vec<Option<NaiveDate>> = downloads.version_downloads.iter()
+        .map(|d| NaiveDate::parse_from_str(&d.date, "%Y-%m-%d").ok())
+        .collect();
+    let (mut last, mut previous) = (0, 0);
+    if let Some(latest) = dates.iter().flatten().max().copied() {
+        for (d, date) in downloads.version_downloads.iter().zip(&dates) {
+            let Some(date) = date else { continue };
+            match (latest - *date).num_days() / TREND_WINDOW_DAYS {
+                0 => last += d.downloads,
+                1 => previous += d.downloads,
+                _ => {}
+            }
+        }
+    }
+    let change = (previous > 0).then(|| last as f64 / previous as f64 - 1.0);
+    let trend = match change {
+        None => "new",
+        Some(c) if c >= TREND_THRESHOLD => "growing",
+        Some(c) if c <= -TREND_THRESHOLD => "declining",
+        Some(_) => "steady",
+    };
+    Downloads {
+        last_30_days: last,
+        previous_30_days: previous,
+        trend,
+        change: change.map(|c| (c * 1000.0).round() / 1000.0),
+    }
+}
+
+fn advisories_for(name: &str, version: &str, advisories: Vec<Advisory>) -> Advisories {
+    let advisories: Vec<Advisory> = advisories.into_iter().filter(|a| a.withdrawn.is_none()).collect();
+    let parsed = Version::parse(version).ok();
+    Advisories {
+        total: advisories.len(),
+        affecting_version: advisories.iter()
+            .filter(|a| parsed.as_ref().is_some_and(|v| a.affects(name, v)))
+            .map(|a| AdvisorySummary { id: a.id.clone(), summary: a.summary.clone(), fixed_in: a.fixed_in(name) })
+            .collect(),
+    }
+}
+
+fn repository_health(project: depsdev::Project) -> RepositoryHealth {
+    let (scorecard, weak_checks) = match project.scorecard {
+        Some(scorecard) => {
+            let mut weak: Vec<_> = scorecard.checks.into_iter()
+                .filter(|c| c.score >= 0 && (c.score as f64) < WEAK_SCORE)
+                .collect();
+            weak.sort_by_key(|c| c.score);
+            (scorecard.overall_score, weak.into_iter().map(|c| c.name).collect())
+        }
+        None => (None, vec![]),
+    };
+    RepositoryHealth {
+        project: project.project_key.id,
+        stars: project.stars_count,
+        open_issues: project.open_issues_count,
+        scorecard,
+        weak_checks,
+    }
+}
+
+fn dependency_counts(line: &IndexLine) -> DependencyCounts {
+    let kind = |d: &crate::sparse_index::DepEntry| d.kind.clone().unwrap_or(DepKind::Normal);
+    let normal = || line.deps.iter().filter(|d| kind(d) == DepKind::Normal);
+    DependencyCounts {
+        required: normal().filter(|d| !d.optional).count(),
+        optional: normal().filter(|d| d.optional).count(),
+        build: line.deps.iter().filter(|d| kind(d) == DepKind::Build).count(),
+        dev: line.deps.iter().filter(|d| kind(d) == DepKind::Dev).count(),
+    }
+}
+
+/// The findings a reviewer should look at first, most serious first.
+fn flags(report: &AuditReport) -> Vec<String> {
+    let mut flags = vec![];
+    if let Some(advisories) = report.advisories.as_ref().filter(|a| !a.affecting_version.is_empty()) {
+        let ids: Vec<&str> = advisories.affecting_version.iter().map(|a| a.id.as_str()).collect();
+        flags.push(format!("{} is affected by {}", report.version, ids.join(", ")));
+    }
+    if report.yanks.as_ref().is_some_and(|y| y.version_yanked) {
+        flags.push(format!("{} is yanked", report.version));
+    }
+    if let Some(days) = report.overview.as_ref().and_then(|o| o.days_since_last_release).filter(|d| *d > STALE_AFTER_DAYS) {
+        flags.push(format!("no release in {days} days"));
+    }
+    if let Some(owners) = report.owners.as_ref().filter(|o| o.users.len() == 1 && o.teams.is_empty()) {
+        flags.push(format!("single owner ({}) and no team", owners.users[0]));
+    }
+    if let Some(score) = report.repository_health.as_ref().and_then(|r| r.scorecard).filter(|s| *s < WEAK_SCORE) {
+        flags.push(format!("OpenSSF Scorecard {score}/10"));
+    }
+    if let Some(downloads) = report.downloads.as_ref().filter(|d| d.trend == "declining") {
+        let change = downloads.change.unwrap_or_default();
+        flags.push(format!("downloads down {:.0}% on the previous 30 days", -change * 100.0));
+    }
+    if report.dependents == Some(0) {
+        flags.push("no crates on crates.io depend on it".to_string());
+    }
+    flags
+}
+
+fn to_markdown(report: &AuditReport) -> String {
+    let mut md = format!("# Audit report: {} {}\n\n", report.name, report.version);
+    let unavailable = |section: &str| report.errors.get(section).map_or_else(
+        || "- not available\n".to_string(),
+        |e| format!("- unavailable: {e}\n"),
+    );
+
+    md.push_str("## Flags\n\n");
+    if report.flags.is_empty() {
+        md.push_str("- none\n");
+    }
+    for flag in &report.flags {
+        let _ = writeln!(md, "- {flag}");
+    }
+
+    md.push_str("\n## Overview\n\n");
+    match &report.overview {
+        Some(o) => {
+            if let Some(description) = &o.description {
+                let _ = writeln!(md, "{description}\n");
+            }
+            let field = |label: &str, value: Option<&str>| value.map(|v| format!("- {label}: {v}\n")).unwrap_or_default();
+            md.push_str(&field("Repository", o.repository.as_deref()));
+            md.push_str(&field("License", o.license.as_deref()));
+            md.push_str(&field("Latest stable", o.latest_stable.as_deref()));
+            md.push_str(&field("Published", o.published_at.as_deref()));
+            if let Some(days) = o.days_since_last_release {
+                let _ = writeln!(md, "- Days since last release: {days}");
+            }
+            let _ = writeln!(md, "- Total downloads: {}", o.total_downloads);
+        }
+        None => md.push_str(&unavailable("overview")),
+    }
+
+    md.push_str("\n## Owners\n\n");
+    match &report.owners {
+        Some(o) => {
+            let _ = writeln!(md, "- Users: {}", list_or_none(&o.users));
+            let _ = writeln!(md, "- Teams: {}", list_or_none(&o.teams));
+        }
+        None => md.push_str(&unavailable("owners")),
+    }
+
+    md.push_str("\n## Adoption\n\n");
+    match &report.downloads {
+        Some(d) => {
+            let _ = writeln!(md, "- Downloads, last 30 days: {} ({}; {} the 30 days before)", d.last_30_days, d.trend, d.previous_30_days);
+        }
+        None => md.push_str(&unavailable("downloads")),
+    }
+    match report.dependents {
+        Some(total) => {
+            let _ = writeln!(md, "- Dependent crates: {total}");
+        }
+        None => md.push_str(&unavailable("dependents")),
+    }
+
+    md.push_str("\n## Advisories\n\n");
+    match &report.advisories {
+        Some(a) => {
+            let _ = writeln!(md, "- Filed against the crate: {}", a.total);
+            for advisory in &a.affecting_version {
+                let fixed = if advisory.fixed_in.is_empty() { "no fix".to_string() } else { format!("fixed in {}", advisory.fixed_in.join(", ")) };
+                let _ = writeln!(md, "- Affects {}: {} {} ({fixed})", report.version, advisory.id, advisory.summary.as_deref().unwrap_or(""));
+            }
+        }
+        None => md.push_str(&unavailable("advisories")),
+    }
+
+    md.push_str("\n## Yanks\n\n");
+    match &report.yanks {
+        Some(y) => {
+            let _ = writeln!(md, "- {} of {} versions yanked", y.yanked_count, y.total_versions);
+            for v in &y.recent {
+                let _ = writeln!(md, "- {}: {}", v.version, v.yank_message.as_deref().unwrap_or("no message"));
+            }
+        }
+        None => md.push_str(&unavailable("yanks")),
+    }
+
+    md.push_str("\n## Repository health\n\n");
+    match &report.repository_health {
+        Some(r) => {
+            let _ = writeln!(md, "- Project: {}", r.project);
+            if let Some(stars) = r.stars {
+                let _ = writeln!(md, "- Stars: {stars}");
+            }
+            if let Some(issues) = r.open_issues {
+                let _ = writeln!(md, "- Open issues: {issues}");
+            }
+            match r.scorecard {
+                Some(score) => {
+                    let _ = writeln!(md, "- OpenSSF Scorecard: {score}/10; weak checks: {}", list_or_none(&r.weak_checks));
+                }
+                None => md.push_str("- OpenSSF Scorecard: not scored\n"),
+            }
+        }
+        None => md.push_str(&unavailable("repository_health")),
+    }
+
+    md.push_str("\n## Dependencies\n\n");
+    match &report.dependencies {
+        Some(d) => {
+            let _ = writeln!(md, "- {} required, {} optional, {} build, {} dev", d.required, d.optional, d.build, d.dev);
+        }
+        None => md.push_str("- not in the index\n"),
+    }
+    md
+}
+
+fn list_or_none(items: &[String]) -> String {
+    if items.is_empty() { "none".to_string() } else { items.join(", ") }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cratesio::VersionDownload;
+
+    #[test]
+    fn download_trend_compares_the_latest_windows() {
+        let day = |date: &str, downloads| VersionDownload { version: 1, downloads, date: date.to_string() };
+        let downloads = DownloadsResponse {
+            version_downloads: vec![day("2026-03-31", 300), day("2026-03-02", 300), day("2026-02-20", 1000), day("2025-12-01", 5000)],
+        };
+        let trend = download_trend(&downloads);
+        assert_eq!((trend.last_30_days, trend.previous_30_days), (600, 1000));
+        assert_eq!(trend.trend, "declining");
+        assert_eq!(trend.change, Some(-0.4));
+
+        let fresh = DownloadsResponse { version_downloads: vec![day("2026-03-31", 10)] };
+        assert_eq!(download_trend(&fresh).trend, "new");
+    }
+}
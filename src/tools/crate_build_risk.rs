@@ -0,0 +1,151 @@
+use std::sync::Arc;
+
+use rmcp::{ErrorData, model::{CallToolResult, Content}};
+use serde::{Deserialize, Serialize};
+use rmcp::schemars::{self, JsonSchema};
+use serde_json::json;
+
+use super::AppState;
+use super::crate_dependencies_list::{walk_transitive, MAX_DEPTH, MAX_TRANSITIVE};
+use crate::cratesio::tarball::{BuildProfile, CrateTarball};
+use crate::error::DocsError;
+use crate::session;
+use crate::sparse_index::DepKind;
+
+/// Package archives downloaded at once while checking the dependency tree.
+const MAX_CONCURRENT_ARCHIVES: usize = 8;
+/// Package archives one call downloads; cached archives don't count, so calling again
+/// checks the dependencies left over.
+const MAX_ARCHIVE_DOWNLOADS: usize = 60;
+
+/// A dependency whose build runs code: a build script or a proc macro.
+#[derive(Serialize)]
+struct BuildCodeDep {
+    name: String,
+    version: String,
+    #[serde(flatten)]
+    profile: BuildProfile,
+    /// Crates from the root down to this one
+    path: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct UncheckedDep {
+    name: String,
+    version: String,
+    reason: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CrateBuildRiskParams {
+    /// Crate name
+    pub name: String,
+    /// Version string. Defaults to latest stable.
+    pub version: Option<String>,
+    /// Levels of the dependency tree to check; 0 checks only the crate itself (default: 8, max: 8)
+    pub depth: Option<u32>,
+}
+
+pub async fn execute(state: &Arc<AppState>, params: CrateBuildRiskParams) -> Result<CallToolResult, ErrorData> {
+    let name = &params.name;
+    let version = state.resolve_version(name, params.version.as_deref()).await
+        .map_err(ErrorData::from)?;
+    let lines = state.fetch_index(name).await
+        .map_err(ErrorData::from)?;
+    let line = lines.iter().find(|l| l.vers == version).ok_or_else(|| ErrorData::from(DocsError::VersionNotFound {
+        name: name.clone(),
+        version: version.clone(),
+    }))?;
+
+    let tarball = state.crate_tarball(name, &version).await
+        .map_err(ErrorData::from)?;
+    let profile = tarball.build_profile().await
+        .map_err(ErrorData::from)?;
+
+    let mut output = json!({
+        "name": name,
+        "version": version,
+        "build_script": profile.build_script,
+        "links": profile.links,
+        "proc_macro": profile.proc_macro,
+        "checksum": tarball.checksum(),
+    });
+
+    let depth = params.depth.unwrap_or(MAX_DEPTH).min(MAX_DEPTH);
+    if depth == 0 {
+        return to_result(&output);
+    }
+
+    // Build dependencies are compiled and run on the build machine too, so follow them
+    let kinds = [DepKind::Normal, DepKind::Build];
+    let walk = walk_transitive(state, line, depth, &kinds).await;
+
+    let resolved = walk.resolved.len();
+    let mut build_code: Vec<BuildCodeDep> = vec![];
+    let mut unchecked: Vec<UncheckedDep> = vec![];
+    let mut pending = walk.resolved.into_iter();
+    let mut downloads = 0;
+    let mut set = tokio::task::JoinSet::new();
+    loop {
+        while set.len() < MAX_CONCURRENT_ARCHIVES {
+            let Some(dep) = pending.next() else { break };
+            if !CrateTarball::is_cached(&dep.name, &dep.version, &state.cache) {
+                if downloads == MAX_ARCHIVE_DOWNLOADS {
+                    let reason = format!("not downloaded: this call already downloaded {MAX_ARCHIVE_DOWNLOADS} package archives; call again to check it");
+                    unchecked.push(UncheckedDep { name: dep.name, version: dep.version, reason });
+                    continue;
+                }
+                downloads += 1;
+            }
+            let state = Arc::clone(state);
+            set.spawn(session::bind(async move {
+                let profile = match state.crate_tarball(&dep.name, &dep.version).await {
+                    Ok(tarball) => tarball.build_profile().await,
+                    Err(e) => Err(e),
+                };
+                (dep, profile)
+            }));
+        }
+        let Some(joined) = set.join_next().await else { break };
+        let (dep, profile) = joined
+            .map_err(|e| ErrorData::internal_error(format!("package archive task failed: {e}"), None))?;
+        match profile {
+            Ok(profile) if profile.build_script.is_some() || profile.proc_macro => {
+                build_code.push(BuildCodeDep { name: dep.name, version: dep.version, profile, path: dep.path });
+            }
+            Ok(_) => {}
+            Err(e) => unchecked.push(UncheckedDep { name: dep.name, version: dep.version, reason: e.to_string() }),
+        }
+    }
+    // Tasks finish in any order; list shallow dependencies first
+    build_code.sort_by(|a, b| (a.path.len(), &a.name, &a.version).cmp(&(b.path.len(), &b.name, &b.version)));
+
+    let count = |f: fn(&BuildProfile) -> bool| build_code.iter().filter(|d| f(&d.profile)).count();
+    let mut dependencies = json!({
+        "depth": depth,
+        "checked": resolved - unchecked.len(),
+        "runs_build_code": build_code.len(),
+        "build_scripts": count(|p| p.build_script.is_some()),
+        "proc_macros": count(|p| p.proc_macro),
+        "native_links": count(|p| p.links.is_some()),
+        "crates": build_code,
+    });
+    if !unchecked.is_empty() {
+        dependencies["unchecked"] = json!(unchecked);
+    }
+    if !walk.unresolved.is_empty() {
+        dependencies["unresolved"] = json!(walk.unresolved);
+    }
+    if walk.truncated {
+        dependencies["truncated"] = json!(format!("stopped after {MAX_TRANSITIVE} crates; lower depth to see a complete level"));
+    }
+    output["dependencies"] = dependencies;
+
+    to_result(&output)
+}
+
+fn to_result(output: &serde_json::Value) -> Result<CallToolResult, ErrorData> {
+    let json = serde_json::to_string_pretty(output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
@@ -2,8 +2,21 @@ use rmcp::{ErrorData, model::{CallToolResult, Content}};
 use serde::{Deserialize, Serialize};
 use rmcp::schemars::{self, JsonSchema};
 use serde_json::json;
+use std::collections::{HashMap, HashSet};
+
+use futures::stream::{self, StreamExt};
 
 use super::AppState;
+use crate::error::DocsError;
+use crate::osv::Advisory;
+use crate::sparse_index::{find_matching, DepEntry as IndexDep, DepKind, IndexLine};
+
+/// Deepest level `depth` may ask for; the direct dependencies are level 1.
+pub(crate) const MAX_DEPTH: u32 = 8;
+/// Crates resolved at most in recursive mode, each costing one sparse index lookup.
+pub(crate) const MAX_TRANSITIVE: usize = 300;
+/// Sparse index lookups in flight at once, resolving requirements or walking a level.
+const MAX_CONCURRENT_FETCHES: usize = 8;
 
 #[derive(Serialize)]
 struct DepEntry {
@@ -15,6 +28,57 @@ struct DepEntry {
     features: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     target: Option<String>,
+    /// The version `req` selects today; only with `resolve`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resolved: Option<ResolvedReq>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resolve_error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ResolvedReq {
+    version: String,
+    rust_version: Option<String>,
+    /// Only yanked versions match `req`, so new lockfiles can't select it
+    yanked: bool,
+}
+
+/// One crate pulled in by following dependencies through the sparse index.
+#[derive(Serialize)]
+pub(crate) struct TransitiveDep {
+    pub(crate) name: String,
+    /// Highest published version matching `req`
+    pub(crate) version: String,
+    req: String,
+    depth: u32,
+    /// Crates from the root down to this one, e.g. `["reqwest", "hyper", "h2"]`
+    pub(crate) path: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct UnresolvedDep {
+    name: String,
+    req: String,
+    required_by: String,
+    reason: String,
+}
+
+/// A crate version in the audited tree with advisories against it.
+#[derive(Serialize)]
+struct VulnerableCrate {
+    name: String,
+    version: String,
+    /// "self", "direct" or "transitive"
+    relation: &'static str,
+    advisories: Vec<Vulnerability>,
+}
+
+#[derive(Serialize)]
+struct Vulnerability {
+    #[serde(flatten)]
+    advisory: Advisory,
+    /// Releases of the crate that fix it
+    fixed_in: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -27,21 +91,56 @@ pub struct CrateDependenciesListParams {
     pub kind: Option<String>,
     /// Filter results by dep name substring
     pub search: Option<String>,
+    /// Follow the normal dependencies a default build compiles (required ones and optional
+    /// ones turned on by default features) this many levels down through the sparse index and add a flattened `transitive` list (default: 1, direct dependencies only; max 8)
+    pub depth: Option<u32>,
+    /// Resolve each requirement against the sparse index, adding the version it selects
+    /// today with that version's MSRV and yank status (default: false)
+    pub resolve: Option<bool>,
+    /// Look up OSV.dev vulnerability records (RustSec and GitHub advisories) for this version
+    /// and the version each listed dependency resolves to (implies `resolve`), plus the
+    /// `transitive` crates when depth > 1 (default: false)
+    pub audit: Option<bool>,
+    /// Also render the resolved tree of those dependencies down to `depth`
+    /// as a `graph`: "dot" (Graphviz) or "mermaid", ready to paste into docs or chat
+    pub graph: Option<String>,
+}
+
+/// Text formats the dependency graph can be rendered in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GraphFormat {
+    Dot,
+    Mermaid,
+}
+
+impl GraphFormat {
+    fn parse(format: &str) -> Result<Self, ErrorData> {
+        match format.trim().to_ascii_lowercase().as_str() {
+            "dot" | "graphviz" => Ok(Self::Dot),
+            "mermaid" => Ok(Self::Mermaid),
+            other => Err(ErrorData::invalid_params(
+                format!("graph must be \"dot\" or \"mermaid\", not \"{other}\"."),
+                None,
+            )),
+        }
+    }
 }
 
 pub async fn execute(state: &AppState, params: CrateDependenciesListParams) -> Result<CallToolResult, ErrorData> {
     let name = &params.name;
+    let graph = params.graph.as_deref().map(GraphFormat::parse).transpose()?;
     let version = state.resolve_version(name, params.version.as_deref()).await
-        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+        .map_err(ErrorData::from)?;
 
-    let client = crate::cratesio::CratesIoClient::new(&state.client, &state.cache);
+    let client = crate::cratesio::CratesIoClient::new(&state.client, &state.cache)
+        .with_dump(state.dump_db.as_ref());
     let resp = client.get_dependencies(name, &version).await
-        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+        .map_err(ErrorData::from)?;
 
     let search_lower = params.search.as_deref().map(|s| s.to_lowercase());
     let kind_filter = params.kind.as_deref();
 
-    let deps = resp.dependencies.into_iter()
+    let mut deps = resp.dependencies.into_iter()
         .filter(|d| {
             if let Some(kf) = kind_filter {
                 let dep_kind = d.kind.as_deref().unwrap_or("normal");
@@ -62,17 +161,338 @@ pub async fn execute(state: &AppState, params: CrateDependenciesListParams) -> R
             default_features: d.default_features,
             features: d.features,
             target: d.target,
+            resolved: None,
+            resolve_error: None,
         })
         .collect::<Vec<_>>();
+    let audit = params.audit.unwrap_or(false);
+    if params.resolve.unwrap_or(false) || audit {
+        let lookups: Vec<_> = deps.iter().map(|dep| resolve_req(state, &dep.crate_id, &dep.req)).collect();
+        let results: Vec<_> = stream::iter(lookups).buffered(MAX_CONCURRENT_FETCHES).collect().await;
+        for (dep, result) in deps.iter_mut().zip(results) {
+            match result {
+                Ok(resolved) => dep.resolved = Some(resolved),
+                Err(e) => dep.resolve_error = Some(e),
+            }
+        }
+    }
 
-    let output = json!({
+    let mut output = json!({
         "name": name,
         "version": version,
         "count": deps.len(),
         "dependencies": deps,
     });
 
+    // (crate, version, relation) of everything to audit
+    let mut audited: Vec<(String, String, &'static str)> = vec![(name.clone(), version.clone(), "self")];
+    audited.extend(deps.iter()
+        .filter_map(|d| d.resolved.as_ref().map(|r| (d.crate_id.clone(), r.version.clone(), "direct"))));
+
+    let depth = params.depth.unwrap_or(1).clamp(1, MAX_DEPTH);
+    // The graph needs the direct dependencies' versions even when depth is 1. Recursion
+    // starts from what a build pulls in, whatever the filters above show.
+    let walk = if depth > 1 || graph.is_some() {
+        let lines = state.fetch_index(name).await
+            .map_err(ErrorData::from)?;
+        let line = lines.iter().find(|l| l.vers == version).ok_or_else(|| ErrorData::from(DocsError::VersionNotFound {
+            name: name.clone(),
+            version: version.clone(),
+        }))?;
+        Some(walk_transitive(state, line, depth, &[DepKind::Normal]).await)
+    } else {
+        None
+    };
+    if let (Some(format), Some(walk)) = (graph, &walk) {
+        output["graph"] = json!(render_graph(format, name, &version, walk));
+    }
+    // Whatever the walk missed, also at depth 1 where only the graph shows it
+    if let Some(walk) = &walk {
+        if !walk.unresolved.is_empty() {
+            output["unresolved"] = json!(walk.unresolved);
+        }
+        if walk.truncated {
+            let hint = if depth > 1 { "; lower depth to see a complete level" } else { "" };
+            output["truncated"] = json!(format!("stopped after {MAX_TRANSITIVE} crates{hint}"));
+        }
+    }
+    if let Some(walk) = walk.filter(|_| depth > 1) {
+        audited.extend(walk.resolved.iter().map(|d| (d.name.clone(), d.version.clone(), "transitive")));
+        let transitive: Vec<&TransitiveDep> = walk.resolved.iter()
+            .filter(|d| search_lower.as_ref().is_none_or(|s| d.name.to_lowercase().contains(s.as_str())))
+            .collect();
+        let unique: HashSet<&str> = walk.resolved.iter().map(|d| d.name.as_str()).collect();
+        output["depth"] = json!(depth);
+        output["transitive_count"] = json!(walk.resolved.len());
+        output["unique_crates"] = json!(unique.len());
+        output["transitive"] = json!(transitive);
+    }
+
+    if audit {
+        let mut seen = HashSet::new();
+        audited.retain(|(name, version, _)| seen.insert((name.clone(), version.clone())));
+        output["audited_count"] = json!(audited.len());
+        // The dependency list stands without advisories, so a lookup failure is reported, not raised
+        match audit_versions(state, audited).await {
+            Ok(vulnerable) => output["vulnerabilities"] = json!(vulnerable),
+            Err(e) => output["vulnerability_lookup_error"] = json!(e.to_string()),
+        }
+    }
+
     let json = serde_json::to_string_pretty(&output)
         .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
     Ok(CallToolResult::success(vec![Content::text(json)]))
 }
+
+/// The crate versions among `audited` that OSV advisories cover, in the same order.
+async fn audit_versions(
+    state: &AppState,
+    audited: Vec<(String, String, &'static str)>,
+) -> crate::error::Result<Vec<VulnerableCrate>> {
+    let packages: Vec<(String, String)> = audited.iter().map(|(n, v, _)| (n.clone(), v.clone())).collect();
+    let advisories = crate::osv::vulnerabilities(&state.client, &state.cache, &packages).await?;
+    Ok(audited.into_iter().zip(advisories)
+        .filter(|(_, advisories)| !advisories.is_empty())
+        .map(|((name, version, relation), advisories)| VulnerableCrate {
+            advisories: advisories.into_iter()
+                .map(|advisory| Vulnerability { fixed_in: advisory.fixed_in(&name), advisory })
+                .collect(),
+            name,
+            version,
+            relation,
+        })
+        .collect())
+}
+
+/// The version a fresh lockfile would pick for `name` at `req`.
+///
+/// Falls back to the highest yanked match when nothing else matches, so an exact pin to
+/// a yanked release is reported as such instead of as unresolvable.
+async fn resolve_req(state: &AppState, name: &str, req: &str) -> Result<ResolvedReq, String> {
+    let lines = state.fetch_index(name).await.map_err(|e| e.to_string())?;
+    let line = find_matching(&lines, req)
+        .or_else(|| {
+            let req = semver::VersionReq::parse(req).ok()?;
+            lines.iter()
+                .filter_map(|l| semver::Version::parse(&l.vers).ok().map(|v| (v, l)))
+                .filter(|(v, _)| req.matches(v))
+                .max_by(|(a, _), (b, _)| a.cmp(b))
+                .map(|(_, l)| l)
+        })
+        .ok_or_else(|| format!("no published version matches {req}"))?;
+    Ok(ResolvedReq {
+        version: line.vers.clone(),
+        rust_version: line.rust_version.clone(),
+        yanked: line.yanked,
+    })
+}
+
+pub(crate) struct Walk {
+    pub(crate) resolved: Vec<TransitiveDep>,
+    pub(crate) unresolved: Vec<UnresolvedDep>,
+    pub(crate) truncated: bool,
+    /// Every `(dependent, dependency)` pair found, as indices into `resolved`; `None` is
+    /// the root. Unlike `path`, this keeps each crate's other dependents too.
+    pub(crate) edges: Vec<(Option<usize>, usize)>,
+}
+
+/// A requirement waiting to be resolved in [`walk_transitive`].
+struct Pending {
+    name: String,
+    req: String,
+    /// Features the dependent turns on, and whether it keeps the default ones
+    features: Vec<String>,
+    default_features: bool,
+    level: u32,
+    /// Crates from the root down to the dependent
+    parents: Vec<String>,
+    /// The dependent's index in `Walk::resolved`; `None` for the root
+    parent: Option<usize>,
+}
+
+/// The dependencies of the `kinds` that `line` compiles with `features` (and its default
+/// features, unless `default_features` is false): the required ones plus the optional
+/// ones those features turn on.
+fn followed_deps<'l>(
+    line: &'l IndexLine,
+    kinds: &[DepKind],
+    features: &[String],
+    default_features: bool,
+) -> Vec<&'l IndexDep> {
+    let enabled = line.enabled_optional_deps(features, default_features);
+    line.deps.iter()
+        .filter(|d| kinds.contains(d.kind.as_ref().unwrap_or(&DepKind::Normal)))
+        .filter(|d| !d.optional || enabled.contains(&d.name))
+        .collect()
+}
+
+/// Breadth-first walk from `line`, the root release, down to `depth` levels, following the
+/// dependencies of the given `kinds` that a default build compiles.
+///
+/// Each requirement resolves to its highest matching version, the one a fresh lockfile
+/// would pick. A crate version reached along several paths is listed once, with the
+/// shortest path that pulled it in; semver-incompatible versions of a crate are kept apart.
+/// Each level's index lookups run a few at a time, and no crate is looked up twice.
+pub(crate) async fn walk_transitive(
+    state: &AppState,
+    line: &IndexLine,
+    depth: u32,
+    kinds: &[DepKind],
+) -> Walk {
+    let root = line.name.clone();
+    let mut walk = Walk { resolved: vec![], unresolved: vec![], truncated: false, edges: vec![] };
+    let mut seen: HashMap<(String, String), usize> = HashMap::new();
+    let mut indexes: HashMap<String, Result<Vec<IndexLine>, String>> = HashMap::new();
+    let mut level_queue: Vec<Pending> = followed_deps(line, kinds, &[], true).into_iter()
+        .map(|dep| Pending::new(dep, 1, vec![root.clone()], None))
+        .collect();
+
+    while !level_queue.is_empty() && !walk.truncated {
+        // Fetch the crates of this level not fetched before, at most as many as can still
+        // be listed; anything past that is fetched when reached
+        let mut wanted = HashSet::new();
+        let fetches: Vec<_> = level_queue.iter()
+            .map(|p| p.name.clone())
+            .filter(|name| !indexes.contains_key(name) && wanted.insert(name.clone()))
+            .take(MAX_TRANSITIVE.saturating_sub(walk.resolved.len()))
+            .map(|name| async move {
+                let lines = state.fetch_index(&name).await.map_err(|e| e.to_string());
+                (name, lines)
+            })
+            .collect();
+        indexes.extend(stream::iter(fetches).buffered(MAX_CONCURRENT_FETCHES).collect::<Vec<_>>().await);
+
+        let mut next_level = vec![];
+        for Pending { name, req, features, default_features, level, parents, parent } in std::mem::take(&mut level_queue) {
+            let required_by = parents.last().cloned().unwrap_or_default();
+            if !indexes.contains_key(&name) {
+                let lines = state.fetch_index(&name).await.map_err(|e| e.to_string());
+                indexes.insert(name.clone(), lines);
+            }
+            let lines = match &indexes[&name] {
+                Ok(lines) => lines,
+                Err(reason) => {
+                    walk.unresolved.push(UnresolvedDep { name, req, required_by, reason: reason.clone() });
+                    continue;
+                }
+            };
+            let Some(line) = find_matching(lines, &req) else {
+                walk.unresolved.push(UnresolvedDep {
+                    name, req, required_by,
+                    reason: "no published version matches the requirement".to_string(),
+                });
+                continue;
+            };
+            if let Some(&index) = seen.get(&(name.clone(), line.vers.clone())) {
+                walk.edges.push((parent, index));
+                continue;
+            }
+            if walk.resolved.len() >= MAX_TRANSITIVE {
+                walk.truncated = true;
+                break;
+            }
+            let index = walk.resolved.len();
+            seen.insert((name.clone(), line.vers.clone()), index);
+            walk.edges.push((parent, index));
+
+            let mut path = parents;
+            path.push(name.clone());
+            if level < depth {
+                next_level.extend(followed_deps(line, kinds, &features, default_features).into_iter()
+                    .map(|dep| Pending::new(dep, level + 1, path.clone(), Some(index))));
+            }
+            walk.resolved.push(TransitiveDep { name, version: line.vers.clone(), req, depth: level, path });
+        }
+        level_queue = next_level;
+    }
+    walk
+}
+
+impl Pending {
+    fn new(dep: &IndexDep, level: u32, parents: Vec<String>, parent: Option<usize>) -> Self {
+        Self {
+            name: dep.package.clone().unwrap_or_else(|| dep.name.clone()),
+            req: dep.req.clone(),
+            features: dep.features.clone(),
+            default_features: dep.default_features,
+            level,
+            parents,
+            parent,
+        }
+    }
+}
+
+/// The dependency tree found by `walk` as Graphviz DOT or Mermaid flowchart text, one node
+/// per crate version and one edge per dependent/dependency pair, from `root` left to right.
+fn render_graph(format: GraphFormat, root: &str, version: &str, walk: &Walk) -> String {
+    // Node 0 is the root; crate `i` of the walk is node `i + 1`
+    let labels: Vec<String> = std::iter::once(format!("{root} {version}"))
+        .chain(walk.resolved.iter().map(|d| format!("{} {}", d.name, d.version)))
+        .collect();
+    let mut seen = HashSet::new();
+    let edges: Vec<(usize, usize)> = walk.edges.iter()
+        .map(|&(from, to)| (from.map_or(0, |i| i + 1), to + 1))
+        .filter(|edge| seen.insert(*edge))
+        .collect();
+
+    let mut text = match format {
+        GraphFormat::Dot => "digraph dependencies {\n    rankdir=LR;\n    node [shape=box];\n".to_string(),
+        GraphFormat::Mermaid => "graph LR\n".to_string(),
+    };
+    for (i, label) in labels.iter().enumerate() {
+        let label = label.replace('"', "'");
+        text.push_str(&match format {
+            GraphFormat::Dot => format!("    n{i} [label=\"{label}\"];\n"),
+            GraphFormat::Mermaid => format!("    n{i}[\"{label}\"]\n"),
+        });
+    }
+    for (from, to) in edges {
+        text.push_str(&match format {
+            GraphFormat::Dot => format!("    n{from} -> n{to};\n"),
+            GraphFormat::Mermaid => format!("    n{from} --> n{to}\n"),
+        });
+    }
+    if walk.truncated {
+        let label = format!("more crates not shown (stopped after {MAX_TRANSITIVE})");
+        text.push_str(&match format {
+            GraphFormat::Dot => format!("    truncated [label=\"{label}\", style=dashed];\n"),
+            GraphFormat::Mermaid => format!("    truncated[\"{label}\"]\n"),
+        });
+    }
+    if format == GraphFormat::Dot {
+        text.push_str("}\n");
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dep(name: &str, version: &str) -> TransitiveDep {
+        TransitiveDep { name: name.to_string(), version: version.to_string(), req: "*".to_string(), depth: 1, path: vec![] }
+    }
+
+    #[test]
+    fn render_graph_keeps_every_dependent_of_a_shared_crate() {
+        // app -> a, app -> b, a -> c, b -> c (c reached twice, and a -> c listed twice)
+        let walk = Walk {
+            resolved: vec![dep("a", "1.0.0"), dep("b", "2.0.0"), dep("c", "0.1.0")],
+            unresolved: vec![],
+            truncated: false,
+            edges: vec![(None, 0), (None, 1), (Some(0), 2), (Some(1), 2), (Some(0), 2)],
+        };
+        let mermaid = render_graph(GraphFormat::Mermaid, "app", "0.3.0", &walk);
+        assert_eq!(mermaid.lines().filter(|l| l.contains("-->")).collect::<Vec<_>>(), [
+            "    n0 --> n1", "    n0 --> n2", "    n1 --> n3", "    n2 --> n3",
+        ]);
+        let dot = render_graph(GraphFormat::Dot, "app", "0.3.0", &walk);
+        assert!(dot.contains("n3 [label=\"c 0.1.0\"];") && dot.ends_with("}\n"), "got: {dot}");
+        assert!(!dot.contains("truncated"), "got: {dot}");
+
+        let cut_off = Walk { truncated: true, ..walk };
+        let dot = render_graph(GraphFormat::Dot, "app", "0.3.0", &cut_off);
+        assert!(dot.contains("truncated [label=\"more crates not shown") && dot.ends_with("}\n"), "got: {dot}");
+        assert!(render_graph(GraphFormat::Mermaid, "app", "0.3.0", &cut_off).contains("    truncated[\"more crates"));
+    }
+}
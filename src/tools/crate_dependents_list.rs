@@ -4,6 +4,7 @@ use rmcp::schemars::{self, JsonSchema};
 use serde_json::json;
 
 use super::AppState;
+use crate::cratesio::{ReverseDep, ReverseDepVersion};
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct CrateDependentsListParams {
@@ -15,38 +16,65 @@ pub struct CrateDependentsListParams {
     pub per_page: Option<u32>,
     /// Filter results by dependent crate name substring
     pub search: Option<String>,
+    /// Order the page's dependents by "downloads" (all-time downloads of the dependent crate,
+    /// highest first) or "recent" (newest dependent release first). Default: crates.io order.
+    pub sort: Option<String>,
+    /// Drop dependents whose crate has fewer all-time downloads than this
+    pub min_downloads: Option<u64>,
 }
 
 pub async fn execute(state: &AppState, params: CrateDependentsListParams) -> Result<CallToolResult, ErrorData> {
     let name = &params.name;
     let page = params.page.unwrap_or(1).max(1);
     let per_page = params.per_page.unwrap_or(20).min(100);
+    if let Some(sort) = params.sort.as_deref().filter(|s| !matches!(*s, "downloads" | "recent")) {
+        return Err(ErrorData::invalid_params(
+            format!("Unknown sort '{sort}'. Use \"downloads\" or \"recent\"."),
+            None,
+        ));
+    }
 
-    let client = crate::cratesio::CratesIoClient::new(&state.client, &state.cache);
-    let resp = client.get_reverse_deps(name, page, per_page).await
-        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    let client = crate::cratesio::CratesIoClient::new(&state.client, &state.cache)
+        .with_dump(state.dump_db.as_ref());
+    let resp = match client.get_reverse_deps(name, page, per_page).await {
+        Ok(resp) => resp,
+        Err(e) => return from_snapshot(state, &params, page, per_page, e),
+    };
 
-    // Build version ID → crate name lookup
-    let version_map: std::collections::HashMap<u64, &str> = resp.versions.iter()
-        .map(|v| (v.id, v.crate_name.as_str()))
+    // Build version ID → dependent version lookup
+    let version_map: std::collections::HashMap<u64, &ReverseDepVersion> = resp.versions.iter()
+        .map(|v| (v.id, v))
         .collect();
 
     let search_lower = params.search.as_deref().map(|s| s.to_lowercase());
 
-    let deps: Vec<serde_json::Value> = resp.dependencies.iter()
-        .filter(|d| {
-            let crate_name = version_map.get(&d.version_id).unwrap_or(&"?");
-            if let Some(ref search) = search_lower {
-                if !crate_name.to_lowercase().contains(search.as_str()) {
-                    return false;
-                }
-            }
-            true
+    let mut rows: Vec<(&ReverseDep, Option<&ReverseDepVersion>)> = resp.dependencies.iter()
+        .map(|d| (d, version_map.get(&d.version_id).copied()))
+        .filter(|(d, v)| {
+            let crate_name = v.map_or("?", |v| v.crate_name.as_str());
+            search_lower.as_ref().is_none_or(|s| crate_name.to_lowercase().contains(s.as_str()))
+                && params.min_downloads.is_none_or(|min| dependent_downloads(d, *v) >= min)
         })
-        .map(|d| {
-            let crate_name = version_map.get(&d.version_id).unwrap_or(&"?");
+        .collect();
+    // Sorting and filtering see only the fetched page; crates.io already pages
+    // dependents most-downloaded first
+    match params.sort.as_deref() {
+        Some("downloads") => rows.sort_by_key(|(d, v)| std::cmp::Reverse(dependent_downloads(d, *v))),
+        // RFC 3339 timestamps order chronologically as strings
+        Some("recent") => rows.sort_by(|(_, a), (_, b)| {
+            let at = |v: &Option<&ReverseDepVersion>| v.and_then(|v| v.created_at.clone());
+            at(b).cmp(&at(a))
+        }),
+        _ => {}
+    }
+
+    let deps: Vec<serde_json::Value> = rows.iter()
+        .map(|(d, v)| {
             json!({
-                "dependent_crate": crate_name,
+                "dependent_crate": v.map_or("?", |v| v.crate_name.as_str()),
+                "dependent_version": v.map(|v| v.num.as_str()),
+                "published_at": v.and_then(|v| v.created_at.as_deref()),
+                "downloads": dependent_downloads(d, *v),
                 "req": d.req,
                 "optional": d.optional,
                 "default_features": d.default_features,
@@ -58,6 +86,7 @@ pub async fn execute(state: &AppState, params: CrateDependentsListParams) -> Res
 
     let output = json!({
         "name": name,
+        "source": "crates_io_api",
         "total": resp.meta.total,
         "page": page,
         "per_page": per_page,
@@ -69,3 +98,71 @@ pub async fn execute(state: &AppState, params: CrateDependentsListParams) -> Res
         .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
     Ok(CallToolResult::success(vec![Content::text(json)]))
 }
+
+/// All-time downloads of the dependent crate, falling back to those of the dependent version.
+fn dependent_downloads(dep: &ReverseDep, version: Option<&ReverseDepVersion>) -> u64 {
+    dep.downloads.or(version.map(|v| v.downloads)).unwrap_or(0)
+}
+
+/// Serve dependents from the DB dump snapshot when the crates.io API call failed.
+///
+/// The snapshot only covers popular crates and lists dependents' latest versions, so it
+/// can lag crates.io by up to a week. Without one, the original API error is returned.
+fn from_snapshot(
+    state: &AppState,
+    params: &CrateDependentsListParams,
+    page: u32,
+    per_page: u32,
+    api_error: crate::error::DocsError,
+) -> Result<CallToolResult, ErrorData> {
+    let Some(snapshot) = state.dependents_snapshot.dependents(&params.name) else {
+        return Err(ErrorData::from(api_error));
+    };
+    tracing::info!("crates.io dependents lookup for {} failed ({api_error}); using the DB dump snapshot", params.name);
+
+    let search_lower = params.search.as_deref().map(|s| s.to_lowercase());
+    let matching: Vec<_> = snapshot.dependents.iter()
+        .filter(|d| search_lower.as_ref().is_none_or(|s| d.crate_name.to_lowercase().contains(s.as_str())))
+        .collect();
+    let total = matching.len();
+
+    let deps: Vec<serde_json::Value> = matching.into_iter()
+        .skip(((page - 1) * per_page) as usize)
+        .take(per_page as usize)
+        .map(|d| json!({
+            "dependent_crate": d.crate_name,
+            "dependent_version": d.version,
+            "req": d.req,
+            "optional": d.optional,
+            "default_features": d.default_features,
+            "features": d.features,
+            "kind": d.kind,
+        }))
+        .collect();
+
+    let built_at = chrono::DateTime::from_timestamp(snapshot.built_at as i64, 0)
+        .map(|t| t.to_rfc3339())
+        .unwrap_or_default();
+
+    let mut note = format!("crates.io reverse-dependencies request failed ({api_error}); \
+                            served from the local DB dump snapshot, which may be slightly stale.");
+    if params.sort.is_some() || params.min_downloads.is_some() {
+        note.push_str(" The snapshot has no download counts, so sort and min_downloads were not applied.");
+    }
+
+    let output = json!({
+        "name": params.name,
+        "source": "db_dump_snapshot",
+        "snapshot_built_at": built_at,
+        "note": note,
+        "total": total,
+        "page": page,
+        "per_page": per_page,
+        "count": deps.len(),
+        "dependents": deps,
+    });
+
+    let json = serde_json::to_string_pretty(&output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
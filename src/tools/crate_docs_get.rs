@@ -1,11 +1,15 @@
+use std::collections::HashSet;
+
 use rmcp::{ErrorData, model::{CallToolResult, Content}};
 use serde::Deserialize;
 use rmcp::schemars::{self, JsonSchema};
 use serde_json::json;
 
 use super::AppState;
-use crate::docsrs::{fetch_rustdoc_json, build_module_tree, ModuleNode, ItemSummary};
+use crate::docsrs::{build_module_subtree, find_item_id, DocDetail, resolve_links, rewrite_doc_links, ModuleNode, ItemSummary, MAX_MODULE_DEPTH};
+use crate::docsrs::{select_doc_sections, split_doc_sections};
 use crate::sparse_index::find_latest_stable;
+use crate::error::ErrorCode;
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct CrateDocsGetParams {
@@ -15,16 +19,29 @@ pub struct CrateDocsGetParams {
     pub version: Option<String>,
     /// Include item-level summaries per module (default: false)
     pub include_items: Option<bool>,
+    /// Include non-public and #[doc(hidden)] items, e.g. to debug a crate's internals (default: false)
+    pub include_hidden: Option<bool>,
+    /// Only return this module's subtree and docs (e.g. "tokio::sync") instead of the whole crate
+    pub module: Option<String>,
+    /// Levels of modules to return below the crate root or `module` (default: 6)
+    pub max_depth: Option<usize>,
+    /// Return only these markdown sections of the docs, matched by heading (e.g. ["Feature flags"]).
+    /// Pass ["toc"] to get the table of contents instead of any docs text.
+    pub sections: Option<Vec<String>>,
+    /// Target triple whose docs.rs build to read (e.g. "x86_64-pc-windows-msvc",
+    /// "wasm32-unknown-unknown"), for platform-specific APIs. Default: the crate's default target.
+    pub target: Option<String>,
 }
 
 pub async fn execute(state: &AppState, params: CrateDocsGetParams) -> Result<CallToolResult, ErrorData> {
     let name = &params.name;
     let version = state.resolve_version(name, params.version.as_deref()).await
-        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+        .map_err(ErrorData::from)?;
 
     // Parallel: fetch docs.rs JSON + sparse index features
+    let target = super::validate_target(params.target.as_deref())?;
     let (docs_result, index_result) = tokio::join!(
-        fetch_rustdoc_json(name, &version, &state.client, &state.cache),
+        state.rustdoc_with_detail(name, &version, target, DocDetail::Outline),
         state.fetch_index(name)
     );
 
@@ -35,15 +52,26 @@ pub async fn execute(state: &AppState, params: CrateDocsGetParams) -> Result<Cal
     let doc = match docs_result {
         Ok(d) => d,
         Err(crate::error::DocsError::DocsNotFound { .. }) => {
+            // The README is no substitute for another platform's API
+            if let Some(target) = target {
+                return Err(super::target_not_built_error(name, &version, target));
+            }
             // Fall back to README; features are still available from the sparse index.
+            tracing::info!("no docs.rs build for {name} {version}; falling back to the README");
             let client = crate::cratesio::CratesIoClient::new(&state.client, &state.cache);
-            let readme = client.get_readme(name, &version).await
+            let (readme, nearest) = tokio::join!(
+                client.get_readme_or_repository(name, &version),
+                state.nearest_built_version(name, &version)
+            );
+            let readme = readme
+                .map(|readme| readme.to_markdown(|href| (!href.starts_with('#')).then(|| href.to_string())))
                 .unwrap_or_else(|_| "No documentation available".to_string());
             let output = json!({
                 "name": name,
                 "version": version,
                 "root_docs": readme,
                 "note": "docs.rs build not available; showing README instead",
+                "nearest_built_version": nearest.ok().flatten(),
                 "module_tree": [],
                 "features": features,
             });
@@ -51,28 +79,74 @@ pub async fn execute(state: &AppState, params: CrateDocsGetParams) -> Result<Cal
                 .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
             return Ok(CallToolResult::success(vec![Content::text(json)]));
         }
-        Err(e) => return Err(ErrorData::internal_error(e.to_string(), None)),
+        Err(e) => return Err(ErrorData::from(e)),
     };
 
-    // Get root docs
-    let root_item = doc.index.get(&doc.root_id());
-    let root_docs = root_item
-        .and_then(|i| i.docs.as_deref())
-        .unwrap_or("")
-        .to_string();
+    let include_hidden = params.include_hidden.unwrap_or(false);
+    let max_depth = params.max_depth.unwrap_or(MAX_MODULE_DEPTH);
+    let module = params.module.as_deref();
 
     // Build module tree
-    let module_tree = build_module_tree(&doc);
-    let tree_json = serialize_module_nodes(&module_tree, params.include_items.unwrap_or(false));
+    let module_tree = build_module_subtree(&doc, module, max_depth, include_hidden).ok_or_else(|| {
+        let module = module.unwrap_or_default();
+        ErrorData::invalid_params(
+            format!("Module '{module}' not found in {name} {version}. \
+                     Call crate_docs_get without `module` to see the module tree."),
+            ErrorCode::ItemNotFound.data(),
+        )
+    })?;
+    let declared_features: HashSet<&str> = features.keys().map(String::as_str).collect();
+    let tree_json = serialize_module_nodes(&module_tree, params.include_items.unwrap_or(false), &declared_features);
+
+    // Docs of the crate root, or of the requested module
+    let docs_item = match module {
+        Some(path) => find_item_id(&doc, path).and_then(|id| doc.index.get(&id)),
+        None => doc.index.get(&doc.root_id()),
+    };
+    let docs = docs_item
+        .and_then(|i| i.docs.as_deref().map(|d| rewrite_doc_links(d, &resolve_links(&doc, i))))
+        .unwrap_or_default();
 
-    let output = json!({
+    let mut output = json!({
         "name": name,
         "version": version,
         "format_version": doc.format_version,
-        "root_docs": root_docs,
         "features": features,
         "module_tree": tree_json,
     });
+    let docs_key = match module {
+        Some(path) => {
+            output["module"] = json!(path);
+            "module_docs"
+        }
+        None => "root_docs",
+    };
+    match params.sections.as_deref() {
+        None => output[docs_key] = json!(docs),
+        Some(sections) => {
+            let all = split_doc_sections(&docs);
+            if sections.len() == 1 && sections[0].eq_ignore_ascii_case("toc") {
+                output["docs_toc"] = json!(all.iter().filter(|s| s.level > 0).map(|s| json!({
+                    "level": s.level,
+                    "title": s.title,
+                    "lines": s.text.lines().count(),
+                })).collect::<Vec<_>>());
+            } else {
+                let (selected, missing) = select_doc_sections(&all, sections);
+                output["docs_sections"] = json!(selected.iter().map(|s| json!({
+                    "title": s.title,
+                    "text": s.text,
+                })).collect::<Vec<_>>());
+                if !missing.is_empty() {
+                    output["missing_sections"] = json!(missing);
+                }
+            }
+        }
+    }
+    let warnings = doc.warnings();
+    if !warnings.is_empty() {
+        output["warnings"] = json!(warnings);
+    }
 
     let json = serde_json::to_string_pretty(&output)
         .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
@@ -88,20 +162,30 @@ fn serialize_item_summary(s: &ItemSummary) -> serde_json::Value {
     })
 }
 
-fn serialize_module_nodes(nodes: &[ModuleNode], include_items: bool) -> serde_json::Value {
+/// Serialize module nodes; `declared_features` drops cfg keys that aren't real features.
+pub(crate) fn serialize_module_nodes(nodes: &[ModuleNode], include_items: bool, declared_features: &HashSet<&str>) -> serde_json::Value {
     let arr: Vec<serde_json::Value> = nodes.iter().map(|n| {
         let mut obj = json!({
             "path": n.path,
             "doc_summary": n.doc_summary,
             "item_counts": n.item_counts,
         });
+        let features: Vec<&String> = n.feature_requirements.iter()
+            .filter(|f| declared_features.is_empty() || declared_features.contains(f.as_str()))
+            .collect();
+        if !features.is_empty() {
+            obj["feature_requirements"] = json!(features);
+        }
+        if let Some(cfg) = &n.cfg {
+            obj["cfg"] = json!(cfg);
+        }
         if include_items && !n.items.is_empty() {
             obj["items"] = serde_json::Value::Array(
                 n.items.iter().map(serialize_item_summary).collect()
             );
         }
         if !n.children.is_empty() {
-            obj["children"] = serialize_module_nodes(&n.children, include_items);
+            obj["children"] = serialize_module_nodes(&n.children, include_items, declared_features);
         }
         obj
     }).collect();
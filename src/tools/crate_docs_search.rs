@@ -0,0 +1,69 @@
+use rmcp::{ErrorData, model::{CallToolResult, Content}};
+use serde::Deserialize;
+use rmcp::schemars::{self, JsonSchema};
+use serde_json::json;
+
+use super::{AppState, docs_not_found_error};
+use crate::error::ErrorCode;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CrateDocsSearchParams {
+    /// Crate name
+    pub name: String,
+    /// Version string. Defaults to latest stable.
+    pub version: Option<String>,
+    /// Words to find anywhere in the doc comments (e.g. "cancel safe", "backpressure")
+    pub query: String,
+    /// Filter by kind: "struct", "enum", "trait", "fn", "method", "module", "type", "const", "macro"
+    pub kind: Option<String>,
+    /// Restrict to items under this module path (e.g. "tokio::sync")
+    pub module_prefix: Option<String>,
+    /// Max results (default: 10, max: 50)
+    pub limit: Option<usize>,
+    /// Target triple whose docs.rs build to read. Default: the crate's default target.
+    pub target: Option<String>,
+}
+
+pub async fn execute(state: &AppState, params: CrateDocsSearchParams) -> Result<CallToolResult, ErrorData> {
+    let name = &params.name;
+    if params.query.trim().is_empty() {
+        return Err(ErrorData::invalid_params("query must contain at least one word.", ErrorCode::InvalidParams.data()));
+    }
+    let version = state.resolve_version(name, params.version.as_deref()).await
+        .map_err(ErrorData::from)?;
+    let limit = params.limit.unwrap_or(10).clamp(1, 50);
+
+    let target = super::validate_target(params.target.as_deref())?;
+    let doc = match state.rustdoc_for_target(name, &version, target).await {
+        Ok(d) => d,
+        Err(crate::error::DocsError::DocsNotFound { .. }) => {
+            if let Some(target) = target {
+                return Err(super::target_not_built_error(name, &version, target));
+            }
+            return Err(docs_not_found_error(state, name, &version).await);
+        }
+        Err(e) => return Err(ErrorData::from(e)),
+    };
+
+    // Built on the first search of this document, then kept with it
+    let (total, hits) = doc.full_text().await.map_err(ErrorData::from)?.search(
+        &doc,
+        &params.query,
+        params.kind.as_deref(),
+        params.module_prefix.as_deref(),
+        limit,
+    );
+
+    let output = json!({
+        "name": name,
+        "version": version,
+        "query": params.query,
+        "total_matches": total,
+        "count": hits.len(),
+        "results": hits,
+    });
+
+    let json = serde_json::to_string_pretty(&output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
@@ -2,21 +2,34 @@ use rmcp::{ErrorData, model::{CallToolResult, Content}};
 use serde::Deserialize;
 use rmcp::schemars::{self, JsonSchema};
 use serde_json::json;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+use futures::stream::{self, StreamExt};
 
 use super::AppState;
 
+/// Download histories fetched at once when comparing crates.
+const MAX_CONCURRENT_LOOKUPS: usize = 8;
+
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct CrateDownloadsGetParams {
     /// Crate name
     pub name: String,
     /// ISO date (YYYY-MM-DD). Returns 90 days ending on this date. Defaults to today.
     pub before_date: Option<String>,
+    /// Other crates to compare against this one (e.g. ["async-std"] for tokio). Returns each
+    /// crate's totals and share of the combined downloads, with daily counts aligned by date,
+    /// instead of the per-version breakdown.
+    pub compare_with: Option<Vec<String>>,
 }
 
 pub async fn execute(state: &AppState, params: CrateDownloadsGetParams) -> Result<CallToolResult, ErrorData> {
     let name = &params.name;
-    let client = crate::cratesio::CratesIoClient::new(&state.client, &state.cache);
+    let client = crate::cratesio::CratesIoClient::new(&state.client, &state.cache)
+        .with_dump(state.dump_db.as_ref());
+    if let Some(others) = params.compare_with.as_deref().filter(|o| !o.is_empty()) {
+        return compare(&client, name, others, params.before_date.as_deref()).await;
+    }
 
     // Fetch download stats and version list in parallel
     let (downloads_result, versions_result) = tokio::join!(
@@ -24,8 +37,8 @@ pub async fn execute(state: &AppState, params: CrateDownloadsGetParams) -> Resul
         client.get_versions(name)
     );
 
-    let downloads = downloads_result.map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
-    let versions = versions_result.map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    let downloads = downloads_result.map_err(ErrorData::from)?;
+    let versions = versions_result.map_err(ErrorData::from)?;
 
     // Build version ID → semver string map
     let version_map: HashMap<u64, &str> = versions.versions.iter()
@@ -77,6 +90,59 @@ pub async fn execute(state: &AppState, params: CrateDownloadsGetParams) -> Resul
     Ok(CallToolResult::success(vec![Content::text(json)]))
 }
 
+/// Download totals of `name` and `others` side by side, with daily counts aligned by date.
+async fn compare(
+    client: &crate::cratesio::CratesIoClient<'_>,
+    name: &str,
+    others: &[String],
+    before_date: Option<&str>,
+) -> Result<CallToolResult, ErrorData> {
+    let today_utc = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let effective_before_date = before_date.unwrap_or(&today_utc).to_string();
+    let cutoff_30 = subtract_days(&effective_before_date, 30);
+
+    let names: Vec<&str> = std::iter::once(name).chain(others.iter().map(String::as_str)).collect();
+    let mut daily: BTreeMap<String, BTreeMap<&str, u64>> = BTreeMap::new();
+    let mut totals: Vec<(&str, u64, u64)> = vec![];
+    let lookups: Vec<_> = names.iter().map(|crate_name| client.get_downloads(crate_name, before_date)).collect();
+    let fetched: Vec<_> = stream::iter(lookups).buffered(MAX_CONCURRENT_LOOKUPS).collect().await;
+    for (&crate_name, downloads) in names.iter().zip(fetched) {
+        let downloads = downloads
+            .map_err(|e| ErrorData::internal_error(format!("{crate_name}: {e}"), e.code().data()))?;
+        let (mut total_30d, mut total_90d) = (0, 0);
+        for vd in &downloads.version_downloads {
+            total_90d += vd.downloads;
+            if vd.date >= cutoff_30 {
+                total_30d += vd.downloads;
+            }
+            *daily.entry(vd.date.clone()).or_default().entry(crate_name).or_insert(0) += vd.downloads;
+        }
+        totals.push((crate_name, total_30d, total_90d));
+    }
+
+    let combined_90d: u64 = totals.iter().map(|(_, _, t)| t).sum();
+    let crates: Vec<serde_json::Value> = totals.iter().map(|&(crate_name, total_30d, total_90d)| json!({
+        "name": crate_name,
+        "total_30d": total_30d,
+        "total_90d": total_90d,
+        "share_90d": if combined_90d == 0 { 0.0 } else { total_90d as f64 / combined_90d as f64 },
+    })).collect();
+    // Every crate appears on every date, with 0 where it had no downloads recorded
+    let daily: Vec<serde_json::Value> = daily.into_iter().map(|(date, counts)| json!({
+        "date": date,
+        "downloads": names.iter().map(|n| (*n, counts.get(n).copied().unwrap_or(0))).collect::<BTreeMap<_, _>>(),
+    })).collect();
+
+    let output = json!({
+        "before_date": effective_before_date,
+        "crates": crates,
+        "daily": daily,
+    });
+    let json = serde_json::to_string_pretty(&output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
+
 /// Subtract N days from an ISO date string (YYYY-MM-DD). Returns the original on error.
 fn subtract_days(date: &str, days: i64) -> String {
     use chrono::NaiveDate;
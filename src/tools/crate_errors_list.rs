@@ -0,0 +1,252 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use rmcp::{ErrorData, model::{CallToolResult, Content}};
+use serde::{Deserialize, Serialize};
+use rmcp::schemars::{self, JsonSchema};
+use serde_json::Value;
+
+use super::{AppState, docs_not_found_error};
+use crate::docsrs::{id_to_string, Item, RustdocJson};
+use crate::docsrs::parser::type_to_string;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CrateErrorsListParams {
+    /// Crate name
+    pub name: String,
+    /// Version string. Defaults to latest stable.
+    pub version: Option<String>,
+}
+
+/// One error type and how values get into and out of it.
+#[derive(Debug, Serialize)]
+struct ErrorType {
+    path: String,
+    kind: String,
+    /// Implements `std::error::Error`; `false` for types only named like an error
+    implements_error: bool,
+    doc_summary: String,
+    /// Enum variants, e.g. `Io(std::io::Error)`, with their doc summaries
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    variants: Vec<Variant>,
+    /// `T` in every `impl From<T> for ThisError`: what `?` converts automatically
+    converts_from: Vec<String>,
+    /// Types with `impl From<ThisError>` in this crate
+    converts_into: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Variant {
+    declaration: String,
+    doc_summary: String,
+}
+
+pub async fn execute(state: &AppState, params: CrateErrorsListParams) -> Result<CallToolResult, ErrorData> {
+    let name = &params.name;
+    let version = state.resolve_version(name, params.version.as_deref()).await
+        .map_err(ErrorData::from)?;
+
+    let doc = match state.rustdoc(name, &version).await {
+        Ok(d) => d,
+        Err(crate::error::DocsError::DocsNotFound { .. }) => {
+            return Err(docs_not_found_error(state, name, &version).await);
+        }
+        Err(e) => return Err(ErrorData::from(e)),
+    };
+
+    let errors = error_types(&doc);
+    let output = serde_json::json!({
+        "name": name,
+        "version": version,
+        "count": errors.len(),
+        "errors": errors,
+    });
+
+    let json = serde_json::to_string_pretty(&output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
+
+/// The crate's public types that implement `std::error::Error` or are named `*Error`, by path.
+fn error_types(doc: &RustdocJson) -> Vec<ErrorType> {
+    let mut implementing = BTreeSet::new();
+    // Error type ID → rendered `From` source types, and target types for conversions out
+    let mut from: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut into: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for item in doc.index.values() {
+        let Some(impl_inner) = item.inner_for("impl") else { continue };
+        // Synthetic auto-trait impls, and blanket impls such as `impl<T> From<T> for T`
+        if impl_inner.get("is_synthetic").and_then(Value::as_bool).unwrap_or(false)
+            || impl_inner.get("blanket_impl").is_some_and(|b| !b.is_null())
+        {
+            continue;
+        }
+        let Some(trait_val) = impl_inner.get("trait").filter(|t| !t.is_null()) else { continue };
+        let Some(for_val) = impl_inner.get("for") else { continue };
+        let self_id = resolved_id(for_val);
+
+        if is_std_error_trait(doc, trait_val) {
+            implementing.extend(self_id);
+        } else if trait_last_segment(trait_val) == Some("From") {
+            let Some(source) = first_type_arg(trait_val) else { continue };
+            if let Some(id) = &self_id {
+                from.entry(id.clone()).or_default().push(type_to_string(source));
+            }
+            if let Some(id) = resolved_id(source) {
+                into.entry(id).or_default().push(type_to_string(for_val));
+            }
+        }
+    }
+
+    let mut errors: Vec<ErrorType> = doc.paths.iter()
+        .filter(|(_, p)| p.crate_id == 0 && matches!(p.kind_name(), "struct" | "enum" | "union"))
+        .filter(|(id, p)| implementing.contains(*id) || p.path.last().is_some_and(|n| n.ends_with("Error")))
+        .filter_map(|(id, p)| {
+            let item = doc.index.get(id).filter(|i| i.is_visible())?;
+            let mut converts_from = from.remove(id).unwrap_or_default();
+            let mut converts_into = into.remove(id).unwrap_or_default();
+            converts_from.sort();
+            converts_from.dedup();
+            converts_into.sort();
+            converts_into.dedup();
+            Some(ErrorType {
+                path: p.full_path(),
+                kind: p.kind_name().to_string(),
+                implements_error: implementing.contains(id),
+                doc_summary: item.doc_summary(),
+                variants: variants(doc, item),
+                converts_from,
+                converts_into,
+            })
+        })
+        .collect();
+    errors.sort_by(|a, b| a.path.cmp(&b.path));
+    errors
+}
+
+/// Whether an impl's trait is `std::error::Error` (or its `core` original), not merely a
+/// trait named `Error` such as `serde::de::Error`.
+fn is_std_error_trait(doc: &RustdocJson, trait_val: &Value) -> bool {
+    let known = resolved_id(trait_val).and_then(|id| doc.paths.get(&id));
+    match known {
+        Some(entry) => matches!(entry.path.iter().map(String::as_str).collect::<Vec<_>>()[..], ["std" | "core", "error", "Error"]),
+        None => trait_val.get("path").and_then(Value::as_str).is_some_and(|p| p.ends_with("error::Error")),
+    }
+}
+
+fn trait_last_segment(trait_val: &Value) -> Option<&str> {
+    trait_val.get("path")?.as_str()?.rsplit("::").next()
+}
+
+/// ID of the item a `resolved_path` type (or trait reference) points at.
+fn resolved_id(ty: &Value) -> Option<String> {
+    id_to_string(ty.get("resolved_path").unwrap_or(ty).get("id")?)
+}
+
+/// `T` in `From<T>`.
+fn first_type_arg(trait_val: &Value) -> Option<&Value> {
+    trait_val.get("args")?.get("angle_bracketed")?.get("args")?.as_array()?
+        .iter()
+        .find_map(|a| a.get("type"))
+}
+
+/// An enum's variants rendered as declarations; empty for other kinds.
+fn variants(doc: &RustdocJson, item: &Item) -> Vec<Variant> {
+    let Some(ids) = item.inner_for("enum").and_then(|e| e.get("variants")).and_then(Value::as_array) else {
+        return vec![];
+    };
+    ids.iter()
+        .filter_map(|id| doc.index.get(&id_to_string(id)?))
+        .map(|variant| Variant {
+            declaration: variant_declaration(doc, variant),
+            doc_summary: variant.doc_summary(),
+        })
+        .collect()
+}
+
+/// `Io(std::io::Error)`, `Parse { line: usize }` or `Closed`.
+fn variant_declaration(doc: &RustdocJson, variant: &Item) -> String {
+    let name = variant.name.as_deref().unwrap_or("_");
+    let kind = variant.inner_for("variant").and_then(|v| v.get("kind"));
+    let field = |id: &Value| id_to_string(id).and_then(|id| doc.index.get(&id));
+    let field_type = |f: &Item| f.inner_for("struct_field").map(type_to_string).unwrap_or_else(|| "_".to_string());
+
+    if let Some(fields) = kind.and_then(|k| k.get("tuple")).and_then(Value::as_array) {
+        // Private fields appear as null
+        let types: Vec<String> = fields.iter()
+            .map(|id| field(id).map(field_type).unwrap_or_else(|| "_".to_string()))
+            .collect();
+        return format!("{name}({})", types.join(", "));
+    }
+    if let Some(fields) = kind.and_then(|k| k.get("struct")).and_then(|s| s.get("fields")).and_then(Value::as_array) {
+        let fields: Vec<String> = fields.iter()
+            .filter_map(field)
+            .map(|f| format!("{}: {}", f.name.as_deref().unwrap_or("_"), field_type(f)))
+            .collect();
+        return format!("{name} {{ {} }}", fields.join(", "));
+    }
+    name.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn make_doc() -> RustdocJson {
+        let no_generics = json!({"params": [], "where_predicates": []});
+        let path_ty = |path: &str, id: u32| json!({"resolved_path": {"path": path, "id": id, "args": null}});
+        let trait_ref = |path: &str, id: u32, arg: Option<Value>| json!({
+            "path": path, "id": id,
+            "args": arg.map(|a| json!({"angle_bracketed": {"args": [{"type": a}], "constraints": []}})),
+        });
+        let trait_impl = |trait_val: Value, for_val: Value| json!({"id": 0, "inner": {"impl": {
+            "is_unsafe": false, "generics": no_generics.clone(), "provided_trait_methods": [],
+            "trait": trait_val, "for": for_val, "items": [], "is_negative": false, "is_synthetic": false, "blanket_impl": null,
+        }}});
+        serde_json::from_value(json!({
+            "format_version": 57,
+            "root": 0,
+            "index": {
+                "1": {"id": 1, "name": "Error", "docs": "Anything that can go wrong.", "visibility": "public",
+                      "inner": {"enum": {"generics": no_generics.clone(), "variants": [2, 3, 5], "has_stripped_variants": false, "impls": []}}},
+                "2": {"id": 2, "name": "Io", "inner": {"variant": {"kind": {"tuple": [4]}, "discriminant": null}}},
+                "3": {"id": 3, "name": "Closed", "docs": "The peer hung up.", "inner": {"variant": {"kind": "plain", "discriminant": null}}},
+                "4": {"id": 4, "name": "0", "inner": {"struct_field": path_ty("std::io::Error", 90)}},
+                "5": {"id": 5, "name": "Parse", "inner": {"variant": {"kind": {"struct": {"fields": [6], "has_stripped_fields": false}}, "discriminant": null}}},
+                "6": {"id": 6, "name": "line", "inner": {"struct_field": {"primitive": "usize"}}},
+                "10": trait_impl(trait_ref("Error", 80, None), path_ty("Error", 1)),
+                "11": trait_impl(trait_ref("From", 81, Some(path_ty("std::io::Error", 90))), path_ty("Error", 1)),
+                "12": trait_impl(trait_ref("From", 81, Some(path_ty("Error", 1))), path_ty("std::io::Error", 90)),
+                "20": {"id": 20, "name": "DecodeError", "visibility": "public",
+                       "inner": {"struct": {"generics": no_generics.clone(), "kind": {"unit": null}, "impls": []}}},
+                "30": {"id": 30, "name": "Report", "visibility": "public",
+                       "inner": {"struct": {"generics": no_generics.clone(), "kind": {"unit": null}, "impls": []}}},
+                "31": trait_impl(trait_ref("de::Error", 82, None), path_ty("Report", 30)),
+            },
+            "paths": {
+                "1": {"crate_id": 0, "path": ["demo", "Error"], "kind": "enum"},
+                "20": {"crate_id": 0, "path": ["demo", "DecodeError"], "kind": "struct"},
+                "30": {"crate_id": 0, "path": ["demo", "Report"], "kind": "struct"},
+                "80": {"crate_id": 1, "path": ["core", "error", "Error"], "kind": "trait"},
+                "81": {"crate_id": 1, "path": ["core", "convert", "From"], "kind": "trait"},
+                "82": {"crate_id": 2, "path": ["serde", "de", "Error"], "kind": "trait"},
+                "90": {"crate_id": 3, "path": ["std", "io", "error", "Error"], "kind": "struct"},
+            }
+        })).unwrap()
+    }
+
+    #[test]
+    fn lists_error_types_with_variants_and_conversions() {
+        let errors = error_types(&make_doc());
+        let paths: Vec<(&str, bool)> = errors.iter().map(|e| (e.path.as_str(), e.implements_error)).collect();
+        assert_eq!(paths, [("demo::DecodeError", false), ("demo::Error", true)]);
+
+        let error = &errors[1];
+        let variants: Vec<&str> = error.variants.iter().map(|v| v.declaration.as_str()).collect();
+        assert_eq!(variants, ["Io(std::io::Error)", "Closed", "Parse { line: usize }"]);
+        assert_eq!(error.variants[1].doc_summary, "The peer hung up.");
+        assert_eq!(error.converts_from, ["std::io::Error"]);
+        assert_eq!(error.converts_into, ["std::io::Error"]);
+    }
+}
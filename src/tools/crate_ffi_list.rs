@@ -0,0 +1,56 @@
+use rmcp::{ErrorData, model::{CallToolResult, Content}};
+use serde::Deserialize;
+use rmcp::schemars::{self, JsonSchema};
+use serde_json::json;
+
+use super::{AppState, docs_not_found_error};
+use crate::docsrs::ffi_surface;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CrateFfiListParams {
+    /// Crate name
+    pub name: String,
+    /// Version string. Defaults to latest stable.
+    pub version: Option<String>,
+    /// Target triple whose docs.rs build to read (e.g. "x86_64-pc-windows-msvc"); FFI
+    /// surfaces often differ per platform. Default: the crate's default target.
+    pub target: Option<String>,
+    /// Include non-public and #[doc(hidden)] items (default: false)
+    pub include_hidden: Option<bool>,
+}
+
+pub async fn execute(state: &AppState, params: CrateFfiListParams) -> Result<CallToolResult, ErrorData> {
+    let name = &params.name;
+    let version = state.resolve_version(name, params.version.as_deref()).await
+        .map_err(ErrorData::from)?;
+
+    let target = super::validate_target(params.target.as_deref())?;
+    let doc = match state.rustdoc_for_target(name, &version, target).await {
+        Ok(d) => d,
+        Err(crate::error::DocsError::DocsNotFound { .. }) => {
+            if let Some(target) = target {
+                return Err(super::target_not_built_error(name, &version, target));
+            }
+            return Err(docs_not_found_error(state, name, &version).await);
+        }
+        Err(e) => return Err(ErrorData::from(e)),
+    };
+
+    let surface = ffi_surface(&doc, params.include_hidden.unwrap_or(false));
+    let output = json!({
+        "name": name,
+        "version": version,
+        "summary": {
+            "extern_functions": surface.functions.iter().filter(|f| f.abi.is_some()).count(),
+            "exported_symbols": surface.functions.iter().filter(|f| f.symbol.is_some()).count(),
+            "raw_pointer_functions": surface.functions.iter().filter(|f| f.raw_pointers).count(),
+            "repr_types": surface.types.len(),
+        },
+        "functions": surface.functions,
+        "types": surface.types,
+    });
+
+    let json = serde_json::to_string_pretty(&output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
@@ -0,0 +1,56 @@
+use rmcp::{ErrorData, model::{CallToolResult, Content}};
+use serde::Deserialize;
+use rmcp::schemars::{self, JsonSchema};
+use serde_json::json;
+
+use super::AppState;
+use crate::error::ErrorCode;
+
+/// Default and largest `max_bytes`.
+const DEFAULT_MAX_BYTES: usize = 64 * 1024;
+const MAX_BYTES_LIMIT: usize = 1024 * 1024;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CrateFileGetParams {
+    /// Crate name
+    pub name: String,
+    /// Version string. Defaults to latest stable.
+    pub version: Option<String>,
+    /// File path relative to the package root, as listed by crate_files_list (e.g. "build.rs")
+    pub path: String,
+    /// Bytes of content to return (default: 65536, max: 1048576). Longer files are truncated.
+    pub max_bytes: Option<usize>,
+}
+
+pub async fn execute(state: &AppState, params: CrateFileGetParams) -> Result<CallToolResult, ErrorData> {
+    let name = &params.name;
+    let version = state.resolve_version(name, params.version.as_deref()).await
+        .map_err(ErrorData::from)?;
+    let max_bytes = params.max_bytes.unwrap_or(DEFAULT_MAX_BYTES).min(MAX_BYTES_LIMIT);
+
+    let tarball = state.crate_tarball(name, &version).await
+        .map_err(ErrorData::from)?;
+    let file = tarball.read_file(&params.path, max_bytes).await
+        .map_err(ErrorData::from)?
+        .ok_or_else(|| ErrorData::invalid_params(
+            format!("File '{}' not found in {name} {version}. \
+                     Use crate_files_list(name=\"{name}\") to see the files the package ships.",
+                    params.path),
+            ErrorCode::FileNotFound.data(),
+        ))?;
+
+    let output = json!({
+        "name": name,
+        "version": version,
+        "path": params.path,
+        "size": file.size,
+        "binary": file.text.is_none(),
+        "truncated": file.truncated,
+        "content": file.text,
+        "checksum": tarball.checksum(),
+    });
+
+    let json = serde_json::to_string_pretty(&output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
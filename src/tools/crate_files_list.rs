@@ -0,0 +1,56 @@
+use rmcp::{ErrorData, model::{CallToolResult, Content}};
+use serde::Deserialize;
+use rmcp::schemars::{self, JsonSchema};
+use serde_json::json;
+
+use super::AppState;
+use crate::cratesio::tarball::glob_match;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CrateFilesListParams {
+    /// Crate name
+    pub name: String,
+    /// Version string. Defaults to latest stable.
+    pub version: Option<String>,
+    /// Glob filter (e.g. "*.proto", "migrations/**", "src/**/*.rs"). `*` stays within one
+    /// directory, `**` spans directories; a pattern without `/` matches file names at any depth.
+    pub pattern: Option<String>,
+    /// Max files to return (default: 200, max: 1000)
+    pub limit: Option<usize>,
+}
+
+pub async fn execute(state: &AppState, params: CrateFilesListParams) -> Result<CallToolResult, ErrorData> {
+    let name = &params.name;
+    let version = state.resolve_version(name, params.version.as_deref()).await
+        .map_err(ErrorData::from)?;
+    let limit = params.limit.unwrap_or(200).min(1000);
+
+    let tarball = state.crate_tarball(name, &version).await
+        .map_err(ErrorData::from)?;
+    let files = tarball.files().await
+        .map_err(ErrorData::from)?;
+
+    let total_files = files.len();
+    let total_size: u64 = files.iter().map(|f| f.size).sum();
+    let matching: Vec<_> = files.into_iter()
+        .filter(|f| params.pattern.as_deref().is_none_or(|p| glob_match(p, &f.path)))
+        .collect();
+    let matched = matching.len();
+    let shown: Vec<_> = matching.into_iter().take(limit).collect();
+
+    let output = json!({
+        "name": name,
+        "version": version,
+        "pattern": params.pattern,
+        "total_files": total_files,
+        "total_size": total_size,
+        "matched": matched,
+        "truncated": matched > shown.len(),
+        "files": shown,
+        "checksum": tarball.checksum(),
+    });
+
+    let json = serde_json::to_string_pretty(&output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
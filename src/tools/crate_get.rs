@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 use rmcp::schemars::{self, JsonSchema};
 
 use super::AppState;
+use crate::cratesio::{Category, Keyword};
+use crate::error::DocsError;
 
 #[derive(Serialize)]
 struct CrateGetOutput<'a> {
@@ -23,22 +25,87 @@ struct CrateGetOutput<'a> {
     max_stable_version: Option<&'a str>,
     #[serde(skip_serializing_if = "Option::is_none")]
     max_version: Option<&'a str>,
+    /// Version the features, MSRV and dependency count below describe
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    yanked: Option<bool>,
+    /// Normal (non-dev, non-build) dependencies of `version`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dependency_count: Option<usize>,
+    /// Latest non-yanked stable release according to the sparse index
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latest_stable: Option<&'a str>,
+    /// MSRV declared by `version`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rust_version: Option<&'a str>,
+    /// Publish date of the latest stable release
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latest_release_date: Option<&'a str>,
+    /// Whether docs.rs has a rustdoc JSON build of `version`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    docs_available: Option<bool>,
     features: std::collections::HashMap<String, Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    keywords: Option<Vec<&'a str>>,
+    keywords: Option<Vec<KeywordOutput<'a>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    categories: Option<Vec<CategoryOutput<'a>>>,
+}
+
+#[derive(Serialize)]
+struct KeywordOutput<'a> {
+    keyword: &'a str,
+    /// Number of crates tagged with this keyword
+    crates_cnt: u64,
+    browse_url: String,
+}
+
+impl<'a> From<&'a Keyword> for KeywordOutput<'a> {
+    fn from(k: &'a Keyword) -> Self {
+        Self {
+            keyword: &k.keyword,
+            crates_cnt: k.crates_cnt,
+            browse_url: format!("https://crates.io/keywords/{}", k.id),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct CategoryOutput<'a> {
+    category: &'a str,
+    /// Slug accepted by crate_list's `category` filter, e.g. "web-programming::http-client"
+    slug: &'a str,
+    crates_cnt: u64,
     #[serde(skip_serializing_if = "Option::is_none")]
-    categories: Option<Vec<&'a str>>,
+    description: Option<&'a str>,
+    browse_url: String,
+}
+
+impl<'a> From<&'a Category> for CategoryOutput<'a> {
+    fn from(c: &'a Category) -> Self {
+        Self {
+            category: &c.category,
+            slug: &c.id,
+            crates_cnt: c.crates_cnt,
+            description: c.description.as_deref(),
+            browse_url: format!("https://crates.io/categories/{}", c.id),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct CrateGetParams {
     /// Exact crate name (e.g. "serde")
     pub name: String,
+    /// Version whose features, MSRV, dependency count and yank status to report.
+    /// Defaults to latest stable.
+    pub version: Option<String>,
 }
 
 pub async fn execute(state: &AppState, params: CrateGetParams) -> Result<CallToolResult, ErrorData> {
     let name = &params.name;
-    let client = crate::cratesio::CratesIoClient::new(&state.client, &state.cache);
+    let client = crate::cratesio::CratesIoClient::new(&state.client, &state.cache)
+        .with_dump(state.dump_db.as_ref());
 
     // Parallel: crates.io API + sparse index
     let (api_result, index_result) = tokio::join!(
@@ -46,12 +113,30 @@ pub async fn execute(state: &AppState, params: CrateGetParams) -> Result<CallToo
         state.fetch_index(name)
     );
 
-    let api = api_result.map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
-    let index_lines = index_result.map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    let api = api_result.map_err(ErrorData::from)?;
+    let index_lines = index_result.map_err(ErrorData::from)?;
 
     // Find latest stable from sparse index
     let latest_stable = crate::sparse_index::find_latest_stable(&index_lines);
-    let features = latest_stable.map(|l| l.all_features()).unwrap_or_default();
+    let selected = match params.version.as_deref().filter(|v| !v.is_empty() && *v != "latest") {
+        Some(v) => Some(index_lines.iter().find(|l| l.vers == v).ok_or_else(|| ErrorData::from(DocsError::VersionNotFound {
+            name: name.clone(),
+            version: v.to_string(),
+        }))?),
+        None => latest_stable,
+    };
+    let features = selected.map(|l| l.all_features()).unwrap_or_default();
+    let latest_release_date = latest_stable.and_then(|l| {
+        api.versions.as_ref()?.iter().find(|v| v.num == l.vers).map(|v| v.created_at.as_str())
+    });
+
+    // A failed probe leaves availability unknown rather than failing the request
+    let docs_available = match selected {
+        Some(l) => crate::docsrs::docs_exist(name, &l.vers, &state.client, &state.cache).await
+            .inspect_err(|e| tracing::debug!("docs.rs probe for {name} {} failed: {e}", l.vers))
+            .ok(),
+        None => None,
+    };
 
     let krate = &api.krate;
     let output = CrateGetOutput {
@@ -65,9 +150,16 @@ pub async fn execute(state: &AppState, params: CrateGetParams) -> Result<CallToo
         updated_at: &krate.updated_at,
         max_stable_version: krate.max_stable_version.as_deref(),
         max_version: krate.max_version.as_deref(),
+        version: selected.map(|l| l.vers.as_str()),
+        yanked: selected.map(|l| l.yanked),
+        dependency_count: selected.map(|l| l.normal_dep_count()),
+        latest_stable: latest_stable.map(|l| l.vers.as_str()),
+        rust_version: selected.and_then(|l| l.rust_version.as_deref()),
+        latest_release_date,
+        docs_available,
         features,
-        keywords: api.keywords.as_ref().map(|kws| kws.iter().map(|k| k.keyword.as_str()).collect()),
-        categories: api.categories.as_ref().map(|cats| cats.iter().map(|c| c.category.as_str()).collect()),
+        keywords: api.keywords.as_ref().map(|kws| kws.iter().map(KeywordOutput::from).collect()),
+        categories: api.categories.as_ref().map(|cats| cats.iter().map(CategoryOutput::from).collect()),
     };
 
     let json = serde_json::to_string_pretty(&output)
@@ -0,0 +1,169 @@
+use rmcp::{ErrorData, model::{CallToolResult, Content}};
+use serde::Deserialize;
+use rmcp::schemars::{self, JsonSchema};
+use serde_json::json;
+use semver::Version;
+
+use super::AppState;
+use crate::docsrs::{fetch_rustdoc_json, DocDetail, api_diff::{diff_api, public_api}};
+use crate::sparse_index::IndexLine;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CrateHealthReportParams {
+    /// Crate name
+    pub name: String,
+    /// Number of recent minor releases to diff for API churn (default: 3, max: 6)
+    pub releases: Option<usize>,
+}
+
+pub async fn execute(state: &AppState, params: CrateHealthReportParams) -> Result<CallToolResult, ErrorData> {
+    let name = &params.name;
+    let releases = params.releases.unwrap_or(3).clamp(1, 6);
+
+    let lines = state.fetch_index(name).await
+        .map_err(ErrorData::from)?;
+    let versions = recent_minor_releases(&lines, releases + 1);
+
+    let api_stability = api_stability(state, name, &versions).await?;
+
+    let output = json!({
+        "name": name,
+        "version": versions.last(),
+        "api_stability": api_stability,
+    });
+
+    let json = serde_json::to_string_pretty(&output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
+
+/// Diff the public API across consecutive `versions` (oldest first) and summarize churn.
+///
+/// Versions without a docs.rs build are skipped, so the diff spans the nearest built pair.
+async fn api_stability(state: &AppState, name: &str, versions: &[String]) -> Result<serde_json::Value, ErrorData> {
+    let mut previous: Option<(String, std::collections::BTreeMap<String, String>)> = None;
+    let mut per_release = vec![];
+    let mut churns = vec![];
+    let mut skipped = vec![];
+
+    for version in versions {
+        let doc = match fetch_rustdoc_json(name, version, None, DocDetail::Full, &state.client, &state.cache).await {
+            Ok(d) => d,
+            Err(crate::error::DocsError::DocsNotFound { .. }) => {
+                skipped.push(version.clone());
+                continue;
+            }
+            Err(e) => return Err(ErrorData::from(e)),
+        };
+        let api = public_api(&doc);
+        drop(doc);
+
+        if let Some((prev_version, prev_api)) = &previous {
+            let diff = diff_api(prev_api, &api);
+            let churn = diff.churn(prev_api.len());
+            churns.push(churn);
+            per_release.push(json!({
+                "from": prev_version,
+                "to": version,
+                "items_before": prev_api.len(),
+                "added": diff.added.len(),
+                "removed": diff.removed.len(),
+                "changed": diff.changed.len(),
+                "churn": round3(churn),
+                "removed_sample": diff.removed.iter().take(10).collect::<Vec<_>>(),
+            }));
+        }
+        previous = Some((version.clone(), api));
+    }
+
+    if churns.is_empty() {
+        return Ok(json!({
+            "releases_compared": 0,
+            "skipped_versions": skipped,
+            "note": "Fewer than two recent minor releases have docs.rs builds; churn cannot be computed.",
+        }));
+    }
+
+    let mean_churn = churns.iter().sum::<f64>() / churns.len() as f64;
+    Ok(json!({
+        "releases_compared": churns.len(),
+        "mean_churn": round3(mean_churn),
+        "stability_score": ((1.0 - mean_churn).clamp(0.0, 1.0) * 100.0).round(),
+        "rating": churn_rating(mean_churn),
+        "releases": per_release,
+        "skipped_versions": skipped,
+    }))
+}
+
+/// The latest patch of each of the `count` most recent (major, minor) lines, oldest first.
+///
+/// Yanked and pre-release versions are ignored.
+fn recent_minor_releases(lines: &[IndexLine], count: usize) -> Vec<String> {
+    let mut versions: Vec<Version> = lines.iter()
+        .filter(|l| !l.yanked && !l.vers.contains('-'))
+        .filter_map(|l| Version::parse(&l.vers).ok())
+        .collect();
+    versions.sort_by(|a, b| b.cmp(a));
+
+    let mut picked: Vec<Version> = vec![];
+    for v in versions {
+        if picked.iter().any(|p| p.major == v.major && p.minor == v.minor) { continue; }
+        picked.push(v);
+        if picked.len() == count { break; }
+    }
+    picked.reverse();
+    picked.into_iter().map(|v| v.to_string()).collect()
+}
+
+fn churn_rating(mean_churn: f64) -> &'static str {
+    if mean_churn < 0.02 {
+        "stable"
+    } else if mean_churn < 0.10 {
+        "moderate"
+    } else {
+        "high"
+    }
+}
+
+fn round3(x: f64) -> f64 {
+    (x * 1000.0).round() / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_line(vers: &str, yanked: bool) -> IndexLine {
+        IndexLine {
+            name: "test".to_string(),
+            vers: vers.to_string(),
+            deps: vec![],
+            cksum: "abc".to_string(),
+            features: Default::default(),
+            yanked,
+            rust_version: None,
+            features2: None,
+        }
+    }
+
+    #[test]
+    fn recent_minor_releases_picks_latest_patch_per_minor_oldest_first() {
+        let lines = vec![
+            make_line("1.0.0", false),
+            make_line("1.0.5", false),
+            make_line("1.1.0", false),
+            make_line("1.2.0", true), // yanked
+            make_line("1.2.1", false),
+            make_line("1.3.0-beta.1", false), // pre-release
+        ];
+        assert_eq!(recent_minor_releases(&lines, 3), vec!["1.0.5", "1.1.0", "1.2.1"]);
+        assert_eq!(recent_minor_releases(&lines, 2), vec!["1.1.0", "1.2.1"]);
+    }
+
+    #[test]
+    fn churn_rating_thresholds() {
+        assert_eq!(churn_rating(0.0), "stable");
+        assert_eq!(churn_rating(0.05), "moderate");
+        assert_eq!(churn_rating(0.5), "high");
+    }
+}
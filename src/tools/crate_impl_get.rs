@@ -0,0 +1,192 @@
+use rmcp::{ErrorData, model::{CallToolResult, Content}};
+use serde::Deserialize;
+use rmcp::schemars::{self, JsonSchema};
+use serde_json::json;
+
+use super::{AppState, docs_not_found_error};
+use crate::docsrs::{find_item_id, function_signature, id_to_string, impl_header, RustdocJson};
+use crate::docsrs::parser::{type_to_string, const_info, format_generics, where_predicates};
+use crate::error::ErrorCode;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CrateImplGetParams {
+    /// Crate name
+    pub name: String,
+    /// Version string. Defaults to latest stable.
+    pub version: Option<String>,
+    /// Impl block ID as returned in `impl_id` by crate_impls_list. Takes precedence over paths.
+    pub impl_id: Option<String>,
+    /// Trait of the impl to expand (e.g. "From" or "serde::Serialize")
+    pub trait_path: Option<String>,
+    /// Implementing type (e.g. "tokio::sync::Mutex"). Omit to pick a blanket impl by trait alone.
+    pub type_path: Option<String>,
+}
+
+pub async fn execute(state: &AppState, params: CrateImplGetParams) -> Result<CallToolResult, ErrorData> {
+    if params.impl_id.is_none() && params.trait_path.is_none() {
+        return Err(ErrorData::invalid_params(
+            "Either impl_id or trait_path must be specified.\n\
+             - Use crate_impls_list to find an impl and pass its impl_id\n\
+             - Or pass trait_path (optionally with type_path) to select the impl directly",
+            None,
+        ));
+    }
+
+    let name = &params.name;
+    let version = state.resolve_version(name, params.version.as_deref()).await
+        .map_err(ErrorData::from)?;
+
+    let doc = match state.rustdoc(name, &version).await {
+        Ok(d) => d,
+        Err(crate::error::DocsError::DocsNotFound { .. }) => {
+            return Err(docs_not_found_error(state, name, &version).await);
+        }
+        Err(e) => return Err(ErrorData::from(e)),
+    };
+
+    let impl_id = match &params.impl_id {
+        Some(id) => id.clone(),
+        None => select_impl(
+            &doc,
+            params.trait_path.as_deref().unwrap_or_default(),
+            params.type_path.as_deref(),
+        ).map_err(|msg| ErrorData::invalid_params(format!("{name} {version}: {msg}"), None))?,
+    };
+
+    let impl_inner = doc.index.get(&impl_id)
+        .and_then(|i| i.inner_for("impl"))
+        .ok_or_else(|| ErrorData::invalid_params(
+            format!("'{impl_id}' is not an impl block in {name} {version}. \
+                     Use crate_impls_list to find valid impl_id values."),
+            ErrorCode::ItemNotFound.data(),
+        ))?;
+
+    let output = json!({
+        "name": name,
+        "version": version,
+        "impl": describe_impl(&doc, &impl_id, impl_inner),
+    });
+
+    let json = serde_json::to_string_pretty(&output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
+
+/// Expand an impl block: header, generics, where clauses, and the items it provides.
+pub(crate) fn describe_impl(doc: &RustdocJson, impl_id: &str, impl_inner: &serde_json::Value) -> serde_json::Value {
+    let trait_path = impl_inner.get("trait").filter(|t| !t.is_null()).map(type_to_string);
+    let for_type = impl_inner.get("for").map(type_to_string);
+    let is_blanket = impl_inner.get("blanket_impl").map(|b| !b.is_null()).unwrap_or(false);
+
+    let mut methods = vec![];
+    let mut assoc_types = vec![];
+    let mut assoc_consts = vec![];
+    let item_ids = impl_inner.get("items").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+    for id_val in &item_ids {
+        let Some(id) = id_to_string(id_val) else { continue };
+        let Some(member) = doc.index.get(&id) else { continue };
+        let member_name = member.name.as_deref().unwrap_or("_");
+        match member.kind() {
+            Some("function") => methods.push(json!({
+                "name": member_name,
+                "signature": function_signature(member),
+                "doc_summary": member.doc_summary(),
+            })),
+            Some("assoc_type") => {
+                let ty = member.inner_for("assoc_type")
+                    .and_then(|a| a.get("type"))
+                    .filter(|t| !t.is_null())
+                    .map(type_to_string);
+                assoc_types.push(json!({ "name": member_name, "type": ty }));
+            }
+            Some("assoc_const") => {
+                let c = const_info(member);
+                assoc_consts.push(json!({
+                    "name": member_name,
+                    "declaration": c.as_ref().map(|c| c.declaration(member_name)),
+                    "type": c.as_ref().map(|c| &c.ty),
+                    "value": c.as_ref().and_then(|c| c.value.as_ref()),
+                }));
+            }
+            _ => {}
+        }
+    }
+
+    let provided: Vec<&str> = impl_inner.get("provided_trait_methods")
+        .and_then(|v| v.as_array())
+        .map(|a| a.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+    let header = impl_header(impl_inner);
+    let where_clauses = where_predicates(impl_inner.get("generics"));
+
+    json!({
+        "impl_id": impl_id,
+        "header": header,
+        "generics": format_generics(impl_inner.get("generics")),
+        "where_clauses": where_clauses,
+        "trait_path": trait_path,
+        "for_type": for_type,
+        "is_blanket": is_blanket,
+        "is_synthetic": impl_inner.get("is_synthetic").and_then(|v| v.as_bool()).unwrap_or(false),
+        "methods": methods,
+        "associated_types": assoc_types,
+        "associated_consts": assoc_consts,
+        "provided_trait_methods": provided,
+    })
+}
+
+/// Pick a single impl block of `trait_path`, optionally restricted to `type_path`.
+///
+/// Returns an error message listing candidate impl IDs when the choice is ambiguous.
+fn select_impl(doc: &RustdocJson, trait_path: &str, type_path: Option<&str>) -> Result<String, String> {
+    let trait_last = trait_path.rsplit("::").next().unwrap_or(trait_path);
+    let trait_matches = |impl_inner: &serde_json::Value| {
+        let Some(t) = impl_inner.get("trait").filter(|t| !t.is_null()) else { return false };
+        let t_name = t.get("path").and_then(|v| v.as_str()).unwrap_or("");
+        let t_last = t_name.rsplit("::").next().unwrap_or(t_name);
+        t_last == trait_last || t_name == trait_path
+    };
+
+    let candidate_ids: Vec<String> = match type_path {
+        Some(type_path) => {
+            let type_id = find_item_id(doc, type_path)
+                .ok_or_else(|| format!("Type '{type_path}' not found"))?;
+            let item = doc.index.get(&type_id)
+                .ok_or_else(|| format!("Type '{type_path}' is re-exported from another crate"))?;
+            ["struct", "enum", "union", "primitive"].iter()
+                .find_map(|k| item.inner_for(k).and_then(|i| i.get("impls")).and_then(|v| v.as_array()))
+                .map(|ids| ids.iter().filter_map(id_to_string).collect())
+                .unwrap_or_default()
+        }
+        None => doc.index.iter()
+            .filter(|(_, i)| i.kind() == Some("impl"))
+            .map(|(id, _)| id.clone())
+            .collect(),
+    };
+
+    let mut matches: Vec<(String, String)> = candidate_ids.into_iter()
+        .filter_map(|id| {
+            let impl_inner = doc.index.get(&id)?.inner_for("impl")?;
+            if !trait_matches(impl_inner) { return None; }
+            if impl_inner.get("is_synthetic").and_then(|v| v.as_bool()).unwrap_or(false) { return None; }
+            Some((id, impl_header(impl_inner)))
+        })
+        .collect();
+    matches.sort();
+
+    match matches.len() {
+        0 => Err(format!("No impl of '{trait_path}' found")),
+        1 => Ok(matches.remove(0).0),
+        _ => {
+            let listing: Vec<String> = matches.iter()
+                .take(10)
+                .map(|(id, header)| format!("  {id}: {}", header.lines().next().unwrap_or("")))
+                .collect();
+            Err(format!(
+                "{} impls of '{trait_path}' match; pass impl_id (or a more specific type_path). Candidates:\n{}",
+                matches.len(),
+                listing.join("\n"),
+            ))
+        }
+    }
+}
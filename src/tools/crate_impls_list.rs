@@ -3,8 +3,10 @@ use serde::Deserialize;
 use rmcp::schemars::{self, JsonSchema};
 use serde_json::json;
 
-use super::AppState;
-use crate::docsrs::{fetch_rustdoc_json, parser::type_to_string};
+use super::{AppState, docs_not_found_error};
+use crate::docsrs::{find_item_id, id_to_string, impl_header, source_link, Item, RustdocJson};
+use crate::docsrs::parser::{generic_arg_to_string, type_to_string, defining_module, format_generics, where_predicates};
+use crate::error::ErrorCode;
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct CrateImplsListParams {
@@ -12,7 +14,9 @@ pub struct CrateImplsListParams {
     pub name: String,
     /// Version string. Defaults to latest stable.
     pub version: Option<String>,
-    /// Fully-qualified trait path to find implementors of (e.g. "serde::Serialize")
+    /// Fully-qualified trait path to find implementors of (e.g. "serde::Serialize"). May carry
+    /// generic arguments to narrow the match: "From<std::io::Error>", or "Deref<Target = String>"
+    /// for an associated type. Use "_" for an argument that may be anything.
     pub trait_path: Option<String>,
     /// Fully-qualified type path to find trait implementations for (e.g. "tokio::sync::Mutex")
     pub type_path: Option<String>,
@@ -35,19 +39,14 @@ pub async fn execute(state: &AppState, params: CrateImplsListParams) -> Result<C
 
     let name = &params.name;
     let version = state.resolve_version(name, params.version.as_deref()).await
-        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+        .map_err(ErrorData::from)?;
 
-    let doc = match fetch_rustdoc_json(name, &version, &state.client, &state.cache).await {
+    let doc = match state.rustdoc(name, &version).await {
         Ok(d) => d,
         Err(crate::error::DocsError::DocsNotFound { .. }) => {
-            return Err(ErrorData::invalid_params(
-                format!("No docs.rs build found for {name} {version}. \
-                         The latest version may not have been built yet. \
-                         Try specifying an older version with the 'version' parameter."),
-                None,
-            ));
+            return Err(docs_not_found_error(state, name, &version).await);
         }
-        Err(e) => return Err(ErrorData::internal_error(e.to_string(), None)),
+        Err(e) => return Err(ErrorData::from(e)),
     };
 
     let search_lower = params.search.as_deref().map(|s| s.to_lowercase());
@@ -55,11 +54,12 @@ pub async fn execute(state: &AppState, params: CrateImplsListParams) -> Result<C
 
     if let Some(ref trait_path) = params.trait_path {
         // Find all types within this crate that implement the given trait.
-        // Match by last component or full path suffix.
-        let trait_last = trait_path.rsplit("::").next().unwrap_or(trait_path.as_str());
+        // Match by last component or full path suffix, then by any generic arguments given.
+        let query = TraitQuery::parse(trait_path);
+        let trait_last = query.path.rsplit("::").next().unwrap_or(query.path);
 
         let mut implementors: Vec<serde_json::Value> = vec![];
-        for item in doc.index.values() {
+        for (impl_id, item) in &doc.index {
             let Some(impl_inner) = item.inner_for("impl") else { continue };
             // Skip synthetic compiler-generated impls (Send, Sync, Freeze, etc.)
             if impl_inner.get("is_synthetic").and_then(|v| v.as_bool()).unwrap_or(false) {
@@ -72,9 +72,9 @@ pub async fn execute(state: &AppState, params: CrateImplsListParams) -> Result<C
             // Match trait by name (last component) or full path
             let t_name = trait_val.get("path").and_then(|v| v.as_str()).unwrap_or("");
             let t_matches = t_name == trait_last
-                || t_name == trait_path.as_str()
-                || trait_path.ends_with(&format!("::{t_name}"));
-            if !t_matches { continue; }
+                || t_name == query.path
+                || query.path.ends_with(&format!("::{t_name}"));
+            if !t_matches || !query.args_match(&doc, trait_val, impl_inner) { continue; }
 
             // Get the type being implemented for
             let for_val = impl_inner.get("for");
@@ -100,10 +100,13 @@ pub async fn execute(state: &AppState, params: CrateImplsListParams) -> Result<C
                 .map(|ps| ps.iter().filter_map(|p| p.get("name").and_then(|v| v.as_str())).collect())
                 .unwrap_or_default();
 
-            implementors.push(json!({
+            let mut entry = json!({
+                "impl_id": impl_id,
                 "type_name": for_name,
                 "impl_generics": if impl_generics.is_empty() { None } else { Some(impl_generics) },
-            }));
+            });
+            add_impl_details(&mut entry, &doc, item, impl_inner, name, &version);
+            implementors.push(entry);
         }
 
         let output = json!({
@@ -121,32 +124,14 @@ pub async fn execute(state: &AppState, params: CrateImplsListParams) -> Result<C
     // type_path branch: find all traits this type implements.
     // Use the type's `inner.{kind}.impls` list for precision (same approach as crate_item_get).
     let type_path_str = params.type_path.as_deref().unwrap();
-    let target_parts: Vec<&str> = type_path_str.split("::").collect();
 
     // Exact match first, then subsequence fallback for re-exports
-    let item_id = doc.paths.iter()
-        .find(|(_, p)| p.full_path() == type_path_str)
-        .or_else(|| {
-            doc.paths.iter().find(|(_, p)| {
-                let parts = &p.path;
-                if parts.is_empty() || target_parts.is_empty() { return false; }
-                if parts[0] != target_parts[0] { return false; }
-                let stored_rest = &parts[1..];
-                let target_rest = &target_parts[1..];
-                if target_rest.is_empty() { return false; }
-                let mut ti = 0;
-                for s in stored_rest {
-                    if ti < target_rest.len() && *s == target_rest[ti] { ti += 1; }
-                }
-                ti == target_rest.len()
-            })
-        })
-        .map(|(id, _)| id.clone());
+    let item_id = find_item_id(&doc, type_path_str);
 
     let item_id = item_id.ok_or_else(|| {
         ErrorData::invalid_params(
             format!("Type '{type_path_str}' not found in {name} {version}"),
-            None,
+            ErrorCode::ItemNotFound.data(),
         )
     })?;
 
@@ -160,15 +145,7 @@ pub async fn execute(state: &AppState, params: CrateImplsListParams) -> Result<C
         for kind in &["struct", "enum", "union", "primitive"] {
             if let Some(inner) = item.inner_for(kind) {
                 if let Some(impls) = inner.get("impls").and_then(|v| v.as_array()) {
-                    for v in impls {
-                        if let Some(id) = match v {
-                            serde_json::Value::Number(n) => Some(n.to_string()),
-                            serde_json::Value::String(s) => Some(s.clone()),
-                            _ => None,
-                        } {
-                            ids.push(id);
-                        }
-                    }
+                    ids.extend(impls.iter().filter_map(id_to_string));
                     break;
                 }
             }
@@ -204,10 +181,13 @@ pub async fn execute(state: &AppState, params: CrateImplsListParams) -> Result<C
 
         if implementations.len() >= limit { break; }
 
-        implementations.push(json!({
+        let mut entry = json!({
+            "impl_id": impl_id,
             "trait_path": trait_name,
             "is_inherent": is_inherent,
-        }));
+        });
+        add_impl_details(&mut entry, &doc, impl_item, impl_inner, name, &version);
+        implementations.push(entry);
     }
 
     let output = json!({
@@ -221,3 +201,328 @@ pub async fn execute(state: &AppState, params: CrateImplsListParams) -> Result<C
         .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
     Ok(CallToolResult::success(vec![Content::text(json)]))
 }
+
+/// Add the full impl header, its generics and where clause, and where the impl is defined.
+fn add_impl_details(
+    entry: &mut serde_json::Value,
+    doc: &RustdocJson,
+    impl_item: &Item,
+    impl_inner: &serde_json::Value,
+    name: &str,
+    version: &str,
+) {
+    let generics = impl_inner.get("generics");
+    let span = impl_item.span.as_ref();
+    entry["header"] = json!(impl_header(impl_inner));
+    entry["generics"] = json!(format_generics(generics));
+    entry["where_clauses"] = json!(where_predicates(generics));
+    entry["module"] = json!(span.and_then(|s| defining_module(doc, s)));
+    entry["source_url"] = json!(span.and_then(|s| source_link(s, name, version)).map(|l| l.url));
+    if let Some(origin) = impl_origin(doc, impl_inner) {
+        entry["origin"] = json!(origin.kind());
+        entry["trait_crate"] = json!(origin.trait_crate.name(name));
+        entry["type_crate"] = json!(origin.type_crate.name(name));
+    }
+}
+
+/// The crate that defines an impl's trait or its self type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Owner {
+    /// The crate being documented
+    Local,
+    /// Another crate (std and core included), by name when rustdoc records it
+    Foreign(Option<String>),
+    /// A generic parameter: the impl is a blanket impl over many types
+    Any,
+}
+
+impl Owner {
+    fn name(&self, crate_name: &str) -> Option<String> {
+        match self {
+            Owner::Local => Some(crate_name.to_string()),
+            Owner::Foreign(name) => name.clone(),
+            Owner::Any => None,
+        }
+    }
+}
+
+/// Where a trait impl's trait and self type are defined.
+#[derive(Debug)]
+struct ImplOrigin {
+    trait_crate: Owner,
+    type_crate: Owner,
+}
+
+impl ImplOrigin {
+    /// `local_trait_local_type`, `foreign_trait_local_type`, `local_trait_foreign_type`,
+    /// `foreign_trait_foreign_type`, or `local_trait_blanket`/`foreign_trait_blanket` for
+    /// impls over a generic parameter.
+    ///
+    /// Under the orphan rule this crate can only write impls involving a local trait or
+    /// type; `foreign_trait_foreign_type` and `foreign_trait_blanket` impls were inlined
+    /// from the crate that defines the trait, which is where to look for them.
+    fn kind(&self) -> String {
+        let side = |owner: &Owner| if *owner == Owner::Local { "local" } else { "foreign" };
+        let for_type = match self.type_crate {
+            Owner::Any => "blanket".to_string(),
+            ref owner => format!("{}_type", side(owner)),
+        };
+        format!("{}_trait_{for_type}", side(&self.trait_crate))
+    }
+}
+
+/// Where the trait and self type of a trait impl are defined, from the crate ids rustdoc
+/// records in `paths` and `external_crates`; `None` for inherent impls.
+fn impl_origin(doc: &RustdocJson, impl_inner: &serde_json::Value) -> Option<ImplOrigin> {
+    let trait_val = impl_inner.get("trait").filter(|t| !t.is_null())?;
+    let trait_crate = trait_val.get("id").map_or(Owner::Foreign(None), |id| item_owner(doc, id));
+    let blanket = impl_inner.get("blanket_impl").is_some_and(|b| !b.is_null());
+    let type_crate = match impl_inner.get("for") {
+        _ if blanket => Owner::Any,
+        Some(for_val) => type_owner(doc, for_val),
+        None => Owner::Foreign(None),
+    };
+    Some(ImplOrigin { trait_crate, type_crate })
+}
+
+/// The crate defining the item with rustdoc id `id`.
+fn item_owner(doc: &RustdocJson, id: &serde_json::Value) -> Owner {
+    let Some(id) = id_to_string(id) else { return Owner::Foreign(None) };
+    match doc.paths.get(&id) {
+        Some(entry) if entry.crate_id == 0 => Owner::Local,
+        Some(entry) => Owner::Foreign(doc.external_crates.get(&entry.crate_id.to_string()).map(|c| c.name.clone())),
+        None if doc.index.contains_key(&id) => Owner::Local,
+        None => Owner::Foreign(None),
+    }
+}
+
+/// The crate defining a rendered rustdoc type. References and trait objects count as the
+/// type or trait they point at; primitives, tuples, slices and arrays belong to `core`.
+fn type_owner(doc: &RustdocJson, ty: &serde_json::Value) -> Owner {
+    if let Some(path) = ty.get("resolved_path") {
+        return path.get("id").map_or(Owner::Foreign(None), |id| item_owner(doc, id));
+    }
+    if let Some(inner) = ty.get("borrowed_ref").and_then(|r| r.get("type")) {
+        return type_owner(doc, inner);
+    }
+    if let Some(id) = ty.pointer("/dyn_trait/traits/0/trait/id") {
+        return item_owner(doc, id);
+    }
+    if ty.get("generic").is_some() {
+        return Owner::Any;
+    }
+    Owner::Foreign(Some("core".to_string()))
+}
+
+/// A `trait_path` split into the trait path and its generic arguments.
+///
+/// `From<std::io::Error>` has the positional argument `std::io::Error`;
+/// `Deref<Target = String>` constrains the impl's associated type `Target`.
+#[derive(Debug, PartialEq)]
+struct TraitQuery<'a> {
+    path: &'a str,
+    args: Vec<&'a str>,
+    constraints: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> TraitQuery<'a> {
+    fn parse(trait_path: &'a str) -> Self {
+        let trait_path = trait_path.trim();
+        let Some((path, rest)) = trait_path.split_once('<') else {
+            return Self { path: trait_path, args: vec![], constraints: vec![] };
+        };
+        let inner = rest.strip_suffix('>').unwrap_or(rest);
+        let mut args = vec![];
+        let mut constraints = vec![];
+        for arg in split_top_level(inner) {
+            // `Name = Ty` binds an associated type; `=` nested deeper belongs to the argument
+            match arg.split_once('=') {
+                Some((name, ty)) if name.trim().chars().all(|c| c.is_alphanumeric() || c == '_') => {
+                    constraints.push((name.trim(), ty.trim()));
+                }
+                _ => args.push(arg),
+            }
+        }
+        Self { path: path.trim(), args, constraints }
+    }
+
+    /// Whether the impl's trait arguments and associated types agree with the query.
+    fn args_match(&self, doc: &RustdocJson, trait_val: &serde_json::Value, impl_inner: &serde_json::Value) -> bool {
+        let mut impl_args: Vec<String> = trait_val.get("args")
+            .and_then(|a| a.get("angle_bracketed"))
+            .and_then(|ab| ab.get("args"))
+            .and_then(|a| a.as_array())
+            .map(|args| args.iter().filter_map(generic_arg_to_string).collect())
+            .unwrap_or_default();
+        // A query naming no lifetimes leaves them out of the positions it counts
+        if !self.args.iter().any(|q| is_lifetime(q)) {
+            impl_args.retain(|a| !is_lifetime(a));
+        }
+        let positional_ok = self.args.iter().enumerate()
+            .all(|(i, q)| impl_args.get(i).is_some_and(|a| arg_matches(q, a)));
+        positional_ok && self.constraints.iter().all(|(name, q)| {
+            assoc_type(doc, impl_inner, name).is_some_and(|ty| type_matches(q, &ty))
+        })
+    }
+}
+
+/// Split generic arguments on commas that are not nested in `<>`, `()` or `[]`.
+fn split_top_level(s: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0i32;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(s[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(s[start..].trim());
+    parts.retain(|p| !p.is_empty());
+    parts
+}
+
+/// Rendered type of the associated type `name` defined in an impl block.
+fn assoc_type(doc: &RustdocJson, impl_inner: &serde_json::Value, name: &str) -> Option<String> {
+    impl_inner.get("items")?.as_array()?.iter()
+        .filter_map(|v| doc.index.get(&id_to_string(v)?))
+        .find(|item| item.name.as_deref() == Some(name))
+        .and_then(|item| item.inner_for("assoc_type"))
+        .and_then(|a| a.get("type"))
+        .filter(|t| !t.is_null())
+        .map(type_to_string)
+}
+
+/// Compare a queried type with a rendered one, ignoring whitespace and module paths
+/// (`std::io::Error` matches `io::Error` and `Error`). `_` matches anything.
+fn type_matches(query: &str, rendered: &str) -> bool {
+    query.trim() == "_" || strip_paths(query) == strip_paths(rendered)
+}
+
+/// Whether a queried generic argument matches a rendered one. Lifetimes must be equal,
+/// with `'_` matching any lifetime; other arguments compare as types. `_` matches anything.
+fn arg_matches(query: &str, rendered: &str) -> bool {
+    let query = query.trim();
+    if query == "_" {
+        return true;
+    }
+    match (is_lifetime(query), is_lifetime(rendered)) {
+        (true, true) => query == "'_" || query == rendered,
+        (false, false) => type_matches(query, rendered),
+        _ => false,
+    }
+}
+
+fn is_lifetime(arg: &str) -> bool {
+    arg.trim_start().starts_with('\'')
+}
+
+/// Drop whitespace and every `module::` prefix, so `Vec<std::string::String>` becomes `Vec<String>`.
+fn strip_paths(s: &str) -> String {
+    let mut out = String::new();
+    let mut token_start = 0;
+    let mut chars = s.chars().filter(|c| !c.is_whitespace()).peekable();
+    while let Some(c) = chars.next() {
+        if c == ':' && chars.peek() == Some(&':') {
+            chars.next();
+            out.truncate(token_start);
+            continue;
+        }
+        out.push(c);
+        if !(c.is_alphanumeric() || c == '_') {
+            token_start = out.len();
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn impl_origin_kinds_name_both_sides() {
+        let origin = |trait_crate, type_crate| ImplOrigin { trait_crate, type_crate }.kind();
+        let std = || Owner::Foreign(Some("std".to_string()));
+        assert_eq!(origin(Owner::Local, Owner::Local), "local_trait_local_type");
+        assert_eq!(origin(std(), Owner::Local), "foreign_trait_local_type");
+        assert_eq!(origin(Owner::Local, std()), "local_trait_foreign_type");
+        assert_eq!(origin(std(), Owner::Any), "foreign_trait_blanket");
+        assert_eq!(Owner::Local.name("rmcp").as_deref(), Some("rmcp"));
+        assert_eq!(Owner::Any.name("rmcp"), None);
+    }
+
+    #[test]
+    fn type_owner_looks_through_references() {
+        let doc: RustdocJson = serde_json::from_value(json!({
+            "format_version": 57,
+            "root": 0,
+            "index": {},
+            "paths": {
+                "1": {"crate_id": 0, "path": ["demo", "Local"], "kind": "struct"},
+                "2": {"crate_id": 5, "path": ["alloc", "string", "String"], "kind": "struct"},
+            },
+            "external_crates": {"5": {"name": "alloc", "html_root_url": null}},
+            "crate_version": null,
+        })).unwrap();
+        let local = json!({"borrowed_ref": {"type": {"resolved_path": {"path": "Local", "id": 1, "args": null}}}});
+        assert_eq!(type_owner(&doc, &local), Owner::Local);
+        let string = json!({"resolved_path": {"path": "String", "id": 2, "args": null}});
+        assert_eq!(type_owner(&doc, &string), Owner::Foreign(Some("alloc".to_string())));
+        assert_eq!(type_owner(&doc, &json!({"primitive": "u8"})), Owner::Foreign(Some("core".to_string())));
+        assert_eq!(type_owner(&doc, &json!({"generic": "T"})), Owner::Any);
+    }
+
+    #[test]
+    fn trait_query_splits_args_and_constraints() {
+        let q = TraitQuery::parse("std::convert::From<std::io::Error>");
+        assert_eq!(q.path, "std::convert::From");
+        assert_eq!(q.args, vec!["std::io::Error"]);
+
+        let q = TraitQuery::parse("Deref<Target = String>");
+        assert_eq!(q.path, "Deref");
+        assert!(q.args.is_empty());
+        assert_eq!(q.constraints, vec![("Target", "String")]);
+
+        let q = TraitQuery::parse("Fn<(u8, HashMap<K, V>), Output = _>");
+        assert_eq!(q.args, vec!["(u8, HashMap<K, V>)"]);
+        assert_eq!(q.constraints, vec![("Output", "_")]);
+
+        let q = TraitQuery::parse("From<Box<dyn Iterator<Item = u8>>>");
+        assert_eq!(q.args, vec!["Box<dyn Iterator<Item = u8>>"]);
+        assert!(q.constraints.is_empty());
+
+        assert_eq!(TraitQuery::parse("Clone"), TraitQuery { path: "Clone", args: vec![], constraints: vec![] });
+    }
+
+    #[test]
+    fn args_match_compares_lifetimes() {
+        let doc: RustdocJson = serde_json::from_value(json!({
+            "format_version": 57, "root": 0, "index": {}, "paths": {},
+        })).unwrap();
+        // impl<'de> Visitor<'de, String>
+        let trait_val = json!({"path": "Visitor", "id": 1, "args": {"angle_bracketed": {
+            "args": [{"lifetime": "'de"}, {"type": {"generic": "String"}}], "constraints": []
+        }}});
+        let matches = |q: &str| TraitQuery::parse(q).args_match(&doc, &trait_val, &json!({}));
+        assert!(matches("Visitor<'de, String>"));
+        assert!(matches("Visitor<'_, String>"));
+        assert!(matches("Visitor<String>"), "a query without lifetimes skips them");
+        assert!(!matches("Visitor<'static, String>"));
+        assert!(!matches("Visitor<'de, u8>"));
+    }
+
+    #[test]
+    fn type_matches_ignores_module_paths() {
+        assert!(type_matches("std::io::Error", "io::Error"));
+        assert!(type_matches("Vec<std::string::String>", "Vec<String>"));
+        assert!(type_matches("_", "anything"));
+        assert!(!type_matches("std::io::Error", "String"));
+        assert!(!type_matches("Vec<u8>", "Vec<u16>"));
+    }
+}
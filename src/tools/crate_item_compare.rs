@@ -0,0 +1,177 @@
+use std::collections::BTreeMap;
+
+use rmcp::{ErrorData, model::{CallToolResult, Content}};
+use serde::Deserialize;
+use rmcp::schemars::{self, JsonSchema};
+use serde_json::{Value, json};
+
+use super::AppState;
+use super::crate_item_get::{self, CrateItemGetParams};
+use crate::docsrs::SummaryPolicy;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CrateItemCompareParams {
+    /// Crate of the first item (e.g. "parking_lot")
+    pub name_a: String,
+    /// Fully-qualified path of the first item (e.g. "parking_lot::Mutex")
+    pub path_a: String,
+    /// Version of the first crate. Defaults to latest stable.
+    pub version_a: Option<String>,
+    /// Crate of the second item; may be the same crate as the first
+    pub name_b: String,
+    /// Fully-qualified path of the second item (e.g. "tokio::sync::Mutex")
+    pub path_b: String,
+    /// Version of the second crate. Defaults to latest stable.
+    pub version_b: Option<String>,
+}
+
+pub async fn execute(state: &AppState, params: CrateItemCompareParams) -> Result<CallToolResult, ErrorData> {
+    let (version_a, version_b) = tokio::join!(
+        state.resolve_version(&params.name_a, params.version_a.as_deref()),
+        state.resolve_version(&params.name_b, params.version_b.as_deref())
+    );
+    let version_a = version_a.map_err(ErrorData::from)?;
+    let version_b = version_b.map_err(ErrorData::from)?;
+
+    let lookup = |name: &str, version: &str, path: &str| CrateItemGetParams {
+        name: name.to_string(),
+        version: Some(version.to_string()),
+        item_path: path.to_string(),
+        include_methods: Some(true),
+        include_trait_impls: Some("filtered".to_string()),
+        include_trait_impl_methods: None,
+        impl_for: None,
+        target: None,
+    };
+    let (a, b) = tokio::join!(
+        crate_item_get::describe(state, lookup(&params.name_a, &version_a, &params.path_a)),
+        crate_item_get::describe(state, lookup(&params.name_b, &version_b, &params.path_b))
+    );
+    let (a, b) = (a?, b?);
+
+    let mut output = json!({
+        "a": overview(&params.name_a, &version_a, &a),
+        "b": overview(&params.name_b, &version_b, &b),
+        "same_kind": a["kind"] == b["kind"],
+        "methods": align(&a["methods"], &b["methods"], "name", "signature"),
+        "trait_impls": compare_traits(&a["trait_impls"], &b["trait_impls"]),
+    });
+    if a.get("fields").is_some() || b.get("fields").is_some() {
+        output["fields"] = align(&a["fields"], &b["fields"], "name", "type");
+    }
+    if a.get("associated_types").is_some() || b.get("associated_types").is_some() {
+        output["associated_types"] = align(&a["associated_types"], &b["associated_types"], "name", "declaration");
+    }
+
+    let json = serde_json::to_string_pretty(&output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
+
+/// One side's headline facts, taken from its crate_item_get result.
+fn overview(name: &str, version: &str, item: &Value) -> Value {
+    let doc_summary = SummaryPolicy::global().summarize(item["docs"].as_str().unwrap_or(""));
+    json!({
+        "crate": name,
+        "version": version,
+        "path": item["path"],
+        "kind": item["kind"],
+        "signature": item["signature"],
+        "generics": item["generics"],
+        "doc_summary": doc_summary,
+        "deprecated": item["deprecated"],
+        "feature_requirements": item["feature_requirements"],
+        "source_url": item["source_url"],
+    })
+}
+
+/// Align two lists of named entries side by side.
+///
+/// Returns `{"common": [{name, a, b, same}], "only_a": [...], "only_b": [...]}` where `a` and
+/// `b` hold each side's `value_key` and the `only_*` lists keep the entry's value and doc
+/// summary. Names are sorted.
+fn align(a: &Value, b: &Value, name_key: &str, value_key: &str) -> Value {
+    let by_name = |list: &Value| -> BTreeMap<String, Value> {
+        list.as_array().into_iter().flatten()
+            .filter_map(|e| Some((e[name_key].as_str()?.to_string(), e.clone())))
+            .collect()
+    };
+    let (a, b) = (by_name(a), by_name(b));
+    let side = |e: &Value| json!({
+        "name": e[name_key],
+        value_key: e[value_key],
+        "doc_summary": e["doc_summary"],
+    });
+
+    let common: Vec<Value> = a.iter()
+        .filter_map(|(name, ea)| {
+            let eb = b.get(name)?;
+            Some(json!({
+                "name": name,
+                "a": ea[value_key],
+                "b": eb[value_key],
+                "same": ea[value_key] == eb[value_key],
+            }))
+        })
+        .collect();
+    let only_a: Vec<Value> = a.iter().filter(|(n, _)| !b.contains_key(*n)).map(|(_, e)| side(e)).collect();
+    let only_b: Vec<Value> = b.iter().filter(|(n, _)| !a.contains_key(*n)).map(|(_, e)| side(e)).collect();
+    json!({ "common": common, "only_a": only_a, "only_b": only_b })
+}
+
+/// Split trait impls into shared and one-sided, matching on the trait's last path segment
+/// plus generic args, since the two crates may name the same trait by different paths.
+fn compare_traits(a: &Value, b: &Value) -> Value {
+    let keys = |list: &Value| -> BTreeMap<String, String> {
+        list.as_array().into_iter().flatten()
+            .filter_map(|t| t["trait_path"].as_str())
+            .map(|p| (trait_key(p), p.to_string()))
+            .collect()
+    };
+    let (a, b) = (keys(a), keys(b));
+    let common: Vec<&String> = a.keys().filter(|k| b.contains_key(*k)).collect();
+    let only_a: Vec<&String> = a.iter().filter(|(k, _)| !b.contains_key(*k)).map(|(_, p)| p).collect();
+    let only_b: Vec<&String> = b.iter().filter(|(k, _)| !a.contains_key(*k)).map(|(_, p)| p).collect();
+    json!({ "common": common, "only_a": only_a, "only_b": only_b })
+}
+
+/// `core::fmt::Debug` → `Debug`; `std::convert::From<T>` → `From<T>`.
+fn trait_key(path: &str) -> String {
+    let (base, args) = path.find('<').map_or((path, ""), |i| path.split_at(i));
+    let last = base.rsplit("::").next().unwrap_or(base);
+    format!("{last}{args}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn align_splits_common_and_one_sided_entries() {
+        let a = json!([
+            {"name": "lock", "signature": "fn lock(&self) -> MutexGuard<'_, T>", "doc_summary": "Blocks."},
+            {"name": "try_lock", "signature": "fn try_lock(&self) -> Option<MutexGuard<'_, T>>"},
+        ]);
+        let b = json!([
+            {"name": "lock", "signature": "async fn lock(&self) -> MutexGuard<'_, T>"},
+            {"name": "blocking_lock", "signature": "fn blocking_lock(&self) -> MutexGuard<'_, T>"},
+        ]);
+        let aligned = align(&a, &b, "name", "signature");
+        assert_eq!(aligned["common"][0]["name"], "lock");
+        assert_eq!(aligned["common"][0]["same"], false);
+        assert_eq!(aligned["only_a"][0]["name"], "try_lock");
+        assert_eq!(aligned["only_b"][0]["name"], "blocking_lock");
+    }
+
+    #[test]
+    fn traits_match_across_paths() {
+        assert_eq!(trait_key("core::fmt::Debug"), "Debug");
+        assert_eq!(trait_key("std::convert::From<T>"), "From<T>");
+        let a = json!([{"trait_path": "core::fmt::Debug"}, {"trait_path": "Default"}]);
+        let b = json!([{"trait_path": "Debug"}]);
+        let traits = compare_traits(&a, &b);
+        assert_eq!(traits["common"], json!(["Debug"]));
+        assert_eq!(traits["only_a"], json!(["Default"]));
+        assert_eq!(traits["only_b"], json!([]));
+    }
+}
@@ -1,14 +1,17 @@
 use std::collections::HashSet;
+use std::sync::Arc;
 
 use rmcp::{ErrorData, model::{CallToolResult, Content}};
 use serde::Deserialize;
 use rmcp::schemars::{self, JsonSchema};
 use serde_json::json;
 
-use super::AppState;
-use crate::docsrs::{fetch_rustdoc_json, function_signature, extract_feature_requirements};
-use crate::docsrs::parser::{type_to_string, format_generics_for_item};
+use super::{AppState, docs_not_found_error, edit_distance};
+use crate::docsrs::{function_signature, cfg_requirements, doc_caveats, resolve_links, rewrite_doc_links, source_link, split_doc_examples};
+use crate::docsrs::{find_item_id, id_to_string, impl_header, impl_skeleton, trait_members, ItemKind, RustdocJson, TraitMember};
+use crate::docsrs::parser::{type_to_string, const_info, format_generics, format_generics_for_item, struct_fields, bounds_to_string};
 use crate::sparse_index::find_latest_stable;
+use crate::error::ErrorCode;
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct CrateItemGetParams {
@@ -16,77 +19,105 @@ pub struct CrateItemGetParams {
     pub name: String,
     /// Version string. Defaults to latest stable.
     pub version: Option<String>,
-    /// Fully-qualified item path (e.g. "tokio::sync::Mutex")
+    /// Fully-qualified item path (e.g. "tokio::sync::Mutex"), or a method path
+    /// (e.g. "tokio::sync::Mutex::lock")
     pub item_path: String,
     /// Include inherent methods from impl blocks (default: true)
     pub include_methods: Option<bool>,
     /// Trait impl filtering mode: "filtered" (default) omits ubiquitous blankets like
     /// Borrow/Into/From<T>/Any; "all" returns everything; "none" omits trait impls entirely.
     pub include_trait_impls: Option<String>,
+    /// Expand each listed trait impl that is not a blanket impl with its method signatures,
+    /// associated types (e.g. Deref::Target, Iterator::Item) and the names of the trait's
+    /// provided methods it inherits (default: false). For types whose API mostly comes
+    /// from trait impls, such as iterator adapters or Deref wrappers.
+    pub include_trait_impl_methods: Option<bool>,
+    /// Traits only: type to implement the trait for (e.g. "MyTransport"). Adds `impl_skeleton`,
+    /// an `impl Trait for MyTransport` block stubbing every required item with `todo!()`.
+    pub impl_for: Option<String>,
+    /// Target triple whose docs.rs build to read (e.g. "x86_64-pc-windows-msvc",
+    /// "wasm32-unknown-unknown"), for platform-specific APIs. Default: the crate's default target.
+    pub target: Option<String>,
 }
 
 pub async fn execute(state: &AppState, params: CrateItemGetParams) -> Result<CallToolResult, ErrorData> {
-    let name = &params.name;
-    let version = state.resolve_version(name, params.version.as_deref()).await
+    let output = describe(state, params).await?;
+    let json = serde_json::to_string_pretty(&output)
         .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
 
-    let include_methods = params.include_methods.unwrap_or(true);
-    let trait_impl_mode = params.include_trait_impls.as_deref().unwrap_or("filtered");
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
+
+/// The crate_item_get result as JSON, shared with crate_item_compare.
+pub(crate) async fn describe(state: &AppState, params: CrateItemGetParams) -> Result<serde_json::Value, ErrorData> {
+    let name = &params.name;
+    let version = state.resolve_version(name, params.version.as_deref()).await
+        .map_err(ErrorData::from)?;
+    let target = super::validate_target(params.target.as_deref())?;
+    let (doc, declared_features) = load(state, name, &version, target).await?;
+    describe_item(&doc, &params, &version, &declared_features)
+}
 
+/// The parsed docs of a crate version and the feature names the crate declares: everything
+/// item lookups share, so a batch of them loads it once.
+pub(crate) async fn load(
+    state: &AppState,
+    name: &str,
+    version: &str,
+    target: Option<&str>,
+) -> Result<(Arc<RustdocJson>, HashSet<String>), ErrorData> {
     let (docs_result, index_result) = tokio::join!(
-        fetch_rustdoc_json(name, &version, &state.client, &state.cache),
+        state.rustdoc_for_target(name, version, target),
         state.fetch_index(name)
     );
 
-    let doc = docs_result.map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    let doc = match docs_result {
+        Ok(d) => d,
+        Err(crate::error::DocsError::DocsNotFound { .. }) => {
+            if let Some(target) = target {
+                return Err(super::target_not_built_error(name, version, target));
+            }
+            return Err(docs_not_found_error(state, name, version).await);
+        }
+        Err(e) => return Err(ErrorData::from(e)),
+    };
     let index_lines = index_result.unwrap_or_default();
     let latest = find_latest_stable(&index_lines);
     let features = latest.map(|l| l.all_features()).unwrap_or_default();
-    let declared_features: HashSet<String> = features.keys().cloned().collect();
+    Ok((doc, features.keys().cloned().collect()))
+}
+
+/// The crate_item_get result for `params.item_path` in already loaded docs.
+pub(crate) fn describe_item(
+    doc: &RustdocJson,
+    params: &CrateItemGetParams,
+    version: &str,
+    declared_features: &HashSet<String>,
+) -> Result<serde_json::Value, ErrorData> {
+    let name = &params.name;
+    let include_methods = params.include_methods.unwrap_or(true);
+    let trait_impl_mode = params.include_trait_impls.as_deref().unwrap_or("filtered");
+    let include_trait_impl_methods = params.include_trait_impl_methods.unwrap_or(false);
 
-    // Find item by path — exact match first, then suffix fallback for re-exports
+    // Find item by path — exact match first, then subsequence fallback for re-exports
     let target_path = &params.item_path;
-    let target_parts: Vec<&str> = target_path.split("::").collect();
-
-    let item_id = doc.paths.iter()
-        .find(|(_, p)| p.full_path() == *target_path)
-        .or_else(|| {
-            // Re-exports: "tokio::sync::Mutex" stored as "tokio::sync::mutex::Mutex"
-            // Match via subsequence on non-crate path components (skip first == crate name).
-            // "tokio::sync::Mutex" → rest = ["sync", "Mutex"]
-            // stored "tokio::sync::mutex::Mutex" → rest = ["sync", "mutex", "Mutex"]
-            // ["sync", "Mutex"] is a subsequence of ["sync", "mutex", "Mutex"] ✓
-            doc.paths.iter().find(|(_, p)| {
-                let parts = &p.path;
-                if parts.is_empty() || target_parts.is_empty() { return false; }
-                // Crate names must match
-                if parts[0] != target_parts[0] { return false; }
-                let stored_rest = &parts[1..];
-                let target_rest = &target_parts[1..];
-                if target_rest.is_empty() { return false; }
-                // Check if target_rest is a subsequence of stored_rest
-                let mut ti = 0;
-                for s in stored_rest {
-                    if ti < target_rest.len() && *s == target_rest[ti] {
-                        ti += 1;
-                    }
-                }
-                ti == target_rest.len()
-            })
-        })
-        .map(|(id, _)| id.clone());
+    let item_id = find_item_id(doc, target_path);
+
+    // Methods are not in doc.paths: `Type::method` resolves through the type's impls
+    let method = if item_id.is_none() { find_method(doc, target_path) } else { None };
+    if let Some(found) = method {
+        return Ok(describe_method(doc, &found, declared_features, name, version));
+    }
 
     let item_id = item_id.ok_or_else(|| {
         // Item not found in doc.paths — check if it's a re-export "use" item in doc.index
         // that points to an external crate (common with facade crates: serde, futures, clap).
         let last_component = target_path.split("::").last().unwrap_or(target_path.as_str());
-        let re_export_sources: Vec<String> = doc.index.iter()
-            .filter(|(id, item)| {
-                !doc.paths.contains_key(*id)
-                    && item.name.as_deref() == Some(last_component)
-                    && item.kind() == Some("use")
-            })
-            .filter_map(|(_, item)| {
+        let re_export_sources: Vec<String> = doc.ids_named(last_component).iter()
+            .filter(|id| !doc.paths.contains_key(*id))
+            .filter_map(|id| doc.index.get(id))
+            .filter(|item| item.kind() == Some("use"))
+            .filter_map(|item| {
                 item.inner_for("use")
                     .and_then(|u| u.get("source"))
                     .and_then(|v| v.as_str())
@@ -104,11 +135,24 @@ pub async fn execute(state: &AppState, params: CrateItemGetParams) -> Result<Cal
                 None,
             )
         } else {
+            let similar = similar_paths(doc, target_path);
+            let closest = if similar.is_empty() {
+                String::new()
+            } else {
+                let paths: Vec<&str> = similar.iter().map(|(path, _)| path.as_str()).collect();
+                format!(" Closest matches: {}.", paths.join(", "))
+            };
+            let mut data = ErrorCode::ItemNotFound.data();
+            if let Some(data) = data.as_mut() {
+                data["suggestions"] = json!(similar.iter()
+                    .map(|(path, kind)| json!({"path": path, "kind": kind}))
+                    .collect::<Vec<_>>());
+            }
             ErrorData::invalid_params(
-                format!("Item '{target_path}' not found in {name} {version}. \
+                format!("Item '{target_path}' not found in {name} {version}.{closest} \
                          Use crate_item_list(name=\"{name}\", query=\"{last_component}\") \
                          to search for available items and discover the correct path."),
-                None,
+                data,
             )
         }
     })?;
@@ -131,17 +175,20 @@ pub async fn execute(state: &AppState, params: CrateItemGetParams) -> Result<Cal
     let kind = path_entry.kind_name();
 
     // Build signature
+    let constant = const_info(item);
     let signature = match kind {
         "function" => function_signature(item),
         _ => {
             let iname = item.name.as_deref().unwrap_or("_");
-            let generics = format_generics_for_item(item, kind);
-            format!("{kind} {iname}{generics}")
+            match &constant {
+                Some(c) => c.declaration(iname),
+                None => format!("{kind} {iname}{}", format_generics_for_item(item, kind)),
+            }
         }
     };
 
-    // Feature requirements
-    let feature_requirements = extract_feature_requirements(&item.attr_strings(), &declared_features);
+    // Feature and target (e.g. unix-only) requirements from cfg attributes
+    let requirements = cfg_requirements(&item.attr_strings(), declared_features);
 
     // Deprecation
     let deprecated = item.deprecation.as_ref().map(|d| json!({
@@ -149,44 +196,364 @@ pub async fn execute(state: &AppState, params: CrateItemGetParams) -> Result<Cal
         "note": d.note,
     }));
 
-    // Methods (inherent impls)
-    let methods: Vec<serde_json::Value> = if include_methods {
-        collect_methods(&doc, item, &declared_features)
+    // Methods (inherent impls), plus the impl blocks that define them
+    let (methods, impl_blocks) = if include_methods {
+        collect_methods(doc, item, declared_features, name, version)
     } else {
-        vec![]
+        (vec![], vec![])
     };
 
     // Trait impls
+    let filter_ubiquitous = trait_impl_mode != "all";  // "filtered" default
     let trait_impls: Vec<serde_json::Value> = match trait_impl_mode {
         "none" => vec![],
-        "all"  => collect_trait_impls(&doc, item, false),
-        _      => collect_trait_impls(&doc, item, true),  // "filtered" default
+        _ if include_trait_impl_methods => trait_impl_ids(doc, item, filter_ubiquitous).into_iter()
+            .map(|(impl_id, trait_path)| expand_trait_impl(doc, &impl_id, trait_path, declared_features, name, version))
+            .collect(),
+        _ => collect_trait_impls(doc, item, filter_ubiquitous),
     };
 
-    let output = json!({
+    let mut output = json!({
         "path": target_path,
         "kind": kind,
         "signature": signature,
-        "docs": item.docs,
+        "generics": format_generics_for_item(item, kind),
         "deprecated": deprecated,
-        "feature_requirements": feature_requirements,
+        "source_url": source_url(item, name, version),
+        "feature_requirements": requirements.features,
+        "target_requirements": requirements.targets,
+        "cfg": requirements.cfg,
         "methods": methods,
+        "impl_blocks": impl_blocks,
         "trait_impls": trait_impls,
     });
 
-    let json = serde_json::to_string_pretty(&output)
-        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    for (key, value) in docs_fields(doc, item) {
+        output[key] = value;
+    }
 
-    Ok(CallToolResult::success(vec![Content::text(json)]))
+    if let ItemKind::Unknown(unknown) = item.item_kind() {
+        output["warnings"] = json!([format!(
+            "Item kind '{unknown}' is not recognized (rustdoc format {}); \
+             its signature and members may be incomplete",
+            doc.format_version,
+        )]);
+    }
+
+    // Constants and statics: type and initializer
+    if let Some(c) = &constant {
+        output["type"] = json!(c.ty);
+        output["value"] = json!(c.value);
+    }
+
+    // Plain struct fields with resolved types
+    if kind == "struct" {
+        let (fields, fields_stripped) = struct_fields(doc, item);
+        output["fields"] = json!(fields.iter().map(|f| json!({
+            "name": f.name,
+            "type": f.type_str,
+            "declaration": f.declaration(),
+            "docs": f.docs,
+        })).collect::<Vec<_>>());
+        output["fields_stripped"] = json!(fields_stripped);
+    }
+
+    // "How do I make one of these": constructor functions plus Default/From-style impls
+    if matches!(kind, "struct" | "enum" | "union") {
+        let type_name = item.name.as_deref().unwrap_or("_");
+        output["constructors"] = collect_constructors(doc, item, type_name, declared_features, name, version);
+    }
+
+    // Trait contract beyond methods: associated types (e.g. Iterator::Item) and consts
+    if kind == "trait" {
+        let (assoc_types, assoc_consts) = collect_assoc_items(doc, item);
+        output["associated_types"] = json!(assoc_types);
+        output["associated_consts"] = json!(assoc_consts);
+
+        if let Some(self_ty) = params.impl_for.as_deref().filter(|t| !t.is_empty()) {
+            let members = trait_members(doc, item);
+            let required: Vec<&TraitMember> = members.iter().filter(|m| m.required).collect();
+            output["impl_skeleton"] = json!(impl_skeleton(target_path, item, self_ty, None, &required));
+        }
+    }
+
+    output["related"] = related_items(doc, &path_entry.path, &item_id);
+
+    Ok(output)
 }
 
-/// Extract a numeric or string ID value as a String (v57 IDs are integers).
-fn id_to_string(v: &serde_json::Value) -> Option<String> {
-    match v {
-        serde_json::Value::Number(n) => Some(n.to_string()),
-        serde_json::Value::String(s) => Some(s.clone()),
-        _ => None,
+/// An item's docs split for display: prose with doc links rewritten to docs.rs URLs, code
+/// `examples`, the `panics`/`errors`/`safety` sections, and the resolved `links`.
+fn docs_fields(doc: &RustdocJson, item: &crate::docsrs::Item) -> [(&'static str, serde_json::Value); 6] {
+    // Intra-doc links: rewrite [`Foo`] references to docs.rs URLs and list their paths
+    let links = resolve_links(doc, item);
+    let docs = item.docs.as_deref().map(|d| rewrite_doc_links(d, &links));
+    let caveats = docs.as_deref().map(doc_caveats).unwrap_or_default();
+    // Code blocks move to `examples`; `docs` keeps the prose around them
+    let (prose, examples) = match docs.as_deref() {
+        Some(d) => {
+            let (prose, examples) = split_doc_examples(d);
+            (Some(prose), examples)
+        }
+        None => (None, vec![]),
+    };
+    let examples_json: Vec<serde_json::Value> = examples.iter().map(|e| json!({
+        "language": e.language,
+        "attributes": e.attributes,
+        "code": e.code,
+        "full_code": e.full_code,
+        "section": e.section,
+    })).collect();
+    let links_json: Vec<serde_json::Value> = links.iter().map(|l| json!({
+        "text": l.text,
+        "path": l.path,
+        "kind": l.kind,
+        "url": l.url,
+    })).collect();
+    [
+        ("docs", json!(prose)),
+        ("examples", json!(examples_json)),
+        ("panics", json!(caveats.panics)),
+        ("errors", json!(caveats.errors)),
+        ("safety", json!(caveats.safety)),
+        ("links", json!(links_json)),
+    ]
+}
+
+/// Neighbours listed under `related`.
+const MAX_RELATED: usize = 12;
+
+/// The module an item lives in, with its doc summary, and the module's other public
+/// items, ranked by [`rank_related`].
+fn related_items(doc: &RustdocJson, item_path: &[String], item_id: &str) -> serde_json::Value {
+    let Some((item_name, module_path)) = item_path.split_last() else { return serde_json::Value::Null };
+    let module_path = module_path.join("::");
+    let Some(module) = find_item_id(doc, &module_path)
+        .and_then(|id| doc.index.get(&id))
+        .filter(|m| m.kind() == Some("module"))
+    else {
+        return serde_json::Value::Null;
+    };
+
+    let ids = module.inner_for("module").and_then(|m| m.get("items")).and_then(|v| v.as_array());
+    let mut siblings: Vec<(String, String, String)> = vec![]; // (name, kind, doc_summary)
+    for sub in ids.into_iter().flatten().filter_map(id_to_string) {
+        if sub == item_id { continue; }
+        let Some(sub_item) = doc.index.get(&sub).filter(|i| i.is_visible()) else { continue };
+        // A `pub use` stands for the item it re-exports; globs have no single name
+        let (sibling_id, sibling) = match sub_item.inner_for("use") {
+            Some(u) => {
+                if u.get("is_glob").and_then(|g| g.as_bool()).unwrap_or(false) { continue; }
+                let target = u.get("id").and_then(id_to_string);
+                if target.as_deref() == Some(item_id) { continue; }
+                let name = u.get("name").and_then(|n| n.as_str()).unwrap_or_default().to_string();
+                (target, name)
+            }
+            None => (Some(sub.clone()), sub_item.name.clone().unwrap_or_default()),
+        };
+        if sibling.is_empty() || sibling == *item_name { continue; }
+        let target = sibling_id.as_deref().and_then(|id| doc.index.get(id));
+        let kind = sibling_id.as_deref().and_then(|id| doc.paths.get(id)).map(|p| p.kind_name())
+            .or_else(|| target.and_then(|t| t.kind()))
+            .unwrap_or("use");
+        if matches!(kind, "impl" | "use") { continue; }
+        let summary = target.map(|t| t.doc_summary()).unwrap_or_default();
+        siblings.push((sibling, kind.to_string(), summary));
+    }
+
+    rank_related(item_name, &mut siblings);
+    let total = siblings.len();
+    let items: Vec<serde_json::Value> = siblings.into_iter()
+        .take(MAX_RELATED)
+        .map(|(sibling, kind, summary)| json!({
+            "path": format!("{module_path}::{sibling}"),
+            "kind": kind,
+            "doc_summary": summary,
+        }))
+        .collect();
+    json!({
+        "module": module_path,
+        "module_summary": module.doc_summary(),
+        "items": items,
+        "more": total.saturating_sub(MAX_RELATED),
+    })
+}
+
+/// Sort `(name, kind, doc_summary)` neighbours of `item_name`: names sharing a word with it
+/// (`lock` → `try_lock`) or containing it (`Mutex` → `MutexGuard`) first, then by name.
+fn rank_related(item_name: &str, siblings: &mut Vec<(String, String, String)>) {
+    let words: Vec<&str> = item_name.split('_').filter(|w| !w.is_empty()).collect();
+    let shares_word = |name: &str| name.contains(item_name) || name.split('_').any(|w| words.contains(&w));
+    siblings.sort_by(|a, b| shares_word(&b.0).cmp(&shares_word(&a.0)).then_with(|| a.0.cmp(&b.0)));
+    siblings.dedup_by(|a, b| a.0 == b.0);
+}
+
+/// A method named by `Type::method` or `Trait::method`.
+struct MethodMatch<'a> {
+    parent_id: String,
+    parent: &'a crate::docsrs::Item,
+    method: &'a crate::docsrs::Item,
+    /// The impl block defining the method; `None` for a trait's own methods
+    impl_id: Option<String>,
+}
+
+/// Resolve `path` as a method: split off the last segment, resolve the rest as a type or
+/// trait, and find a function of that name among its inherent impls, then its trait impls.
+fn find_method<'a>(doc: &'a RustdocJson, path: &str) -> Option<MethodMatch<'a>> {
+    let (parent_path, method_name) = path.rsplit_once("::")?;
+    let parent_id = find_item_id(doc, parent_path)?;
+    let parent = doc.index.get(&parent_id)?;
+    let named = |ids: Option<&serde_json::Value>| {
+        ids.and_then(|v| v.as_array()).into_iter().flatten()
+            .filter_map(id_to_string)
+            .filter_map(|id| doc.index.get(&id))
+            .find(|m| m.kind() == Some("function") && m.name.as_deref() == Some(method_name))
+    };
+
+    if let Some(trait_inner) = parent.inner_for("trait") {
+        let method = named(trait_inner.get("items"))?;
+        return Some(MethodMatch { parent_id, parent, method, impl_id: None });
     }
+
+    // Inherent impls first: a trait method of the same name is shadowed by them
+    let mut impls: Vec<(bool, String, &serde_json::Value)> = get_impl_ids(parent).into_iter()
+        .filter_map(|impl_id| {
+            let impl_inner = doc.index.get(&impl_id)?.inner_for("impl")?;
+            if impl_inner.get("is_synthetic").and_then(|v| v.as_bool()).unwrap_or(false)
+                || impl_inner.get("blanket_impl").is_some_and(|b| !b.is_null())
+            {
+                return None;
+            }
+            let is_trait_impl = impl_inner.get("trait").is_some_and(|t| !t.is_null());
+            Some((is_trait_impl, impl_id, impl_inner))
+        })
+        .collect();
+    impls.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| id_order(&a.1).cmp(&id_order(&b.1))));
+    impls.into_iter().find_map(|(_, impl_id, impl_inner)| {
+        let method = named(impl_inner.get("items"))?;
+        Some(MethodMatch { parent_id: parent_id.clone(), parent, method, impl_id: Some(impl_id) })
+    })
+}
+
+/// The crate_item_get result for a method: its signature and docs, the impl block that
+/// defines it, and the other methods of that block (or trait) as `related`.
+fn describe_method(
+    doc: &RustdocJson,
+    found: &MethodMatch,
+    declared_features: &HashSet<String>,
+    name: &str,
+    version: &str,
+) -> serde_json::Value {
+    let parent_entry = doc.paths.get(&found.parent_id);
+    let parent_path = parent_entry.map(|p| p.full_path()).unwrap_or_default();
+    let method_name = found.method.name.as_deref().unwrap_or("_");
+    let impl_item = found.impl_id.as_deref().and_then(|id| doc.index.get(id));
+    let impl_inner = impl_item.and_then(|i| i.inner_for("impl"));
+
+    // A method inherits the cfg of its impl block
+    let mut attrs = found.method.attr_strings();
+    attrs.extend(impl_item.map(|i| i.attr_strings()).unwrap_or_default());
+    let requirements = cfg_requirements(&attrs, declared_features);
+
+    let impl_block = match (&found.impl_id, impl_item, impl_inner) {
+        (Some(impl_id), Some(impl_item), Some(impl_inner)) => json!({
+            "impl_id": impl_id,
+            "header": impl_header(impl_inner),
+            "generics": format_generics(impl_inner.get("generics")),
+            "cfg": cfg_requirements(&impl_item.attr_strings(), declared_features).cfg,
+        }),
+        _ => serde_json::Value::Null,
+    };
+
+    // The other methods of the same impl block, or of the trait
+    let sibling_ids = match impl_inner {
+        Some(inner) => inner.get("items"),
+        None => found.parent.inner_for("trait").and_then(|t| t.get("items")),
+    };
+    let mut siblings: Vec<(String, String, String)> = sibling_ids.and_then(|v| v.as_array()).into_iter().flatten()
+        .filter_map(id_to_string)
+        .filter_map(|id| doc.index.get(&id))
+        .filter(|m| m.kind() == Some("function") && m.is_visible())
+        .filter_map(|m| Some((m.name.clone()?, "method".to_string(), m.doc_summary())))
+        .filter(|(sibling, _, _)| sibling != method_name)
+        .collect();
+    rank_related(method_name, &mut siblings);
+    let total = siblings.len();
+    let related_methods: Vec<serde_json::Value> = siblings.into_iter()
+        .take(MAX_RELATED)
+        .map(|(sibling, kind, summary)| json!({
+            "path": format!("{parent_path}::{sibling}"),
+            "kind": kind,
+            "doc_summary": summary,
+        }))
+        .collect();
+
+    let mut output = json!({
+        "path": format!("{parent_path}::{method_name}"),
+        "kind": "method",
+        "parent": {
+            "path": parent_path,
+            "kind": parent_entry.map(|p| p.kind_name()),
+        },
+        "signature": function_signature(found.method),
+        "deprecated": found.method.deprecation.as_ref().map(|d| json!({
+            "since": d.since,
+            "note": d.note,
+        })),
+        "source_url": source_url(found.method, name, version),
+        "feature_requirements": requirements.features,
+        "target_requirements": requirements.targets,
+        "cfg": requirements.cfg,
+        "impl_block": impl_block,
+        "related": {
+            "parent": parent_path,
+            "parent_summary": found.parent.doc_summary(),
+            "items": related_methods,
+            "more": total.saturating_sub(MAX_RELATED),
+        },
+    });
+    for (key, value) in docs_fields(doc, found.method) {
+        output[key] = value;
+    }
+    output
+}
+
+/// Suggestions offered when an item path misses.
+const MAX_PATH_SUGGESTIONS: usize = 5;
+
+/// The crate's own item paths most like `wanted`, best first, as `(path, kind)`.
+///
+/// Ranked by how close the last segment is to the requested name (case-insensitive edit
+/// distance; a name containing the requested one counts as two edits), then by how many
+/// of the requested module segments the path shares, then by shortness.
+fn similar_paths(doc: &RustdocJson, wanted: &str) -> Vec<(String, String)> {
+    let wanted_parts: Vec<String> = wanted.split("::").map(str::to_lowercase).collect();
+    let Some((wanted_last, wanted_modules)) = wanted_parts.split_last() else { return vec![] };
+    let max_distance = (wanted_last.len() / 3).max(2);
+
+    let mut ranked: Vec<((usize, usize, usize), String, String)> = doc.paths.values()
+        .filter(|p| p.crate_id == 0)
+        .filter_map(|p| {
+            let last = p.path.last()?.to_lowercase();
+            let mut distance = edit_distance(wanted_last, &last);
+            if last.contains(wanted_last.as_str()) {
+                distance = distance.min(2);
+            }
+            if distance > max_distance {
+                return None;
+            }
+            let missing_modules = wanted_modules.iter()
+                .filter(|m| !p.path.iter().any(|s| s.eq_ignore_ascii_case(m)))
+                .count();
+            Some(((distance, missing_modules, p.path.len()), p.full_path(), p.kind.clone()))
+        })
+        .collect();
+    ranked.sort();
+    ranked.dedup_by(|a, b| a.1 == b.1);
+    ranked.into_iter()
+        .take(MAX_PATH_SUGGESTIONS)
+        .map(|(_, path, kind)| (path, kind))
+        .collect()
 }
 
 /// Get the impl block IDs for a struct/enum/union item.
@@ -202,38 +569,37 @@ fn get_impl_ids(item: &crate::docsrs::Item) -> Vec<String> {
     vec![]
 }
 
+/// Collect methods as `(methods, impl_blocks)`.
+///
+/// For traits, `methods` are the trait's required/provided methods and there are no blocks.
+/// For types, `methods` is the flat list of inherent methods sorted by name, with
+/// methods defined identically in several cfg'd impl blocks listed once, and
+/// `impl_blocks` groups method names under each inherent impl (header, generics, cfg).
 fn collect_methods(
     doc: &crate::docsrs::RustdocJson,
     item: &crate::docsrs::Item,
     declared_features: &HashSet<String>,
-) -> Vec<serde_json::Value> {
-    let mut methods = vec![];
-
+    name: &str,
+    version: &str,
+) -> (Vec<serde_json::Value>, Vec<serde_json::Value>) {
     // For traits: collect required/provided methods from inner.trait.items directly
     if let Some(trait_inner) = item.inner_for("trait") {
         let trait_items = trait_inner.get("items")
             .and_then(|v| v.as_array())
             .cloned()
             .unwrap_or_default();
-        for method_id_val in &trait_items {
-            let Some(method_id) = id_to_string(method_id_val) else { continue };
-            let Some(method_item) = doc.index.get(&method_id) else { continue };
-            if method_item.kind().unwrap_or("") != "function" { continue; }
-            let sig = function_signature(method_item);
-            let doc_summary = method_item.doc_summary();
-            let feature_reqs = extract_feature_requirements(&method_item.attr_strings(), declared_features);
-            methods.push(json!({
-                "name": method_item.name,
-                "signature": sig,
-                "doc_summary": doc_summary,
-                "feature_requirements": feature_reqs,
-                "deprecated": method_item.deprecation.as_ref().map(|d| &d.note),
-            }));
-        }
-        return methods;
+        let mut methods: Vec<serde_json::Value> = trait_items.iter()
+            .filter_map(id_to_string)
+            .filter_map(|id| doc.index.get(&id))
+            .filter(|m| m.kind() == Some("function"))
+            .map(|m| method_json(m, None, declared_features, name, version))
+            .collect();
+        sort_by_name(&mut methods);
+        return (methods, vec![]);
     }
 
     // For structs/enums/unions: use inherent impl blocks from inner.{kind}.impls
+    let mut blocks = vec![];
     for impl_id in get_impl_ids(item) {
         let Some(impl_item) = doc.index.get(&impl_id) else { continue };
         let Some(impl_inner) = impl_item.inner_for("impl") else { continue };
@@ -241,29 +607,243 @@ fn collect_methods(
         if !impl_inner.get("trait").map(|t| t.is_null()).unwrap_or(true) {
             continue;
         }
-        let impl_items = impl_inner.get("items")
+        let mut block_methods: Vec<serde_json::Value> = impl_inner.get("items")
             .and_then(|v| v.as_array())
-            .cloned()
-            .unwrap_or_default();
-        for method_id_val in &impl_items {
-            let Some(method_id) = id_to_string(method_id_val) else { continue };
-            let Some(method_item) = doc.index.get(&method_id) else { continue };
-            if method_item.kind().unwrap_or("") != "function" {
-                continue;
+            .into_iter()
+            .flatten()
+            .filter_map(id_to_string)
+            .filter_map(|id| doc.index.get(&id))
+            .filter(|m| m.kind() == Some("function"))
+            .map(|m| method_json(m, Some(impl_id.as_str()), declared_features, name, version))
+            .collect();
+        if block_methods.is_empty() { continue; }
+        sort_by_name(&mut block_methods);
+        let cfg = cfg_requirements(&impl_item.attr_strings(), declared_features).cfg;
+        blocks.push((impl_header(impl_inner), impl_id, format_generics(impl_inner.get("generics")), cfg, block_methods));
+    }
+    // Deterministic block order: by header, then numerically by impl ID
+    blocks.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| id_order(&a.1).cmp(&id_order(&b.1))));
+
+    let mut methods: Vec<serde_json::Value> = vec![];
+    let mut impl_blocks = vec![];
+    for (header, impl_id, generics, cfg, block_methods) in blocks {
+        let names: Vec<&serde_json::Value> = block_methods.iter().map(|m| &m["name"]).collect();
+        impl_blocks.push(json!({
+            "impl_id": impl_id,
+            "header": header,
+            "generics": generics,
+            "cfg": cfg,
+            "methods": names,
+        }));
+        for method in block_methods {
+            // The same method under mutually exclusive cfg'd impls is listed once
+            let duplicate = methods.iter().any(|m| m["name"] == method["name"] && m["signature"] == method["signature"]);
+            if !duplicate {
+                methods.push(method);
+            }
+        }
+    }
+    sort_by_name(&mut methods);
+    (methods, impl_blocks)
+}
+
+fn method_json(
+    method_item: &crate::docsrs::Item,
+    impl_id: Option<&str>,
+    declared_features: &HashSet<String>,
+    name: &str,
+    version: &str,
+) -> serde_json::Value {
+    let reqs = cfg_requirements(&method_item.attr_strings(), declared_features);
+    let mut method = json!({
+        "name": method_item.name,
+        "signature": function_signature(method_item),
+        "doc_summary": method_item.doc_summary(),
+        "feature_requirements": reqs.features,
+        "target_requirements": reqs.targets,
+        "deprecated": method_item.deprecation.as_ref().map(|d| &d.note),
+        "source_url": source_url(method_item, name, version),
+    });
+    if let Some(impl_id) = impl_id {
+        method["impl_id"] = json!(impl_id);
+    }
+    // Only the caveats a method documents, to keep long method lists short
+    let caveats = method_item.docs.as_deref().map(doc_caveats).unwrap_or_default();
+    for (key, text) in [("panics", caveats.panics), ("errors", caveats.errors), ("safety", caveats.safety)] {
+        if let Some(text) = text {
+            method[key] = json!(text);
+        }
+    }
+    method
+}
+
+/// Stable sort of method JSON objects by `name`.
+fn sort_by_name(methods: &mut [serde_json::Value]) {
+    methods.sort_by(|a, b| a["name"].as_str().unwrap_or("").cmp(b["name"].as_str().unwrap_or("")));
+}
+
+/// Numeric ordering key for item IDs (falls back to the end for non-numeric IDs).
+fn id_order(id: &str) -> u64 {
+    id.parse().unwrap_or(u64::MAX)
+}
+
+/// docs.rs source URL for an item, anchored at its first line.
+fn source_url(item: &crate::docsrs::Item, name: &str, version: &str) -> Option<String> {
+    item.span.as_ref().and_then(|s| source_link(s, name, version)).map(|l| l.url)
+}
+
+/// Traits whose impls construct the implementing type, with how to call them.
+const CONSTRUCTOR_TRAITS: &[(&str, &str)] = &[
+    ("Default", "::default()"),
+    ("From", "::from(value)"),
+    ("TryFrom", "::try_from(value)"),
+    ("FromStr", "::from_str(s)"),
+];
+
+/// Ways to construct a type as `{"functions": [...], "trait_impls": [...]}`.
+///
+/// `functions` are inherent associated functions (no `self`) returning the type, directly
+/// or wrapped in `Result`/`Option`/a smart pointer. `trait_impls` are the type's own
+/// `Default`, `From`, `TryFrom` and `FromStr` impls; blanket impls such as `From<T> for T`
+/// are left out.
+fn collect_constructors(
+    doc: &crate::docsrs::RustdocJson,
+    item: &crate::docsrs::Item,
+    type_name: &str,
+    declared_features: &HashSet<String>,
+    name: &str,
+    version: &str,
+) -> serde_json::Value {
+    let mut functions: Vec<serde_json::Value> = vec![];
+    let mut trait_impls: Vec<serde_json::Value> = vec![];
+    for impl_id in get_impl_ids(item) {
+        let Some(impl_inner) = doc.index.get(&impl_id).and_then(|i| i.inner_for("impl")) else { continue };
+        if impl_inner.get("is_synthetic").and_then(|v| v.as_bool()).unwrap_or(false) { continue; }
+        if impl_inner.get("blanket_impl").is_some_and(|b| !b.is_null()) { continue; }
+
+        match impl_inner.get("trait").filter(|t| !t.is_null()) {
+            None => {
+                let fns = impl_inner.get("items")
+                    .and_then(|v| v.as_array())
+                    .into_iter()
+                    .flatten()
+                    .filter_map(id_to_string)
+                    .filter_map(|id| doc.index.get(&id));
+                for f in fns {
+                    let Some(sig) = f.inner_for("function").and_then(|i| i.get("sig")) else { continue };
+                    let takes_self = sig.get("inputs")
+                        .and_then(|v| v.as_array())
+                        .and_then(|inputs| inputs.first())
+                        .and_then(|first| first.get(0))
+                        .and_then(|n| n.as_str()) == Some("self");
+                    if takes_self { continue; }
+                    let output = sig.get("output").filter(|o| !o.is_null()).map(type_to_string).unwrap_or_default();
+                    let Some(fallible) = constructs(&output, type_name) else { continue };
+                    let mut entry = method_json(f, Some(impl_id.as_str()), declared_features, name, version);
+                    entry["fallible"] = json!(fallible);
+                    functions.push(entry);
+                }
+            }
+            Some(trait_) => {
+                let trait_path = type_to_string(trait_);
+                let base = trait_path.split('<').next().unwrap_or(&trait_path);
+                let base = base.rsplit("::").next().unwrap_or(base);
+                let Some((_, call)) = CONSTRUCTOR_TRAITS.iter().find(|(t, _)| *t == base) else { continue };
+                trait_impls.push(json!({
+                    "trait_path": trait_path,
+                    "impl_id": impl_id,
+                    "call": format!("{type_name}{call}"),
+                }));
             }
-            let sig = function_signature(method_item);
-            let doc_summary = method_item.doc_summary();
-            let feature_reqs = extract_feature_requirements(&method_item.attr_strings(), declared_features);
-            methods.push(json!({
-                "name": method_item.name,
-                "signature": sig,
-                "doc_summary": doc_summary,
-                "feature_requirements": feature_reqs,
-                "deprecated": method_item.deprecation.as_ref().map(|d| &d.note),
+        }
+    }
+    sort_by_name(&mut functions);
+    functions.dedup_by(|a, b| a["name"] == b["name"] && a["signature"] == b["signature"]);
+    trait_impls.sort_by(|a, b| a["trait_path"].as_str().cmp(&b["trait_path"].as_str()));
+    json!({ "functions": functions, "trait_impls": trait_impls })
+}
+
+/// Whether a function returning `output` constructs `type_name`.
+///
+/// Returns `Some(fallible)`: `Some(false)` for `Self`/`Type<..>` (also behind `Box`, `Arc`,
+/// `Rc` or `Pin`), `Some(true)` when wrapped in `Result` or `Option`, `None` otherwise.
+fn constructs(output: &str, type_name: &str) -> Option<bool> {
+    let output = output.trim();
+    let Some((wrapper, rest)) = output.split_once('<') else {
+        return (output == "Self" || output == type_name).then_some(false);
+    };
+    let wrapper = wrapper.rsplit("::").next().unwrap_or(wrapper);
+    if wrapper == type_name {
+        return Some(false);
+    }
+    // First generic argument, up to a top-level comma or the closing bracket
+    let mut depth = 0;
+    let end = rest.char_indices()
+        .find(|&(_, c)| match c {
+            '<' | '(' | '[' => { depth += 1; false }
+            '>' | ')' | ']' if depth > 0 => { depth -= 1; false }
+            '>' | ',' => depth == 0,
+            _ => false,
+        })
+        .map(|(i, _)| i)
+        .unwrap_or(rest.len());
+    let first_arg = &rest[..end];
+    match wrapper {
+        "Result" | "Option" => constructs(first_arg, type_name).map(|_| true),
+        "Box" | "Arc" | "Rc" | "Pin" => constructs(first_arg, type_name),
+        _ => None,
+    }
+}
+
+/// Collect a trait's associated types and consts from `inner.trait.items`.
+///
+/// Returns `(associated_types, associated_consts)`. Each entry carries its bounds or type,
+/// any default, and a rendered declaration such as `type Item: Clone = u8;`.
+fn collect_assoc_items(
+    doc: &crate::docsrs::RustdocJson,
+    item: &crate::docsrs::Item,
+) -> (Vec<serde_json::Value>, Vec<serde_json::Value>) {
+    let mut types = vec![];
+    let mut consts = vec![];
+    let Some(trait_inner) = item.inner_for("trait") else { return (types, consts) };
+    let trait_items = trait_inner.get("items")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+    for id_val in &trait_items {
+        let Some(id) = id_to_string(id_val) else { continue };
+        let Some(assoc) = doc.index.get(&id) else { continue };
+        let assoc_name = assoc.name.as_deref().unwrap_or("_");
+        if let Some(at) = assoc.inner_for("assoc_type") {
+            let bounds = at.get("bounds")
+                .and_then(|v| v.as_array())
+                .map(|bs| bounds_to_string(bs))
+                .filter(|s| !s.is_empty());
+            let default = at.get("type")
+                .filter(|v| !v.is_null())
+                .map(type_to_string);
+            let mut decl = format!("type {assoc_name}");
+            if let Some(b) = &bounds { decl.push_str(&format!(": {b}")); }
+            if let Some(d) = &default { decl.push_str(&format!(" = {d}")); }
+            decl.push(';');
+            types.push(json!({
+                "name": assoc_name,
+                "bounds": bounds,
+                "default": default,
+                "declaration": decl,
+                "doc_summary": assoc.doc_summary(),
+            }));
+        } else if let Some(c) = const_info(assoc) {
+            consts.push(json!({
+                "name": assoc_name,
+                "declaration": c.declaration(assoc_name),
+                "type": c.ty,
+                "default": c.value,
+                "doc_summary": assoc.doc_summary(),
             }));
         }
     }
-    methods
+    (types, consts)
 }
 
 /// Trait names that are ubiquitous blanket impls present on virtually every type.
@@ -312,6 +892,17 @@ fn collect_trait_impls(
     item: &crate::docsrs::Item,
     filter_ubiquitous: bool,
 ) -> Vec<serde_json::Value> {
+    trait_impl_ids(doc, item, filter_ubiquitous).into_iter()
+        .map(|(_, trait_path)| json!({ "trait_path": trait_path }))
+        .collect()
+}
+
+/// `(impl_id, trait_path)` of each trait impl listed for `item`.
+fn trait_impl_ids(
+    doc: &crate::docsrs::RustdocJson,
+    item: &crate::docsrs::Item,
+    filter_ubiquitous: bool,
+) -> Vec<(String, String)> {
     let mut impls = vec![];
     for impl_id in get_impl_ids(item) {
         let Some(impl_item) = doc.index.get(&impl_id) else { continue };
@@ -329,11 +920,71 @@ fn collect_trait_impls(
         if filter_ubiquitous && is_ubiquitous_blanket(&trait_path) {
             continue;
         }
-        impls.push(json!({ "trait_path": trait_path }));
+        impls.push((impl_id, trait_path));
     }
     impls
 }
 
+/// A trait impl entry with what the impl gives the type: the methods it defines (with
+/// signatures), its associated types, and the trait's provided methods it inherits.
+///
+/// Blanket impls (`impl<T: Display> ToString for T`) apply to far more than this type,
+/// so they are marked as such and left unexpanded.
+fn expand_trait_impl(
+    doc: &crate::docsrs::RustdocJson,
+    impl_id: &str,
+    trait_path: String,
+    declared_features: &HashSet<String>,
+    name: &str,
+    version: &str,
+) -> serde_json::Value {
+    let mut entry = json!({ "trait_path": trait_path });
+    let Some(impl_inner) = doc.index.get(impl_id).and_then(|i| i.inner_for("impl")) else { return entry };
+    entry["impl_id"] = json!(impl_id);
+    entry["header"] = json!(impl_header(impl_inner));
+    if impl_inner.get("blanket_impl").is_some_and(|b| !b.is_null()) {
+        entry["blanket"] = json!(true);
+        return entry;
+    }
+
+    let members: Vec<&crate::docsrs::Item> = impl_inner.get("items")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(id_to_string)
+        .filter_map(|id| doc.index.get(&id))
+        .collect();
+    let mut methods: Vec<serde_json::Value> = members.iter()
+        .filter(|m| m.kind() == Some("function"))
+        .map(|m| method_json(m, None, declared_features, name, version))
+        .collect();
+    sort_by_name(&mut methods);
+    let associated_types: Vec<serde_json::Value> = members.iter()
+        .filter_map(|m| {
+            let ty = m.inner_for("assoc_type")?.get("type").filter(|t| !t.is_null()).map(type_to_string)?;
+            let assoc_name = m.name.as_deref().unwrap_or("_");
+            Some(json!({
+                "name": assoc_name,
+                "type": ty,
+                "declaration": format!("type {assoc_name} = {ty};"),
+            }))
+        })
+        .collect();
+    let mut provided: Vec<&str> = impl_inner.get("provided_trait_methods")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|m| m.as_str())
+        .filter(|p| !methods.iter().any(|m| m["name"] == *p))
+        .collect();
+    provided.sort_unstable();
+
+    entry["associated_types"] = json!(associated_types);
+    entry["methods"] = json!(methods);
+    entry["provided_methods"] = json!(provided);
+    entry
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -351,6 +1002,32 @@ mod tests {
     // inherent impl 12027: items=[12015(new),12018(builder),12020(id),12021(graceful_shutdown),12022(into_inner),12024(split)]
     // trait impls include: Send(12028), Sync(12029)
 
+    #[test]
+    fn similar_paths_ranks_typos_and_moved_items() {
+        let doc = load_rmcp();
+        let similar = similar_paths(&doc, "rmcp::ServerHandlr");
+        assert_eq!(similar[0], ("rmcp::handler::server::ServerHandler".to_string(), "trait".to_string()));
+
+        let similar = similar_paths(&doc, "rmcp::handler::ServiceExt");
+        assert_eq!(similar[0].0, "rmcp::service::ServiceExt");
+        assert!(similar.len() <= MAX_PATH_SUGGESTIONS);
+        assert!(similar_paths(&doc, "rmcp::Zzzzqqqq").is_empty());
+    }
+
+    #[test]
+    fn related_items_lists_module_neighbours() {
+        let doc = load_rmcp();
+        let path = &doc.paths["9410"].path;
+        let related = related_items(&doc, path, "9410");
+        assert_eq!(related["module"], "rmcp::transport::child_process");
+        let paths: Vec<&str> = related["items"].as_array().unwrap().iter()
+            .filter_map(|i| i["path"].as_str())
+            .collect();
+        assert!(!paths.is_empty(), "child_process has other public items");
+        assert!(!paths.contains(&"rmcp::transport::child_process::TokioChildProcess"), "the item itself is not related");
+        assert!(related_items(&doc, &["rmcp".to_string()], "0").is_null(), "the crate root has no module");
+    }
+
     #[test]
     fn get_impl_ids_returns_all_impls_for_tokiochildprocess() {
         let doc = load_rmcp();
@@ -374,7 +1051,7 @@ mod tests {
         let doc = load_rmcp();
         let item = doc.index.get("9410").expect("TokioChildProcess must exist");
         let features = HashSet::new();
-        let methods = collect_methods(&doc, item, &features);
+        let (methods, _) = collect_methods(&doc, item, &features, "rmcp", "0.16.0");
         assert_eq!(methods.len(), 6, "TokioChildProcess should have 6 inherent methods, got {}", methods.len());
 
         let names: Vec<&str> = methods.iter()
@@ -393,7 +1070,7 @@ mod tests {
         let doc = load_rmcp();
         let item = doc.index.get("9410").expect("TokioChildProcess must exist");
         let features = HashSet::new();
-        let methods = collect_methods(&doc, item, &features);
+        let (methods, _) = collect_methods(&doc, item, &features, "rmcp", "0.16.0");
         for method in &methods {
             let name = method.get("name").and_then(|v| v.as_str()).unwrap_or("");
             assert!(!name.is_empty(), "method name should not be empty");
@@ -445,7 +1122,7 @@ mod tests {
         let item = doc.index.get("9410").expect("TokioChildProcess must exist");
         // inherent impls have no trait; collect_trait_impls must not include them
         let trait_impls = collect_trait_impls(&doc, item, true);
-        let methods = collect_methods(&doc, item, &HashSet::new());
+        let (methods, _) = collect_methods(&doc, item, &HashSet::new(), "rmcp", "0.16.0");
         // The 6 inherent methods should NOT appear in trait_impls
         let trait_names: Vec<&str> = trait_impls.iter()
             .filter_map(|t| t.get("trait_path").and_then(|v| v.as_str()))
@@ -455,19 +1132,88 @@ mod tests {
     }
 
     #[test]
-    fn id_to_string_handles_integer() {
-        let v = serde_json::json!(42);
-        assert_eq!(id_to_string(&v), Some("42".to_string()));
+    fn constructs_recognizes_wrapped_self() {
+        assert_eq!(constructs("Self", "Client"), Some(false));
+        assert_eq!(constructs("Client<T>", "Client"), Some(false));
+        assert_eq!(constructs("Arc<Self>", "Client"), Some(false));
+        assert_eq!(constructs("Result<Self, Error>", "Client"), Some(true));
+        assert_eq!(constructs("io::Result<Client>", "Client"), Some(true));
+        assert_eq!(constructs("Option<Box<Self>>", "Client"), Some(true));
+        assert_eq!(constructs("Result<(Self, Receiver<u8>), Error>", "Client"), None);
+        assert_eq!(constructs("ClientBuilder", "Client"), None);
+    }
+
+    #[test]
+    fn collect_constructors_finds_new_on_tokiochildprocess() {
+        let doc = load_rmcp();
+        let item = doc.index.get("9410").expect("TokioChildProcess must exist");
+        let ctors = collect_constructors(&doc, item, "TokioChildProcess", &HashSet::new(), "rmcp", "0.16.0");
+        let names: Vec<&str> = ctors["functions"].as_array().unwrap().iter()
+            .filter_map(|f| f["name"].as_str())
+            .collect();
+        assert!(names.contains(&"new"), "got: {names:?}");
+        assert!(!names.contains(&"id"), "methods taking self are not constructors: {names:?}");
     }
 
     #[test]
-    fn id_to_string_handles_string() {
-        let v = serde_json::json!("hello");
-        assert_eq!(id_to_string(&v), Some("hello".to_string()));
+    fn collect_assoc_items_renders_types_and_consts() {
+        let doc: RustdocJson = serde_json::from_value(serde_json::json!({
+            "format_version": 57,
+            "root": 0,
+            "index": {
+                "1": {"id": 1, "name": "Codec", "inner": {"trait": {"items": [2, 3, 4], "generics": {"params": [], "where_predicates": []}}}},
+                "2": {"id": 2, "name": "Item", "inner": {"assoc_type": {
+                    "generics": {"params": [], "where_predicates": []},
+                    "bounds": [{"trait_bound": {"trait": {"id": 9, "path": "Clone", "args": null}}}],
+                    "type": null
+                }}},
+                "3": {"id": 3, "name": "MAX", "inner": {"assoc_const": {"type": {"primitive": "usize"}, "value": "16"}}},
+                "4": {"id": 4, "name": "encode", "inner": {"function": {"sig": {"inputs": [], "output": null}}}}
+            },
+            "paths": {}
+        })).unwrap();
+        let item = doc.index.get("1").unwrap();
+        let (types, consts) = collect_assoc_items(&doc, item);
+        assert_eq!(types.len(), 1);
+        assert_eq!(types[0]["declaration"], "type Item: Clone;");
+        assert_eq!(consts.len(), 1);
+        assert_eq!(consts[0]["declaration"], "const MAX: usize = 16;");
     }
 
     #[test]
-    fn id_to_string_rejects_null() {
-        assert_eq!(id_to_string(&serde_json::Value::Null), None);
+    fn collect_methods_groups_by_impl_sorts_and_dedups() {
+        let unix = r#"#[attr = CfgTrace([NameValue { name: "unix", value: None, span: None }])]"#;
+        let windows = r#"#[attr = CfgTrace([NameValue { name: "windows", value: None, span: None }])]"#;
+        let doc: RustdocJson = serde_json::from_value(serde_json::json!({
+            "format_version": 57,
+            "root": 0,
+            "index": {
+                "1": {"id": 1, "name": "Pipe", "inner": {"struct": {"kind": {"unit": null}, "impls": [20, 10]}}},
+                "10": {"id": 10, "attrs": [{"other": unix}], "inner": {"impl": {
+                    "trait": null, "for": {"resolved_path": {"id": 1, "path": "Pipe", "args": null}},
+                    "generics": {"params": [], "where_predicates": []}, "items": [11, 12]
+                }}},
+                "11": {"id": 11, "name": "write", "inner": {"function": {"sig": {"inputs": [], "output": null}}}},
+                "12": {"id": 12, "name": "open", "inner": {"function": {"sig": {"inputs": [], "output": null}}}},
+                "20": {"id": 20, "attrs": [{"other": windows}], "inner": {"impl": {
+                    "trait": null, "for": {"resolved_path": {"id": 1, "path": "Pipe", "args": null}},
+                    "generics": {"params": [], "where_predicates": []}, "items": [21]
+                }}},
+                "21": {"id": 21, "name": "open", "inner": {"function": {"sig": {"inputs": [], "output": null}}}}
+            },
+            "paths": {}
+        })).unwrap();
+        let item = doc.index.get("1").unwrap();
+        let (methods, blocks) = collect_methods(&doc, item, &HashSet::new(), "demo", "1.0.0");
+
+        let names: Vec<&str> = methods.iter().filter_map(|m| m["name"].as_str()).collect();
+        assert_eq!(names, vec!["open", "write"], "sorted, with the cfg'd duplicate listed once");
+
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0]["impl_id"], "10", "equal headers fall back to numeric ID order");
+        assert_eq!(blocks[0]["cfg"], "unix");
+        assert_eq!(blocks[0]["methods"], serde_json::json!(["open", "write"]));
+        assert_eq!(blocks[1]["cfg"], "windows");
+        assert_eq!(blocks[1]["methods"], serde_json::json!(["open"]));
     }
 }
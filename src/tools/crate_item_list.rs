@@ -5,8 +5,9 @@ use serde::Deserialize;
 use rmcp::schemars::{self, JsonSchema};
 use serde_json::json;
 
-use super::AppState;
-use crate::docsrs::{fetch_rustdoc_json, search_items};
+use super::{AppState, docs_not_found_error};
+use crate::docsrs::{SearchResult, search_items};
+use crate::error::ErrorCode;
 use crate::sparse_index::find_latest_stable;
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -23,46 +24,67 @@ pub struct CrateItemListParams {
     pub module_prefix: Option<String>,
     /// Max results (default: 10, max: 50)
     pub limit: Option<usize>,
+    /// Include non-public and #[doc(hidden)] items, e.g. to debug a crate's internals (default: false)
+    pub include_hidden: Option<bool>,
+    /// Target triple whose docs.rs build to read (e.g. "x86_64-pc-windows-msvc",
+    /// "wasm32-unknown-unknown"), for platform-specific APIs. Default: the crate's default target.
+    pub target: Option<String>,
+    /// Rank items by how close their docs are in meaning to the query, for conceptual queries
+    /// such as "rate limiting with a token bucket" (default: false). Needs a server built with
+    /// the `semantic-search` feature; the first such query on a crate version embeds its docs.
+    pub semantic: Option<bool>,
 }
 
 pub async fn execute(state: &AppState, params: CrateItemListParams) -> Result<CallToolResult, ErrorData> {
     let name = &params.name;
     let version = state.resolve_version(name, params.version.as_deref()).await
-        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+        .map_err(ErrorData::from)?;
     let limit = params.limit.unwrap_or(10).min(50);
+    let semantic = params.semantic.unwrap_or(false);
+    if semantic && !cfg!(feature = "semantic-search") {
+        return Err(ErrorData::invalid_params(
+            "This server was built without the semantic-search feature; search by name instead, or rebuild with `--features semantic-search`.",
+            ErrorCode::InvalidParams.data(),
+        ));
+    }
 
+    let target = super::validate_target(params.target.as_deref())?;
     let (docs_result, index_result) = tokio::join!(
-        fetch_rustdoc_json(name, &version, &state.client, &state.cache),
+        state.rustdoc_for_target(name, &version, target),
         state.fetch_index(name)
     );
 
     let doc = match docs_result {
         Ok(d) => d,
         Err(crate::error::DocsError::DocsNotFound { .. }) => {
-            // Suggest the user try an earlier version that may have a build.
-            return Err(ErrorData::invalid_params(
-                format!("No docs.rs build found for {name} {version}. \
-                         The latest version may not have been built yet. \
-                         Try specifying an older version with the 'version' parameter, \
-                         or use crate_docs_get (which falls back to README)."),
-                None,
-            ));
+            if let Some(target) = target {
+                return Err(super::target_not_built_error(name, &version, target));
+            }
+            return Err(docs_not_found_error(state, name, &version).await);
         }
-        Err(e) => return Err(ErrorData::internal_error(e.to_string(), None)),
+        Err(e) => return Err(ErrorData::from(e)),
     };
     let index_lines = index_result.unwrap_or_default();
     let latest = find_latest_stable(&index_lines);
     let features = latest.map(|l| l.all_features()).unwrap_or_default();
     let declared_features: HashSet<String> = features.keys().cloned().collect();
 
-    let results = search_items(
-        &doc,
-        &params.query,
-        params.kind.as_deref(),
-        params.module_prefix.as_deref(),
-        limit,
-        &declared_features,
-    );
+    let (results, embedder): (Vec<SearchResult>, Option<String>) = match semantic {
+        #[cfg(feature = "semantic-search")]
+        true => {
+            let (results, embedder) = semantic_search(state, &doc, &params, &version, limit, &declared_features).await?;
+            (results, Some(embedder))
+        }
+        _ => (search_items(
+            &doc,
+            &params.query,
+            params.kind.as_deref(),
+            params.module_prefix.as_deref(),
+            limit,
+            &declared_features,
+            params.include_hidden.unwrap_or(false),
+        ), None),
+    };
 
     let items: Vec<serde_json::Value> = results.iter().map(|r| {
         json!({
@@ -71,20 +93,61 @@ pub async fn execute(state: &AppState, params: CrateItemListParams) -> Result<Ca
             "signature": r.signature,
             "doc_summary": r.doc_summary,
             "feature_requirements": r.feature_requirements,
+            "target_requirements": r.target_requirements,
             "score": r.score,
         })
     }).collect();
 
-    let output = json!({
+    let mut output = json!({
         "name": name,
         "version": version,
         "query": params.query,
         "count": items.len(),
         "items": items,
     });
+    if let Some(embedder) = embedder {
+        output["embedder"] = json!(embedder);
+    }
+    let warnings = doc.warnings();
+    if !warnings.is_empty() {
+        output["warnings"] = json!(warnings);
+    }
 
     let json = serde_json::to_string_pretty(&output)
         .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
 
     Ok(CallToolResult::success(vec![Content::text(json)]))
 }
+
+/// Results ranked by embedding similarity, and the ID of the embedder that ranked them.
+#[cfg(feature = "semantic-search")]
+async fn semantic_search(
+    state: &AppState,
+    doc: &crate::docsrs::RustdocJson,
+    params: &CrateItemListParams,
+    version: &str,
+    limit: usize,
+    declared_features: &HashSet<String>,
+) -> Result<(Vec<SearchResult>, String), ErrorData> {
+    use crate::semantic::{self, SemanticIndex};
+
+    let embedder = semantic::from_env(&state.client);
+    let key = match &params.target {
+        Some(target) => format!("{}@{version}-{target}", params.name),
+        None => format!("{}@{version}", params.name),
+    };
+    let index = SemanticIndex::load_or_build(state.cache.dir(), &key, doc, embedder.as_ref()).await
+        .map_err(ErrorData::from)?;
+    let results = semantic::search(
+        doc,
+        &index,
+        embedder.as_ref(),
+        &params.query,
+        params.kind.as_deref(),
+        params.module_prefix.as_deref(),
+        limit,
+        declared_features,
+        params.include_hidden.unwrap_or(false),
+    ).await.map_err(ErrorData::from)?;
+    Ok((results, embedder.id()))
+}
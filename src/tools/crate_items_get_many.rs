@@ -0,0 +1,88 @@
+use rmcp::{ErrorData, model::{CallToolResult, Content}};
+use serde::Deserialize;
+use rmcp::schemars::{self, JsonSchema};
+use serde_json::json;
+
+use super::AppState;
+use super::crate_item_get::{self, CrateItemGetParams};
+use crate::error::ErrorCode;
+
+/// Paths accepted in one call; each full item can run to thousands of lines.
+const MAX_ITEMS: usize = 20;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CrateItemsGetManyParams {
+    /// Crate name
+    pub name: String,
+    /// Version string. Defaults to latest stable.
+    pub version: Option<String>,
+    /// Fully-qualified item or method paths (e.g. ["tokio::sync::Mutex", "tokio::sync::Mutex::lock"]), at most 20
+    pub item_paths: Vec<String>,
+    /// Include inherent methods from impl blocks (default: true)
+    pub include_methods: Option<bool>,
+    /// Trait impl filtering mode: "filtered" (default), "all" or "none", as in crate_item_get
+    pub include_trait_impls: Option<String>,
+    /// Target triple whose docs.rs build to read. Default: the crate's default target.
+    pub target: Option<String>,
+}
+
+pub async fn execute(state: &AppState, params: CrateItemsGetManyParams) -> Result<CallToolResult, ErrorData> {
+    let name = &params.name;
+    let mut paths: Vec<&str> = vec![];
+    for path in params.item_paths.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    if paths.is_empty() || paths.len() > MAX_ITEMS {
+        return Err(ErrorData::invalid_params(
+            format!("Pass between 1 and {MAX_ITEMS} item_paths (got {}).", paths.len()),
+            ErrorCode::InvalidParams.data(),
+        ));
+    }
+
+    let version = state.resolve_version(name, params.version.as_deref()).await
+        .map_err(ErrorData::from)?;
+    let target = super::validate_target(params.target.as_deref())?;
+    // One load for the whole batch; a path that misses fails alone
+    let (doc, declared_features) = crate_item_get::load(state, name, &version, target).await?;
+
+    let mut found = 0;
+    let items: Vec<serde_json::Value> = paths.iter()
+        .map(|path| {
+            let lookup = CrateItemGetParams {
+                name: name.clone(),
+                version: Some(version.clone()),
+                item_path: path.to_string(),
+                include_methods: params.include_methods,
+                include_trait_impls: params.include_trait_impls.clone(),
+                include_trait_impl_methods: None,
+                impl_for: None,
+                target: params.target.clone(),
+            };
+            match crate_item_get::describe_item(&doc, &lookup, &version, &declared_features) {
+                Ok(mut item) => {
+                    found += 1;
+                    item["requested"] = json!(path);
+                    item
+                }
+                Err(e) => {
+                    let suggestions = e.data.as_ref().and_then(|d| d.get("suggestions")).cloned();
+                    json!({"requested": path, "error": e.message, "suggestions": suggestions})
+                }
+            }
+        })
+        .collect();
+
+    let output = json!({
+        "name": name,
+        "version": version,
+        "found": found,
+        "missing": items.len() - found,
+        "items": items,
+    });
+
+    let json = serde_json::to_string_pretty(&output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
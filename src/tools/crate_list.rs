@@ -3,8 +3,14 @@ use rmcp::model::Content;
 use serde::{Deserialize, Serialize};
 use rmcp::schemars::{self, JsonSchema};
 
+use futures::{StreamExt, stream};
+
 use crate::cratesio::CrateInfo;
 use super::AppState;
+use super::crate_versions_list::parse_rust_version;
+
+/// Crates whose filter metadata is looked up at once.
+const MAX_CONCURRENT_LOOKUPS: usize = 8;
 
 #[derive(Serialize)]
 struct CrateListEntry<'a> {
@@ -16,6 +22,32 @@ struct CrateListEntry<'a> {
     recent_downloads: Option<u64>,
     updated_at: &'a str,
     repository: Option<&'a str>,
+    /// SPDX license expression of `version`; looked up only when filtering by license or edition
+    #[serde(skip_serializing_if = "Option::is_none")]
+    license: Option<String>,
+    /// MSRV of `version`; looked up only when filtering
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rust_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    edition: Option<String>,
+    /// Near-duplicate crates from this page collapsed into this entry
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    forks: Vec<&'a str>,
+    /// The search record this entry summarizes, for `detail: "full"`
+    #[serde(skip)]
+    info: &'a CrateInfo,
+}
+
+impl CrateListEntry<'_> {
+    /// The complete crates.io record with this entry's fields (resolved version, filter
+    /// metadata, forks) layered on top.
+    fn to_full(&self) -> serde_json::Value {
+        let mut full = serde_json::to_value(self.info).unwrap_or_default();
+        if let (Some(full), serde_json::Value::Object(summary)) = (full.as_object_mut(), serde_json::json!(self)) {
+            full.extend(summary);
+        }
+        full
+    }
 }
 
 impl<'a> From<&'a CrateInfo> for CrateListEntry<'a> {
@@ -29,10 +61,22 @@ impl<'a> From<&'a CrateInfo> for CrateListEntry<'a> {
             recent_downloads: c.recent_downloads,
             updated_at: &c.updated_at,
             repository: c.repository.as_deref(),
+            license: None,
+            rust_version: None,
+            edition: None,
+            forks: vec![],
+            info: c,
         }
     }
 }
 
+#[derive(Serialize)]
+struct CollapsedFork<'a> {
+    name: &'a str,
+    forks_of: &'a str,
+    downloads: u64,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct CrateListParams {
     /// Free-text search query (e.g. "async http client")
@@ -47,9 +91,50 @@ pub struct CrateListParams {
     pub page: Option<u32>,
     /// Results per page (max 100, default: 10)
     pub per_page: Option<u32>,
+    /// Collapse near-duplicate forks (same description, or a forked repository) into the
+    /// most-downloaded crate of each group (default: true)
+    pub dedupe_forks: Option<bool>,
+    /// Keep only crates whose license permits one of these SPDX identifiers, comma-separated
+    /// (e.g. "MIT,Apache-2.0"). Costs one crates.io request per result.
+    pub license: Option<String>,
+    /// Keep only crates whose current version declares a rust-version at or below this
+    /// toolchain (e.g. "1.70"). Crates that declare none are dropped.
+    pub max_rust_version: Option<String>,
+    /// Keep only crates whose current version uses this edition or an older one (e.g. "2021").
+    /// Costs one crates.io request per result.
+    pub max_edition: Option<String>,
+    /// How much to return per crate: "names" (just the crate names, cheapest for broad surveys),
+    /// "summary" (default: version, description, downloads, repository), or "full" (the complete
+    /// crates.io record, adding homepage, documentation, keywords, categories and timestamps)
+    pub detail: Option<String>,
 }
 
 pub async fn execute(state: &AppState, params: CrateListParams) -> Result<CallToolResult, ErrorData> {
+    let detail = params.detail.as_deref().unwrap_or("summary");
+    if !matches!(detail, "names" | "summary" | "full") {
+        return Err(ErrorData::invalid_params(
+            format!("Unknown detail '{detail}'. Use \"names\", \"summary\" or \"full\"."),
+            None,
+        ));
+    }
+    let max_rust_version = match params.max_rust_version.as_deref() {
+        Some(s) => Some(parse_rust_version(s).ok_or_else(|| ErrorData::invalid_params(
+            format!("Invalid max_rust_version '{s}'. Expected a toolchain version like \"1.70\" or \"1.70.0\"."),
+            None,
+        ))?),
+        None => None,
+    };
+    let max_edition = match params.max_edition.as_deref() {
+        Some(s) => Some(s.trim().parse::<u32>().map_err(|_| ErrorData::invalid_params(
+            format!("Invalid max_edition '{s}'. Expected an edition year like \"2021\"."),
+            None,
+        ))?),
+        None => None,
+    };
+    let licenses: Vec<String> = params.license.as_deref()
+        .map(|l| l.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+
     let query = params.query.as_deref().unwrap_or("");
     let page = params.page.unwrap_or(1).max(1);
     let per_page = params.per_page.unwrap_or(10).min(100);
@@ -65,12 +150,336 @@ pub async fn execute(state: &AppState, params: CrateListParams) -> Result<CallTo
             per_page,
         )
         .await
-        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+        .map_err(ErrorData::from)?;
+
+    let mut entries: Vec<CrateListEntry> = vec![];
+    let mut collapsed: Vec<CollapsedFork> = vec![];
+    if params.dedupe_forks.unwrap_or(true) {
+        for (dominant, forks) in group_forks(&result.crates) {
+            let mut entry = CrateListEntry::from(&result.crates[dominant]);
+            for &f in &forks {
+                let fork = &result.crates[f];
+                entry.forks.push(&fork.name);
+                collapsed.push(CollapsedFork {
+                    name: &fork.name,
+                    forks_of: &result.crates[dominant].name,
+                    downloads: fork.downloads,
+                });
+            }
+            entries.push(entry);
+        }
+    } else {
+        entries = result.crates.iter().map(CrateListEntry::from).collect();
+    }
 
-    let entries: Vec<CrateListEntry> = result.crates.iter().map(CrateListEntry::from).collect();
-    let output = serde_json::json!({ "crates": entries, "total": result.meta.total });
+    let filtering = max_rust_version.is_some() || max_edition.is_some() || !licenses.is_empty();
+    let mut filtered_out: Vec<&str> = vec![];
+    if filtering {
+        let filters = Filters { licenses: &licenses, max_rust_version: max_rust_version.as_ref(), max_edition };
+        // Futures are built up front so the stream holds no borrowing closure; `buffered`
+        // keeps the search order
+        let checks: Vec<_> = entries.into_iter().map(|entry| filters.check(state, entry)).collect();
+        let checked: Vec<(CrateListEntry, bool)> = stream::iter(checks)
+            .buffered(MAX_CONCURRENT_LOOKUPS)
+            .collect()
+            .await;
+        let mut kept = vec![];
+        for (entry, admitted) in checked {
+            if admitted {
+                kept.push(entry);
+            } else {
+                filtered_out.push(entry.name);
+            }
+        }
+        entries = kept;
+    }
+
+    let crates = match detail {
+        "names" => serde_json::json!(entries.iter().map(|e| e.name).collect::<Vec<_>>()),
+        "full" => serde_json::json!(entries.iter().map(CrateListEntry::to_full).collect::<Vec<_>>()),
+        _ => serde_json::json!(entries),
+    };
+    let mut output = serde_json::json!({ "crates": crates, "total": result.meta.total });
+    if filtering {
+        // `total` counts the unfiltered search; these are the page's crates that failed
+        output["filtered_out"] = serde_json::json!(filtered_out);
+    }
+    if !collapsed.is_empty() && detail != "names" {
+        output["collapsed_forks"] = serde_json::json!(collapsed);
+    }
     let json = serde_json::to_string_pretty(&output)
         .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
 
     Ok(CallToolResult::success(vec![Content::text(json)]))
 }
+
+// ─── Adoption filters ─────────────────────────────────────────────────────────
+
+/// License, MSRV and edition constraints checked against each result's current version.
+struct Filters<'f> {
+    licenses: &'f [String],
+    max_rust_version: Option<&'f semver::Version>,
+    max_edition: Option<u32>,
+}
+
+impl Filters<'_> {
+    /// [`admit`](Self::admit) `entry`, handing it back with the verdict.
+    async fn check<'e>(&self, state: &AppState, mut entry: CrateListEntry<'e>) -> (CrateListEntry<'e>, bool) {
+        let admitted = self.admit(state, &mut entry).await;
+        (entry, admitted)
+    }
+
+    /// Look up the metadata the filters need, record it on `entry`, and report whether it passes.
+    ///
+    /// MSRV comes from the sparse index; license and edition need the crates.io crate
+    /// record. A failed lookup drops the crate, since its constraints can't be confirmed.
+    async fn admit(&self, state: &AppState, entry: &mut CrateListEntry<'_>) -> bool {
+        let name = entry.name;
+        if let Some(max) = self.max_rust_version {
+            let Ok(lines) = state.fetch_index(name).await else { return false };
+            let line = entry.version
+                .and_then(|v| lines.iter().find(|l| l.vers == v))
+                .or_else(|| crate::sparse_index::find_latest_stable(&lines));
+            entry.rust_version = line.and_then(|l| l.rust_version.clone());
+            let msrv = entry.rust_version.as_deref().and_then(parse_rust_version);
+            if msrv.is_none_or(|m| m > *max) { return false; }
+        }
+
+        if self.licenses.is_empty() && self.max_edition.is_none() {
+            return true;
+        }
+        let client = crate::cratesio::CratesIoClient::new(&state.client, &state.cache)
+            .with_dump(state.dump_db.as_ref());
+        let Ok(krate) = client.get_crate(name).await else { return false };
+        let Some(version) = krate.versions.as_ref()
+            .and_then(|vs| vs.iter().find(|v| Some(v.num.as_str()) == entry.version))
+        else {
+            return false;
+        };
+        entry.license = version.license.clone();
+        entry.edition = version.edition.clone();
+        if entry.rust_version.is_none() {
+            entry.rust_version = version.rust_version.clone();
+        }
+
+        if !self.licenses.is_empty()
+            && !version.license.as_deref().is_some_and(|l| license_allows(l, self.licenses))
+        {
+            return false;
+        }
+        if let Some(max) = self.max_edition {
+            // Crates without an edition key build as 2015
+            let edition = version.edition.as_deref().and_then(|e| e.parse::<u32>().ok()).unwrap_or(2015);
+            if edition > max { return false; }
+        }
+        true
+    }
+}
+
+/// Whether the SPDX expression `expr` can be complied with using only `allowed` licenses.
+///
+/// `OR` (and the legacy `/`) needs one side allowed, `AND` needs both; `AND` binds tighter.
+/// A `WITH` exception is judged by its license alone.
+fn license_allows(expr: &str, allowed: &[String]) -> bool {
+    let spaced = expr.replace('(', " ( ").replace(')', " ) ").replace('/', " OR ");
+    let tokens: Vec<&str> = spaced.split_whitespace().collect();
+    let mut pos = 0;
+    eval_or(&tokens, &mut pos, allowed)
+}
+
+fn eval_or(tokens: &[&str], pos: &mut usize, allowed: &[String]) -> bool {
+    let mut ok = eval_and(tokens, pos, allowed);
+    while tokens.get(*pos).is_some_and(|t| t.eq_ignore_ascii_case("OR")) {
+        *pos += 1;
+        ok |= eval_and(tokens, pos, allowed);
+    }
+    ok
+}
+
+fn eval_and(tokens: &[&str], pos: &mut usize, allowed: &[String]) -> bool {
+    let mut ok = eval_term(tokens, pos, allowed);
+    while tokens.get(*pos).is_some_and(|t| t.eq_ignore_ascii_case("AND")) {
+        *pos += 1;
+        ok &= eval_term(tokens, pos, allowed);
+    }
+    ok
+}
+
+fn eval_term(tokens: &[&str], pos: &mut usize, allowed: &[String]) -> bool {
+    let Some(&token) = tokens.get(*pos) else { return false };
+    *pos += 1;
+    if token == "(" {
+        let ok = eval_or(tokens, pos, allowed);
+        if tokens.get(*pos) == Some(&")") { *pos += 1; }
+        return ok;
+    }
+    if tokens.get(*pos).is_some_and(|t| t.eq_ignore_ascii_case("WITH")) {
+        *pos += 2;
+    }
+    let id = token.trim_end_matches('+');
+    allowed.iter().any(|a| a.eq_ignore_ascii_case(id))
+}
+
+// ─── Fork detection ───────────────────────────────────────────────────────────
+
+/// Descriptions shorter than this are too generic ("A Rust library") to signal a fork.
+const MIN_FORK_DESCRIPTION_LEN: usize = 20;
+
+/// Group near-duplicate crates and pick the most-downloaded one of each group.
+///
+/// Two crates are treated as duplicates when their normalized descriptions match, or when
+/// their repositories have the same name under different owners (a forked repository).
+/// Crates sharing the exact same repository are workspace siblings, not forks.
+///
+/// Returns `(dominant, forks)` index pairs in the order the dominant crates appeared.
+fn group_forks(crates: &[CrateInfo]) -> Vec<(usize, Vec<usize>)> {
+    let n = crates.len();
+    let mut parent: Vec<usize> = (0..n).collect();
+
+    fn find(parent: &mut [usize], i: usize) -> usize {
+        let mut root = i;
+        while parent[root] != root {
+            root = parent[root];
+        }
+        parent[i] = root;
+        root
+    }
+
+    for i in 0..n {
+        for j in (i + 1)..n {
+            if is_near_duplicate(&crates[i], &crates[j]) {
+                let (ri, rj) = (find(&mut parent, i), find(&mut parent, j));
+                if ri != rj {
+                    parent[rj] = ri;
+                }
+            }
+        }
+    }
+
+    let mut groups: Vec<Vec<usize>> = vec![vec![]; n];
+    for i in 0..n {
+        let root = find(&mut parent, i);
+        groups[root].push(i);
+    }
+
+    let mut result: Vec<(usize, Vec<usize>)> = groups.into_iter()
+        .filter(|g| !g.is_empty())
+        .map(|members| {
+            let dominant = *members.iter()
+                .max_by_key(|&&i| (crates[i].downloads, std::cmp::Reverse(i)))
+                .unwrap_or(&members[0]);
+            let forks = members.into_iter().filter(|&i| i != dominant).collect();
+            (dominant, forks)
+        })
+        .collect();
+    result.sort_by_key(|(dominant, _)| *dominant);
+    result
+}
+
+fn is_near_duplicate(a: &CrateInfo, b: &CrateInfo) -> bool {
+    if let (Some(da), Some(db)) = (normalize_description(a), normalize_description(b))
+        && da.len() >= MIN_FORK_DESCRIPTION_LEN
+        && da == db
+    {
+        return true;
+    }
+    match (repo_owner_and_name(a), repo_owner_and_name(b)) {
+        (Some((owner_a, name_a)), Some((owner_b, name_b))) => name_a == name_b && owner_a != owner_b,
+        _ => false,
+    }
+}
+
+fn normalize_description(c: &CrateInfo) -> Option<String> {
+    let d = c.description.as_deref()?;
+    let collapsed = d.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    let trimmed = collapsed.trim_end_matches('.').to_string();
+    if trimmed.is_empty() { None } else { Some(trimmed) }
+}
+
+/// Extract `(owner, repo)` from a repository URL like `https://github.com/owner/repo.git`.
+fn repo_owner_and_name(c: &CrateInfo) -> Option<(String, String)> {
+    let url = c.repository.as_deref()?.trim().to_lowercase();
+    let url = url.trim_end_matches('/').trim_end_matches(".git");
+    let rest = url.split("://").nth(1).unwrap_or(url);
+    let mut parts = rest.split('/').skip(1); // skip host
+    let owner = parts.next().filter(|s| !s.is_empty())?;
+    let name = parts.next().filter(|s| !s.is_empty())?;
+    Some((owner.to_string(), name.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_crate(name: &str, downloads: u64, description: Option<&str>, repository: Option<&str>) -> CrateInfo {
+        CrateInfo {
+            id: name.to_string(),
+            name: name.to_string(),
+            description: description.map(str::to_string),
+            homepage: None,
+            documentation: None,
+            repository: repository.map(str::to_string),
+            downloads,
+            recent_downloads: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+            max_stable_version: None,
+            max_version: None,
+            newest_version: None,
+            links: None,
+            categories: None,
+            keywords: None,
+        }
+    }
+
+    #[test]
+    fn identical_descriptions_collapse_into_most_downloaded() {
+        let crates = vec![
+            make_crate("foo-fork", 10, Some("A fast foo implementation."), None),
+            make_crate("foo", 5000, Some("A fast  foo implementation"), None),
+            make_crate("bar", 100, Some("Something else entirely"), None),
+        ];
+        let groups = group_forks(&crates);
+        assert_eq!(groups, vec![(1, vec![0]), (2, vec![])]);
+    }
+
+    #[test]
+    fn forked_repository_collapses() {
+        let crates = vec![
+            make_crate("foo", 5000, Some("Original"), Some("https://github.com/alice/foo")),
+            make_crate("foo2", 20, Some("Patched"), Some("https://github.com/bob/foo.git")),
+        ];
+        assert_eq!(group_forks(&crates), vec![(0, vec![1])]);
+    }
+
+    #[test]
+    fn workspace_siblings_sharing_a_repository_are_not_forks() {
+        let crates = vec![
+            make_crate("tokio", 5000, Some("An event-driven runtime"), Some("https://github.com/tokio-rs/tokio")),
+            make_crate("tokio-util", 3000, Some("Additional utilities for Tokio"), Some("https://github.com/tokio-rs/tokio")),
+        ];
+        assert_eq!(group_forks(&crates), vec![(0, vec![]), (1, vec![])]);
+    }
+
+    #[test]
+    fn license_expressions_are_matched_against_allowed_ids() {
+        let allowed = vec!["MIT".to_string(), "Apache-2.0".to_string()];
+        assert!(license_allows("MIT OR Apache-2.0", &allowed));
+        assert!(license_allows("MIT/Apache-2.0", &allowed));
+        assert!(license_allows("GPL-3.0 OR mit", &allowed));
+        assert!(!license_allows("GPL-3.0-only", &allowed));
+        assert!(!license_allows("MIT AND BSD-3-Clause", &allowed));
+        assert!(license_allows("(MIT OR Apache-2.0) AND Apache-2.0", &allowed));
+        assert!(!license_allows("(MIT OR Apache-2.0) AND Unicode-3.0", &allowed));
+        assert!(license_allows("Apache-2.0 WITH LLVM-exception", &allowed));
+    }
+
+    #[test]
+    fn short_generic_descriptions_do_not_collapse() {
+        let crates = vec![
+            make_crate("a", 1, Some("A library"), None),
+            make_crate("b", 2, Some("A library"), None),
+        ];
+        assert_eq!(group_forks(&crates).len(), 2);
+    }
+}
@@ -0,0 +1,181 @@
+use chrono::{DateTime, Utc};
+use rmcp::{ErrorData, model::{CallToolResult, Content}};
+use serde::{Deserialize, Serialize};
+use rmcp::schemars::{self, JsonSchema};
+use serde_json::json;
+use semver::Version;
+
+use super::AppState;
+use super::crate_versions_list::release_cadence;
+use crate::depsdev;
+
+/// A release this recent means the crate is maintained, whatever else it shows.
+const ACTIVE_WITHIN_DAYS: i64 = 180;
+/// Past this, a crate with no sign of repository activity is likely abandoned.
+const ABANDONED_AFTER_DAYS: i64 = 730;
+/// Scorecard "Maintained" score (0 to 10, from commits and issue activity in the last 90
+/// days) that counts as an active repository.
+const ACTIVE_REPO_SCORE: i32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Status {
+    ActivelyMaintained,
+    MaintenanceMode,
+    LikelyAbandoned,
+}
+
+/// Recent activity in the source repository, from deps.dev.
+#[derive(Serialize)]
+struct RepositoryActivity {
+    project: String,
+    /// Scorecard "Maintained" check, absent when the repository has not been scored
+    maintained_score: Option<i32>,
+    reason: Option<String>,
+    open_issues: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CrateMaintenanceGetParams {
+    /// Crate name
+    pub name: String,
+    /// Also weigh recent commit and issue activity in the source repository, from the
+    /// OpenSSF Scorecard "Maintained" check on deps.dev (default: false)
+    pub include_repository: Option<bool>,
+}
+
+pub async fn execute(state: &AppState, params: CrateMaintenanceGetParams) -> Result<CallToolResult, ErrorData> {
+    let name = &params.name;
+    let client = crate::cratesio::CratesIoClient::new(&state.client, &state.cache)
+        .with_dump(state.dump_db.as_ref());
+    let versions = client.get_versions(name).await
+        .map_err(ErrorData::from)?
+        .versions;
+    let now = Utc::now();
+
+    // (version, published, yanked) of stable releases, newest first
+    let mut releases: Vec<(Version, DateTime<Utc>, bool)> = versions.iter()
+        .filter_map(|v| Some((
+            Version::parse(&v.num).ok().filter(|v| v.pre.is_empty())?,
+            DateTime::parse_from_rfc3339(&v.created_at).ok()?.to_utc(),
+            v.yanked,
+        )))
+        .collect();
+    releases.sort_by_key(|r| std::cmp::Reverse(r.1));
+    let Some((last_version, last_date, _)) = releases.first().cloned() else {
+        let output = json!({
+            "name": name,
+            "status": Status::LikelyAbandoned,
+            "evidence": ["no stable release has ever been published"],
+        });
+        return to_result(&output);
+    };
+    let days_since = (now - last_date).num_days();
+    let dates: Vec<DateTime<Utc>> = releases.iter().map(|(_, d, _)| *d).collect();
+    let releases_last_year = dates.iter().filter(|d| (now - **d).num_days() < 365).count();
+
+    let mut evidence = vec![format!("last release {last_version} was {days_since} days ago")];
+    evidence.push(format!("{releases_last_year} release(s) in the last 365 days"));
+    let yanked_last_year = releases.iter()
+        .filter(|(_, d, yanked)| *yanked && (now - *d).num_days() < 365)
+        .count();
+    if yanked_last_year > 0 {
+        evidence.push(format!("{yanked_last_year} release(s) from the last 365 days are yanked"));
+    }
+    // The newest releases being yanked with nothing after them leaves users stuck on older code
+    let newest_usable = releases.iter().find(|(_, _, yanked)| !yanked).map(|(v, _, _)| v);
+    if newest_usable != Some(&last_version) {
+        match newest_usable {
+            Some(v) => evidence.push(format!("the newest release is yanked; {v} is the newest usable one")),
+            None => evidence.push("every stable release is yanked".to_string()),
+        }
+    }
+
+    let mut output = json!({
+        "name": name,
+        "last_release": {
+            "version": last_version.to_string(),
+            "published_at": last_date.to_rfc3339(),
+            "days_ago": days_since,
+        },
+        "yanked_last_365_days": yanked_last_year,
+        "cadence": release_cadence(&dates, now),
+    });
+
+    let mut repo_score = None;
+    if params.include_repository.unwrap_or(false) {
+        match repository_activity(state, name, &last_version.to_string()).await {
+            Ok(Some(activity)) => {
+                if let Some(reason) = &activity.reason {
+                    evidence.push(format!("repository {}: {reason}", activity.project));
+                }
+                repo_score = activity.maintained_score;
+                output["repository"] = json!(activity);
+            }
+            Ok(None) => output["repository"] = json!({ "note": "deps.dev links no source repository to this version" }),
+            Err(e) => output["repository_error"] = json!(e.to_string()),
+        }
+    }
+
+    output["status"] = json!(classify(days_since, releases_last_year, repo_score));
+    output["evidence"] = json!(evidence);
+    to_result(&output)
+}
+
+/// Maintenance status from the age of the last release, the releases in the past year and,
+/// when known, the repository's Scorecard "Maintained" score.
+fn classify(days_since_last_release: i64, releases_last_year: usize, repo_score: Option<i32>) -> Status {
+    let repo_active = repo_score.is_some_and(|s| s >= ACTIVE_REPO_SCORE);
+    match days_since_last_release {
+        d if d <= ACTIVE_WITHIN_DAYS => Status::ActivelyMaintained,
+        d if d <= 365 && (releases_last_year >= 2 || repo_active) => Status::ActivelyMaintained,
+        d if d <= ABANDONED_AFTER_DAYS => Status::MaintenanceMode,
+        // Commits without releases: slow-moving but not abandoned
+        _ if repo_active => Status::MaintenanceMode,
+        _ => Status::LikelyAbandoned,
+    }
+}
+
+/// The source repository deps.dev links to `name` `version`, or `None` when it links none.
+async fn repository_activity(
+    state: &AppState,
+    name: &str,
+    version: &str,
+) -> crate::error::Result<Option<RepositoryActivity>> {
+    let package = depsdev::package_version(&state.client, &state.cache, name, version).await?;
+    let Some(repo) = package.source_repo() else { return Ok(None) };
+    let project = depsdev::project(&state.client, &state.cache, repo).await?;
+    // -1 means Scorecard could not run the check
+    let maintained = project.scorecard
+        .and_then(|s| s.checks.into_iter().find(|c| c.name == "Maintained"))
+        .filter(|c| c.score >= 0);
+    Ok(Some(RepositoryActivity {
+        project: project.project_key.id,
+        maintained_score: maintained.as_ref().map(|c| c.score),
+        reason: maintained.and_then(|c| c.reason),
+        open_issues: project.open_issues_count,
+    }))
+}
+
+fn to_result(output: &serde_json::Value) -> Result<CallToolResult, ErrorData> {
+    let json = serde_json::to_string_pretty(output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_weighs_release_age_cadence_and_repository() {
+        assert_eq!(classify(30, 1, None), Status::ActivelyMaintained);
+        assert_eq!(classify(300, 4, None), Status::ActivelyMaintained);
+        assert_eq!(classify(300, 1, None), Status::MaintenanceMode);
+        assert_eq!(classify(300, 1, Some(8)), Status::ActivelyMaintained);
+        assert_eq!(classify(600, 0, None), Status::MaintenanceMode);
+        assert_eq!(classify(1000, 0, None), Status::LikelyAbandoned);
+        assert_eq!(classify(1000, 0, Some(2)), Status::LikelyAbandoned);
+        assert_eq!(classify(1000, 0, Some(10)), Status::MaintenanceMode);
+    }
+}
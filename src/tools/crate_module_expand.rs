@@ -0,0 +1,90 @@
+use std::collections::HashSet;
+
+use rmcp::{ErrorData, model::{CallToolResult, Content}};
+use serde::Deserialize;
+use rmcp::schemars::{self, JsonSchema};
+use serde_json::json;
+
+use super::{AppState, docs_not_found_error};
+use super::crate_docs_get::serialize_module_nodes;
+use crate::docsrs::build_module_subtree;
+use crate::sparse_index::find_latest_stable;
+use crate::error::ErrorCode;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CrateModuleExpandParams {
+    /// Crate name
+    pub name: String,
+    /// Version string. Defaults to latest stable.
+    pub version: Option<String>,
+    /// Module to expand (e.g. "tokio::sync"). Defaults to the crate root.
+    pub path: Option<String>,
+    /// Include non-public and #[doc(hidden)] items (default: false)
+    pub include_hidden: Option<bool>,
+}
+
+pub async fn execute(state: &AppState, params: CrateModuleExpandParams) -> Result<CallToolResult, ErrorData> {
+    let name = &params.name;
+    let version = state.resolve_version(name, params.version.as_deref()).await
+        .map_err(ErrorData::from)?;
+
+    let (docs_result, index_result) = tokio::join!(
+        state.rustdoc(name, &version),
+        state.fetch_index(name)
+    );
+    let doc = match docs_result {
+        Ok(d) => d,
+        Err(crate::error::DocsError::DocsNotFound { .. }) => {
+            return Err(docs_not_found_error(state, name, &version).await);
+        }
+        Err(e) => return Err(ErrorData::from(e)),
+    };
+    let index_lines = index_result.unwrap_or_default();
+    let features = find_latest_stable(&index_lines).map(|l| l.all_features()).unwrap_or_default();
+    let declared_features: HashSet<&str> = features.keys().map(String::as_str).collect();
+
+    // The crate root is a module like any other, named by the crate
+    let path = params.path.clone().unwrap_or_else(|| name.replace('-', "_"));
+    let include_hidden = params.include_hidden.unwrap_or(false);
+    let node = build_module_subtree(&doc, Some(&path), 1, include_hidden)
+        .and_then(|nodes| nodes.into_iter().next())
+        .ok_or_else(|| ErrorData::invalid_params(
+            format!("Module '{path}' not found in {name} {version}. \
+                     Call crate_module_expand without `path` to start from the crate root."),
+            ErrorCode::ItemNotFound.data(),
+        ))?;
+
+    // Children come back without their own subtrees; flag the ones worth expanding next
+    let mut submodules = serialize_module_nodes(&node.children, false, &declared_features);
+    for (json, child) in submodules.as_array_mut().into_iter().flatten().zip(&node.children) {
+        json["has_submodules"] = json!(child.item_counts.contains_key("module"));
+    }
+    let items: Vec<_> = node.items.iter().map(|i| json!({
+        "kind": i.kind,
+        "path": format!("{}::{}", node.path, i.name),
+        "doc_summary": i.doc_summary,
+    })).collect();
+
+    let mut output = json!({
+        "name": name,
+        "version": version,
+        "path": node.path,
+        "doc_summary": node.doc_summary,
+        "submodules": submodules,
+        "items": items,
+    });
+    let requirements: Vec<&String> = node.feature_requirements.iter()
+        .filter(|f| declared_features.is_empty() || declared_features.contains(f.as_str()))
+        .collect();
+    if !requirements.is_empty() {
+        output["feature_requirements"] = json!(requirements);
+    }
+    if let Some(cfg) = &node.cfg {
+        output["cfg"] = json!(cfg);
+    }
+
+    let json = serde_json::to_string_pretty(&output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
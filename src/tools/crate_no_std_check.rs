@@ -0,0 +1,414 @@
+use std::collections::{BTreeSet, HashMap};
+
+use rmcp::{ErrorData, model::{CallToolResult, Content}};
+use serde::{Deserialize, Serialize};
+use rmcp::schemars::{self, JsonSchema};
+
+use super::AppState;
+use crate::cratesio::tarball::Checksum;
+use crate::error::{DocsError, ErrorCode};
+use crate::sparse_index::{DepKind, IndexLine};
+
+/// Crate-level attributes sit at the top of the crate root.
+const LIB_ROOT_MAX_BYTES: usize = 64 * 1024;
+
+/// How a crate declares `#![no_std]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Support {
+    /// `#![no_std]`, or a `cfg_attr` that does not depend on features
+    Unconditional,
+    /// `#![cfg_attr(<feature condition>, no_std)]`
+    Conditional,
+    /// No `no_std` attribute: the crate always links std
+    Unsupported,
+}
+
+#[derive(Serialize)]
+struct NoStdOutput {
+    name: String,
+    version: String,
+    support: Support,
+    /// File the attributes were read from
+    lib_root: String,
+    /// The `no_std` attributes as written, whitespace collapsed
+    attributes: Vec<String>,
+    /// Features that turn std back on (negated in the `cfg_attr` condition)
+    std_features: Vec<String>,
+    /// Features that switch to no_std (required by the `cfg_attr` condition)
+    no_std_features: Vec<String>,
+    /// Whether the default feature set enables one of `std_features`
+    default_enables_std: bool,
+    /// Feature for the `alloc`-backed APIs (Vec, String, Box), if the crate has one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    alloc_feature: Option<String>,
+    /// The crate root has `extern crate alloc`
+    uses_alloc: bool,
+    /// Dependency line for a no_std build
+    #[serde(skip_serializing_if = "Option::is_none")]
+    recommended: Option<String>,
+    /// Required normal dependencies built with default features, which may pull std back in
+    deps_with_default_features: Vec<String>,
+    /// The package archive checked against the index checksum
+    checksum: Checksum,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CrateNoStdCheckParams {
+    /// Crate name
+    pub name: String,
+    /// Version string. Defaults to latest stable.
+    pub version: Option<String>,
+}
+
+pub async fn execute(state: &AppState, params: CrateNoStdCheckParams) -> Result<CallToolResult, ErrorData> {
+    let name = &params.name;
+    let version = state.resolve_version(name, params.version.as_deref()).await
+        .map_err(ErrorData::from)?;
+    let lines = state.fetch_index(name).await
+        .map_err(ErrorData::from)?;
+    let line = lines.iter().find(|l| l.vers == version).ok_or_else(|| ErrorData::from(DocsError::VersionNotFound {
+        name: name.clone(),
+        version: version.clone(),
+    }))?;
+
+    let tarball = state.crate_tarball(name, &version).await
+        .map_err(ErrorData::from)?;
+    let (lib_root, source) = tarball.lib_root(LIB_ROOT_MAX_BYTES).await
+        .map_err(ErrorData::from)?
+        .and_then(|(path, file)| Some((path, file.text?)))
+        .ok_or_else(|| ErrorData::invalid_params(
+            format!("{name} {version} has no library target, so no_std does not apply."),
+            ErrorCode::FileNotFound.data(),
+        ))?;
+
+    let attributes: Vec<String> = crate_attributes(&source).into_iter()
+        .filter(|a| declares_no_std(a))
+        .collect();
+    let mut std_features = BTreeSet::new();
+    let mut no_std_features = BTreeSet::new();
+    for attr in &attributes {
+        for (feature, negated) in cfg_features(attr) {
+            let set = if negated { &mut std_features } else { &mut no_std_features };
+            set.insert(feature);
+        }
+    }
+    let support = if attributes.is_empty() {
+        Support::Unsupported
+    } else if std_features.is_empty() && no_std_features.is_empty() {
+        Support::Unconditional
+    } else {
+        Support::Conditional
+    };
+
+    let features = line.all_features();
+    let default_enables_std = default_closure(&features).iter().any(|f| std_features.contains(f));
+    let recommended = (support != Support::Unsupported)
+        .then(|| dependency_line(name, &version, default_enables_std, no_std_features.first()));
+    let deps_with_default_features = match support {
+        Support::Unsupported => vec![],
+        _ => deps_with_default_features(line),
+    };
+
+    let output = NoStdOutput {
+        name: name.clone(),
+        version,
+        support,
+        lib_root,
+        attributes,
+        std_features: std_features.into_iter().collect(),
+        no_std_features: no_std_features.into_iter().collect(),
+        default_enables_std,
+        alloc_feature: features.contains_key("alloc").then(|| "alloc".to_string()),
+        uses_alloc: source.contains("extern crate alloc"),
+        recommended,
+        deps_with_default_features,
+        checksum: tarball.checksum().clone(),
+    };
+
+    let json = serde_json::to_string_pretty(&output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
+
+/// Inner attributes (`#![...]`) of a crate root with whitespace removed, skipping comments.
+fn crate_attributes(source: &str) -> Vec<String> {
+    let code = strip_comments(source);
+    let mut attrs = vec![];
+    let mut rest = code.as_str();
+    while let Some(start) = rest.find("#![") {
+        let mut depth = 0;
+        let mut end = None;
+        for (i, c) in rest[start + 2..].char_indices() {
+            match c {
+                '[' => depth += 1,
+                ']' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = Some(start + 2 + i + 1);
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        let Some(end) = end else { break };
+        attrs.push(rest[start..end].chars().filter(|c| !c.is_whitespace()).collect());
+        rest = &rest[end..];
+    }
+    attrs
+}
+
+/// Source with `//` and `/* */` comments removed, leaving string and char literals intact;
+/// doc comments may quote attributes and attribute strings may hold URLs.
+fn strip_comments(source: &str) -> String {
+    let chars: Vec<char> = source.chars().collect();
+    let mut out = String::with_capacity(source.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let literal_end = match (chars[i], chars.get(i + 1)) {
+            ('/', Some('/')) => {
+                // Keep the newline so line structure survives
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            ('/', Some('*')) => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+                continue;
+            }
+            ('"', _) => Some(string_end(&chars, i + 1)),
+            ('r', Some('"' | '#')) if starts_literal(&chars[..i]) => raw_string_end(&chars, i + 1),
+            ('\'', _) => char_literal_end(&chars, i + 1),
+            _ => None,
+        };
+        let end = literal_end.unwrap_or(i + 1).min(chars.len());
+        out.extend(&chars[i..end]);
+        i = end;
+    }
+    out
+}
+
+/// Whether a raw string can start after `before`: not inside an identifier, except for
+/// the `b` of `br"…"`.
+fn starts_literal(before: &[char]) -> bool {
+    let before = before.strip_suffix(&['b']).unwrap_or(before);
+    !before.last().is_some_and(|&c| c.is_alphanumeric() || c == '_')
+}
+
+/// Index just past the `"` closing the string whose contents start at `start`.
+fn string_end(chars: &[char], start: usize) -> usize {
+    let mut i = start;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            '"' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    chars.len()
+}
+
+/// Index just past a raw string whose `#`s or opening `"` start at `start`; `None` when
+/// no `"` follows the `#`s, as in the raw identifier `r#type`.
+fn raw_string_end(chars: &[char], start: usize) -> Option<usize> {
+    let hashes = chars[start..].iter().take_while(|&&c| c == '#').count();
+    let open = start + hashes;
+    if chars.get(open) != Some(&'"') {
+        return None;
+    }
+    let mut i = open + 1;
+    while i < chars.len() {
+        if chars[i] == '"' && chars[i + 1..].iter().take(hashes).filter(|&&c| c == '#').count() == hashes {
+            return Some(i + 1 + hashes);
+        }
+        i += 1;
+    }
+    Some(chars.len())
+}
+
+/// Index just past a char literal whose contents start at `start`; `None` for a lifetime
+/// or label such as `'a`.
+fn char_literal_end(chars: &[char], start: usize) -> Option<usize> {
+    match chars.get(start)? {
+        '\\' => {
+            let close = chars[start + 1..].iter().position(|&c| c == '\'')?;
+            Some(start + 1 + close + 1)
+        }
+        _ => (chars.get(start + 1) == Some(&'\'')).then_some(start + 2),
+    }
+}
+
+/// Whether a compacted attribute is `#![no_std]` or a `cfg_attr` applying `no_std`.
+fn declares_no_std(attr: &str) -> bool {
+    let Some(inner) = attr.strip_prefix("#![").and_then(|a| a.strip_suffix(']')) else { return false };
+    if inner == "no_std" {
+        return true;
+    }
+    let Some(args) = inner.strip_prefix("cfg_attr(").and_then(|a| a.strip_suffix(')')) else { return false };
+    split_top_level(args).iter().skip(1).any(|a| *a == "no_std")
+}
+
+/// Arguments separated by commas outside parentheses.
+fn split_top_level(args: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in args.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&args[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&args[start..]);
+    parts
+}
+
+/// `feature = "..."` predicates in an attribute, each with whether it sits under an odd
+/// number of `not(...)`.
+fn cfg_features(attr: &str) -> Vec<(String, bool)> {
+    let mut found = vec![];
+    // One entry per open parenthesis: whether it opened a `not(`
+    let mut stack: Vec<bool> = vec![];
+    let mut i = 0;
+    while i < attr.len() {
+        let rest = &attr[i..];
+        if let Some(value) = rest.strip_prefix("feature=\"") {
+            let Some(end) = value.find('"') else { break };
+            let negated = stack.iter().filter(|&&not| not).count() % 2 == 1;
+            found.push((value[..end].to_string(), negated));
+            i += "feature=\"".len() + end + 1;
+            continue;
+        }
+        if rest.starts_with('(') {
+            stack.push(attr[..i].ends_with("not"));
+        } else if rest.starts_with(')') {
+            stack.pop();
+        }
+        i += rest.chars().next().map_or(1, char::len_utf8);
+    }
+    found
+}
+
+/// Every feature the `default` feature turns on, directly or through other features.
+fn default_closure(features: &HashMap<String, Vec<String>>) -> BTreeSet<String> {
+    let mut enabled = BTreeSet::new();
+    let mut pending = vec!["default".to_string()];
+    while let Some(feature) = pending.pop() {
+        let Some(entries) = features.get(&feature) else { continue };
+        for entry in entries {
+            // `dep:x` and `x/y` enable dependencies, not features of this crate
+            if features.contains_key(entry) && enabled.insert(entry.clone()) {
+                pending.push(entry.clone());
+            }
+        }
+    }
+    enabled
+}
+
+fn dependency_line(name: &str, version: &str, default_off: bool, feature: Option<&String>) -> String {
+    if !default_off && feature.is_none() {
+        return format!("{name} = \"{version}\"");
+    }
+    let mut parts = vec![format!("version = \"{version}\"")];
+    if default_off {
+        parts.push("default-features = false".to_string());
+    }
+    if let Some(feature) = feature {
+        parts.push(format!("features = [\"{feature}\"]"));
+    }
+    format!("{name} = {{ {} }}", parts.join(", "))
+}
+
+/// Crates that `line` always depends on with their default features, by package name
+/// rather than the key a renamed dependency is declared under.
+fn deps_with_default_features(line: &IndexLine) -> Vec<String> {
+    line.deps.iter()
+        .filter(|d| !d.optional && d.default_features && d.kind.as_ref().is_none_or(|k| *k == DepKind::Normal))
+        .map(|d| d.package.clone().unwrap_or_else(|| d.name.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_no_std_attributes_outside_comments() {
+        let source = "//! Use `#![no_std]` in your crate\n\
+                      /* #![no_std] */\n\
+                      #![cfg_attr(\n    not(any(feature = \"std\", test)),\n    no_std\n)]\n\
+                      #![doc(html_root_url = \"https://docs.rs/demo\")] // root\n\
+                      #![warn(missing_docs)]\n";
+        let attrs = crate_attributes(source);
+        assert!(attrs.contains(&"#![doc(html_root_url=\"https://docs.rs/demo\")]".to_string()), "got: {attrs:?}");
+        let attrs: Vec<String> = attrs.into_iter().filter(|a| declares_no_std(a)).collect();
+        assert_eq!(attrs, ["#![cfg_attr(not(any(feature=\"std\",test)),no_std)]"]);
+        assert!(declares_no_std("#![no_std]"));
+        assert!(!declares_no_std("#![cfg_attr(docsrs,feature(doc_cfg))]"));
+    }
+
+    #[test]
+    fn comment_markers_inside_literals_are_kept() {
+        let source = "let quote = '\"'; // a quote\n\
+                      let s = r#\"/* not a comment \"quoted\" */\"#; /* gone */\n\
+                      let b = br\"// kept\";\n\
+                      let slash = '/'; let esc = '\\''; fn f<'a>(x: &'a str) {} // tail\n";
+        assert_eq!(strip_comments(source), "let quote = '\"'; \n\
+                                           let s = r#\"/* not a comment \"quoted\" */\"#; \n\
+                                           let b = br\"// kept\";\n\
+                                           let slash = '/'; let esc = '\\''; fn f<'a>(x: &'a str) {} \n");
+        // A raw identifier is not a raw string
+        assert_eq!(strip_comments("r#type // c\n#![no_std]"), "r#type \n#![no_std]");
+    }
+
+    #[test]
+    fn feature_polarity_follows_not() {
+        assert_eq!(
+            cfg_features("#![cfg_attr(all(not(feature=\"std\"),not(feature=\"use_std\")),no_std)]"),
+            [("std".to_string(), true), ("use_std".to_string(), true)],
+        );
+        assert_eq!(cfg_features("#![cfg_attr(feature=\"no_std\",no_std)]"), [("no_std".to_string(), false)]);
+        assert!(cfg_features("#![cfg_attr(not(test),no_std)]").is_empty());
+    }
+
+    #[test]
+    fn default_closure_follows_feature_chains() {
+        let features: HashMap<String, Vec<String>> = [
+            ("default", vec!["full"]),
+            ("full", vec!["std", "dep:serde", "serde?/std"]),
+            ("std", vec!["alloc"]),
+            ("alloc", vec![]),
+            ("extra", vec![]),
+        ].into_iter().map(|(k, v)| (k.to_string(), v.into_iter().map(String::from).collect())).collect();
+        assert_eq!(default_closure(&features).into_iter().collect::<Vec<_>>(), ["alloc", "full", "std"]);
+    }
+
+    #[test]
+    fn renamed_dependencies_are_reported_by_package() {
+        let line: IndexLine = serde_json::from_str(r#"{"name":"demo","vers":"1.0.0","cksum":"","features":{},"yanked":false,"deps":[
+            {"name":"foo","package":"bar","req":"^1","features":[],"optional":false,"default_features":true,"target":null,"kind":"normal"},
+            {"name":"libm","req":"^0.2","features":[],"optional":false,"default_features":true,"target":null,"kind":null},
+            {"name":"serde","req":"^1","features":[],"optional":false,"default_features":false,"target":null,"kind":"normal"}
+        ]}"#).unwrap();
+        assert_eq!(deps_with_default_features(&line), ["bar", "libm"]);
+    }
+
+    #[test]
+    fn dependency_lines() {
+        assert_eq!(dependency_line("libm", "0.2.8", false, None), "libm = \"0.2.8\"");
+        assert_eq!(
+            dependency_line("serde", "1.0.217", true, None),
+            "serde = { version = \"1.0.217\", default-features = false }",
+        );
+    }
+}
@@ -4,6 +4,9 @@ use rmcp::schemars::{self, JsonSchema};
 use serde_json::json;
 
 use super::AppState;
+use crate::cratesio::Readme;
+use crate::docsrs::{select_doc_sections, split_doc_sections};
+use crate::error::ErrorCode;
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct CrateReadmeGetParams {
@@ -11,25 +14,73 @@ pub struct CrateReadmeGetParams {
     pub name: String,
     /// Version string. Defaults to latest stable.
     pub version: Option<String>,
+    /// "text" (default): crates.io's rendered README converted to plain text, keeping links as
+    /// `[text](url)` and tables as pipe-separated rows. Crates published without a README get
+    /// their GitHub repository's README instead.
+    /// "markdown": the original README file from the published package, with links and tables intact.
+    pub format: Option<String>,
+    /// Return only the part of the README under this heading (e.g. "Usage", "Examples",
+    /// "Feature flags"), including its subsections. Matched case-insensitively; an exact
+    /// heading wins, otherwise headings containing the text match.
+    pub section: Option<String>,
 }
 
+/// Largest README returned in markdown form.
+const MARKDOWN_MAX_BYTES: usize = 512 * 1024;
+
 pub async fn execute(state: &AppState, params: CrateReadmeGetParams) -> Result<CallToolResult, ErrorData> {
     let name = &params.name;
     let version = state.resolve_version(name, params.version.as_deref()).await
-        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+        .map_err(ErrorData::from)?;
 
-    let client = crate::cratesio::CratesIoClient::new(&state.client, &state.cache);
-    let readme_html = client.get_readme(name, &version).await
-        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    match params.format.as_deref().unwrap_or("text") {
+        "text" => {}
+        "markdown" => return readme_markdown(state, name, &version, params.section.as_deref()).await,
+        other => {
+            return Err(ErrorData::invalid_params(
+                format!("Unknown format '{other}'. Use \"text\" or \"markdown\"."),
+                None,
+            ));
+        }
+    }
 
-    let readme_text = html_to_text(&readme_html);
+    let client = crate::cratesio::CratesIoClient::new(&state.client, &state.cache)
+        .with_dump(state.dump_db.as_ref());
+    let (readme, krate) = tokio::join!(
+        client.get_readme_or_repository(name, &version),
+        client.get_crate(name)
+    );
+    let readme = readme
+        .map_err(ErrorData::from)?;
+    // Only used to resolve relative links; without it they are left as written
+    let repository = krate.ok().and_then(|c| c.krate.repository);
 
-    let output = json!({
+    let mut readme_text = match &readme {
+        Readme::Published(html) => html_to_text(html, repository.as_deref()),
+        Readme::Repository { markdown, .. } => markdown.clone(),
+    };
+    let mut matched = None;
+    if let Some(section) = params.section.as_deref() {
+        let (text, titles) = select_section(&readme_text, section, name, &version)?;
+        readme_text = text;
+        matched = Some(titles);
+    }
+
+    let mut output = json!({
         "name": name,
         "version": version,
         "readme_text": readme_text,
-        "readme_html_url": format!("https://crates.io/crates/{name}/{version}/readme"),
     });
+    match &readme {
+        Readme::Published(_) => output["readme_html_url"] = json!(format!("https://crates.io/crates/{name}/{version}/readme")),
+        Readme::Repository { url, .. } => {
+            output["readme_url"] = json!(url);
+            output["note"] = json!("crates.io has no README for this version; showing the repository's current README, which may differ from the release");
+        }
+    }
+    if let Some(titles) = matched {
+        output["sections_matched"] = json!(titles);
+    }
 
     let json = serde_json::to_string_pretty(&output)
         .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
@@ -37,139 +88,106 @@ pub async fn execute(state: &AppState, params: CrateReadmeGetParams) -> Result<C
     Ok(CallToolResult::success(vec![Content::text(json)]))
 }
 
-/// Convert HTML to plain text, preserving structure as best as possible.
-///
-/// Key behaviours:
-/// - `<pre>`/`<code>` blocks → fenced ``` markdown
-/// - `<img alt="...">` → `[alt text]` so badges/shields show their label
-/// - `<td>`/`<th>` → cell separator so table rows aren't mashed together
-/// - `<script>`/`<style>` content is skipped entirely
-/// - HTML entities are decoded
-fn html_to_text(html: &str) -> String {
-    let mut output = String::new();
-    let mut in_pre = false;
-    let mut in_code = false; // inline code (not inside pre)
-    let mut skip_content = false; // inside <script> or <style>
-    let mut tag_buf = String::new();
-    let mut in_tag = false;
-
-    for ch in html.chars() {
-        if ch == '<' {
-            in_tag = true;
-            tag_buf.clear();
-        } else if ch == '>' && in_tag {
-            in_tag = false;
-            let tag_lower = tag_buf.trim().to_lowercase();
-            let tag_name = tag_lower.split_whitespace().next().unwrap_or("");
-            match tag_name {
-                "script" | "style" => { skip_content = true; }
-                "/script" | "/style" => { skip_content = false; }
-                "pre" => {
-                    if !in_pre {
-                        in_pre = true;
-                        in_code = false;
-                        output.push_str("\n```\n");
-                    }
-                }
-                "/pre" => {
-                    if in_pre {
-                        in_pre = false;
-                        output.push_str("\n```\n");
-                    }
-                }
-                "code" => {
-                    if !in_pre {
-                        in_code = true;
-                        output.push('`');
-                    }
-                }
-                "/code" => {
-                    if !in_pre && in_code {
-                        in_code = false;
-                        output.push('`');
-                    }
-                }
-                "img" => {
-                    // Emit alt text for badges and images so content isn't lost
-                    if let Some(alt) = extract_attr(&tag_lower, "alt") {
-                        if !alt.is_empty() {
-                            output.push('[');
-                            output.push_str(&alt);
-                            output.push(']');
-                        }
-                    }
-                }
-                "p" | "/p" | "br" | "br/" => { output.push('\n'); }
-                "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => { output.push('\n'); }
-                "/h1" | "/h2" | "/h3" | "/h4" | "/h5" | "/h6" => { output.push_str("\n\n"); }
-                "li" => { output.push_str("\n- "); }
-                "td" | "th" => { output.push_str("  "); }
-                "/tr" => { output.push('\n'); }
-                _ => {}
-            }
-        } else if in_tag {
-            tag_buf.push(ch);
-        } else if !skip_content {
-            output.push(ch);
-        }
+/// The README file straight from the published `.crate` archive.
+async fn readme_markdown(
+    state: &AppState,
+    name: &str,
+    version: &str,
+    section: Option<&str>,
+) -> Result<CallToolResult, ErrorData> {
+    let tarball = state.crate_tarball(name, version).await
+        .map_err(ErrorData::from)?;
+    let (path, contents) = tarball.readme(MARKDOWN_MAX_BYTES).await
+        .map_err(ErrorData::from)?
+        .ok_or_else(|| ErrorData::invalid_params(
+            format!("{name} {version} does not ship a README file. Try format=\"text\" for the crates.io rendering."),
+            ErrorCode::FileNotFound.data(),
+        ))?;
+
+    let mut markdown = contents.text;
+    let mut matched = None;
+    if let (Some(section), Some(text)) = (section, markdown.as_deref()) {
+        let (text, titles) = select_section(text, section, name, version)?;
+        markdown = Some(text);
+        matched = Some(titles);
+    }
+
+    let mut output = json!({
+        "name": name,
+        "version": version,
+        "readme_path": path,
+        "readme_markdown": markdown,
+        "truncated": contents.truncated,
+        "checksum": tarball.checksum(),
+    });
+    if let Some(titles) = matched {
+        output["sections_matched"] = json!(titles);
     }
 
-    // Decode HTML entities
-    let output = decode_html_entities(&output);
+    let json = serde_json::to_string_pretty(&output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
 
-    // Collapse excessive blank lines
-    let mut result = String::new();
-    let mut blank_count = 0;
-    for line in output.lines() {
-        if line.trim().is_empty() {
-            blank_count += 1;
-            if blank_count <= 2 {
-                result.push('\n');
-            }
+/// The README content under headings matching `section`, plus the matched heading titles.
+/// When nothing matches, the error lists the README's headings.
+fn select_section(readme: &str, section: &str, name: &str, version: &str) -> Result<(String, Vec<String>), ErrorData> {
+    let sections = split_doc_sections(readme);
+    let (selected, _) = select_doc_sections(&sections, &[section.to_string()]);
+    if selected.is_empty() {
+        let headings: Vec<&str> = sections.iter()
+            .filter(|s| s.level > 0)
+            .map(|s| s.title.as_str())
+            .collect();
+        let available = if headings.is_empty() {
+            "The README has no headings".to_string()
         } else {
-            blank_count = 0;
-            result.push_str(line);
-            result.push('\n');
-        }
+            format!("Available headings: {}", headings.join(", "))
+        };
+        return Err(ErrorData::invalid_params(
+            format!("No README section matching '{section}' in {name} {version}. {available}."),
+            None,
+        ));
     }
-    result
+    let text = selected.iter().map(|s| s.text.as_str()).collect::<Vec<_>>().join("\n\n");
+    let titles = selected.iter().map(|s| s.title.clone()).collect();
+    Ok((text, titles))
 }
 
-/// Extract a named attribute value from a lowercased tag string.
-/// Handles both double-quoted (`attr="val"`) and single-quoted (`attr='val'`) forms.
-fn extract_attr(tag_lower: &str, attr: &str) -> Option<String> {
-    // Try double-quoted first
-    let dq_needle = format!("{attr}=\"");
-    if let Some(start) = tag_lower.find(&dq_needle) {
-        let rest = &tag_lower[start + dq_needle.len()..];
-        if let Some(end) = rest.find('"') {
-            return Some(rest[..end].to_string());
-        }
-    }
-    // Try single-quoted
-    let sq_needle = format!("{attr}='");
-    if let Some(start) = tag_lower.find(&sq_needle) {
-        let rest = &tag_lower[start + sq_needle.len()..];
-        if let Some(end) = rest.find('\'') {
-            return Some(rest[..end].to_string());
-        }
-    }
-    None
+/// Convert crates.io's rendered README to Markdown text (see [`crate::html`]), resolving
+/// relative links against `repository`.
+fn html_to_text(html: &str, repository: Option<&str>) -> String {
+    crate::html::to_markdown(html, |href| resolve_link(href, repository))
 }
 
-/// Decode common HTML entities to their character equivalents.
-fn decode_html_entities(s: &str) -> String {
-    s.replace("&amp;", "&")
-     .replace("&lt;", "<")
-     .replace("&gt;", ">")
-     .replace("&quot;", "\"")
-     .replace("&#39;", "'")
-     .replace("&apos;", "'")
-     .replace("&nbsp;", " ")
-     .replace("&#x27;", "'")
-     .replace("&#x2F;", "/")
-     .replace("&#x60;", "`")
-     .replace("&#x3D;", "=")
+/// Absolute target for a README link, or `None` for in-page anchors, which mean nothing
+/// once the page is flattened to text.
+///
+/// Relative paths are resolved the way the repository host renders the README: against
+/// the repository root (GitHub-style hosts get `/blob/HEAD/`). Without a repository they
+/// are kept as written.
+fn resolve_link(href: &str, repository: Option<&str>) -> Option<String> {
+    let href = href.trim();
+    if href.is_empty() || href.starts_with('#') {
+        return None;
+    }
+    if href.contains("://") || href.starts_with("mailto:") {
+        return Some(href.to_string());
+    }
+    if let Some(rest) = href.strip_prefix("//") {
+        return Some(format!("https://{rest}"));
+    }
+    let Some(repo) = repository else { return Some(href.to_string()) };
+    let repo = repo.trim_end_matches('/').trim_end_matches(".git");
+    let path = href.trim_start_matches("./").trim_start_matches('/');
+    let hosted = ["https://github.com/", "https://gitlab.com/", "https://codeberg.org/"]
+        .iter()
+        .any(|host| repo.starts_with(host));
+    if hosted {
+        Some(format!("{repo}/blob/HEAD/{path}"))
+    } else {
+        Some(format!("{repo}/{path}"))
+    }
 }
 
 #[cfg(test)]
@@ -179,21 +197,21 @@ mod tests {
     #[test]
     fn img_alt_text_is_preserved() {
         let html = r#"<img src="https://shields.io/badge" alt="build: passing">"#;
-        let text = html_to_text(html);
+        let text = html_to_text(html, None);
         assert!(text.contains("[build: passing]"), "img alt should appear as [text], got: {text}");
     }
 
     #[test]
     fn img_without_alt_emits_nothing() {
         let html = r#"<img src="logo.png">"#;
-        let text = html_to_text(html);
+        let text = html_to_text(html, None);
         assert!(!text.contains('['), "img without alt should emit nothing, got: {text}");
     }
 
     #[test]
     fn script_content_is_skipped() {
         let html = "<p>before</p><script>var x = secret();</script><p>after</p>";
-        let text = html_to_text(html);
+        let text = html_to_text(html, None);
         assert!(!text.contains("secret"), "script content must be skipped, got: {text}");
         assert!(text.contains("before"), "content before script must appear");
         assert!(text.contains("after"), "content after script must appear");
@@ -202,16 +220,27 @@ mod tests {
     #[test]
     fn style_content_is_skipped() {
         let html = "<p>text</p><style>.foo { color: red; }</style><p>more</p>";
-        let text = html_to_text(html);
+        let text = html_to_text(html, None);
         assert!(!text.contains("color"), "style content must be skipped, got: {text}");
         assert!(text.contains("text"), "content before style must appear");
         assert!(text.contains("more"), "content after style must appear");
     }
 
+    #[test]
+    fn select_section_returns_heading_and_subsections() {
+        let readme = "# demo\n\nBadges.\n\n## Usage\n\nCall it.\n\n### Async\n\nAwait it.\n\n## License\n\nMIT";
+        let (text, titles) = select_section(readme, "usage", "demo", "1.0.0").unwrap();
+        assert_eq!(text, "## Usage\n\nCall it.\n\n### Async\n\nAwait it.");
+        assert_eq!(titles, vec!["Usage".to_string()]);
+
+        let err = select_section(readme, "Examples", "demo", "1.0.0").unwrap_err();
+        assert!(err.message.contains("Available headings: demo, Usage, Async, License"), "got: {}", err.message);
+    }
+
     #[test]
     fn table_cells_are_separated() {
         let html = "<table><tr><td>Cell A</td><td>Cell B</td></tr></table>";
-        let text = html_to_text(html);
+        let text = html_to_text(html, None);
         assert!(text.contains("Cell A"), "first cell must appear");
         assert!(text.contains("Cell B"), "second cell must appear");
         // Cells should be separated by whitespace, not jammed together
@@ -221,30 +250,42 @@ mod tests {
     #[test]
     fn inline_code_gets_backticks() {
         let html = "<p>Use the <code>spawn</code> function.</p>";
-        let text = html_to_text(html);
+        let text = html_to_text(html, None);
         assert!(text.contains("`spawn`"), "inline code should be wrapped in backticks, got: {text}");
     }
 
     #[test]
     fn pre_code_block_gets_fences() {
         let html = "<pre><code>fn main() {}</code></pre>";
-        let text = html_to_text(html);
+        let text = html_to_text(html, None);
         assert!(text.contains("```"), "pre block should produce fenced code block");
         assert!(text.contains("fn main()"), "code content should be preserved");
     }
 
     #[test]
-    fn extract_attr_double_quoted() {
-        assert_eq!(extract_attr(r#"img src="x.png" alt="hello""#, "alt"), Some("hello".to_string()));
+    fn table_rows_get_pipes_and_header_rule() {
+        let html = "<table>\n<thead>\n<tr>\n<th>Feature</th>\n<th>Default</th>\n</tr>\n</thead>\n\
+                    <tbody>\n<tr>\n<td>json</td>\n<td>yes</td>\n</tr>\n</tbody>\n</table>";
+        let text = html_to_text(html, None);
+        assert!(text.contains("| Feature | Default |\n| --- | --- |\n| json | yes |"), "got: {text}");
     }
 
     #[test]
-    fn extract_attr_single_quoted() {
-        assert_eq!(extract_attr("img src='x.png' alt='world'", "alt"), Some("world".to_string()));
+    fn links_become_markdown() {
+        let html = r##"<p>See <a href="https://docs.rs/Serde" rel="nofollow">the docs</a> and <a href="#usage">usage</a>.</p>"##;
+        let text = html_to_text(html, None);
+        assert!(text.contains("[the docs](https://docs.rs/Serde)"), "got: {text}");
+        assert!(text.contains(" usage."), "in-page anchors keep only their text, got: {text}");
     }
 
     #[test]
-    fn extract_attr_missing_returns_none() {
-        assert_eq!(extract_attr("img src=\"x.png\"", "alt"), None);
+    fn relative_links_resolve_against_repository() {
+        let html = r#"<a href="examples/README.md">examples</a> <a href="/LICENSE"><img alt="license"></a>"#;
+        let text = html_to_text(html, Some("https://github.com/serde-rs/serde.git"));
+        assert!(text.contains("[examples](https://github.com/serde-rs/serde/blob/HEAD/examples/README.md)"), "got: {text}");
+        assert!(text.contains("[[license]](https://github.com/serde-rs/serde/blob/HEAD/LICENSE)"), "got: {text}");
+        assert_eq!(resolve_link("docs/guide.md", None).as_deref(), Some("docs/guide.md"));
+        assert_eq!(resolve_link("//example.com/x", None).as_deref(), Some("https://example.com/x"));
+        assert_eq!(resolve_link("guide.md", Some("https://git.sr.ht/~me/demo")).as_deref(), Some("https://git.sr.ht/~me/demo/guide.md"));
     }
 }
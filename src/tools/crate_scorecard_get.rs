@@ -0,0 +1,111 @@
+use rmcp::{ErrorData, model::{CallToolResult, Content}};
+use serde::Deserialize;
+use rmcp::schemars::{self, JsonSchema};
+use serde_json::json;
+
+use super::AppState;
+use crate::depsdev;
+use crate::error::ErrorCode;
+
+/// Dependencies listed in full; larger graphs are summarized by counts only.
+const MAX_LISTED_DEPENDENCIES: usize = 100;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CrateScorecardGetParams {
+    /// Crate name
+    pub name: String,
+    /// Version string. Defaults to latest stable.
+    pub version: Option<String>,
+    /// List each crate in the resolved dependency graph, not just the counts (default: false)
+    pub include_dependencies: Option<bool>,
+}
+
+pub async fn execute(state: &AppState, params: CrateScorecardGetParams) -> Result<CallToolResult, ErrorData> {
+    let name = &params.name;
+    let version = state.resolve_version(name, params.version.as_deref()).await
+        .map_err(ErrorData::from)?;
+
+    let (package, graph) = tokio::join!(
+        depsdev::package_version(&state.client, &state.cache, name, &version),
+        depsdev::dependencies(&state.client, &state.cache, name, &version)
+    );
+    let package = package.map_err(|e| {
+        if !e.is_not_found() {
+            return ErrorData::from(e);
+        }
+        ErrorData::invalid_params(
+            format!("deps.dev has no data for {name} {version} yet; it indexes new releases within a day or so."),
+            ErrorCode::NotFound.data(),
+        )
+    })?;
+
+    let mut output = json!({
+        "name": name,
+        "version": version,
+        "deps_dev_url": format!("https://deps.dev/cargo/{name}/{version}"),
+        "licenses": package.licenses,
+        "advisories": package.advisory_keys.iter().map(|a| &a.id).collect::<Vec<_>>(),
+        "published_at": package.published_at,
+    });
+
+    // deps.dev only resolves the normal dependencies, as a fresh lockfile would
+    match graph {
+        Ok(graph) => {
+            let count = |relation: &str| graph.nodes.iter().filter(|n| n.relation == relation).count();
+            let mut dependencies = json!({
+                "direct": count("DIRECT"),
+                "indirect": count("INDIRECT"),
+            });
+            if !graph.error.is_empty() {
+                dependencies["resolution_error"] = json!(graph.error);
+            }
+            if params.include_dependencies.unwrap_or(false) {
+                let listed: Vec<_> = graph.nodes.iter()
+                    .filter(|n| n.relation != "SELF")
+                    .take(MAX_LISTED_DEPENDENCIES)
+                    .map(|n| json!({
+                        "name": n.version_key.name,
+                        "version": n.version_key.version,
+                        "relation": n.relation.to_lowercase(),
+                    }))
+                    .collect();
+                dependencies["crates"] = json!(listed);
+            }
+            output["dependencies"] = dependencies;
+        }
+        Err(e) => output["dependencies_error"] = json!(e.to_string()),
+    }
+
+    let Some(repo) = package.source_repo() else {
+        output["note"] = json!("deps.dev links no source repository to this version, so there is no Scorecard.");
+        return to_result(&output);
+    };
+    match depsdev::project(&state.client, &state.cache, repo).await {
+        Ok(project) => {
+            output["project"] = json!({
+                "id": project.project_key.id,
+                "stars": project.stars_count,
+                "forks": project.forks_count,
+                "open_issues": project.open_issues_count,
+                "license": project.license,
+            });
+            match project.scorecard {
+                Some(mut scorecard) => {
+                    // Weakest practices first; -1 (not run) after the scored ones
+                    scorecard.checks.sort_by_key(|c| (c.score < 0, c.score));
+                    output["scorecard"] = json!(scorecard);
+                }
+                None => output["note"] = json!(format!("OpenSSF Scorecard has not scored {repo}.")),
+            }
+        }
+        Err(e) => output["project_error"] = json!(format!("{repo}: {e}")),
+    }
+
+    to_result(&output)
+}
+
+fn to_result(output: &serde_json::Value) -> Result<CallToolResult, ErrorData> {
+    let json = serde_json::to_string_pretty(output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
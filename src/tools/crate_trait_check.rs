@@ -0,0 +1,397 @@
+use std::collections::HashSet;
+
+use rmcp::{ErrorData, model::{CallToolResult, Content}};
+use serde::Deserialize;
+use rmcp::schemars::{self, JsonSchema};
+use serde_json::{Value, json};
+
+use super::{AppState, docs_not_found_error};
+use crate::docsrs::{find_item_id, id_to_string, impl_header, Item, RustdocJson};
+use crate::docsrs::parser::type_to_string;
+use crate::error::ErrorCode;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CrateTraitCheckParams {
+    /// Crate name
+    pub name: String,
+    /// Version string. Defaults to latest stable.
+    pub version: Option<String>,
+    /// Type to check (e.g. "rmcp::transport::TokioChildProcess")
+    pub type_path: String,
+    /// Trait to look for (e.g. "Send", "serde::Serialize", "IntoTransport")
+    pub trait_path: String,
+}
+
+pub async fn execute(state: &AppState, params: CrateTraitCheckParams) -> Result<CallToolResult, ErrorData> {
+    let name = &params.name;
+    let version = state.resolve_version(name, params.version.as_deref()).await
+        .map_err(ErrorData::from)?;
+
+    let doc = match state.rustdoc(name, &version).await {
+        Ok(d) => d,
+        Err(crate::error::DocsError::DocsNotFound { .. }) => {
+            return Err(docs_not_found_error(state, name, &version).await);
+        }
+        Err(e) => return Err(ErrorData::from(e)),
+    };
+
+    let type_path = params.type_path.as_str();
+    let type_id = find_item_id(&doc, type_path).ok_or_else(|| ErrorData::invalid_params(
+        format!("Type '{type_path}' not found in {name} {version}. Use crate_item_list to find the path."),
+        ErrorCode::ItemNotFound.data(),
+    ))?;
+    let item = doc.index.get(&type_id).ok_or_else(|| ErrorData::invalid_params(
+        format!("Type '{type_path}' is re-exported from another crate; check it in the defining crate."),
+        None,
+    ))?;
+
+    let check = check_trait(&doc, item, &params.trait_path);
+    let output = json!({
+        "name": name,
+        "version": version,
+        "type_path": type_path,
+        "trait_path": params.trait_path,
+        "verdict": check.verdict,
+        "matches": check.matches,
+        "note": "Bounds are checked syntactically against the traits rustdoc lists for the type; \
+                 a \"conditional\" verdict means the impl applies only when its constraints hold.",
+    });
+
+    let json = serde_json::to_string_pretty(&output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
+
+/// Result of checking one type against one trait.
+pub(crate) struct TraitCheck {
+    /// "yes", "conditional", or "no"
+    pub verdict: &'static str,
+    pub matches: Vec<Value>,
+}
+
+/// Find every impl through which the type `item` implements `trait_path`.
+///
+/// Considers the impls rustdoc attaches to the type (direct, generic, and the blanket impls
+/// rustdoc already resolved), plus blanket impls of the trait elsewhere in the crate
+/// (`impl<T: Bound> Trait for T`) whose bounds the type meets.
+pub(crate) fn check_trait(doc: &RustdocJson, item: &Item, trait_path: &str) -> TraitCheck {
+    let own_impls = impl_ids(item);
+    let implemented = implemented_traits(doc, &own_impls);
+    let mut seen: HashSet<String> = HashSet::new();
+    // Traits rustdoc already resolved a blanket impl of for this type
+    let mut resolved_blankets: HashSet<String> = HashSet::new();
+    let mut matches: Vec<Value> = vec![];
+
+    for impl_id in &own_impls {
+        let Some(impl_inner) = doc.index.get(impl_id).and_then(|i| i.inner_for("impl")) else { continue };
+        if !trait_matches(doc, impl_inner, trait_path) { continue; }
+        seen.insert(impl_id.clone());
+        let blanket = impl_inner.get("blanket_impl").is_some_and(|b| !b.is_null());
+        if blanket && let Some(key) = impl_trait(impl_inner).map(|t| trait_key(doc, t)) {
+            resolved_blankets.insert(key);
+        }
+        let constraints = impl_constraints(impl_inner);
+        let via = if blanket {
+            "blanket"
+        } else if constraints.is_empty() {
+            "direct"
+        } else {
+            "generic"
+        };
+        matches.push(describe_match(impl_id, impl_inner, via, constraints, vec![]));
+    }
+
+    // Blanket impls the type's impl list doesn't mention
+    for (impl_id, impl_item) in &doc.index {
+        if seen.contains(impl_id) { continue; }
+        let Some(impl_inner) = impl_item.inner_for("impl") else { continue };
+        if impl_inner.get("is_synthetic").and_then(|v| v.as_bool()).unwrap_or(false) { continue; }
+        if !trait_matches(doc, impl_inner, trait_path) { continue; }
+        // The generic original of a blanket impl already reported through the type
+        if impl_trait(impl_inner).is_some_and(|t| resolved_blankets.contains(&trait_key(doc, t))) { continue; }
+        let Some(param) = impl_inner.get("for").and_then(|f| f.get("generic")).and_then(|g| g.as_str()) else {
+            continue;
+        };
+        let bounds = param_bounds(impl_inner, param);
+        let unmet: Vec<String> = bounds.iter()
+            .filter(|(_, bound)| !implemented.contains(&trait_key(doc, bound)))
+            .map(|(rendered, _)| rendered.clone())
+            .collect();
+        let bounds: Vec<String> = bounds.into_iter().map(|(rendered, _)| rendered).collect();
+        if !unmet.is_empty() {
+            // Report near misses so callers can see what is missing
+            matches.push(describe_match(impl_id, impl_inner, "blanket_unmet", bounds, unmet));
+            continue;
+        }
+        matches.push(describe_match(impl_id, impl_inner, "blanket", bounds, vec![]));
+    }
+
+    let applicable: Vec<&Value> = matches.iter().filter(|m| m["via"] != "blanket_unmet").collect();
+    let verdict = if applicable.iter().any(|m| m["via"] == "direct" || m["via"] == "blanket") {
+        "yes"
+    } else if !applicable.is_empty() {
+        "conditional"
+    } else {
+        "no"
+    };
+    TraitCheck { verdict, matches }
+}
+
+fn describe_match(impl_id: &str, impl_inner: &Value, via: &str, constraints: Vec<String>, unmet: Vec<String>) -> Value {
+    json!({
+        "impl_id": impl_id,
+        "via": via,
+        "header": impl_header(impl_inner),
+        "constraints": constraints,
+        "unmet_bounds": unmet,
+    })
+}
+
+/// Impl IDs listed on a struct/enum/union/primitive item.
+pub(crate) fn impl_ids(item: &Item) -> Vec<String> {
+    ["struct", "enum", "union", "primitive"].iter()
+        .find_map(|k| item.inner_for(k).and_then(|i| i.get("impls")).and_then(|v| v.as_array()))
+        .map(|ids| ids.iter().filter_map(id_to_string).collect())
+        .unwrap_or_default()
+}
+
+/// Keys (see [`trait_key`]) of every trait implemented by the given impls, auto traits included.
+fn implemented_traits(doc: &RustdocJson, impl_ids: &[String]) -> HashSet<String> {
+    impl_ids.iter()
+        .filter_map(|id| doc.index.get(id)?.inner_for("impl"))
+        .filter_map(impl_trait)
+        .map(|t| trait_key(doc, t))
+        .collect()
+}
+
+/// The trait an impl implements, or `None` for an inherent impl.
+fn impl_trait(impl_inner: &Value) -> Option<&Value> {
+    impl_inner.get("trait").filter(|t| !t.is_null())
+}
+
+/// Identity of a trait path within one document: its item ID, or the path as written
+/// when rustdoc gives none.
+fn trait_key(doc: &RustdocJson, trait_ref: &Value) -> String {
+    trait_ref.get("id").and_then(id_to_string).unwrap_or_else(|| trait_full_path(doc, trait_ref))
+}
+
+/// Fully-qualified path of a trait reference, from the `paths` table when it is listed
+/// there and as written in the impl otherwise.
+fn trait_full_path(doc: &RustdocJson, trait_ref: &Value) -> String {
+    match trait_ref.get("id").and_then(id_to_string).and_then(|id| doc.paths.get(&id)) {
+        Some(entry) => entry.full_path(),
+        None => trait_ref.get("path").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+    }
+}
+
+/// Whether the impl's trait is `trait_path`. A bare name matches any trait with that name;
+/// a qualified one must match the trait's path segment by segment, from the end, so
+/// `io::Write` matches `std::io::Write` but not `core::fmt::Write`.
+fn trait_matches(doc: &RustdocJson, impl_inner: &Value, trait_path: &str) -> bool {
+    let Some(t) = impl_trait(impl_inner) else { return false };
+    let full_path = trait_full_path(doc, t);
+    let (wanted, actual) = (segments(trait_path), segments(&full_path));
+    if wanted.len() == 1 || actual.len() == 1 {
+        // Nothing to compare beyond the name
+        return wanted.last() == actual.last();
+    }
+    let common = wanted.len().min(actual.len());
+    let (wanted, actual) = (&wanted[wanted.len() - common..], &actual[actual.len() - common..]);
+    // std re-exports core and alloc, so their crate names are interchangeable
+    let std_crate = |s: &str| matches!(s, "std" | "core" | "alloc");
+    wanted.iter().zip(actual).all(|(w, a)| w == a || (std_crate(w) && std_crate(a)))
+}
+
+/// Path segments with generic args dropped: "convert::From<io::Error>" → ["convert", "From"].
+fn segments(path: &str) -> Vec<&str> {
+    let base = path.split('<').next().unwrap_or(path);
+    base.split("::").collect()
+}
+
+/// Every bound and where clause on the impl, rendered (e.g. `T: Clone`).
+fn impl_constraints(impl_inner: &Value) -> Vec<String> {
+    let header = impl_header(impl_inner);
+    let mut constraints: Vec<String> = impl_inner.get("generics")
+        .and_then(|g| g.get("params"))
+        .and_then(|p| p.as_array())
+        .map(|ps| ps.iter()
+            .filter_map(|p| {
+                let name = p.get("name")?.as_str()?;
+                let bounds = p.get("kind")?.get("type")?.get("bounds")?.as_array()?;
+                let rendered = crate::docsrs::parser::bounds_to_string(bounds);
+                (!rendered.is_empty()).then(|| format!("{name}: {rendered}"))
+            })
+            .collect())
+        .unwrap_or_default();
+    if let Some((_, clauses)) = header.split_once("\nwhere\n") {
+        constraints.extend(clauses.split(",\n").map(|c| c.trim().to_string()));
+    }
+    constraints
+}
+
+/// Trait bounds on generic param `param`, rendered and as trait references, from its
+/// declaration and the where clause. `?Sized` and lifetime bounds are skipped since they
+/// never exclude a type.
+fn param_bounds<'a>(impl_inner: &'a Value, param: &str) -> Vec<(String, &'a Value)> {
+    let generics = impl_inner.get("generics");
+    let mut bound_lists: Vec<&Vec<Value>> = vec![];
+    if let Some(params) = generics.and_then(|g| g.get("params")).and_then(|p| p.as_array()) {
+        bound_lists.extend(params.iter()
+            .filter(|p| p.get("name").and_then(|n| n.as_str()) == Some(param))
+            .filter_map(|p| p.get("kind")?.get("type")?.get("bounds")?.as_array()));
+    }
+    if let Some(preds) = generics.and_then(|g| g.get("where_predicates")).and_then(|p| p.as_array()) {
+        bound_lists.extend(preds.iter()
+            .filter_map(|p| p.get("bound_predicate"))
+            .filter(|bp| bp.get("type").and_then(|t| t.get("generic")).and_then(|g| g.as_str()) == Some(param))
+            .filter_map(|bp| bp.get("bounds")?.as_array()));
+    }
+    bound_lists.into_iter()
+        .flatten()
+        .filter_map(|b| b.get("trait_bound"))
+        .filter(|tb| tb.get("modifier").and_then(|m| m.as_str()) != Some("maybe"))
+        .filter_map(|tb| tb.get("trait").map(|t| (type_to_string(t), t)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_doc() -> RustdocJson {
+        let path = |p: &str, id: u32| json!({"path": p, "id": id, "args": null});
+        let no_generics = json!({"params": [], "where_predicates": []});
+        serde_json::from_value(json!({
+            "format_version": 57,
+            "root": 0,
+            "index": {
+                "1": {"id": 1, "name": "Widget", "inner": {"struct": {
+                    "generics": no_generics.clone(), "kind": {"unit": null}, "impls": [2, 3]
+                }}},
+                "2": {"id": 2, "inner": {"impl": {
+                    "generics": no_generics.clone(), "trait": path("Clone", 90),
+                    "for": {"resolved_path": path("Widget", 1)}, "items": [], "is_synthetic": false, "blanket_impl": null
+                }}},
+                "3": {"id": 3, "inner": {"impl": {
+                    "generics": no_generics.clone(), "trait": path("Send", 91),
+                    "for": {"resolved_path": path("Widget", 1)}, "items": [], "is_synthetic": true, "blanket_impl": null
+                }}},
+                // impl<T: Clone + Send> Describe for T
+                "4": {"id": 4, "inner": {"impl": {
+                    "generics": {"params": [{"name": "T", "kind": {"type": {
+                        "bounds": [
+                            {"trait_bound": {"trait": path("Clone", 90), "generic_params": [], "modifier": "none"}},
+                            {"trait_bound": {"trait": path("Send", 91), "generic_params": [], "modifier": "none"}}
+                        ],
+                        "default": null, "is_synthetic": false
+                    }}}], "where_predicates": []},
+                    "trait": path("Describe", 5), "for": {"generic": "T"}, "items": [], "is_synthetic": false, "blanket_impl": null
+                }}},
+                // impl<T> Render for T where T: Display
+                "6": {"id": 6, "inner": {"impl": {
+                    "generics": {"params": [{"name": "T", "kind": {"type": {"bounds": [], "default": null, "is_synthetic": false}}}],
+                        "where_predicates": [{"bound_predicate": {"type": {"generic": "T"}, "bounds": [
+                            {"trait_bound": {"trait": path("Display", 92), "generic_params": [], "modifier": "none"}}
+                        ], "generic_params": []}}]},
+                    "trait": path("Render", 7), "for": {"generic": "T"}, "items": [], "is_synthetic": false, "blanket_impl": null
+                }}}
+            },
+            "paths": {
+                "1": {"crate_id": 0, "path": ["demo", "Widget"], "kind": "struct"}
+            }
+        })).unwrap()
+    }
+
+    /// `Widget` implementing `std::io::Write`, plus `impl<T: Clone> Describe for T` both
+    /// generically and as the copy rustdoc resolves onto `Widget`.
+    fn make_io_doc() -> RustdocJson {
+        let path = |p: &str, id: u32| json!({"path": p, "id": id, "args": null});
+        let no_generics = json!({"params": [], "where_predicates": []});
+        let clone_bound = json!({"params": [{"name": "T", "kind": {"type": {
+            "bounds": [{"trait_bound": {"trait": path("Clone", 90), "generic_params": [], "modifier": "none"}}],
+            "default": null, "is_synthetic": false
+        }}}], "where_predicates": []});
+        serde_json::from_value(json!({
+            "format_version": 57,
+            "root": 0,
+            "index": {
+                "1": {"id": 1, "name": "Widget", "inner": {"struct": {
+                    "generics": no_generics.clone(), "kind": {"unit": null}, "impls": [2, 3, 4]
+                }}},
+                "2": {"id": 2, "inner": {"impl": {
+                    "generics": no_generics.clone(), "trait": path("Write", 80),
+                    "for": {"resolved_path": path("Widget", 1)}, "items": [], "is_synthetic": false, "blanket_impl": null
+                }}},
+                "3": {"id": 3, "inner": {"impl": {
+                    "generics": no_generics.clone(), "trait": path("Clone", 90),
+                    "for": {"resolved_path": path("Widget", 1)}, "items": [], "is_synthetic": false, "blanket_impl": null
+                }}},
+                "4": {"id": 4, "inner": {"impl": {
+                    "generics": clone_bound.clone(), "trait": path("Describe", 5),
+                    "for": {"resolved_path": path("Widget", 1)}, "items": [], "is_synthetic": false,
+                    "blanket_impl": {"generic": "T"}
+                }}},
+                "6": {"id": 6, "inner": {"impl": {
+                    "generics": clone_bound, "trait": path("Describe", 5),
+                    "for": {"generic": "T"}, "items": [], "is_synthetic": false, "blanket_impl": null
+                }}}
+            },
+            "paths": {
+                "1": {"crate_id": 0, "path": ["demo", "Widget"], "kind": "struct"},
+                "5": {"crate_id": 0, "path": ["demo", "Describe"], "kind": "trait"},
+                "80": {"crate_id": 1, "path": ["std", "io", "Write"], "kind": "trait"},
+                "90": {"crate_id": 1, "path": ["core", "clone", "Clone"], "kind": "trait"}
+            }
+        })).unwrap()
+    }
+
+    #[test]
+    fn direct_impls_are_found() {
+        let doc = make_doc();
+        let item = doc.index.get("1").unwrap();
+        let check = check_trait(&doc, item, "std::clone::Clone");
+        assert_eq!(check.verdict, "yes");
+        assert_eq!(check.matches[0]["via"], "direct");
+    }
+
+    #[test]
+    fn blanket_impls_apply_when_bounds_are_met() {
+        let doc = make_doc();
+        let item = doc.index.get("1").unwrap();
+        let check = check_trait(&doc, item, "Describe");
+        assert_eq!(check.verdict, "yes");
+        assert_eq!(check.matches[0]["via"], "blanket");
+        assert_eq!(check.matches[0]["impl_id"], "4");
+    }
+
+    #[test]
+    fn unmet_blanket_bounds_are_reported() {
+        let doc = make_doc();
+        let item = doc.index.get("1").unwrap();
+        let check = check_trait(&doc, item, "Render");
+        assert_eq!(check.verdict, "no");
+        assert_eq!(check.matches[0]["unmet_bounds"], json!(["Display"]));
+    }
+
+    #[test]
+    fn qualified_trait_paths_compare_every_segment() {
+        let doc = make_io_doc();
+        let item = doc.index.get("1").unwrap();
+        assert_eq!(check_trait(&doc, item, "io::Write").verdict, "yes");
+        assert_eq!(check_trait(&doc, item, "std::io::Write").verdict, "yes");
+        assert_eq!(check_trait(&doc, item, "fmt::Write").verdict, "no");
+        assert_eq!(check_trait(&doc, item, "core::fmt::Write").verdict, "no");
+        // std re-exports core
+        assert_eq!(check_trait(&doc, item, "std::clone::Clone").verdict, "yes");
+    }
+
+    #[test]
+    fn resolved_blanket_impls_are_reported_once() {
+        let doc = make_io_doc();
+        let item = doc.index.get("1").unwrap();
+        let check = check_trait(&doc, item, "Describe");
+        assert_eq!(check.verdict, "yes");
+        assert_eq!(check.matches.len(), 1);
+        assert_eq!(check.matches[0]["impl_id"], "4");
+    }
+}
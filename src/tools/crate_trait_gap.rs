@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+
+use rmcp::{ErrorData, model::{CallToolResult, Content}};
+use serde::Deserialize;
+use rmcp::schemars::{self, JsonSchema};
+use serde_json::{Value, json};
+
+use super::{AppState, docs_not_found_error};
+use super::crate_trait_check::check_trait;
+use crate::docsrs::{find_item_id, id_to_string, impl_skeleton, trait_members, Item, RustdocJson, TraitMember};
+use crate::error::ErrorCode;
+use crate::sparse_index::{find_latest_stable, find_matching, DepKind};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CrateTraitGapParams {
+    /// Crate defining the type
+    pub name: String,
+    /// Version string. Defaults to latest stable.
+    pub version: Option<String>,
+    /// Type to implement the trait for (e.g. "rmcp::transport::TokioChildProcess")
+    pub type_path: String,
+    /// Trait to implement (e.g. "rmcp::transport::Transport")
+    pub trait_path: String,
+    /// Crate defining the trait, when it is not `name` (e.g. "serde"). Uses the version `name`
+    /// depends on, or the latest stable version when `name` does not depend on it.
+    pub trait_crate: Option<String>,
+}
+
+pub async fn execute(state: &AppState, params: CrateTraitGapParams) -> Result<CallToolResult, ErrorData> {
+    let name = &params.name;
+    let version = state.resolve_version(name, params.version.as_deref()).await
+        .map_err(ErrorData::from)?;
+
+    let doc = match state.rustdoc(name, &version).await {
+        Ok(d) => d,
+        Err(crate::error::DocsError::DocsNotFound { .. }) => {
+            return Err(docs_not_found_error(state, name, &version).await);
+        }
+        Err(e) => return Err(ErrorData::from(e)),
+    };
+
+    let type_path = params.type_path.as_str();
+    let type_id = find_item_id(&doc, type_path).ok_or_else(|| ErrorData::invalid_params(
+        format!("Type '{type_path}' not found in {name} {version}. Use crate_item_list to find the path."),
+        ErrorCode::ItemNotFound.data(),
+    ))?;
+    let type_item = doc.index.get(&type_id).ok_or_else(|| ErrorData::invalid_params(
+        format!("Type '{type_path}' is re-exported from another crate; check it in the defining crate."),
+        None,
+    ))?;
+
+    let (trait_crate, trait_version, trait_requirement, trait_doc) = match params.trait_crate.as_deref().filter(|c| *c != name.as_str()) {
+        None => (name.clone(), version.clone(), None, doc.clone()),
+        Some(trait_crate) => {
+            let (trait_version, requirement) = dependency_version(state, name, &version, trait_crate).await
+                .map_err(ErrorData::from)?;
+            let trait_doc = match state.rustdoc(trait_crate, &trait_version).await {
+                Ok(d) => d,
+                Err(crate::error::DocsError::DocsNotFound { .. }) => {
+                    return Err(docs_not_found_error(state, trait_crate, &trait_version).await);
+                }
+                Err(e) => return Err(ErrorData::from(e)),
+            };
+            (trait_crate.to_string(), trait_version, requirement, trait_doc)
+        }
+    };
+
+    let trait_path = params.trait_path.as_str();
+    let trait_item = find_item_id(&trait_doc, trait_path)
+        .and_then(|id| trait_doc.index.get(&id))
+        .filter(|i| i.inner_for("trait").is_some())
+        .ok_or_else(|| ErrorData::invalid_params(
+            format!("Trait '{trait_path}' not found in {trait_crate} {trait_version}. \
+                     Pass trait_crate if the trait is defined in another crate; \
+                     std traits are not available."),
+            ErrorCode::ItemNotFound.data(),
+        ))?;
+
+    let check = check_trait(&doc, type_item, trait_path);
+    let applicable: Vec<&Value> = check.matches.iter().filter(|m| m["via"] != "blanket_unmet").collect();
+
+    // Trait items each applicable impl already defines, by name
+    let mut defined: HashMap<String, String> = HashMap::new();
+    for m in &applicable {
+        let impl_id = m["impl_id"].as_str().unwrap_or_default();
+        for member in impl_members(&doc, impl_id) {
+            if let Some(member_name) = &member.name {
+                defined.entry(member_name.clone()).or_insert_with(|| impl_id.to_string());
+            }
+        }
+    }
+
+    let members = trait_members(&trait_doc, trait_item);
+    let mut satisfied = vec![];
+    let mut missing = vec![];
+    let mut defaults = vec![];
+    for member in &members {
+        if let Some(impl_id) = defined.get(&member.name) {
+            satisfied.push(json!({ "name": member.name, "kind": member.kind, "impl_id": impl_id }));
+        } else if member.required {
+            missing.push(member.to_json());
+        } else {
+            defaults.push(member.to_json());
+        }
+    }
+
+    let type_name = type_item.name.as_deref().unwrap_or(type_path);
+    let type_generics = ["struct", "enum", "union"].iter()
+        .find_map(|k| type_item.inner_for(k))
+        .and_then(|i| i.get("generics"));
+    let missing_members: Vec<&TraitMember> = members.iter()
+        .filter(|m| m.required && !defined.contains_key(&m.name))
+        .collect();
+    // A trait the type already implements needs no new impl block
+    let skeleton = (check.verdict != "yes")
+        .then(|| impl_skeleton(trait_path, trait_item, type_name, type_generics, &missing_members));
+
+    let output = json!({
+        "name": name,
+        "version": version,
+        "type_path": type_path,
+        "trait_path": trait_path,
+        "trait_crate": trait_crate,
+        "trait_version": trait_version,
+        "trait_requirement": trait_requirement,
+        "verdict": check.verdict,
+        "impls": applicable,
+        "satisfied": satisfied,
+        "missing": missing,
+        "provided_defaults": defaults,
+        "skeleton": skeleton,
+    });
+
+    let json = serde_json::to_string_pretty(&output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
+
+/// Items defined inside the impl block `impl_id`.
+fn impl_members<'a>(doc: &'a RustdocJson, impl_id: &str) -> Vec<&'a Item> {
+    doc.index.get(impl_id)
+        .and_then(|i| i.inner_for("impl"))
+        .and_then(|i| i.get("items"))
+        .and_then(|v| v.as_array())
+        .map(|ids| ids.iter().filter_map(|id| doc.index.get(&id_to_string(id)?)).collect())
+        .unwrap_or_default()
+}
+
+/// The version of `dependency` that `name` `version` builds against: the newest release
+/// matching its requirement, which is returned alongside. Falls back to the latest stable
+/// release when `name` does not depend on it.
+async fn dependency_version(state: &AppState, name: &str, version: &str, dependency: &str) -> crate::error::Result<(String, Option<String>)> {
+    let lines = state.fetch_index(name).await?;
+    // Normal dependencies first; a build or dev dependency can pin another version
+    let requirement = lines.iter()
+        .find(|l| l.vers == version)
+        .into_iter()
+        .flat_map(|l| &l.deps)
+        .filter(|d| d.package.as_deref().unwrap_or(&d.name) == dependency)
+        .min_by_key(|d| d.kind.as_ref().is_some_and(|k| *k != DepKind::Normal))
+        .map(|d| d.req.clone());
+    let dep_lines = state.fetch_index(dependency).await?;
+    let matched = requirement.as_deref().and_then(|req| find_matching(&dep_lines, req));
+    let chosen = matched.or_else(|| find_latest_stable(&dep_lines))
+        .ok_or_else(|| crate::error::DocsError::NoStableVersion(dependency.to_string()))?;
+    Ok((chosen.vers.clone(), requirement.filter(|_| matched.is_some())))
+}
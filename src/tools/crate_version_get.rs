@@ -11,6 +11,17 @@ struct PublisherOutput {
     name: Option<String>,
 }
 
+#[derive(Serialize)]
+struct TrustedPublishingOutput {
+    provider: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    repository: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    commit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    run_url: Option<String>,
+}
+
 #[derive(Serialize)]
 struct VersionGetOutput {
     num: String,
@@ -32,6 +43,11 @@ struct VersionGetOutput {
     yank_message: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     published_by: Option<PublisherOutput>,
+    /// "trusted_publishing" (a CI workflow) or "api_token" (a user's token); absent when unknown
+    #[serde(skip_serializing_if = "Option::is_none")]
+    published_via: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    trusted_publishing: Option<TrustedPublishingOutput>,
     created_at: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     checksum: Option<String>,
@@ -51,9 +67,23 @@ pub async fn execute(state: &AppState, params: CrateVersionGetParams) -> Result<
     let name = &params.name;
     let version = &params.version;
 
+    // From the API, not the database dump: the dump records neither who published a
+    // version nor how, so only the API can tell the two apart
     let client = crate::cratesio::CratesIoClient::new(&state.client, &state.cache);
     let v = client.get_version(name, version).await
-        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+        .map_err(ErrorData::from)?;
+
+    let published_via = match (&v.trustpub_data, &v.published_by) {
+        (Some(_), _) => Some("trusted_publishing"),
+        (None, Some(_)) => Some("api_token"),
+        (None, None) => None,
+    };
+    let trusted_publishing = v.trustpub_data.map(|t| TrustedPublishingOutput {
+        repository: t.repository().map(str::to_string),
+        run_url: t.run_url(),
+        commit: t.sha,
+        provider: t.provider,
+    });
 
     let output = VersionGetOutput {
         num: v.num,
@@ -67,6 +97,8 @@ pub async fn execute(state: &AppState, params: CrateVersionGetParams) -> Result<
         yanked: v.yanked,
         yank_message: v.yank_message,
         published_by: v.published_by.map(|p| PublisherOutput { login: p.login, name: p.name }),
+        published_via,
+        trusted_publishing,
         created_at: v.created_at,
         checksum: v.checksum,
         lib_links: v.lib_links,
@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 use rmcp::schemars::{self, JsonSchema};
 use serde_json::json;
 use semver::Version;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
 
 use super::AppState;
 
@@ -14,6 +16,9 @@ struct VersionEntry {
     rust_version: Option<String>,
     features: Vec<String>,
     dep_count: usize,
+    /// Publication time from crates.io; only with `cadence`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    published_at: Option<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -26,10 +31,16 @@ pub struct CrateVersionsListParams {
     pub include_prerelease: Option<bool>,
     /// Filter by semver prefix or substring (e.g. "1.0")
     pub search: Option<String>,
+    /// Only return versions whose declared rust_version (MSRV) is at most this toolchain
+    /// (e.g. "1.70"). Versions that do not declare a rust_version are excluded.
+    pub max_rust_version: Option<String>,
     /// Results per page (default: 30, max: 100)
     pub per_page: Option<usize>,
     /// Page number, 1-indexed (default: 1)
     pub page: Option<usize>,
+    /// Add a `cadence` summary (releases per year, median days between releases, days since
+    /// the last release) and each version's publication date. Costs one crates.io request.
+    pub cadence: Option<bool>,
 }
 
 pub async fn execute(state: &AppState, params: CrateVersionsListParams) -> Result<CallToolResult, ErrorData> {
@@ -37,8 +48,37 @@ pub async fn execute(state: &AppState, params: CrateVersionsListParams) -> Resul
     let include_yanked = params.include_yanked.unwrap_or(false);
     let include_prerelease = params.include_prerelease.unwrap_or(false);
 
+    let max_rust_version = match params.max_rust_version.as_deref() {
+        Some(s) => Some(parse_rust_version(s).ok_or_else(|| ErrorData::invalid_params(
+            format!("Invalid max_rust_version '{s}'. Expected a toolchain version like \"1.70\" or \"1.70.0\"."),
+            None,
+        ))?),
+        None => None,
+    };
+
     let lines = state.fetch_index(name).await
-        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+        .map_err(ErrorData::from)?;
+
+    // Release dates aren't in the index; crates.io has them per version
+    let published: HashMap<String, DateTime<Utc>> = if params.cadence.unwrap_or(false) {
+        let client = crate::cratesio::CratesIoClient::new(&state.client, &state.cache)
+            .with_dump(state.dump_db.as_ref());
+        let versions = client.get_versions(name).await
+            .map_err(ErrorData::from)?;
+        versions.versions.iter()
+            .filter_map(|v| Some((v.num.clone(), DateTime::parse_from_rfc3339(&v.created_at).ok()?.to_utc())))
+            .collect()
+    } else {
+        HashMap::new()
+    };
+    // Cadence counts every stable release, whatever the filters below keep
+    let cadence = (!published.is_empty()).then(|| {
+        let dates: Vec<DateTime<Utc>> = lines.iter()
+            .filter(|l| !l.vers.contains('-'))
+            .filter_map(|l| published.get(&l.vers).copied())
+            .collect();
+        release_cadence(&dates, Utc::now())
+    });
 
     let mut versions: Vec<_> = lines.into_iter()
         .filter(|l| {
@@ -49,6 +89,10 @@ pub async fn execute(state: &AppState, params: CrateVersionsListParams) -> Resul
                     return false;
                 }
             }
+            if let Some(ref max) = max_rust_version {
+                let msrv = l.rust_version.as_deref().and_then(parse_rust_version);
+                if msrv.is_none_or(|m| m > *max) { return false; }
+            }
             true
         })
         .collect();
@@ -61,6 +105,10 @@ pub async fn execute(state: &AppState, params: CrateVersionsListParams) -> Resul
     });
 
     let total = versions.len();
+    // Versions are sorted newest first, so the first survivor is the newest usable one
+    let newest_compatible = max_rust_version.as_ref()
+        .and_then(|_| versions.first())
+        .map(|l| json!({ "version": l.vers, "rust_version": l.rust_version }));
     let per_page = params.per_page.unwrap_or(30).min(100).max(1);
     let page = params.page.unwrap_or(1).max(1);
     let start = (page - 1) * per_page;
@@ -68,9 +116,6 @@ pub async fn execute(state: &AppState, params: CrateVersionsListParams) -> Resul
     let versions = &versions[..per_page.min(versions.len())];
 
     let items: Vec<VersionEntry> = versions.iter().map(|l| {
-        let normal_deps = l.deps.iter().filter(|d| {
-            d.kind.as_ref().map(|k| matches!(k, crate::sparse_index::DepKind::Normal)).unwrap_or(true)
-        }).count();
         // Emit feature names only (not their dep-enable lists) to keep output compact.
         // The full feature map for large crates (tokio, serde) would be enormous across
         // many versions and is rarely needed by an LLM.
@@ -82,11 +127,12 @@ pub async fn execute(state: &AppState, params: CrateVersionsListParams) -> Resul
             yanked: l.yanked,
             rust_version: l.rust_version.clone(),
             features: feature_names,
-            dep_count: normal_deps,
+            dep_count: l.normal_dep_count(),
+            published_at: published.get(&l.vers).map(|d| d.to_rfc3339()),
         }
     }).collect();
 
-    let output = json!({
+    let mut output = json!({
         "name": name,
         "total": total,
         "page": page,
@@ -94,8 +140,86 @@ pub async fn execute(state: &AppState, params: CrateVersionsListParams) -> Resul
         "count": items.len(),
         "versions": items,
     });
+    if let Some(cadence) = cadence {
+        output["cadence"] = cadence;
+    }
+    if let Some(max) = &params.max_rust_version {
+        output["max_rust_version"] = json!(max);
+        output["newest_compatible"] = newest_compatible.unwrap_or(serde_json::Value::Null);
+    }
 
     let json = serde_json::to_string_pretty(&output)
         .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
     Ok(CallToolResult::success(vec![Content::text(json)]))
 }
+
+/// Maintenance cadence of releases published at `dates`, as of `now`.
+pub(crate) fn release_cadence(dates: &[DateTime<Utc>], now: DateTime<Utc>) -> serde_json::Value {
+    let mut dates = dates.to_vec();
+    dates.sort();
+    let (Some(first), Some(last)) = (dates.first().copied(), dates.last().copied()) else {
+        return json!({ "releases": 0 });
+    };
+
+    let mut gaps: Vec<i64> = dates.windows(2).map(|w| (w[1] - w[0]).num_days()).collect();
+    gaps.sort_unstable();
+    let median_gap = match gaps.len() {
+        0 => None,
+        n if n % 2 == 1 => Some(gaps[n / 2] as f64),
+        n => Some((gaps[n / 2 - 1] + gaps[n / 2]) as f64 / 2.0),
+    };
+    // Lifetime rate; a crate younger than a year is measured over a full year
+    let years = ((now - first).num_days() as f64 / 365.25).max(1.0);
+
+    json!({
+        "releases": dates.len(),
+        "first_release": first.to_rfc3339(),
+        "last_release": last.to_rfc3339(),
+        "days_since_last_release": (now - last).num_days(),
+        "releases_last_365_days": dates.iter().filter(|d| (now - **d).num_days() < 365).count(),
+        "releases_per_year": ((dates.len() as f64 / years) * 10.0).round() / 10.0,
+        "median_days_between_releases": median_gap,
+    })
+}
+
+/// Parse a toolchain or `rust-version` string ("1.70", "1.70.0") into a comparable version.
+pub(crate) fn parse_rust_version(s: &str) -> Option<Version> {
+    let s = s.trim();
+    match s.split('.').count() {
+        1 => Version::parse(&format!("{s}.0.0")).ok(),
+        2 => Version::parse(&format!("{s}.0")).ok(),
+        _ => Version::parse(s).ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn release_cadence_summarizes_gaps() {
+        let at = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().to_utc();
+        let dates = [at("2022-01-01T00:00:00Z"), at("2023-01-02T00:00:00Z"), at("2023-01-11T00:00:00Z"), at("2023-03-12T00:00:00Z")];
+        let cadence = release_cadence(&dates, at("2024-01-01T00:00:00Z"));
+        assert_eq!(cadence["releases"], 4);
+        assert_eq!(cadence["days_since_last_release"], 295);
+        assert_eq!(cadence["releases_last_365_days"], 3);
+        assert_eq!(cadence["releases_per_year"], 2.0);
+        // Gaps of 366, 9 and 60 days
+        assert_eq!(cadence["median_days_between_releases"], 60.0);
+
+        let single = release_cadence(&dates[..1], at("2022-06-01T00:00:00Z"));
+        assert!(single["median_days_between_releases"].is_null());
+        assert_eq!(release_cadence(&[], at("2022-06-01T00:00:00Z"))["releases"], 0);
+    }
+
+    #[test]
+    fn parse_rust_version_pads_missing_components() {
+        assert_eq!(parse_rust_version("1.70"), Some(Version::new(1, 70, 0)));
+        assert_eq!(parse_rust_version("1.70.1"), Some(Version::new(1, 70, 1)));
+        assert_eq!(parse_rust_version(" 1 "), Some(Version::new(1, 0, 0)));
+        assert!(parse_rust_version("1.x").is_none());
+        assert!(parse_rust_version("1.70") < parse_rust_version("1.70.1"));
+        assert!(parse_rust_version("1.9") < parse_rust_version("1.70"));
+    }
+}
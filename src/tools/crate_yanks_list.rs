@@ -0,0 +1,99 @@
+use rmcp::{ErrorData, model::{CallToolResult, Content}};
+use serde::{Deserialize, Serialize};
+use rmcp::schemars::{self, JsonSchema};
+use semver::Version;
+
+use super::AppState;
+use crate::osv::Advisory;
+
+/// Words in a yank message that point at a security reason.
+const SECURITY_WORDS: [&str; 6] = ["security", "vulnerab", "cve-", "rustsec", "ghsa-", "exploit"];
+
+#[derive(Serialize)]
+struct YankedVersion {
+    version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    yank_message: Option<String>,
+    published_at: String,
+    /// Advisory ids whose affected ranges include this version
+    advisories: Vec<String>,
+    /// An advisory covers this version or the yank message cites a security reason
+    security_related: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CrateYanksListParams {
+    /// Crate name
+    pub name: String,
+}
+
+pub async fn execute(state: &AppState, params: CrateYanksListParams) -> Result<CallToolResult, ErrorData> {
+    let name = &params.name;
+    let client = crate::cratesio::CratesIoClient::new(&state.client, &state.cache)
+        .with_dump(state.dump_db.as_ref());
+    let versions = client.get_versions(name).await
+        .map_err(ErrorData::from)?;
+
+    // Yank reasons still read without advisories, so a lookup failure is reported, not raised
+    let (advisories, advisory_error) = match crate::osv::advisories(&state.client, &state.cache, name).await {
+        Ok(advisories) => (advisories, None),
+        Err(e) => (vec![], Some(e.to_string())),
+    };
+
+    let yanked: Vec<YankedVersion> = versions.versions.iter()
+        .filter(|v| v.yanked)
+        .map(|v| {
+            let ids = Version::parse(&v.num).ok()
+                .map(|parsed| affecting(&advisories, name, &parsed))
+                .unwrap_or_default();
+            YankedVersion {
+                security_related: !ids.is_empty() || v.yank_message.as_deref().is_some_and(cites_security),
+                version: v.num.clone(),
+                yank_message: v.yank_message.clone(),
+                published_at: v.created_at.clone(),
+                advisories: ids,
+            }
+        })
+        .collect();
+
+    let mut output = serde_json::json!({
+        "name": name,
+        "total_versions": versions.versions.len(),
+        "yanked_count": yanked.len(),
+        "security_related_count": yanked.iter().filter(|y| y.security_related).count(),
+        "yanked": yanked,
+        "advisories": advisories,
+    });
+    if let Some(err) = advisory_error {
+        output["advisory_lookup_error"] = serde_json::json!(err);
+    }
+
+    let json = serde_json::to_string_pretty(&output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
+
+/// Ids of the non-withdrawn advisories that cover `version`.
+fn affecting(advisories: &[Advisory], name: &str, version: &Version) -> Vec<String> {
+    advisories.iter()
+        .filter(|a| a.withdrawn.is_none() && a.affects(name, version))
+        .map(|a| a.id.clone())
+        .collect()
+}
+
+fn cites_security(message: &str) -> bool {
+    let message = message.to_lowercase();
+    SECURITY_WORDS.iter().any(|w| message.contains(w))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn yank_messages_citing_security() {
+        assert!(cites_security("Yanked due to RUSTSEC-2023-0001"));
+        assert!(cites_security("Contains a soundness VULNERABILITY"));
+        assert!(!cites_security("Accidental breaking change, use 1.2.4"));
+    }
+}
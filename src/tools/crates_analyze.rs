@@ -0,0 +1,86 @@
+use rmcp::{ErrorData, model::{CallToolResult, Content}};
+use serde::Deserialize;
+use rmcp::schemars::{self, JsonSchema};
+use serde_json::json;
+
+use super::AppState;
+
+/// Crates fetched per call; owners go through the rate-limited crates.io API.
+const MAX_CRATES: usize = 20;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CratesAnalyzeParams {
+    /// Analysis to run: "dependents_skew" (which requirement ranges known dependents use for
+    /// each crate), "owner_overlap" (owners shared between the crates), or "stack" (dependencies
+    /// the crates share, with the requirement ranges each uses)
+    pub analysis: String,
+    /// Crate names to analyze (max 20)
+    pub crates: Vec<String>,
+}
+
+pub async fn execute(state: &AppState, params: CratesAnalyzeParams) -> Result<CallToolResult, ErrorData> {
+    if params.crates.is_empty() {
+        return Err(ErrorData::invalid_params("crates must name at least one crate", None));
+    }
+    let crates: Vec<String> = params.crates.iter().take(MAX_CRATES).cloned().collect();
+
+    // Fetching the index records each crate in the metadata store
+    for name in &crates {
+        state.fetch_index(name).await
+            .map_err(|e| ErrorData::internal_error(format!("{name}: {e}"), e.code().data()))?;
+    }
+
+    let internal = |e: crate::error::DocsError| ErrorData::internal_error(e.to_string(), e.code().data());
+    let result = match params.analysis.as_str() {
+        "dependents_skew" => {
+            let names = crates.clone();
+            state.with_store(move |store| {
+                let mut per_crate = serde_json::Map::new();
+                for name in &names {
+                    per_crate.insert(name.clone(), json!({
+                        "latest_stable": store.latest_stable(name)?,
+                        "requirements": store.dependents_skew(name)?,
+                    }));
+                }
+                Ok(serde_json::Value::Object(per_crate))
+            }).await.map_err(internal)?
+        }
+        "owner_overlap" => {
+            let client = crate::cratesio::CratesIoClient::new(&state.client, &state.cache);
+            for name in &crates {
+                let owners = client.get_owners(name).await
+                    .map_err(|e| ErrorData::internal_error(format!("{name}: {e}"), e.code().data()))?;
+                let owners: Vec<(String, String)> = owners.users.into_iter()
+                    .map(|o| (o.login, o.kind.unwrap_or_else(|| "user".to_string())))
+                    .collect();
+                let owned = name.clone();
+                state.with_store(move |store| store.record_owners(&owned, &owners)).await.map_err(internal)?;
+            }
+            let names = crates.clone();
+            json!(state.with_store(move |store| store.owner_overlap(&names)).await.map_err(internal)?)
+        }
+        "stack" => {
+            let names = crates.clone();
+            json!(state.with_store(move |store| store.shared_dependencies(&names)).await.map_err(internal)?)
+        }
+        other => {
+            return Err(ErrorData::invalid_params(
+                format!("Unknown analysis '{other}'. Use \"dependents_skew\", \"owner_overlap\", or \"stack\"."),
+                None,
+            ));
+        }
+    };
+
+    let output = json!({
+        "analysis": params.analysis,
+        "crates": crates,
+        "crates_in_store": state.with_store(|store| store.crate_count()).await.map_err(internal)?,
+        "note": "Built from crates this server has fetched so far, not the whole registry. \
+                 dependents_skew only counts dependents that have been looked up before.",
+        "result": result,
+    });
+
+    let json = serde_json::to_string_pretty(&output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
@@ -0,0 +1,221 @@
+use std::time::Duration;
+
+use rmcp::{ErrorData, model::{CallToolResult, Content, Tool}};
+use serde::Deserialize;
+use rmcp::schemars::{self, JsonSchema};
+use serde_json::{json, Value};
+
+use super::AppState;
+use crate::cratesio::dump::DependentsSnapshot;
+use crate::docsrs::{max_doc_mb, SummaryPolicy};
+use crate::house_rules::HouseRules;
+
+/// A sequence of tools for one kind of question.
+pub struct Workflow {
+    pub name: &'static str,
+    pub when: &'static str,
+    pub steps: &'static [&'static str],
+}
+
+pub const WORKFLOWS: &[Workflow] = &[
+    Workflow {
+        name: "discovery",
+        when: "You need a crate for a job and don't have a name yet",
+        steps: &["crate_list", "crate_get", "crate_readme_get"],
+    },
+    Workflow {
+        name: "understanding",
+        when: "You are using a crate and need its API",
+        steps: &["crate_docs_get", "crate_item_list", "crate_item_get", "crate_impls_list", "crate_impl_get"],
+    },
+    Workflow {
+        name: "due_diligence",
+        when: "You are deciding whether to depend on a crate",
+        steps: &["crate_audit_report", "owner_crates_list", "crate_maintenance_get", "crate_versions_list", "crate_yanks_list", "crate_downloads_get", "crate_dependents_list", "crate_dependencies_list", "crate_health_report", "crate_scorecard_get", "crate_build_risk"],
+    },
+];
+
+/// When to reach for each tool, one line per tool.
+pub const TOOL_GUIDE: &[(&str, &str)] = &[
+    ("crate_list", "search crates.io when you don't have a crate name yet"),
+    ("crate_get", "metadata, features and MSRV of a crate you have a name for"),
+    ("crate_readme_get", "raw README prose only"),
+    ("crate_files_list", "list files shipped in the published package (non-Rust assets, examples, build.rs)"),
+    ("crate_file_get", "read one file from the published package"),
+    ("crate_docs_get", "structured docs + module tree (falls back to README if no docs.rs build)"),
+    ("crate_module_expand", "one module's items and submodules at a time, for drilling into large crates"),
+    ("crate_item_list", "search items by name/concept when you don't have the exact path"),
+    ("crate_docs_search", "find where the full doc text discusses a topic (\"cancel safe\", \"backpressure\"), with snippets"),
+    ("multi_crate_item_search", "the same search across a list of crates, e.g. a project's dependencies"),
+    ("crate_item_get", "full item details when you have the exact fully-qualified path"),
+    ("crate_items_get_many", "several items from one crate at once, when you already know their paths"),
+    ("crate_item_compare", "two items side by side (methods, fields, trait impls) when choosing between alternatives"),
+    ("crate_impls_list", "implementors of a trait, or the traits a type implements"),
+    ("crate_impl_get", "one impl block expanded, by impl_id from crate_impls_list"),
+    ("crate_trait_check", "yes/no 'does type T implement trait X', including blanket impls"),
+    ("crate_trait_gap", "what is left to implement trait X for type T, with an impl skeleton"),
+    ("crate_ffi_list", "extern functions, exported symbols, raw-pointer signatures and repr(C) types"),
+    ("crate_errors_list", "a crate's error types, their variants and From conversions"),
+    ("crate_async_list", "async fns and future/stream-returning functions by module; is the crate async-first?"),
+    ("crate_versions_list", "release history with MSRV and yank status"),
+    ("crate_version_get", "one release's edition, license, size and publisher; was it published from CI?"),
+    ("crate_yanks_list", "why versions were yanked, and whether a security advisory covers them"),
+    ("crate_no_std_check", "whether a crate builds without std, and with which features"),
+    ("crate_dependencies_list", "what a release depends on, optionally transitively, and which of those versions have known vulnerabilities"),
+    ("crate_dependents_list", "who depends on a crate"),
+    ("crate_downloads_get", "daily download trend for the past 90 days"),
+    ("crate_health_report", "API churn across recent releases"),
+    ("crate_scorecard_get", "OpenSSF Scorecard, licenses and dependency graph size from deps.dev, for security reviews"),
+    ("crate_audit_report", "owners, adoption, advisories, yanks and repository health in one report, with flags"),
+    ("owner_crates_list", "the rest of an owner's crates, to judge an author or team you don't know"),
+    ("crate_maintenance_get", "is it actively maintained, in maintenance mode or likely abandoned, and why"),
+    ("rust_toolchain_get", "current stable/beta/nightly Rust, and how old a given MSRV is"),
+    ("crate_build_risk", "which crates in the tree run code at build time: build scripts, native links, proc macros"),
+    ("crates_analyze", "cross-crate questions (dependents' version skew, shared owners, shared dependencies)"),
+    ("cache_warm", "prefetch a project's dependencies (or a list of crates) before going offline or a long task"),
+    ("session_context", "crates, versions and default features already looked at in this session"),
+    ("server_status", "effective crates.io rate and request counts when responses seem slow or throttled"),
+    ("server_stats", "per-tool call counts, latencies, errors and cache hit rates"),
+    ("docs_mcp_help", "this guide, per tool or in full, with the server's current configuration"),
+];
+
+/// Parameter mistakes agents commonly make, as `(tool, advice)`; `"*"` applies to every tool.
+pub const GOTCHAS: &[(&str, &str)] = &[
+    ("*", "version defaults to the latest stable release; pass an exact version like \"1.0.197\", not a requirement like \"^1\""),
+    ("*", "name is the crates.io name (\"serde_json\"), while item paths use the crate's Rust name with underscores"),
+    ("crate_item_get", "item_path must be fully qualified (e.g. \"tokio::sync::Mutex\", or \"tokio::sync::Mutex::lock\" for a method); find it with crate_item_list first"),
+    ("crate_impls_list", "requires trait_path or type_path; use crate_item_list to find names"),
+    ("crate_impl_get", "pass the impl_id returned by crate_impls_list, or trait_path (plus type_path to pick one impl)"),
+    ("crate_trait_check", "type_path and trait_path must both be paths within the crate named by name"),
+    ("crate_trait_gap", "set trait_crate when the trait lives in another crate than the type"),
+    ("crate_item_compare", "each side names its own crate (name_a, name_b), so items from different crates can be compared"),
+    ("crate_version_get", "version is required and must be exact"),
+    ("crate_docs_get", "for very large crates, pass module and max_depth or use crate_module_expand instead of the whole tree"),
+    ("crate_dependents_list", "sort and min_downloads apply within the fetched page"),
+    ("*", "a DOCS_TOO_LARGE error means the crate's rustdoc JSON is too big to load whole; crate_docs_get still browses its modules and docs"),
+];
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DocsMcpHelpParams {
+    /// Narrow the guide to one tool (e.g. "crate_item_get") or one section:
+    /// "workflows", "gotchas" or "config". Default: everything.
+    pub topic: Option<String>,
+}
+
+/// The server instructions sent at initialization, built from the same tables as the tool
+/// and limited to the registered `tools`. `large_outputs` says whether oversized results
+/// are stored and linked (see [`crate::large_output`]).
+pub fn instructions(tools: &[Tool], large_outputs: bool) -> String {
+    let mut text = String::from("This server provides accurate, up-to-date access to the Rust crate ecosystem.\n\n");
+    for w in WORKFLOWS {
+        let steps = registered_steps(w, tools);
+        if !steps.is_empty() {
+            text.push_str(&format!("{} WORKFLOW: {}\n", w.name.replace('_', " ").to_uppercase(), steps.join(" → ")));
+        }
+    }
+    text.push_str("\nTool selection guide:\n");
+    for (tool, when) in TOOL_GUIDE.iter().filter(|(t, _)| tools.iter().any(|r| r.name == *t)) {
+        text.push_str(&format!("- {tool}: {when}\n"));
+    }
+    text.push_str("\nAll tools default to the latest stable version when version is not specified. \
+                   When an older version is requested, responses carry a newer_available note. ");
+    if large_outputs {
+        text.push_str("Results too large to return inline come back as an outline with resource links \
+                       to read with resources/read. ");
+    }
+    text.push_str("Call docs_mcp_help for parameter details and common mistakes.");
+    text
+}
+
+pub async fn execute(
+    state: &AppState,
+    tools: &[Tool],
+    dedup_ttl: Duration,
+    house_rules: Option<&HouseRules>,
+    params: DocsMcpHelpParams,
+) -> Result<CallToolResult, ErrorData> {
+    let workflows = || json!(WORKFLOWS.iter().map(|w| json!({
+        "name": w.name,
+        "when": w.when,
+        "steps": registered_steps(w, tools),
+    })).collect::<Vec<_>>());
+    let gotchas = |tool: Option<&str>| json!(GOTCHAS.iter()
+        .filter(|(t, _)| tool.is_none_or(|tool| *t == "*" || *t == tool))
+        .map(|(t, advice)| json!({"tool": t, "advice": advice}))
+        .collect::<Vec<_>>());
+    let config = || json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "cache_dir": state.cache.dir().display().to_string(),
+        "dedup_ttl_secs": dedup_ttl.as_secs(),
+        "dependents_snapshot": DependentsSnapshot::enabled(),
+        "db_dump_sqlite": state.dump_db.is_some(),
+        "http": state.http_config,
+        "doc_summary": SummaryPolicy::global(),
+        "max_doc_mb": max_doc_mb(),
+        "tools_registered": tools.len(),
+        "instructions_file": house_rules,
+    });
+
+    let output = match params.topic.as_deref() {
+        None => json!({
+            "tools": tools.iter().map(describe_tool).collect::<Vec<_>>(),
+            "workflows": workflows(),
+            "gotchas": gotchas(None),
+            "config": config(),
+            "house_rules": house_rules.map(|r| &r.text),
+        }),
+        Some("workflows") => json!({ "workflows": workflows() }),
+        Some("gotchas") => json!({ "gotchas": gotchas(None) }),
+        Some("config") => json!({ "config": config() }),
+        Some(topic) => {
+            let Some(tool) = tools.iter().find(|t| t.name == topic) else {
+                return Err(ErrorData::invalid_params(
+                    format!("Unknown topic '{topic}'. Use a tool name, \"workflows\", \"gotchas\" or \"config\"."),
+                    None,
+                ));
+            };
+            let mut output = describe_tool(tool);
+            output["gotchas"] = gotchas(Some(topic));
+            output["workflows"] = json!(WORKFLOWS.iter()
+                .filter(|w| w.steps.contains(&topic))
+                .map(|w| w.name)
+                .collect::<Vec<_>>());
+            output
+        }
+    };
+
+    let json = serde_json::to_string_pretty(&output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
+
+/// The steps of `workflow` whose tools are registered; operators can disable tools.
+fn registered_steps(workflow: &Workflow, tools: &[Tool]) -> Vec<&'static str> {
+    workflow.steps.iter()
+        .copied()
+        .filter(|step| tools.iter().any(|t| t.name == *step))
+        .collect()
+}
+
+/// A tool's name, guide line, description and parameters, read from its registered schema.
+fn describe_tool(tool: &Tool) -> Value {
+    let required: Vec<&str> = tool.input_schema.get("required")
+        .and_then(Value::as_array)
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    let parameters: Vec<Value> = tool.input_schema.get("properties")
+        .and_then(Value::as_object)
+        .map(|props| props.iter().map(|(name, schema)| json!({
+            "name": name,
+            "type": schema.get("type"),
+            "required": required.contains(&name.as_str()),
+            "description": schema.get("description"),
+        })).collect())
+        .unwrap_or_default();
+    json!({
+        "name": tool.name,
+        "when_to_use": TOOL_GUIDE.iter().find(|(t, _)| *t == tool.name).map(|(_, when)| when),
+        "description": tool.description,
+        "parameters": parameters,
+    })
+}
@@ -1,37 +1,244 @@
-use std::sync::Arc;
+use std::path::PathBuf;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use async_trait::async_trait;
 use governor::{DefaultDirectRateLimiter, Quota, RateLimiter};
 use http::Extensions;
 use nonzero_ext::nonzero;
-use reqwest::Request;
+use reqwest::{Request, StatusCode};
 use reqwest_middleware::{Middleware, Next};
+use rmcp::ErrorData;
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
 
 use crate::cache::DiskCache;
-use crate::error::Result;
-use crate::sparse_index::{self, IndexLine};
+use crate::cratesio::dump::{self, DependentsSnapshot};
+use crate::cratesio::tarball::CrateTarball;
+use crate::cratesio::dump_db::DumpDatabase;
+use crate::docsrs::{fetch_rustdoc_json, DocDetail, RustdocJson};
+use crate::error::{ErrorCode, Result};
+use crate::fixtures::FixtureMiddleware;
+use crate::http_config::HttpConfig;
+use crate::metrics::RequestMetrics;
+use crate::session;
+use crate::singleflight::Singleflight;
+use crate::store::MetadataStore;
+use crate::telemetry::Telemetry;
+use crate::sparse_index::{self, Freshness, IndexLine};
 
 pub mod crate_list;
 pub mod crate_get;
 pub mod crate_readme_get;
+pub mod crate_files_list;
+pub mod crate_file_get;
 pub mod crate_docs_get;
+pub mod crate_docs_search;
+pub mod crate_module_expand;
 pub mod crate_item_list;
+pub mod multi_crate_item_search;
 pub mod crate_item_get;
+pub mod crate_item_compare;
+pub mod crate_items_get_many;
 pub mod crate_impls_list;
+pub mod crate_impl_get;
+pub mod crate_trait_check;
+pub mod crate_trait_gap;
+pub mod crate_ffi_list;
+pub mod crate_errors_list;
+pub mod crate_async_list;
 pub mod crate_versions_list;
 pub mod crate_version_get;
+pub mod crate_yanks_list;
+pub mod crate_scorecard_get;
+pub mod crate_build_risk;
+pub mod crate_audit_report;
+pub mod owner_crates_list;
+pub mod crate_maintenance_get;
+pub mod rust_toolchain_get;
+pub mod crate_no_std_check;
 pub mod crate_dependencies_list;
 pub mod crate_dependents_list;
 pub mod crate_downloads_get;
+pub mod crate_health_report;
+pub mod server_status;
+pub mod server_stats;
+pub mod session_context;
+pub mod docs_mcp_help;
+pub mod crates_analyze;
+pub mod cache_warm;
+
+/// Older stable versions probed when looking for the nearest docs.rs build.
+const FALLBACK_CANDIDATES: usize = 4;
+
+/// Environment variable bounding the memory, in MiB, that parsed rustdoc documents are
+/// kept in; large crates parse to hundreds of MB.
+pub const PARSED_DOCS_MB_ENV: &str = "DOCS_MCP_PARSED_DOCS_MB";
+pub const DEFAULT_PARSED_DOCS_MB: usize = 1024;
+
+/// The [`PARSED_DOCS_MB_ENV`] budget in bytes.
+fn parsed_docs_budget() -> usize {
+    let mb = std::env::var(PARSED_DOCS_MB_ENV).ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(DEFAULT_PARSED_DOCS_MB);
+    mb.saturating_mul(1 << 20)
+}
+
+/// Lock `mutex` even if a panicking call poisoned it. The maps behind these locks are
+/// only ever changed in whole steps, so a panic elsewhere leaves them consistent.
+fn lock_unpoisoned<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Names offered when a crate doesn't exist.
+const MAX_SUGGESTIONS: usize = 5;
+/// Search results at most this many edits from the requested name count as near-matches.
+const MAX_SUGGESTION_DISTANCE: usize = 2;
 
 /// Shared application state, held behind an Arc in the server.
+///
+/// One `AppState` serves every session of the process: caches, pacing and parsed
+/// documents are shared, while what each session did is kept in its [`session::SessionLog`].
 pub struct AppState {
     pub client: reqwest_middleware::ClientWithMiddleware,
     pub cache: DiskCache,
+    /// Timeouts and connection limit the client was built with
+    pub http_config: HttpConfig,
+    /// Per-host request counts, persisted to tune crates.io pacing
+    pub metrics: Arc<RequestMetrics>,
+    /// Reverse-dependency snapshot from the crates.io DB dump (opt-in)
+    pub dependents_snapshot: DependentsSnapshot,
+    /// SQLite copy of the crates.io DB dump, when opted in
+    pub dump_db: Option<DumpDatabase>,
+    /// Normalized metadata for every crate seen, for cross-crate queries; `None` if the
+    /// database could not be opened
+    pub store: Option<Arc<MetadataStore>>,
+    /// Per-tool call counts and latencies, for `server_stats`
+    pub telemetry: Telemetry,
+    /// Progress of the latest `cache_warm` run
+    pub warming: cache_warm::WarmProgress,
+    /// Cancelled on shutdown; background work stops at its next step
+    pub shutdown: CancellationToken,
+    /// Background work outliving the call that started it, awaited on shutdown
+    background: TaskTracker,
+    /// Recently parsed rustdoc documents, most recent last, keyed by `name@version[/target]`
+    /// with `#outline` appended for [`DocDetail::Outline`] parses
+    docs: Mutex<VecDeque<(String, Arc<RustdocJson>)>>,
+    /// Documents being fetched and parsed, so concurrent calls for the same one wait for
+    /// a single parse instead of each starting their own
+    loading: Singleflight,
 }
 
 impl AppState {
     pub async fn new() -> Result<Self> {
+        Self::with_http_config(HttpConfig::from_env()).await
+    }
+
+    /// Live state whose HTTP client uses `http_config`'s timeouts and connection limit.
+    pub async fn with_http_config(http_config: HttpConfig) -> Result<Self> {
+        let http = Self::http_client(&http_config)?;
+        let cache = DiskCache::new()?;
+        let metrics = Arc::new(RequestMetrics::load(cache.dir()));
+        let rate_mw = RateLimitMiddleware::new(metrics.clone());
+
+        // Retry outermost so every attempt is paced and counted by the rate limiter
+        let client = reqwest_middleware::ClientBuilder::new(http)
+            .with(RetryMiddleware)
+            .with(rate_mw)
+            .with(ConcurrencyLimitMiddleware::new(http_config.max_connections))
+            .build();
+
+        let dependents_snapshot = DependentsSnapshot::new(cache.dir());
+        let dump_db = DumpDatabase::enabled().then(|| DumpDatabase::new(cache.dir()));
+
+        // One download of the DB dump feeds every opted-in consumer that is out of date
+        let stale_snapshot = (DependentsSnapshot::enabled() && dependents_snapshot.is_stale())
+            .then(|| dependents_snapshot.clone());
+        let stale_db = dump_db.clone().filter(|db| db.is_stale());
+        let (shutdown, background) = (CancellationToken::new(), TaskTracker::new());
+        if stale_snapshot.is_some() || stale_db.is_some() {
+            let client = client.clone();
+            let work_dir = cache.dir().join("db-dump");
+            let cancel = shutdown.clone();
+            background.spawn(async move {
+                tracing::info!("refreshing crates.io DB dump");
+                if let Err(e) = dump::refresh(&client, &work_dir, stale_snapshot, stale_db, cancel).await {
+                    tracing::warn!("crates.io DB dump refresh failed: {e}");
+                }
+            });
+        }
+
+        // The store only serves crates_analyze; the rest of the server works without it
+        let store = match MetadataStore::open(cache.dir()) {
+            Ok(store) => Some(Arc::new(store)),
+            Err(e) => {
+                tracing::warn!("metadata store unavailable, crates_analyze is disabled: {e}");
+                None
+            }
+        };
+        Ok(Self {
+            client,
+            cache,
+            http_config,
+            metrics,
+            dependents_snapshot,
+            dump_db,
+            store,
+            telemetry: Telemetry::default(),
+            warming: cache_warm::WarmProgress::default(),
+            shutdown,
+            background,
+            docs: Mutex::default(),
+            loading: Singleflight::default(),
+        })
+    }
+
+    /// Offline state for `--fixture-mode`: every request is answered from `fixture_dir`
+    /// and responses are cached in a throwaway directory, never the user's cache. The
+    /// directory is deleted when the state is dropped.
+    pub async fn with_fixtures(fixture_dir: PathBuf) -> Result<Self> {
+        let http_config = HttpConfig::default();
+        let http = Self::http_client(&http_config)?;
+        let cache = DiskCache::scratch("docs-mcp-fixtures")?;
+
+        let client = reqwest_middleware::ClientBuilder::new(http)
+            .with(FixtureMiddleware::new(fixture_dir))
+            .build();
+
+        let dependents_snapshot = DependentsSnapshot::new(cache.dir());
+        Ok(Self {
+            client,
+            cache,
+            http_config,
+            metrics: Arc::new(RequestMetrics::in_memory()),
+            dependents_snapshot,
+            dump_db: None,
+            store: Some(Arc::new(MetadataStore::in_memory()?)),
+            telemetry: Telemetry::default(),
+            warming: cache_warm::WarmProgress::default(),
+            shutdown: CancellationToken::new(),
+            background: TaskTracker::new(),
+            docs: Mutex::default(),
+            loading: Singleflight::default(),
+        })
+    }
+
+    /// Run `task` in the background; [`shut_down`](Self::shut_down) waits for it. The
+    /// task should stop when [`shutdown`](Self::shutdown) is cancelled.
+    pub fn spawn_background(&self, task: impl std::future::Future<Output = ()> + Send + 'static) {
+        self.background.spawn(task);
+    }
+
+    /// Cancel background work and wait up to `grace` for it to clean up; whether it all
+    /// finished in time.
+    pub async fn shut_down(&self, grace: Duration) -> bool {
+        self.shutdown.cancel();
+        self.background.close();
+        tokio::time::timeout(grace, self.background.wait()).await.is_ok()
+    }
+
+    fn http_client(config: &HttpConfig) -> Result<reqwest::Client> {
         let mut headers = reqwest::header::HeaderMap::new();
         headers.insert(
             reqwest::header::USER_AGENT,
@@ -40,51 +247,303 @@ impl AppState {
             ),
         );
 
-        let http = reqwest::Client::builder()
+        reqwest::Client::builder()
             .default_headers(headers)
+            .timeout(config.timeout)
+            .connect_timeout(config.connect_timeout)
             .build()
-            .map_err(crate::error::DocsError::Http)?;
-
-        let rate_mw = RateLimitMiddleware::new();
-        let cache = DiskCache::new()?;
-
-        let client = reqwest_middleware::ClientBuilder::new(http)
-            .with(rate_mw)
-            .build();
-
-        Ok(Self { client, cache })
+            .map_err(crate::error::DocsError::Http)
     }
 
     /// Resolve a version string: if None or "latest", look up the latest stable version.
+    /// An explicit version must be published in the sparse index.
     pub async fn resolve_version(&self, name: &str, version: Option<&str>) -> Result<String> {
-        match version {
-            Some(v) if !v.is_empty() && v != "latest" => Ok(v.to_string()),
+        let lines = self.fetch_index(name).await?;
+        let (resolved, defaulted) = match version {
+            Some(v) if !v.is_empty() && v != "latest" => {
+                if !lines.iter().any(|l| l.vers == v) {
+                    return Err(crate::error::DocsError::VersionNotFound { name: name.to_string(), version: v.to_string() });
+                }
+                (v.to_string(), false)
+            }
             _ => {
-                let lines = sparse_index::fetch_index(name, &self.client, &self.cache).await?;
                 let latest = sparse_index::find_latest_stable(&lines)
                     .ok_or_else(|| crate::error::DocsError::NoStableVersion(name.to_string()))?;
-                Ok(latest.vers.clone())
+                (latest.vers.clone(), true)
             }
+        };
+        if let Some(session) = session::current() {
+            session.record_version(name, &resolved, defaulted);
         }
+        Ok(resolved)
     }
 
-    /// Fetch all index lines for a crate.
+    /// Fetch all index lines for a crate, recording them in the metadata store.
     pub async fn fetch_index(&self, name: &str) -> Result<Vec<IndexLine>> {
-        sparse_index::fetch_index(name, &self.client, &self.cache).await
+        let lines = sparse_index::fetch_index(name, &self.client, &self.cache).await?;
+        // The store is a by-product; a write failure must not fail the request
+        if self.store.as_ref().is_some_and(|store| store.needs_record(name)) {
+            let (owned, recorded) = (name.to_string(), lines.clone());
+            if let Err(e) = self.with_store(move |store| store.record_index(&owned, &recorded)).await {
+                tracing::debug!("failed to record {name} in the metadata store: {e}");
+            }
+        }
+        Ok(lines)
+    }
+
+    /// Run `query` against the metadata store on the blocking pool.
+    pub async fn with_store<T: Send + 'static>(
+        &self,
+        query: impl FnOnce(&MetadataStore) -> Result<T> + Send + 'static,
+    ) -> Result<T> {
+        let store = self.store.clone().ok_or_else(|| crate::error::DocsError::Other(
+            "The metadata store could not be opened; see the server log.".to_string(),
+        ))?;
+        tokio::task::spawn_blocking(move || query(&store))
+            .await
+            .map_err(|e| crate::error::DocsError::Other(format!("metadata store task failed: {e}")))?
+    }
+
+    /// The `.crate` archive of a version, verified against the checksum in its index line.
+    pub async fn crate_tarball(&self, name: &str, version: &str) -> Result<CrateTarball> {
+        let lines = self.fetch_index(name).await?;
+        let cksum = lines.iter().find(|l| l.vers == version)
+            .map(|l| l.cksum.clone())
+            .ok_or_else(|| crate::error::DocsError::VersionNotFound { name: name.to_string(), version: version.to_string() })?;
+        CrateTarball::fetch(name, version, &cksum, &self.client, &self.cache).await
+    }
+
+    /// Parsed rustdoc JSON for a crate version.
+    ///
+    /// Keeps recently used documents in memory, up to [`PARSED_DOCS_MB_ENV`] of their
+    /// estimated size, so follow-up calls skip re-parsing and reuse the lookups each
+    /// document memoizes.
+    pub async fn rustdoc(&self, name: &str, version: &str) -> Result<Arc<RustdocJson>> {
+        self.rustdoc_for_target(name, version, None).await
     }
+
+    /// Parsed rustdoc JSON built for `target`, or for the crate's default target if `None`.
+    pub async fn rustdoc_for_target(&self, name: &str, version: &str, target: Option<&str>) -> Result<Arc<RustdocJson>> {
+        self.rustdoc_with_detail(name, version, target, DocDetail::Full).await
+    }
+
+    /// Parsed rustdoc JSON for `target` at `detail`. A kept full document also answers
+    /// outline requests; an outline never answers full ones.
+    pub async fn rustdoc_with_detail(
+        &self,
+        name: &str,
+        version: &str,
+        target: Option<&str>,
+        detail: DocDetail,
+    ) -> Result<Arc<RustdocJson>> {
+        let full_key = match target {
+            Some(target) => format!("{name}@{version}/{target}"),
+            None => format!("{name}@{version}"),
+        };
+        let key = match detail {
+            DocDetail::Full => full_key.clone(),
+            DocDetail::Outline => format!("{full_key}#outline"),
+        };
+        if let Some(doc) = self.kept_doc(&full_key, &key) {
+            return Ok(doc);
+        }
+
+        self.loading.run(&key, || async {
+            // Another call may have parsed it while this one waited
+            match self.kept_doc(&full_key, &key) {
+                Some(doc) => Ok(doc),
+                None => self.load_doc(name, version, target, detail, key.clone()).await,
+            }
+        }).await
+    }
+
+    /// A kept document for `full_key` or `key`, marked most recently used.
+    fn kept_doc(&self, full_key: &str, key: &str) -> Option<Arc<RustdocJson>> {
+        let mut docs = lock_unpoisoned(&self.docs);
+        let pos = docs.iter().position(|(k, _)| k == full_key || k == key)?;
+        let entry = docs.remove(pos).expect("position is in bounds");
+        let doc = entry.1.clone();
+        docs.push_back(entry);
+        Some(doc)
+    }
+
+    /// Fetch and parse a document, keeping it under `key`.
+    async fn load_doc(
+        &self,
+        name: &str,
+        version: &str,
+        target: Option<&str>,
+        detail: DocDetail,
+        key: String,
+    ) -> Result<Arc<RustdocJson>> {
+        let doc = Arc::new(fetch_rustdoc_json(name, version, target, detail, &self.client, &self.cache).await?);
+        let mut docs = lock_unpoisoned(&self.docs);
+        if !docs.iter().any(|(k, _)| *k == key) {
+            docs.push_back((key, doc.clone()));
+        }
+        // Sizes are summed afresh since lookups a document builds on demand add to them;
+        // the newest document is kept whatever its size
+        let budget = parsed_docs_budget();
+        while docs.len() > 1 && docs.iter().map(|(_, d)| d.estimated_size()).sum::<usize>() > budget {
+            docs.pop_front();
+        }
+        Ok(doc)
+    }
+
+    /// The newest stable version older than `version` that has a docs.rs build.
+    ///
+    /// Probes at most [`FALLBACK_CANDIDATES`] versions, concurrently and through the
+    /// HEAD cache, stopping at the first one built.
+    pub async fn nearest_built_version(&self, name: &str, version: &str) -> Result<Option<String>> {
+        let Ok(requested) = semver::Version::parse(version) else { return Ok(None) };
+        let lines = self.fetch_index(name).await?;
+        let mut older: Vec<semver::Version> = lines.iter()
+            .filter(|l| !l.yanked && !l.vers.contains('-'))
+            .filter_map(|l| semver::Version::parse(&l.vers).ok())
+            .filter(|v| *v < requested)
+            .collect();
+        older.sort_by(|a, b| b.cmp(a));
+        let candidates: Vec<String> = older.iter()
+            .take(FALLBACK_CANDIDATES)
+            .map(|v| v.to_string())
+            .collect();
+        crate::docsrs::nearest_built_version(name, &candidates, &self.client, &self.cache).await
+    }
+
+    /// Freshness caveat for an explicitly requested `version` that trails the latest stable.
+    ///
+    /// `None` for current versions and whenever the index can't be read; the caveat is
+    /// advisory and never fails a call.
+    pub async fn newer_available(&self, name: &str, version: &str) -> Option<Freshness> {
+        let lines = self.fetch_index(name).await.ok()?;
+        sparse_index::freshness(&lines, version)
+    }
+
+    /// Existing crates an agent probably meant by the unknown `name`, best first.
+    ///
+    /// The hyphen/underscore swap is checked in the index; other near-matches come from a
+    /// crates.io search filtered to [`MAX_SUGGESTION_DISTANCE`] edits. Lookup failures just
+    /// mean fewer suggestions.
+    pub async fn crate_name_suggestions(&self, name: &str) -> Vec<String> {
+        // A crate that exists was named right; whatever failed was something else
+        if self.fetch_index(name).await.is_ok() {
+            return vec![];
+        }
+        let mut found: Vec<(usize, String)> = vec![];
+        for variant in [name.replace('_', "-"), name.replace('-', "_")] {
+            if variant == name {
+                continue;
+            }
+            if let Some(line) = self.fetch_index(&variant).await.ok().and_then(|lines| lines.into_iter().next()) {
+                found.push((0, line.name));
+            }
+        }
+
+        let client = crate::cratesio::CratesIoClient::new(&self.client, &self.cache);
+        if let Ok(results) = client.search(name, None, None, None, 1, 20).await {
+            let wanted = normalize_crate_name(name);
+            for krate in results.crates {
+                let distance = edit_distance(&wanted, &normalize_crate_name(&krate.name));
+                let new = distance > 0 && !found.iter().any(|(_, n)| *n == krate.name);
+                if new && distance <= MAX_SUGGESTION_DISTANCE {
+                    found.push((distance, krate.name));
+                }
+            }
+        }
+        // Stable sort: equally close names keep crates.io's relevance order
+        found.sort_by_key(|(distance, _)| *distance);
+        found.into_iter().map(|(_, n)| n).take(MAX_SUGGESTIONS).collect()
+    }
+}
+
+/// Error for a version without a docs.rs build, naming the nearest older version that has one.
+pub(crate) async fn docs_not_found_error(state: &AppState, name: &str, version: &str) -> ErrorData {
+    let nearest = state.nearest_built_version(name, version).await.ok().flatten();
+    let hint = match &nearest {
+        Some(v) => format!("The nearest older version with docs is {v}; retry with version=\"{v}\"."),
+        None => "The latest version may not have been built yet. \
+                 Try specifying an older version with the 'version' parameter.".to_string(),
+    };
+    let mut data = ErrorCode::DocsBuildMissing.data();
+    if let (Some(data), Some(v)) = (data.as_mut(), nearest) {
+        data["suggested_version"] = serde_json::json!(v);
+    }
+    ErrorData::invalid_params(
+        format!("No docs.rs build found for {name} {version}. {hint} \
+                 crate_docs_get falls back to the README."),
+        data,
+    )
+}
+
+/// crates.io treats `-` and `_` as the same and ignores case when comparing names.
+fn normalize_crate_name(name: &str) -> String {
+    name.to_lowercase().replace('_', "-")
+}
+
+/// Levenshtein distance between two names.
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = prev[j] + usize::from(ca != *cb);
+            row.push(substitute.min(prev[j + 1] + 1).min(row[j] + 1));
+        }
+        prev = row;
+    }
+    prev[b.len()]
+}
+
+/// Check a `target` parameter is shaped like a target triple before it goes into a URL.
+pub(crate) fn validate_target(target: Option<&str>) -> std::result::Result<Option<&str>, ErrorData> {
+    match target {
+        Some(t) if !is_target_triple(t) => {
+            Err(ErrorData::invalid_params(
+                format!("Invalid target '{t}'. Expected a target triple like \"x86_64-pc-windows-msvc\"."),
+                ErrorCode::InvalidParams.data(),
+            ))
+        }
+        other => Ok(other),
+    }
+}
+
+/// Whether `target` looks like `arch-vendor-os[-env]`: dash-separated segments of
+/// letters, digits, `_` and `.`, none empty or made of dots only, so it can't name
+/// another docs.rs path such as `..`.
+fn is_target_triple(target: &str) -> bool {
+    target.contains('-')
+        && target.split('-').all(|segment| {
+            !segment.is_empty()
+                && !segment.chars().all(|c| c == '.')
+                && segment.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.'))
+        })
+}
+
+/// Error for a target docs.rs did not build; unlike a missing build, older versions won't help.
+pub(crate) fn target_not_built_error(name: &str, version: &str, target: &str) -> ErrorData {
+    ErrorData::invalid_params(
+        format!("No docs.rs build of {name} {version} for target '{target}'. docs.rs builds only \
+                 the targets a crate lists under [package.metadata.docs.rs]; omit target for \
+                 the default one."),
+        ErrorCode::DocsBuildMissing.data(),
+    )
 }
 
 // ─── Rate limit middleware ─────────────────────────────────────────────────────
 
 pub struct RateLimitMiddleware {
     limiter: Arc<DefaultDirectRateLimiter>,
+    metrics: Arc<RequestMetrics>,
 }
 
 impl RateLimitMiddleware {
-    pub fn new() -> Self {
-        let quota = Quota::per_second(nonzero!(1u32));
+    /// Pace crates.io at the interval tuned from previous runs' metrics.
+    pub fn new(metrics: Arc<RequestMetrics>) -> Self {
+        let quota = Quota::with_period(metrics.crates_io_interval())
+            .unwrap_or_else(|| Quota::per_second(nonzero!(1u32)));
         let limiter = Arc::new(RateLimiter::direct(quota));
-        Self { limiter }
+        Self { limiter, metrics }
     }
 }
 
@@ -97,9 +556,176 @@ impl Middleware for RateLimitMiddleware {
         next: Next<'_>,
     ) -> reqwest_middleware::Result<reqwest::Response> {
         // Only rate limit crates.io API calls (not sparse index or docs.rs)
-        if req.url().host_str() == Some("crates.io") {
+        let host = req.url().host_str().unwrap_or_default().to_string();
+        if host == "crates.io" {
             self.limiter.until_ready().await;
         }
+        let result = next.run(req, extensions).await;
+        if let Ok(resp) = &result {
+            let throttled = resp.status() == reqwest::StatusCode::TOO_MANY_REQUESTS;
+            self.metrics.record(&host, throttled);
+            if let Some(session) = session::current() {
+                session.record_request(&host, throttled);
+            }
+        }
+        result
+    }
+}
+
+// ─── Concurrency limit middleware ──────────────────────────────────────────────
+
+/// Caps the requests in flight at once, so a burst of parallel tool calls can't open an
+/// unbounded number of connections.
+///
+/// A permit is held until the response headers arrive; body downloads are bounded by the
+/// client's request timeout instead.
+pub struct ConcurrencyLimitMiddleware {
+    permits: Arc<tokio::sync::Semaphore>,
+}
+
+impl ConcurrencyLimitMiddleware {
+    pub fn new(max_connections: usize) -> Self {
+        Self { permits: Arc::new(tokio::sync::Semaphore::new(max_connections.max(1))) }
+    }
+}
+
+#[async_trait]
+impl Middleware for ConcurrencyLimitMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<reqwest::Response> {
+        let _permit = self.permits.acquire().await.map_err(reqwest_middleware::Error::middleware)?;
         next.run(req, extensions).await
     }
 }
+
+// ─── Retry middleware ──────────────────────────────────────────────────────────
+
+/// Retries after the first attempt for a transient failure.
+const MAX_RETRIES: u32 = 3;
+/// Backoff before the first retry; doubles with each further retry.
+const RETRY_BASE: Duration = Duration::from_millis(500);
+/// Longest wait honored, whether from backoff or a server's Retry-After.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Retries 429 and 5xx responses, and connection failures, with bounded exponential backoff.
+///
+/// A `Retry-After` header sets the wait instead of the backoff; one asking for longer than
+/// [`MAX_RETRY_DELAY`] is returned to the caller rather than blocking the tool call.
+pub struct RetryMiddleware;
+
+#[async_trait]
+impl Middleware for RetryMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<reqwest::Response> {
+        let mut attempt = 0;
+        loop {
+            // Streaming bodies can't be replayed; send those once
+            let Some(retry_req) = req.try_clone().filter(|_| attempt < MAX_RETRIES) else {
+                return next.run(req, extensions).await;
+            };
+            let result = next.clone().run(retry_req, extensions).await;
+            let retry_after = match &result {
+                Ok(resp) if is_transient(resp.status()) => resp.headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .map(|v| parse_retry_after(v, chrono::Utc::now())),
+                // A request that hit the client timeout already waited its full budget
+                Err(reqwest_middleware::Error::Reqwest(e)) if e.is_connect() => None,
+                _ => return result,
+            };
+            let Some(delay) = retry_delay(attempt, retry_after.flatten(), jitter()) else {
+                return result;
+            };
+            tracing::debug!("retrying {} in {delay:?} (attempt {})", req.url(), attempt + 1);
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+fn is_transient(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Wait before retry number `attempt` (0-based), or `None` to give up.
+///
+/// Without a `Retry-After`, the backoff is `RETRY_BASE * 2^attempt` with its upper half
+/// scaled by `jitter` (0..1) so concurrent calls don't retry in lockstep.
+fn retry_delay(attempt: u32, retry_after: Option<Duration>, jitter: f64) -> Option<Duration> {
+    if let Some(wait) = retry_after {
+        return (wait <= MAX_RETRY_DELAY).then_some(wait);
+    }
+    let backoff = RETRY_BASE.saturating_mul(1 << attempt.min(16)).min(MAX_RETRY_DELAY);
+    Some(backoff / 2 + (backoff / 2).mul_f64(jitter.clamp(0.0, 1.0)))
+}
+
+/// A `Retry-After` value: delay in seconds, or an HTTP date relative to `now`.
+fn parse_retry_after(value: &str, now: chrono::DateTime<chrono::Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some((at.to_utc() - now).to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Cheap 0..1 jitter from the clock; retries don't need a real RNG.
+fn jitter() -> f64 {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.subsec_nanos()).unwrap_or(0);
+    f64::from(nanos % 1000) / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn targets_must_be_shaped_like_triples() {
+        for valid in ["x86_64-pc-windows-msvc", "wasm32-unknown-unknown", "thumbv7em-none-eabihf", "aarch64-apple-ios-sim"] {
+            assert!(is_target_triple(valid), "{valid}");
+        }
+        for invalid in ["", ".", "..", "...", "../x", "x86_64", "x86_64--linux", "-linux-gnu", "x86_64-..-gnu", "x86_64-pc/linux"] {
+            assert!(!is_target_triple(invalid), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn edit_distance_counts_single_character_edits() {
+        assert_eq!(edit_distance("tokio", "tokio"), 0);
+        assert_eq!(edit_distance("tokoi", "tokio"), 2);
+        assert_eq!(edit_distance("serd", "serde"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(normalize_crate_name("Tokio_Util"), normalize_crate_name("tokio-util"));
+    }
+
+    #[test]
+    fn backoff_doubles_with_jitter_and_is_capped() {
+        assert_eq!(retry_delay(0, None, 0.0), Some(Duration::from_millis(250)));
+        assert_eq!(retry_delay(0, None, 1.0), Some(Duration::from_millis(500)));
+        assert_eq!(retry_delay(2, None, 0.0), Some(Duration::from_millis(1000)));
+        assert_eq!(retry_delay(20, None, 1.0), Some(MAX_RETRY_DELAY));
+    }
+
+    #[test]
+    fn retry_after_overrides_backoff_unless_too_long() {
+        assert_eq!(retry_delay(0, Some(Duration::from_secs(7)), 0.5), Some(Duration::from_secs(7)));
+        assert_eq!(retry_delay(0, Some(Duration::from_secs(600)), 0.5), None);
+    }
+
+    #[test]
+    fn parses_retry_after_seconds_and_dates() {
+        let now = chrono::DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z").unwrap().to_utc();
+        assert_eq!(parse_retry_after(" 12 ", now), Some(Duration::from_secs(12)));
+        assert_eq!(parse_retry_after("Wed, 01 May 2024 12:00:05 GMT", now), Some(Duration::from_secs(5)));
+        assert_eq!(parse_retry_after("Wed, 01 May 2024 11:00:00 GMT", now), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+}
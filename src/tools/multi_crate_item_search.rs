@@ -0,0 +1,98 @@
+use rmcp::{ErrorData, model::{CallToolResult, Content}};
+use serde::Deserialize;
+use rmcp::schemars::{self, JsonSchema};
+use serde_json::json;
+
+use super::AppState;
+use super::crate_item_get;
+use crate::docsrs::search_items;
+use crate::error::ErrorCode;
+
+/// Crates searched per call; each one loads its full rustdoc JSON.
+const MAX_CRATES: usize = 30;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct MultiCrateItemSearchParams {
+    /// Crates to search, as names ("serde") or pinned versions ("serde@1.0.217"); max 30.
+    /// Unpinned crates use their latest stable version.
+    pub crates: Vec<String>,
+    /// Search string — item name or concept (required)
+    pub query: String,
+    /// Filter by kind: "struct", "enum", "trait", "fn", "type", "const", "macro"
+    pub kind: Option<String>,
+    /// Max results per crate (default: 5, max: 20)
+    pub limit_per_crate: Option<usize>,
+}
+
+pub async fn execute(state: &AppState, params: MultiCrateItemSearchParams) -> Result<CallToolResult, ErrorData> {
+    let mut crates: Vec<(&str, Option<&str>)> = vec![];
+    for spec in params.crates.iter().map(|c| c.trim()).filter(|c| !c.is_empty()) {
+        let (name, version) = match spec.split_once('@') {
+            Some((name, version)) => (name.trim(), Some(version.trim())),
+            None => (spec, None),
+        };
+        if !crates.iter().any(|(n, _)| *n == name) {
+            crates.push((name, version));
+        }
+    }
+    if crates.is_empty() || crates.len() > MAX_CRATES {
+        return Err(ErrorData::invalid_params(
+            format!("Pass between 1 and {MAX_CRATES} crates (got {}).", crates.len()),
+            ErrorCode::InvalidParams.data(),
+        ));
+    }
+    let limit = params.limit_per_crate.unwrap_or(5).clamp(1, 20);
+
+    // One crate at a time: large crates parse to hundreds of MB, and a crate without a
+    // docs.rs build or with a bad name is reported without failing the others
+    let mut groups = vec![];
+    let mut no_matches = vec![];
+    let mut errors = vec![];
+    for (name, version) in crates {
+        let loaded = match state.resolve_version(name, version).await {
+            Ok(version) => crate_item_get::load(state, name, &version, None).await.map(|loaded| (version, loaded)),
+            Err(e) => Err(ErrorData::from(e)),
+        };
+        let (version, (doc, declared_features)) = match loaded {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                errors.push(json!({"crate": name, "error": e.message}));
+                continue;
+            }
+        };
+        let results = search_items(&doc, &params.query, params.kind.as_deref(), None, limit, &declared_features, false);
+        if results.is_empty() {
+            no_matches.push(name.to_string());
+            continue;
+        }
+        let best_score = results.iter().map(|r| r.score).fold(0.0, f32::max);
+        let items: Vec<serde_json::Value> = results.iter().map(|r| json!({
+            "path": r.path,
+            "kind": r.kind,
+            "signature": r.signature,
+            "doc_summary": r.doc_summary,
+            "feature_requirements": r.feature_requirements,
+            "score": r.score,
+        })).collect();
+        groups.push((best_score, json!({
+            "crate": name,
+            "version": version,
+            "best_score": best_score,
+            "items": items,
+        })));
+    }
+    // Crates with the strongest match first
+    groups.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let output = json!({
+        "query": params.query,
+        "crates_with_matches": groups.len(),
+        "results": groups.into_iter().map(|(_, group)| group).collect::<Vec<_>>(),
+        "no_matches": no_matches,
+        "errors": errors,
+    });
+
+    let json = serde_json::to_string_pretty(&output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
@@ -0,0 +1,87 @@
+use rmcp::{ErrorData, model::{CallToolResult, Content}};
+use serde::{Deserialize, Serialize};
+use rmcp::schemars::{self, JsonSchema};
+use serde_json::json;
+
+use super::AppState;
+use crate::cratesio::CrateInfo;
+use crate::error::ErrorCode;
+
+#[derive(Serialize)]
+struct OwnedCrate<'a> {
+    name: &'a str,
+    description: Option<&'a str>,
+    version: Option<&'a str>,
+    downloads: u64,
+    recent_downloads: Option<u64>,
+    created_at: &'a str,
+    updated_at: &'a str,
+    repository: Option<&'a str>,
+}
+
+impl<'a> From<&'a CrateInfo> for OwnedCrate<'a> {
+    fn from(c: &'a CrateInfo) -> Self {
+        Self {
+            name: &c.name,
+            description: c.description.as_deref().map(str::trim),
+            version: c.max_stable_version.as_deref().or(c.max_version.as_deref()),
+            downloads: c.downloads,
+            recent_downloads: c.recent_downloads,
+            created_at: &c.created_at,
+            updated_at: &c.updated_at,
+            repository: c.repository.as_deref(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct OwnerCratesListParams {
+    /// crates.io login of a user (e.g. "dtolnay"), or of a team as "github:{org}:{team}"
+    /// (e.g. "github:rust-lang:libs"), as crate owner lists show them
+    pub owner: String,
+    /// Sort order: "downloads" (default), "recent-downloads", "recent-updates", "new", "alpha"
+    pub sort: Option<String>,
+    /// Page number (1-indexed, default: 1)
+    pub page: Option<u32>,
+    /// Results per page (max 100, default: 50)
+    pub per_page: Option<u32>,
+}
+
+pub async fn execute(state: &AppState, params: OwnerCratesListParams) -> Result<CallToolResult, ErrorData> {
+    let login = params.owner.trim();
+    let page = params.page.unwrap_or(1).max(1);
+    let per_page = params.per_page.unwrap_or(50).clamp(1, 100);
+    let sort = params.sort.as_deref().unwrap_or("downloads");
+
+    let client = crate::cratesio::CratesIoClient::new(&state.client, &state.cache);
+    let account = client.get_account(login).await.map_err(|e| {
+        if !e.is_not_found() {
+            return ErrorData::from(e);
+        }
+        ErrorData::invalid_params(
+            format!("crates.io has no user or team '{login}'. Team logins look like \"github:rust-lang:libs\"; \
+                     crate_audit_report lists a crate's owners."),
+            ErrorCode::NotFound.data(),
+        )
+    })?;
+    let result = client.crates_owned_by(&account, Some(sort), page, per_page).await
+        .map_err(ErrorData::from)?;
+
+    let crates: Vec<OwnedCrate> = result.crates.iter().map(OwnedCrate::from).collect();
+    let output = json!({
+        "owner": {
+            "login": account.login,
+            "kind": if account.login.contains(':') { "team" } else { "user" },
+            "name": account.name,
+            "url": account.url,
+        },
+        "total": result.meta.total,
+        "page": page,
+        "sort": sort,
+        "crates": crates,
+    });
+
+    let json = serde_json::to_string_pretty(&output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
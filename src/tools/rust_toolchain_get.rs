@@ -0,0 +1,84 @@
+use chrono::Utc;
+use rmcp::{ErrorData, model::{CallToolResult, Content}};
+use serde::Deserialize;
+use rmcp::schemars::{self, JsonSchema};
+use serde_json::json;
+
+use super::AppState;
+use super::crate_versions_list::parse_rust_version;
+use crate::error::ErrorCode;
+use crate::rust_releases::{self, months_between, stable_release_date, MAX_SCHEDULED_MINOR};
+
+const CHANNELS: [&str; 3] = ["stable", "beta", "nightly"];
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RustToolchainGetParams {
+    /// A rust_version (MSRV) to date against the stable release history, e.g. "1.70"
+    pub rust_version: Option<String>,
+}
+
+pub async fn execute(state: &AppState, params: RustToolchainGetParams) -> Result<CallToolResult, ErrorData> {
+    let msrv = match params.rust_version.as_deref() {
+        Some(s) => Some(parse_rust_version(s).filter(|v| v.major == 1).ok_or_else(|| ErrorData::invalid_params(
+            format!("Invalid rust_version '{s}'. Expected a toolchain version like \"1.70\" or \"1.70.0\"."),
+            None,
+        ))?),
+        None => None,
+    };
+    if let Some(msrv) = msrv.as_ref().filter(|v| stable_release_date(v.minor).is_none()) {
+        return Err(ErrorData::invalid_params(
+            format!("rust_version 1.{} is past the release schedule; minor versions up to {MAX_SCHEDULED_MINOR} are dated.", msrv.minor),
+            ErrorCode::InvalidParams.data(),
+        ));
+    }
+
+    let (stable, beta, nightly) = tokio::join!(
+        rust_releases::channel_release(&state.client, &state.cache, CHANNELS[0]),
+        rust_releases::channel_release(&state.client, &state.cache, CHANNELS[1]),
+        rust_releases::channel_release(&state.client, &state.cache, CHANNELS[2])
+    );
+    let mut channels = serde_json::Map::new();
+    let mut errors = serde_json::Map::new();
+    for (channel, release) in CHANNELS.into_iter().zip([stable, beta, nightly]) {
+        match release {
+            Ok(release) => { channels.insert(channel.to_string(), json!(release)); }
+            Err(e) => { errors.insert(channel.to_string(), json!(e.to_string())); }
+        }
+    }
+    let stable_minor = channels.get("stable")
+        .and_then(|r| r["version"].as_str())
+        .and_then(parse_rust_version)
+        .map(|v| v.minor);
+
+    let mut output = json!({ "channels": channels });
+    if let Some(msrv) = msrv {
+        let today = Utc::now().date_naive();
+        let released = stable_release_date(msrv.minor).expect("checked with the parameters");
+        let months_ago = months_between(released, today);
+        let version = format!("1.{}", msrv.minor);
+        let mut entry = json!({
+            "rust_version": msrv.to_string(),
+            "released": released.to_string(),
+        });
+        if released > today {
+            entry["summary"] = json!(format!("Rust {version} is not out yet; it is scheduled for {released}"));
+        } else {
+            entry["months_ago"] = json!(months_ago);
+            let mut summary = format!("requires a Rust released {months_ago} months ago ({version}, {released})");
+            if let Some(stable_minor) = stable_minor {
+                let behind = stable_minor.saturating_sub(msrv.minor);
+                entry["releases_behind_stable"] = json!(behind);
+                summary.push_str(&format!("; stable is {behind} releases newer"));
+            }
+            entry["summary"] = json!(summary);
+        }
+        output["msrv"] = entry;
+    }
+    if !errors.is_empty() {
+        output["errors"] = json!(errors);
+    }
+
+    let json = serde_json::to_string_pretty(&output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
@@ -0,0 +1,22 @@
+use rmcp::{ErrorData, model::{CallToolResult, Content}};
+use serde_json::json;
+
+use super::AppState;
+
+pub async fn execute(state: &AppState) -> Result<CallToolResult, ErrorData> {
+    let tools = state.telemetry.snapshot();
+    let calls: u64 = tools.iter().map(|t| t.calls).sum();
+    let errors: u64 = tools.iter().map(|t| t.errors).sum();
+
+    let output = json!({
+        "uptime_secs": state.telemetry.uptime().as_secs(),
+        "calls": calls,
+        "errors": errors,
+        "tools": tools,
+        "requests": state.metrics.process(),
+    });
+
+    let json = serde_json::to_string_pretty(&output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
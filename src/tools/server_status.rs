@@ -0,0 +1,37 @@
+use rmcp::{ErrorData, model::{CallToolResult, Content}};
+use serde_json::json;
+
+use super::AppState;
+
+pub async fn execute(state: &AppState) -> Result<CallToolResult, ErrorData> {
+    let interval = state.metrics.crates_io_interval();
+    let stats = state.metrics.stats();
+
+    let output = json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "cache_dir": state.cache.dir().display().to_string(),
+        "rate_limit": {
+            "crates_io_interval_ms": interval.as_millis(),
+            "crates_io_requests_per_second": 1000.0 / interval.as_millis().max(1) as f64,
+            "tuning_window": stats.window,
+        },
+        "db_dump": {
+            "dependents_snapshot_built_at": state.dependents_snapshot.built_at(),
+            "sqlite": state.dump_db.as_ref().map(|db| json!({
+                "path": db.path().display().to_string(),
+                "built_at": db.built_at(),
+                "fresh": db.is_fresh(),
+            })),
+        },
+        "requests": {
+            // Requests this client's calls caused; "process" adds every other session's
+            "session": crate::session::current().map(|s| s.requests()).unwrap_or_default(),
+            "process": state.metrics.process(),
+            "lifetime": stats.hosts,
+        },
+    });
+
+    let json = serde_json::to_string_pretty(&output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
@@ -0,0 +1,39 @@
+use rmcp::{ErrorData, model::{CallToolResult, Content}};
+use serde_json::json;
+
+use super::AppState;
+
+pub async fn execute(state: &AppState) -> Result<CallToolResult, ErrorData> {
+    let touched = crate::session::current().map(|s| s.crates()).unwrap_or_default();
+    let mut crates = vec![];
+    for touch in touched {
+        // Usually a cache hit: resolving the version already fetched the index
+        let lines = state.fetch_index(&touch.name).await.unwrap_or_default();
+        let versions: Vec<_> = touch.versions.iter().map(|v| {
+            let line = lines.iter().find(|l| l.vers == v.version);
+            json!({
+                "version": v.version,
+                "defaulted": v.defaulted,
+                // What a plain `cargo add` of this version turns on
+                "default_features": line.map(|l| l.all_features().remove("default").unwrap_or_default()),
+                "yanked": line.map(|l| l.yanked),
+            })
+        }).collect();
+        crates.push(json!({
+            "name": touch.name,
+            "versions": versions,
+            "tools": touch.tools,
+            "requests": touch.requests,
+            "calls": touch.calls,
+        }));
+    }
+
+    let output = json!({
+        "crate_count": crates.len(),
+        "crates": crates,
+    });
+
+    let json = serde_json::to_string_pretty(&output)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
@@ -23,6 +23,11 @@ async fn cratesio_crate_list_serde_returns_results() {
         sort: None,
         page: None,
         per_page: Some(5),
+        dedupe_forks: None,
+        license: None,
+        max_rust_version: None,
+        max_edition: None,
+        detail: None,
     };
     let result = crate_list::execute(&state, params).await
         .expect("crate_list should succeed");
@@ -40,6 +45,7 @@ async fn cratesio_crate_get_tokio_returns_features() {
     let state = make_state().await;
     let params = crate_get::CrateGetParams {
         name: "tokio".to_string(),
+        version: None,
     };
     let result = crate_get::execute(&state, params).await
         .expect("crate_get should succeed");
@@ -59,8 +65,10 @@ async fn cratesio_versions_list_serde_returns_stable_versions() {
         include_yanked: Some(false),
         include_prerelease: Some(false),
         search: None,
+        max_rust_version: None,
         page: None,
         per_page: None,
+        cadence: None,
     };
     let result = crate_versions_list::execute(&state, params).await
         .expect("crate_versions_list should succeed");
@@ -81,6 +89,7 @@ async fn cratesio_downloads_get_anyhow_returns_nonzero() {
     let params = crate_downloads_get::CrateDownloadsGetParams {
         name: "anyhow".to_string(),
         before_date: None,
+        compare_with: None,
     };
     let result = crate_downloads_get::execute(&state, params).await
         .expect("crate_downloads_get should succeed");
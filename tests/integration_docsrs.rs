@@ -60,6 +60,11 @@ async fn docsrs_fetch_serde_rustdoc_json_succeeds() {
         name: "serde".to_string(),
         version: Some("1.0.217".to_string()),
         include_items: Some(false),
+        include_hidden: None,
+        module: None,
+        max_depth: None,
+        sections: None,
+        target: None,
     };
     let result = crate_docs_get::execute(&state, params).await
         .expect("crate_docs_get should succeed");
@@ -81,6 +86,9 @@ async fn docsrs_crate_item_list_serde_serialize() {
         kind: None,
         module_prefix: None,
         limit: Some(10),
+        include_hidden: None,
+        target: None,
+        semantic: None,
     };
     let result = crate_item_list::execute(&state, params).await
         .expect("crate_item_list should succeed");
@@ -104,6 +112,9 @@ async fn docsrs_crate_item_get_serde_serialize_trait() {
         item_path: "serde::Serialize".to_string(),
         include_methods: None,
         include_trait_impls: None,
+        include_trait_impl_methods: None,
+        impl_for: None,
+        target: None,
     };
     let result = crate_item_get::execute(&state, params).await
         .expect("crate_item_get should succeed");
@@ -122,11 +133,21 @@ async fn docsrs_second_fetch_uses_cache() {
         name: "anyhow".to_string(),
         version: None,
         include_items: Some(false),
+        include_hidden: None,
+        module: None,
+        max_depth: None,
+        sections: None,
+        target: None,
     }).await.expect("first fetch should succeed");
     let result2 = crate_docs_get::execute(&state, crate_docs_get::CrateDocsGetParams {
         name: "anyhow".to_string(),
         version: None,
         include_items: Some(false),
+        include_hidden: None,
+        module: None,
+        max_depth: None,
+        sections: None,
+        target: None,
     }).await.expect("second fetch should succeed");
     let j1: serde_json::Value = serde_json::from_str(&extract_text(&result1)).unwrap();
     let j2: serde_json::Value = serde_json::from_str(&extract_text(&result2)).unwrap();
@@ -0,0 +1,1188 @@
+/// Integration tests for `--fixture-mode`: tools run end to end against the bundled
+/// fixtures with no network access, so these are enabled by default.
+use docs_mcp::fixtures::default_fixture_dir;
+use docs_mcp::tools::{
+    AppState, cache_warm, crate_async_list, crate_errors_list, crate_ffi_list, crate_get, crate_item_get,
+    crate_docs_search, crate_item_list, crate_items_get_many, crate_no_std_check,
+    multi_crate_item_search, crate_versions_list, crate_yanks_list,
+};
+
+async fn make_state() -> AppState {
+    AppState::with_fixtures(default_fixture_dir()).await.expect("AppState::with_fixtures should succeed")
+}
+
+fn extract_text(result: &rmcp::model::CallToolResult) -> String {
+    result.content[0].as_text().expect("expected text content").text.clone()
+}
+
+#[tokio::test]
+async fn fixture_mode_resolves_latest_from_fake_index() {
+    let state = make_state().await;
+    let params = crate_versions_list::CrateVersionsListParams {
+        name: "rmcp".to_string(),
+        include_yanked: None,
+        include_prerelease: None,
+        search: None,
+        max_rust_version: None,
+        page: None,
+        per_page: None,
+        cadence: None,
+    };
+    let result = crate_versions_list::execute(&state, params).await.expect("versions list should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["versions"][0]["version"], "0.16.0");
+}
+
+#[tokio::test]
+async fn fixture_mode_serves_rustdoc_json() {
+    let state = make_state().await;
+    let params = crate_item_list::CrateItemListParams {
+        name: "clap".to_string(),
+        version: None,
+        query: "Parser".to_string(),
+        kind: None,
+        module_prefix: None,
+        limit: Some(5),
+        include_hidden: None,
+        target: None,
+        semantic: None,
+    };
+    let result = crate_item_list::execute(&state, params).await.expect("item list should succeed");
+    let text = extract_text(&result);
+    assert!(text.contains("Parser"), "expected Parser in results, got: {text}");
+
+    let params = crate_item_get::CrateItemGetParams {
+        name: "rmcp".to_string(),
+        version: Some("0.16.0".to_string()),
+        item_path: "rmcp::service::ServiceExt".to_string(),
+        include_methods: Some(false),
+        include_trait_impls: Some("none".to_string()),
+        include_trait_impl_methods: None,
+        impl_for: None,
+        target: None,
+    };
+    let result = crate_item_get::execute(&state, params).await.expect("item get should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["kind"], "trait");
+}
+
+#[tokio::test]
+async fn fixture_mode_unpublished_version_is_not_reported_as_a_missing_build() {
+    let state = make_state().await;
+    let params = crate_item_get::CrateItemGetParams {
+        name: "rmcp".to_string(),
+        version: Some("0.15.9".to_string()),
+        item_path: "rmcp::service::ServiceExt".to_string(),
+        include_methods: None,
+        include_trait_impls: None,
+        include_trait_impl_methods: None,
+        impl_for: None,
+        target: None,
+    };
+    let err = crate_item_get::execute(&state, params).await.unwrap_err();
+    assert_eq!(err.data.unwrap()["code"], "VERSION_NOT_FOUND");
+    assert!(err.message.contains("crate_versions_list"), "got: {}", err.message);
+}
+
+#[tokio::test]
+async fn fixture_mode_item_get_resolves_method_paths() {
+    let state = make_state().await;
+    let get = |item_path: &str| crate_item_get::CrateItemGetParams {
+        name: "rmcp".to_string(),
+        version: Some("0.16.0".to_string()),
+        item_path: item_path.to_string(),
+        include_methods: None,
+        include_trait_impls: None,
+        include_trait_impl_methods: None,
+        impl_for: None,
+        target: None,
+    };
+
+    let result = crate_item_get::execute(&state, get("rmcp::transport::TokioChildProcess::graceful_shutdown"))
+        .await
+        .expect("inherent method should resolve");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["kind"], "method");
+    assert_eq!(json["path"], "rmcp::transport::child_process::TokioChildProcess::graceful_shutdown");
+    assert_eq!(json["parent"]["kind"], "struct");
+    assert!(json["signature"].as_str().unwrap().contains("fn graceful_shutdown"), "got: {json}");
+    assert!(json["impl_block"]["header"].as_str().unwrap().starts_with("impl"), "got: {json}");
+    let related: Vec<&str> = json["related"]["items"].as_array().unwrap().iter()
+        .filter_map(|i| i["path"].as_str())
+        .collect();
+    assert!(related.contains(&"rmcp::transport::child_process::TokioChildProcess::new"), "got: {related:?}");
+
+    let result = crate_item_get::execute(&state, get("rmcp::ServerHandler::list_tools"))
+        .await
+        .expect("trait method should resolve");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["parent"]["kind"], "trait");
+    assert!(json["impl_block"].is_null());
+
+    let err = crate_item_get::execute(&state, get("rmcp::transport::TokioChildProcess::no_such_method"))
+        .await
+        .expect_err("unknown method should miss");
+    assert!(err.message.contains("not found"), "got: {}", err.message);
+}
+
+#[tokio::test]
+async fn fixture_mode_item_get_expands_trait_impl_methods() {
+    let state = make_state().await;
+    let get = |expand: Option<bool>| crate_item_get::CrateItemGetParams {
+        name: "rmcp".to_string(),
+        version: Some("0.16.0".to_string()),
+        item_path: "rmcp::service::RunningService".to_string(),
+        include_methods: Some(false),
+        include_trait_impls: None,
+        include_trait_impl_methods: expand,
+        impl_for: None,
+        target: None,
+    };
+    let deref_impl = |json: &serde_json::Value| json["trait_impls"].as_array().unwrap().iter()
+        .find(|t| t["trait_path"] == "Deref")
+        .cloned()
+        .expect("RunningService implements Deref");
+
+    let result = crate_item_get::execute(&state, get(None)).await.expect("item should resolve");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(deref_impl(&json), serde_json::json!({"trait_path": "Deref"}));
+
+    let result = crate_item_get::execute(&state, get(Some(true))).await.expect("item should resolve");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    let deref = deref_impl(&json);
+    assert_eq!(deref["associated_types"][0]["name"], "Target");
+    assert!(deref["associated_types"][0]["type"].as_str().unwrap().contains("Peer"), "got: {deref}");
+    assert_eq!(deref["methods"][0]["name"], "deref");
+    assert!(deref["methods"][0]["signature"].as_str().unwrap().contains("fn deref(&self)"), "got: {deref}");
+    assert!(deref["header"].as_str().unwrap().starts_with("impl"), "got: {deref}");
+}
+
+#[tokio::test]
+async fn fixture_mode_items_get_many_reports_each_path() {
+    let state = make_state().await;
+    let params = crate_items_get_many::CrateItemsGetManyParams {
+        name: "rmcp".to_string(),
+        version: Some("0.16.0".to_string()),
+        item_paths: vec![
+            "rmcp::service::ServiceExt".to_string(),
+            "rmcp::transport::TokioChildProcess::new".to_string(),
+            "rmcp::ServerHandlr".to_string(),
+            "rmcp::service::ServiceExt".to_string(),
+        ],
+        include_methods: Some(false),
+        include_trait_impls: Some("none".to_string()),
+        target: None,
+    };
+    let result = crate_items_get_many::execute(&state, params).await.expect("batch should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!((json["found"].as_u64(), json["missing"].as_u64()), (Some(2), Some(1)), "duplicates are looked up once");
+    let items = json["items"].as_array().unwrap();
+    assert_eq!(items[0]["kind"], "trait");
+    assert_eq!(items[1]["kind"], "method");
+    assert_eq!(items[2]["requested"], "rmcp::ServerHandlr");
+    assert_eq!(items[2]["suggestions"][0]["path"], "rmcp::handler::server::ServerHandler");
+
+    let params = crate_items_get_many::CrateItemsGetManyParams {
+        name: "rmcp".to_string(),
+        version: Some("0.16.0".to_string()),
+        item_paths: vec![],
+        include_methods: None,
+        include_trait_impls: None,
+        target: None,
+    };
+    assert!(crate_items_get_many::execute(&state, params).await.is_err(), "an empty batch is rejected");
+}
+
+#[tokio::test]
+async fn fixture_mode_searches_several_crates() {
+    let state = make_state().await;
+    let params = multi_crate_item_search::MultiCrateItemSearchParams {
+        crates: vec!["clap".to_string(), "rmcp@0.16.0".to_string(), "no-such-crate-xyz".to_string()],
+        query: "ServerHandler".to_string(),
+        kind: None,
+        limit_per_crate: Some(3),
+    };
+    let result = multi_crate_item_search::execute(&state, params).await.expect("search should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    let results = json["results"].as_array().unwrap();
+    assert_eq!(results[0]["crate"], "rmcp", "got: {json}");
+    assert_eq!(results[0]["version"], "0.16.0");
+    assert!(results[0]["items"].as_array().unwrap().len() <= 3);
+    assert_eq!(json["errors"][0]["crate"], "no-such-crate-xyz", "a missing crate fails alone");
+}
+
+#[tokio::test]
+async fn fixture_mode_cache_warm_reports_each_crate() {
+    let state = std::sync::Arc::new(make_state().await);
+    let idle = cache_warm::execute(&state, cache_warm::CacheWarmParams { crates: None, manifest_path: None, wait: None, cancel: None })
+        .await
+        .expect("progress should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&idle)).unwrap();
+    assert_eq!(json["state"], "idle");
+
+    let params = cache_warm::CacheWarmParams {
+        crates: Some(vec!["rmcp".to_string(), "clap@4.5.59".to_string(), "no-such-crate-xyz".to_string()]),
+        manifest_path: None,
+        wait: Some(true),
+        cancel: None,
+    };
+    let result = cache_warm::execute(&state, params).await.expect("warm-up should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["state"], "finished", "got: {json}");
+    assert_eq!(json["completed"], 3);
+    let warmed: Vec<(&str, &str, bool)> = json["warmed"].as_array().unwrap().iter()
+        .map(|w| (w["crate"].as_str().unwrap(), w["version"].as_str().unwrap(), w["docs"].as_bool().unwrap()))
+        .collect();
+    assert_eq!(warmed, [("rmcp", "0.16.0", true), ("clap", "4.5.59", true)]);
+    assert_eq!(json["failed"][0]["crate"], "no-such-crate-xyz");
+}
+
+#[tokio::test]
+async fn fixture_mode_serves_cratesio_metadata() {
+    let state = make_state().await;
+    let result = crate_get::execute(&state, crate_get::CrateGetParams { name: "clap".to_string(), version: None })
+        .await
+        .expect("crate get should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["name"], "clap");
+    assert_eq!(json["latest_stable"], "4.5.59");
+    assert_eq!(json["docs_available"], true);
+    assert!(json["latest_release_date"].is_string(), "got: {json}");
+}
+
+#[tokio::test]
+async fn fixture_mode_version_get_reports_trusted_publishing() {
+    use docs_mcp::tools::crate_version_get::{self, CrateVersionGetParams};
+    let state = make_state().await;
+    let params = CrateVersionGetParams { name: "rmcp".to_string(), version: "0.16.0".to_string() };
+    let result = crate_version_get::execute(&state, params).await.expect("version get should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["published_via"], "trusted_publishing");
+    assert_eq!(json["trusted_publishing"]["repository"], "modelcontextprotocol/rust-sdk");
+    assert_eq!(json["trusted_publishing"]["run_url"], "https://github.com/modelcontextprotocol/rust-sdk/actions/runs/13250000001");
+
+    let params = CrateVersionGetParams { name: "clap".to_string(), version: "4.5.59".to_string() };
+    let result = crate_version_get::execute(&state, params).await.expect("version get should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["published_via"], "api_token");
+    assert!(json.get("trusted_publishing").is_none(), "got: {json}");
+}
+
+#[tokio::test]
+async fn fixture_mode_crate_get_reports_requested_version() {
+    let state = make_state().await;
+    let params = crate_get::CrateGetParams { name: "clap".to_string(), version: Some("4.5.59".to_string()) };
+    let result = crate_get::execute(&state, params).await.expect("crate get should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["version"], "4.5.59");
+    assert_eq!(json["rust_version"], "1.74");
+    assert_eq!(json["yanked"], false);
+    assert!(json["dependency_count"].is_u64(), "got: {json}");
+
+    let params = crate_get::CrateGetParams { name: "clap".to_string(), version: Some("0.0.1".to_string()) };
+    let err = crate_get::execute(&state, params).await.unwrap_err();
+    assert_eq!(err.data.unwrap()["code"], "VERSION_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn fixture_mode_unknown_crate_is_an_error_not_a_network_call() {
+    let state = make_state().await;
+    let err = crate_get::execute(&state, crate_get::CrateGetParams { name: "serde".to_string(), version: None }).await.unwrap_err();
+    assert_eq!(err.data.unwrap()["code"], "CRATE_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn fixture_mode_probes_versions_in_order() {
+    let state = make_state().await;
+    let versions: Vec<String> = ["0.17.0", "0.16.0", "0.15.0", "0.14.0", "0.13.0", "0.16.0"]
+        .iter().map(|v| v.to_string()).collect();
+    let exists = docs_mcp::docsrs::docs_exist_many("rmcp", &versions, &state.client, &state.cache).await.unwrap();
+    assert_eq!(exists, vec![false, true, false, false, false, true]);
+
+    let nearest = docs_mcp::docsrs::nearest_built_version("rmcp", &versions, &state.client, &state.cache).await.unwrap();
+    assert_eq!(nearest.as_deref(), Some("0.16.0"));
+
+    // Both answers, the 404s included, now come from the cache
+    let (again, (hits, misses)) = docs_mcp::telemetry::count_cache_lookups(
+        docs_mcp::docsrs::docs_exist_many("rmcp", &versions, &state.client, &state.cache),
+    ).await;
+    assert_eq!(again.unwrap(), exists);
+    assert_eq!((hits, misses), (versions.len() as u64, 0));
+}
+
+#[tokio::test]
+async fn fixture_mode_records_fetched_crates_for_cross_crate_analysis() {
+    use docs_mcp::tools::crates_analyze;
+    let state = make_state().await;
+    let params = crates_analyze::CratesAnalyzeParams {
+        analysis: "stack".to_string(),
+        crates: vec!["rmcp".to_string(), "clap".to_string()],
+    };
+    let result = crates_analyze::execute(&state, params).await.expect("stack analysis should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["crates_in_store"], 2);
+    assert_eq!(json["result"], serde_json::json!([]), "rmcp and clap share no dependencies");
+    let store = state.store.as_ref().expect("fixture mode has a store");
+    assert_eq!(store.latest_stable("rmcp").unwrap().as_deref(), Some("0.16.0"));
+    assert!(!store.needs_record("rmcp"), "recorded within the refresh interval");
+}
+
+#[tokio::test]
+async fn fixture_mode_trait_check_sees_blanket_impls() {
+    use docs_mcp::tools::crate_trait_check::{self, CrateTraitCheckParams};
+    let state = make_state().await;
+    let check = |trait_path: &str| CrateTraitCheckParams {
+        name: "rmcp".to_string(),
+        version: None,
+        type_path: "rmcp::transport::child_process::TokioChildProcess".to_string(),
+        trait_path: trait_path.to_string(),
+    };
+
+    let result = crate_trait_check::execute(&state, check("IntoTransport")).await.expect("trait check should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["verdict"], "yes");
+    assert!(json["matches"].as_array().unwrap().iter().any(|m| m["via"] == "blanket"), "got: {json}");
+
+    let result = crate_trait_check::execute(&state, check("Send")).await.expect("trait check should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["verdict"], "yes", "auto traits count: {json}");
+}
+
+#[tokio::test]
+async fn fixture_mode_impls_list_reports_headers_and_modules() {
+    use docs_mcp::tools::crate_impls_list::{self, CrateImplsListParams};
+    let state = make_state().await;
+    let params = CrateImplsListParams {
+        name: "rmcp".to_string(),
+        version: None,
+        trait_path: None,
+        type_path: Some("rmcp::transport::child_process::TokioChildProcess".to_string()),
+        search: None,
+        limit: None,
+    };
+    let result = crate_impls_list::execute(&state, params).await.expect("impls list should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    let transport = json["implementations"].as_array().unwrap().iter()
+        .find(|i| i["impl_id"] == "9409")
+        .unwrap_or_else(|| panic!("Transport impl missing: {json}"));
+    assert!(transport["header"].as_str().unwrap().contains("for TokioChildProcess"), "got: {transport}");
+    assert_eq!(transport["module"], "rmcp::transport::child_process");
+    assert!(transport["where_clauses"].is_array());
+    assert_eq!(transport["origin"], "local_trait_local_type");
+    assert_eq!((transport["trait_crate"].as_str(), transport["type_crate"].as_str()), (Some("rmcp"), Some("rmcp")));
+    let into = json["implementations"].as_array().unwrap().iter()
+        .find(|i| i["trait_path"] == "Into<U>")
+        .unwrap_or_else(|| panic!("Into impl missing: {json}"));
+    assert_eq!(into["origin"], "foreign_trait_blanket");
+    assert!(into["type_crate"].is_null());
+}
+
+#[tokio::test]
+async fn fixture_mode_impls_list_matches_trait_generic_args() {
+    use docs_mcp::tools::crate_impls_list::{self, CrateImplsListParams};
+    let state = make_state().await;
+    let list = |trait_path: &str| CrateImplsListParams {
+        name: "rmcp".to_string(),
+        version: None,
+        trait_path: Some(trait_path.to_string()),
+        type_path: None,
+        search: None,
+        limit: Some(200),
+    };
+    let count = |json: &serde_json::Value| json["count"].as_u64().unwrap();
+
+    let result = crate_impls_list::execute(&state, list("From")).await.expect("impls list should succeed");
+    let all: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    let result = crate_impls_list::execute(&state, list("From<tokio::task::JoinError>")).await.expect("impls list should succeed");
+    let join_error: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+
+    assert!(count(&join_error) > 0, "got: {join_error}");
+    assert!(count(&join_error) < count(&all));
+    assert!(join_error["implementors"].as_array().unwrap().iter()
+        .all(|i| i["header"].as_str().unwrap().contains("From<JoinError>")), "got: {join_error}");
+    assert!(join_error["implementors"].as_array().unwrap().iter()
+        .all(|i| i["origin"] == "foreign_trait_local_type" && i["type_crate"] == "rmcp"), "got: {join_error}");
+
+    let result = crate_impls_list::execute(&state, list("rmcp::model::IntoContents")).await.expect("impls list should succeed");
+    let into_contents: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    let for_string = into_contents["implementors"].as_array().unwrap().iter()
+        .find(|i| i["type_name"] == "String")
+        .unwrap_or_else(|| panic!("IntoContents for String missing: {into_contents}"));
+    assert_eq!(for_string["origin"], "local_trait_foreign_type");
+    assert_ne!(for_string["type_crate"], "rmcp");
+}
+
+#[tokio::test]
+async fn fixture_mode_lists_crate_files_with_glob() {
+    use docs_mcp::tools::crate_files_list::{self, CrateFilesListParams};
+    let state = make_state().await;
+    let list = |pattern: Option<&str>| CrateFilesListParams {
+        name: "rmcp".to_string(),
+        version: None,
+        pattern: pattern.map(str::to_string),
+        limit: None,
+    };
+
+    let result = crate_files_list::execute(&state, list(None)).await.expect("files list should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    let paths: Vec<&str> = json["files"].as_array().unwrap().iter().map(|f| f["path"].as_str().unwrap()).collect();
+    assert!(paths.contains(&"Cargo.toml") && paths.contains(&"src/lib.rs"), "got: {paths:?}");
+    assert_eq!(json["matched"], json["total_files"]);
+    // The archive matches the cksum of its index line
+    assert_eq!(json["checksum"]["verified"], true, "got: {}", json["checksum"]);
+
+    let result = crate_files_list::execute(&state, list(Some("*.md"))).await.expect("files list should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    let paths: Vec<&str> = json["files"].as_array().unwrap().iter().map(|f| f["path"].as_str().unwrap()).collect();
+    assert_eq!(paths, ["README.md", "examples/README.md"]);
+}
+
+#[tokio::test]
+async fn fixture_mode_reads_crate_files() {
+    use docs_mcp::tools::crate_file_get::{self, CrateFileGetParams};
+    let state = make_state().await;
+    let get = |path: &str, max_bytes: Option<usize>| CrateFileGetParams {
+        name: "rmcp".to_string(),
+        version: None,
+        path: path.to_string(),
+        max_bytes,
+    };
+
+    let result = crate_file_get::execute(&state, get("src/lib.rs", None)).await.expect("file get should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert!(json["content"].as_str().unwrap().contains("pub mod transport;"), "got: {json}");
+    assert_eq!(json["binary"], false);
+    assert_eq!(json["truncated"], false);
+
+    let result = crate_file_get::execute(&state, get("README.md", Some(10))).await.expect("file get should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["content"], "# RMCP\n\nAn");
+    assert_eq!(json["truncated"], true);
+
+    let result = crate_file_get::execute(&state, get("assets/logo.png", None)).await.expect("file get should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["binary"], true);
+    assert!(json["content"].is_null());
+
+    let err = crate_file_get::execute(&state, get("src/missing.rs", None)).await.unwrap_err();
+    assert!(err.message.contains("crate_files_list"), "got: {}", err.message);
+}
+
+#[tokio::test]
+async fn fixture_mode_readme_markdown_comes_from_package() {
+    use docs_mcp::tools::crate_readme_get::{self, CrateReadmeGetParams};
+    let state = make_state().await;
+    let params = CrateReadmeGetParams {
+        name: "rmcp".to_string(),
+        version: None,
+        format: Some("markdown".to_string()),
+        section: None,
+    };
+    let result = crate_readme_get::execute(&state, params).await.expect("readme get should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["readme_path"], "README.md");
+    let markdown = json["readme_markdown"].as_str().unwrap();
+    assert!(markdown.contains("[examples](examples/README.md)"), "links stay markdown: {markdown}");
+    assert!(markdown.contains("| `server` |"), "tables stay markdown: {markdown}");
+}
+
+#[tokio::test]
+async fn fixture_mode_readme_section_filter() {
+    use docs_mcp::tools::crate_readme_get::{self, CrateReadmeGetParams};
+    let state = make_state().await;
+    let params = |section: &str| CrateReadmeGetParams {
+        name: "rmcp".to_string(),
+        version: None,
+        format: Some("markdown".to_string()),
+        section: Some(section.to_string()),
+    };
+    let result = crate_readme_get::execute(&state, params("usage")).await.expect("readme get should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    let markdown = json["readme_markdown"].as_str().unwrap();
+    assert!(markdown.starts_with("## Usage"), "got: {markdown}");
+    assert!(markdown.contains("use rmcp::"), "got: {markdown}");
+    assert!(!markdown.contains("Apache-2.0"), "later sections are left out: {markdown}");
+    assert_eq!(json["sections_matched"], serde_json::json!(["Usage"]));
+
+    let err = crate_readme_get::execute(&state, params("Examples")).await.unwrap_err();
+    assert!(err.message.contains("Usage, License"), "got: {}", err.message);
+}
+
+#[tokio::test]
+async fn fixture_mode_compares_items_side_by_side() {
+    use docs_mcp::tools::crate_item_compare::{self, CrateItemCompareParams};
+    let state = make_state().await;
+    let params = CrateItemCompareParams {
+        name_a: "rmcp".to_string(),
+        path_a: "rmcp::transport::child_process::TokioChildProcess".to_string(),
+        version_a: None,
+        name_b: "rmcp".to_string(),
+        path_b: "rmcp::transport::async_rw::AsyncRwTransport".to_string(),
+        version_b: Some("0.16.0".to_string()),
+    };
+    let result = crate_item_compare::execute(&state, params).await.expect("item compare should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["a"]["version"], "0.16.0");
+    assert_eq!(json["same_kind"], true);
+    let only_a: Vec<&str> = json["methods"]["only_a"].as_array().unwrap().iter()
+        .filter_map(|m| m["name"].as_str())
+        .collect();
+    assert!(only_a.contains(&"graceful_shutdown"), "got: {only_a:?}");
+    assert!(json["trait_impls"]["common"].is_array());
+}
+
+#[tokio::test]
+async fn fixture_mode_reports_trait_gap_with_skeleton() {
+    use docs_mcp::tools::crate_trait_gap::{self, CrateTraitGapParams};
+    let state = make_state().await;
+    let gap = |type_path: &str| CrateTraitGapParams {
+        name: "rmcp".to_string(),
+        version: None,
+        type_path: type_path.to_string(),
+        trait_path: "rmcp::transport::Transport".to_string(),
+        trait_crate: None,
+    };
+
+    let result = crate_trait_gap::execute(&state, gap("rmcp::transport::child_process::TokioChildProcess")).await
+        .expect("trait gap should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["verdict"], "yes");
+    assert_eq!(json["missing"], serde_json::json!([]));
+    assert!(json["skeleton"].is_null());
+
+    let result = crate_trait_gap::execute(&state, gap("rmcp::model::Tool")).await
+        .expect("trait gap should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    let missing: Vec<&str> = json["missing"].as_array().unwrap().iter()
+        .filter_map(|m| m["name"].as_str())
+        .collect();
+    assert!(missing.contains(&"send") && missing.contains(&"Error"), "got: {missing:?}");
+    let skeleton = json["skeleton"].as_str().expect("skeleton for an unimplemented trait");
+    assert!(skeleton.starts_with("impl<R"), "got: {skeleton}");
+    assert!(skeleton.contains("todo!()"), "got: {skeleton}");
+}
+
+#[tokio::test]
+async fn fixture_mode_trait_item_renders_impl_skeleton() {
+    let state = make_state().await;
+    let params = crate_item_get::CrateItemGetParams {
+        name: "rmcp".to_string(),
+        version: None,
+        item_path: "rmcp::transport::Transport".to_string(),
+        include_methods: Some(false),
+        include_trait_impls: Some("none".to_string()),
+        include_trait_impl_methods: None,
+        impl_for: Some("MyTransport".to_string()),
+        target: None,
+    };
+    let result = crate_item_get::execute(&state, params).await.expect("item get should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    let skeleton = json["impl_skeleton"].as_str().expect("skeleton for a trait");
+    assert!(skeleton.starts_with("impl<R> rmcp::transport::Transport<R> for MyTransport\nwhere\n"), "got: {skeleton}");
+    assert!(
+        skeleton.contains("fn send(&mut self, item: TxJsonRpcMessage<R>) -> impl Future<Output = Result<(), Self::Error>> + Send + 'static {"),
+        "got: {skeleton}",
+    );
+    assert!(skeleton.contains("type Error = /* a type implementing "), "got: {skeleton}");
+    assert!(!skeleton.contains("fn name"), "provided methods are not stubbed: {skeleton}");
+}
+
+#[tokio::test]
+async fn fixture_mode_crate_list_filters_by_msrv_edition_and_license() {
+    use docs_mcp::tools::crate_list::{self, CrateListParams};
+    let state = make_state().await;
+    let list = |license: Option<&str>, max_rust_version: Option<&str>, max_edition: Option<&str>| CrateListParams {
+        query: Some("mcp".to_string()),
+        category: None,
+        keyword: None,
+        sort: None,
+        page: None,
+        per_page: None,
+        dedupe_forks: None,
+        license: license.map(str::to_string),
+        max_rust_version: max_rust_version.map(str::to_string),
+        max_edition: max_edition.map(str::to_string),
+        detail: None,
+    };
+    let names = |json: &serde_json::Value| -> Vec<String> {
+        json["crates"].as_array().unwrap().iter()
+            .filter_map(|c| c["name"].as_str().map(str::to_string))
+            .collect()
+    };
+
+    for params in [list(None, Some("1.80"), None), list(None, None, Some("2021")), list(Some("Apache-2.0"), None, None)] {
+        let result = crate_list::execute(&state, params).await.expect("crate list should succeed");
+        let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+        assert_eq!(names(&json), vec!["clap"], "got: {json}");
+        assert_eq!(json["filtered_out"], serde_json::json!(["rmcp"]));
+    }
+}
+
+#[tokio::test]
+async fn fixture_mode_crate_list_detail_levels() {
+    use docs_mcp::tools::crate_list::{self, CrateListParams};
+    let state = make_state().await;
+    let list = |detail: Option<&str>| CrateListParams {
+        query: Some("mcp".to_string()),
+        category: None,
+        keyword: None,
+        sort: None,
+        page: None,
+        per_page: None,
+        dedupe_forks: None,
+        license: None,
+        max_rust_version: None,
+        max_edition: None,
+        detail: detail.map(str::to_string),
+    };
+    let run = |params| async {
+        let result = crate_list::execute(&state, params).await.expect("crate list should succeed");
+        serde_json::from_str::<serde_json::Value>(&extract_text(&result)).unwrap()
+    };
+
+    let names = run(list(Some("names"))).await;
+    assert_eq!(names["crates"], serde_json::json!(["rmcp", "clap"]), "got: {names}");
+
+    let summary = run(list(None)).await;
+    let first = &summary["crates"][0];
+    assert_eq!(first["name"], "rmcp");
+    assert!(first["downloads"].is_number());
+    assert!(first.get("created_at").is_none(), "summary omits the full record: {first}");
+
+    let full = run(list(Some("full"))).await;
+    let first = &full["crates"][0];
+    assert_eq!(first["name"], "rmcp");
+    assert!(first["created_at"].is_string(), "got: {first}");
+    assert_eq!(first["version"], summary["crates"][0]["version"]);
+
+    let err = crate_list::execute(&state, list(Some("ids"))).await.unwrap_err();
+    assert!(err.message.contains("Unknown detail"), "got: {}", err.message);
+}
+
+#[tokio::test]
+async fn fixture_mode_module_expand_drills_down_one_level() {
+    use docs_mcp::tools::crate_module_expand::{self, CrateModuleExpandParams};
+    let state = make_state().await;
+    let expand = |path: Option<&str>| CrateModuleExpandParams {
+        name: "rmcp".to_string(),
+        version: None,
+        path: path.map(str::to_string),
+        include_hidden: None,
+    };
+    let submodule = |json: &serde_json::Value, path: &str| json["submodules"].as_array().unwrap().iter()
+        .find(|m| m["path"] == path)
+        .cloned()
+        .unwrap_or_else(|| panic!("no submodule {path} in {json}"));
+
+    let result = crate_module_expand::execute(&state, expand(None)).await.expect("root should expand");
+    let root: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(root["path"], "rmcp");
+    let transport = submodule(&root, "rmcp::transport");
+    assert_eq!(transport["has_submodules"], true);
+    assert!(transport.get("children").is_none(), "only one level is returned: {transport}");
+
+    let result = crate_module_expand::execute(&state, expand(Some("rmcp::transport"))).await.expect("transport should expand");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    let child_process = submodule(&json, "rmcp::transport::child_process");
+    assert_eq!(child_process["feature_requirements"], serde_json::json!(["transport-child-process"]));
+    let items: Vec<&str> = json["items"].as_array().unwrap().iter()
+        .filter_map(|i| i["path"].as_str())
+        .collect();
+    assert!(items.contains(&"rmcp::transport::Transport"), "got: {items:?}");
+
+    let err = crate_module_expand::execute(&state, expand(Some("rmcp::nope"))).await.unwrap_err();
+    assert!(err.message.contains("not found"), "got: {}", err.message);
+}
+
+#[tokio::test]
+async fn fixture_mode_session_context_lists_resolved_versions() {
+    use docs_mcp::{session, tools::session_context};
+    let state = make_state().await;
+    // Versions are recorded against the session whose call resolves them
+    let result = session::scope(Default::default(), async {
+        state.resolve_version("rmcp", None).await.unwrap();
+        state.resolve_version("clap", Some("4.5.59")).await.unwrap();
+        session_context::execute(&state).await
+    }).await.expect("session context should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["crate_count"], 2);
+    let rmcp = &json["crates"][0];
+    assert_eq!(rmcp["name"], "rmcp");
+    assert_eq!(rmcp["versions"][0]["version"], "0.16.0");
+    assert_eq!(rmcp["versions"][0]["defaulted"], true);
+    assert!(rmcp["versions"][0]["default_features"].as_array().is_some_and(|f| !f.is_empty()), "got: {rmcp}");
+    let clap = &json["crates"][1];
+    assert_eq!(clap["versions"][0]["defaulted"], false);
+    assert_eq!(clap["versions"][0]["yanked"], false);
+}
+
+#[tokio::test]
+async fn fixture_mode_dependencies_follow_the_index_to_depth() {
+    use docs_mcp::tools::crate_dependencies_list::{self, CrateDependenciesListParams};
+    let state = make_state().await;
+    let params = CrateDependenciesListParams {
+        name: "clap".to_string(),
+        version: Some("4.5.59".to_string()),
+        kind: None,
+        search: None,
+        depth: Some(3),
+        resolve: None,
+        audit: None,
+        graph: None,
+    };
+    let result = crate_dependencies_list::execute(&state, params).await.expect("dependencies should list");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+
+    assert_eq!(json["count"], 2, "direct list is unchanged: {json}");
+    // clap_derive and strsim are optional, so only the builder chain is followed
+    assert_eq!(json["transitive"], serde_json::json!([
+        {"name": "clap_builder", "version": "4.5.59", "req": "=4.5.59", "depth": 1, "path": ["clap", "clap_builder"]},
+        {"name": "clap_lex", "version": "0.7.5", "req": "^0.7.4", "depth": 2, "path": ["clap", "clap_builder", "clap_lex"]},
+    ]));
+    assert_eq!(json["unique_crates"], 2);
+    assert!(json.get("unresolved").is_none(), "got: {json}");
+}
+
+#[tokio::test]
+async fn fixture_mode_dependencies_render_as_dot_or_mermaid() {
+    use docs_mcp::tools::crate_dependencies_list::{self, CrateDependenciesListParams};
+    let state = make_state().await;
+    let params = |depth: u32, graph: &str| CrateDependenciesListParams {
+        name: "clap".to_string(),
+        version: Some("4.5.59".to_string()),
+        kind: None,
+        search: None,
+        depth: Some(depth),
+        resolve: None,
+        audit: None,
+        graph: Some(graph.to_string()),
+    };
+    let result = crate_dependencies_list::execute(&state, params(3, "dot")).await.expect("dependencies should list");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["graph"], "digraph dependencies {\n    rankdir=LR;\n    node [shape=box];\n    \
+        n0 [label=\"clap 4.5.59\"];\n    n1 [label=\"clap_builder 4.5.59\"];\n    n2 [label=\"clap_lex 0.7.5\"];\n    \
+        n0 -> n1;\n    n1 -> n2;\n}\n");
+
+    // Depth 1 still resolves the direct dependencies for their versions, without a transitive list
+    let result = crate_dependencies_list::execute(&state, params(1, "Mermaid")).await.expect("dependencies should list");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["graph"], "graph LR\n    n0[\"clap 4.5.59\"]\n    n1[\"clap_builder 4.5.59\"]\n    n0 --> n1\n");
+    assert!(json.get("transitive").is_none(), "got: {json}");
+
+    assert!(crate_dependencies_list::execute(&state, params(1, "svg")).await.is_err());
+}
+
+#[tokio::test]
+async fn fixture_mode_dependencies_audit_reports_unreachable_osv() {
+    use docs_mcp::tools::crate_dependencies_list::{self, CrateDependenciesListParams};
+    let state = make_state().await;
+    let params = CrateDependenciesListParams {
+        name: "clap".to_string(),
+        version: Some("4.5.59".to_string()),
+        kind: None,
+        search: None,
+        depth: Some(3),
+        resolve: None,
+        audit: Some(true),
+        graph: None,
+    };
+    let result = crate_dependencies_list::execute(&state, params).await.expect("dependencies should list");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+
+    // clap itself, plus clap_builder (direct and transitive) and clap_lex
+    assert_eq!(json["audited_count"], 3, "got: {json}");
+    // Fixture mode has no OSV responses; the list still comes back
+    assert!(json["vulnerability_lookup_error"].as_str().is_some_and(|e| e.contains("api.osv.dev")), "got: {json}");
+    assert_eq!(json["count"], 2);
+}
+
+#[tokio::test]
+async fn fixture_mode_dependencies_resolve_requirements() {
+    use docs_mcp::tools::crate_dependencies_list::{self, CrateDependenciesListParams};
+    let state = make_state().await;
+    let params = CrateDependenciesListParams {
+        name: "clap".to_string(),
+        version: Some("4.5.59".to_string()),
+        kind: None,
+        search: None,
+        depth: None,
+        resolve: Some(true),
+        audit: None,
+        graph: None,
+    };
+    let result = crate_dependencies_list::execute(&state, params).await.expect("dependencies should list");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    let dep = |name: &str| json["dependencies"].as_array().unwrap().iter()
+        .find(|d| d["crate_id"] == name)
+        .cloned()
+        .unwrap();
+
+    assert_eq!(dep("clap_builder")["resolved"], serde_json::json!({
+        "version": "4.5.59", "rust_version": "1.74", "yanked": false,
+    }));
+    // No index fixture for clap_derive
+    let derive = dep("clap_derive");
+    assert!(derive.get("resolved").is_none());
+    assert!(derive["resolve_error"].is_string(), "got: {derive}");
+}
+
+#[tokio::test]
+async fn fixture_mode_compares_downloads_across_crates() {
+    use docs_mcp::tools::crate_downloads_get::{self, CrateDownloadsGetParams};
+    let state = make_state().await;
+    let params = CrateDownloadsGetParams {
+        name: "rmcp".to_string(),
+        before_date: Some("2026-02-28".to_string()),
+        compare_with: Some(vec!["clap".to_string()]),
+    };
+    let result = crate_downloads_get::execute(&state, params).await.expect("comparison should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+
+    let crates = json["crates"].as_array().unwrap();
+    assert_eq!(crates.iter().map(|c| c["name"].as_str().unwrap()).collect::<Vec<_>>(), ["rmcp", "clap"]);
+    let share: f64 = crates.iter().map(|c| c["share_90d"].as_f64().unwrap()).sum();
+    assert!((share - 1.0).abs() < 1e-9, "shares add up: {share}");
+    assert!(crates[1]["share_90d"].as_f64().unwrap() > crates[0]["share_90d"].as_f64().unwrap());
+
+    let daily = json["daily"].as_array().unwrap();
+    assert!(!daily.is_empty());
+    for day in daily {
+        assert!(day["downloads"]["rmcp"].is_u64() && day["downloads"]["clap"].is_u64(), "aligned: {day}");
+    }
+}
+
+#[tokio::test]
+async fn fixture_mode_versions_list_reports_release_cadence() {
+    let state = make_state().await;
+    let params = crate_versions_list::CrateVersionsListParams {
+        name: "rmcp".to_string(),
+        include_yanked: None,
+        include_prerelease: None,
+        search: None,
+        max_rust_version: None,
+        page: None,
+        per_page: None,
+        cadence: Some(true),
+    };
+    let result = crate_versions_list::execute(&state, params).await.expect("versions list should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["versions"][0]["published_at"], "2026-02-01T12:00:00+00:00");
+    let cadence = &json["cadence"];
+    assert_eq!(cadence["releases"], 1);
+    assert_eq!(cadence["last_release"], "2026-02-01T12:00:00+00:00");
+    assert!(cadence["days_since_last_release"].as_i64().is_some_and(|d| d >= 0), "got: {cadence}");
+    assert!(cadence["median_days_between_releases"].is_null());
+}
+
+#[tokio::test]
+async fn fixture_mode_yanks_list_reports_advisory_lookup_failure_without_failing() {
+    let state = make_state().await;
+    let params = crate_yanks_list::CrateYanksListParams { name: "rmcp".to_string() };
+    let result = crate_yanks_list::execute(&state, params).await.expect("yanks list should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["total_versions"], 1);
+    assert_eq!(json["yanked_count"], 0);
+    assert!(json["yanked"].as_array().unwrap().is_empty());
+    // OSV has no fixtures, so the advisory lookup 404s and is reported alongside the yanks
+    assert!(json["advisory_lookup_error"].is_string(), "got: {json}");
+}
+
+#[tokio::test]
+async fn fixture_mode_suggests_near_matches_for_unknown_crates() {
+    let state = make_state().await;
+    assert_eq!(state.crate_name_suggestions("clap-builder").await, ["clap_builder"]);
+    assert_eq!(state.crate_name_suggestions("rmpc").await, ["rmcp"]);
+    assert!(state.crate_name_suggestions("rmcp").await.is_empty(), "existing crates get no suggestions");
+}
+
+#[tokio::test]
+async fn fixture_mode_unbuilt_target_is_a_missing_build() {
+    let state = make_state().await;
+    let params = |target: &str| crate_item_get::CrateItemGetParams {
+        name: "rmcp".to_string(),
+        version: Some("0.16.0".to_string()),
+        item_path: "rmcp::service::ServiceExt".to_string(),
+        include_methods: Some(false),
+        include_trait_impls: Some("none".to_string()),
+        include_trait_impl_methods: None,
+        impl_for: None,
+        target: Some(target.to_string()),
+    };
+    let err = crate_item_get::execute(&state, params("wasm32-unknown-unknown")).await.unwrap_err();
+    assert_eq!(err.data.unwrap()["code"], "DOCS_BUILD_MISSING");
+    assert!(err.message.contains("wasm32-unknown-unknown"), "got: {}", err.message);
+
+    for target in ["../x", ".", ".."] {
+        let err = crate_item_get::execute(&state, params(target)).await.unwrap_err();
+        assert_eq!(err.data.unwrap()["code"], "INVALID_PARAMS", "{target}");
+    }
+}
+
+#[tokio::test]
+async fn fixture_mode_no_std_check_reads_the_crate_root() {
+    let state = make_state().await;
+    let params = crate_no_std_check::CrateNoStdCheckParams { name: "rmcp".to_string(), version: None };
+    let result = crate_no_std_check::execute(&state, params).await.expect("no_std check should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["lib_root"], "src/lib.rs");
+    assert_eq!(json["support"], "unsupported");
+    assert!(json["recommended"].is_null(), "got: {json}");
+}
+
+#[tokio::test]
+async fn fixture_mode_ffi_list_finds_no_ffi_in_a_pure_rust_crate() {
+    let state = make_state().await;
+    let params = crate_ffi_list::CrateFfiListParams {
+        name: "rmcp".to_string(), version: None, target: None, include_hidden: None,
+    };
+    let result = crate_ffi_list::execute(&state, params).await.expect("ffi list should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["summary"]["extern_functions"], 0, "got: {json}");
+    // No extern fns, raw pointer signatures or repr types, so both lists are empty
+    assert_eq!(json["summary"]["raw_pointer_functions"], 0, "got: {json}");
+    assert_eq!(json["functions"], serde_json::json!([]));
+    assert_eq!(json["types"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn fixture_mode_errors_list_catalogues_error_types() {
+    let state = make_state().await;
+    let params = crate_errors_list::CrateErrorsListParams { name: "rmcp".to_string(), version: None };
+    let result = crate_errors_list::execute(&state, params).await.expect("errors list should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    let errors = json["errors"].as_array().unwrap();
+    let find = |path: &str| errors.iter().find(|e| e["path"] == path).unwrap_or_else(|| panic!("{path} missing"));
+
+    let rmcp_error = find("rmcp::error::RmcpError");
+    assert_eq!(rmcp_error["implements_error"], true);
+    assert!(rmcp_error["variants"].as_array().unwrap().iter().any(|v| v["declaration"] == "Runtime(tokio::task::JoinError)"));
+    assert_eq!(rmcp_error["converts_from"].as_array().unwrap().len(), 4, "blanket From<T> is skipped: {rmcp_error}");
+    assert!(find("rmcp::service::ServiceError")["converts_into"].as_array().unwrap().contains(&serde_json::json!("RmcpError")));
+    assert_eq!(find("rmcp::model::JsonRpcError")["implements_error"], false);
+}
+
+#[tokio::test]
+async fn fixture_mode_async_list_groups_by_module() {
+    let state = make_state().await;
+    let params = crate_async_list::CrateAsyncListParams { name: "rmcp".to_string(), version: None };
+    let result = crate_async_list::execute(&state, params).await.expect("async list should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    let summary = &json["summary"];
+    assert!(summary["async_fns"].as_u64().unwrap() > 0 && summary["future_returning"].as_u64().unwrap() > 0, "got: {summary}");
+
+    let client = json["modules"].as_array().unwrap().iter()
+        .find(|m| m["module"] == "rmcp::handler::client")
+        .expect("trait methods are grouped under the trait's module");
+    let list_roots = client["functions"].as_array().unwrap().iter()
+        .find(|f| f["path"] == "rmcp::handler::client::ClientHandler::list_roots")
+        .expect("list_roots returns impl Future");
+    assert_eq!(list_roots["kind"], "future");
+}
+
+#[tokio::test]
+async fn fixture_mode_item_list_semantic_mode() {
+    let state = make_state().await;
+    let params = crate_item_list::CrateItemListParams {
+        name: "rmcp".to_string(),
+        version: None,
+        query: "cancel a request that is running".to_string(),
+        kind: None,
+        module_prefix: None,
+        limit: Some(5),
+        include_hidden: None,
+        target: None,
+        semantic: Some(true),
+    };
+    let result = crate_item_list::execute(&state, params).await;
+    if !cfg!(feature = "semantic-search") {
+        let err = result.expect_err("semantic mode needs the semantic-search feature");
+        assert!(err.message.contains("semantic-search"), "got: {}", err.message);
+        return;
+    }
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result.expect("semantic search should succeed"))).unwrap();
+    assert_eq!(json["embedder"], "hashing-384");
+    let items = json["items"].as_array().unwrap();
+    assert!(!items.is_empty(), "expected semantic matches, got: {json}");
+    assert_eq!(items[0]["path"], "rmcp::service::RequestHandle::cancel");
+    // The index is stored for the next query
+    assert!(state.cache.dir().join("embeddings/hashing-384/rmcp@0.16.0.idx").exists());
+}
+
+#[tokio::test]
+async fn fixture_mode_docs_search_finds_text_beyond_summaries() {
+    let state = make_state().await;
+    let params = crate_docs_search::CrateDocsSearchParams {
+        name: "rmcp".to_string(),
+        version: None,
+        query: "stdin stdout".to_string(),
+        kind: None,
+        module_prefix: None,
+        limit: Some(5),
+        target: None,
+    };
+    let result = crate_docs_search::execute(&state, params).await.expect("docs search should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    let results = json["results"].as_array().unwrap();
+    assert!(json["total_matches"].as_u64().unwrap() >= results.len() as u64);
+    // Found by words deep in the docs, not just the summary line
+    let stdio = results.iter().find(|r| r["path"] == "rmcp::transport::io::stdio").expect("stdio should match");
+    assert_eq!(stdio["section"], "StdIO Transport");
+    assert!(stdio["snippet"].as_str().unwrap().contains("**Stdin**"), "got: {stdio}");
+    // Hidden doctest lines are not section headings
+    assert!(results.iter().all(|r| !r["section"].as_str().unwrap_or("").starts_with("use ")), "got: {json}");
+}
+
+#[tokio::test]
+async fn fixture_mode_scorecard_combines_package_graph_and_project() {
+    use docs_mcp::tools::crate_scorecard_get::{self, CrateScorecardGetParams};
+    let state = make_state().await;
+    let params = CrateScorecardGetParams {
+        name: "rmcp".to_string(),
+        version: Some("0.16.0".to_string()),
+        include_dependencies: Some(true),
+    };
+    let result = crate_scorecard_get::execute(&state, params).await.expect("scorecard should load");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+
+    assert_eq!(json["licenses"], serde_json::json!(["Apache-2.0"]));
+    assert_eq!(json["dependencies"]["direct"], 6);
+    assert_eq!(json["dependencies"]["indirect"], 3);
+    assert!(json["dependencies"].get("resolution_error").is_none(), "got: {json}");
+    assert_eq!(json["dependencies"]["crates"][0], serde_json::json!({"name": "futures", "version": "0.3.31", "relation": "direct"}));
+    assert_eq!(json["project"]["id"], "github.com/modelcontextprotocol/rust-sdk");
+    assert_eq!(json["project"]["stars"], 2741);
+    assert_eq!(json["scorecard"]["overall_score"], 6.4);
+    let checks: Vec<&str> = json["scorecard"]["checks"].as_array().unwrap().iter()
+        .map(|c| c["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(checks, ["Fuzzing", "Token-Permissions", "Code-Review", "Maintained", "Packaging"]);
+}
+
+#[tokio::test]
+async fn fixture_mode_build_risk_reads_the_manifest_and_reports_unresolved_deps() {
+    use docs_mcp::tools::crate_build_risk::{self, CrateBuildRiskParams};
+    let state = std::sync::Arc::new(make_state().await);
+    let params = CrateBuildRiskParams { name: "rmcp".to_string(), version: None, depth: None };
+    let result = crate_build_risk::execute(&state, params).await.expect("build risk should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+
+    // The fixture manifest says `build = false` and has no `[lib] proc-macro`
+    assert_eq!(json["build_script"], serde_json::Value::Null);
+    assert_eq!(json["proc_macro"], false);
+    assert_eq!(json["checksum"]["verified"], true);
+    // Only rmcp is in the fixture index, so its dependencies can't be resolved or checked.
+    // base64, schemars and rmcp-macros are optional but turned on by default features.
+    let dependencies = &json["dependencies"];
+    assert_eq!(dependencies["checked"], 0);
+    let unresolved: Vec<&str> = dependencies["unresolved"].as_array().unwrap().iter()
+        .map(|d| d["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(unresolved, ["serde", "serde_json", "tokio", "futures", "thiserror", "base64", "schemars", "rmcp-macros", "tracing"]);
+}
+
+#[tokio::test]
+async fn fixture_mode_audit_report_combines_sections_and_flags() {
+    use docs_mcp::tools::crate_audit_report::{self, CrateAuditReportParams};
+    let state = make_state().await;
+    let params = CrateAuditReportParams { name: "rmcp".to_string(), version: None, format: None };
+    let result = crate_audit_report::execute(&state, params).await.expect("audit report should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+
+    assert_eq!(json["version"], "0.16.0");
+    assert_eq!(json["owners"]["users"], serde_json::json!(["fixture-publisher"]));
+    assert_eq!(json["dependents"], 1);
+    assert_eq!(json["repository_health"]["scorecard"], 6.4);
+    assert_eq!(json["dependencies"]["required"], 6);
+    assert!(json["flags"].as_array().unwrap().iter().any(|f| f.as_str().unwrap().starts_with("single owner")), "got: {json}");
+    // OSV has no fixtures; the rest of the report still stands
+    assert!(json["errors"]["advisories"].is_string(), "got: {json}");
+
+    let params = CrateAuditReportParams { name: "rmcp".to_string(), version: None, format: Some("markdown".to_string()) };
+    let result = crate_audit_report::execute(&state, params).await.expect("audit report should succeed");
+    let markdown = extract_text(&result);
+    assert!(markdown.starts_with("# Audit report: rmcp 0.16.0"), "got: {markdown}");
+    assert!(markdown.contains("## Repository health") && markdown.contains("- Dependent crates: 1"), "got: {markdown}");
+}
+
+#[tokio::test]
+async fn fixture_mode_owner_crates_list_looks_up_the_account() {
+    use docs_mcp::tools::owner_crates_list::{self, OwnerCratesListParams};
+    let state = make_state().await;
+    let params = |owner: &str| OwnerCratesListParams { owner: owner.to_string(), sort: None, page: None, per_page: None };
+    let result = owner_crates_list::execute(&state, params("fixture-publisher")).await.expect("owner crates should list");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["owner"]["kind"], "user");
+    assert_eq!(json["owner"]["name"], "Fixture Publisher");
+    assert_eq!(json["crates"][0]["name"], "rmcp");
+
+    let err = owner_crates_list::execute(&state, params("github:nobody:team")).await.unwrap_err();
+    assert_eq!(err.data.unwrap()["code"], "NOT_FOUND");
+    // The login stays one path segment
+    let err = owner_crates_list::execute(&state, params("../crates")).await.unwrap_err();
+    assert_eq!(err.data.unwrap()["code"], "NOT_FOUND");
+
+    let unknown_sort = OwnerCratesListParams { sort: Some("stars".to_string()), ..params("fixture-publisher") };
+    let err = owner_crates_list::execute(&state, unknown_sort).await.unwrap_err();
+    assert!(err.message.contains("recent-downloads"), "{}", err.message);
+    assert_eq!(err.data.unwrap()["code"], "INVALID_PARAMS");
+}
+
+#[tokio::test]
+async fn fixture_mode_maintenance_get_weighs_repository_activity() {
+    use docs_mcp::tools::crate_maintenance_get::{self, CrateMaintenanceGetParams};
+    let state = make_state().await;
+    let params = CrateMaintenanceGetParams { name: "rmcp".to_string(), include_repository: Some(true) };
+    let result = crate_maintenance_get::execute(&state, params).await.expect("maintenance status should load");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["last_release"]["version"], "0.16.0");
+    assert_eq!(json["repository"]["project"], "github.com/modelcontextprotocol/rust-sdk");
+    assert_eq!(json["repository"]["maintained_score"], 10);
+    // An active repository keeps a crate with rare releases from reading as abandoned
+    assert_ne!(json["status"], "likely_abandoned");
+    assert!(json["evidence"].as_array().unwrap().iter().any(|e| e.as_str().unwrap().contains("commit(s)")));
+}
+
+#[tokio::test]
+async fn fixture_mode_rust_toolchain_get_dates_an_msrv() {
+    use docs_mcp::tools::rust_toolchain_get::{self, RustToolchainGetParams};
+    let state = make_state().await;
+    let params = RustToolchainGetParams { rust_version: Some("1.70".to_string()) };
+    let result = rust_toolchain_get::execute(&state, params).await.expect("toolchain info should load");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["channels"]["stable"]["version"], "1.87.0");
+    assert_eq!(json["channels"]["stable"]["date"], "2025-05-15");
+    assert_eq!(json["channels"]["beta"]["version"], "1.88.0-beta.4");
+    assert_eq!(json["channels"]["nightly"]["commit"], "60dabef95");
+    assert_eq!(json["msrv"]["released"], "2023-06-01");
+    assert_eq!(json["msrv"]["releases_behind_stable"], 17);
+    assert!(json["msrv"]["summary"].as_str().unwrap().starts_with("requires a Rust released"));
+
+    let params = RustToolchainGetParams { rust_version: Some("latest".to_string()) };
+    assert!(rust_toolchain_get::execute(&state, params).await.is_err());
+    // Too far out to date, not a panic
+    let params = RustToolchainGetParams { rust_version: Some("1.99999999999999999".to_string()) };
+    let err = rust_toolchain_get::execute(&state, params).await.unwrap_err();
+    assert_eq!(err.data.unwrap()["code"], "INVALID_PARAMS");
+}
+
+#[tokio::test]
+async fn fixture_mode_concurrent_calls_share_one_parsed_doc() {
+    let state = std::sync::Arc::new(make_state().await);
+    let (a, b) = tokio::join!(state.rustdoc("rmcp", "0.16.0"), state.rustdoc("rmcp", "0.16.0"));
+    let (a, b) = (a.expect("rustdoc should load"), b.expect("rustdoc should load"));
+    assert!(std::sync::Arc::ptr_eq(&a, &b), "the second call should wait for the first parse");
+}
+
+#[tokio::test]
+async fn fixture_mode_query_calls_a_tool_without_a_client() {
+    use docs_mcp::{cli, server::DocsMcpServer};
+    let server = DocsMcpServer::new_with_state(std::sync::Arc::new(make_state().await));
+    let args: Vec<String> = ["--name", "rmcp", "--item-path", "rmcp::ServiceExt"].iter().map(|s| s.to_string()).collect();
+    let result = cli::query(server.clone(), "crate_item_get", &args).await.expect("query should succeed");
+    let json: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+    assert_eq!(json["kind"], "trait");
+
+    let err = cli::query(server.clone(), "crate_item_gte", &args).await.unwrap_err();
+    assert!(err.message.contains("crate_item_get"), "{}", err.message);
+    assert!(cli::query(server, "crate_get", &["--name".to_string(), "nosuchcrate".to_string()]).await.is_err());
+}
@@ -3,15 +3,15 @@
 /// Tool-behavior tests call real external APIs and are marked #[ignore = "requires network access"].
 use std::sync::Arc;
 
-use docs_mcp::{server::DocsMcpServer, tools::AppState};
+use docs_mcp::{call_memo::{self, CallMemo}, house_rules::{HouseRules, Mode}, large_output::OutputLimit, mcp_log::McpLogger, server::DocsMcpServer, tool_filter::{self, ToolFilter}, tools::AppState};
 use rmcp::{
     ServiceExt,
     handler::client::ClientHandler,
     model::{
         CallToolRequestParams, CallToolResult, ClientCapabilities, ClientInfo,
-        Implementation, ProtocolVersion,
+        Implementation, LoggingMessageNotificationParam, ProtocolVersion, ReadResourceRequestParams, ReadResourceResult, ResourceContents,
     },
-    service::{serve_client, Peer, RunningService, RoleClient},
+    service::{serve_client, NotificationContext, Peer, RunningService, RoleClient},
 };
 use serde_json::Value;
 
@@ -38,8 +38,15 @@ impl ClientHandler for TestClient {
 }
 
 async fn connect() -> RunningService<RoleClient, TestClient> {
+    connect_to(new_server().await).await
+}
+
+async fn new_server() -> DocsMcpServer {
     let state = AppState::new().await.expect("AppState::new should succeed");
-    let server = DocsMcpServer::new_with_state(Arc::new(state));
+    DocsMcpServer::new_with_state(Arc::new(state))
+}
+
+async fn connect_to(server: DocsMcpServer) -> RunningService<RoleClient, TestClient> {
     let (server_side, client_side) = tokio::io::duplex(65536);
     let (server_r, server_w) = tokio::io::split(server_side);
     let (client_r, client_w) = tokio::io::split(client_side);
@@ -79,18 +86,17 @@ async fn call(peer: &Peer<RoleClient>, tool: &'static str, args: Value) -> Value
 // ─── Registration smoke tests (no network) ────────────────────────────────────
 
 #[tokio::test]
-async fn mcp_server_lists_12_tools() {
-    let client = connect().await;
+async fn mcp_server_lists_every_registered_tool() {
+    let server = new_server().await;
+    let mut registered: Vec<String> = server.tools().into_iter().map(|t| t.name.into_owned()).collect();
+    let client = connect_to(server).await;
     let tools = client.peer().list_all_tools().await.expect("list_tools should succeed");
-    let names: Vec<&str> = tools.iter().map(|t| t.name.as_ref()).collect();
-    assert_eq!(tools.len(), 12, "expected 12 tools, got: {:?}", names);
-    for expected in [
-        "crate_list", "crate_get", "crate_readme_get", "crate_docs_get",
-        "crate_item_list", "crate_item_get", "crate_impls_list",
-        "crate_versions_list", "crate_version_get",
-        "crate_dependencies_list", "crate_dependents_list", "crate_downloads_get",
-    ] {
-        assert!(names.contains(&expected), "missing tool '{}'; got: {:?}", expected, names);
+    let mut names: Vec<String> = tools.iter().map(|t| t.name.to_string()).collect();
+    registered.sort();
+    names.sort();
+    assert_eq!(names, registered);
+    for expected in ["crate_list", "crate_docs_get", "crate_item_get", "docs_mcp_help"] {
+        assert!(names.iter().any(|n| n == expected), "missing tool '{}'; got: {:?}", expected, names);
     }
     client.cancel().await.expect("clean shutdown");
 }
@@ -108,6 +114,264 @@ async fn mcp_server_tools_have_descriptions() {
     client.cancel().await.expect("clean shutdown");
 }
 
+#[tokio::test]
+async fn tool_groups_cover_every_tool_once() {
+    let client = connect().await;
+    let tools = client.peer().list_all_tools().await.expect("list_tools should succeed");
+    for tool in &tools {
+        let group = tool_filter::group_of(tool);
+        assert!(group.is_some_and(|g| tool_filter::GROUPS.contains(&g)), "tool '{}' is in group {group:?}", tool.name);
+    }
+    for group in tool_filter::GROUPS {
+        assert!(tools.iter().any(|t| tool_filter::group_of(t) == Some(group)), "group '{group}' has no tools");
+    }
+    client.cancel().await.expect("clean shutdown");
+}
+
+#[tokio::test]
+async fn tool_filter_trims_the_tool_list_and_guide() {
+    let filter = ToolFilter { enabled: Some(vec!["docs".into(), "docs_mcp_help".into()]), disabled: vec!["crate_ffi_list".into()] };
+    let client = connect_to(new_server().await.with_tool_filter(&filter)).await;
+    let tools = client.peer().list_all_tools().await.expect("list_tools should succeed");
+    let names: Vec<&str> = tools.iter().map(|t| t.name.as_ref()).collect();
+    assert_eq!(names.len(), 18, "got: {names:?}");
+    assert!(names.contains(&"crate_item_get") && !names.contains(&"crate_ffi_list") && !names.contains(&"crate_audit_report"));
+
+    let instructions = client.peer_info().and_then(|i| i.instructions.clone()).expect("server sends instructions");
+    assert!(!instructions.contains("crate_audit_report"), "got: {instructions}");
+    assert!(!instructions.contains("resources/read"), "no output limit, so no resource links; got: {instructions}");
+    let j = call(client.peer(), "docs_mcp_help", serde_json::json!({"topic": "workflows"})).await;
+    let due_diligence = j["workflows"].as_array().unwrap().iter().find(|w| w["name"] == "due_diligence").unwrap();
+    assert_eq!(due_diligence["steps"], serde_json::json!([]));
+
+    let err = client.peer().call_tool(params("crate_audit_report", serde_json::json!({"name": "serde"}))).await;
+    assert!(err.is_err(), "a disabled tool must not be callable");
+    client.cancel().await.expect("clean shutdown");
+}
+
+#[tokio::test]
+async fn house_rules_extend_the_instructions_and_help() {
+    let rules = HouseRules { path: "rules.md".into(), mode: Mode::Append, text: "Never suggest yanked versions.".to_string() };
+    let client = connect_to(new_server().await.with_house_rules(rules)).await;
+    let instructions = client.peer_info().and_then(|i| i.instructions.clone()).expect("server sends instructions");
+    assert!(instructions.contains("WORKFLOW") && instructions.ends_with("Never suggest yanked versions."), "got: {instructions}");
+    let j = call(client.peer(), "docs_mcp_help", serde_json::json!({})).await;
+    assert_eq!(j["house_rules"], "Never suggest yanked versions.");
+    assert_eq!(j["config"]["instructions_file"], serde_json::json!({"mode": "append"}), "the server's path stays private");
+    client.cancel().await.expect("clean shutdown");
+}
+
+#[tokio::test]
+async fn new_sessions_keep_separate_logs() {
+    let state = AppState::with_fixtures(docs_mcp::fixtures::default_fixture_dir()).await.expect("fixture state");
+    let server = DocsMcpServer::new_with_state(Arc::new(state));
+    let (a, b) = (connect_to(server.new_session()).await, connect_to(server.new_session()).await);
+    call(a.peer(), "crate_versions_list", serde_json::json!({"name": "rmcp"})).await;
+
+    let seen_by_a = call(a.peer(), "session_context", serde_json::json!({})).await;
+    assert_eq!(seen_by_a["crates"][0]["name"], "rmcp");
+    let seen_by_b = call(b.peer(), "session_context", serde_json::json!({})).await;
+    assert_eq!(seen_by_b["crate_count"], 0, "b never asked about rmcp; got {seen_by_b}");
+    a.cancel().await.expect("clean shutdown");
+    b.cancel().await.expect("clean shutdown");
+}
+
+#[tokio::test]
+async fn cache_warm_progress_is_never_memoized() {
+    let state = AppState::with_fixtures(docs_mcp::fixtures::default_fixture_dir()).await.expect("fixture state");
+    let server = DocsMcpServer::new_with_state(Arc::new(state)).with_call_memo(CallMemo::new(call_memo::DEFAULT_TTL));
+    let client = connect_to(server).await;
+    let before = call(client.peer(), "cache_warm", serde_json::json!({})).await;
+    assert_eq!(before["state"], "idle");
+    call(client.peer(), "cache_warm", serde_json::json!({"crates": ["rmcp"], "wait": true})).await;
+
+    let after = call(client.peer(), "cache_warm", serde_json::json!({})).await;
+    assert_eq!(after["state"], "finished", "the second poll sees current progress; got {after}");
+    assert_eq!(after.get("deduplicated"), None);
+    client.cancel().await.expect("clean shutdown");
+}
+
+/// Test client that passes on the text of each log notification it receives.
+struct LogClient(tokio::sync::mpsc::UnboundedSender<Value>);
+
+impl ClientHandler for LogClient {
+    fn get_info(&self) -> ClientInfo {
+        TestClient.get_info()
+    }
+
+    async fn on_logging_message(&self, params: LoggingMessageNotificationParam, _context: NotificationContext<RoleClient>) {
+        let _ = self.0.send(params.data);
+    }
+}
+
+#[tokio::test]
+async fn log_notifications_reach_every_session() {
+    use tracing_subscriber::layer::SubscriberExt;
+    let logger = McpLogger::from_env();
+    let _subscriber = tracing::subscriber::set_default(tracing_subscriber::registry().with(logger.layer()));
+    let state = AppState::with_fixtures(docs_mcp::fixtures::default_fixture_dir()).await.expect("fixture state");
+    let server = DocsMcpServer::new_with_state(Arc::new(state)).with_logger(logger);
+
+    let mut sessions = vec![];
+    for _ in 0..2 {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let (server_side, client_side) = tokio::io::duplex(65536);
+        let handler = server.new_session();
+        tokio::spawn(async move {
+            if let Ok(running) = handler.serve(server_side).await {
+                let _ = running.waiting().await;
+            }
+        });
+        let client = serve_client(LogClient(tx), client_side).await.expect("client should connect to server");
+        // A round trip, so the server has seen the initialized notification
+        client.peer().list_all_tools().await.expect("tools/list");
+        sessions.push((client, rx));
+    }
+    tracing::info!(target: "docs_mcp::test", "warming serde");
+    for (client, mut rx) in sessions {
+        let data = tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv()).await
+            .expect("each session gets the notification");
+        assert_eq!(data, Some(serde_json::json!("warming serde")));
+        client.cancel().await.expect("clean shutdown");
+    }
+}
+
+/// Middleware whose requests never finish, so a call stays in flight until cancelled.
+struct Stall;
+
+#[async_trait::async_trait]
+impl reqwest_middleware::Middleware for Stall {
+    async fn handle(
+        &self,
+        _req: reqwest::Request,
+        _extensions: &mut http::Extensions,
+        _next: reqwest_middleware::Next<'_>,
+    ) -> reqwest_middleware::Result<reqwest::Response> {
+        std::future::pending().await
+    }
+}
+
+#[tokio::test]
+async fn cancelled_calls_stop_at_once() {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+    let mut state = AppState::with_fixtures(docs_mcp::fixtures::default_fixture_dir()).await.expect("fixture state");
+    state.client = reqwest_middleware::ClientBuilder::new(reqwest::Client::new()).with(Stall).build();
+    let server = DocsMcpServer::new_with_state(Arc::new(state));
+    let (server_side, client_side) = tokio::io::duplex(65536);
+    tokio::spawn(async move {
+        if let Ok(running) = server.serve(server_side).await {
+            let _ = running.waiting().await;
+        }
+    });
+    let (read, mut write) = tokio::io::split(client_side);
+    let mut lines = tokio::io::BufReader::new(read).lines();
+    let mut send = async |message: Value| write.write_all(format!("{message}\n").as_bytes()).await.unwrap();
+
+    send(serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {
+        "protocolVersion": "2024-11-05", "capabilities": {}, "clientInfo": {"name": "raw", "version": "0"},
+    }})).await;
+    lines.next_line().await.unwrap().expect("initialize response");
+    send(serde_json::json!({"jsonrpc": "2.0", "method": "notifications/initialized"})).await;
+    send(serde_json::json!({"jsonrpc": "2.0", "id": 2, "method": "tools/call",
+        "params": {"name": "crate_get", "arguments": {"name": "serde"}}})).await;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    send(serde_json::json!({"jsonrpc": "2.0", "method": "notifications/cancelled", "params": {"requestId": 2}})).await;
+    send(serde_json::json!({"jsonrpc": "2.0", "id": 3, "method": "tools/call",
+        "params": {"name": "docs_mcp_help", "arguments": {"topic": "gotchas"}}})).await;
+
+    let mut responses = vec![];
+    for _ in 0..2 {
+        let line = tokio::time::timeout(std::time::Duration::from_secs(5), lines.next_line()).await
+            .expect("both calls end").unwrap().expect("a response line");
+        let response: Value = serde_json::from_str(&line).unwrap();
+        responses.push((response["id"].as_u64(), response.get("error").is_some()));
+    }
+    responses.sort();
+    assert_eq!(responses, [(Some(2), true), (Some(3), false)], "the stalled call ends as soon as it is cancelled");
+}
+
+#[tokio::test]
+async fn pinned_old_versions_report_newer_available() {
+    // One crate with two releases; the default fixtures have a single version per crate
+    let dir = std::env::temp_dir().join(format!("docs-mcp-newer-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("index")).unwrap();
+    std::fs::create_dir_all(dir.join("cratesio/crates/demo/1.0.0")).unwrap();
+    std::fs::create_dir_all(dir.join("cratesio/crates/demo/1.1.0")).unwrap();
+    std::fs::write(dir.join("index/demo"), [
+        r#"{"name":"demo","vers":"1.0.0","deps":[],"cksum":"a","features":{},"yanked":false}"#,
+        r#"{"name":"demo","vers":"1.1.0","deps":[],"cksum":"b","features":{},"yanked":false}"#,
+    ].join("\n")).unwrap();
+    for version in ["1.0.0", "1.1.0"] {
+        std::fs::write(dir.join(format!("cratesio/crates/demo/{version}/dependencies.json")), r#"{"dependencies":[]}"#).unwrap();
+    }
+    let state = AppState::with_fixtures(dir.clone()).await.expect("fixture state");
+    let client = connect_to(DocsMcpServer::new_with_state(Arc::new(state))).await;
+
+    let old = call(client.peer(), "crate_dependencies_list", serde_json::json!({"name": "demo", "version": "1.0.0"})).await;
+    assert_eq!(old["newer_available"]["latest_stable"], "1.1.0", "got {old}");
+    assert_eq!(old["newer_available"]["releases_behind"], 1);
+    let current = call(client.peer(), "crate_dependencies_list", serde_json::json!({"name": "demo", "version": "1.1.0"})).await;
+    assert!(current.get("newer_available").is_none(), "got {current}");
+    let unpinned = call(client.peer(), "crate_dependencies_list", serde_json::json!({"name": "demo"})).await;
+    assert!(unpinned.get("newer_available").is_none(), "got {unpinned}");
+    client.cancel().await.expect("clean shutdown");
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn oversized_results_link_to_readable_resources() {
+    let state = AppState::with_fixtures(docs_mcp::fixtures::default_fixture_dir()).await.expect("fixture state");
+    let server = DocsMcpServer::new_with_state(Arc::new(state)).with_output_limit(OutputLimit::new(2_000));
+    let client = connect_to(server).await;
+    let instructions = client.peer_info().and_then(|i| i.instructions.clone()).expect("server sends instructions");
+    assert!(instructions.contains("resources/read"), "got: {instructions}");
+    let result = client.peer().call_tool(params("docs_mcp_help", serde_json::json!({}))).await.expect("help should succeed");
+    let summary: Value = serde_json::from_str(&result.content[0].as_text().expect("outline is text").text).unwrap();
+    assert_eq!(summary["oversized"], true);
+    assert!(result.content[1].as_resource_link().is_some());
+
+    let read = |uri: String| client.peer().read_resource(ReadResourceRequestParams { meta: None, uri });
+    let text = |r: ReadResourceResult| match r.contents.into_iter().next() {
+        Some(ResourceContents::TextResourceContents { text, .. }) => text,
+        other => panic!("expected text, got {other:?}"),
+    };
+    let full = text(read(summary["resource"].as_str().unwrap().to_string()).await.expect("resource should read"));
+    assert_eq!(full.len() as u64, summary["bytes"].as_u64().unwrap());
+    let mut joined = String::new();
+    for part in summary["parts"].as_array().unwrap() {
+        joined += &text(read(part.as_str().unwrap().to_string()).await.expect("part should read"));
+    }
+    assert_eq!(joined, full);
+    let help: Value = serde_json::from_str(&full).expect("full result is JSON");
+    assert!(help.is_object());
+    assert!(read("docs-mcp://outputs/0000".to_string()).await.is_err());
+    client.cancel().await.expect("clean shutdown");
+}
+
+#[tokio::test]
+async fn mcp_server_tools_are_annotated_read_only() {
+    let client = connect().await;
+    let tools = client.peer().list_all_tools().await.expect("list_tools should succeed");
+    for tool in &tools {
+        let annotations = tool.annotations.as_ref()
+            .unwrap_or_else(|| panic!("tool '{}' should declare annotations", tool.name));
+        if tool.name == "cache_warm" {
+            // Starts background downloads, so clients should not approve it as a read
+            assert_eq!(annotations.read_only_hint, None);
+            assert_eq!(annotations.idempotent_hint, None);
+            assert_eq!(annotations.destructive_hint, Some(false));
+            assert_eq!(annotations.open_world_hint, Some(true));
+            continue;
+        }
+        assert_eq!(annotations.read_only_hint, Some(true), "tool '{}'", tool.name);
+        assert_eq!(annotations.idempotent_hint, Some(true), "tool '{}'", tool.name);
+        // Only the tools that report on the server itself stay off the network
+        let local = ["server_status", "server_stats", "session_context", "docs_mcp_help"].contains(&tool.name.as_ref());
+        assert_eq!(annotations.open_world_hint, Some(!local), "tool '{}'", tool.name);
+    }
+    client.cancel().await.expect("clean shutdown");
+}
+
 #[tokio::test]
 async fn mcp_server_tools_have_input_schemas() {
     let client = connect().await;
@@ -120,6 +384,44 @@ async fn mcp_server_tools_have_input_schemas() {
     client.cancel().await.expect("clean shutdown");
 }
 
+#[tokio::test]
+async fn docs_mcp_help_covers_every_registered_tool() {
+    let server = new_server().await;
+    let registered = server.tools().len();
+    let client = connect_to(server).await;
+    let j = call(client.peer(), "docs_mcp_help", serde_json::json!({})).await;
+    let tools = j["tools"].as_array().expect("tools should be array");
+    assert_eq!(tools.len(), registered);
+    for tool in tools {
+        assert!(tool["when_to_use"].is_string(), "tool '{}' is missing from the guide", tool["name"]);
+    }
+    assert!(j["workflows"].is_array() && j["config"]["cache_dir"].is_string());
+
+    let j = call(client.peer(), "docs_mcp_help", serde_json::json!({"topic": "crate_item_get"})).await;
+    let params: Vec<&str> = j["parameters"].as_array().unwrap().iter()
+        .filter_map(|p| p["name"].as_str())
+        .collect();
+    assert!(params.contains(&"item_path"), "got: {params:?}");
+    assert!(j["gotchas"].as_array().unwrap().iter().any(|g| g["tool"] == "crate_item_get"));
+    assert_eq!(j["workflows"], serde_json::json!(["understanding"]));
+    client.cancel().await.expect("clean shutdown");
+}
+
+#[tokio::test]
+async fn server_stats_counts_calls_per_tool() {
+    let client = connect().await;
+    call(client.peer(), "docs_mcp_help", serde_json::json!({"topic": "workflows"})).await;
+    call(client.peer(), "docs_mcp_help", serde_json::json!({"topic": "gotchas"})).await;
+    let j = call(client.peer(), "server_stats", serde_json::json!({})).await;
+    let help = j["tools"].as_array().expect("tools should be array").iter()
+        .find(|t| t["tool"] == "docs_mcp_help")
+        .expect("docs_mcp_help should be recorded");
+    assert_eq!(help["calls"], 2);
+    assert_eq!(help["errors"], 0);
+    assert!(help["p95_ms"].is_u64(), "got: {help}");
+    client.cancel().await.expect("clean shutdown");
+}
+
 // ─── crate_list ───────────────────────────────────────────────────────────────
 
 #[tokio::test]
@@ -235,6 +537,20 @@ async fn crate_get_version_matches_index() {
     client.cancel().await.ok();
 }
 
+#[tokio::test]
+#[ignore = "requires network access"]
+async fn crate_get_categories_include_slug_and_counts() {
+    let client = connect().await;
+    let j = call(client.peer(), "crate_get", serde_json::json!({"name": "reqwest"})).await;
+    let categories = j["categories"].as_array().expect("reqwest should have categories");
+    for c in categories {
+        assert!(c["slug"].is_string(), "category should have a slug: {c}");
+        assert!(c["crates_cnt"].is_u64(), "category should have crates_cnt: {c}");
+        assert!(c["browse_url"].as_str().unwrap_or("").starts_with("https://crates.io/categories/"));
+    }
+    client.cancel().await.ok();
+}
+
 // ─── crate_readme_get ─────────────────────────────────────────────────────────
 
 #[tokio::test]
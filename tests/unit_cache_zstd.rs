@@ -1,4 +1,5 @@
-use docs_mcp::cache::decompress_zstd;
+use docs_mcp::cache::{decompress_zstd, decompress_zstd_capped};
+use docs_mcp::error::DocsError;
 
 fn zstd_compress(data: &[u8]) -> Vec<u8> {
     zstd::encode_all(std::io::Cursor::new(data), 0).unwrap()
@@ -69,6 +70,21 @@ fn decompress_errors_on_non_utf8_output() {
     );
 }
 
+#[test]
+fn capped_decompression_refuses_text_over_the_limit() {
+    let text = "x".repeat(10_000);
+    let too_large = |len: usize| DocsError::Other(format!("too large: {len}"));
+    // Streamed frames carry no content size, so the length is counted past the limit;
+    // single-shot frames record it in the header
+    let streamed = zstd_compress(text.as_bytes());
+    let sized = zstd::bulk::compress(text.as_bytes(), 0).unwrap();
+    for compressed in [streamed, sized] {
+        assert_eq!(decompress_zstd_capped(&compressed, 10_000, too_large).unwrap(), text);
+        let err = decompress_zstd_capped(&compressed, 9_999, too_large).unwrap_err();
+        assert_eq!(err.to_string(), "too large: 10000");
+    }
+}
+
 /// Test with the real downloaded .zst fixture from docs.rs (if present).
 #[test]
 fn decompress_real_docsrs_fixture_if_present() {
@@ -83,3 +99,15 @@ fn decompress_real_docsrs_fixture_if_present() {
     let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
     assert!(parsed["format_version"].as_u64().unwrap_or(0) >= 33);
 }
+
+#[test]
+fn scratch_cache_is_deleted_on_drop() {
+    let cache = docs_mcp::cache::DiskCache::scratch("docs-mcp-test-scratch").unwrap();
+    let dir = cache.dir().to_path_buf();
+    std::fs::write(dir.join("entry.json"), "{}").unwrap();
+    let other = docs_mcp::cache::DiskCache::scratch("docs-mcp-test-scratch").unwrap();
+    assert_ne!(other.dir(), dir, "each scratch cache gets its own directory");
+    drop(cache);
+    assert!(!dir.exists());
+    assert!(other.dir().exists());
+}
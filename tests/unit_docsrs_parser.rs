@@ -30,7 +30,7 @@ fn type_ref_immutable() {
     let ty = serde_json::json!({
         "borrowed_ref": {
             "lifetime": null,
-            "mutable": false,
+            "is_mutable": false,
             "type": {"primitive": "str"}
         }
     });
@@ -42,7 +42,7 @@ fn type_ref_mutable() {
     let ty = serde_json::json!({
         "borrowed_ref": {
             "lifetime": null,
-            "mutable": true,
+            "is_mutable": true,
             "type": {"generic": "T"}
         }
     });
@@ -54,13 +54,74 @@ fn type_ref_with_lifetime() {
     let ty = serde_json::json!({
         "borrowed_ref": {
             "lifetime": "a",
-            "mutable": true,
+            "is_mutable": true,
             "type": {"generic": "T"}
         }
     });
     assert_eq!(type_to_string(&ty), "&'a mut T");
 }
 
+#[test]
+fn type_ref_mutable_pre_v57_field() {
+    // Before v57 the flag is `mutable`; the format adapter renames it
+    let mut doc: RustdocJson = serde_json::from_value(serde_json::json!({
+        "format_version": 56,
+        "root": "0:0",
+        "crate_version": "0.1.0",
+        "index": {
+            "0:1": {
+                "id": "0:1", "name": "reset", "docs": null, "attrs": [], "deprecation": null,
+                "visibility": "public", "span": null, "links": {},
+                "inner": {"function": {
+                    "sig": {"inputs": [["self", {"borrowed_ref": {"lifetime": null, "mutable": true, "type": {"generic": "Self"}}}]],
+                            "output": null},
+                    "generics": {"params": [], "where_predicates": []},
+                }},
+            },
+        },
+        "paths": {},
+        "external_crates": {},
+    })).unwrap();
+    docs_mcp::docsrs::format::normalize(&mut doc);
+    let self_ty = &doc.index["0:1"].inner["function"]["sig"]["inputs"][0][1];
+    assert_eq!(type_to_string(self_ty), "&mut Self");
+}
+
+#[test]
+fn type_impl_trait_keeps_assoc_bindings_and_lifetimes() {
+    // impl Future<Output = Result<(), Self::Error>> + Send + 'static
+    let ty = serde_json::json!({
+        "impl_trait": [
+            {"trait_bound": {"trait": {"path": "Future", "id": 1, "args": {"angle_bracketed": {
+                "args": [],
+                "constraints": [{"name": "Output", "args": null, "binding": {"equality": {"type": {
+                    "resolved_path": {"path": "Result", "id": 2, "args": {"angle_bracketed": {"args": [
+                        {"type": {"tuple": []}},
+                        {"type": {"qualified_path": {"name": "Error", "args": null,
+                            "self_type": {"generic": "Self"}, "trait": {"path": "", "id": 3, "args": null}}}}
+                    ], "constraints": []}}}
+                }}}}]
+            }}}, "generic_params": [], "modifier": "none"}},
+            {"trait_bound": {"trait": {"path": "Send", "id": 4, "args": null}, "generic_params": [], "modifier": "none"}},
+            {"outlives": "'static"}
+        ]
+    });
+    assert_eq!(type_to_string(&ty), "impl Future<Output = Result<(), Self::Error>> + Send + 'static");
+}
+
+#[test]
+fn type_path_with_lifetime_and_fn_args() {
+    let cow = serde_json::json!({"resolved_path": {"path": "Cow", "id": 1, "args": {"angle_bracketed": {
+        "args": [{"lifetime": "'static"}, {"type": {"primitive": "str"}}], "constraints": []
+    }}}});
+    assert_eq!(type_to_string(&cow), "Cow<'static, str>");
+
+    let fn_bound = serde_json::json!({"path": "Fn", "id": 2, "args": {"parenthesized": {
+        "inputs": [{"primitive": "u8"}], "output": {"primitive": "bool"}
+    }}});
+    assert_eq!(type_to_string(&fn_bound), "Fn(u8) -> bool");
+}
+
 #[test]
 fn type_tuple_empty() {
     let ty = serde_json::json!({"tuple": []});
@@ -99,7 +160,7 @@ fn type_array() {
 fn type_raw_pointer_const() {
     let ty = serde_json::json!({
         "raw_pointer": {
-            "mutable": false,
+            "is_mutable": false,
             "type": {"primitive": "u8"}
         }
     });
@@ -110,7 +171,7 @@ fn type_raw_pointer_const() {
 fn type_raw_pointer_mut() {
     let ty = serde_json::json!({
         "raw_pointer": {
-            "mutable": true,
+            "is_mutable": true,
             "type": {"primitive": "u8"}
         }
     });
@@ -181,6 +242,18 @@ fn feature_old_cfg_pattern_does_not_match_v57() {
     assert!(matches.is_empty(), "Old pattern should NOT match v57 attr format");
 }
 
+#[test]
+fn cfg_syntax_round_trips_through_cfg_trace() {
+    use docs_mcp::docsrs::{parse_cfg_attr, parse_cfg_syntax};
+    for predicate in [r#"feature = "rt""#, r#"all(unix, any(feature = "a", feature = "b",), not(test))"#, "true"] {
+        let cfg = parse_cfg_syntax(predicate).expect("valid cfg syntax");
+        let attr = format!("#[attr = CfgTrace([{}])]", cfg.to_trace());
+        assert_eq!(parse_cfg_attr(&attr), Some(cfg));
+    }
+    assert_eq!(parse_cfg_syntax("all(unix"), None);
+    assert_eq!(parse_cfg_syntax("not(unix, windows)"), None);
+}
+
 #[test]
 fn feature_cross_reference_filters_undeclared() {
     let attr = r#"#[attr = CfgTrace([NameValue { name: "feature", value: Some("undeclared-feat"), span: None }])]"#;
@@ -212,6 +285,48 @@ fn feature_empty_declared_set_allows_all() {
     assert_eq!(features, vec!["anything"]);
 }
 
+// ─── Structured cfg parsing ───────────────────────────────────────────────────
+
+#[test]
+fn cfg_parses_any_all_not_combinators() {
+    use docs_mcp::docsrs::{parse_cfg_attr, Cfg};
+    let attr = r#"#[attr = CfgTrace([Any([NameValue { name: "feature", value: Some("rt"), span: src/lib.rs:3:7: 3:20 (#0) }, All([NameValue { name: "feature", value: Some("net"), span: None }, Not(NameValue { name: "windows", value: None, span: None }, None)], None)], src/lib.rs:3:1: 3:40 (#0))])]"#;
+    let cfg = parse_cfg_attr(attr).unwrap();
+    assert_eq!(cfg.to_string(), r#"any(feature = "rt", all(feature = "net", not(windows)))"#);
+    assert_eq!(cfg.features(), vec!["rt", "net"]);
+    assert!(matches!(cfg, Cfg::Any(_)));
+}
+
+#[test]
+fn cfg_requirements_reports_features_and_targets() {
+    let attrs = vec![
+        r#"#[attr = CfgTrace([NameValue { name: "feature", value: Some("process"), span: None }, NameValue { name: "unix", value: None, span: None }])]"#.to_string(),
+        r#"#[attr = CfgTrace([Not(NameValue { name: "target_arch", value: Some("wasm32"), span: None }, None)])]"#.to_string(),
+    ];
+    let declared = HashSet::from(["process".to_string()]);
+    let reqs = docs_mcp::docsrs::cfg_requirements(&attrs, &declared);
+    assert_eq!(reqs.features, vec!["process"]);
+    assert_eq!(reqs.targets, vec!["unix", r#"not(target_arch = "wasm32")"#]);
+    assert_eq!(
+        reqs.cfg.as_deref(),
+        Some(r#"all(all(feature = "process", unix), not(target_arch = "wasm32"))"#),
+    );
+}
+
+#[test]
+fn cfg_features_under_not_are_not_requirements() {
+    let attr = r#"#[attr = CfgTrace([Not(NameValue { name: "feature", value: Some("std"), span: None }, None)])]"#;
+    let features = extract_feature_requirements(&[attr.to_string()], &HashSet::new());
+    assert!(features.is_empty());
+}
+
+#[test]
+fn cfg_non_cfg_attrs_are_ignored() {
+    let attrs = vec!["#[must_use]".to_string(), "#[attr = Repr([ReprC])]".to_string()];
+    let reqs = docs_mcp::docsrs::cfg_requirements(&attrs, &HashSet::new());
+    assert_eq!(reqs, docs_mcp::docsrs::CfgRequirements::default());
+}
+
 // ─── Fixture-based parser tests ───────────────────────────────────────────────
 
 /// Load the clap fixture and verify basic structure parses correctly.
@@ -300,14 +415,14 @@ fn load_clap() -> RustdocJson {
 #[test]
 fn fixture_rmcp_module_tree_is_nonempty() {
     let doc = load_rmcp();
-    let tree = build_module_tree(&doc);
+    let tree = build_module_tree(&doc, false);
     assert!(!tree.is_empty(), "rmcp module tree should not be empty");
 }
 
 #[test]
 fn fixture_rmcp_module_tree_nodes_have_paths() {
     let doc = load_rmcp();
-    let tree = build_module_tree(&doc);
+    let tree = build_module_tree(&doc, false);
     for node in &tree {
         assert!(!node.path.is_empty(), "module tree node should have a path");
         assert!(node.path.starts_with("rmcp"), "module path should start with crate name, got: {}", node.path);
@@ -317,18 +432,85 @@ fn fixture_rmcp_module_tree_nodes_have_paths() {
 #[test]
 fn fixture_rmcp_module_tree_has_item_counts() {
     let doc = load_rmcp();
-    let tree = build_module_tree(&doc);
+    let tree = build_module_tree(&doc, false);
     // At least one node should have non-empty item counts (has structs, fns, etc.)
     let any_with_counts = tree.iter().any(|n| !n.item_counts.is_empty());
     assert!(any_with_counts, "at least one module node should have item counts");
 }
 
+#[test]
+fn fixture_rmcp_module_subtree_and_depth() {
+    use docs_mcp::docsrs::build_module_subtree;
+    let doc = load_rmcp();
+
+    let subtree = build_module_subtree(&doc, Some("rmcp::transport"), 1, false).expect("transport is a module");
+    assert_eq!(subtree.len(), 1);
+    assert_eq!(subtree[0].path, "rmcp::transport");
+    assert!(!subtree[0].children.is_empty(), "transport should list its submodules");
+    assert!(subtree[0].children.iter().all(|c| c.children.is_empty()), "max_depth 1 stops below direct children");
+
+    let top_only = build_module_subtree(&doc, None, 1, false).unwrap();
+    assert!(top_only.iter().all(|n| n.children.is_empty()));
+
+    assert!(build_module_subtree(&doc, Some("rmcp::no_such_module"), 3, false).is_none());
+    assert!(build_module_subtree(&doc, Some("rmcp::transport::child_process::TokioChildProcess"), 3, false).is_none());
+}
+
+#[test]
+fn fixture_rmcp_module_nodes_carry_feature_requirements() {
+    use docs_mcp::docsrs::build_module_subtree;
+    let doc = load_rmcp();
+
+    let transport = build_module_subtree(&doc, Some("rmcp::transport"), 1, false).expect("transport is a module");
+    let child_process = transport[0].children.iter()
+        .find(|c| c.path == "rmcp::transport::child_process")
+        .expect("child_process submodule");
+    assert_eq!(child_process.feature_requirements, vec!["transport-child-process"]);
+    assert_eq!(child_process.cfg.as_deref(), Some("feature = \"transport-child-process\""));
+    assert!(transport[0].feature_requirements.is_empty(), "transport itself is ungated");
+
+    let service = build_module_subtree(&doc, Some("rmcp::service"), 0, false).expect("service is a module");
+    assert_eq!(service[0].feature_requirements, vec!["client", "server"]);
+    assert!(service[0].cfg.as_deref().is_some_and(|c| c.starts_with("any(")), "got: {:?}", service[0].cfg);
+}
+
+#[test]
+fn fixture_rmcp_outline_parse_keeps_the_module_tree() {
+    use docs_mcp::docsrs::{DocDetail, ModuleNode, OutlineJson};
+    fn flatten(nodes: &[ModuleNode], out: &mut Vec<String>) {
+        for n in nodes {
+            let mut counts: Vec<_> = n.item_counts.iter().collect();
+            counts.sort();
+            out.push(format!("{} {:?} {:?} {:?} {}", n.path, counts, n.feature_requirements, n.cfg, n.doc_summary));
+            out.extend(n.items.iter().map(|i| format!("  {} {} {}", i.kind, i.name, i.doc_summary)));
+            flatten(&n.children, out);
+        }
+    }
+
+    let full = load_rmcp();
+    let json_str = std::fs::read_to_string("tests/fixtures/rmcp_0.16.0.json").unwrap();
+    let outline: RustdocJson = serde_json::from_str::<OutlineJson>(&json_str).expect("outline must parse").into();
+    assert_eq!(outline.detail, DocDetail::Outline);
+    assert_eq!(outline.index.len(), full.index.len());
+
+    let (mut expected, mut actual) = (vec![], vec![]);
+    flatten(&build_module_tree(&full, true), &mut expected);
+    flatten(&build_module_tree(&outline, true), &mut actual);
+    assert_eq!(actual, expected);
+
+    let root = &outline.index[&outline.root_id()];
+    assert_eq!(root.links, full.index[&full.root_id()].links, "module links are kept for doc rewriting");
+    assert!(outline.index.values().all(|i| i.span.is_none()));
+    let function = outline.index.values().find(|i| i.kind() == Some("function")).expect("rmcp has functions");
+    assert!(function.inner["function"].is_null() && function.links.is_none());
+}
+
 #[test]
 fn fixture_clap_module_tree_reflects_format_version() {
     // clap fixture is stripped (only module/use items), so tree may be minimal
     // but must not panic and must return a valid result
     let doc = load_clap();
-    let tree = build_module_tree(&doc);
+    let tree = build_module_tree(&doc, false);
     // Result can be empty for stripped fixtures — just ensure it doesn't panic
     let _ = tree;
 }
@@ -339,7 +521,7 @@ fn fixture_clap_module_tree_reflects_format_version() {
 fn fixture_rmcp_search_finds_tokiochildprocess() {
     let doc = load_rmcp();
     let features = HashSet::new();
-    let results = search_items(&doc, "TokioChildProcess", None, None, 10, &features);
+    let results = search_items(&doc, "TokioChildProcess", None, None, 10, &features, false);
     assert!(!results.is_empty(), "search for 'TokioChildProcess' should return results");
     let found = results.iter().any(|r| r.path.contains("TokioChildProcess"));
     assert!(found, "TokioChildProcess should appear in results");
@@ -349,7 +531,7 @@ fn fixture_rmcp_search_finds_tokiochildprocess() {
 fn fixture_rmcp_search_kind_fn_returns_only_functions() {
     let doc = load_rmcp();
     let features = HashSet::new();
-    let results = search_items(&doc, "", Some("fn"), None, 50, &features);
+    let results = search_items(&doc, "", Some("fn"), None, 50, &features, false);
     assert!(!results.is_empty(), "kind=fn should return results");
     for r in &results {
         assert_eq!(r.kind, "function", "kind=fn filter must only return functions, got: {}", r.kind);
@@ -361,8 +543,8 @@ fn fixture_rmcp_search_kind_function_alias_same_as_fn() {
     // "function" and "fn" should be equivalent
     let doc = load_rmcp();
     let features = HashSet::new();
-    let by_fn = search_items(&doc, "", Some("fn"), None, 200, &features);
-    let by_function = search_items(&doc, "", Some("function"), None, 200, &features);
+    let by_fn = search_items(&doc, "", Some("fn"), None, 200, &features, false);
+    let by_function = search_items(&doc, "", Some("function"), None, 200, &features, false);
     assert_eq!(
         by_fn.len(), by_function.len(),
         "kind='fn' and kind='function' should return same count"
@@ -373,7 +555,7 @@ fn fixture_rmcp_search_kind_function_alias_same_as_fn() {
 fn fixture_rmcp_search_kind_struct_returns_only_structs() {
     let doc = load_rmcp();
     let features = HashSet::new();
-    let results = search_items(&doc, "", Some("struct"), None, 50, &features);
+    let results = search_items(&doc, "", Some("struct"), None, 50, &features, false);
     assert!(!results.is_empty(), "kind=struct should return results");
     for r in &results {
         assert_eq!(r.kind, "struct", "kind=struct filter must only return structs, got: {}", r.kind);
@@ -384,7 +566,7 @@ fn fixture_rmcp_search_kind_struct_returns_only_structs() {
 fn fixture_rmcp_search_kind_trait_returns_only_traits() {
     let doc = load_rmcp();
     let features = HashSet::new();
-    let results = search_items(&doc, "", Some("trait"), None, 50, &features);
+    let results = search_items(&doc, "", Some("trait"), None, 50, &features, false);
     assert!(!results.is_empty(), "kind=trait should return results");
     for r in &results {
         assert_eq!(r.kind, "trait", "kind=trait filter must only return traits, got: {}", r.kind);
@@ -395,7 +577,7 @@ fn fixture_rmcp_search_kind_trait_returns_only_traits() {
 fn fixture_rmcp_search_limit_respected() {
     let doc = load_rmcp();
     let features = HashSet::new();
-    let results = search_items(&doc, "", None, None, 5, &features);
+    let results = search_items(&doc, "", None, None, 5, &features, false);
     assert!(results.len() <= 5, "limit=5 should return at most 5 results, got {}", results.len());
 }
 
@@ -403,7 +585,7 @@ fn fixture_rmcp_search_limit_respected() {
 fn fixture_rmcp_search_results_have_nonempty_paths() {
     let doc = load_rmcp();
     let features = HashSet::new();
-    let results = search_items(&doc, "new", None, None, 20, &features);
+    let results = search_items(&doc, "new", None, None, 20, &features, false);
     for r in &results {
         assert!(!r.path.is_empty(), "search result path must not be empty");
         assert!(!r.kind.is_empty(), "search result kind must not be empty");
@@ -414,7 +596,7 @@ fn fixture_rmcp_search_results_have_nonempty_paths() {
 fn fixture_rmcp_search_module_prefix_filter() {
     let doc = load_rmcp();
     let features = HashSet::new();
-    let results = search_items(&doc, "", None, Some("rmcp::transport"), 50, &features);
+    let results = search_items(&doc, "", None, Some("rmcp::transport"), 50, &features, false);
     for r in &results {
         assert!(
             r.path.starts_with("rmcp::transport"),
@@ -557,7 +739,7 @@ fn type_ref_lifetime_bare_name() {
     let ty = serde_json::json!({
         "borrowed_ref": {
             "lifetime": "a",
-            "mutable": false,
+            "is_mutable": false,
             "type": {"generic": "T"}
         }
     });
@@ -572,7 +754,7 @@ fn type_ref_lifetime_with_apostrophe() {
     let ty = serde_json::json!({
         "borrowed_ref": {
             "lifetime": "'a",
-            "mutable": false,
+            "is_mutable": false,
             "type": {"generic": "T"}
         }
     });
@@ -587,7 +769,7 @@ fn type_ref_static_lifetime_with_apostrophe() {
     let ty = serde_json::json!({
         "borrowed_ref": {
             "lifetime": "'static",
-            "mutable": false,
+            "is_mutable": false,
             "type": {"primitive": "str"}
         }
     });
@@ -630,7 +812,7 @@ fn make_fn_item(name: &str, inputs: serde_json::Value, output: Option<serde_json
 fn function_signature_self_ref_normalized() {
     // &self should NOT be rendered as "self: &Self"
     let item = make_fn_item("foo", serde_json::json!([
-        ["self", {"borrowed_ref": {"lifetime": null, "mutable": false, "type": {"generic": "Self"}}}]
+        ["self", {"borrowed_ref": {"lifetime": null, "is_mutable": false, "type": {"generic": "Self"}}}]
     ]), None, None);
     let sig = function_signature(&item);
     assert!(sig.contains("(&self)"), "expected &self in sig, got: {sig}");
@@ -641,7 +823,7 @@ fn function_signature_self_ref_normalized() {
 fn function_signature_self_mut_ref_normalized() {
     // &mut self should NOT be rendered as "self: &mut Self"
     let item = make_fn_item("bar", serde_json::json!([
-        ["self", {"borrowed_ref": {"lifetime": null, "mutable": true, "type": {"generic": "Self"}}}]
+        ["self", {"borrowed_ref": {"lifetime": null, "is_mutable": true, "type": {"generic": "Self"}}}]
     ]), None, None);
     let sig = function_signature(&item);
     assert!(sig.contains("(&mut self)"), "expected &mut self in sig, got: {sig}");
@@ -664,7 +846,7 @@ fn function_signature_consuming_self_normalized() {
 fn function_signature_self_and_other_params() {
     // Ensure normalization works alongside regular params
     let item = make_fn_item("get", serde_json::json!([
-        ["self", {"borrowed_ref": {"lifetime": null, "mutable": false, "type": {"generic": "Self"}}}],
+        ["self", {"borrowed_ref": {"lifetime": null, "is_mutable": false, "type": {"generic": "Self"}}}],
         ["index", {"primitive": "usize"}]
     ]), Some(serde_json::json!({"primitive": "bool"})), None);
     let sig = function_signature(&item);
@@ -832,6 +1014,106 @@ fn type_const_generic_in_format_generics() {
     assert_eq!(generics, "<const N: usize>", "got: {generics}");
 }
 
+#[test]
+fn format_generics_renders_defaults_and_lifetime_bounds() {
+    use docs_mcp::docsrs::Item;
+    let item: Item = serde_json::from_value(serde_json::json!({
+        "id": 998,
+        "name": "HashMap",
+        "inner": {
+            "struct": {
+                "generics": {
+                    "params": [
+                        {"name": "'a", "kind": {"lifetime": {"outlives": ["'b"]}}},
+                        {"name": "K", "kind": {"type": {
+                            "bounds": [{"trait_bound": {
+                                "trait": {"path": "Sized", "id": 1, "args": null},
+                                "generic_params": [],
+                                "modifier": "maybe"
+                            }}],
+                            "default": null,
+                            "is_synthetic": false
+                        }}},
+                        {"name": "S", "kind": {"type": {
+                            "bounds": [],
+                            "default": {"resolved_path": {"path": "RandomState", "id": 2, "args": null}},
+                            "is_synthetic": false
+                        }}},
+                        {"name": "N", "kind": {"const": {"type": {"primitive": "usize"}, "default": "64"}}}
+                    ],
+                    "where_predicates": []
+                },
+                "kind": {"unit": null},
+                "impls": []
+            }
+        }
+    })).unwrap();
+    let generics = format_generics_for_item(&item, "struct");
+    assert_eq!(generics, "<'a: 'b, K: ?Sized, S = RandomState, const N: usize = 64>");
+}
+
+#[test]
+fn impl_header_renders_hrtb_and_lifetime_predicates() {
+    use docs_mcp::docsrs::impl_header;
+    let impl_inner = serde_json::json!({
+        "is_unsafe": false,
+        "generics": {
+            "params": [
+                {"name": "'a", "kind": {"lifetime": {"outlives": []}}},
+                {"name": "T", "kind": {"type": {"bounds": [], "default": null, "is_synthetic": false}}}
+            ],
+            "where_predicates": [
+                {"bound_predicate": {
+                    "type": {"generic": "T"},
+                    "bounds": [{"trait_bound": {
+                        "trait": {"path": "Deserialize", "id": 3, "args": {"angle_bracketed": {"args": [], "constraints": []}}},
+                        "generic_params": [{"name": "'de", "kind": {"lifetime": {"outlives": []}}}],
+                        "modifier": "none"
+                    }}],
+                    "generic_params": []
+                }},
+                {"lifetime_predicate": {"lifetime": "'a", "outlives": ["'static"]}}
+            ]
+        },
+        "trait": null,
+        "for": {"resolved_path": {"path": "Wrapper", "id": 4, "args": null}},
+        "is_negative": false
+    });
+    let header = impl_header(&impl_inner);
+    assert_eq!(header, "impl<'a, T> Wrapper\nwhere\n    T: for<'de> Deserialize,\n    'a: 'static");
+}
+
+// ─── constants and statics ────────────────────────────────────────────────────
+
+#[test]
+fn const_info_renders_type_and_value() {
+    use docs_mcp::docsrs::{const_info, Item};
+    let item = |inner: serde_json::Value| -> Item {
+        serde_json::from_value(serde_json::json!({"id": 1, "name": "MAX", "inner": inner})).unwrap()
+    };
+
+    let literal = item(serde_json::json!({"constant": {
+        "type": {"primitive": "usize"},
+        "const": {"expr": "64", "value": "64usize", "is_literal": true}
+    }}));
+    assert_eq!(const_info(&literal).unwrap().declaration("MAX"), "const MAX: usize = 64;");
+
+    // Non-literal initializers are rendered as `_`; fall back to the evaluated value
+    let computed = item(serde_json::json!({"constant": {
+        "type": {"primitive": "u32"},
+        "const": {"expr": "_", "value": "4_096u32", "is_literal": false}
+    }}));
+    assert_eq!(const_info(&computed).unwrap().value.as_deref(), Some("4_096u32"));
+
+    let trait_const = item(serde_json::json!({"assoc_const": {"type": {"primitive": "bool"}, "value": null}}));
+    assert_eq!(const_info(&trait_const).unwrap().declaration("MAX"), "const MAX: bool;");
+
+    let static_mut = item(serde_json::json!({"static": {
+        "type": {"primitive": "i32"}, "is_mutable": true, "is_unsafe": false, "expr": "0"
+    }}));
+    assert_eq!(const_info(&static_mut).unwrap().declaration("MAX"), "static mut MAX: i32 = 0;");
+}
+
 // ─── struct/enum signature includes generics ──────────────────────────────────
 
 #[test]
@@ -855,7 +1137,7 @@ fn search_methods_finds_inherent_methods_without_kind_filter() {
     let doc = load_rmcp();
     let features = HashSet::new();
     // Search by type name — the method pass should match methods whose parent path contains the query.
-    let results = search_items(&doc, "TokioChildProcess", None, None, 50, &features);
+    let results = search_items(&doc, "TokioChildProcess", None, None, 50, &features, false);
     let method_results: Vec<_> = results.iter().filter(|r| r.kind == "method").collect();
     assert!(!method_results.is_empty(), "search for 'TokioChildProcess' with no kind filter should find methods");
     let paths: Vec<&str> = method_results.iter().map(|r| r.path.as_str()).collect();
@@ -875,7 +1157,7 @@ fn search_methods_finds_inherent_methods_without_kind_filter() {
 fn search_methods_kind_method_filter_returns_only_methods() {
     let doc = load_rmcp();
     let features = HashSet::new();
-    let results = search_items(&doc, "", Some("method"), None, 50, &features);
+    let results = search_items(&doc, "", Some("method"), None, 50, &features, false);
     assert!(!results.is_empty(), "kind=method should return results");
     for r in &results {
         assert_eq!(r.kind, "method", "kind=method must only return methods, got: {}", r.kind);
@@ -887,7 +1169,7 @@ fn search_methods_kind_fn_excludes_methods() {
     // kind="fn" should only return free functions, NOT inherent methods
     let doc = load_rmcp();
     let features = HashSet::new();
-    let results = search_items(&doc, "", Some("fn"), None, 200, &features);
+    let results = search_items(&doc, "", Some("fn"), None, 200, &features, false);
     for r in &results {
         assert_ne!(r.kind, "method", "kind=fn must not return methods, got method: {}", r.path);
     }
@@ -898,7 +1180,7 @@ fn search_methods_path_includes_parent_type() {
     // Method paths should be "ParentType::method_name"
     let doc = load_rmcp();
     let features = HashSet::new();
-    let results = search_items(&doc, "", Some("method"), None, 50, &features);
+    let results = search_items(&doc, "", Some("method"), None, 50, &features, false);
     for r in &results {
         assert!(
             r.path.contains("::"),
@@ -912,7 +1194,7 @@ fn search_methods_path_includes_parent_type() {
 fn search_methods_signature_contains_fn_keyword() {
     let doc = load_rmcp();
     let features = HashSet::new();
-    let results = search_items(&doc, "new", Some("method"), None, 20, &features);
+    let results = search_items(&doc, "new", Some("method"), None, 20, &features, false);
     for r in &results {
         assert!(
             r.signature.contains("fn "),
@@ -923,3 +1205,471 @@ fn search_methods_signature_contains_fn_keyword() {
 }
 
 // ─── html_to_text entity decoding ────────────────────────────────────────────
+
+// ─── struct_fields ────────────────────────────────────────────────────────────
+
+/// Build a minimal doc containing a plain struct `Config` (id=1) with two fields.
+fn make_struct_doc(has_stripped_fields: bool) -> RustdocJson {
+    serde_json::from_value(serde_json::json!({
+        "format_version": 57,
+        "root": 0,
+        "index": {
+            "1": {
+                "id": 1,
+                "name": "Config",
+                "inner": {
+                    "struct": {
+                        "generics": {"params": [], "where_predicates": []},
+                        "kind": {"plain": {"fields": [2, 3], "has_stripped_fields": has_stripped_fields}},
+                        "impls": []
+                    }
+                }
+            },
+            "2": {
+                "id": 2,
+                "name": "name",
+                "docs": "Display name.",
+                "inner": {"struct_field": {"resolved_path": {"path": "String", "args": null}}}
+            },
+            "3": {
+                "id": 3,
+                "name": "retries",
+                "inner": {"struct_field": {"resolved_path": {"path": "Option", "args": {
+                    "angle_bracketed": {"args": [{"type": {"primitive": "u32"}}]}
+                }}}}
+            }
+        },
+        "paths": {}
+    })).unwrap()
+}
+
+#[test]
+fn struct_fields_resolves_names_and_types() {
+    let doc = make_struct_doc(false);
+    let item = doc.index.get("1").unwrap();
+    let (fields, stripped) = docs_mcp::docsrs::struct_fields(&doc, item);
+    let decls: Vec<String> = fields.iter().map(|f| f.declaration()).collect();
+    assert_eq!(decls, vec!["name: String", "retries: Option<u32>"]);
+    assert_eq!(fields[0].docs.as_deref(), Some("Display name."));
+    assert!(!stripped);
+}
+
+#[test]
+fn struct_fields_reports_stripped_fields() {
+    let doc = make_struct_doc(true);
+    let item = doc.index.get("1").unwrap();
+    let (_, stripped) = docs_mcp::docsrs::struct_fields(&doc, item);
+    assert!(stripped, "has_stripped_fields=true should be reported");
+}
+
+#[test]
+fn struct_fields_empty_for_non_struct() {
+    let doc = make_struct_doc(false);
+    let field_item = doc.index.get("2").unwrap();
+    let (fields, stripped) = docs_mcp::docsrs::struct_fields(&doc, field_item);
+    assert!(fields.is_empty());
+    assert!(!stripped);
+}
+
+// ─── API diff ─────────────────────────────────────────────────────────────────
+
+fn make_api_doc(fn_output: &str, extra_struct: bool) -> RustdocJson {
+    let mut index = serde_json::json!({
+        "1": {"id": 1, "name": "run", "inner": {"function": {
+            "sig": {"inputs": [], "output": {"primitive": fn_output}},
+            "generics": {"params": [], "where_predicates": []},
+            "header": {"is_async": false, "is_const": false, "is_unsafe": false}
+        }}}
+    });
+    let mut paths = serde_json::json!({
+        "1": {"kind": "function", "path": ["demo", "run"], "crate_id": 0},
+        "50": {"kind": "struct", "path": ["std", "string", "String"], "crate_id": 1}
+    });
+    if extra_struct {
+        index["2"] = serde_json::json!({"id": 2, "name": "Widget", "inner": {"struct": {
+            "generics": {"params": [], "where_predicates": []}, "kind": {"unit": null}, "impls": []
+        }}});
+        paths["2"] = serde_json::json!({"kind": "struct", "path": ["demo", "Widget"], "crate_id": 0});
+    }
+    serde_json::from_value(serde_json::json!({
+        "format_version": 57, "root": 0, "index": index, "paths": paths
+    })).unwrap()
+}
+
+#[test]
+fn public_api_excludes_external_crate_paths() {
+    use docs_mcp::docsrs::api_diff::public_api;
+    let api = public_api(&make_api_doc("u8", false));
+    assert_eq!(api.keys().collect::<Vec<_>>(), vec!["demo::run"]);
+}
+
+#[test]
+fn diff_api_reports_added_removed_and_changed() {
+    use docs_mcp::docsrs::api_diff::{diff_api, public_api};
+    let old = public_api(&make_api_doc("u8", true));
+    let new = public_api(&make_api_doc("u16", false));
+    let diff = diff_api(&old, &new);
+    assert_eq!(diff.removed, vec!["demo::Widget"]);
+    assert_eq!(diff.changed, vec!["demo::run"]);
+    assert!(diff.added.is_empty());
+    assert!((diff.churn(old.len()) - 1.0).abs() < f64::EPSILON);
+}
+
+// ─── impl_header ──────────────────────────────────────────────────────────────
+
+#[test]
+fn impl_header_renders_blanket_impl_with_where_clause() {
+    let impl_inner = serde_json::json!({
+        "generics": {
+            "params": [{"name": "T", "kind": {"type": {"bounds": []}}}],
+            "where_predicates": [{"bound_predicate": {
+                "type": {"generic": "T"},
+                "bounds": [{"trait_bound": {"trait": {"id": 1, "path": "Display", "args": null}}}]
+            }}]
+        },
+        "trait": {"id": 2, "path": "ToString", "args": null},
+        "for": {"generic": "T"},
+        "is_unsafe": false,
+        "is_negative": false
+    });
+    let header = docs_mcp::docsrs::impl_header(&impl_inner);
+    assert_eq!(header, "impl<T> ToString for T\nwhere\n    T: Display");
+}
+
+#[test]
+fn impl_header_inherent_impl_has_no_trait() {
+    let impl_inner = serde_json::json!({
+        "generics": {"params": [], "where_predicates": []},
+        "trait": null,
+        "for": {"resolved_path": {"path": "Widget", "args": null}}
+    });
+    assert_eq!(docs_mcp::docsrs::impl_header(&impl_inner), "impl Widget");
+}
+
+// ─── Intra-doc links ──────────────────────────────────────────────────────────
+
+fn make_links_doc() -> RustdocJson {
+    serde_json::from_value(serde_json::json!({
+        "format_version": 57,
+        "root": 0,
+        "crate_version": "1.2.0",
+        "index": {
+            "1": {
+                "id": 1,
+                "name": "Client",
+                "docs": "Build one with [`Builder`]. See [the module][`crate::net`] and [`Duration`].",
+                "links": {"`Builder`": 2, "`crate::net`": 3, "`Duration`": 4},
+                "inner": {"struct": {"kind": {"unit": null}, "impls": []}}
+            }
+        },
+        "paths": {
+            "2": {"kind": "struct", "path": ["demo", "net", "Builder"], "crate_id": 0},
+            "3": {"kind": "module", "path": ["demo", "net"], "crate_id": 0},
+            "4": {"kind": "struct", "path": ["core", "time", "Duration"], "crate_id": 2}
+        }
+    })).unwrap()
+}
+
+#[test]
+fn resolve_links_maps_text_to_paths_and_urls() {
+    let doc = make_links_doc();
+    let links = docs_mcp::docsrs::resolve_links(&doc, doc.index.get("1").unwrap());
+    assert_eq!(links.len(), 3);
+    let builder = links.iter().find(|l| l.text == "`Builder`").unwrap();
+    assert_eq!(builder.path, "demo::net::Builder");
+    assert_eq!(builder.url.as_deref(), Some("https://docs.rs/demo/1.2.0/demo/net/struct.Builder.html"));
+    let duration = links.iter().find(|l| l.text == "`Duration`").unwrap();
+    assert_eq!(duration.url.as_deref(), Some("https://docs.rs/core/latest/core/time/struct.Duration.html"));
+}
+
+#[test]
+fn rewrite_doc_links_produces_markdown_links() {
+    let doc = make_links_doc();
+    let item = doc.index.get("1").unwrap();
+    let links = docs_mcp::docsrs::resolve_links(&doc, item);
+    let rewritten = docs_mcp::docsrs::rewrite_doc_links(item.docs.as_deref().unwrap(), &links);
+    assert!(rewritten.contains("[`Builder`](https://docs.rs/demo/1.2.0/demo/net/struct.Builder.html)"), "got: {rewritten}");
+    assert!(rewritten.contains("[the module](https://docs.rs/demo/1.2.0/demo/net/index.html)"), "got: {rewritten}");
+    // Rewriting is idempotent
+    assert_eq!(docs_mcp::docsrs::rewrite_doc_links(&rewritten, &links), rewritten);
+}
+
+// ─── Visibility ───────────────────────────────────────────────────────────────
+
+fn make_visibility_doc() -> RustdocJson {
+    serde_json::from_value(serde_json::json!({
+        "format_version": 57,
+        "root": 0,
+        "index": {
+            "0": {"id": 0, "name": "demo", "visibility": "public",
+                  "inner": {"module": {"items": [1, 2, 3]}}},
+            "1": {"id": 1, "name": "Client", "visibility": "public",
+                  "inner": {"struct": {"kind": {"unit": null}, "impls": []}}},
+            "2": {"id": 2, "name": "__private", "visibility": "public",
+                  "attrs": [{"other": "#[doc(hidden)]"}],
+                  "inner": {"module": {"items": [4]}}},
+            "3": {"id": 3, "name": "ClientState", "visibility": "crate",
+                  "inner": {"struct": {"kind": {"unit": null}, "impls": []}}},
+            "4": {"id": 4, "name": "ClientBuilderInternals", "visibility": "public",
+                  "inner": {"struct": {"kind": {"unit": null}, "impls": []}}}
+        },
+        "paths": {
+            "0": {"kind": "module", "path": ["demo"], "crate_id": 0},
+            "1": {"kind": "struct", "path": ["demo", "Client"], "crate_id": 0},
+            "2": {"kind": "module", "path": ["demo", "__private"], "crate_id": 0},
+            "3": {"kind": "struct", "path": ["demo", "ClientState"], "crate_id": 0},
+            "4": {"kind": "struct", "path": ["demo", "__private", "ClientBuilderInternals"], "crate_id": 0}
+        }
+    })).unwrap()
+}
+
+#[test]
+fn search_skips_hidden_and_private_items_by_default() {
+    let doc = make_visibility_doc();
+    let features = HashSet::new();
+    let paths: Vec<String> = search_items(&doc, "client", None, None, 10, &features, false)
+        .into_iter().map(|r| r.path).collect();
+    assert_eq!(paths, vec!["demo::Client"]);
+
+    let mut all: Vec<String> = search_items(&doc, "client", None, None, 10, &features, true)
+        .into_iter().map(|r| r.path).collect();
+    all.sort();
+    assert_eq!(all, vec!["demo::Client", "demo::ClientState", "demo::__private::ClientBuilderInternals"]);
+}
+
+#[test]
+fn module_tree_skips_hidden_modules_by_default() {
+    let doc = make_visibility_doc();
+    assert!(build_module_tree(&doc, false).is_empty());
+    let tree = build_module_tree(&doc, true);
+    assert_eq!(tree.len(), 1);
+    assert_eq!(tree[0].path, "demo::__private");
+}
+
+// ─── Source links ─────────────────────────────────────────────────────────────
+
+#[test]
+fn source_link_for_local_file() {
+    let span = docs_mcp::docsrs::Span { filename: "src/sync/mutex.rs".to_string(), begin: (120, 1), end: (140, 2) };
+    let link = docs_mcp::docsrs::source_link(&span, "tokio", "1.40.0").unwrap();
+    assert_eq!(link.url, "https://docs.rs/crate/tokio/1.40.0/source/src/sync/mutex.rs#L120");
+    assert_eq!((link.begin_line, link.end_line), (120, 140));
+}
+
+#[test]
+fn source_link_for_registry_dependency() {
+    let span = docs_mcp::docsrs::Span {
+        filename: "/opt/rustwide/cargo-home/registry/src/index.crates.io-1949cf8c6b5b557f/tower-http-0.6.8/src/follow_redirect/policy/mod.rs".to_string(),
+        begin: (171, 1),
+        end: (173, 15),
+    };
+    let link = docs_mcp::docsrs::source_link(&span, "rmcp", "0.16.0").unwrap();
+    assert_eq!(link.file, "src/follow_redirect/policy/mod.rs");
+    assert_eq!(link.url, "https://docs.rs/crate/tower-http/0.6.8/source/src/follow_redirect/policy/mod.rs#L171");
+}
+
+#[test]
+fn source_link_unknown_locations_are_none() {
+    let std_span = docs_mcp::docsrs::Span { filename: "/rustc/abc123/library/core/src/option.rs".to_string(), begin: (1, 1), end: (2, 1) };
+    assert!(docs_mcp::docsrs::source_link(&std_span, "demo", "1.0.0").is_none());
+    let empty = docs_mcp::docsrs::Span { filename: String::new(), begin: (0, 0), end: (0, 0) };
+    assert!(docs_mcp::docsrs::source_link(&empty, "demo", "1.0.0").is_none());
+}
+
+#[test]
+fn fixture_rmcp_memoized_lookups() {
+    let json_str = std::fs::read_to_string("tests/fixtures/rmcp_0.16.0.json")
+        .expect("rmcp fixture should exist");
+    let doc: docs_mcp::docsrs::RustdocJson =
+        serde_json::from_str(&json_str).expect("rmcp fixture should parse");
+
+    assert_eq!(doc.id_for_path("rmcp::transport::child_process::TokioChildProcess"), Some("9410"));
+    assert_eq!(doc.id_for_path("rmcp::transport::NoSuchThing"), None);
+    assert!(doc.ids_named("TokioChildProcess").iter().any(|id| id == "9410"));
+    // Second call returns the same memoized map
+    assert!(std::ptr::eq(doc.method_parents(), doc.method_parents()));
+}
+
+#[test]
+fn fixture_rmcp_find_item_id_follows_reexport_paths() {
+    let json_str = std::fs::read_to_string("tests/fixtures/rmcp_0.16.0.json")
+        .expect("rmcp fixture should exist");
+    let doc: docs_mcp::docsrs::RustdocJson =
+        serde_json::from_str(&json_str).expect("rmcp fixture should parse");
+
+    let find = |path| docs_mcp::docsrs::find_item_id(&doc, path);
+    assert_eq!(find("rmcp::transport::child_process::TokioChildProcess").as_deref(), Some("9410"));
+    // Shortened re-export path resolves through the last-segment index
+    assert_eq!(find("rmcp::transport::TokioChildProcess").as_deref(), Some("9410"));
+    assert_eq!(find("other_crate::transport::TokioChildProcess"), None);
+    assert_eq!(find("rmcp::transport::NoSuchThing"), None);
+}
+
+#[test]
+fn fixture_rmcp_impl_defining_module() {
+    let json_str = std::fs::read_to_string("tests/fixtures/rmcp_0.16.0.json")
+        .expect("rmcp fixture should exist");
+    let doc: docs_mcp::docsrs::RustdocJson =
+        serde_json::from_str(&json_str).expect("rmcp fixture should parse");
+
+    // impl Transport for TokioChildProcess, in src/transport/child_process.rs
+    let span = doc.index["9409"].span.as_ref().expect("impl should have a span");
+    assert_eq!(
+        docs_mcp::docsrs::parser::defining_module(&doc, span).as_deref(),
+        Some("rmcp::transport::child_process"),
+    );
+}
+
+// ─── Item kinds ───────────────────────────────────────────────────────────────
+
+#[test]
+fn item_kind_prefers_known_keys_and_reports_unknown() {
+    use docs_mcp::docsrs::{ItemKind, RustdocJson};
+    let doc: RustdocJson = serde_json::from_value(serde_json::json!({
+        "format_version": 99,
+        "root": 0,
+        "index": {
+            "0": {"id": 0, "name": "demo", "inner": {"module": {"items": [1, 2]}}},
+            // A future rustdoc adding a sibling key that sorts before the real kind
+            "1": {"id": 1, "name": "f", "inner": {"annotations": {}, "function": {}}},
+            "2": {"id": 2, "name": "G", "inner": {"generator": {}}},
+        },
+        "paths": {},
+    })).unwrap();
+
+    assert_eq!(doc.index["1"].kind(), Some("function"));
+    assert_eq!(doc.index["1"].item_kind(), ItemKind::Function);
+    assert_eq!(doc.index["2"].item_kind(), ItemKind::Unknown("generator".to_string()));
+    assert_eq!(ItemKind::AssocType.as_str(), "assoc_type");
+    assert_eq!(doc.unknown_kinds(), &[("generator".to_string(), 1)]);
+    assert_eq!(doc.warnings().len(), 1);
+    assert!(doc.warnings()[0].contains("'generator'"));
+}
+
+// ─── Doc sections ─────────────────────────────────────────────────────────────
+
+#[test]
+fn split_doc_sections_nests_subsections_and_skips_code() {
+    use docs_mcp::docsrs::{select_doc_sections, split_doc_sections};
+    let docs = "Intro line.\n\
+                \n\
+                # Usage\n\
+                ```rust\n\
+                # fn main() {}\n\
+                ```\n\
+                ## Feature flags\n\
+                - `full`\n\
+                ### Optional\n\
+                - `extra`\n\
+                ## Runtime ##\n\
+                Text.";
+    let sections = split_doc_sections(docs);
+    let titles: Vec<(usize, &str)> = sections.iter().map(|s| (s.level, s.title.as_str())).collect();
+    assert_eq!(titles, [(0, ""), (1, "Usage"), (2, "Feature flags"), (3, "Optional"), (2, "Runtime")]);
+    assert!(sections[1].text.contains("# fn main() {}"), "code block stays in its section");
+    assert_eq!(sections[2].text, "## Feature flags\n- `full`\n### Optional\n- `extra`");
+
+    let (selected, missing) = select_doc_sections(&sections, &["feature flags".to_string(), "Nope".to_string()]);
+    assert_eq!(selected.len(), 1);
+    assert_eq!(selected[0].title, "Feature flags");
+    assert_eq!(missing, ["Nope"]);
+
+    // No exact title: fall back to substring matches
+    let (selected, _) = select_doc_sections(&sections, &["run".to_string()]);
+    assert_eq!(selected[0].title, "Runtime");
+
+    // A subsection inside a selected section is not repeated on its own
+    let (selected, _) = select_doc_sections(&sections, &["Feature flags".to_string(), "Optional".to_string()]);
+    let titles: Vec<&str> = selected.iter().map(|s| s.title.as_str()).collect();
+    assert_eq!(titles, ["Feature flags"]);
+    let (selected, _) = select_doc_sections(&sections, &["Optional".to_string(), "Runtime".to_string()]);
+    let titles: Vec<&str> = selected.iter().map(|s| s.title.as_str()).collect();
+    assert_eq!(titles, ["Optional", "Runtime"]);
+}
+
+#[test]
+fn doc_caveats_extracts_panics_errors_and_safety() {
+    use docs_mcp::docsrs::doc_caveats;
+    let docs = "Reads a frame.\n\
+                \n\
+                # Errors\n\
+                \n\
+                Returns `Err` if the peer hung up.\n\
+                \n\
+                ## panics\n\
+                Panics if `len` is zero.\n\
+                \n\
+                # Examples\n\
+                ```rust\n\
+                # Safety\n\
+                ```\n\
+                # Safety\n";
+    let caveats = doc_caveats(docs);
+    assert_eq!(caveats.errors.as_deref(), Some("Returns `Err` if the peer hung up."));
+    assert_eq!(caveats.panics.as_deref(), Some("Panics if `len` is zero."));
+    assert_eq!(caveats.safety, None, "a heading in a code block or with no text is not a section");
+}
+
+#[test]
+fn split_doc_examples_separates_code_from_prose() {
+    use docs_mcp::docsrs::split_doc_examples;
+    let docs = "Opens a session.\n\
+                \n\
+                ```\n\
+                # use demo::Session;\n\
+                let s = Session::open()?;\n\
+                # Ok::<(), demo::Error>(())\n\
+                ```\n\
+                \n\
+                # Examples\n\
+                \n\
+                ```rust,no_run,edition2021\n\
+                #[derive(Debug)]\n\
+                struct Config;\n\
+                ```\n\
+                \n\
+                ```toml\n\
+                # not hidden in TOML\n\
+                demo = \"1\"\n\
+                ```\n\
+                That is all.";
+    let (prose, examples) = split_doc_examples(docs);
+    assert_eq!(prose, "Opens a session.\n\n# Examples\n\nThat is all.");
+    assert_eq!(examples.len(), 3);
+
+    assert_eq!(examples[0].language, "rust");
+    assert_eq!(examples[0].code, "let s = Session::open()?;");
+    assert_eq!(
+        examples[0].full_code.as_deref(),
+        Some("use demo::Session;\nlet s = Session::open()?;\nOk::<(), demo::Error>(())"),
+    );
+    assert_eq!(examples[0].section, None);
+
+    assert_eq!(examples[1].attributes, ["no_run", "edition2021"]);
+    assert_eq!(examples[1].code, "#[derive(Debug)]\nstruct Config;");
+    assert_eq!(examples[1].full_code, None, "nothing hidden");
+    assert_eq!(examples[1].section.as_deref(), Some("Examples"));
+
+    assert_eq!(examples[2].language, "toml");
+    assert_eq!(examples[2].code, "# not hidden in TOML\ndemo = \"1\"");
+}
+
+#[test]
+fn id_to_string_accepts_integer_and_string_ids() {
+    use docs_mcp::docsrs::id_to_string;
+    assert_eq!(id_to_string(&serde_json::json!(42)), Some("42".to_string()));
+    assert_eq!(id_to_string(&serde_json::json!("0:12")), Some("0:12".to_string()));
+    assert_eq!(id_to_string(&serde_json::Value::Null), None);
+}
+
+#[test]
+fn estimated_size_scales_with_the_source_json() {
+    let json_str = std::fs::read_to_string("tests/fixtures/clap_4.5.59.json")
+        .expect("clap fixture should exist");
+    let mut doc: RustdocJson = serde_json::from_str(&json_str).expect("clap fixture should parse");
+
+    // Without the text length the item count stands in
+    assert!(doc.estimated_size() > 0);
+    doc.source_len = json_str.len();
+    assert!(doc.estimated_size() > json_str.len(), "parsed JSON outweighs its text");
+}